@@ -2,6 +2,10 @@ import type { Principal } from '@dfinity/principal';
 import type { ActorMethod } from '@dfinity/agent';
 import type { IDL } from '@dfinity/candid';
 
+export interface Account {
+  'owner' : Principal,
+  'subaccount' : [] | [Uint8Array | number[]],
+}
 export type AddLiquiditAmountsResult = { 'Ok' : AddLiquidityAmountsReply } |
   { 'Err' : string };
 export interface AddLiquidityAmountsReply {
@@ -27,6 +31,7 @@ export interface AddLiquidityArgs {
   'amount_1' : bigint,
   'tx_id_0' : [] | [TxId],
   'tx_id_1' : [] | [TxId],
+  'lp_fee_bps' : [] | [number],
 }
 export type AddLiquidityAsyncResult = { 'Ok' : bigint } |
   { 'Err' : string };
@@ -37,6 +42,7 @@ export interface AddLiquidityReply {
   'tx_id' : bigint,
   'add_lp_token_amount' : bigint,
   'transfer_ids' : Array<TransferIdReply>,
+  'swap_tx_id' : [] | [bigint],
   'amount_0' : bigint,
   'amount_1' : bigint,
   'claim_ids' : BigUint64Array | bigint[],
@@ -57,8 +63,10 @@ export interface AddPoolArgs {
   'token_1' : string,
   'amount_0' : bigint,
   'amount_1' : bigint,
+  'weight_0' : [] | [number],
   'tx_id_0' : [] | [TxId],
   'tx_id_1' : [] | [TxId],
+  'tick_spacing' : [] | [number],
   'lp_fee_bps' : [] | [number],
 }
 export interface AddPoolReply {
@@ -88,9 +96,45 @@ export type AddPoolResult = { 'Ok' : AddPoolReply } |
   { 'Err' : string };
 export interface AddTokenArgs { 'token' : string }
 export type AddTokenReply = { 'IC' : ICTokenReply } |
-  { 'Solana' : SolanaTokenReply };
+  { 'Solana' : SolanaTokenReply } |
+  { 'Ripple' : RippleTokenReply };
 export type AddTokenResult = { 'Ok' : AddTokenReply } |
   { 'Err' : string };
+export interface CampaignReply {
+  'ts' : bigint,
+  'apr' : [] | [number],
+  'request_id' : bigint,
+  'distributed' : bigint,
+  'start_ts' : bigint,
+  'reward_amount' : bigint,
+  'reward_per_sec' : bigint,
+  'reward_chain' : string,
+  'reward_symbol' : string,
+  'reward_address' : string,
+  'is_active' : boolean,
+  'pool_id' : number,
+  'campaign_id' : number,
+  'symbol' : string,
+  'refund_claim_id' : [] | [bigint],
+  'end_ts' : bigint,
+}
+export type CampaignResult = { 'Ok' : CampaignReply } |
+  { 'Err' : string };
+export interface CampaignRewardReply {
+  'ts' : bigint,
+  'lp_token_amount' : bigint,
+  'pending' : bigint,
+  'reward_chain' : string,
+  'reward_symbol' : string,
+  'claim_ids' : BigUint64Array | bigint[],
+  'collected' : bigint,
+  'campaign_id' : number,
+  'symbol' : string,
+}
+export type CampaignRewardsResult = { 'Ok' : Array<CampaignRewardReply> } |
+  { 'Err' : string };
+export type CampaignsResult = { 'Ok' : Array<CampaignReply> } |
+  { 'Err' : string };
 export interface CheckPoolsReply {
   'expected_balance' : ExpectedBalance,
   'diff_balance' : bigint,
@@ -128,11 +172,68 @@ export interface ClaimsReply {
 }
 export type ClaimsResult = { 'Ok' : Array<ClaimsReply> } |
   { 'Err' : string };
+export interface CreateCampaignArgs {
+  'reward_token' : string,
+  'start_ts' : [] | [bigint],
+  'pool' : string,
+  'reward_amount' : bigint,
+  'end_ts' : bigint,
+}
+export interface DynamicFeeArgs {
+  'max_fee_bps' : number,
+  'half_life_secs' : bigint,
+  'max_volatility_bps' : number,
+  'min_fee_bps' : number,
+}
 export interface ExpectedBalance {
   'balance' : bigint,
   'pool_balances' : Array<PoolExpectedBalance>,
   'unclaimed_claims' : bigint,
 }
+export interface FlashSwapArgs {
+  'token' : string,
+  'data' : [] | [Uint8Array | number[]],
+  'pool' : string,
+  'amount' : bigint,
+}
+export interface FlashSwapCallbackArgs {
+  'fee' : bigint,
+  'request_id' : bigint,
+  'token' : string,
+  'data' : [] | [Uint8Array | number[]],
+  'repay_amount' : bigint,
+  'amount' : bigint,
+  'symbol' : string,
+}
+export interface FlashSwapReply {
+  'ts' : bigint,
+  'fee' : bigint,
+  'request_id' : bigint,
+  'status' : string,
+  'token' : string,
+  'transfer_ids' : Array<TransferIdReply>,
+  'chain' : string,
+  'repay_amount' : bigint,
+  'address' : string,
+  'amount' : bigint,
+  'symbol' : string,
+}
+export type FlashSwapResult = { 'Ok' : FlashSwapReply } |
+  { 'Err' : string };
+export type GetBlocksArgs = Array<{ 'start' : bigint, 'length' : bigint }>;
+export interface GetBlocksResult {
+  'log_length' : bigint,
+  'blocks' : Array<{ 'id' : bigint, 'block' : ICRC3Value }>,
+  'archived_blocks' : Array<
+    { 'args' : GetBlocksArgs, 'callback' : [Principal, string] }
+  >,
+}
+export type ICRC3Value = { 'Int' : bigint } |
+  { 'Map' : Array<[string, ICRC3Value]> } |
+  { 'Nat' : bigint } |
+  { 'Blob' : Uint8Array | number[] } |
+  { 'Text' : string } |
+  { 'Array' : Array<ICRC3Value> };
 export interface ICTokenReply {
   'fee' : bigint,
   'decimals' : number,
@@ -158,36 +259,155 @@ export interface Icrc10SupportedStandards { 'url' : string, 'name' : string }
 export interface Icrc28TrustedOriginsResponse {
   'trusted_origins' : Array<string>,
 }
+export interface LPAllowance {
+  'allowance' : bigint,
+  'expires_at' : [] | [bigint],
+}
+export interface LPAllowanceArgs { 'account' : Account, 'spender' : Account }
+export type LPAllowanceResult = { 'Ok' : LPAllowance } |
+  { 'Err' : string };
+export interface LPApproveArgs {
+  'fee' : [] | [bigint],
+  'memo' : [] | [Uint8Array | number[]],
+  'from_subaccount' : [] | [Uint8Array | number[]],
+  'created_at_time' : [] | [bigint],
+  'amount' : bigint,
+  'expected_allowance' : [] | [bigint],
+  'expires_at' : [] | [bigint],
+  'spender' : Account,
+}
+export type LPApproveError = {
+    'GenericError' : { 'message' : string, 'error_code' : bigint }
+  } |
+  { 'TemporarilyUnavailable' : null } |
+  { 'Duplicate' : { 'duplicate_of' : bigint } } |
+  { 'BadFee' : { 'expected_fee' : bigint } } |
+  { 'AllowanceChanged' : { 'current_allowance' : bigint } } |
+  { 'CreatedInFuture' : { 'ledger_time' : bigint } } |
+  { 'TooOld' : null } |
+  { 'Expired' : { 'ledger_time' : bigint } } |
+  { 'InsufficientFunds' : { 'balance' : bigint } };
+export type LPApproveResult = { 'Ok' : bigint } |
+  { 'Err' : LPApproveError };
 export interface LPBalancesReply {
   'ts' : bigint,
   'usd_balance' : number,
   'balance' : number,
   'name' : string,
+  'usd_amount_1_nat' : bigint,
   'amount_0' : number,
   'amount_1' : number,
+  'amount_1_nat' : bigint,
   'address_0' : string,
   'address_1' : string,
   'symbol_0' : string,
   'symbol_1' : string,
+  'balance_nat' : bigint,
   'usd_amount_0' : number,
   'usd_amount_1' : number,
+  'usd_amount_0_nat' : bigint,
   'chain_0' : string,
   'chain_1' : string,
+  'amount_0_nat' : bigint,
+  'usd_balance_nat' : bigint,
   'symbol' : string,
   'lp_token_id' : bigint,
 }
+export type LPGetBlocksResult = { 'Ok' : GetBlocksResult } |
+  { 'Err' : string };
+export interface LPLockReply {
+  'ts' : bigint,
+  'lp_token_symbol' : string,
+  'unlock_ts' : bigint,
+  'lock_id' : bigint,
+  'boost_bps' : number,
+  'principal_id' : string,
+  'amount' : bigint,
+  'symbol' : string,
+  'is_locked' : boolean,
+}
+export type LPLockResult = { 'Ok' : LPLockReply } |
+  { 'Err' : string };
+export interface LPLocksReply {
+  'lp_token_symbol' : string,
+  'locked' : bigint,
+  'locks' : Array<LPLockReply>,
+  'lp_token_supply' : bigint,
+  'symbol' : string,
+}
+export type LPLocksResult = { 'Ok' : LPLocksReply } |
+  { 'Err' : string };
+export type LPMetadataResult = { 'Ok' : Array<[string, LPMetadataValue]> } |
+  { 'Err' : string };
+export type LPMetadataValue = { 'Int' : bigint } |
+  { 'Nat' : bigint } |
+  { 'Blob' : Uint8Array | number[] } |
+  { 'Text' : string };
+export type LPNatResult = { 'Ok' : bigint } |
+  { 'Err' : string };
 export interface LPTokenReply {
   'fee' : bigint,
   'decimals' : number,
   'token_id' : number,
   'chain' : string,
   'name' : string,
+  'ledger_id' : [] | [Principal],
   'address' : string,
   'pool_id_of' : number,
   'is_removed' : boolean,
   'total_supply' : bigint,
   'symbol' : string,
 }
+export interface LPTransferArgs {
+  'to' : Account,
+  'fee' : [] | [bigint],
+  'memo' : [] | [Uint8Array | number[]],
+  'from_subaccount' : [] | [Uint8Array | number[]],
+  'created_at_time' : [] | [bigint],
+  'amount' : bigint,
+}
+export type LPTransferError = {
+    'GenericError' : { 'message' : string, 'error_code' : bigint }
+  } |
+  { 'TemporarilyUnavailable' : null } |
+  { 'BadBurn' : { 'min_burn_amount' : bigint } } |
+  { 'Duplicate' : { 'duplicate_of' : bigint } } |
+  { 'BadFee' : { 'expected_fee' : bigint } } |
+  { 'CreatedInFuture' : { 'ledger_time' : bigint } } |
+  { 'TooOld' : null } |
+  { 'InsufficientFunds' : { 'balance' : bigint } };
+export interface LPTransferFromArgs {
+  'to' : Account,
+  'fee' : [] | [bigint],
+  'spender_subaccount' : [] | [Uint8Array | number[]],
+  'from' : Account,
+  'memo' : [] | [Uint8Array | number[]],
+  'created_at_time' : [] | [bigint],
+  'amount' : bigint,
+}
+export type LPTransferFromError = {
+    'GenericError' : { 'message' : string, 'error_code' : bigint }
+  } |
+  { 'TemporarilyUnavailable' : null } |
+  { 'InsufficientAllowance' : { 'allowance' : bigint } } |
+  { 'BadBurn' : { 'min_burn_amount' : bigint } } |
+  { 'Duplicate' : { 'duplicate_of' : bigint } } |
+  { 'BadFee' : { 'expected_fee' : bigint } } |
+  { 'CreatedInFuture' : { 'ledger_time' : bigint } } |
+  { 'TooOld' : null } |
+  { 'InsufficientFunds' : { 'balance' : bigint } };
+export type LPTransferFromResult = { 'Ok' : bigint } |
+  { 'Err' : LPTransferFromError };
+export type LPTransferResult = { 'Ok' : bigint } |
+  { 'Err' : LPTransferError };
+export interface LimitOrderArgs {
+  'receive_token' : string,
+  'limit_price' : number,
+  'pay_amount' : bigint,
+  'receive_address' : [] | [string],
+  'pay_token' : string,
+  'expires_at' : [] | [bigint],
+}
 export interface MessagesReply {
   'ts' : bigint,
   'title' : string,
@@ -196,21 +416,88 @@ export interface MessagesReply {
 }
 export type MessagesResult = { 'Ok' : Array<MessagesReply> } |
   { 'Err' : string };
+export interface MintPositionArgs {
+  'token_0' : string,
+  'token_1' : string,
+  'amount_0' : bigint,
+  'amount_1' : bigint,
+  'tick_lower' : number,
+  'tick_upper' : number,
+  'lp_fee_bps' : [] | [number],
+}
+export interface OrderReply {
+  'ts' : bigint,
+  'request_id' : bigint,
+  'status' : string,
+  'limit_price' : [] | [number],
+  'filled_pay_amount' : bigint,
+  'receive_chain' : string,
+  'twap' : [] | [TwapReply],
+  'pay_amount' : bigint,
+  'fill_request_ids' : BigUint64Array | bigint[],
+  'receive_amount' : bigint,
+  'to_address' : string,
+  'claim_ids' : BigUint64Array | bigint[],
+  'pay_symbol' : string,
+  'receive_symbol' : string,
+  'receive_address' : string,
+  'order_type' : string,
+  'tx_ids' : BigUint64Array | bigint[],
+  'order_id' : bigint,
+  'pay_address' : string,
+  'expires_at' : [] | [bigint],
+  'pay_chain' : string,
+}
+export type OrderResult = { 'Ok' : OrderReply } |
+  { 'Err' : string };
+export type OrdersResult = { 'Ok' : Array<OrderReply> } |
+  { 'Err' : string };
 export interface PoolExpectedBalance {
   'balance' : bigint,
   'kong_fee' : bigint,
   'pool_symbol' : string,
   'lp_fee' : bigint,
 }
+export interface PoolFeeChangeReply {
+  'ts' : bigint,
+  'old_dynamic_fee_bps' : [] | [[number, number]],
+  'fee_change_id' : bigint,
+  'lp_token_symbol' : string,
+  'old_lp_fee_bps' : number,
+  'kong_fee_bps' : number,
+  'old_kong_fee_bps' : number,
+  'principal_id' : string,
+  'pool_id' : number,
+  'symbol' : string,
+  'lp_fee_bps' : number,
+  'dynamic_fee_bps' : [] | [[number, number]],
+}
+export type PoolFeeChangeResult = { 'Ok' : PoolFeeChangeReply } |
+  { 'Err' : string };
+export type PoolFeeChangesResult = { 'Ok' : Array<PoolFeeChangeReply> } |
+  { 'Err' : string };
+export interface PoolProtocolFeesReply {
+  'accrued_0' : bigint,
+  'accrued_1' : bigint,
+  'symbol_0' : string,
+  'symbol_1' : string,
+  'swept_0' : bigint,
+  'swept_1' : bigint,
+  'pool_id' : number,
+  'symbol' : string,
+  'lp_fee_bps' : number,
+}
 export interface PoolReply {
   'lp_token_symbol' : string,
   'name' : string,
   'lp_fee_0' : bigint,
   'lp_fee_1' : bigint,
+  'pool_type' : PoolType,
   'balance_0' : bigint,
   'balance_1' : bigint,
   'address_0' : string,
   'address_1' : string,
+  'live_lp_fee_bps' : number,
   'symbol_0' : string,
   'symbol_1' : string,
   'pool_id' : number,
@@ -221,8 +508,90 @@ export interface PoolReply {
   'symbol' : string,
   'lp_fee_bps' : number,
 }
+export type PoolResult = { 'Ok' : PoolReply } |
+  { 'Err' : string };
+export type PoolType = { 'StableSwap' : { 'amp' : bigint } } |
+  { 'Weighted' : { 'weight_0' : number } } |
+  { 'ConstantProduct' : null } |
+  { 'Concentrated' : { 'tick_spacing' : number } };
 export type PoolsResult = { 'Ok' : Array<PoolReply> } |
   { 'Err' : string };
+export interface PositionPnlReply {
+  'ts' : bigint,
+  'deposit_0' : bigint,
+  'deposit_1' : bigint,
+  'usd_impermanent_loss' : [] | [number],
+  'lp_token_symbol' : string,
+  'balance' : bigint,
+  'fees_0' : bigint,
+  'fees_1' : bigint,
+  'usd_deposit' : [] | [number],
+  'amount_0' : bigint,
+  'amount_1' : bigint,
+  'usd_value' : number,
+  'symbol_0' : string,
+  'symbol_1' : string,
+  'pool_id' : number,
+  'usd_fees' : number,
+  'usd_pnl' : [] | [number],
+  'symbol' : string,
+}
+export type PositionPnlResult = { 'Ok' : PositionPnlReply } |
+  { 'Err' : string };
+export interface PositionReply {
+  'ts' : bigint,
+  'request_id' : bigint,
+  'status' : string,
+  'fees_0' : bigint,
+  'fees_1' : bigint,
+  'in_range' : boolean,
+  'transfer_ids' : Array<TransferIdReply>,
+  'liquidity' : bigint,
+  'amount_0' : bigint,
+  'amount_1' : bigint,
+  'claim_ids' : BigUint64Array | bigint[],
+  'price_lower' : number,
+  'address_0' : string,
+  'address_1' : string,
+  'tick_lower' : number,
+  'symbol_0' : string,
+  'symbol_1' : string,
+  'price_upper' : number,
+  'chain_0' : string,
+  'chain_1' : string,
+  'tick_upper' : number,
+  'symbol' : string,
+  'lp_fee_bps' : number,
+  'position_id' : bigint,
+}
+export type PositionResult = { 'Ok' : PositionReply } |
+  { 'Err' : string };
+export type PositionsResult = { 'Ok' : Array<PositionReply> } |
+  { 'Err' : string };
+export interface ProtocolFeesReply {
+  'tokens' : Array<TokenProtocolFeesReply>,
+  'pools' : Array<PoolProtocolFeesReply>,
+  'treasury' : [] | [string],
+}
+export type ProtocolFeesResult = { 'Ok' : ProtocolFeesReply } |
+  { 'Err' : string };
+export interface ReferralStatsReply {
+  'referees' : number,
+  'referral_fee_share_bps' : number,
+  'tokens' : Array<ReferralTokenReply>,
+  'active_referees' : number,
+  'my_referral_code' : string,
+}
+export type ReferralStatsResult = { 'Ok' : ReferralStatsReply } |
+  { 'Err' : string };
+export interface ReferralTokenReply {
+  'claim_id' : [] | [bigint],
+  'chain' : string,
+  'volume' : bigint,
+  'earned' : bigint,
+  'address' : string,
+  'symbol' : string,
+}
 export interface RemoveLiquidityAmountsReply {
   'lp_fee_0' : bigint,
   'lp_fee_1' : bigint,
@@ -244,11 +613,14 @@ export type RemoveLiquidityAmountsResult = {
 export interface RemoveLiquidityArgs {
   'signature_0' : [] | [string],
   'signature_1' : [] | [string],
+  'receive_token' : [] | [string],
   'token_0' : string,
   'token_1' : string,
   'payout_address_0' : [] | [string],
   'payout_address_1' : [] | [string],
+  'min_receive_amount' : [] | [bigint],
   'remove_lp_token_amount' : bigint,
+  'lp_fee_bps' : [] | [number],
 }
 export type RemoveLiquidityAsyncResult = { 'Ok' : bigint } |
   { 'Err' : string };
@@ -260,6 +632,7 @@ export interface RemoveLiquidityReply {
   'transfer_ids' : Array<TransferIdReply>,
   'lp_fee_0' : bigint,
   'lp_fee_1' : bigint,
+  'swap_tx_id' : [] | [bigint],
   'amount_0' : bigint,
   'amount_1' : bigint,
   'claim_ids' : BigUint64Array | bigint[],
@@ -274,14 +647,29 @@ export interface RemoveLiquidityReply {
 }
 export type RemoveLiquidityResult = { 'Ok' : RemoveLiquidityReply } |
   { 'Err' : string };
-export type RequestReply = { 'AddLiquidity' : AddLiquidityReply } |
+export type RequestReply = { 'Campaign' : CampaignReply } |
+  { 'AddLiquidity' : AddLiquidityReply } |
   { 'Swap' : SwapReply } |
   { 'AddPool' : AddPoolReply } |
+  { 'Position' : PositionReply } |
+  { 'FlashSwap' : FlashSwapReply } |
+  { 'Order' : OrderReply } |
   { 'RemoveLiquidity' : RemoveLiquidityReply } |
   { 'Pending' : null };
-export type RequestRequest = { 'AddLiquidity' : AddLiquidityArgs } |
+export type RequestRequest = { 'ZapIn' : ZapInArgs } |
+  { 'AddLiquidity' : AddLiquidityArgs } |
+  { 'TwapOrder' : TwapOrderArgs } |
+  { 'LimitOrder' : LimitOrderArgs } |
+  { 'CreateCampaign' : CreateCampaignArgs } |
+  { 'CollectFees' : bigint } |
   { 'Swap' : SwapArgs } |
   { 'AddPool' : AddPoolArgs } |
+  { 'MintPosition' : MintPositionArgs } |
+  { 'FlashSwap' : FlashSwapArgs } |
+  { 'FillOrder' : bigint } |
+  { 'SweepProtocolFees' : null } |
+  { 'CancelOrder' : bigint } |
+  { 'BurnPosition' : bigint } |
   { 'RemoveLiquidity' : RemoveLiquidityArgs };
 export interface RequestsReply {
   'ts' : bigint,
@@ -289,9 +677,28 @@ export interface RequestsReply {
   'request' : RequestRequest,
   'statuses' : Array<string>,
   'reply' : RequestReply,
+  'solana_swap_jobs' : Array<SolanaSwapJobReply>,
 }
 export type RequestsResult = { 'Ok' : Array<RequestsReply> } |
   { 'Err' : string };
+export interface RippleTokenReply {
+  'fee' : bigint,
+  'decimals' : number,
+  'token_id' : number,
+  'chain' : string,
+  'name' : string,
+  'issuer' : [] | [string],
+  'currency' : string,
+  'symbol' : string,
+}
+export interface RippleTransferReply {
+  'is_send' : boolean,
+  'chain' : string,
+  'address' : string,
+  'tx_hash' : string,
+  'amount' : bigint,
+  'symbol' : string,
+}
 export interface SendArgs {
   'token' : string,
   'to_address' : string,
@@ -309,6 +716,19 @@ export interface SendReply {
 }
 export type SendResult = { 'Ok' : SendReply } |
   { 'Err' : string };
+export interface SolanaSwapJobReply {
+  'request_id' : bigint,
+  'status' : string,
+  'updated_at' : bigint,
+  'retry_count' : number,
+  'error_message' : [] | [string],
+  'created_at' : bigint,
+  'job_id' : bigint,
+  'tx_signature' : string,
+  'solana_tx_signature_of_payout' : [] | [string],
+}
+export type SolanaSwapJobsResult = { 'Ok' : Array<SolanaSwapJobReply> } |
+  { 'Err' : string };
 export interface SolanaTokenReply {
   'fee' : bigint,
   'decimals' : number,
@@ -318,13 +738,13 @@ export interface SolanaTokenReply {
   'program_id' : string,
   'is_spl_token' : boolean,
   'mint_address' : string,
+  'has_transfer_fee' : boolean,
   'symbol' : string,
 }
 export interface SolanaTransferReply {
   'is_send' : boolean,
   'signature' : string,
   'chain' : string,
-  'slot' : [] | [bigint],
   'amount' : bigint,
   'mint_address' : string,
   'symbol' : string,
@@ -358,6 +778,7 @@ export interface SwapAmountsTxReply {
   'pay_chain' : string,
   'lp_fee' : bigint,
   'gas_fee' : bigint,
+  'lp_fee_bps' : number,
 }
 export interface SwapArgs {
   'receive_token' : string,
@@ -365,13 +786,21 @@ export interface SwapArgs {
   'pay_amount' : bigint,
   'referred_by' : [] | [string],
   'receive_amount' : [] | [bigint],
+  'exact_out' : [] | [boolean],
   'receive_address' : [] | [string],
   'pay_token' : string,
   'pay_tx_id' : [] | [TxId],
   'pay_signature' : [] | [string],
+  'max_routes' : [] | [number],
 }
 export type SwapAsyncResult = { 'Ok' : bigint } |
   { 'Err' : string };
+export interface SwapRefundReply {
+  'claim_id' : [] | [bigint],
+  'transfer_id' : [] | [bigint],
+  'amount' : bigint,
+  'symbol' : string,
+}
 export interface SwapReply {
   'ts' : bigint,
   'txs' : Array<SwapTxReply>,
@@ -382,6 +811,7 @@ export interface SwapReply {
   'receive_chain' : string,
   'mid_price' : number,
   'pay_amount' : bigint,
+  'solana_job_id' : [] | [bigint],
   'receive_amount' : bigint,
   'claim_ids' : BigUint64Array | bigint[],
   'pay_symbol' : string,
@@ -391,6 +821,7 @@ export interface SwapReply {
   'price' : number,
   'pay_chain' : string,
   'slippage' : number,
+  'refund' : [] | [SwapRefundReply],
 }
 export type SwapResult = { 'Ok' : SwapReply } |
   { 'Err' : string };
@@ -408,10 +839,32 @@ export interface SwapTxReply {
   'pay_chain' : string,
   'lp_fee' : bigint,
   'gas_fee' : bigint,
+  'lp_fee_bps' : number,
+}
+export interface SweepProtocolFeesReply {
+  'ts' : bigint,
+  'request_id' : bigint,
+  'status' : string,
+  'tx_id' : bigint,
+  'transfer_ids' : Array<TransferIdReply>,
+  'chain' : string,
+  'to_address' : string,
+  'amount' : bigint,
+  'symbol' : string,
+}
+export type SweepProtocolFeesResult = { 'Ok' : Array<SweepProtocolFeesReply> } |
+  { 'Err' : string };
+export interface TokenProtocolFeesReply {
+  'chain' : string,
+  'swept' : bigint,
+  'address' : string,
+  'accrued' : bigint,
+  'symbol' : string,
 }
 export type TokenReply = { 'IC' : ICTokenReply } |
   { 'LP' : LPTokenReply } |
-  { 'Solana' : SolanaTokenReply };
+  { 'Solana' : SolanaTokenReply } |
+  { 'Ripple' : RippleTokenReply };
 export type TokensResult = { 'Ok' : Array<TokenReply> } |
   { 'Err' : string };
 export interface TransferIdReply {
@@ -419,9 +872,27 @@ export interface TransferIdReply {
   'transfer' : TransferReply,
 }
 export type TransferReply = { 'IC' : ICTransferReply } |
-  { 'Solana' : SolanaTransferReply };
+  { 'Solana' : SolanaTransferReply } |
+  { 'Ripple' : RippleTransferReply };
 export type TransfersResult = { 'Ok' : Array<TransferIdReply> } |
   { 'Err' : string };
+export interface TwapOrderArgs {
+  'receive_token' : string,
+  'max_slippage' : [] | [number],
+  'num_slices' : number,
+  'pay_amount' : bigint,
+  'interval_secs' : bigint,
+  'receive_address' : [] | [string],
+  'pay_token' : string,
+}
+export interface TwapReply {
+  'failed_slices' : number,
+  'slices_filled' : number,
+  'next_slice_at' : bigint,
+  'max_slippage' : number,
+  'num_slices' : number,
+  'interval_secs' : bigint,
+}
 export type TxId = { 'TransactionId' : string } |
   { 'BlockIndex' : bigint };
 export type TxsReply = { 'AddLiquidity' : AddLiquidityReply } |
@@ -430,20 +901,33 @@ export type TxsReply = { 'AddLiquidity' : AddLiquidityReply } |
   { 'RemoveLiquidity' : RemoveLiquidityReply };
 export type TxsResult = { 'Ok' : Array<TxsReply> } |
   { 'Err' : string };
-export interface UpdateTokenArgs { 'token' : string }
-export type UpdateTokenReply = { 'IC' : ICTokenReply };
+export interface UpdateTokenArgs {
+  'decimals' : [] | [number],
+  'token' : string,
+  'name' : [] | [string],
+  'symbol' : [] | [string],
+}
+export type UpdateTokenReply = { 'IC' : ICTokenReply } |
+  { 'Ripple' : RippleTokenReply };
 export type UpdateTokenResult = { 'Ok' : UpdateTokenReply } |
   { 'Err' : string };
 export type UserBalancesReply = { 'LP' : LPBalancesReply };
 export type UserBalancesResult = { 'Ok' : Array<UserBalancesReply> } |
   { 'Err' : string };
+export type UserLPLocksResult = { 'Ok' : Array<LPLockReply> } |
+  { 'Err' : string };
 export interface UserReply {
   'account_id' : string,
   'fee_level_expires_at' : [] | [bigint],
+  'next_fee_level' : [] | [number],
+  'volume_30d' : number,
+  'next_fee_level_volume' : [] | [number],
   'referred_by' : [] | [string],
   'user_id' : number,
   'fee_level' : number,
+  'current_fee_level' : number,
   'principal_id' : string,
+  'earned_fee_level' : number,
   'referred_by_expires_at' : [] | [bigint],
   'my_referral_code' : string,
 }
@@ -453,6 +937,12 @@ export type ValidateAddLiquidityResult = { 'Ok' : string } |
   { 'Err' : string };
 export type ValidateRemoveLiquidityResult = { 'Ok' : string } |
   { 'Err' : string };
+export interface ZapInArgs {
+  'token' : string,
+  'max_slippage' : [] | [number],
+  'pool' : string,
+  'amount' : bigint,
+}
 export interface icrc21_consent_info {
   'metadata' : icrc21_consent_message_metadata,
   'consent_message' : icrc21_consent_message,
@@ -494,7 +984,7 @@ export interface icrc21_error_info { 'description' : string }
 export interface _SERVICE {
   'add_liquidity' : ActorMethod<[AddLiquidityArgs], AddLiquidityResult>,
   'add_liquidity_amounts' : ActorMethod<
-    [string, bigint, string],
+    [string, bigint, string, [] | [number]],
     AddLiquiditAmountsResult
   >,
   'add_liquidity_async' : ActorMethod<
@@ -503,14 +993,18 @@ export interface _SERVICE {
   >,
   'add_pool' : ActorMethod<[AddPoolArgs], AddPoolResult>,
   'add_token' : ActorMethod<[AddTokenArgs], AddTokenResult>,
+  'burn_position' : ActorMethod<[bigint], PositionResult>,
+  'campaign_rewards' : ActorMethod<[string], CampaignRewardsResult>,
+  'campaigns' : ActorMethod<[[] | [string]], CampaignsResult>,
+  'cancel_order' : ActorMethod<[bigint], OrderResult>,
   'check_pools' : ActorMethod<[], CheckPoolsResult>,
   'claim' : ActorMethod<[bigint], ClaimResult>,
   'claims' : ActorMethod<[string], ClaimsResult>,
-  'cleanup_expired_solana_jobs' : ActorMethod<
-    [],
-    { 'Ok' : number } |
-      { 'Err' : string }
-  >,
+  'collect_fees' : ActorMethod<[bigint], PositionResult>,
+  'collect_rewards' : ActorMethod<[], CampaignRewardsResult>,
+  'create_campaign' : ActorMethod<[CreateCampaignArgs], CampaignResult>,
+  'flash_swap' : ActorMethod<[FlashSwapArgs], FlashSwapResult>,
+  'get_ripple_address' : ActorMethod<[], string>,
   'get_solana_address' : ActorMethod<[], string>,
   'get_user' : ActorMethod<[], UserResult>,
   'icrc10_supported_standards' : ActorMethod<
@@ -523,18 +1017,48 @@ export interface _SERVICE {
     icrc21_consent_message_response
   >,
   'icrc28_trusted_origins' : ActorMethod<[], Icrc28TrustedOriginsResponse>,
+  'limit_order' : ActorMethod<[LimitOrderArgs], OrderResult>,
+  'lock_lp' : ActorMethod<[string, bigint, bigint], LPLockResult>,
+  'lp_icrc1_balance_of' : ActorMethod<[Account], LPNatResult>,
+  'lp_icrc1_metadata' : ActorMethod<[], LPMetadataResult>,
+  'lp_icrc1_total_supply' : ActorMethod<[], LPNatResult>,
+  'lp_icrc1_transfer' : ActorMethod<
+    [Principal, LPTransferArgs],
+    LPTransferResult
+  >,
+  'lp_icrc2_allowance' : ActorMethod<[LPAllowanceArgs], LPAllowanceResult>,
+  'lp_icrc2_approve' : ActorMethod<[Principal, LPApproveArgs], LPApproveResult>,
+  'lp_icrc2_transfer_from' : ActorMethod<
+    [Principal, LPTransferFromArgs],
+    LPTransferFromResult
+  >,
+  'lp_icrc3_get_blocks' : ActorMethod<[GetBlocksArgs], LPGetBlocksResult>,
+  'lp_locks' : ActorMethod<[string], LPLocksResult>,
+  'mint_position' : ActorMethod<[MintPositionArgs], PositionResult>,
+  'notify_solana_swap_not_found' : ActorMethod<
+    [bigint, Array<string>],
+    { 'Ok' : null } |
+      { 'Err' : string }
+  >,
   'notify_solana_transfer' : ActorMethod<
-    [string, [] | [string]],
+    [string, bigint, bigint, [] | [string]],
     { 'Ok' : null } |
       { 'Err' : string }
   >,
+  'orders' : ActorMethod<[string], OrdersResult>,
+  'pool_fee_changes' : ActorMethod<[string], PoolFeeChangesResult>,
   'pools' : ActorMethod<[[] | [string]], PoolsResult>,
+  'position_pnl' : ActorMethod<[string, string], PositionPnlResult>,
+  'positions' : ActorMethod<[string], PositionsResult>,
+  'protocol_fees' : ActorMethod<[], ProtocolFeesResult>,
+  'referral_stats' : ActorMethod<[string], ReferralStatsResult>,
+  'refund_campaign' : ActorMethod<[number], CampaignResult>,
   'remove_liquidity' : ActorMethod<
     [RemoveLiquidityArgs],
     RemoveLiquidityResult
   >,
   'remove_liquidity_amounts' : ActorMethod<
-    [string, string, bigint],
+    [string, string, bigint, [] | [number]],
     RemoveLiquidityAmountsResult
   >,
   'remove_liquidity_async' : ActorMethod<
@@ -543,11 +1067,34 @@ export interface _SERVICE {
   >,
   'requests' : ActorMethod<[[] | [bigint]], RequestsResult>,
   'send' : ActorMethod<[SendArgs], SendResult>,
+  'set_pool_dynamic_fee' : ActorMethod<
+    [string, [] | [DynamicFeeArgs]],
+    PoolFeeChangeResult
+  >,
+  'set_pool_fees' : ActorMethod<[string, number, number], PoolFeeChangeResult>,
+  'set_pool_type' : ActorMethod<[string, PoolType], PoolResult>,
+  'set_user_fee_level' : ActorMethod<
+    [string, number, [] | [bigint]],
+    UserResult
+  >,
+  'solana_swap_jobs' : ActorMethod<
+    [[] | [bigint], [] | [string]],
+    SolanaSwapJobsResult
+  >,
   'swap' : ActorMethod<[SwapArgs], SwapResult>,
-  'swap_amounts' : ActorMethod<[string, bigint, string], SwapAmountsResult>,
+  'swap_amounts' : ActorMethod<
+    [string, bigint, string, [] | [number]],
+    SwapAmountsResult
+  >,
+  'swap_amounts_exact_out' : ActorMethod<
+    [string, string, bigint],
+    SwapAmountsResult
+  >,
   'swap_async' : ActorMethod<[SwapArgs], SwapAsyncResult>,
+  'sweep_protocol_fees' : ActorMethod<[], SweepProtocolFeesResult>,
   'tokens' : ActorMethod<[[] | [string]], TokensResult>,
-  'update_solana_latest_blockhash' : ActorMethod<
+  'twap_order' : ActorMethod<[TwapOrderArgs], OrderResult>,
+  'update_solana_blockhash' : ActorMethod<
     [string],
     { 'Ok' : null } |
       { 'Err' : string }
@@ -559,8 +1106,10 @@ export interface _SERVICE {
   >,
   'update_token' : ActorMethod<[UpdateTokenArgs], UpdateTokenResult>,
   'user_balances' : ActorMethod<[string], UserBalancesResult>,
+  'user_lp_locks' : ActorMethod<[string], UserLPLocksResult>,
   'validate_add_liquidity' : ActorMethod<[], ValidateAddLiquidityResult>,
   'validate_remove_liquidity' : ActorMethod<[], ValidateRemoveLiquidityResult>,
+  'zap_in' : ActorMethod<[ZapInArgs], AddLiquidityResult>,
 }
 export declare const idlFactory: IDL.InterfaceFactory;
-export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];
+export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];
\ No newline at end of file
@@ -1,4 +1,6 @@
 export const idlFactory = ({ IDL }) => {
+  const GetBlocksResult = IDL.Rec();
+  const ICRC3Value = IDL.Rec();
   const TxId = IDL.Variant({
     'TransactionId' : IDL.Text,
     'BlockIndex' : IDL.Nat,
@@ -12,6 +14,7 @@ export const idlFactory = ({ IDL }) => {
     'amount_1' : IDL.Nat,
     'tx_id_0' : IDL.Opt(TxId),
     'tx_id_1' : IDL.Opt(TxId),
+    'lp_fee_bps' : IDL.Opt(IDL.Nat8),
   });
   const ICTransferReply = IDL.Record({
     'is_send' : IDL.Bool,
@@ -25,14 +28,22 @@ export const idlFactory = ({ IDL }) => {
     'is_send' : IDL.Bool,
     'signature' : IDL.Text,
     'chain' : IDL.Text,
-    'slot' : IDL.Opt(IDL.Nat64),
     'amount' : IDL.Nat,
     'mint_address' : IDL.Text,
     'symbol' : IDL.Text,
   });
+  const RippleTransferReply = IDL.Record({
+    'is_send' : IDL.Bool,
+    'chain' : IDL.Text,
+    'address' : IDL.Text,
+    'tx_hash' : IDL.Text,
+    'amount' : IDL.Nat,
+    'symbol' : IDL.Text,
+  });
   const TransferReply = IDL.Variant({
     'IC' : ICTransferReply,
     'Solana' : SolanaTransferReply,
+    'Ripple' : RippleTransferReply,
   });
   const TransferIdReply = IDL.Record({
     'transfer_id' : IDL.Nat64,
@@ -45,6 +56,7 @@ export const idlFactory = ({ IDL }) => {
     'tx_id' : IDL.Nat64,
     'add_lp_token_amount' : IDL.Nat,
     'transfer_ids' : IDL.Vec(TransferIdReply),
+    'swap_tx_id' : IDL.Opt(IDL.Nat64),
     'amount_0' : IDL.Nat,
     'amount_1' : IDL.Nat,
     'claim_ids' : IDL.Vec(IDL.Nat64),
@@ -89,8 +101,10 @@ export const idlFactory = ({ IDL }) => {
     'token_1' : IDL.Text,
     'amount_0' : IDL.Nat,
     'amount_1' : IDL.Nat,
+    'weight_0' : IDL.Opt(IDL.Nat8),
     'tx_id_0' : IDL.Opt(TxId),
     'tx_id_1' : IDL.Opt(TxId),
+    'tick_spacing' : IDL.Opt(IDL.Nat16),
     'lp_fee_bps' : IDL.Opt(IDL.Nat8),
   });
   const AddPoolReply = IDL.Record({
@@ -140,16 +154,127 @@ export const idlFactory = ({ IDL }) => {
     'program_id' : IDL.Text,
     'is_spl_token' : IDL.Bool,
     'mint_address' : IDL.Text,
+    'has_transfer_fee' : IDL.Bool,
+    'symbol' : IDL.Text,
+  });
+  const RippleTokenReply = IDL.Record({
+    'fee' : IDL.Nat,
+    'decimals' : IDL.Nat8,
+    'token_id' : IDL.Nat32,
+    'chain' : IDL.Text,
+    'name' : IDL.Text,
+    'issuer' : IDL.Opt(IDL.Text),
+    'currency' : IDL.Text,
     'symbol' : IDL.Text,
   });
   const AddTokenReply = IDL.Variant({
     'IC' : ICTokenReply,
     'Solana' : SolanaTokenReply,
+    'Ripple' : RippleTokenReply,
   });
   const AddTokenResult = IDL.Variant({
     'Ok' : AddTokenReply,
     'Err' : IDL.Text,
   });
+  const PositionReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'request_id' : IDL.Nat64,
+    'status' : IDL.Text,
+    'fees_0' : IDL.Nat,
+    'fees_1' : IDL.Nat,
+    'in_range' : IDL.Bool,
+    'transfer_ids' : IDL.Vec(TransferIdReply),
+    'liquidity' : IDL.Nat,
+    'amount_0' : IDL.Nat,
+    'amount_1' : IDL.Nat,
+    'claim_ids' : IDL.Vec(IDL.Nat64),
+    'price_lower' : IDL.Float64,
+    'address_0' : IDL.Text,
+    'address_1' : IDL.Text,
+    'tick_lower' : IDL.Int32,
+    'symbol_0' : IDL.Text,
+    'symbol_1' : IDL.Text,
+    'price_upper' : IDL.Float64,
+    'chain_0' : IDL.Text,
+    'chain_1' : IDL.Text,
+    'tick_upper' : IDL.Int32,
+    'symbol' : IDL.Text,
+    'lp_fee_bps' : IDL.Nat8,
+    'position_id' : IDL.Nat64,
+  });
+  const PositionResult = IDL.Variant({
+    'Ok' : PositionReply,
+    'Err' : IDL.Text,
+  });
+  const CampaignRewardReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'lp_token_amount' : IDL.Nat,
+    'pending' : IDL.Nat,
+    'reward_chain' : IDL.Text,
+    'reward_symbol' : IDL.Text,
+    'claim_ids' : IDL.Vec(IDL.Nat64),
+    'collected' : IDL.Nat,
+    'campaign_id' : IDL.Nat32,
+    'symbol' : IDL.Text,
+  });
+  const CampaignRewardsResult = IDL.Variant({
+    'Ok' : IDL.Vec(CampaignRewardReply),
+    'Err' : IDL.Text,
+  });
+  const CampaignReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'apr' : IDL.Opt(IDL.Float64),
+    'request_id' : IDL.Nat64,
+    'distributed' : IDL.Nat,
+    'start_ts' : IDL.Nat64,
+    'reward_amount' : IDL.Nat,
+    'reward_per_sec' : IDL.Nat,
+    'reward_chain' : IDL.Text,
+    'reward_symbol' : IDL.Text,
+    'reward_address' : IDL.Text,
+    'is_active' : IDL.Bool,
+    'pool_id' : IDL.Nat32,
+    'campaign_id' : IDL.Nat32,
+    'symbol' : IDL.Text,
+    'refund_claim_id' : IDL.Opt(IDL.Nat64),
+    'end_ts' : IDL.Nat64,
+  });
+  const CampaignsResult = IDL.Variant({
+    'Ok' : IDL.Vec(CampaignReply),
+    'Err' : IDL.Text,
+  });
+  const TwapReply = IDL.Record({
+    'failed_slices' : IDL.Nat32,
+    'slices_filled' : IDL.Nat32,
+    'next_slice_at' : IDL.Nat64,
+    'max_slippage' : IDL.Float64,
+    'num_slices' : IDL.Nat32,
+    'interval_secs' : IDL.Nat64,
+  });
+  const OrderReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'request_id' : IDL.Nat64,
+    'status' : IDL.Text,
+    'limit_price' : IDL.Opt(IDL.Float64),
+    'filled_pay_amount' : IDL.Nat,
+    'receive_chain' : IDL.Text,
+    'twap' : IDL.Opt(TwapReply),
+    'pay_amount' : IDL.Nat,
+    'fill_request_ids' : IDL.Vec(IDL.Nat64),
+    'receive_amount' : IDL.Nat,
+    'to_address' : IDL.Text,
+    'claim_ids' : IDL.Vec(IDL.Nat64),
+    'pay_symbol' : IDL.Text,
+    'receive_symbol' : IDL.Text,
+    'receive_address' : IDL.Text,
+    'order_type' : IDL.Text,
+    'tx_ids' : IDL.Vec(IDL.Nat64),
+    'order_id' : IDL.Nat64,
+    'pay_address' : IDL.Text,
+    'expires_at' : IDL.Opt(IDL.Nat64),
+    'pay_chain' : IDL.Text,
+  });
+  const OrderResult = IDL.Variant({ 'Ok' : OrderReply, 'Err' : IDL.Text });
   const PoolExpectedBalance = IDL.Record({
     'balance' : IDL.Nat,
     'kong_fee' : IDL.Nat,
@@ -201,13 +326,52 @@ export const idlFactory = ({ IDL }) => {
     'Ok' : IDL.Vec(ClaimsReply),
     'Err' : IDL.Text,
   });
+  const CreateCampaignArgs = IDL.Record({
+    'reward_token' : IDL.Text,
+    'start_ts' : IDL.Opt(IDL.Nat64),
+    'pool' : IDL.Text,
+    'reward_amount' : IDL.Nat,
+    'end_ts' : IDL.Nat64,
+  });
+  const CampaignResult = IDL.Variant({
+    'Ok' : CampaignReply,
+    'Err' : IDL.Text,
+  });
+  const FlashSwapArgs = IDL.Record({
+    'token' : IDL.Text,
+    'data' : IDL.Opt(IDL.Vec(IDL.Nat8)),
+    'pool' : IDL.Text,
+    'amount' : IDL.Nat,
+  });
+  const FlashSwapReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'fee' : IDL.Nat,
+    'request_id' : IDL.Nat64,
+    'status' : IDL.Text,
+    'token' : IDL.Text,
+    'transfer_ids' : IDL.Vec(TransferIdReply),
+    'chain' : IDL.Text,
+    'repay_amount' : IDL.Nat,
+    'address' : IDL.Text,
+    'amount' : IDL.Nat,
+    'symbol' : IDL.Text,
+  });
+  const FlashSwapResult = IDL.Variant({
+    'Ok' : FlashSwapReply,
+    'Err' : IDL.Text,
+  });
   const UserReply = IDL.Record({
     'account_id' : IDL.Text,
     'fee_level_expires_at' : IDL.Opt(IDL.Nat64),
+    'next_fee_level' : IDL.Opt(IDL.Nat8),
+    'volume_30d' : IDL.Float64,
+    'next_fee_level_volume' : IDL.Opt(IDL.Float64),
     'referred_by' : IDL.Opt(IDL.Text),
     'user_id' : IDL.Nat32,
     'fee_level' : IDL.Nat8,
+    'current_fee_level' : IDL.Nat8,
     'principal_id' : IDL.Text,
+    'earned_fee_level' : IDL.Nat8,
     'referred_by_expires_at' : IDL.Opt(IDL.Nat64),
     'my_referral_code' : IDL.Text,
   });
@@ -264,15 +428,218 @@ export const idlFactory = ({ IDL }) => {
   const Icrc28TrustedOriginsResponse = IDL.Record({
     'trusted_origins' : IDL.Vec(IDL.Text),
   });
+  const LimitOrderArgs = IDL.Record({
+    'receive_token' : IDL.Text,
+    'limit_price' : IDL.Float64,
+    'pay_amount' : IDL.Nat,
+    'receive_address' : IDL.Opt(IDL.Text),
+    'pay_token' : IDL.Text,
+    'expires_at' : IDL.Opt(IDL.Nat64),
+  });
+  const LPLockReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'lp_token_symbol' : IDL.Text,
+    'unlock_ts' : IDL.Nat64,
+    'lock_id' : IDL.Nat64,
+    'boost_bps' : IDL.Nat16,
+    'principal_id' : IDL.Text,
+    'amount' : IDL.Nat,
+    'symbol' : IDL.Text,
+    'is_locked' : IDL.Bool,
+  });
+  const LPLockResult = IDL.Variant({ 'Ok' : LPLockReply, 'Err' : IDL.Text });
+  const Account = IDL.Record({
+    'owner' : IDL.Principal,
+    'subaccount' : IDL.Opt(IDL.Vec(IDL.Nat8)),
+  });
+  const LPNatResult = IDL.Variant({ 'Ok' : IDL.Nat, 'Err' : IDL.Text });
+  const LPMetadataValue = IDL.Variant({
+    'Int' : IDL.Int,
+    'Nat' : IDL.Nat,
+    'Blob' : IDL.Vec(IDL.Nat8),
+    'Text' : IDL.Text,
+  });
+  const LPMetadataResult = IDL.Variant({
+    'Ok' : IDL.Vec(IDL.Tuple(IDL.Text, LPMetadataValue)),
+    'Err' : IDL.Text,
+  });
+  const LPTransferArgs = IDL.Record({
+    'to' : Account,
+    'fee' : IDL.Opt(IDL.Nat),
+    'memo' : IDL.Opt(IDL.Vec(IDL.Nat8)),
+    'from_subaccount' : IDL.Opt(IDL.Vec(IDL.Nat8)),
+    'created_at_time' : IDL.Opt(IDL.Nat64),
+    'amount' : IDL.Nat,
+  });
+  const LPTransferError = IDL.Variant({
+    'GenericError' : IDL.Record({
+      'message' : IDL.Text,
+      'error_code' : IDL.Nat,
+    }),
+    'TemporarilyUnavailable' : IDL.Null,
+    'BadBurn' : IDL.Record({ 'min_burn_amount' : IDL.Nat }),
+    'Duplicate' : IDL.Record({ 'duplicate_of' : IDL.Nat }),
+    'BadFee' : IDL.Record({ 'expected_fee' : IDL.Nat }),
+    'CreatedInFuture' : IDL.Record({ 'ledger_time' : IDL.Nat64 }),
+    'TooOld' : IDL.Null,
+    'InsufficientFunds' : IDL.Record({ 'balance' : IDL.Nat }),
+  });
+  const LPTransferResult = IDL.Variant({
+    'Ok' : IDL.Nat,
+    'Err' : LPTransferError,
+  });
+  const LPAllowanceArgs = IDL.Record({
+    'account' : Account,
+    'spender' : Account,
+  });
+  const LPAllowance = IDL.Record({
+    'allowance' : IDL.Nat,
+    'expires_at' : IDL.Opt(IDL.Nat64),
+  });
+  const LPAllowanceResult = IDL.Variant({
+    'Ok' : LPAllowance,
+    'Err' : IDL.Text,
+  });
+  const LPApproveArgs = IDL.Record({
+    'fee' : IDL.Opt(IDL.Nat),
+    'memo' : IDL.Opt(IDL.Vec(IDL.Nat8)),
+    'from_subaccount' : IDL.Opt(IDL.Vec(IDL.Nat8)),
+    'created_at_time' : IDL.Opt(IDL.Nat64),
+    'amount' : IDL.Nat,
+    'expected_allowance' : IDL.Opt(IDL.Nat),
+    'expires_at' : IDL.Opt(IDL.Nat64),
+    'spender' : Account,
+  });
+  const LPApproveError = IDL.Variant({
+    'GenericError' : IDL.Record({
+      'message' : IDL.Text,
+      'error_code' : IDL.Nat,
+    }),
+    'TemporarilyUnavailable' : IDL.Null,
+    'Duplicate' : IDL.Record({ 'duplicate_of' : IDL.Nat }),
+    'BadFee' : IDL.Record({ 'expected_fee' : IDL.Nat }),
+    'AllowanceChanged' : IDL.Record({ 'current_allowance' : IDL.Nat }),
+    'CreatedInFuture' : IDL.Record({ 'ledger_time' : IDL.Nat64 }),
+    'TooOld' : IDL.Null,
+    'Expired' : IDL.Record({ 'ledger_time' : IDL.Nat64 }),
+    'InsufficientFunds' : IDL.Record({ 'balance' : IDL.Nat }),
+  });
+  const LPApproveResult = IDL.Variant({
+    'Ok' : IDL.Nat,
+    'Err' : LPApproveError,
+  });
+  const LPTransferFromArgs = IDL.Record({
+    'to' : Account,
+    'fee' : IDL.Opt(IDL.Nat),
+    'spender_subaccount' : IDL.Opt(IDL.Vec(IDL.Nat8)),
+    'from' : Account,
+    'memo' : IDL.Opt(IDL.Vec(IDL.Nat8)),
+    'created_at_time' : IDL.Opt(IDL.Nat64),
+    'amount' : IDL.Nat,
+  });
+  const LPTransferFromError = IDL.Variant({
+    'GenericError' : IDL.Record({
+      'message' : IDL.Text,
+      'error_code' : IDL.Nat,
+    }),
+    'TemporarilyUnavailable' : IDL.Null,
+    'InsufficientAllowance' : IDL.Record({ 'allowance' : IDL.Nat }),
+    'BadBurn' : IDL.Record({ 'min_burn_amount' : IDL.Nat }),
+    'Duplicate' : IDL.Record({ 'duplicate_of' : IDL.Nat }),
+    'BadFee' : IDL.Record({ 'expected_fee' : IDL.Nat }),
+    'CreatedInFuture' : IDL.Record({ 'ledger_time' : IDL.Nat64 }),
+    'TooOld' : IDL.Null,
+    'InsufficientFunds' : IDL.Record({ 'balance' : IDL.Nat }),
+  });
+  const LPTransferFromResult = IDL.Variant({
+    'Ok' : IDL.Nat,
+    'Err' : LPTransferFromError,
+  });
+  const GetBlocksArgs = IDL.Vec(
+    IDL.Record({ 'start' : IDL.Nat, 'length' : IDL.Nat })
+  );
+  ICRC3Value.fill(
+    IDL.Variant({
+      'Int' : IDL.Int,
+      'Map' : IDL.Vec(IDL.Tuple(IDL.Text, ICRC3Value)),
+      'Nat' : IDL.Nat,
+      'Blob' : IDL.Vec(IDL.Nat8),
+      'Text' : IDL.Text,
+      'Array' : IDL.Vec(ICRC3Value),
+    })
+  );
+  GetBlocksResult.fill(
+    IDL.Record({
+      'log_length' : IDL.Nat,
+      'blocks' : IDL.Vec(IDL.Record({ 'id' : IDL.Nat, 'block' : ICRC3Value })),
+      'archived_blocks' : IDL.Vec(
+        IDL.Record({
+          'args' : GetBlocksArgs,
+          'callback' : IDL.Func([GetBlocksArgs], [GetBlocksResult], ['query']),
+        })
+      ),
+    })
+  );
+  const LPGetBlocksResult = IDL.Variant({
+    'Ok' : GetBlocksResult,
+    'Err' : IDL.Text,
+  });
+  const LPLocksReply = IDL.Record({
+    'lp_token_symbol' : IDL.Text,
+    'locked' : IDL.Nat,
+    'locks' : IDL.Vec(LPLockReply),
+    'lp_token_supply' : IDL.Nat,
+    'symbol' : IDL.Text,
+  });
+  const LPLocksResult = IDL.Variant({ 'Ok' : LPLocksReply, 'Err' : IDL.Text });
+  const MintPositionArgs = IDL.Record({
+    'token_0' : IDL.Text,
+    'token_1' : IDL.Text,
+    'amount_0' : IDL.Nat,
+    'amount_1' : IDL.Nat,
+    'tick_lower' : IDL.Int32,
+    'tick_upper' : IDL.Int32,
+    'lp_fee_bps' : IDL.Opt(IDL.Nat8),
+  });
+  const OrdersResult = IDL.Variant({
+    'Ok' : IDL.Vec(OrderReply),
+    'Err' : IDL.Text,
+  });
+  const PoolFeeChangeReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'old_dynamic_fee_bps' : IDL.Opt(IDL.Tuple(IDL.Nat8, IDL.Nat8)),
+    'fee_change_id' : IDL.Nat64,
+    'lp_token_symbol' : IDL.Text,
+    'old_lp_fee_bps' : IDL.Nat8,
+    'kong_fee_bps' : IDL.Nat8,
+    'old_kong_fee_bps' : IDL.Nat8,
+    'principal_id' : IDL.Text,
+    'pool_id' : IDL.Nat32,
+    'symbol' : IDL.Text,
+    'lp_fee_bps' : IDL.Nat8,
+    'dynamic_fee_bps' : IDL.Opt(IDL.Tuple(IDL.Nat8, IDL.Nat8)),
+  });
+  const PoolFeeChangesResult = IDL.Variant({
+    'Ok' : IDL.Vec(PoolFeeChangeReply),
+    'Err' : IDL.Text,
+  });
+  const PoolType = IDL.Variant({
+    'StableSwap' : IDL.Record({ 'amp' : IDL.Nat64 }),
+    'Weighted' : IDL.Record({ 'weight_0' : IDL.Nat8 }),
+    'ConstantProduct' : IDL.Null,
+    'Concentrated' : IDL.Record({ 'tick_spacing' : IDL.Nat16 }),
+  });
   const PoolReply = IDL.Record({
     'lp_token_symbol' : IDL.Text,
     'name' : IDL.Text,
     'lp_fee_0' : IDL.Nat,
     'lp_fee_1' : IDL.Nat,
+    'pool_type' : PoolType,
     'balance_0' : IDL.Nat,
     'balance_1' : IDL.Nat,
     'address_0' : IDL.Text,
     'address_1' : IDL.Text,
+    'live_lp_fee_bps' : IDL.Nat8,
     'symbol_0' : IDL.Text,
     'symbol_1' : IDL.Text,
     'pool_id' : IDL.Nat32,
@@ -287,14 +654,91 @@ export const idlFactory = ({ IDL }) => {
     'Ok' : IDL.Vec(PoolReply),
     'Err' : IDL.Text,
   });
+  const PositionPnlReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'deposit_0' : IDL.Nat,
+    'deposit_1' : IDL.Nat,
+    'usd_impermanent_loss' : IDL.Opt(IDL.Float64),
+    'lp_token_symbol' : IDL.Text,
+    'balance' : IDL.Nat,
+    'fees_0' : IDL.Nat,
+    'fees_1' : IDL.Nat,
+    'usd_deposit' : IDL.Opt(IDL.Float64),
+    'amount_0' : IDL.Nat,
+    'amount_1' : IDL.Nat,
+    'usd_value' : IDL.Float64,
+    'symbol_0' : IDL.Text,
+    'symbol_1' : IDL.Text,
+    'pool_id' : IDL.Nat32,
+    'usd_fees' : IDL.Float64,
+    'usd_pnl' : IDL.Opt(IDL.Float64),
+    'symbol' : IDL.Text,
+  });
+  const PositionPnlResult = IDL.Variant({
+    'Ok' : PositionPnlReply,
+    'Err' : IDL.Text,
+  });
+  const PositionsResult = IDL.Variant({
+    'Ok' : IDL.Vec(PositionReply),
+    'Err' : IDL.Text,
+  });
+  const TokenProtocolFeesReply = IDL.Record({
+    'chain' : IDL.Text,
+    'swept' : IDL.Nat,
+    'address' : IDL.Text,
+    'accrued' : IDL.Nat,
+    'symbol' : IDL.Text,
+  });
+  const PoolProtocolFeesReply = IDL.Record({
+    'accrued_0' : IDL.Nat,
+    'accrued_1' : IDL.Nat,
+    'symbol_0' : IDL.Text,
+    'symbol_1' : IDL.Text,
+    'swept_0' : IDL.Nat,
+    'swept_1' : IDL.Nat,
+    'pool_id' : IDL.Nat32,
+    'symbol' : IDL.Text,
+    'lp_fee_bps' : IDL.Nat8,
+  });
+  const ProtocolFeesReply = IDL.Record({
+    'tokens' : IDL.Vec(TokenProtocolFeesReply),
+    'pools' : IDL.Vec(PoolProtocolFeesReply),
+    'treasury' : IDL.Opt(IDL.Text),
+  });
+  const ProtocolFeesResult = IDL.Variant({
+    'Ok' : ProtocolFeesReply,
+    'Err' : IDL.Text,
+  });
+  const ReferralTokenReply = IDL.Record({
+    'claim_id' : IDL.Opt(IDL.Nat64),
+    'chain' : IDL.Text,
+    'volume' : IDL.Nat,
+    'earned' : IDL.Nat,
+    'address' : IDL.Text,
+    'symbol' : IDL.Text,
+  });
+  const ReferralStatsReply = IDL.Record({
+    'referees' : IDL.Nat32,
+    'referral_fee_share_bps' : IDL.Nat16,
+    'tokens' : IDL.Vec(ReferralTokenReply),
+    'active_referees' : IDL.Nat32,
+    'my_referral_code' : IDL.Text,
+  });
+  const ReferralStatsResult = IDL.Variant({
+    'Ok' : ReferralStatsReply,
+    'Err' : IDL.Text,
+  });
   const RemoveLiquidityArgs = IDL.Record({
     'signature_0' : IDL.Opt(IDL.Text),
     'signature_1' : IDL.Opt(IDL.Text),
+    'receive_token' : IDL.Opt(IDL.Text),
     'token_0' : IDL.Text,
     'token_1' : IDL.Text,
     'payout_address_0' : IDL.Opt(IDL.Text),
     'payout_address_1' : IDL.Opt(IDL.Text),
+    'min_receive_amount' : IDL.Opt(IDL.Nat),
     'remove_lp_token_amount' : IDL.Nat,
+    'lp_fee_bps' : IDL.Opt(IDL.Nat8),
   });
   const RemoveLiquidityReply = IDL.Record({
     'ts' : IDL.Nat64,
@@ -304,6 +748,7 @@ export const idlFactory = ({ IDL }) => {
     'transfer_ids' : IDL.Vec(TransferIdReply),
     'lp_fee_0' : IDL.Nat,
     'lp_fee_1' : IDL.Nat,
+    'swap_tx_id' : IDL.Opt(IDL.Nat64),
     'amount_0' : IDL.Nat,
     'amount_1' : IDL.Nat,
     'claim_ids' : IDL.Vec(IDL.Nat64),
@@ -342,21 +787,49 @@ export const idlFactory = ({ IDL }) => {
     'Ok' : IDL.Nat64,
     'Err' : IDL.Text,
   });
+  const ZapInArgs = IDL.Record({
+    'token' : IDL.Text,
+    'max_slippage' : IDL.Opt(IDL.Float64),
+    'pool' : IDL.Text,
+    'amount' : IDL.Nat,
+  });
+  const TwapOrderArgs = IDL.Record({
+    'receive_token' : IDL.Text,
+    'max_slippage' : IDL.Opt(IDL.Float64),
+    'num_slices' : IDL.Nat32,
+    'pay_amount' : IDL.Nat,
+    'interval_secs' : IDL.Nat64,
+    'receive_address' : IDL.Opt(IDL.Text),
+    'pay_token' : IDL.Text,
+  });
   const SwapArgs = IDL.Record({
     'receive_token' : IDL.Text,
     'max_slippage' : IDL.Opt(IDL.Float64),
     'pay_amount' : IDL.Nat,
     'referred_by' : IDL.Opt(IDL.Text),
     'receive_amount' : IDL.Opt(IDL.Nat),
+    'exact_out' : IDL.Opt(IDL.Bool),
     'receive_address' : IDL.Opt(IDL.Text),
     'pay_token' : IDL.Text,
     'pay_tx_id' : IDL.Opt(TxId),
     'pay_signature' : IDL.Opt(IDL.Text),
+    'max_routes' : IDL.Opt(IDL.Nat8),
   });
   const RequestRequest = IDL.Variant({
+    'ZapIn' : ZapInArgs,
     'AddLiquidity' : AddLiquidityArgs,
+    'TwapOrder' : TwapOrderArgs,
+    'LimitOrder' : LimitOrderArgs,
+    'CreateCampaign' : CreateCampaignArgs,
+    'CollectFees' : IDL.Nat64,
     'Swap' : SwapArgs,
     'AddPool' : AddPoolArgs,
+    'MintPosition' : MintPositionArgs,
+    'FlashSwap' : FlashSwapArgs,
+    'FillOrder' : IDL.Nat64,
+    'SweepProtocolFees' : IDL.Null,
+    'CancelOrder' : IDL.Nat64,
+    'BurnPosition' : IDL.Nat64,
     'RemoveLiquidity' : RemoveLiquidityArgs,
   });
   const SwapTxReply = IDL.Record({
@@ -373,6 +846,13 @@ export const idlFactory = ({ IDL }) => {
     'pay_chain' : IDL.Text,
     'lp_fee' : IDL.Nat,
     'gas_fee' : IDL.Nat,
+    'lp_fee_bps' : IDL.Nat8,
+  });
+  const SwapRefundReply = IDL.Record({
+    'claim_id' : IDL.Opt(IDL.Nat64),
+    'transfer_id' : IDL.Opt(IDL.Nat64),
+    'amount' : IDL.Nat,
+    'symbol' : IDL.Text,
   });
   const SwapReply = IDL.Record({
     'ts' : IDL.Nat64,
@@ -384,6 +864,7 @@ export const idlFactory = ({ IDL }) => {
     'receive_chain' : IDL.Text,
     'mid_price' : IDL.Float64,
     'pay_amount' : IDL.Nat,
+    'solana_job_id' : IDL.Opt(IDL.Nat64),
     'receive_amount' : IDL.Nat,
     'claim_ids' : IDL.Vec(IDL.Nat64),
     'pay_symbol' : IDL.Text,
@@ -393,20 +874,37 @@ export const idlFactory = ({ IDL }) => {
     'price' : IDL.Float64,
     'pay_chain' : IDL.Text,
     'slippage' : IDL.Float64,
+    'refund' : IDL.Opt(SwapRefundReply),
   });
   const RequestReply = IDL.Variant({
+    'Campaign' : CampaignReply,
     'AddLiquidity' : AddLiquidityReply,
     'Swap' : SwapReply,
     'AddPool' : AddPoolReply,
+    'Position' : PositionReply,
+    'FlashSwap' : FlashSwapReply,
+    'Order' : OrderReply,
     'RemoveLiquidity' : RemoveLiquidityReply,
     'Pending' : IDL.Null,
   });
+  const SolanaSwapJobReply = IDL.Record({
+    'request_id' : IDL.Nat64,
+    'status' : IDL.Text,
+    'updated_at' : IDL.Nat64,
+    'retry_count' : IDL.Nat8,
+    'error_message' : IDL.Opt(IDL.Text),
+    'created_at' : IDL.Nat64,
+    'job_id' : IDL.Nat64,
+    'tx_signature' : IDL.Text,
+    'solana_tx_signature_of_payout' : IDL.Opt(IDL.Text),
+  });
   const RequestsReply = IDL.Record({
     'ts' : IDL.Nat64,
     'request_id' : IDL.Nat64,
     'request' : RequestRequest,
     'statuses' : IDL.Vec(IDL.Text),
     'reply' : RequestReply,
+    'solana_swap_jobs' : IDL.Vec(SolanaSwapJobReply),
   });
   const RequestsResult = IDL.Variant({
     'Ok' : IDL.Vec(RequestsReply),
@@ -428,6 +926,21 @@ export const idlFactory = ({ IDL }) => {
     'symbol' : IDL.Text,
   });
   const SendResult = IDL.Variant({ 'Ok' : SendReply, 'Err' : IDL.Text });
+  const DynamicFeeArgs = IDL.Record({
+    'max_fee_bps' : IDL.Nat8,
+    'half_life_secs' : IDL.Nat64,
+    'max_volatility_bps' : IDL.Nat32,
+    'min_fee_bps' : IDL.Nat8,
+  });
+  const PoolFeeChangeResult = IDL.Variant({
+    'Ok' : PoolFeeChangeReply,
+    'Err' : IDL.Text,
+  });
+  const PoolResult = IDL.Variant({ 'Ok' : PoolReply, 'Err' : IDL.Text });
+  const SolanaSwapJobsResult = IDL.Variant({
+    'Ok' : IDL.Vec(SolanaSwapJobReply),
+    'Err' : IDL.Text,
+  });
   const SwapResult = IDL.Variant({ 'Ok' : SwapReply, 'Err' : IDL.Text });
   const SwapAmountsTxReply = IDL.Record({
     'receive_chain' : IDL.Text,
@@ -442,6 +955,7 @@ export const idlFactory = ({ IDL }) => {
     'pay_chain' : IDL.Text,
     'lp_fee' : IDL.Nat,
     'gas_fee' : IDL.Nat,
+    'lp_fee_bps' : IDL.Nat8,
   });
   const SwapAmountsReply = IDL.Record({
     'txs' : IDL.Vec(SwapAmountsTxReply),
@@ -462,12 +976,28 @@ export const idlFactory = ({ IDL }) => {
     'Err' : IDL.Text,
   });
   const SwapAsyncResult = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : IDL.Text });
+  const SweepProtocolFeesReply = IDL.Record({
+    'ts' : IDL.Nat64,
+    'request_id' : IDL.Nat64,
+    'status' : IDL.Text,
+    'tx_id' : IDL.Nat64,
+    'transfer_ids' : IDL.Vec(TransferIdReply),
+    'chain' : IDL.Text,
+    'to_address' : IDL.Text,
+    'amount' : IDL.Nat,
+    'symbol' : IDL.Text,
+  });
+  const SweepProtocolFeesResult = IDL.Variant({
+    'Ok' : IDL.Vec(SweepProtocolFeesReply),
+    'Err' : IDL.Text,
+  });
   const LPTokenReply = IDL.Record({
     'fee' : IDL.Nat,
     'decimals' : IDL.Nat8,
     'token_id' : IDL.Nat32,
     'chain' : IDL.Text,
     'name' : IDL.Text,
+    'ledger_id' : IDL.Opt(IDL.Principal),
     'address' : IDL.Text,
     'pool_id_of' : IDL.Nat32,
     'is_removed' : IDL.Bool,
@@ -478,13 +1008,22 @@ export const idlFactory = ({ IDL }) => {
     'IC' : ICTokenReply,
     'LP' : LPTokenReply,
     'Solana' : SolanaTokenReply,
+    'Ripple' : RippleTokenReply,
   });
   const TokensResult = IDL.Variant({
     'Ok' : IDL.Vec(TokenReply),
     'Err' : IDL.Text,
   });
-  const UpdateTokenArgs = IDL.Record({ 'token' : IDL.Text });
-  const UpdateTokenReply = IDL.Variant({ 'IC' : ICTokenReply });
+  const UpdateTokenArgs = IDL.Record({
+    'decimals' : IDL.Opt(IDL.Nat8),
+    'token' : IDL.Text,
+    'name' : IDL.Opt(IDL.Text),
+    'symbol' : IDL.Opt(IDL.Text),
+  });
+  const UpdateTokenReply = IDL.Variant({
+    'IC' : ICTokenReply,
+    'Ripple' : RippleTokenReply,
+  });
   const UpdateTokenResult = IDL.Variant({
     'Ok' : UpdateTokenReply,
     'Err' : IDL.Text,
@@ -494,16 +1033,22 @@ export const idlFactory = ({ IDL }) => {
     'usd_balance' : IDL.Float64,
     'balance' : IDL.Float64,
     'name' : IDL.Text,
+    'usd_amount_1_nat' : IDL.Nat,
     'amount_0' : IDL.Float64,
     'amount_1' : IDL.Float64,
+    'amount_1_nat' : IDL.Nat,
     'address_0' : IDL.Text,
     'address_1' : IDL.Text,
     'symbol_0' : IDL.Text,
     'symbol_1' : IDL.Text,
+    'balance_nat' : IDL.Nat,
     'usd_amount_0' : IDL.Float64,
     'usd_amount_1' : IDL.Float64,
+    'usd_amount_0_nat' : IDL.Nat,
     'chain_0' : IDL.Text,
     'chain_1' : IDL.Text,
+    'amount_0_nat' : IDL.Nat,
+    'usd_balance_nat' : IDL.Nat,
     'symbol' : IDL.Text,
     'lp_token_id' : IDL.Nat64,
   });
@@ -512,6 +1057,10 @@ export const idlFactory = ({ IDL }) => {
     'Ok' : IDL.Vec(UserBalancesReply),
     'Err' : IDL.Text,
   });
+  const UserLPLocksResult = IDL.Variant({
+    'Ok' : IDL.Vec(LPLockReply),
+    'Err' : IDL.Text,
+  });
   const ValidateAddLiquidityResult = IDL.Variant({
     'Ok' : IDL.Text,
     'Err' : IDL.Text,
@@ -523,7 +1072,7 @@ export const idlFactory = ({ IDL }) => {
   return IDL.Service({
     'add_liquidity' : IDL.Func([AddLiquidityArgs], [AddLiquidityResult], []),
     'add_liquidity_amounts' : IDL.Func(
-        [IDL.Text, IDL.Nat, IDL.Text],
+        [IDL.Text, IDL.Nat, IDL.Text, IDL.Opt(IDL.Nat8)],
         [AddLiquiditAmountsResult],
         ['query'],
       ),
@@ -534,19 +1083,23 @@ export const idlFactory = ({ IDL }) => {
       ),
     'add_pool' : IDL.Func([AddPoolArgs], [AddPoolResult], []),
     'add_token' : IDL.Func([AddTokenArgs], [AddTokenResult], []),
-    'check_pools' : IDL.Func([], [CheckPoolsResult], []),
-    'claim' : IDL.Func([IDL.Nat64], [ClaimResult], []),
-    'claims' : IDL.Func([IDL.Text], [ClaimsResult], ['query']),
-    'cleanup_expired_solana_jobs' : IDL.Func(
-        [],
-        [IDL.Variant({ 'Ok' : IDL.Nat32, 'Err' : IDL.Text })],
-        [],
-      ),
-    'get_solana_address' : IDL.Func(
-        [],
+    'burn_position' : IDL.Func([IDL.Nat64], [PositionResult], []),
+    'campaign_rewards' : IDL.Func(
         [IDL.Text],
+        [CampaignRewardsResult],
         ['query'],
       ),
+    'campaigns' : IDL.Func([IDL.Opt(IDL.Text)], [CampaignsResult], ['query']),
+    'cancel_order' : IDL.Func([IDL.Nat64], [OrderResult], []),
+    'check_pools' : IDL.Func([], [CheckPoolsResult], []),
+    'claim' : IDL.Func([IDL.Nat64], [ClaimResult], []),
+    'claims' : IDL.Func([IDL.Text], [ClaimsResult], ['query']),
+    'collect_fees' : IDL.Func([IDL.Nat64], [PositionResult], []),
+    'collect_rewards' : IDL.Func([], [CampaignRewardsResult], []),
+    'create_campaign' : IDL.Func([CreateCampaignArgs], [CampaignResult], []),
+    'flash_swap' : IDL.Func([FlashSwapArgs], [FlashSwapResult], []),
+    'get_ripple_address' : IDL.Func([], [IDL.Text], ['query']),
+    'get_solana_address' : IDL.Func([], [IDL.Text], ['query']),
     'get_user' : IDL.Func([], [UserResult], ['query']),
     'icrc10_supported_standards' : IDL.Func(
         [],
@@ -560,19 +1113,71 @@ export const idlFactory = ({ IDL }) => {
         [],
       ),
     'icrc28_trusted_origins' : IDL.Func([], [Icrc28TrustedOriginsResponse], []),
+    'limit_order' : IDL.Func([LimitOrderArgs], [OrderResult], []),
+    'lock_lp' : IDL.Func([IDL.Text, IDL.Nat, IDL.Nat64], [LPLockResult], []),
+    'lp_icrc1_balance_of' : IDL.Func([Account], [LPNatResult], ['query']),
+    'lp_icrc1_metadata' : IDL.Func([], [LPMetadataResult], ['query']),
+    'lp_icrc1_total_supply' : IDL.Func([], [LPNatResult], ['query']),
+    'lp_icrc1_transfer' : IDL.Func(
+        [IDL.Principal, LPTransferArgs],
+        [LPTransferResult],
+        [],
+      ),
+    'lp_icrc2_allowance' : IDL.Func(
+        [LPAllowanceArgs],
+        [LPAllowanceResult],
+        ['query'],
+      ),
+    'lp_icrc2_approve' : IDL.Func(
+        [IDL.Principal, LPApproveArgs],
+        [LPApproveResult],
+        [],
+      ),
+    'lp_icrc2_transfer_from' : IDL.Func(
+        [IDL.Principal, LPTransferFromArgs],
+        [LPTransferFromResult],
+        [],
+      ),
+    'lp_icrc3_get_blocks' : IDL.Func(
+        [GetBlocksArgs],
+        [LPGetBlocksResult],
+        ['query'],
+      ),
+    'lp_locks' : IDL.Func([IDL.Text], [LPLocksResult], ['query']),
+    'mint_position' : IDL.Func([MintPositionArgs], [PositionResult], []),
+    'notify_solana_swap_not_found' : IDL.Func(
+        [IDL.Nat64, IDL.Vec(IDL.Text)],
+        [IDL.Variant({ 'Ok' : IDL.Null, 'Err' : IDL.Text })],
+        [],
+      ),
     'notify_solana_transfer' : IDL.Func(
-        [IDL.Text, IDL.Opt(IDL.Text)],
+        [IDL.Text, IDL.Nat64, IDL.Nat64, IDL.Opt(IDL.Text)],
         [IDL.Variant({ 'Ok' : IDL.Null, 'Err' : IDL.Text })],
         [],
       ),
+    'orders' : IDL.Func([IDL.Text], [OrdersResult], ['query']),
+    'pool_fee_changes' : IDL.Func(
+        [IDL.Text],
+        [PoolFeeChangesResult],
+        ['query'],
+      ),
     'pools' : IDL.Func([IDL.Opt(IDL.Text)], [PoolsResult], ['query']),
+    'position_pnl' : IDL.Func(
+        [IDL.Text, IDL.Text],
+        [PositionPnlResult],
+        ['query'],
+      ),
+    'positions' : IDL.Func([IDL.Text], [PositionsResult], ['query']),
+    'protocol_fees' : IDL.Func([], [ProtocolFeesResult], ['query']),
+    'referral_stats' : IDL.Func([IDL.Text], [ReferralStatsResult], ['query']),
+    'refund_campaign' : IDL.Func([IDL.Nat32], [CampaignResult], []),
     'remove_liquidity' : IDL.Func(
         [RemoveLiquidityArgs],
         [RemoveLiquidityResult],
         [],
       ),
     'remove_liquidity_amounts' : IDL.Func(
-        [IDL.Text, IDL.Text, IDL.Nat],
+        [IDL.Text, IDL.Text, IDL.Nat, IDL.Opt(IDL.Nat8)],
         [RemoveLiquidityAmountsResult],
         ['query'],
       ),
@@ -583,15 +1188,43 @@ export const idlFactory = ({ IDL }) => {
       ),
     'requests' : IDL.Func([IDL.Opt(IDL.Nat64)], [RequestsResult], ['query']),
     'send' : IDL.Func([SendArgs], [SendResult], []),
+    'set_pool_dynamic_fee' : IDL.Func(
+        [IDL.Text, IDL.Opt(DynamicFeeArgs)],
+        [PoolFeeChangeResult],
+        [],
+      ),
+    'set_pool_fees' : IDL.Func(
+        [IDL.Text, IDL.Nat8, IDL.Nat8],
+        [PoolFeeChangeResult],
+        [],
+      ),
+    'set_pool_type' : IDL.Func([IDL.Text, PoolType], [PoolResult], []),
+    'set_user_fee_level' : IDL.Func(
+        [IDL.Text, IDL.Nat8, IDL.Opt(IDL.Nat64)],
+        [UserResult],
+        [],
+      ),
+    'solana_swap_jobs' : IDL.Func(
+        [IDL.Opt(IDL.Nat64), IDL.Opt(IDL.Text)],
+        [SolanaSwapJobsResult],
+        ['query'],
+      ),
     'swap' : IDL.Func([SwapArgs], [SwapResult], []),
     'swap_amounts' : IDL.Func(
-        [IDL.Text, IDL.Nat, IDL.Text],
+        [IDL.Text, IDL.Nat, IDL.Text, IDL.Opt(IDL.Nat8)],
+        [SwapAmountsResult],
+        ['query'],
+      ),
+    'swap_amounts_exact_out' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Nat],
         [SwapAmountsResult],
         ['query'],
       ),
     'swap_async' : IDL.Func([SwapArgs], [SwapAsyncResult], []),
+    'sweep_protocol_fees' : IDL.Func([], [SweepProtocolFeesResult], []),
     'tokens' : IDL.Func([IDL.Opt(IDL.Text)], [TokensResult], ['query']),
-    'update_solana_latest_blockhash' : IDL.Func(
+    'twap_order' : IDL.Func([TwapOrderArgs], [OrderResult], []),
+    'update_solana_blockhash' : IDL.Func(
         [IDL.Text],
         [IDL.Variant({ 'Ok' : IDL.Null, 'Err' : IDL.Text })],
         [],
@@ -603,12 +1236,14 @@ export const idlFactory = ({ IDL }) => {
       ),
     'update_token' : IDL.Func([UpdateTokenArgs], [UpdateTokenResult], []),
     'user_balances' : IDL.Func([IDL.Text], [UserBalancesResult], ['query']),
+    'user_lp_locks' : IDL.Func([IDL.Text], [UserLPLocksResult], ['query']),
     'validate_add_liquidity' : IDL.Func([], [ValidateAddLiquidityResult], []),
     'validate_remove_liquidity' : IDL.Func(
         [],
         [ValidateRemoveLiquidityResult],
         [],
       ),
+    'zap_in' : IDL.Func([ZapInArgs], [AddLiquidityResult], []),
   });
 };
-export const init = ({ IDL }) => { return []; };
+export const init = ({ IDL }) => { return []; };
\ No newline at end of file
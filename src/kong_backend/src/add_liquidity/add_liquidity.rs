@@ -66,24 +66,24 @@ pub enum TokenIndex {
 #[update(guard = "not_in_maintenance_mode")]
 pub async fn add_liquidity(args: AddLiquidityArgs) -> Result<AddLiquidityReply, String> {
     // Import token_map to check token types
-    use crate::chains::chains::SOL_CHAIN;
+    use crate::chains::chains::{SOL_CHAIN, XRP_CHAIN};
     use crate::stable_token::token_map;
 
-    if pool_map::get_by_tokens(&args.token_0, &args.token_1).is_err() {
+    if pool_map::get_by_tokens(&args.token_0, &args.token_1, args.lp_fee_bps).is_err() {
         return add_pool_if_not_exist(args).await;
     }
 
-    // Check if either token is a Solana token
+    // Check if either token is a Solana or XRPL token
     let token_0_is_solana = token_map::get_by_token(&args.token_0)
-        .map(|t| t.chain() == SOL_CHAIN)
+        .map(|t| t.chain() == SOL_CHAIN || t.chain() == XRP_CHAIN)
         .unwrap_or(false);
     let token_1_is_solana = token_map::get_by_token(&args.token_1)
-        .map(|t| t.chain() == SOL_CHAIN)
+        .map(|t| t.chain() == SOL_CHAIN || t.chain() == XRP_CHAIN)
         .unwrap_or(false);
 
     // Route based on token types
     if token_0_is_solana || token_1_is_solana {
-        // If either token is Solana, use transfer_from flow (requires signatures)
+        // If either token is Solana or XRPL, use transfer_from flow (requires signatures)
         add_liquidity_transfer_from(args).await
     } else if args.tx_id_0.is_none() && args.tx_id_1.is_none() {
         // ICRC2 approve flow for IC tokens
@@ -106,24 +106,24 @@ pub async fn add_liquidity(args: AddLiquidityArgs) -> Result<AddLiquidityReply,
 #[update(guard = "not_in_maintenance_mode")]
 pub async fn add_liquidity_async(args: AddLiquidityArgs) -> Result<u64, String> {
     // Import token_map to check token types
-    use crate::chains::chains::SOL_CHAIN;
+    use crate::chains::chains::{SOL_CHAIN, XRP_CHAIN};
     use crate::stable_token::token_map;
 
-    if pool_map::get_by_tokens(&args.token_0, &args.token_1).is_err() {
+    if pool_map::get_by_tokens(&args.token_0, &args.token_1, args.lp_fee_bps).is_err() {
         return add_pool_if_not_exist_async(args).await;
     }
 
-    // Check if either token is a Solana token
+    // Check if either token is a Solana or XRPL token
     let token_0_is_solana = token_map::get_by_token(&args.token_0)
-        .map(|t| t.chain() == SOL_CHAIN)
+        .map(|t| t.chain() == SOL_CHAIN || t.chain() == XRP_CHAIN)
         .unwrap_or(false);
     let token_1_is_solana = token_map::get_by_token(&args.token_1)
-        .map(|t| t.chain() == SOL_CHAIN)
+        .map(|t| t.chain() == SOL_CHAIN || t.chain() == XRP_CHAIN)
         .unwrap_or(false);
 
     // Route based on token types
     if token_0_is_solana || token_1_is_solana {
-        // If either token is Solana, use transfer_from flow (requires signatures)
+        // If either token is Solana or XRPL, use transfer_from flow (requires signatures)
         add_liquidity_transfer_from_async(args).await
     } else if args.tx_id_0.is_none() && args.tx_id_1.is_none() {
         // ICRC2 approve flow for IC tokens
@@ -13,8 +13,8 @@ pub struct AddLiquidityArgs {
     pub token_1: String,
     pub amount_1: Nat,
     pub tx_id_1: Option<TxId>,
+    pub lp_fee_bps: Option<u8>, // fee tier of the pool, defaults to the first pool of the token pair
     // Cross-chain signature support (following issue #6 spec)
-    pub signature_0: Option<String>,     // Ed25519 signature for token_0 transfer
-    pub signature_1: Option<String>,     // Ed25519 signature for token_1 transfer
+    pub signature_0: Option<String>, // Ed25519 signature for token_0 transfer
+    pub signature_1: Option<String>, // Ed25519 signature for token_1 transfer
 }
-
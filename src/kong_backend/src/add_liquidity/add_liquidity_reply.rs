@@ -27,6 +27,8 @@ pub struct AddLiquidityReply {
     pub transfer_ids: Vec<TransferIdReply>,
     pub claim_ids: Vec<u64>,
     pub ts: u64,
+    #[serde(default)]
+    pub swap_tx_id: Option<u64>, // tx_id of the internal swap of zap_in()
 }
 
 impl TryFrom<&AddLiquidityTx> for AddLiquidityReply {
@@ -60,6 +62,7 @@ impl TryFrom<&AddLiquidityTx> for AddLiquidityReply {
                 .collect(),
             claim_ids: add_liquidity_tx.claim_ids.clone(),
             ts: add_liquidity_tx.ts,
+            swap_tx_id: add_liquidity_tx.swap_tx_id,
         })
     }
 }
@@ -116,6 +119,7 @@ impl AddLiquidityReply {
                 .collect(),
             claim_ids: claim_ids.to_vec(),
             ts,
+            swap_tx_id: None,
         }
     }
 }
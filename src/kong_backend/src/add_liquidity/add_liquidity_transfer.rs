@@ -234,7 +234,7 @@ async fn process_add_liquidity(
         let tok_id_0 = tok_0.token_id();
         let tok_1 = token_1.unwrap();
         let tok_id_1 = tok_1.token_id();
-        match pool_map::get_by_token_ids(tok_id_0, tok_id_1) {
+        match pool_map::get_by_token_ids(tok_id_0, tok_id_1, args.lp_fee_bps) {
             Some(pool) => {
                 if transfer_0.is_err() && tx_id_0.is_none() {
                     transfer_0 = transfer_from_token(
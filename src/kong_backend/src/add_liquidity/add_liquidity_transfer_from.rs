@@ -3,7 +3,7 @@ use icrc_ledger_types::icrc1::account::Account;
 
 use crate::add_pool::add_pool;
 use crate::add_pool::add_pool_args::AddPoolArgs;
-use crate::chains::chains::SOL_CHAIN;
+use crate::chains::chains::{SOL_CHAIN, XRP_CHAIN};
 use crate::helpers::nat_helpers::{
     nat_add, nat_divide, nat_is_zero, nat_multiply, nat_sqrt, nat_subtract, nat_to_decimal_precision, nat_zero,
 };
@@ -12,11 +12,16 @@ use crate::ic::{
     network::ICNetwork,
     transfer::{icrc1_transfer, icrc2_transfer_from},
 };
+use crate::ripple::create_ripple_payment_job::create_ripple_payment_job;
+use crate::ripple::verify_transfer::{
+    get_sender_from_transfers as get_ripple_sender_from_transfers, verify_transfer as verify_transfer_ripple, RippleVerificationResult,
+};
 use crate::solana::verify_transfer::verify_transfer as verify_transfer_solana;
 use crate::stable_claim::{claim_map, stable_claim::StableClaim};
 use crate::stable_kong_settings::kong_settings_map;
-use crate::stable_lp_token::{lp_token_map, stable_lp_token::StableLPToken};
-use crate::stable_pool::{pool_map, stable_pool::StablePool};
+use crate::stable_lp_token::lp_token_fees::LPTokenBasis;
+use crate::stable_lp_token::{lp_block_map, lp_token_map, stable_lp_token::StableLPToken};
+use crate::stable_pool::{pool_lock, pool_map, pool_type::PoolType, stable_pool::StablePool, stable_swap_math};
 use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
 use crate::stable_token::stable_token::StableToken;
 use crate::stable_token::token::Token;
@@ -78,7 +83,8 @@ async fn check_arguments(args: &AddLiquidityArgs) -> Result<(u32, StablePool, Na
 
     // add_amount_0 and add_amount_1 are the amounts to be added to the pool with the current state
     // these are the amounts that will be transferred to the pool
-    let (pool, add_amount_0, add_amount_1, _) = calculate_amounts(&args.token_0, &args.amount_0, &args.token_1, &args.amount_1)?;
+    let (pool, add_amount_0, add_amount_1, _) =
+        calculate_amounts(&args.token_0, &args.amount_0, &args.token_1, &args.amount_1, args.lp_fee_bps)?;
 
     let token_0 = pool.token_0();
     if token_0.is_removed() {
@@ -92,6 +98,13 @@ async fn check_arguments(args: &AddLiquidityArgs) -> Result<(u32, StablePool, Na
         if args.tx_id_0.is_none() {
             Err("Token_0: Solana tokens require tx_id_0 for verification".to_string())?
         }
+    } else if token_0.chain() == XRP_CHAIN {
+        if args.signature_0.is_none() {
+            Err("Token_0: XRPL tokens require signature_0 for verification".to_string())?
+        }
+        if args.tx_id_0.is_none() {
+            Err("Token_0: XRPL tokens require tx_id_0 for verification".to_string())?
+        }
     } else {
         // IC token_0 - uses ICRC2 approve (caller() verification)
         if args.tx_id_0.is_some() {
@@ -114,6 +127,13 @@ async fn check_arguments(args: &AddLiquidityArgs) -> Result<(u32, StablePool, Na
         if args.tx_id_1.is_none() {
             Err("Token_1: Solana tokens require tx_id_1 for verification".to_string())?
         }
+    } else if token_1.chain() == XRP_CHAIN {
+        if args.signature_1.is_none() {
+            Err("Token_1: XRPL tokens require signature_1 for verification".to_string())?
+        }
+        if args.tx_id_1.is_none() {
+            Err("Token_1: XRPL tokens require tx_id_1 for verification".to_string())?
+        }
     } else {
         // IC token_1 - uses ICRC2 approve (caller() verification)
         if args.tx_id_1.is_some() {
@@ -134,9 +154,26 @@ async fn check_arguments(args: &AddLiquidityArgs) -> Result<(u32, StablePool, Na
 /// calculate the LP token amount for the user
 ///
 /// returns (pool, amount_0, amount_1, add_lp_token_amount)
-pub fn calculate_amounts(token_0: &str, amount_0: &Nat, token_1: &str, amount_1: &Nat) -> Result<(StablePool, Nat, Nat, Nat), String> {
+pub fn calculate_amounts(
+    token_0: &str,
+    amount_0: &Nat,
+    token_1: &str,
+    amount_1: &Nat,
+    lp_fee_bps: Option<u8>,
+) -> Result<(StablePool, Nat, Nat, Nat), String> {
     // Pool - make sure pool exists, refresh balances of the pool to make sure we have the latest state
-    let pool = pool_map::get_by_tokens(token_0, token_1)?;
+    let pool = pool_map::get_by_tokens(token_0, token_1, lp_fee_bps)?;
+    calculate_pool_amounts(pool, amount_0, amount_1)
+}
+
+/// same as calculate_amounts() for a given pool
+pub fn calculate_pool_amounts(pool: StablePool, amount_0: &Nat, amount_1: &Nat) -> Result<(StablePool, Nat, Nat, Nat), String> {
+    if pool.is_concentrated() {
+        Err(format!(
+            "Pool {} is a concentrated liquidity pool. Use mint_position()",
+            pool.symbol()
+        ))?
+    }
     // Token0
     let token_0 = pool.token_0();
     // reserve_0 is the total balance of token_0 in the pool = balance_0 + lp_fee_0
@@ -159,6 +196,22 @@ pub fn calculate_amounts(token_0: &str, amount_0: &Nat, token_1: &str, amount_1:
         return Ok((pool, amount_0.clone(), amount_1.clone(), add_lp_token_amount));
     }
 
+    if let PoolType::StableSwap { amp } = pool.pool_type {
+        // StableSwap pools take the amounts in any ratio and mint LP tokens by the increase of the invariant
+        let max_decimals = std::cmp::max(token_0.decimals(), token_1.decimals());
+        let add_lp_token_amount = stable_swap_math::add_liquidity_lp_amount(
+            &nat_to_decimal_precision(&reserve_0, token_0.decimals(), max_decimals),
+            &nat_to_decimal_precision(&reserve_1, token_1.decimals(), max_decimals),
+            &nat_to_decimal_precision(amount_0, token_0.decimals(), max_decimals),
+            &nat_to_decimal_precision(amount_1, token_1.decimals(), max_decimals),
+            &lp_total_supply,
+            pool.live_lp_fee_bps(),
+            amp,
+        )
+        .ok_or("Invalid LP token amount")?;
+        return Ok((pool, amount_0.clone(), amount_1.clone(), add_lp_token_amount));
+    }
+
     // amount_0 * reserve_1 = amount_1 * reserve_0 for constant K
     let amount_0_reserve_1 = nat_multiply(amount_0, &reserve_1);
     let amount_1_reserve_0 = nat_multiply(amount_1, &reserve_0);
@@ -285,6 +338,20 @@ async fn process_add_liquidity(
         });
         transfer_ids.push(transfer_id);
         request_map::update_status(request_id, StatusCode::SendToken0Success, None);
+    } else if token_0.chain() == XRP_CHAIN {
+        let canonical_message = CanonicalAddLiquidityMessage::from_add_liquidity_args(args).to_signing_message();
+        verify_ripple_token(
+            request_id,
+            &TokenIndex::Token0,
+            &token_0,
+            args.tx_id_0.as_ref(),
+            args.signature_0.as_ref(),
+            add_amount_0,
+            &canonical_message,
+            &mut transfer_ids,
+            ts,
+        )
+        .map_err(|e| format!("Token_0 XRPL payment verification failed. {}", e))?;
     } else {
         // Standard ICRC2 transfer
         transfer_from_token(
@@ -328,7 +395,16 @@ async fn process_add_liquidity(
                 let canonical_message = CanonicalAddLiquidityMessage::from_add_liquidity_args(args).to_signing_message();
 
                 // Verify the Solana transfer
-                match verify_transfer_solana(&tx_signature_str, sig, add_amount_1, &canonical_message, &token_1, sol_token.is_spl_token).await {
+                match verify_transfer_solana(
+                    &tx_signature_str,
+                    sig,
+                    add_amount_1,
+                    &canonical_message,
+                    &token_1,
+                    sol_token.is_spl_token,
+                )
+                .await
+                {
                     Ok(verification) => {
                         // Check if this Solana transaction has already been used
                         if transfer_map::contains_tx_signature(token_1.token_id(), &verification.tx_signature) {
@@ -354,6 +430,20 @@ async fn process_add_liquidity(
             }
             (Err(e), _) | (_, Err(e)) => Err(e),
         }
+    } else if token_1.chain() == XRP_CHAIN {
+        let canonical_message = CanonicalAddLiquidityMessage::from_add_liquidity_args(args).to_signing_message();
+        verify_ripple_token(
+            request_id,
+            &TokenIndex::Token1,
+            &token_1,
+            args.tx_id_1.as_ref(),
+            args.signature_1.as_ref(),
+            add_amount_1,
+            &canonical_message,
+            &mut transfer_ids,
+            ts,
+        )
+        .map_err(|e| format!("XRPL payment verification failed. {}", e))
     } else {
         // Standard ICRC2 transfer
         transfer_from_token(
@@ -428,6 +518,73 @@ async fn process_add_liquidity(
     Ok(reply)
 }
 
+/// verify the XRPL payment of a token reported by kong_rpc and record the transfer
+#[allow(clippy::too_many_arguments)]
+fn verify_ripple_token(
+    request_id: u64,
+    token_index: &TokenIndex,
+    token: &StableToken,
+    tx_id: Option<&TxId>,
+    signature: Option<&String>,
+    amount: &Nat,
+    canonical_message: &str,
+    transfer_ids: &mut Vec<u64>,
+    ts: u64,
+) -> Result<(), String> {
+    match token_index {
+        TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::SendToken0, None),
+        TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::SendToken1, None),
+    };
+
+    let result = verify_ripple_payment(token, tx_id, signature, amount, canonical_message);
+    match result {
+        Ok(verification) => {
+            let transfer_id = transfer_map::insert(&StableTransfer {
+                transfer_id: 0,
+                request_id,
+                is_send: true,
+                amount: verification.amount,
+                token_id: token.token_id(),
+                tx_id: TxId::TransactionId(verification.tx_hash),
+                ts,
+            });
+            transfer_ids.push(transfer_id);
+            match token_index {
+                TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::SendToken0Success, None),
+                TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::SendToken1Success, None),
+            };
+            Ok(())
+        }
+        Err(e) => {
+            match token_index {
+                TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::SendToken0Failed, Some(&e)),
+                TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::SendToken1Failed, Some(&e)),
+            };
+            Err(e)
+        }
+    }
+}
+
+fn verify_ripple_payment(
+    token: &StableToken,
+    tx_id: Option<&TxId>,
+    signature: Option<&String>,
+    amount: &Nat,
+    canonical_message: &str,
+) -> Result<RippleVerificationResult, String> {
+    let tx_hash = match tx_id {
+        Some(TxId::TransactionId(hash)) => hash.to_uppercase(),
+        Some(TxId::BlockIndex(_)) => Err("BlockIndex not supported for XRPL transactions".to_string())?,
+        None => Err("XRPL tokens require tx_id".to_string())?,
+    };
+    let signature = signature.ok_or("XRPL tokens require signature")?;
+    // hashes are case-insensitive hex, so compare in uppercase as stored
+    if transfer_map::contains_tx_signature(token.token_id(), &tx_hash) {
+        Err("XRPL transaction already used".to_string())?
+    }
+    verify_transfer_ripple(&tx_hash, signature, amount, canonical_message, token)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn transfer_from_token(
     request_id: u64,
@@ -490,12 +647,13 @@ pub fn update_liquidity_pool(
 ) -> Result<(StablePool, Nat, Nat, Nat), String> {
     request_map::update_status(request_id, StatusCode::CalculatePoolAmounts, None);
 
-    let token_0 = pool.token_0().address_with_chain();
-    let token_1 = pool.token_1().address_with_chain();
     // re-calculate the amounts to be added to the pool with new state (after token_0 and token_1 transfers)
     // add_amount_0 and add_amount_1 are the transferred amounts from the initial calculations
     // amount_0, amount_1 and add_lp_token_amount will be the actual amounts to be added to the pool
-    match calculate_amounts(&token_0, add_amount_0, &token_1, add_amount_1) {
+    let pool = pool_map::get_by_pool_id(pool.pool_id)
+        .ok_or_else(|| format!("Pool #{} not found", pool.pool_id))
+        .and_then(|pool| pool_lock::check_unlocked(pool.pool_id).map(|_| pool));
+    match pool.and_then(|pool| calculate_pool_amounts(pool, add_amount_0, add_amount_1)) {
         Ok((mut pool, amount_0, amount_1, add_lp_token_amount)) => {
             request_map::update_status(request_id, StatusCode::CalculatePoolAmountsSuccess, None);
 
@@ -507,7 +665,7 @@ pub fn update_liquidity_pool(
             request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
 
             // update user's LP token amount
-            update_lp_token(request_id, user_id, pool.lp_token_id, &add_lp_token_amount, ts);
+            update_lp_token(request_id, user_id, &pool, &add_lp_token_amount, &amount_0, &amount_1, ts);
 
             Ok((pool, amount_0, amount_1, add_lp_token_amount))
         }
@@ -520,26 +678,34 @@ pub fn update_liquidity_pool(
 
 /// update the user's LP token amount
 /// ensure we have the latest state of the LP token before adding the new amounts
-fn update_lp_token(request_id: u64, user_id: u32, lp_token_id: u32, add_lp_token_amount: &Nat, ts: u64) {
+fn update_lp_token(request_id: u64, user_id: u32, pool: &StablePool, add_lp_token_amount: &Nat, amount_0: &Nat, amount_1: &Nat, ts: u64) {
+    let lp_token_id = pool.lp_token_id;
+    // cost basis of the new LP tokens
+    let basis = LPTokenBasis {
+        deposit_0: amount_0.clone(),
+        deposit_1: amount_1.clone(),
+        ..Default::default()
+    };
     request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmount, None);
 
     // refresh with the latest state if the entry exists
     match lp_token_map::get_by_token_id(lp_token_id) {
-        Some(lp_token) => {
+        Some(mut lp_token) => {
             // update adding the new deposit amount
-            let new_user_lp_token = StableLPToken {
-                amount: nat_add(&lp_token.amount, add_lp_token_amount),
-                ts,
-                ..lp_token.clone()
-            };
-            lp_token_map::update(&new_user_lp_token);
+            lp_token.mint(pool, add_lp_token_amount, &basis, ts);
+            lp_token_map::update(&lp_token);
+            lp_block_map::insert_mint(lp_token_id, user_id, add_lp_token_amount, ts);
             request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountSuccess, None);
         }
         None => {
             // new entry
-            let new_user_lp_token = StableLPToken::new(user_id, lp_token_id, add_lp_token_amount.clone(), ts);
+            let mut new_user_lp_token = StableLPToken::new(user_id, lp_token_id, nat_zero(), ts);
+            new_user_lp_token.mint(pool, add_lp_token_amount, &basis, ts);
             match lp_token_map::insert(&new_user_lp_token) {
-                Ok(_) => request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountSuccess, None),
+                Ok(_) => {
+                    lp_block_map::insert_mint(lp_token_id, user_id, add_lp_token_amount, ts);
+                    request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountSuccess, None)
+                }
                 Err(e) => request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountFailed, Some(&e)),
             };
         }
@@ -616,6 +782,48 @@ async fn return_token(
     };
 
     let amount_with_gas = nat_subtract(amount, &fee).unwrap_or(nat_zero());
+
+    // XRPL tokens are returned to the account that sent the payment
+    if token.chain() == XRP_CHAIN {
+        let result = get_ripple_sender_from_transfers(token_id, transfer_ids).map(Address::RippleAddress);
+        let result = match result {
+            Ok(to_address) => create_ripple_payment_job(request_id, user_id, token, &amount_with_gas, &to_address, ts)
+                .await
+                .map_err(|e| (Some(to_address), e)),
+            Err(e) => Err((None, e)),
+        };
+        match result {
+            Ok(job_id) => {
+                let transfer_id = transfer_map::insert(&StableTransfer {
+                    transfer_id: 0,
+                    request_id,
+                    is_send: false,
+                    amount: amount_with_gas,
+                    token_id,
+                    tx_id: TxId::TransactionId(format!("job_{}", job_id)),
+                    ts,
+                });
+                transfer_ids.push(transfer_id);
+                let message = format!("Ripple payment job #{} created", job_id);
+                match token_index {
+                    TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnToken0Success, Some(&message)),
+                    TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnToken1Success, Some(&message)),
+                };
+            }
+            Err((to_address, e)) => {
+                let claim = StableClaim::new(user_id, token_id, amount, Some(request_id), to_address, ts);
+                let claim_id = claim_map::insert(&claim);
+                claim_ids.push(claim_id);
+                let message = format!("Saved as claim #{}. {}", claim_id, e);
+                match token_index {
+                    TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnToken0Failed, Some(&message)),
+                    TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnToken1Failed, Some(&message)),
+                };
+            }
+        }
+        return;
+    }
+
     match icrc1_transfer(&amount_with_gas, to_principal_id, token, None).await {
         Ok(block_id) => {
             let transfer_id = transfer_map::insert(&StableTransfer {
@@ -690,7 +898,9 @@ pub async fn add_pool_if_not_exist(args: AddLiquidityArgs) -> Result<AddLiquidit
         token_1: args.token_1,
         amount_1: args.amount_1,
         tx_id_1: args.tx_id_1,
-        lp_fee_bps: None,
+        lp_fee_bps: args.lp_fee_bps,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: args.signature_0,
         signature_1: args.signature_1,
     })
@@ -713,6 +923,7 @@ pub async fn add_pool_if_not_exist(args: AddLiquidityArgs) -> Result<AddLiquidit
         transfer_ids: res.transfer_ids,
         claim_ids: res.claim_ids,
         ts: res.ts,
+        swap_tx_id: None,
     })
 }
 
@@ -1,6 +1,7 @@
 use candid::decode_one;
 use ic_cdk;
 
+use crate::ripple::stable_memory::get_ripple_transaction;
 use crate::solana::stable_memory::get_solana_transaction;
 use crate::stable_transfer::tx_id::TxId;
 
@@ -15,7 +16,7 @@ use add_liquidity_args::AddLiquidityArgs;
 
 fn check_transaction_ready(signature: &Option<String>, tx_id: &Option<TxId>) -> Result<(), String> {
     if let (Some(_signature), Some(TxId::TransactionId(tx_sig))) = (signature, tx_id) {
-        if get_solana_transaction(tx_sig.to_string()).is_none() {
+        if get_solana_transaction(tx_sig.to_string()).is_none() && get_ripple_transaction(tx_sig).is_none() {
             return Err("TRANSACTION_NOT_READY".to_string());
         }
     }
@@ -26,6 +27,9 @@ fn check_solana_signature_provided(token: &str, signature: &Option<String>) -> R
     if token.starts_with("SOL.") && signature.is_none() {
         return Err("Solana token transfer requires signature".to_string());
     }
+    if token.starts_with("XRP.") && signature.is_none() {
+        return Err("XRPL token transfer requires signature".to_string());
+    }
     Ok(())
 }
 
@@ -39,7 +43,7 @@ pub fn validate_arguments() -> Result<(), String> {
     if let Ok(add_liquidity_args) = decode_one::<AddLiquidityArgs>(&args_bytes) {
         check_transaction_ready(&add_liquidity_args.signature_0, &add_liquidity_args.tx_id_0)?;
         check_transaction_ready(&add_liquidity_args.signature_1, &add_liquidity_args.tx_id_1)?;
-        
+
         check_solana_signature_provided(&add_liquidity_args.token_0, &add_liquidity_args.signature_0)?;
         check_solana_signature_provided(&add_liquidity_args.token_1, &add_liquidity_args.signature_1)?;
     }
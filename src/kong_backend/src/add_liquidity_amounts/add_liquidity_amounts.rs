@@ -1,10 +1,12 @@
 use candid::Nat;
 use ic_cdk::query;
 
+use crate::add_liquidity::add_liquidity_transfer_from::calculate_pool_amounts;
 use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply, nat_to_decimal_precision};
 use crate::ic::guards::not_in_maintenance_mode;
 use crate::stable_lp_token::lp_token_map;
 use crate::stable_pool::pool_map;
+use crate::stable_pool::pool_type::PoolType;
 use crate::stable_token::token::Token;
 
 use super::add_liquidity_amounts_reply::AddLiquidityAmountsReply;
@@ -12,12 +14,26 @@ use super::add_liquidity_amounts_reply::AddLiquidityAmountsReply;
 /// Add liquidity to a pool
 ///
 /// Given an amount of one of the tokens, calculate the amount of the other token to maintain a constant K
+/// Deposits to weighted pools are also in proportion to the pool balances, which keeps the price and weights unchanged
+/// StableSwap pools also take amounts in other ratios with add_liquidity(), the returned amounts are in the pool ratio
 ///
 /// The output of amount_0 and amount_1 should be passed to add_liquidity() to execute the actual transaction
 /// Also calculate the amount of LP token user will receive
+/// lp_fee_bps selects the pool of the token pair by fee tier, defaults to the first pool of the token pair
 #[query(guard = "not_in_maintenance_mode")]
-fn add_liquidity_amounts(token_0: String, amount: Nat, token_1: String) -> Result<AddLiquidityAmountsReply, String> {
-    if let Ok(pool) = pool_map::get_by_tokens(&token_0, &token_1) {
+fn add_liquidity_amounts(
+    token_0: String,
+    amount: Nat,
+    token_1: String,
+    lp_fee_bps: Option<u8>,
+) -> Result<AddLiquidityAmountsReply, String> {
+    if let Ok(pool) = pool_map::get_by_tokens(&token_0, &token_1, lp_fee_bps) {
+        if pool.is_concentrated() {
+            Err(format!(
+                "Pool {} is a concentrated liquidity pool. Use mint_position()",
+                pool.symbol()
+            ))?
+        }
         // Pool
         let symbol = pool.symbol();
         // Token0
@@ -52,12 +68,16 @@ fn add_liquidity_amounts(token_0: String, amount: Nat, token_1: String) -> Resul
         let amount_1 = nat_divide(&numerator_in_token_1_decimals, &reserve_0_in_token_1_decimals).ok_or("Invalid amount_1")?;
 
         // calculate the amount of LP token user will receive
-        // add_lp_token_amount = lp_total_supply * amount_0 / reserve_0
-        let amount_0_in_lp_token_decimals = nat_to_decimal_precision(&amount, token_0.decimals(), lp_token.decimals());
-        let reserve_0_in_lp_token_decimals = nat_to_decimal_precision(&reserve_0, token_0.decimals(), lp_token.decimals());
-        let numerator_in_lp_token_decimals = nat_multiply(&lp_total_supply, &amount_0_in_lp_token_decimals);
-        let add_lp_token_amount =
-            nat_divide(&numerator_in_lp_token_decimals, &reserve_0_in_lp_token_decimals).ok_or("Invalid LP token amount")?;
+        let add_lp_token_amount = if let PoolType::StableSwap { .. } = pool.pool_type {
+            // StableSwap pools mint LP tokens by the increase of the invariant
+            calculate_pool_amounts(pool, &amount, &amount_1)?.3
+        } else {
+            // add_lp_token_amount = lp_total_supply * amount_0 / reserve_0
+            let amount_0_in_lp_token_decimals = nat_to_decimal_precision(&amount, token_0.decimals(), lp_token.decimals());
+            let reserve_0_in_lp_token_decimals = nat_to_decimal_precision(&reserve_0, token_0.decimals(), lp_token.decimals());
+            let numerator_in_lp_token_decimals = nat_multiply(&lp_total_supply, &amount_0_in_lp_token_decimals);
+            nat_divide(&numerator_in_lp_token_decimals, &reserve_0_in_lp_token_decimals).ok_or("Invalid LP token amount")?
+        };
 
         return Ok(AddLiquidityAmountsReply {
             symbol,
@@ -73,7 +93,7 @@ fn add_liquidity_amounts(token_0: String, amount: Nat, token_1: String) -> Resul
             fee_1,
             add_lp_token_amount,
         });
-    } else if let Ok(pool) = pool_map::get_by_tokens(&token_1, &token_0) {
+    } else if let Ok(pool) = pool_map::get_by_tokens(&token_1, &token_0, lp_fee_bps) {
         let symbol = pool.symbol();
         // Token0
         let token_0 = pool.token_0();
@@ -106,12 +126,16 @@ fn add_liquidity_amounts(token_0: String, amount: Nat, token_1: String) -> Resul
         let numerator_in_token_0_decimals = nat_multiply(&amount_1_in_token_0_decimals, &reserve_0);
         let amount_0 = nat_divide(&numerator_in_token_0_decimals, &reserve_1_in_token_0_decimals).ok_or("Invalid amount_0")?;
 
-        // add_lp_token_amount = lp_total_supply * amount_1 / reserve_1
-        let amount_1_in_lp_token_decimals = nat_to_decimal_precision(&amount, token_1.decimals(), lp_token.decimals());
-        let reserve_1_in_lp_token_decimals = nat_to_decimal_precision(&reserve_1, token_1.decimals(), lp_token.decimals());
-        let numerator_in_lp_token_decimals = nat_multiply(&lp_total_supply, &amount_1_in_lp_token_decimals);
-        let add_lp_token_amount =
-            nat_divide(&numerator_in_lp_token_decimals, &reserve_1_in_lp_token_decimals).ok_or("Invalid LP token amount")?;
+        let add_lp_token_amount = if let PoolType::StableSwap { .. } = pool.pool_type {
+            // StableSwap pools mint LP tokens by the increase of the invariant
+            calculate_pool_amounts(pool, &amount_0, &amount)?.3
+        } else {
+            // add_lp_token_amount = lp_total_supply * amount_1 / reserve_1
+            let amount_1_in_lp_token_decimals = nat_to_decimal_precision(&amount, token_1.decimals(), lp_token.decimals());
+            let reserve_1_in_lp_token_decimals = nat_to_decimal_precision(&reserve_1, token_1.decimals(), lp_token.decimals());
+            let numerator_in_lp_token_decimals = nat_multiply(&lp_total_supply, &amount_1_in_lp_token_decimals);
+            nat_divide(&numerator_in_lp_token_decimals, &reserve_1_in_lp_token_decimals).ok_or("Invalid LP token amount")?
+        };
 
         return Ok(AddLiquidityAmountsReply {
             symbol,
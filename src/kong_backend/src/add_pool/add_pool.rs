@@ -3,7 +3,7 @@ use ic_cdk::update;
 use icrc_ledger_types::icrc1::account::Account;
 
 use crate::add_token::add_token::{add_ic_token, add_lp_token};
-use crate::chains::chains::{IC_CHAIN, SOL_CHAIN};
+use crate::chains::chains::{IC_CHAIN, SOL_CHAIN, XRP_CHAIN};
 use crate::helpers::nat_helpers::{nat_add, nat_is_zero, nat_multiply, nat_sqrt, nat_subtract, nat_to_decimal_precision, nat_zero};
 use crate::ic::{
     address::Address,
@@ -14,13 +14,24 @@ use crate::ic::{
     transfer::{icrc1_transfer, icrc2_transfer_from},
     verify_transfer::verify_transfer,
 };
+use crate::ripple::create_ripple_payment_job::create_ripple_payment_job;
+use crate::ripple::verify_transfer::{
+    get_sender_from_transfers as get_ripple_sender_from_transfers, verify_transfer as verify_transfer_ripple,
+};
+use crate::solana::create_solana_swap_job::create_solana_swap_job;
 use crate::solana::verify_transfer::verify_transfer as verify_transfer_solana;
 use crate::stable_claim::{claim_map, stable_claim::StableClaim};
 use crate::stable_kong_settings::kong_settings_map;
-use crate::stable_lp_token::lp_token_map;
+use crate::stable_lp_token::lp_position_map;
+use crate::stable_lp_token::lp_token_fees::LPTokenBasis;
+use crate::stable_lp_token::stable_lp_position::StableLPPosition;
 use crate::stable_lp_token::stable_lp_token::StableLPToken;
+use crate::stable_lp_token::{lp_block_map, lp_token_map};
+use crate::stable_pool::concentrated_pool::ConcentratedPool;
 use crate::stable_pool::pool_map;
+use crate::stable_pool::pool_type::PoolType;
 use crate::stable_pool::stable_pool::StablePool;
+use crate::stable_pool::{concentrated_liquidity, concentrated_math, weighted_math};
 use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
 use crate::stable_token::lp_token::LP_DECIMALS;
 use crate::stable_token::stable_token::StableToken;
@@ -33,7 +44,6 @@ use crate::stable_transfer::transfer_map;
 use crate::stable_transfer::tx_id::TxId;
 use crate::stable_tx::{add_pool_tx::AddPoolTx, stable_tx::StableTx, tx_map};
 use crate::stable_user::user_map;
-use crate::solana::create_solana_swap_job::create_solana_swap_job;
 
 use super::add_pool_args::AddPoolArgs;
 use super::add_pool_reply::AddPoolReply;
@@ -100,15 +110,16 @@ async fn check_arguments(
         Err("Invalid zero amounts".to_string())?
     }
 
-    // Check if either token is a Solana token for fee calculation
-    let has_solana_token = token_map::get_chain(&args.token_0).map(|chain| chain == SOL_CHAIN).unwrap_or(false)
-        || token_map::get_chain(&args.token_1).map(|chain| chain == SOL_CHAIN).unwrap_or(false);
+    // Check if either token is a Solana or XRPL token for fee calculation
+    let is_cross_chain = |chain: String| chain == SOL_CHAIN || chain == XRP_CHAIN;
+    let has_cross_chain_token = token_map::get_chain(&args.token_0).map(is_cross_chain).unwrap_or(false)
+        || token_map::get_chain(&args.token_1).map(is_cross_chain).unwrap_or(false);
 
     let lp_fee_bps = match args.lp_fee_bps {
         Some(lp_fee_bps) => lp_fee_bps,
         None => {
-            if has_solana_token {
-                100 // 1% fee for pools with Solana tokens
+            if has_cross_chain_token {
+                100 // 1% fee for pools with Solana or XRPL tokens
             } else {
                 kong_settings_map::get().default_lp_fee_bps // Default 0.3% for IC-only pools
             }
@@ -117,9 +128,7 @@ async fn check_arguments(
 
     let default_kong_fee_bps = kong_settings_map::get().default_kong_fee_bps;
     let kong_fee_bps = default_kong_fee_bps;
-    if lp_fee_bps < kong_fee_bps {
-        Err(format!("LP fee cannot be less than Kong fee of {}", kong_fee_bps))?
-    }
+    pool_map::check_fees(lp_fee_bps, kong_fee_bps)?;
 
     // Check tx_id_0 and tx_id_1 - support both BlockIndex and TransactionId for cross-chain
     let tx_id_0 = match &args.tx_id_0 {
@@ -153,19 +162,23 @@ async fn check_arguments(
                 "Solana tokens are added automatically via ATA discovery. Manual pool creation with Solana tokens not supported."
                     .to_string(),
             )?,
+            Some(chain) if chain == XRP_CHAIN => Err("XRP Ledger tokens are added by Kong.")?,
             Some(_) | None => Err("Token_0 chain not supported")?,
         },
     };
 
-    // make sure LP token does not already exist
-    let lp_token_address = token::address(&token_0, &token_1);
-    if token_map::exists(&lp_token_address) {
-        Err(format!("LP token {} already exists", token::symbol(&token_0, &token_1)))?
+    // a token pair can have several pools but only one for each fee tier
+    if pool_map::exists_fee_tier(&token_0, &token_1, lp_fee_bps) {
+        Err(format!(
+            "Pool {} with LP fee {} bps already exists",
+            pool_map::symbol(&token_0, &token_1),
+            lp_fee_bps
+        ))?
     }
 
-    // make sure pool does not already exist
-    if pool_map::exists(&token_0, &token_1) {
-        Err(format!("Pool {} already exists", pool_map::symbol(&token_0, &token_1)))?
+    // make sure LP token does not already exist. LP tokens of additional pools are unique by pool_id
+    if !pool_map::exists(&token_0, &token_1) && token_map::exists(&token::address(&token_0, &token_1)) {
+        Err(format!("LP token {} already exists", token::symbol(&token_0, &token_1)))?
     }
 
     // prevent creating pools with identical token pairs
@@ -177,7 +190,9 @@ async fn check_arguments(
         ))?
     }
 
-    let (add_amount_0, add_amount_1, add_lp_token_amount) = calculate_amounts(&token_0, &args.amount_0, &token_1, &args.amount_1)?;
+    let pool_type = new_pool_type(args)?;
+    let (add_amount_0, add_amount_1, add_lp_token_amount) =
+        calculate_amounts(&token_0, &args.amount_0, &token_1, &args.amount_1, &pool_type)?;
 
     // make sure user is registered, if not create a new user
     let user_id = user_map::insert(None)?;
@@ -196,13 +211,53 @@ async fn check_arguments(
     ))
 }
 
-pub fn calculate_amounts(token_0: &StableToken, amount_0: &Nat, token_1: &StableToken, amount_1: &Nat) -> Result<(Nat, Nat, Nat), String> {
+/// pool type of a new pool from weight_0 and tick_spacing
+fn new_pool_type(args: &AddPoolArgs) -> Result<PoolType, String> {
+    match (args.weight_0, args.tick_spacing) {
+        (Some(_), Some(_)) => Err("Concentrated liquidity pools cannot be weighted".to_string()),
+        (_, Some(tick_spacing)) => PoolType::from_tick_spacing(tick_spacing),
+        (weight_0, None) => PoolType::from_weight(weight_0),
+    }
+}
+
+/// state of a new concentrated liquidity pool priced at amount_1 / amount_0
+fn new_concentrated_pool(token_0: &StableToken, amount_0: &Nat, token_1: &StableToken, amount_1: &Nat) -> Result<ConcentratedPool, String> {
+    let max_decimals = std::cmp::max(token_0.decimals(), token_1.decimals());
+    let amount_0_in_max_decimals = nat_to_decimal_precision(amount_0, token_0.decimals(), max_decimals);
+    let amount_1_in_max_decimals = nat_to_decimal_precision(amount_1, token_1.decimals(), max_decimals);
+    let sqrt_price =
+        concentrated_math::sqrt_price_from_amounts(&amount_0_in_max_decimals, &amount_1_in_max_decimals).ok_or("Invalid pool price")?;
+    ConcentratedPool::new(&sqrt_price)
+}
+
+pub fn calculate_amounts(
+    token_0: &StableToken,
+    amount_0: &Nat,
+    token_1: &StableToken,
+    amount_1: &Nat,
+    pool_type: &PoolType,
+) -> Result<(Nat, Nat, Nat), String> {
     // new pool as there are no balances - take user amounts as initial ratio
     // initialize LP tokens as sqrt(amount_0 * amount_1)
     // convert the amounts to the same decimal precision as the LP token
     let amount_0_in_lp_token_decimals = nat_to_decimal_precision(amount_0, token_0.decimals(), LP_DECIMALS);
     let amount_1_in_lp_token_decimals = nat_to_decimal_precision(amount_1, token_1.decimals(), LP_DECIMALS);
-    let add_lp_token_amount = nat_sqrt(&nat_multiply(&amount_0_in_lp_token_decimals, &amount_1_in_lp_token_decimals));
+    let add_lp_token_amount = match pool_type {
+        // weighted pools use the weighted geometric mean amount_0^(weight_0 / 100) * amount_1^(weight_1 / 100)
+        PoolType::Weighted { .. } => {
+            let (weight_0, weight_1) = pool_type.weights();
+            weighted_math::initial_lp_amount(&amount_0_in_lp_token_decimals, &amount_1_in_lp_token_decimals, weight_0, weight_1)
+                .ok_or("Invalid LP token amount")?
+        }
+        PoolType::ConstantProduct | PoolType::StableSwap { .. } => {
+            nat_sqrt(&nat_multiply(&amount_0_in_lp_token_decimals, &amount_1_in_lp_token_decimals))
+        }
+        // concentrated liquidity pools do not mint LP tokens, the amounts go to a full range position
+        PoolType::Concentrated { .. } => {
+            new_concentrated_pool(token_0, amount_0, token_1, amount_1)?;
+            nat_zero()
+        }
+    };
 
     Ok((amount_0.clone(), amount_1.clone(), add_lp_token_amount))
 }
@@ -324,7 +379,14 @@ async fn process_add_pool(
 
     // add LP token
     request_map::update_status(request_id, StatusCode::AddLPToken, None);
-    let lp_token = match add_lp_token(token_0, token_1) {
+    // additional pools of a token pair have the pool_id in the LP token symbol
+    // the LP token and the pool are added without an await in between, so the pool gets the next pool_id
+    let lp_token_pool_id = if pool_map::exists(token_0, token_1) {
+        Some(pool_map::next_pool_id())
+    } else {
+        None
+    };
+    let lp_token = match add_lp_token(token_0, token_1, lp_token_pool_id) {
         Ok(lp_token) => {
             request_map::update_status(request_id, StatusCode::AddLPTokenSuccess, None);
             lp_token
@@ -357,6 +419,11 @@ async fn process_add_pool(
         lp_fee_bps,
         kong_fee_bps,
         lp_token.token_id(),
+        new_pool_type(args).unwrap_or_default(), // weight_0 and tick_spacing are validated in check_arguments()
+        token_0,
+        amount_0,
+        token_1,
+        amount_1,
     ) {
         Ok(pool) => {
             request_map::update_status(request_id, StatusCode::AddPoolSuccess, None);
@@ -383,7 +450,17 @@ async fn process_add_pool(
     };
 
     // update pool with new balances
-    update_liquidity_pool(request_id, user_id, &pool, amount_0, amount_1, add_lp_token_amount, ts);
+    update_liquidity_pool(
+        request_id,
+        user_id,
+        &pool,
+        token_0,
+        amount_0,
+        token_1,
+        amount_1,
+        add_lp_token_amount,
+        ts,
+    );
 
     // successful, add tx and update request with reply
     let add_pool_tx = AddPoolTx::new_success(
@@ -497,36 +574,45 @@ async fn verify_cross_chain_transfer(
     // Extract transaction signature
     let tx_signature_str = match &tx_id_value {
         TxId::TransactionId(hash) => hash.clone(),
-        TxId::BlockIndex(_) => return Err("BlockIndex not supported for Solana transactions".to_string()),
-    };
-
-    // Get Solana token details
-    let sol_token = match token {
-        StableToken::Solana(token) => token,
-        _ => return Err("Cross-chain transfers only supported for Solana tokens".to_string()),
+        TxId::BlockIndex(_) => return Err("BlockIndex not supported for cross-chain transactions".to_string()),
     };
 
     // Create canonical message for verification
     let canonical_message = CanonicalAddPoolMessage::from_add_pool_args(args).to_signing_message();
 
-    // Verify the Solana transfer
-    let verification = verify_transfer_solana(&tx_signature_str, signature, amount, &canonical_message, token, sol_token.is_spl_token)
+    // Verify the Solana or XRPL transfer
+    let verification = match token {
+        StableToken::Solana(sol_token) => verify_transfer_solana(
+            &tx_signature_str,
+            signature,
+            amount,
+            &canonical_message,
+            token,
+            sol_token.is_spl_token,
+        )
         .await
-        .map_err(|e| {
-            let error_msg = format!("Cross-chain pool verification failed: {}", e);
-            match token_index {
-                TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::VerifyToken0Failed, Some(&error_msg)),
-                TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::VerifyToken1Failed, Some(&error_msg)),
-            };
-            error_msg
-        })?;
+        .map(|verification| verification.tx_signature)
+        .map_err(|e| e.to_string()),
+        // hashes are case-insensitive hex, so compare in uppercase as stored
+        StableToken::Ripple(_) => verify_transfer_ripple(&tx_signature_str.to_uppercase(), signature, amount, &canonical_message, token)
+            .map(|verification| verification.tx_hash),
+        _ => return Err("Cross-chain transfers only supported for Solana and XRPL tokens".to_string()),
+    };
+    let tx_signature = verification.map_err(|e| {
+        let error_msg = format!("Cross-chain pool verification failed: {}", e);
+        match token_index {
+            TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::VerifyToken0Failed, Some(&error_msg)),
+            TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::VerifyToken1Failed, Some(&error_msg)),
+        };
+        error_msg
+    })?;
 
-    // Check if this Solana transaction has already been used
-    if transfer_map::contains_tx_signature(token.token_id(), &verification.tx_signature) {
-        return Err(format!("Solana transaction signature already used for {}", token.symbol()));
+    // Check if this transaction has already been used
+    if transfer_map::contains_tx_signature(token.token_id(), &tx_signature) {
+        return Err(format!("Transaction signature already used for {}", token.symbol()));
     }
 
-    let final_tx_id = TxId::TransactionId(verification.tx_signature);
+    let final_tx_id = TxId::TransactionId(tx_signature);
 
     let transfer_id = transfer_map::insert(&StableTransfer {
         transfer_id: 0,
@@ -595,49 +681,101 @@ async fn transfer_from_token(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_liquidity_pool(
     request_id: u64,
     user_id: u32,
     pool: &StablePool,
+    token_0: &StableToken,
     amount_0: &Nat,
+    token_1: &StableToken,
     amount_1: &Nat,
     add_lp_token_amount: &Nat,
     ts: u64,
 ) {
     request_map::update_status(request_id, StatusCode::UpdatePoolAmounts, None);
 
-    let update_pool = StablePool {
+    let mut update_pool = StablePool {
         balance_0: nat_add(&pool.balance_0, amount_0),
         balance_1: nat_add(&pool.balance_1, amount_1),
         ..pool.clone()
     };
+    // concentrated liquidity pools start with a full range position of the pool creator
+    if let PoolType::Concentrated { tick_spacing } = pool.pool_type {
+        if let Err(e) = add_full_range_position(&mut update_pool, user_id, token_0, amount_0, token_1, amount_1, tick_spacing, ts) {
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsFailed, Some(&e));
+        }
+    }
     pool_map::update(&update_pool);
     request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
 
     // update user's LP token amount
-    update_lp_token(request_id, user_id, pool.lp_token_id, add_lp_token_amount, ts);
+    update_lp_token(request_id, user_id, &update_pool, add_lp_token_amount, amount_0, amount_1, ts);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_full_range_position(
+    pool: &mut StablePool,
+    user_id: u32,
+    token_0: &StableToken,
+    amount_0: &Nat,
+    token_1: &StableToken,
+    amount_1: &Nat,
+    tick_spacing: u16,
+    ts: u64,
+) -> Result<u64, String> {
+    let (tick_lower, tick_upper) = concentrated_math::full_range(tick_spacing);
+    let sqrt_price_lower = concentrated_math::sqrt_price_at_tick(tick_lower).ok_or("Invalid tick_lower")?;
+    let sqrt_price_upper = concentrated_math::sqrt_price_at_tick(tick_upper).ok_or("Invalid tick_upper")?;
+    let sqrt_price = pool
+        .concentrated
+        .as_ref()
+        .ok_or("Pool is not a concentrated liquidity pool")?
+        .sqrt_price
+        .clone();
+    let max_decimals = std::cmp::max(token_0.decimals(), token_1.decimals());
+    let liquidity = concentrated_math::liquidity_for_amounts(
+        &sqrt_price,
+        &sqrt_price_lower,
+        &sqrt_price_upper,
+        &nat_to_decimal_precision(amount_0, token_0.decimals(), max_decimals),
+        &nat_to_decimal_precision(amount_1, token_1.decimals(), max_decimals),
+    )
+    .ok_or("Invalid liquidity")?;
+
+    let mut position = StableLPPosition::new(user_id, pool.pool_id, tick_lower, tick_upper, ts);
+    concentrated_liquidity::update_position(pool, &mut position, &liquidity, true)?;
+    Ok(lp_position_map::insert(&position))
 }
 
-fn update_lp_token(request_id: u64, user_id: u32, lp_token_id: u32, add_lp_token_amount: &Nat, ts: u64) {
+fn update_lp_token(request_id: u64, user_id: u32, pool: &StablePool, add_lp_token_amount: &Nat, amount_0: &Nat, amount_1: &Nat, ts: u64) {
+    let lp_token_id = pool.lp_token_id;
+    // cost basis of the new LP tokens
+    let basis = LPTokenBasis {
+        deposit_0: amount_0.clone(),
+        deposit_1: amount_1.clone(),
+        ..Default::default()
+    };
     request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmount, None);
 
     // refresh with the latest state if the entry exists
     match lp_token_map::get_by_token_id(lp_token_id) {
-        Some(lp_token) => {
+        Some(mut lp_token) => {
             // update adding the new deposit amount
-            let new_user_lp_token = StableLPToken {
-                amount: nat_add(&lp_token.amount, add_lp_token_amount),
-                ts,
-                ..lp_token.clone()
-            };
-            lp_token_map::update(&new_user_lp_token);
+            lp_token.mint(pool, add_lp_token_amount, &basis, ts);
+            lp_token_map::update(&lp_token);
+            lp_block_map::insert_mint(lp_token_id, user_id, add_lp_token_amount, ts);
             request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountSuccess, None);
         }
         None => {
             // new entry
-            let new_user_lp_token = StableLPToken::new(user_id, lp_token_id, add_lp_token_amount.clone(), ts);
+            let mut new_user_lp_token = StableLPToken::new(user_id, lp_token_id, nat_zero(), ts);
+            new_user_lp_token.mint(pool, add_lp_token_amount, &basis, ts);
             match lp_token_map::insert(&new_user_lp_token) {
-                Ok(_) => request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountSuccess, None),
+                Ok(_) => {
+                    lp_block_map::insert_mint(lp_token_id, user_id, add_lp_token_amount, ts);
+                    request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountSuccess, None)
+                }
                 Err(e) => request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountFailed, Some(&e)),
             };
         }
@@ -777,6 +915,44 @@ async fn return_token(
                 };
             }
         }
+    } else if token.chain() == XRP_CHAIN {
+        // XRPL tokens are returned to the account that sent the payment
+        let amount_with_gas = nat_subtract(amount, &token.fee()).unwrap_or(nat_zero());
+        let result = match get_ripple_sender_from_transfers(token.token_id(), transfer_ids).map(Address::RippleAddress) {
+            Ok(to_address) => create_ripple_payment_job(request_id, user_id, token, &amount_with_gas, &to_address, ts)
+                .await
+                .map_err(|e| (Some(to_address), format!("Error creating payment job: {}", e))),
+            Err(e) => Err((None, e)),
+        };
+        match result {
+            Ok(job_id) => {
+                let transfer_id = transfer_map::insert(&StableTransfer {
+                    transfer_id: 0,
+                    request_id,
+                    is_send: false,
+                    amount: amount_with_gas,
+                    token_id: token.token_id(),
+                    tx_id: TxId::TransactionId(format!("job_{}", job_id)),
+                    ts,
+                });
+                transfer_ids.push(transfer_id);
+                let message = format!("Ripple payment job #{} created", job_id);
+                match token_index {
+                    TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnToken0Success, Some(&message)),
+                    TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnToken1Success, Some(&message)),
+                };
+            }
+            Err((to_address, e)) => {
+                let claim = StableClaim::new(user_id, token.token_id(), amount, Some(request_id), to_address, ts);
+                let claim_id = claim_map::insert(&claim);
+                claim_ids.push(claim_id);
+                let message = format!("Saved as claim #{}. {}", claim_id, e);
+                match token_index {
+                    TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnToken0Failed, Some(&message)),
+                    TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnToken1Failed, Some(&message)),
+                };
+            }
+        }
     } else {
         // IC token - use existing transfer logic
         let amount_with_gas = nat_subtract(amount, &token.fee()).unwrap_or(nat_zero());
@@ -818,8 +994,28 @@ async fn return_token(
     }
 }
 
-fn add_new_pool(token_id_0: u32, token_id_1: u32, lp_fee_bps: u8, kong_fee_bps: u8, lp_token_id: u32) -> Result<StablePool, String> {
-    let pool = StablePool::new(token_id_0, token_id_1, lp_fee_bps, kong_fee_bps, lp_token_id);
+#[allow(clippy::too_many_arguments)]
+fn add_new_pool(
+    token_id_0: u32,
+    token_id_1: u32,
+    lp_fee_bps: u8,
+    kong_fee_bps: u8,
+    lp_token_id: u32,
+    pool_type: PoolType,
+    token_0: &StableToken,
+    amount_0: &Nat,
+    token_1: &StableToken,
+    amount_1: &Nat,
+) -> Result<StablePool, String> {
+    let concentrated = match pool_type {
+        PoolType::Concentrated { .. } => Some(new_concentrated_pool(token_0, amount_0, token_1, amount_1)?),
+        _ => None,
+    };
+    let pool = StablePool {
+        pool_type,
+        concentrated,
+        ..StablePool::new(token_id_0, token_id_1, lp_fee_bps, kong_fee_bps, lp_token_id)
+    };
     let pool_id = pool_map::insert(&pool)?;
 
     // Retrieves the inserted pool by its pool_id
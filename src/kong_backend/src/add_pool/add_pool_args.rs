@@ -18,6 +18,8 @@ pub struct AddPoolArgs {
     pub amount_1: Nat,
     pub tx_id_1: Option<TxId>,
     pub lp_fee_bps: Option<u8>,
+    pub weight_0: Option<u8>,      // weight of token_0 in percent for a weighted pool. None for 50/50
+    pub tick_spacing: Option<u16>, // tick spacing for a concentrated liquidity pool. None for liquidity over the full price range
     // Cross-chain fields (if signature exists, it's cross-chain)
     pub signature_0: Option<String>,     // Ed25519 signature for token_0 transfer
     pub signature_1: Option<String>,     // Ed25519 signature for token_1 transfer
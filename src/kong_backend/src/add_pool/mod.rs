@@ -1,6 +1,7 @@
 use candid::decode_one;
 use ic_cdk;
 
+use crate::ripple::stable_memory::get_ripple_transaction;
 use crate::solana::stable_memory::get_solana_transaction;
 use crate::stable_transfer::tx_id::TxId;
 
@@ -13,7 +14,7 @@ use add_pool_args::AddPoolArgs;
 
 fn check_transaction_ready(signature: &Option<String>, tx_id: &Option<TxId>) -> Result<(), String> {
     if let (Some(_signature), Some(TxId::TransactionId(tx_sig))) = (signature, tx_id) {
-        if get_solana_transaction(tx_sig.to_string()).is_none() {
+        if get_solana_transaction(tx_sig.to_string()).is_none() && get_ripple_transaction(tx_sig).is_none() {
             return Err("TRANSACTION_NOT_READY".to_string());
         }
     }
@@ -24,6 +25,9 @@ fn check_solana_signature_provided(token: &str, signature: &Option<String>) -> R
     if token.starts_with("SOL.") && signature.is_none() {
         return Err("Solana token transfer requires signature".to_string());
     }
+    if token.starts_with("XRP.") && signature.is_none() {
+        return Err("XRPL token transfer requires signature".to_string());
+    }
     Ok(())
 }
 
@@ -37,7 +41,7 @@ pub fn validate_arguments() -> Result<(), String> {
     if let Ok(add_pool_args) = decode_one::<AddPoolArgs>(&args_bytes) {
         check_transaction_ready(&add_pool_args.signature_0, &add_pool_args.tx_id_0)?;
         check_transaction_ready(&add_pool_args.signature_1, &add_pool_args.tx_id_1)?;
-        
+
         check_solana_signature_provided(&add_pool_args.token_0, &add_pool_args.signature_0)?;
         check_solana_signature_provided(&add_pool_args.token_1, &add_pool_args.signature_1)?;
     }
@@ -1,15 +1,19 @@
 use candid::{Nat, Principal};
 use ic_cdk::update;
 
-use crate::chains::chains::{IC_CHAIN, SOL_CHAIN};
-use crate::ic::guards::{caller_is_kong_rpc, not_in_maintenance_mode};
+use crate::chains::chains::{IC_CHAIN, SOL_CHAIN, XRP_CHAIN};
+use crate::ic::guards::{caller_is_kingkong, caller_is_kong_rpc, not_in_maintenance_mode};
+use crate::ripple::transaction::amount::currency_code;
+use crate::ripple::utils::validation;
+use crate::solana::network::{SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
 use crate::stable_token::ic_token::ICToken;
 use crate::stable_token::lp_token::LPToken;
+use crate::stable_token::ripple_token::{RippleToken, XRP_CURRENCY};
 use crate::stable_token::solana_token::SolanaToken;
 use crate::stable_token::stable_token::StableToken;
 use crate::stable_token::token_map;
 
-use super::add_token_args::{AddSplTokenArgs, AddTokenArgs};
+use super::add_token_args::{AddRippleTokenArgs, AddSplTokenArgs, AddTokenArgs};
 use super::add_token_reply::AddTokenReply;
 
 /// Adds a token to Kong
@@ -51,6 +55,7 @@ async fn add_token(args: AddTokenArgs) -> Result<AddTokenReply, String> {
                     // Solana tokens are added automatically via ATA discovery
                     Err("Solana SPL tokens are added automatically.".to_string())?
                 }
+                XRP_CHAIN => Err("XRP Ledger tokens are added by Kong.".to_string())?,
                 _ => Err("Chain not supported")?,
             }
         }
@@ -138,6 +143,17 @@ async fn add_solana_token_internal(args: &AddSplTokenArgs) -> Result<StableToken
     // Use provided fee or default to 5000 (0.005 SOL)
     let fee = args.fee.clone().unwrap_or_else(|| Nat::from(5000u64));
 
+    let is_spl_token = mint_address != SYSTEM_PROGRAM_ID; // False for native SOL
+                                                          // SPL tokens are classic SPL Token or Token-2022 mints
+    if is_spl_token && args.program_id != TOKEN_PROGRAM_ID && args.program_id != TOKEN_2022_PROGRAM_ID {
+        Err(format!("Unsupported token program {} for {}", args.program_id, args.token))?
+    }
+    let has_transfer_fee = args.has_transfer_fee.unwrap_or(false);
+    // the transfer fee extension only exists for Token-2022 mints
+    if has_transfer_fee && args.program_id != TOKEN_2022_PROGRAM_ID {
+        Err(format!("Transfer fee extension requires a Token-2022 mint for {}", args.token))?
+    }
+
     let solana_token = StableToken::Solana(SolanaToken {
         token_id: 0, // Will be set by insert
         name: args.name.clone(),
@@ -146,7 +162,8 @@ async fn add_solana_token_internal(args: &AddSplTokenArgs) -> Result<StableToken
         fee,
         mint_address: mint_address.to_string(),
         program_id: args.program_id.clone(),
-        is_spl_token: mint_address != "11111111111111111111111111111111", // False for native SOL
+        is_spl_token,
+        has_transfer_fee,
     });
 
     let token_id = token_map::insert(&solana_token)?;
@@ -155,8 +172,59 @@ async fn add_solana_token_internal(args: &AddSplTokenArgs) -> Result<StableToken
     token_map::get_by_token_id(token_id).ok_or_else(|| format!("Failed to add Solana token {}", args.token))
 }
 
-pub fn add_lp_token(token_0: &StableToken, token_1: &StableToken) -> Result<StableToken, String> {
-    let lp_token = StableToken::LP(LPToken::new(token_0, token_1));
+/// Adds an XRP Ledger token, native XRP or an issued currency (kingkong-only).
+///
+/// Kong must hold a trust line to the issuer before users can pay issued currencies to Kong.
+#[update(hidden = true, guard = "caller_is_kingkong")]
+async fn add_ripple_token(args: AddRippleTokenArgs) -> Result<AddTokenReply, String> {
+    if token_map::get_by_address(&args.token).is_ok() {
+        Err(format!("Token {} already exists", args.token))?
+    }
+    if token_map::get_chain(&args.token).as_deref() != Some(XRP_CHAIN) {
+        Err("This endpoint is only for XRP Ledger tokens".to_string())?
+    }
+    let address = token_map::get_address(&args.token).ok_or_else(|| format!("Invalid address {}", args.token))?;
+
+    let (currency, issuer, fee) = if address == XRP_CURRENCY {
+        if args.decimals != 6 {
+            Err("XRP has 6 decimals".to_string())?
+        }
+        // Use provided fee or default to 12 drops
+        (XRP_CURRENCY.to_string(), None, args.fee.unwrap_or_else(|| Nat::from(12_u64)))
+    } else {
+        let (currency, issuer) = address
+            .split_once('.')
+            .ok_or_else(|| format!("Invalid address {}. Expected XRP.Currency.Issuer", args.token))?;
+        currency_code(currency).map_err(|e| e.to_string())?;
+        validation::validate_address(issuer).map_err(|e| format!("Invalid issuer {}: {}", issuer, e))?;
+        (
+            currency.to_string(),
+            Some(issuer.to_string()),
+            args.fee.unwrap_or_else(|| Nat::from(0_u64)),
+        )
+    };
+
+    let ripple_token = StableToken::Ripple(RippleToken {
+        token_id: 0, // Will be set by insert
+        name: args.name,
+        symbol: args.symbol,
+        decimals: args.decimals,
+        fee,
+        currency,
+        issuer,
+    });
+    let token_id = token_map::insert(&ripple_token)?;
+
+    let token = token_map::get_by_token_id(token_id).ok_or_else(|| format!("Failed to add XRP Ledger token {}", args.token))?;
+    AddTokenReply::try_from(&token)
+}
+
+/// lp_fee_bps is set for additional pools of a token pair at a different fee tier
+pub fn add_lp_token(token_0: &StableToken, token_1: &StableToken, pool_id: Option<u32>) -> Result<StableToken, String> {
+    let lp_token = match pool_id {
+        Some(pool_id) => StableToken::LP(LPToken::new_additional_pool(token_0, token_1, pool_id)),
+        None => StableToken::LP(LPToken::new(token_0, token_1)),
+    };
     let token_id = token_map::insert(&lp_token)?;
 
     // Retrieves the inserted token by its token_id
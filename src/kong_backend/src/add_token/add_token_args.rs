@@ -26,6 +26,22 @@ pub struct AddSplTokenArgs {
     pub decimals: u8,
     /// Transaction fee in lamports (defaults to 5000)
     pub fee: Option<Nat>,
-    /// Solana program ID (from Solana metadata)
+    /// Solana program ID (from Solana metadata). SPL Token or Token-2022 program for SPL tokens
     pub program_id: String,
+    /// Token-2022 mint with the transfer fee extension (from Solana metadata)
+    #[serde(default)]
+    pub has_transfer_fee: Option<bool>,
+}
+
+/// Arguments for adding an XRP Ledger token (kingkong-only).
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct AddRippleTokenArgs {
+    /// XRPL token address (format: XRP.XRP for native XRP or XRP.Currency.Issuer for issued currencies)
+    pub token: String,
+    pub name: String,
+    pub symbol: String,
+    /// 6 for native XRP. for issued currencies, the decimals Kong keeps balances in
+    pub decimals: u8,
+    /// Fee charged for payments from Kong in token units (defaults to 12 drops for XRP and 0 for issued currencies)
+    pub fee: Option<Nat>,
 }
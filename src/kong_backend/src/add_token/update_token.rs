@@ -3,6 +3,7 @@ use ic_cdk::update;
 
 use crate::ic::guards::{caller_is_kingkong, not_in_maintenance_mode};
 use crate::stable_token::ic_token::ICToken;
+use crate::stable_token::ripple_token::RippleToken;
 use crate::stable_token::solana_token::SolanaToken;
 use crate::stable_token::stable_token::StableToken;
 use crate::stable_token::token;
@@ -21,6 +22,7 @@ async fn update_token(args: UpdateTokenArgs) -> Result<UpdateTokenReply, String>
     match stable_token {
         StableToken::IC(ic_token) => UpdateTokenReply::try_from(&update_ic_token(ic_token).await?),
         StableToken::Solana(solana_token) => UpdateTokenReply::try_from(&update_solana_token(solana_token, &args).await?),
+        StableToken::Ripple(ripple_token) => UpdateTokenReply::try_from(&update_ripple_token(ripple_token, &args)?),
         StableToken::LP(_) => Err("Cannot update LP tokens directly".to_string()),
     }
 }
@@ -78,25 +80,51 @@ pub async fn update_solana_token(mut solana_token: SolanaToken, args: &UpdateTok
     token_map::update(&StableToken::Solana(solana_token.clone()));
 
     // Update LP token symbols if they exist (using old symbol to find them)
-    update_lp_token_symbols(&solana_token, &old_symbol)?;
+    update_lp_token_symbols(&StableToken::Solana(solana_token.clone()), &old_symbol)?;
 
     token_map::get_by_token_id(solana_token.token_id)
         .ok_or_else(|| format!("Failed to update Solana token with id {}", solana_token.token_id))
 }
 
-/// Updates LP token symbols that include the updated Solana token
-fn update_lp_token_symbols(solana_token: &SolanaToken, old_symbol: &str) -> Result<(), String> {
+/// Updates an XRP Ledger token's metadata
+/// Only callable by King Kong to ensure metadata changes are authorized
+pub fn update_ripple_token(mut ripple_token: RippleToken, args: &UpdateTokenArgs) -> Result<StableToken, String> {
+    caller_is_kingkong()?;
+
+    // Store the old symbol before updating (needed to find existing LP tokens)
+    let old_symbol = ripple_token.symbol.clone();
+
+    if let Some(name) = &args.name {
+        ripple_token.name = name.clone();
+    }
+    if let Some(symbol) = &args.symbol {
+        ripple_token.symbol = symbol.clone();
+    }
+    if args.decimals.is_some() {
+        return Err("Token decimals cannot be changed after creation".to_string());
+    }
+
+    token_map::update(&StableToken::Ripple(ripple_token.clone()));
+
+    update_lp_token_symbols(&StableToken::Ripple(ripple_token.clone()), &old_symbol)?;
+
+    token_map::get_by_token_id(ripple_token.token_id)
+        .ok_or_else(|| format!("Failed to update XRP Ledger token with id {}", ripple_token.token_id))
+}
+
+/// Updates LP token symbols that include the updated token
+fn update_lp_token_symbols(token: &StableToken, old_symbol: &str) -> Result<(), String> {
     // Update _ckUSDT pool for symbol
     let ckusdt = token_map::get_ckusdt()?;
     if let Ok(StableToken::LP(mut lp_token)) = token_map::get_by_token(&format!("LP.{}_{}", old_symbol, ckusdt.symbol())) {
-        lp_token.symbol = token::symbol(&StableToken::Solana(solana_token.clone()), &ckusdt);
+        lp_token.symbol = token::symbol(token, &ckusdt);
         token_map::update(&StableToken::LP(lp_token));
     }
 
     // Update _ICP pool for symbol
     let icp = token_map::get_icp()?;
     if let Ok(StableToken::LP(mut lp_token)) = token_map::get_by_token(&format!("LP.{}_{}", old_symbol, icp.symbol())) {
-        lp_token.symbol = token::symbol(&StableToken::Solana(solana_token.clone()), &icp);
+        lp_token.symbol = token::symbol(token, &icp);
         token_map::update(&StableToken::LP(lp_token));
     }
 
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 #[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateTokenArgs {
     pub token: String,
-    // Optional fields for updating Solana token metadata
+    // Optional fields for updating Solana and XRP Ledger token metadata
     pub name: Option<String>,
     pub symbol: Option<String>,
     pub decimals: Option<u8>,
@@ -5,12 +5,14 @@ use std::convert::TryFrom;
 use crate::stable_token::stable_token::StableToken;
 use crate::stable_token::token::Token;
 use crate::tokens::ic_reply::ICReply;
+use crate::tokens::ripple_reply::RippleReply;
 use crate::tokens::solana_reply::SolanaReply;
 
 #[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
 pub enum UpdateTokenReply {
     IC(ICReply),
     Solana(SolanaReply),
+    Ripple(RippleReply),
 }
 
 impl TryFrom<&StableToken> for UpdateTokenReply {
@@ -41,6 +43,17 @@ impl TryFrom<&StableToken> for UpdateTokenReply {
                 decimals: token.decimals(),
                 fee: token.fee(),
                 is_spl_token: solana_token.is_spl_token,
+                has_transfer_fee: solana_token.has_transfer_fee,
+            })),
+            StableToken::Ripple(ref ripple_token) => Ok(UpdateTokenReply::Ripple(RippleReply {
+                token_id: token.token_id(),
+                chain: token.chain(),
+                name: token.name(),
+                symbol: token.symbol(),
+                currency: ripple_token.currency.clone(),
+                issuer: ripple_token.issuer.clone(),
+                decimals: token.decimals(),
+                fee: token.fee(),
             })),
             _ => Err("Unsupported token type".to_string()),
         }
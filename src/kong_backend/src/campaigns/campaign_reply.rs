@@ -0,0 +1,88 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::nat_helpers::{nat_add, nat_multiply};
+use crate::ic::ckusdt::{ckusdt_amount, to_ckusdt_decimals_f64};
+use crate::ic::network::ICNetwork;
+use crate::stable_campaign::stable_campaign::StableCampaign;
+use crate::stable_pool::pool_map;
+use crate::stable_pool::stable_pool::StablePool;
+use crate::stable_token::stable_token::StableToken;
+use crate::stable_token::token::Token;
+use crate::stable_token::token_map;
+
+const YEAR_SECS: u64 = 365 * 24 * 60 * 60;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignReply {
+    pub campaign_id: u32,
+    pub request_id: u64,
+    pub pool_id: u32,
+    pub symbol: String,
+    pub reward_chain: String,
+    pub reward_symbol: String,
+    pub reward_address: String,
+    pub reward_amount: Nat,
+    pub reward_per_sec: Nat,
+    pub distributed: Nat,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    pub is_active: bool,
+    pub apr: Option<f64>, // annualized value of the rewards over the pool's liquidity in %. only while the campaign is active
+    #[serde(default)]
+    pub refund_claim_id: Option<u64>, // claim of the undistributed rewards returned to the funder after end_ts
+    pub ts: u64,
+}
+
+impl From<&StableCampaign> for CampaignReply {
+    fn from(campaign: &StableCampaign) -> Self {
+        let pool = pool_map::get_by_pool_id(campaign.pool_id);
+        let reward_token = token_map::get_by_token_id(campaign.reward_token_id);
+        let (reward_chain, reward_symbol, reward_address) = match reward_token {
+            Some(ref token) => (token.chain(), token.symbol(), token.address()),
+            None => (
+                "Reward chain not found".to_string(),
+                "Reward symbol not found".to_string(),
+                "Reward address not found".to_string(),
+            ),
+        };
+        let is_active = campaign.is_active(ICNetwork::get_time());
+        let apr = match (is_active, &pool, &reward_token) {
+            (true, Some(pool), Some(reward_token)) => apr(campaign, pool, reward_token),
+            _ => None,
+        };
+
+        CampaignReply {
+            campaign_id: campaign.campaign_id,
+            request_id: campaign.request_id,
+            pool_id: campaign.pool_id,
+            symbol: pool.map_or_else(|| "Pool not found".to_string(), |pool| pool.symbol()),
+            reward_chain,
+            reward_symbol,
+            reward_address,
+            reward_amount: campaign.reward_amount.clone(),
+            reward_per_sec: campaign.reward_per_sec(),
+            distributed: campaign.distributed.clone(),
+            start_ts: campaign.start_ts,
+            end_ts: campaign.end_ts,
+            is_active,
+            apr,
+            refund_claim_id: campaign.refund_claim_id,
+            ts: campaign.ts,
+        }
+    }
+}
+
+/// APR of the campaign's rewards. rewards per year in USD / pool's liquidity in USD * 100
+fn apr(campaign: &StableCampaign, pool: &StablePool, reward_token: &StableToken) -> Option<f64> {
+    let usd_amount =
+        |token: &StableToken, amount: &Nat| ckusdt_amount(token, amount).ok().and_then(|amount| to_ckusdt_decimals_f64(&amount));
+    let rewards_per_year = nat_multiply(&campaign.reward_per_sec(), &Nat::from(YEAR_SECS));
+    let usd_rewards_per_year = usd_amount(reward_token, &rewards_per_year)?;
+    let usd_liquidity = usd_amount(&pool.token_0(), &nat_add(&pool.balance_0, &pool.lp_fee_0))?
+        + usd_amount(&pool.token_1(), &nat_add(&pool.balance_1, &pool.lp_fee_1))?;
+    if usd_liquidity <= 0.0 {
+        return None;
+    }
+    Some(usd_rewards_per_year / usd_liquidity * 100.0)
+}
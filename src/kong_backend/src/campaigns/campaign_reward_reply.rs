@@ -0,0 +1,40 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::stable_campaign::stable_campaign::StableCampaign;
+use crate::stable_pool::pool_map;
+use crate::stable_token::token::Token;
+use crate::stable_token::token_map;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignRewardReply {
+    pub campaign_id: u32,
+    pub symbol: String,
+    pub reward_chain: String,
+    pub reward_symbol: String,
+    pub lp_token_amount: Nat, // LP tokens earning rewards in the campaign, including lock boosts
+    pub pending: Nat,         // rewards earned and not collected yet
+    pub collected: Nat,       // rewards collected as claims
+    pub claim_ids: Vec<u64>,
+    pub ts: u64,
+}
+
+impl CampaignRewardReply {
+    pub fn new(campaign: &StableCampaign, lp_token_amount: &Nat, pending: &Nat, collected: &Nat, claim_ids: &[u64], ts: u64) -> Self {
+        let (reward_chain, reward_symbol) = match token_map::get_by_token_id(campaign.reward_token_id) {
+            Some(token) => (token.chain(), token.symbol()),
+            None => ("Reward chain not found".to_string(), "Reward symbol not found".to_string()),
+        };
+        CampaignRewardReply {
+            campaign_id: campaign.campaign_id,
+            symbol: pool_map::get_by_pool_id(campaign.pool_id).map_or_else(|| "Pool not found".to_string(), |pool| pool.symbol()),
+            reward_chain,
+            reward_symbol,
+            lp_token_amount: lp_token_amount.clone(),
+            pending: pending.clone(),
+            collected: collected.clone(),
+            claim_ids: claim_ids.to_vec(),
+            ts,
+        }
+    }
+}
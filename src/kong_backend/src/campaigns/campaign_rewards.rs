@@ -0,0 +1,159 @@
+use candid::Nat;
+
+use crate::helpers::nat_helpers::{nat_add, nat_zero};
+use crate::ic::network::ICNetwork;
+use crate::stable_campaign::campaign_map;
+use crate::stable_campaign::campaign_reward_map;
+use crate::stable_campaign::stable_campaign::StableCampaign;
+use crate::stable_campaign::stable_campaign_reward::StableCampaignReward;
+use crate::stable_lp_token::lp_token_fees::earned_lp_fee;
+use crate::stable_lp_token::lp_lock_map;
+use crate::stable_lp_token::lp_token_map;
+use crate::stable_pool::pool_map;
+
+/// update the campaigns of an LP token's pool and the user's rewards in them with the LP token balances before they change
+/// lp_token_map calls this before every insert or update of an LP token balance and LP locks before their boosts change
+pub fn update_lp_rewards(user_id: u32, lp_token_id: u32) {
+    let ts = ICNetwork::get_time();
+    update_campaign_rewards(user_id, lp_token_id, ts);
+    // boosts of locks past unlock_ts end once the rewards earned with them are settled
+    lp_lock_map::expire(user_id, lp_token_id, ts);
+}
+
+fn update_campaign_rewards(user_id: u32, lp_token_id: u32, ts: u64) {
+    let Some(pool) = pool_map::get_by_lp_token_id(lp_token_id) else {
+        return;
+    };
+    // campaigns that have not started have nothing to accrue
+    let campaigns: Vec<_> = campaign_map::get_by_pool_id(pool.pool_id)
+        .into_iter()
+        .filter(|campaign| ts > campaign.start_ts)
+        .collect();
+    if campaigns.is_empty() {
+        return;
+    }
+
+    let total_lp = total_lp(lp_token_id);
+    let user_lp = user_lp(user_id, lp_token_id);
+    for mut campaign in campaigns {
+        update_campaign(&mut campaign, &total_lp, ts);
+        update_user_reward(&campaign, user_id, &user_lp, ts);
+    }
+}
+
+/// LP tokens of a user that earn rewards in the campaigns of the LP token's pool, including the boosts of the user's locks
+pub fn user_lp(user_id: u32, lp_token_id: u32) -> Nat {
+    let amount = lp_token_map::get_by_token_id_by_user_id(lp_token_id, user_id).map_or(nat_zero(), |lp_token| lp_token.amount);
+    nat_add(&amount, &lp_lock_map::get_boost(user_id, lp_token_id))
+}
+
+/// LP tokens that earn rewards in the campaigns of the LP token's pool, including the boosts of all locks
+pub fn total_lp(lp_token_id: u32) -> Nat {
+    nat_add(&lp_token_map::get_total_supply(lp_token_id), &lp_lock_map::get_total_boost(lp_token_id))
+}
+
+/// accrue the rewards released since the campaign was last updated
+pub fn update_campaign(campaign: &mut StableCampaign, total_lp: &Nat, ts: u64) {
+    let last_update_ts = campaign.last_update_ts;
+    campaign.update_reward_per_lp(total_lp, ts);
+    if campaign.last_update_ts != last_update_ts {
+        campaign_map::update(campaign);
+    }
+}
+
+/// add the rewards earned by the user since the last update to the user's pending rewards
+/// campaign must be updated to ts first
+pub fn update_user_reward(campaign: &StableCampaign, user_id: u32, user_lp: &Nat, ts: u64) -> StableCampaignReward {
+    let mut reward = match campaign_reward_map::get(user_id, campaign.campaign_id) {
+        Some(reward) if reward.reward_per_lp == campaign.reward_per_lp => return reward,
+        Some(reward) => reward,
+        // users without rewards yet have held the same LP tokens since the campaign started
+        None => StableCampaignReward::new(user_id, campaign.campaign_id, ts),
+    };
+    let earned = earned_lp_fee(user_lp, &campaign.reward_per_lp, &reward.reward_per_lp);
+    reward.pending = nat_add(&reward.pending, &earned);
+    reward.reward_per_lp = campaign.reward_per_lp.clone();
+    reward.ts = ts;
+    campaign_reward_map::update(&reward);
+    reward
+}
+
+/// rewards of the user in the campaign not collected yet as of ts, without updating the campaign
+pub fn pending_reward(campaign: &StableCampaign, user_id: u32, user_lp: &Nat, total_lp: &Nat, ts: u64) -> Nat {
+    let mut campaign = campaign.clone();
+    campaign.update_reward_per_lp(total_lp, ts);
+    match campaign_reward_map::get(user_id, campaign.campaign_id) {
+        Some(reward) => nat_add(
+            &reward.pending,
+            &earned_lp_fee(user_lp, &campaign.reward_per_lp, &reward.reward_per_lp),
+        ),
+        None => earned_lp_fee(user_lp, &campaign.reward_per_lp, &nat_zero()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_campaign, insert_pool};
+    use crate::stable_lp_token::stable_lp_token::StableLPToken;
+    use crate::stable_pool::stable_pool::StablePool;
+
+    const LP_TOKEN_ID: u32 = 3;
+
+    // 1_000 rewards to the LPs of pool 1 from 0 to 100
+    fn setup() -> u32 {
+        init_kong_settings();
+        let pool = insert_pool(1, 1, 1_000, 2, 1_000);
+        pool_map::update(&StablePool {
+            lp_token_id: LP_TOKEN_ID,
+            ..pool
+        });
+        insert_campaign(1, 1, 1, 1_000, 0, 100).campaign_id
+    }
+
+    // change the LP token balance of a user at ts, after accruing the rewards of the balance before the change like lp_token_map
+    fn set_lp_balance(user_id: u32, amount: u64, ts: u64) {
+        update_campaign_rewards(user_id, LP_TOKEN_ID, ts);
+        match lp_token_map::get_by_token_id_by_user_id(LP_TOKEN_ID, user_id) {
+            Some(lp_token) => lp_token_map::update(&StableLPToken {
+                amount: Nat::from(amount),
+                ..lp_token
+            }),
+            None => {
+                lp_token_map::insert(&StableLPToken::new(user_id, LP_TOKEN_ID, Nat::from(amount), ts)).unwrap();
+            }
+        }
+    }
+
+    fn pending(campaign_id: u32, user_id: u32, ts: u64) -> Nat {
+        let campaign = campaign_map::get_by_campaign_id(campaign_id).unwrap();
+        pending_reward(&campaign, user_id, &user_lp(user_id, LP_TOKEN_ID), &total_lp(LP_TOKEN_ID), ts)
+    }
+
+    #[test]
+    fn test_late_joiner_earns_from_joining() {
+        let campaign_id = setup();
+        set_lp_balance(1, 100, 0);
+        // user 2 joins half way with the same LP tokens
+        set_lp_balance(2, 100, 50);
+
+        assert_eq!(pending(campaign_id, 1, 100), Nat::from(750_u32));
+        assert_eq!(pending(campaign_id, 2, 100), Nat::from(250_u32));
+    }
+
+    #[test]
+    fn test_balance_change_mid_campaign() {
+        let campaign_id = setup();
+        set_lp_balance(1, 100, 0);
+        set_lp_balance(2, 100, 0);
+        // user 1 triples its LP tokens half way
+        set_lp_balance(1, 300, 50);
+
+        // 500 split 1:1, then 500 split 3:1
+        assert_eq!(pending(campaign_id, 1, 100), Nat::from(625_u32));
+        assert_eq!(pending(campaign_id, 2, 100), Nat::from(375_u32));
+        // rewards of the balance before the change are pending in the user's reward
+        assert_eq!(campaign_reward_map::get(1, campaign_id).unwrap().pending, Nat::from(250_u32));
+    }
+}
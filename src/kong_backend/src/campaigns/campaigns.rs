@@ -0,0 +1,75 @@
+use candid::Nat;
+use ic_cdk::query;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::helpers::nat_helpers::{nat_is_zero, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_campaign::campaign_map;
+use crate::stable_campaign::campaign_reward_map;
+use crate::stable_campaign::stable_campaign::StableCampaign;
+use crate::stable_lp_token::lp_token_map;
+use crate::stable_pool::pool_map;
+use crate::stable_user::user_map;
+
+use super::campaign_reply::CampaignReply;
+use super::campaign_reward_reply::CampaignRewardReply;
+use super::campaign_rewards::{pending_reward, total_lp, user_lp};
+
+/// Return all LP incentive campaigns or the campaigns of a pool, latest first, with their APR
+#[query(guard = "not_in_maintenance_mode")]
+fn campaigns(pool: Option<String>) -> Result<Vec<CampaignReply>, String> {
+    let campaigns = match pool {
+        Some(pool) => {
+            let pool_id = pool_map::get_by_token(&pool)?.pool_id;
+            campaign_map::get_by_pool_id(pool_id).into_iter().rev().collect()
+        }
+        None => campaign_map::get(),
+    };
+    Ok(campaigns.iter().map(CampaignReply::from).collect())
+}
+
+/// Return the pending and collected rewards of a user in all campaigns
+#[query(guard = "not_in_maintenance_mode")]
+fn campaign_rewards(principal_id: String) -> Result<Vec<CampaignRewardReply>, String> {
+    let user_id = user_map::get_by_principal_id(&principal_id)
+        .ok()
+        .flatten()
+        .ok_or("User not found")?
+        .user_id;
+
+    let ts = ICNetwork::get_time();
+    Ok(user_campaigns(user_id)
+        .iter()
+        .map(|(campaign, user_lp)| {
+            let total_lp = total_lp(campaign.lp_token_id);
+            let pending = pending_reward(campaign, user_id, user_lp, &total_lp, ts);
+            let (collected, claim_ids) = campaign_reward_map::get(user_id, campaign.campaign_id)
+                .map_or((nat_zero(), Vec::new()), |reward| (reward.collected, reward.claim_ids));
+            CampaignRewardReply::new(campaign, user_lp, &pending, &collected, &claim_ids, ts)
+        })
+        .collect())
+}
+
+/// campaigns a user has LP tokens or rewards in, with the user's boosted LP tokens in the campaign's pool
+pub fn user_campaigns(user_id: u32) -> Vec<(StableCampaign, Nat)> {
+    let user_lp_tokens: BTreeMap<u32, Nat> = lp_token_map::get_by_user_id(user_id)
+        .into_iter()
+        .map(|lp_token| (lp_token.token_id, user_lp(user_id, lp_token.token_id)))
+        .collect();
+    let reward_campaign_ids: BTreeSet<u32> = campaign_reward_map::get_by_user_id(user_id)
+        .iter()
+        .map(|reward| reward.campaign_id)
+        .collect();
+
+    campaign_map::get()
+        .into_iter()
+        .filter_map(|campaign| {
+            let user_lp = user_lp_tokens.get(&campaign.lp_token_id).cloned().unwrap_or(nat_zero());
+            if nat_is_zero(&user_lp) && !reward_campaign_ids.contains(&campaign.campaign_id) {
+                return None;
+            }
+            Some((campaign, user_lp))
+        })
+        .collect()
+}
@@ -0,0 +1,97 @@
+use ic_cdk::update;
+
+use crate::helpers::nat_helpers::{nat_add, nat_is_zero, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_campaign::campaign_reward_map;
+use crate::stable_claim::claim_map;
+use crate::stable_claim::stable_claim::{ClaimStatus, StableClaim};
+use crate::stable_user::user_map;
+
+use super::campaign_reward_reply::CampaignRewardReply;
+use super::campaign_rewards::{total_lp, update_campaign, update_user_reward};
+use super::campaigns::user_campaigns;
+
+/// Collect the caller's pending rewards in all campaigns
+/// the rewards of each campaign are saved as a claim which the user collects with claim()
+#[update(guard = "not_in_maintenance_mode")]
+fn collect_rewards() -> Result<Vec<CampaignRewardReply>, String> {
+    let user_id = user_map::get_by_caller().ok().flatten().ok_or("User not found")?.user_id;
+    Ok(collect_user_rewards(user_id, ICNetwork::get_time()))
+}
+
+/// save the user's pending rewards in each campaign as a claim
+fn collect_user_rewards(user_id: u32, ts: u64) -> Vec<CampaignRewardReply> {
+    let mut replies = Vec::new();
+    for (mut campaign, user_lp) in user_campaigns(user_id) {
+        if ts <= campaign.start_ts {
+            continue;
+        }
+        let total_lp = total_lp(campaign.lp_token_id);
+        update_campaign(&mut campaign, &total_lp, ts);
+        let mut reward = update_user_reward(&campaign, user_id, &user_lp, ts);
+        if !nat_is_zero(&reward.pending) {
+            let mut claim = StableClaim::new(user_id, campaign.reward_token_id, &reward.pending, None, None, ts);
+            claim.status = ClaimStatus::Claimable;
+            claim.desc = Some(format!("Campaign #{} reward", campaign.campaign_id));
+            let claim_id = claim_map::insert(&claim);
+            reward.collected = nat_add(&reward.collected, &reward.pending);
+            reward.pending = nat_zero();
+            reward.claim_ids.push(claim_id);
+            reward.ts = ts;
+            campaign_reward_map::update(&reward);
+        }
+        replies.push(CampaignRewardReply::new(
+            &campaign,
+            &user_lp,
+            &reward.pending,
+            &reward.collected,
+            &reward.claim_ids,
+            ts,
+        ));
+    }
+
+    replies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Nat;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_campaign, insert_ic_token, insert_pool};
+    use crate::stable_lp_token::lp_token_map;
+    use crate::stable_lp_token::stable_lp_token::StableLPToken;
+
+    #[test]
+    fn test_collect_rewards_saves_claim() {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckUSDT", 6);
+        init_kong_settings();
+        let pool = insert_pool(1, 1, 1_000, 2, 1_000);
+        // 1_000 rewards of token 2 from 0 to 100
+        let campaign_id = insert_campaign(1, 1, 2, 1_000, 0, 100).campaign_id;
+        lp_token_map::insert(&StableLPToken::new(2, pool.lp_token_id, Nat::from(100_u32), 0)).unwrap();
+
+        let replies = collect_user_rewards(2, 50);
+        assert_eq!(replies.len(), 1);
+        assert!(nat_is_zero(&replies[0].pending));
+        assert_eq!(replies[0].collected, Nat::from(500_u32));
+        let claim = claim_map::get_by_claim_id(replies[0].claim_ids[0]).unwrap();
+        assert_eq!((claim.user_id, claim.token_id, claim.amount), (2, 2, Nat::from(500_u32)));
+        assert!(matches!(claim.status, ClaimStatus::Claimable));
+        assert_eq!(claim.desc, Some(format!("Campaign #{} reward", campaign_id)));
+
+        // nothing more to collect at the same time
+        assert_eq!(collect_user_rewards(2, 50)[0].claim_ids.len(), 1);
+
+        // the rest is collected in a second claim
+        let replies = collect_user_rewards(2, 100);
+        assert_eq!(replies[0].collected, Nat::from(1_000_u32));
+        assert_eq!(replies[0].claim_ids.len(), 2);
+        assert_eq!(
+            claim_map::get_by_claim_id(replies[0].claim_ids[1]).unwrap().amount,
+            Nat::from(500_u32)
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use candid::Nat;
+use ic_cdk::update;
+
+use crate::helpers::nat_helpers::nat_is_zero;
+use crate::ic::guards::caller_is_kingkong;
+use crate::ic::network::ICNetwork;
+use crate::stable_campaign::campaign_map;
+use crate::stable_campaign::stable_campaign::StableCampaign;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_pool::{pool_map, stable_pool::StablePool};
+use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
+use crate::stable_user::user_map;
+use crate::swap::swap_transfer_from::transfer_from_token;
+
+use super::campaign_reply::CampaignReply;
+use super::create_campaign_args::CreateCampaignArgs;
+
+/// Create an LP incentive campaign
+/// reward_amount of the reward token is taken from the caller with icrc2_transfer_from and distributed to the LPs of the pool
+/// from start_ts to end_ts in proportion to their LP tokens
+#[update(guard = "caller_is_kingkong")]
+async fn create_campaign(args: CreateCampaignArgs) -> Result<CampaignReply, String> {
+    let ts = ICNetwork::get_time();
+    let (user_id, pool, reward_token, reward_amount, start_ts) = check_arguments(&args, ts)?;
+    let request_id = request_map::insert(&StableRequest::new(user_id, &Request::CreateCampaign(args.clone()), ts));
+
+    request_map::update_status(request_id, StatusCode::Start, None);
+
+    let mut transfer_ids = Vec::new();
+    let caller_id = ICNetwork::caller_id();
+    let kong_backend = kong_settings_map::get().kong_backend;
+    if let Err(e) = transfer_from_token(
+        request_id,
+        &caller_id,
+        &reward_token,
+        &reward_amount,
+        &kong_backend,
+        &mut transfer_ids,
+        ts,
+    )
+    .await
+    {
+        request_map::update_status(request_id, StatusCode::Failed, None);
+        Err(format!("Req #{} failed. Reward token transfer_from failed. {}", request_id, e))?
+    }
+
+    let campaign = StableCampaign::new(
+        user_id,
+        request_id,
+        pool.pool_id,
+        pool.lp_token_id,
+        reward_token.token_id(),
+        &reward_amount,
+        start_ts,
+        args.end_ts,
+        ts,
+    );
+    let campaign_id = campaign_map::insert(&campaign);
+    request_map::update_status(request_id, StatusCode::CampaignCreated, Some(&format!("Campaign #{}", campaign_id)));
+
+    let reply = CampaignReply::from(&campaign_map::get_by_campaign_id(campaign_id).ok_or("Campaign not found")?);
+    request_map::update_reply(request_id, Reply::Campaign(reply.clone()));
+    request_map::update_status(request_id, StatusCode::Success, None);
+
+    Ok(reply)
+}
+
+fn check_arguments(args: &CreateCampaignArgs, ts: u64) -> Result<(u32, StablePool, StableToken, Nat, u64), String> {
+    let pool = pool_map::get_by_token(&args.pool)?;
+    if pool.is_removed {
+        Err("Pool is removed".to_string())?;
+    }
+    if pool.is_concentrated() {
+        Err("Campaigns are not supported for concentrated liquidity pools".to_string())?;
+    }
+
+    let reward_token = token_map::get_by_token(&args.reward_token)?;
+    if reward_token.is_removed() {
+        Err("Reward token is suspended or removed".to_string())?;
+    }
+    if !reward_token.is_icrc2() {
+        Err("Reward token must support ICRC2".to_string())?;
+    }
+    let reward_amount = args.reward_amount.clone();
+    if nat_is_zero(&reward_amount) {
+        Err("Reward amount is zero".to_string())?;
+    }
+
+    let start_ts = args.start_ts.unwrap_or(ts);
+    if start_ts < ts {
+        Err("Campaign start must not be in the past".to_string())?;
+    }
+    if args.end_ts <= start_ts {
+        Err("Campaign end must be after its start".to_string())?;
+    }
+
+    // make sure user is registered, if not create a new user
+    let user_id = user_map::insert(None)?;
+
+    Ok((user_id, pool, reward_token, reward_amount, start_ts))
+}
@@ -0,0 +1,13 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+/// Data structure for the arguments of the `create_campaign` function.
+/// Used in StableRequest
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCampaignArgs {
+    pub pool: String,
+    pub reward_token: String,
+    pub reward_amount: Nat,    // total rewards distributed over the campaign. funded with icrc2_transfer_from
+    pub start_ts: Option<u64>, // time in nanoseconds the campaign starts. defaults to now
+    pub end_ts: u64,           // time in nanoseconds the campaign ends
+}
@@ -0,0 +1,9 @@
+pub mod campaign_reply;
+pub mod campaign_reward_reply;
+pub mod campaign_rewards;
+#[allow(clippy::module_inception)]
+pub mod campaigns;
+pub mod collect_rewards;
+pub mod create_campaign;
+pub mod create_campaign_args;
+pub mod refund_campaign;
@@ -0,0 +1,96 @@
+use ic_cdk::update;
+
+use crate::helpers::nat_helpers::nat_is_zero;
+use crate::ic::guards::caller_is_kingkong;
+use crate::ic::network::ICNetwork;
+use crate::stable_campaign::campaign_map;
+use crate::stable_campaign::stable_campaign::StableCampaign;
+use crate::stable_claim::claim_map;
+use crate::stable_claim::stable_claim::{ClaimStatus, StableClaim};
+
+use super::campaign_reply::CampaignReply;
+use super::campaign_rewards::{total_lp, update_campaign};
+
+/// Return the rewards of a campaign not distributed to LPs to its funder after end_ts
+/// rewards released while the pool had no LPs and the rounding of rewards per LP token are saved as a claim of the funder
+#[update(guard = "caller_is_kingkong")]
+fn refund_campaign(campaign_id: u32) -> Result<CampaignReply, String> {
+    let mut campaign = campaign_map::get_by_campaign_id(campaign_id).ok_or(format!("Campaign #{} not found", campaign_id))?;
+    refund(&mut campaign, ICNetwork::get_time())?;
+    Ok(CampaignReply::from(&campaign))
+}
+
+/// save the undistributed rewards of an ended campaign as a claim of its funder. returns the claim_id
+fn refund(campaign: &mut StableCampaign, ts: u64) -> Result<u64, String> {
+    if ts < campaign.end_ts {
+        Err(format!("Campaign #{} has not ended", campaign.campaign_id))?;
+    }
+    if let Some(claim_id) = campaign.refund_claim_id {
+        Err(format!(
+            "Campaign #{} already refunded with claim #{}",
+            campaign.campaign_id, claim_id
+        ))?;
+    }
+
+    // accrue the rewards up to end_ts so only the rewards no LP earned are refunded
+    update_campaign(campaign, &total_lp(campaign.lp_token_id), ts);
+    let amount = campaign.undistributed();
+    if nat_is_zero(&amount) {
+        Err(format!("Campaign #{} has no undistributed rewards", campaign.campaign_id))?;
+    }
+
+    let mut claim = StableClaim::new(
+        campaign.user_id,
+        campaign.reward_token_id,
+        &amount,
+        Some(campaign.request_id),
+        None,
+        ts,
+    );
+    claim.status = ClaimStatus::Claimable;
+    claim.desc = Some(format!("Campaign #{} refund", campaign.campaign_id));
+    let claim_id = claim_map::insert(&claim);
+    campaign.refund_claim_id = Some(claim_id);
+    campaign_map::update(campaign);
+
+    Ok(claim_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Nat;
+
+    use crate::campaigns::campaign_rewards::{pending_reward, user_lp};
+    use crate::helpers::test_helpers::{init_kong_settings, insert_campaign, insert_pool};
+    use crate::stable_lp_token::lp_token_map;
+    use crate::stable_lp_token::stable_lp_token::StableLPToken;
+
+    #[test]
+    fn test_refund_undistributed_rewards() {
+        init_kong_settings();
+        let pool = insert_pool(1, 1, 1_000, 2, 1_000);
+        // 1_000 rewards of token 2 from 0 to 100 funded by user 1
+        let mut campaign = insert_campaign(1, 1, 2, 1_000, 0, 100);
+        // no LPs until 40, then 7 LP tokens
+        update_campaign(&mut campaign, &total_lp(pool.lp_token_id), 40);
+        lp_token_map::insert(&StableLPToken::new(2, pool.lp_token_id, Nat::from(7_u32), 40)).unwrap();
+
+        assert!(refund(&mut campaign, 99).is_err());
+
+        let claim_id = refund(&mut campaign, 100).unwrap();
+        // 600 released to the LPs rounds down to 599 per 7 LP tokens
+        let lp_reward = pending_reward(&campaign, 2, &user_lp(2, pool.lp_token_id), &total_lp(pool.lp_token_id), 100);
+        assert_eq!(lp_reward, Nat::from(599_u32));
+        let claim = claim_map::get_by_claim_id(claim_id).unwrap();
+        assert_eq!((claim.user_id, claim.token_id, claim.amount), (1, 2, Nat::from(401_u32)));
+        assert!(matches!(claim.status, ClaimStatus::Claimable));
+        assert_eq!(
+            campaign_map::get_by_campaign_id(campaign.campaign_id).unwrap().refund_claim_id,
+            Some(claim_id)
+        );
+
+        // refunded once
+        assert!(refund(&mut campaign, 200).is_err());
+    }
+}
@@ -17,12 +17,19 @@ use crate::add_token::add_token_args::AddTokenArgs;
 use crate::add_token::add_token_reply::AddTokenReply;
 use crate::add_token::update_token_args::UpdateTokenArgs;
 use crate::add_token::update_token_reply::UpdateTokenReply;
+use crate::campaigns::campaign_reply::CampaignReply;
+use crate::campaigns::campaign_reward_reply::CampaignRewardReply;
+use crate::campaigns::create_campaign_args::CreateCampaignArgs;
 use crate::claims::claims_timer::process_claims_timer;
+use crate::fee_tiers::fee_tiers_timer::update_fee_tiers;
 use crate::helpers::nat_helpers::{nat_to_decimals_f64, nat_to_f64};
 use crate::ic::network::ICNetwork;
-use crate::ripple::stable_memory::get_cached_ripple_address;
+use crate::lp_locks::lp_locks_timer::expire_lp_locks;
+use crate::orders::orders_timer::process_orders_timer;
+use crate::ripple::stable_memory::{cleanup_old_ripple_notifications, get_cached_ripple_address};
 use crate::solana::stable_memory::{cleanup_old_notifications, get_cached_solana_address};
 use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_lp_token::lp_lock_map::create_lp_lock_index;
 use crate::stable_request::request_archive::archive_request_map;
 use crate::stable_token::token::Token;
 use crate::stable_token::token_management::check_disabled_tokens;
@@ -36,19 +43,36 @@ use super::kong_backend::KongBackend;
 use super::{APP_NAME, APP_VERSION};
 
 // list of query calls
-static QUERY_METHODS: [&str; 13] = [
+static QUERY_METHODS: [&str; 30] = [
     "icrc1_name",
     "icrc10_supported_standards",
     "tokens",
     "pools",
+    "pool_fee_changes",
+    "protocol_fees",
     "get_user",
+    "referral_stats",
     "user_balances",
+    "position_pnl",
+    "campaigns",
+    "campaign_rewards",
     "requests",
     "add_liquidity_amounts",
     "remove_liquidity_amounts",
     "swap_amounts",
+    "swap_amounts_exact_out",
     "claims",
+    "orders",
+    "positions",
+    "lp_locks",
+    "user_lp_locks",
+    "lp_icrc1_metadata",
+    "lp_icrc1_total_supply",
+    "lp_icrc1_balance_of",
+    "lp_icrc2_allowance",
+    "lp_icrc3_get_blocks",
     "get_solana_address",
+    "solana_swap_jobs",
     "get_ripple_address",
 ];
 
@@ -57,6 +81,7 @@ async fn init() {
     ICNetwork::info_log(&format!("{} canister is being initialized", APP_NAME));
 
     create_principal_id_map();
+    create_lp_lock_index();
 
     set_timer_processes().await;
 }
@@ -71,6 +96,7 @@ async fn post_upgrade() {
     ICNetwork::info_log(&format!("{} canister has been upgraded", APP_NAME));
 
     create_principal_id_map();
+    create_lp_lock_index();
 
     // Check if Solana address is cached
     // NOTE: We cannot make inter-canister calls in post_upgrade, even with spawn
@@ -85,7 +111,7 @@ async fn post_upgrade() {
 
     let cached_ripple_address = get_cached_ripple_address();
     if !cached_ripple_address.is_empty() {
-        ICNetwork::info_log(&format!("Ripple address: {}", cached_solana_address));
+        ICNetwork::info_log(&format!("Ripple address: {}", cached_ripple_address));
     } else {
         ICNetwork::error_log("No cached Ripple address found");
         ICNetwork::error_log("REQUIRED: Call cache_ripple_address() to initialize it");
@@ -102,6 +128,27 @@ async fn set_timer_processes() {
         });
     });
 
+    // start the background timer to process limit orders
+    let _ = set_timer_interval(Duration::from_secs(kong_settings_map::get().orders_interval_secs), || {
+        ic_cdk::futures::spawn(async {
+            process_orders_timer().await;
+        });
+    });
+
+    // start the background timer to update users' earned fee levels
+    let _ = set_timer_interval(Duration::from_secs(kong_settings_map::get().fee_tiers_interval_secs), || {
+        ic_cdk::futures::spawn(async {
+            update_fee_tiers();
+        });
+    });
+
+    // start the background timer to end the campaign boosts of expired LP locks
+    let _ = set_timer_interval(Duration::from_secs(kong_settings_map::get().lp_locks_interval_secs), || {
+        ic_cdk::futures::spawn(async {
+            expire_lp_locks();
+        });
+    });
+
     // start the background timer to archive request map
     let _ = set_timer_interval(Duration::from_secs(kong_settings_map::get().requests_archive_interval_secs), || {
         ic_cdk::futures::spawn(async {
@@ -136,19 +183,37 @@ async fn set_timer_processes() {
         },
     );
 
-    // start the background timer to cleanup old Solana notifications
+    // start the background timer to cleanup old Solana notifications and confirmed swap jobs
     let _ = set_timer_interval(Duration::from_secs(3600), || {
         // Clean up every hour
         ic_cdk::futures::spawn(async {
             cleanup_old_notifications();
+            crate::solana::swap_job_cleanup::cleanup_confirmed_swap_jobs();
         });
     });
 
-    // start the background timer to cleanup expired Solana swap jobs
+    // start the background timer to cleanup and retry expired Solana swap jobs
     let _ = set_timer_interval(Duration::from_secs(60), || {
         // Check every minute for expired swap jobs
         ic_cdk::futures::spawn(async {
             crate::solana::swap_job_cleanup::cleanup_expired_swap_jobs();
+            crate::solana::swap_job_retry::retry_expired_swap_jobs().await;
+        });
+    });
+
+    // start the background timer to cleanup old XRPL notifications
+    let _ = set_timer_interval(Duration::from_secs(3600), || {
+        // Clean up every hour
+        ic_cdk::futures::spawn(async {
+            cleanup_old_ripple_notifications();
+        });
+    });
+
+    // start the background timer to cleanup expired XRPL payment jobs
+    let _ = set_timer_interval(Duration::from_secs(60), || {
+        // Check every minute for expired payment jobs
+        ic_cdk::futures::spawn(async {
+            crate::ripple::payment_job_cleanup::cleanup_expired_payment_jobs();
         });
     });
 }
@@ -1,3 +1,4 @@
 pub const LP_CHAIN: &str = "LP";
 pub const IC_CHAIN: &str = "IC";
 pub const SOL_CHAIN: &str = "SOL";
+pub const XRP_CHAIN: &str = "XRP";
@@ -2,7 +2,7 @@ use candid::Nat;
 
 use crate::helpers::nat_helpers::{nat_subtract, nat_zero};
 use crate::ic::{
-    address::Address::{self, AccountId, PrincipalId, SolanaAddress},
+    address::Address::{self, AccountId, PrincipalId, RippleAddress, SolanaAddress},
     transfer::{icp_transfer, icrc1_transfer},
 };
 use crate::stable_claim::claim_map;
@@ -200,5 +200,48 @@ async fn send_claim(
                 }
             }
         }
+        RippleAddress(ripple_address) => {
+            // Create Ripple payment job for failed claim
+            match crate::ripple::create_ripple_payment_job::create_ripple_payment_job(
+                request_id,
+                claim.user_id,
+                token,
+                amount,
+                &Address::RippleAddress(ripple_address.clone()),
+                ts,
+            )
+            .await
+            {
+                Ok(job_id) => {
+                    let transfer_id = transfer_map::insert(&StableTransfer {
+                        transfer_id: 0,
+                        request_id,
+                        is_send: false,
+                        amount: amount.clone(),
+                        token_id: token.token_id(),
+                        tx_id: TxId::TransactionId(format!("job_{}", job_id)),
+                        ts,
+                    });
+                    transfer_ids.push(transfer_id);
+                    claim_map::update_claimed_status(claim.claim_id, request_id, transfer_id);
+                    request_map::update_status(
+                        request_id,
+                        StatusCode::ClaimTokenSuccess,
+                        Some(&format!("Ripple payment job #{} created", job_id)),
+                    );
+                    Ok(())
+                }
+                Err(e) => {
+                    // revert claim status to unclaimed or claimable
+                    match claim_status {
+                        ClaimStatus::Claimable => claim_map::update_claimable_status(claim.claim_id, request_id),
+                        _ => claim_map::update_unclaimed_status(claim.claim_id, request_id),
+                    };
+                    let message = format!("Failed to create Ripple job: {}", e);
+                    request_map::update_status(request_id, StatusCode::ClaimTokenFailed, Some(&message));
+                    Err(format!("Failed to send claim_id #{}. {}", claim.claim_id, message))
+                }
+            }
+        }
     }
 }
@@ -4,6 +4,7 @@ use crate::ic::guards::caller_is_kingkong;
 use crate::kong_backend::KongBackend;
 use crate::ripple::stable_memory::{get_cached_ripple_address, set_cached_ripple_address};
 use crate::solana::stable_memory::{get_cached_solana_address, set_cached_solana_address};
+use crate::stable_token::{stable_token::StableToken, token_map};
 
 /// Cache the canister's Solana address (One-time initialization)
 /// This method derives the Solana address from the canister's Ed25519 key
@@ -65,6 +66,11 @@ pub async fn cache_ripple_address() -> Result<String, String> {
 
             if cached == address {
                 Ok(format!("Address already cached and verified: {}", cached))
+            } else if !token_map::get().iter().any(|token| matches!(token, StableToken::Ripple(_))) {
+                // addresses cached before the Ed25519 prefix fix were derived wrongly. nothing can have been paid to them
+                // before an XRPL token was added, so replace it
+                set_cached_ripple_address(address.clone());
+                Ok(format!("Replaced cached Ripple address {} with {}", cached, address))
             } else {
                 // CRITICAL: Cached address doesn't match current derivation
                 Err(format!(
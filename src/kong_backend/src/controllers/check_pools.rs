@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use crate::ic::guards::caller_is_kingkong;
 use crate::stable_pool::check_token_balance::{check_token_balance, ExpectedBalance};
 use crate::stable_token::stable_token::StableToken;
-use crate::stable_token::stable_token::StableToken::{IC, LP, Solana};
+use crate::stable_token::stable_token::StableToken::{IC, LP, Solana, Ripple};
 use crate::stable_token::token::Token;
 use crate::stable_token::token_map;
 
@@ -47,6 +47,7 @@ async fn check_pools() -> Result<Vec<CheckPoolReply>, String> {
             LP(_) => None, // pools for LP tokens are not supported
             IC(_) => Some(check_token_balance(token)),
             Solana(_) => None, // Solana token balance checks not yet implemented
+            Ripple(_) => None, // XRPL token balance checks not yet implemented
         })
         .collect::<Vec<_>>();
     let results = join_all(futures).await;
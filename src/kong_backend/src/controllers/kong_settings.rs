@@ -1,7 +1,9 @@
+use candid::Principal;
 use ic_cdk::{query, update};
 
 use crate::helpers::json_helpers;
 use crate::ic::guards::caller_is_kingkong;
+use crate::stable_kong_settings::kong_settings_map;
 use crate::stable_kong_settings::stable_kong_settings::StableKongSettings;
 use crate::stable_memory::KONG_SETTINGS;
 
@@ -44,6 +46,9 @@ fn set_kong_settings(update_settings: String) -> Result<String, String> {
 
     let kong_settings: StableKongSettings =
         serde_json::from_value(kong_settings_value).map_err(|e| format!("Failed to parse updated Kong settings: {}", e))?;
+    if updates.get("kong_rpc_relayers").is_some() || updates.get("solana_attestation_threshold").is_some() {
+        check_solana_attestation(&kong_settings.kong_rpc_relayers, kong_settings.solana_attestation_threshold)?;
+    }
 
     KONG_SETTINGS.with(|m| {
         m.borrow_mut()
@@ -52,3 +57,74 @@ fn set_kong_settings(update_settings: String) -> Result<String, String> {
         serde_json::to_string(&kong_settings).map_err(|e| format!("Failed to serialize: {}", e))
     })
 }
+
+/// set the kong_rpc relayers and how many of them must attest an incoming Solana transfer
+#[update(hidden = true, guard = "caller_is_kingkong")]
+fn set_solana_attestation(kong_rpc_relayers: Vec<Principal>, solana_attestation_threshold: u8) -> Result<String, String> {
+    check_solana_attestation(&kong_rpc_relayers, solana_attestation_threshold)?;
+
+    let kong_settings = StableKongSettings {
+        kong_rpc_relayers,
+        solana_attestation_threshold,
+        ..kong_settings_map::get()
+    };
+    KONG_SETTINGS.with(|m| {
+        m.borrow_mut()
+            .set(kong_settings.clone())
+            .map_err(|_| "Failed to update Kong settings".to_string())?;
+        serde_json::to_string(&kong_settings).map_err(|e| format!("Failed to serialize: {}", e))
+    })
+}
+
+/// a Solana transfer must be attested by more than one relayer (M > 1) and no more than there are relayers (M <= N)
+fn check_solana_attestation(kong_rpc_relayers: &[Principal], solana_attestation_threshold: u8) -> Result<(), String> {
+    let mut relayers = kong_rpc_relayers.to_vec();
+    relayers.sort();
+    relayers.dedup();
+    if relayers.len() != kong_rpc_relayers.len() {
+        Err("Duplicate kong_rpc relayer".to_string())?;
+    }
+    if solana_attestation_threshold <= 1 {
+        Err("Solana attestation threshold must be more than 1".to_string())?;
+    }
+    if solana_attestation_threshold as usize > relayers.len() {
+        Err(format!(
+            "Solana attestation threshold {} is more than the {} kong_rpc relayers",
+            solana_attestation_threshold,
+            relayers.len()
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relayers(n: u8) -> Vec<Principal> {
+        (1..=n).map(|id| Principal::from_slice(&[id])).collect()
+    }
+
+    #[test]
+    fn test_check_solana_attestation() {
+        assert!(check_solana_attestation(&relayers(2), 2).is_ok());
+        assert!(check_solana_attestation(&relayers(3), 2).is_ok());
+        assert!(check_solana_attestation(&relayers(3), 3).is_ok());
+        // a single relayer can not confirm a transfer
+        assert!(check_solana_attestation(&relayers(3), 1).is_err());
+        assert!(check_solana_attestation(&relayers(3), 0).is_err());
+        // more than the number of relayers
+        assert!(check_solana_attestation(&relayers(2), 3).is_err());
+        // the same relayer twice does not count as two
+        let mut duplicated = relayers(1);
+        duplicated.push(duplicated[0]);
+        assert!(check_solana_attestation(&duplicated, 2).is_err());
+    }
+
+    #[test]
+    fn test_default_solana_attestation_is_valid() {
+        let kong_settings = StableKongSettings::default();
+        assert_eq!(kong_settings.solana_attestation_threshold, 2);
+        assert!(check_solana_attestation(&kong_settings.kong_rpc_relayers, kong_settings.solana_attestation_threshold).is_ok());
+    }
+}
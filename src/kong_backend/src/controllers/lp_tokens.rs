@@ -1,11 +1,16 @@
+use candid::Principal;
 use ic_cdk::{query, update};
 use std::collections::BTreeMap;
 
 use crate::helpers::nat_helpers::nat_zero;
 use crate::ic::guards::caller_is_kingkong;
+use crate::lp_ledger::lp_ledger::{get_lp_token, get_lp_token_by_ledger};
 use crate::stable_lp_token::lp_token_map;
 use crate::stable_lp_token::stable_lp_token::{StableLPToken, StableLPTokenId};
 use crate::stable_memory::LP_TOKEN_MAP;
+use crate::stable_token::lp_token::LPToken;
+use crate::stable_token::stable_token::StableToken;
+use crate::stable_token::token_map;
 
 const MAX_LP_TOKENS: usize = 1_000;
 
@@ -64,3 +69,21 @@ fn remove_zero_lp_tokens() -> Result<String, String> {
 
     Ok("Zero LP tokens removed".to_string())
 }
+
+/// set the kong_lp_ledger canister serving the ICRC-1/2/3 interface of an LP token
+#[update(hidden = true, guard = "caller_is_kingkong")]
+fn set_lp_token_ledger(token: String, ledger_id: Principal) -> Result<String, String> {
+    let lp_token = get_lp_token(&token)?;
+    if let Ok(other_lp_token) = get_lp_token_by_ledger(ledger_id) {
+        if other_lp_token.token_id != lp_token.token_id {
+            Err(format!("{} is already the ledger of {}", ledger_id, other_lp_token.symbol))?
+        }
+    }
+    let symbol = lp_token.symbol.clone();
+    token_map::update(&StableToken::LP(LPToken {
+        ledger_id: Some(ledger_id),
+        ..lp_token
+    }));
+
+    Ok(format!("Ledger of {} set to {}", symbol, ledger_id))
+}
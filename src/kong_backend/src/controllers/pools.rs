@@ -7,12 +7,19 @@ use std::collections::BTreeMap;
 
 use crate::helpers::nat_helpers::{nat_add, nat_subtract, nat_zero};
 use crate::ic::guards::caller_is_kingkong;
+use crate::ic::network::ICNetwork;
+use crate::pools::dynamic_fee_args::DynamicFeeArgs;
+use crate::pools::pool_fee_change_reply::PoolFeeChangeReply;
+use crate::pools::pools_reply::PoolReply;
 use crate::remove_liquidity::remove_liquidity::remove_liquidity_from_pool;
 use crate::remove_liquidity::remove_liquidity_args::RemoveLiquidityArgs;
 use crate::stable_lp_token::lp_token_map;
 use crate::stable_memory::{LP_TOKEN_MAP, POOL_MAP};
-use crate::stable_pool::pool_map;
+use crate::stable_pool::dynamic_fee::DynamicFee;
+use crate::stable_pool::pool_type::{PoolType, MAX_AMP};
 use crate::stable_pool::stable_pool::{StablePool, StablePoolId};
+use crate::stable_pool::stable_pool_fee_change::StablePoolFeeChange;
+use crate::stable_pool::{pool_fee_change_map, pool_map};
 use crate::stable_token::token::Token;
 use crate::stable_user::user_map;
 
@@ -118,10 +125,13 @@ async fn remove_lps_from_pool(symbol: String) -> Result<String, String> {
             token_0: token_0.clone(),
             token_1: token_1.clone(),
             remove_lp_token_amount,
+            lp_fee_bps: Some(pool.lp_fee_bps),
             payout_address_0: None,
             payout_address_1: None,
             signature_0: None,
             signature_1: None,
+            receive_token: None,
+            min_receive_amount: None,
         };
         match Principal::from_text(principal_id) {
             Ok(principal) => {
@@ -205,3 +215,148 @@ fn adjust_pool_balances(symbol: String, direction: String, amount_0: Nat, amount
         symbol, pool.balance_0, pool.balance_1
     ))
 }
+
+/// set the LP and Kong fees of a pool
+/// pool = pool symbol, address or LP token symbol
+/// every change is recorded in POOL_FEE_CHANGE_MAP
+#[update(guard = "caller_is_kingkong")]
+fn set_pool_fees(pool: String, lp_fee_bps: u8, kong_fee_bps: u8) -> Result<PoolFeeChangeReply, String> {
+    let mut pool = pool_map::get_by_token(&pool)?;
+    pool_map::check_fees(lp_fee_bps, kong_fee_bps)?;
+    if pool.lp_fee_bps == lp_fee_bps && pool.kong_fee_bps == kong_fee_bps {
+        Err(format!("Pool {} fees are unchanged", pool.symbol()))?
+    }
+    // a token pair can only have one pool for each fee tier
+    if pool.lp_fee_bps != lp_fee_bps && pool_map::exists_fee_tier(&pool.token_0(), &pool.token_1(), lp_fee_bps) {
+        Err(format!("Pool {} with LP fee {} bps already exists", pool.symbol(), lp_fee_bps))?
+    }
+
+    let fee_change = StablePoolFeeChange {
+        fee_change_id: 0, // will be set with insert into POOL_FEE_CHANGE_MAP
+        pool_id: pool.pool_id,
+        old_lp_fee_bps: pool.lp_fee_bps,
+        old_kong_fee_bps: pool.kong_fee_bps,
+        lp_fee_bps,
+        kong_fee_bps,
+        old_dynamic_fee_bps: dynamic_fee_bps(&pool),
+        dynamic_fee_bps: dynamic_fee_bps(&pool),
+        principal_id: ICNetwork::caller().to_text(),
+        ts: ICNetwork::get_time(),
+    };
+
+    pool.lp_fee_bps = lp_fee_bps;
+    pool.kong_fee_bps = kong_fee_bps;
+    pool_map::update(&pool);
+    let fee_change_id = pool_fee_change_map::insert(&fee_change);
+
+    Ok(PoolFeeChangeReply::from(&StablePoolFeeChange {
+        fee_change_id,
+        ..fee_change
+    }))
+}
+
+/// enable, update or disable the dynamic LP fee of a pool
+/// pool = pool symbol, address or LP token symbol
+/// dynamic_fee = None to disable dynamic fees and charge lp_fee_bps again
+/// the accumulated price moves are kept when updating the settings of a pool with dynamic fees
+/// every change is recorded in POOL_FEE_CHANGE_MAP
+#[update(guard = "caller_is_kingkong")]
+fn set_pool_dynamic_fee(pool: String, dynamic_fee: Option<DynamicFeeArgs>) -> Result<PoolFeeChangeReply, String> {
+    let mut pool = pool_map::get_by_token(&pool)?;
+    let old_dynamic_fee_bps = dynamic_fee_bps(&pool);
+
+    pool.dynamic_fee = match dynamic_fee {
+        Some(args) => {
+            if args.min_fee_bps > args.max_fee_bps {
+                Err(format!(
+                    "Min fee of {} bps cannot be more than max fee of {} bps",
+                    args.min_fee_bps, args.max_fee_bps
+                ))?
+            }
+            if args.min_fee_bps < pool.kong_fee_bps {
+                Err(format!("Min fee cannot be less than Kong fee of {}", pool.kong_fee_bps))?
+            }
+            if args.max_volatility_bps == 0 {
+                Err("Max volatility must be greater than zero")?
+            }
+            if args.half_life_secs == 0 {
+                Err("Half-life must be greater than zero")?
+            }
+            let mut new_dynamic_fee = DynamicFee::new(args.min_fee_bps, args.max_fee_bps, args.max_volatility_bps, args.half_life_secs);
+            if let Some(dynamic_fee) = &pool.dynamic_fee {
+                new_dynamic_fee.volatility_bps = dynamic_fee.volatility_bps;
+                new_dynamic_fee.last_update_ts = dynamic_fee.last_update_ts;
+            }
+            Some(new_dynamic_fee)
+        }
+        None => {
+            if pool.dynamic_fee.is_none() {
+                Err(format!("Pool {} does not have dynamic fees", pool.symbol()))?
+            }
+            None
+        }
+    };
+
+    let fee_change = StablePoolFeeChange {
+        fee_change_id: 0, // will be set with insert into POOL_FEE_CHANGE_MAP
+        pool_id: pool.pool_id,
+        old_lp_fee_bps: pool.lp_fee_bps,
+        old_kong_fee_bps: pool.kong_fee_bps,
+        lp_fee_bps: pool.lp_fee_bps,
+        kong_fee_bps: pool.kong_fee_bps,
+        old_dynamic_fee_bps,
+        dynamic_fee_bps: dynamic_fee_bps(&pool),
+        principal_id: ICNetwork::caller().to_text(),
+        ts: ICNetwork::get_time(),
+    };
+
+    pool_map::update(&pool);
+    let fee_change_id = pool_fee_change_map::insert(&fee_change);
+
+    Ok(PoolFeeChangeReply::from(&StablePoolFeeChange {
+        fee_change_id,
+        ..fee_change
+    }))
+}
+
+/// set the invariant used to price swaps of a pool
+/// pool = pool symbol, address or LP token symbol
+/// StableSwap { amp } is meant for pegged pairs. LP positions are unchanged as liquidity is paid out in proportion to the balances
+#[update(guard = "caller_is_kingkong")]
+fn set_pool_type(pool: String, pool_type: PoolType) -> Result<PoolReply, String> {
+    let mut pool = pool_map::get_by_token(&pool)?;
+    if pool.pool_type == pool_type {
+        Err(format!("Pool {} is already {}", pool.symbol(), pool_type))?
+    }
+    // the weights set the price of a pool so they can only be chosen when the pool is created with add_pool()
+    if let PoolType::Weighted { .. } = pool.pool_type {
+        Err(format!("Pool {} is a weighted pool", pool.symbol()))?
+    }
+    if let PoolType::Weighted { .. } = pool_type {
+        Err("Weighted pools can only be created with add_pool()")?
+    }
+    // concentrated liquidity is held in positions, not in the pool balances the other invariants use
+    if pool.is_concentrated() {
+        Err(format!("Pool {} is a concentrated liquidity pool", pool.symbol()))?
+    }
+    if let PoolType::Concentrated { .. } = pool_type {
+        Err("Concentrated liquidity pools can only be created with add_pool()")?
+    }
+    if let PoolType::StableSwap { amp } = pool_type {
+        if amp == 0 || amp > MAX_AMP {
+            Err(format!("Amplification coefficient must be between 1 and {}", MAX_AMP))?
+        }
+    }
+
+    ICNetwork::info_log(&format!("Pool {} changed from {} to {}", pool.symbol(), pool.pool_type, pool_type));
+    pool.pool_type = pool_type;
+    pool_map::update(&pool);
+
+    Ok(PoolReply::from(&pool))
+}
+
+fn dynamic_fee_bps(pool: &StablePool) -> Option<(u8, u8)> {
+    pool.dynamic_fee
+        .as_ref()
+        .map(|dynamic_fee| (dynamic_fee.min_fee_bps, dynamic_fee.max_fee_bps))
+}
@@ -44,6 +44,9 @@ fn suspend_token(symbol: String) -> Result<String, String> {
         StableToken::Solana(token) => {
             token_map::remove(token.token_id)?;
         }
+        StableToken::Ripple(token) => {
+            token_map::remove(token.token_id)?;
+        }
     }
 
     Ok(format!("Token {} suspended", symbol))
@@ -60,6 +63,9 @@ fn unsuspend_token(symbol: String) -> Result<String, String> {
         StableToken::Solana(token) => {
             token_map::unremove(token.token_id)?;
         }
+        StableToken::Ripple(token) => {
+            token_map::unremove(token.token_id)?;
+        }
     }
 
     Ok(format!("Token {} unsuspended", symbol))
@@ -2,9 +2,12 @@ use ic_cdk::{query, update};
 use std::collections::BTreeMap;
 
 use crate::ic::guards::caller_is_kingkong;
+use crate::ic::network::ICNetwork;
 use crate::stable_memory::{PRINCIPAL_ID_MAP, USER_MAP};
 use crate::stable_user::principal_id_map::create_principal_id_map;
 use crate::stable_user::stable_user::{StableUser, StableUserId};
+use crate::stable_user::user_map;
+use crate::user::user_reply::UserReply;
 
 const MAX_USERS: usize = 1_000;
 
@@ -95,3 +98,23 @@ fn remove_user(user_id: u32) -> Result<String, String> {
 
     Ok("User removed".to_string())
 }
+
+/// assign a fee level to a user. the user swaps with the higher of this and the fee level earned from volume
+/// fee_level - 0 = no discount, 100 = pays no lp_fee on swaps
+/// expires_at - timestamp after which the fee level no longer applies. None for no expiry
+#[update(guard = "caller_is_kingkong")]
+fn set_user_fee_level(principal_id: String, fee_level: u8, expires_at: Option<u64>) -> Result<UserReply, String> {
+    if fee_level > 100 {
+        Err("Fee level must be between 0 and 100")?
+    }
+    if expires_at.is_some_and(|expires_at| expires_at <= ICNetwork::get_time()) {
+        Err("Expiry must be in the future")?
+    }
+
+    let mut user = user_map::get_by_principal_id(&principal_id)?.ok_or("User not found")?;
+    user.fee_level = fee_level;
+    user.fee_level_expires_at = if fee_level == 0 { None } else { expires_at };
+    user_map::update(&user);
+
+    Ok(UserReply::from(&user))
+}
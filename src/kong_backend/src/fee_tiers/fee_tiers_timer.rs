@@ -0,0 +1,295 @@
+use candid::Nat;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::helpers::nat_helpers::nat_add;
+use crate::ic::ckusdt::{ckusdt_amount, to_ckusdt_decimals_f64};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_kong_settings::fee_tier::earned_fee_level;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::{TX_ARCHIVE_MAP, TX_MAP, USER_MAP};
+use crate::stable_token::token_map;
+use crate::stable_tx::stable_tx::{StableTx, StableTxId};
+use crate::stable_tx::status_tx::StatusTx;
+use crate::stable_tx::tx::Tx;
+use crate::stable_user::stable_user::StableUserId;
+use crate::stable_user::user_map;
+
+// fee tiers are earned with the swap volume of the last 30 days
+const VOLUME_WINDOW_NANOSECS: u64 = 30 * 24 * 60 * 60 * 1_000_000_000;
+// txs or users read in one message
+const PAGE_SIZE: usize = 1_000;
+
+thread_local! {
+    // fee tier update in progress. kept in heap memory so an upgrade starts it again
+    static FEE_TIERS_UPDATE: RefCell<Option<FeeTiersUpdate>> = RefCell::default();
+}
+
+/// cursor of a fee tier update. txs are read newest first from TX_MAP and then TX_ARCHIVE_MAP, then the users are updated
+enum FeeTiersStep {
+    TxMap(Option<u64>), // tx_id of the last tx read
+    TxArchiveMap(u64),  // read the archive before this tx_id
+    Users(Option<u32>), // user_id of the last user updated
+}
+
+struct FeeTiersUpdate {
+    ts: u64,
+    step: FeeTiersStep,
+    pay_amounts: BTreeMap<(u32, u32), Nat>, // swap pay amounts by user and token
+    volumes: BTreeMap<u32, f64>,            // 30-day swap volume in USD by user, once all txs are read
+}
+
+impl FeeTiersUpdate {
+    fn new(ts: u64) -> Self {
+        Self {
+            ts,
+            step: FeeTiersStep::TxMap(None),
+            pay_amounts: BTreeMap::new(),
+            volumes: BTreeMap::new(),
+        }
+    }
+}
+
+/// start updating the rolling 30-day swap volume and earned fee level of all users and clearing expired fee levels
+/// the update is done a page at a time, each page in its own message
+pub fn update_fee_tiers() {
+    if not_in_maintenance_mode().is_err() {
+        return;
+    }
+    let started = FEE_TIERS_UPDATE.with(|u| {
+        let mut update = u.borrow_mut();
+        if update.is_some() {
+            return false;
+        }
+        *update = Some(FeeTiersUpdate::new(ICNetwork::get_time()));
+        true
+    });
+    if started {
+        update_fee_tiers_page();
+    }
+}
+
+/// update the next page and schedule the page after it
+fn update_fee_tiers_page() {
+    let done = FEE_TIERS_UPDATE.with(|u| {
+        let mut update = u.borrow_mut();
+        match update.as_mut() {
+            Some(fee_tiers_update) if not_in_maintenance_mode().is_ok() => update_page(fee_tiers_update, PAGE_SIZE),
+            _ => true,
+        }
+    });
+    if done {
+        FEE_TIERS_UPDATE.with(|u| *u.borrow_mut() = None);
+    } else {
+        ic_cdk_timers::set_timer(Duration::ZERO, update_fee_tiers_page);
+    }
+}
+
+/// read or update at most page_size txs or users. returns true when all users are updated
+fn update_page(update: &mut FeeTiersUpdate, page_size: usize) -> bool {
+    let start_ts = update.ts.saturating_sub(VOLUME_WINDOW_NANOSECS);
+    match update.step {
+        FeeTiersStep::TxMap(last_tx_id) => {
+            let (last_tx_id, first_tx_id) = TX_MAP.with(|m| {
+                let map = m.borrow();
+                let txs = match last_tx_id {
+                    Some(last_tx_id) => map.range(..StableTxId(last_tx_id)),
+                    None => map.range(..),
+                };
+                let last_tx_id = add_swaps(txs.rev(), start_ts, page_size, &mut update.pay_amounts);
+                (last_tx_id, map.first_key_value().map(|(k, _)| k.0))
+            });
+            // txs are in both maps until they are removed from TX_MAP, so only read the archive before the first tx in TX_MAP
+            update.step = match (last_tx_id, first_tx_id) {
+                (Some(last_tx_id), _) => FeeTiersStep::TxMap(Some(last_tx_id)),
+                (None, Some(first_tx_id)) => FeeTiersStep::TxArchiveMap(first_tx_id),
+                (None, None) => FeeTiersStep::TxArchiveMap(u64::MAX),
+            };
+        }
+        FeeTiersStep::TxArchiveMap(before_tx_id) => {
+            let last_tx_id = TX_ARCHIVE_MAP.with(|m| {
+                let map = m.borrow();
+                add_swaps(
+                    map.range(..StableTxId(before_tx_id)).rev(),
+                    start_ts,
+                    page_size,
+                    &mut update.pay_amounts,
+                )
+            });
+            update.step = match last_tx_id {
+                Some(last_tx_id) => FeeTiersStep::TxArchiveMap(last_tx_id),
+                None => {
+                    update.volumes = swap_volumes(&update.pay_amounts);
+                    FeeTiersStep::Users(None)
+                }
+            };
+        }
+        FeeTiersStep::Users(last_user_id) => {
+            let fee_tiers = kong_settings_map::get().fee_tiers;
+            let users: Vec<_> = USER_MAP.with(|m| {
+                let map = m.borrow();
+                let users = match last_user_id {
+                    Some(last_user_id) => map.range(StableUserId(last_user_id.saturating_add(1))..),
+                    None => map.range(..),
+                };
+                users.take(page_size).map(|(_, v)| v).collect()
+            });
+            let mut updated_users = Vec::new();
+            for mut user in users.iter().cloned() {
+                let volume_30d = update.volumes.get(&user.user_id).copied().unwrap_or(0_f64);
+                let earned_fee_level = earned_fee_level(&fee_tiers, volume_30d);
+                let expired = user.fee_level_expires_at.is_some_and(|expires_at| update.ts > expires_at);
+                if user.volume_30d == volume_30d && user.earned_fee_level == earned_fee_level && !expired {
+                    continue;
+                }
+                user.volume_30d = volume_30d;
+                user.earned_fee_level = earned_fee_level;
+                if expired {
+                    user.fee_level = 0;
+                    user.fee_level_expires_at = None;
+                }
+                updated_users.push(user);
+            }
+            user_map::update_users(&updated_users);
+            match users.last() {
+                Some(user) if users.len() == page_size => update.step = FeeTiersStep::Users(Some(user.user_id)),
+                _ => return true,
+            }
+        }
+    }
+    false
+}
+
+/// add the pay amounts of successful swaps from txs, newest first, until a tx before start_ts
+/// returns the tx_id of the last tx read if page_size txs were read and there could be more
+fn add_swaps(
+    txs: impl Iterator<Item = (StableTxId, StableTx)>,
+    start_ts: u64,
+    page_size: usize,
+    pay_amounts: &mut BTreeMap<(u32, u32), Nat>,
+) -> Option<u64> {
+    let mut num_txs = 0;
+    let mut last_tx_id = None;
+    for (tx_id, tx) in txs.take(page_size) {
+        if tx.ts() < start_ts {
+            return None;
+        }
+        if let StableTx::Swap(swap_tx) = &tx {
+            if swap_tx.status == StatusTx::Success {
+                let pay_amount = pay_amounts.entry((swap_tx.user_id, swap_tx.pay_token_id)).or_default();
+                *pay_amount = nat_add(pay_amount, &swap_tx.pay_amount);
+            }
+        }
+        num_txs += 1;
+        last_tx_id = Some(tx_id.0);
+    }
+    if num_txs == page_size {
+        last_tx_id
+    } else {
+        None
+    }
+}
+
+/// swap volume in USD of each user from the pay amounts by user and token
+fn swap_volumes(pay_amounts: &BTreeMap<(u32, u32), Nat>) -> BTreeMap<u32, f64> {
+    let mut volumes = BTreeMap::new();
+    for ((user_id, token_id), pay_amount) in pay_amounts {
+        let Some(volume) = token_map::get_by_token_id(*token_id)
+            .and_then(|token| ckusdt_amount(&token, pay_amount).ok())
+            .and_then(|amount| to_ckusdt_decimals_f64(&amount))
+        else {
+            continue;
+        };
+        *volumes.entry(*user_id).or_insert(0_f64) += volume;
+    }
+    volumes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_ic_token, insert_pool, insert_user, update_kong_settings};
+    use crate::stable_kong_settings::fee_tier::FeeTier;
+    use crate::stable_tx::swap_tx::SwapTx;
+    use crate::stable_tx::tx_map;
+
+    const TS: u64 = VOLUME_WINDOW_NANOSECS + 1_000;
+
+    // swap paying 1 ICP, 100 USD at the pool price
+    fn swap_tx(user_id: u32, ts: u64) -> StableTx {
+        StableTx::Swap(SwapTx::new_success(
+            user_id,
+            0,
+            1,
+            &Nat::from(100_000_000_u64),
+            2,
+            &Nat::from(100_000_000_u64),
+            100_f64,
+            100_f64,
+            0_f64,
+            &[],
+            &[],
+            &[],
+            ts,
+        ))
+    }
+
+    #[test]
+    fn test_update_page_pages_through_txs_and_users() {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckUSDT", 6);
+        init_kong_settings();
+        update_kong_settings(|s| {
+            s.ckusdt_token_id = 2;
+            s.fee_tiers = vec![
+                FeeTier {
+                    fee_level: 10,
+                    min_volume: 100_f64,
+                },
+                FeeTier {
+                    fee_level: 20,
+                    min_volume: 300_f64,
+                },
+            ];
+        });
+        insert_pool(1, 1, 1_000_000_000, 2, 1_000_000_000);
+        for user_id in 1..=5 {
+            insert_user(user_id, Principal::anonymous());
+        }
+        let mut expired_user = insert_user(3, Principal::anonymous());
+        expired_user.fee_level = 50;
+        expired_user.fee_level_expires_at = Some(TS - 1);
+        user_map::update(&expired_user);
+        // archived tx that is no longer in TX_MAP, and one before the 30-day window
+        TX_ARCHIVE_MAP.with(|m| {
+            let mut map = m.borrow_mut();
+            map.insert(StableTxId(1), swap_tx(2, 0));
+            map.insert(StableTxId(2), swap_tx(1, TS));
+        });
+        update_kong_settings(|s| s.tx_map_idx = 2);
+        for user_id in [1, 1, 2, 5, 1] {
+            tx_map::insert(&swap_tx(user_id, TS));
+        }
+
+        let mut update = FeeTiersUpdate::new(TS);
+        let mut pages = 1;
+        while !update_page(&mut update, 2) {
+            pages += 1;
+        }
+
+        // 3 pages of txs, 1 of the archive and 3 of users
+        assert_eq!(pages, 7);
+        let user = user_map::get_by_user_id(1).unwrap();
+        assert_eq!((user.volume_30d, user.earned_fee_level), (400_f64, 20));
+        let user = user_map::get_by_user_id(2).unwrap();
+        assert_eq!((user.volume_30d, user.earned_fee_level), (100_f64, 10));
+        let user = user_map::get_by_user_id(3).unwrap();
+        assert_eq!((user.volume_30d, user.fee_level, user.fee_level_expires_at), (0_f64, 0, None));
+        assert_eq!(user_map::get_by_user_id(4).unwrap().earned_fee_level, 0);
+        assert_eq!(user_map::get_by_user_id(5).unwrap().earned_fee_level, 10);
+    }
+}
@@ -0,0 +1,446 @@
+use candid::{Nat, Principal};
+use ic_cdk::call::Call;
+use ic_cdk::update;
+use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc2::allowance::Allowance;
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_divide_ceil, nat_is_zero, nat_multiply, nat_subtract, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::ledger::{get_allowance, get_balance};
+use crate::ic::network::ICNetwork;
+use crate::ic::transfer::{icrc1_transfer, icrc2_transfer_from};
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_lp_token::lp_token_fees::lp_fee_per_lp;
+use crate::stable_lp_token::lp_token_map;
+use crate::stable_pool::pool_lock::PoolLock;
+use crate::stable_pool::{pool_map, stable_pool::StablePool};
+use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+use crate::stable_token::token_management::handle_failed_transfer;
+use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
+use crate::stable_transfer::{stable_transfer::StableTransfer, transfer_map, tx_id::TxId};
+use crate::stable_tx::status_tx::StatusTx;
+use crate::stable_user::user_map;
+
+use super::flash_swap_args::FlashSwapArgs;
+use super::flash_swap_callback_args::FlashSwapCallbackArgs;
+use super::flash_swap_reply::FlashSwapReply;
+
+// method of the borrower canister called with the loan
+const FLASH_SWAP_CALLBACK: &str = "kong_flash_swap_callback";
+
+/// Flash swap. lend amount of a pool's token to the calling canister for the duration of a callback
+///
+/// 1. lock the pool so no swap or liquidity change of the pool is committed until the flash swap ends
+/// 2. check the caller has approved and holds repay_amount (amount + fee + gas of the loan transfer) and its gas
+/// 3. send amount to the caller with icrc1_transfer
+/// 4. call kong_flash_swap_callback(FlashSwapCallbackArgs) of the caller
+/// 5. take repay_amount from the caller with icrc2_transfer_from, or only amount + gas if the callback failed
+/// 6. add the fee to the pool's LP and Kong fees. the pool's balances are never changed
+#[update(guard = "not_in_maintenance_mode")]
+async fn flash_swap(args: FlashSwapArgs) -> Result<FlashSwapReply, String> {
+    let (user_id, pool, token) = check_arguments(&args)?;
+    // released when flash_swap returns
+    let _pool_lock = PoolLock::new(pool.pool_id)?;
+
+    let ts = ICNetwork::get_time();
+    let request_id = request_map::insert(&StableRequest::new(user_id, &Request::FlashSwap(args.clone()), ts));
+
+    request_map::update_status(request_id, StatusCode::Start, None);
+
+    process_flash_swap(request_id, &pool, &token, &args.amount, args.data, ts).await
+}
+
+fn check_arguments(args: &FlashSwapArgs) -> Result<(u32, StablePool, StableToken), String> {
+    // the loan is at risk until it is repaid, so only canisters set by kingkong can borrow
+    if !kong_settings_map::get().flash_swap_borrowers.contains(&ICNetwork::caller()) {
+        Err("Caller is not a flash swap borrower".to_string())?;
+    }
+
+    let pool = pool_map::get_by_token(&args.pool)?;
+    if pool.is_removed {
+        Err("Pool is removed".to_string())?;
+    }
+    if pool.is_concentrated() {
+        Err("Flash swaps are not supported for concentrated liquidity pools".to_string())?;
+    }
+
+    let token = token_map::get_by_token(&args.token)?;
+    let balance = if token.token_id() == pool.token_id_0 {
+        &pool.balance_0
+    } else if token.token_id() == pool.token_id_1 {
+        &pool.balance_1
+    } else {
+        Err(format!("Token {} is not in pool {}", token.symbol(), pool.symbol()))?
+    };
+    if !matches!(token, StableToken::IC(_)) || !token.is_icrc2() {
+        Err("Token must be an IC token that supports ICRC2".to_string())?;
+    }
+    if token.is_removed() {
+        Err("Token is suspended or removed".to_string())?;
+    }
+    if nat_is_zero(&args.amount) {
+        Err("Flash swap amount is zero".to_string())?;
+    }
+    if args.amount > *balance {
+        Err(format!("Flash swap amount exceeds pool balance of {}", balance))?;
+    }
+
+    // make sure user is registered, if not create a new user
+    let user_id = user_map::insert(None)?;
+
+    Ok((user_id, pool, token))
+}
+
+async fn process_flash_swap(
+    request_id: u64,
+    pool: &StablePool,
+    token: &StableToken,
+    amount: &Nat,
+    data: Option<Vec<u8>>,
+    ts: u64,
+) -> Result<FlashSwapReply, String> {
+    let borrower = ICNetwork::caller();
+    let borrower_account = Account::from(borrower);
+    let kong_backend = kong_settings_map::get().kong_backend;
+    let token_id = token.token_id();
+    // the fee is charged and split at the pool's live LP fee
+    let fee_bps = pool.live_lp_fee_bps();
+    let fee = flash_swap_fee(amount, fee_bps);
+    // the borrower also pays back the gas of the loan transfer
+    let repay_amount = nat_add(&nat_add(amount, &fee), &token.fee());
+    let mut transfer_ids = Vec::new();
+
+    // make sure the borrower has approved and holds the repayment before anything is lent
+    request_map::update_status(request_id, StatusCode::FlashSwapCheckFunds, None);
+    if let Err(e) = get_borrower_funds(token, &borrower_account, &kong_backend)
+        .await
+        .and_then(|(allowance, balance)| check_borrower_funds(&allowance, &balance, &nat_add(&repay_amount, &token.fee()), ts))
+    {
+        request_map::update_status(request_id, StatusCode::FlashSwapCheckFundsFailed, Some(&e));
+        let reply = FlashSwapReply::new(
+            request_id,
+            StatusTx::Failed,
+            pool,
+            token,
+            amount,
+            &fee,
+            &repay_amount,
+            &transfer_ids,
+            ts,
+        );
+        request_map::update_reply(request_id, Reply::FlashSwap(reply));
+        request_map::update_status(request_id, StatusCode::Failed, None);
+        Err(format!("Req #{} failed. {}", request_id, e))?
+    }
+    request_map::update_status(request_id, StatusCode::FlashSwapCheckFundsSuccess, None);
+
+    // send the loan
+    request_map::update_status(request_id, StatusCode::FlashSwapSendLoan, None);
+    match icrc1_transfer(amount, &borrower_account, token, None).await {
+        Ok(block_id) => {
+            let transfer_id = transfer_map::insert(&StableTransfer {
+                transfer_id: 0,
+                request_id,
+                is_send: false,
+                amount: amount.clone(),
+                token_id,
+                tx_id: TxId::BlockIndex(block_id),
+                ts,
+            });
+            transfer_ids.push(transfer_id);
+            request_map::update_status(request_id, StatusCode::FlashSwapSendLoanSuccess, None);
+        }
+        Err(e) => {
+            request_map::update_status(request_id, StatusCode::FlashSwapSendLoanFailed, Some(&e.to_string()));
+            handle_failed_transfer(token, e.clone());
+            let reply = FlashSwapReply::new(
+                request_id,
+                StatusTx::Failed,
+                pool,
+                token,
+                amount,
+                &fee,
+                &repay_amount,
+                &transfer_ids,
+                ts,
+            );
+            request_map::update_reply(request_id, Reply::FlashSwap(reply));
+            request_map::update_status(request_id, StatusCode::Failed, None);
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    }
+
+    // call the borrower. bounded wait so a borrower that does not return can not keep the pool locked
+    request_map::update_status(request_id, StatusCode::FlashSwapCallback, None);
+    let callback_args = FlashSwapCallbackArgs {
+        request_id,
+        symbol: pool.symbol(),
+        token: token.symbol(),
+        amount: amount.clone(),
+        fee: fee.clone(),
+        repay_amount: repay_amount.clone(),
+        data,
+    };
+    let callback_result = Call::bounded_wait(borrower, FLASH_SWAP_CALLBACK)
+        .with_arg(callback_args)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|response| response.candid::<Result<(), String>>().map_err(|e| e.to_string()))
+        .and_then(|result| result);
+    match &callback_result {
+        Ok(()) => request_map::update_status(request_id, StatusCode::FlashSwapCallbackSuccess, None),
+        Err(e) => request_map::update_status(request_id, StatusCode::FlashSwapCallbackFailed, Some(e)),
+    };
+
+    // take the repayment. the borrower holds the loan whatever the callback returned. if the callback failed, the flash
+    // swap is reverted and only the loan and the gas of its transfer are taken back
+    request_map::update_status(request_id, StatusCode::FlashSwapRepay, None);
+    let take_amount = amount_to_repay(amount, &fee, &token.fee(), callback_result.is_ok());
+    match icrc2_transfer_from(token, &take_amount, &borrower_account, &kong_backend).await {
+        Ok(block_id) => {
+            let transfer_id = transfer_map::insert(&StableTransfer {
+                transfer_id: 0,
+                request_id,
+                is_send: true,
+                amount: take_amount.clone(),
+                token_id,
+                tx_id: TxId::BlockIndex(block_id),
+                ts,
+            });
+            transfer_ids.push(transfer_id);
+            request_map::update_status(request_id, StatusCode::FlashSwapRepaySuccess, None);
+
+            if let Err(e) = callback_result {
+                let reply = FlashSwapReply::new(
+                    request_id,
+                    StatusTx::Failed,
+                    pool,
+                    token,
+                    amount,
+                    &fee,
+                    &repay_amount,
+                    &transfer_ids,
+                    ts,
+                );
+                request_map::update_reply(request_id, Reply::FlashSwap(reply));
+                request_map::update_status(request_id, StatusCode::Failed, None);
+                Err(format!(
+                    "Req #{} failed. Flash swap callback failed and the loan was returned. {}",
+                    request_id, e
+                ))?
+            }
+
+            update_pool_fees(pool.pool_id, token_id, &fee, fee_bps);
+
+            let reply = FlashSwapReply::new(
+                request_id,
+                StatusTx::Success,
+                pool,
+                token,
+                amount,
+                &fee,
+                &repay_amount,
+                &transfer_ids,
+                ts,
+            );
+            request_map::update_reply(request_id, Reply::FlashSwap(reply.clone()));
+            request_map::update_status(request_id, StatusCode::Success, None);
+            Ok(reply)
+        }
+        Err(e) => {
+            request_map::update_status(request_id, StatusCode::FlashSwapRepayFailed, Some(&e.to_string()));
+            handle_failed_transfer(token, e.clone());
+
+            handle_unpaid_loan(request_id, pool, token, &borrower, amount, &e.to_string());
+
+            let reply = FlashSwapReply::new(
+                request_id,
+                StatusTx::Failed,
+                pool,
+                token,
+                amount,
+                &fee,
+                &repay_amount,
+                &transfer_ids,
+                ts,
+            );
+            request_map::update_reply(request_id, Reply::FlashSwap(reply));
+            request_map::update_status(request_id, StatusCode::Failed, None);
+            Err(format!("Req #{} failed. Flash swap not repaid. {}", request_id, e))?
+        }
+    }
+}
+
+/// allowance of the borrower to Kong and balance of the borrower for the token of the loan
+async fn get_borrower_funds(token: &StableToken, borrower: &Account, kong_backend: &Account) -> Result<(Allowance, Nat), String> {
+    let ledger = token.canister_id().ok_or("Invalid ledger id")?;
+    let allowance = get_allowance(*borrower, *kong_backend, ledger).await?;
+    let balance = get_balance(*borrower, ledger).await?;
+    Ok((allowance, balance))
+}
+
+/// the borrower must have approved and hold the repayment and its gas before the loan is sent
+fn check_borrower_funds(allowance: &Allowance, balance: &Nat, required_amount: &Nat, ts: u64) -> Result<(), String> {
+    if allowance.expires_at.is_some_and(|expires_at| expires_at <= ts) {
+        Err("Allowance of the borrower has expired".to_string())?;
+    }
+    if allowance.allowance < *required_amount {
+        Err(format!(
+            "Allowance of the borrower {} is less than repay amount and gas {}",
+            allowance.allowance, required_amount
+        ))?;
+    }
+    if *balance < *required_amount {
+        Err(format!(
+            "Balance of the borrower {} is less than repay amount and gas {}",
+            balance, required_amount
+        ))?;
+    }
+    Ok(())
+}
+
+/// amount taken from the borrower after the callback. a failed callback is reverted without a fee
+fn amount_to_repay(amount: &Nat, fee: &Nat, gas: &Nat, callback_succeeded: bool) -> Nat {
+    if callback_succeeded {
+        nat_add(&nat_add(amount, fee), gas)
+    } else {
+        nat_add(amount, gas)
+    }
+}
+
+/// fee of a flash swap, fee_bps of amount rounded up
+fn flash_swap_fee(amount: &Nat, fee_bps: u8) -> Nat {
+    nat_divide_ceil(&nat_multiply(amount, &Nat::from(fee_bps)), &Nat::from(10_000_u32)).unwrap_or(nat_zero())
+}
+
+/// add the fee of a repaid flash swap to the pool's LP and Kong fees, split as for swaps
+/// fee_bps is the LP fee the flash swap was charged at
+/// refreshes the pool first to make sure we have the latest state
+fn update_pool_fees(pool_id: u32, token_id: u32, fee: &Nat, fee_bps: u8) {
+    let Some(mut pool) = pool_map::get_by_pool_id(pool_id) else {
+        return;
+    };
+    let lp_total_supply = lp_token_map::get_total_supply(pool.lp_token_id);
+    // kong_fee = fee * kong_fee_bps / fee_bps
+    let kong_fee = nat_divide(&nat_multiply(fee, &Nat::from(pool.kong_fee_bps)), &Nat::from(fee_bps)).unwrap_or(nat_zero());
+    let kong_fee = std::cmp::min(kong_fee, fee.clone());
+    let lp_fee = nat_subtract(fee, &kong_fee).unwrap_or(nat_zero());
+    if token_id == pool.token_id_0 {
+        pool.lp_fee_0 = nat_add(&pool.lp_fee_0, &lp_fee);
+        pool.lp_fee_per_lp_0 = nat_add(&pool.lp_fee_per_lp_0, &lp_fee_per_lp(&lp_fee, &lp_total_supply));
+        pool.kong_fee_0 = nat_add(&pool.kong_fee_0, &kong_fee);
+    } else {
+        pool.lp_fee_1 = nat_add(&pool.lp_fee_1, &lp_fee);
+        pool.lp_fee_per_lp_1 = nat_add(&pool.lp_fee_per_lp_1, &lp_fee_per_lp(&lp_fee, &lp_total_supply));
+        pool.kong_fee_1 = nat_add(&pool.kong_fee_1, &kong_fee);
+    }
+    pool_map::update(&pool);
+}
+
+/// a loan that was not repaid is never written off the pool's balances, so the liquidity providers do not bear the loss
+/// the borrower can no longer borrow and the loss is logged for King Kong to cover
+fn handle_unpaid_loan(request_id: u64, pool: &StablePool, token: &StableToken, borrower: &Principal, amount: &Nat, error: &str) {
+    kong_settings_map::remove_flash_swap_borrower(borrower);
+    ICNetwork::error_log(&format!(
+        "Req #{} flash swap of {} {} from pool {} not repaid by {}: {}",
+        request_id,
+        amount,
+        token.symbol(),
+        pool.symbol(),
+        borrower.to_text(),
+        error
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_ic_token, insert_pool, update_kong_settings};
+    use crate::stable_pool::dynamic_fee::DynamicFee;
+
+    fn allowance(allowance: u64, expires_at: Option<u64>) -> Allowance {
+        Allowance {
+            allowance: Nat::from(allowance),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_check_borrower_funds() {
+        let required_amount = Nat::from(1_013_000_u64);
+        assert!(check_borrower_funds(&allowance(1_013_000, None), &Nat::from(1_013_000_u64), &required_amount, 100).is_ok());
+        assert!(check_borrower_funds(&allowance(2_000_000, Some(200)), &Nat::from(5_000_000_u64), &required_amount, 100).is_ok());
+
+        // the repayment is not approved
+        let e = check_borrower_funds(&allowance(1_012_999, None), &Nat::from(5_000_000_u64), &required_amount, 100).unwrap_err();
+        assert!(e.contains("Allowance"), "{}", e);
+        let e = check_borrower_funds(&allowance(2_000_000, Some(100)), &Nat::from(5_000_000_u64), &required_amount, 100).unwrap_err();
+        assert!(e.contains("expired"), "{}", e);
+        // the borrower does not hold the repayment
+        let e = check_borrower_funds(&allowance(2_000_000, None), &Nat::from(1_012_999_u64), &required_amount, 100).unwrap_err();
+        assert!(e.contains("Balance"), "{}", e);
+    }
+
+    #[test]
+    fn test_amount_to_repay_failed_callback_is_reverted_without_fee() {
+        let (amount, fee, gas) = (Nat::from(1_000_000_u64), Nat::from(3_000_u64), Nat::from(10_000_u64));
+        assert_eq!(amount_to_repay(&amount, &fee, &gas, true), Nat::from(1_013_000_u64));
+        assert_eq!(amount_to_repay(&amount, &fee, &gas, false), Nat::from(1_010_000_u64));
+    }
+
+    #[test]
+    fn test_handle_unpaid_loan_keeps_pool_balances() {
+        let token = insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckUSDT", 6);
+        init_kong_settings();
+        let pool = insert_pool(1, 1, 1_000_000_000, 2, 1_000_000_000);
+        let borrower = Principal::from_slice(&[1]);
+        update_kong_settings(|s| s.flash_swap_borrowers = vec![borrower, Principal::from_slice(&[2])]);
+
+        handle_unpaid_loan(1, &pool, &token, &borrower, &Nat::from(500_000_000_u64), "InsufficientAllowance");
+
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.balance_0, Nat::from(1_000_000_000_u64));
+        assert_eq!(pool.balance_1, Nat::from(1_000_000_000_u64));
+        assert_eq!(kong_settings_map::get().flash_swap_borrowers, vec![Principal::from_slice(&[2])]);
+    }
+
+    #[test]
+    fn test_update_pool_fees_splits_fee() {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckUSDT", 6);
+        init_kong_settings();
+        insert_pool(1, 1, 1_000_000_000, 2, 1_000_000_000);
+
+        // kong_fee = 3_000 * 8 / 30 = 800
+        update_pool_fees(1, 2, &Nat::from(3_000_u64), 30);
+
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.lp_fee_1, Nat::from(2_200_u64));
+        assert_eq!(pool.kong_fee_1, Nat::from(800_u64));
+        assert_eq!(pool.balance_1, Nat::from(1_000_000_000_u64));
+    }
+
+    #[test]
+    fn test_update_pool_fees_splits_at_the_dynamic_fee_charged() {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckUSDT", 6);
+        init_kong_settings();
+        insert_pool(1, 1, 1_000_000_000, 2, 1_000_000_000);
+        // calm pool with min 10 / static 30 / kong 8 charges 10 bps
+        let mut pool = pool_map::get_by_pool_id(1).unwrap();
+        pool.dynamic_fee = Some(DynamicFee::new(10, 50, 200, 600));
+        pool_map::update(&pool);
+        let fee_bps = pool.live_lp_fee_bps();
+        let fee = flash_swap_fee(&Nat::from(1_000_000_u64), fee_bps);
+        assert_eq!(fee, Nat::from(1_000_u64));
+
+        // kong_fee = 1_000 * 8 / 10 = 800
+        update_pool_fees(1, 2, &fee, fee_bps);
+
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.lp_fee_1, Nat::from(200_u64));
+        assert_eq!(pool.kong_fee_1, Nat::from(800_u64));
+    }
+}
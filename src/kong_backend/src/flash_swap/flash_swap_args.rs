@@ -0,0 +1,10 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct FlashSwapArgs {
+    pub pool: String,          // pool symbol, address or LP token symbol
+    pub token: String,         // token of the pool to borrow
+    pub amount: Nat,           // amount to borrow
+    pub data: Option<Vec<u8>>, // passed to the borrower's callback
+}
@@ -0,0 +1,14 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+/// argument of kong_flash_swap_callback() of the borrower canister
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct FlashSwapCallbackArgs {
+    pub request_id: u64,
+    pub symbol: String,        // symbol of the pool
+    pub token: String,         // symbol of the token borrowed
+    pub amount: Nat,           // amount borrowed
+    pub fee: Nat,              // flash swap fee
+    pub repay_amount: Nat,     // amount + fee + gas of the loan transfer. taken with icrc2_transfer_from after the callback
+    pub data: Option<Vec<u8>>, // data of the flash swap
+}
@@ -0,0 +1,61 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::stable_pool::stable_pool::StablePool;
+use crate::stable_token::stable_token::StableToken;
+use crate::stable_token::token::Token;
+use crate::stable_token::token_map;
+use crate::stable_transfer::transfer_map;
+use crate::stable_tx::status_tx::StatusTx;
+use crate::transfers::transfer_reply::TransferIdReply;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct FlashSwapReply {
+    pub request_id: u64,
+    pub status: String,
+    pub symbol: String,
+    pub chain: String,
+    pub token: String,
+    pub address: String,
+    pub amount: Nat,
+    pub fee: Nat,
+    pub repay_amount: Nat,
+    pub transfer_ids: Vec<TransferIdReply>,
+    pub ts: u64,
+}
+
+impl FlashSwapReply {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        request_id: u64,
+        status: StatusTx,
+        pool: &StablePool,
+        token: &StableToken,
+        amount: &Nat,
+        fee: &Nat,
+        repay_amount: &Nat,
+        transfer_ids: &[u64],
+        ts: u64,
+    ) -> Self {
+        FlashSwapReply {
+            request_id,
+            status: status.to_string(),
+            symbol: pool.symbol(),
+            chain: token.chain(),
+            token: token.symbol(),
+            address: token.address(),
+            amount: amount.clone(),
+            fee: fee.clone(),
+            repay_amount: repay_amount.clone(),
+            transfer_ids: transfer_ids
+                .iter()
+                .filter_map(|&transfer_id| {
+                    let transfer = transfer_map::get_by_transfer_id(transfer_id)?;
+                    let token = token_map::get_by_token_id(transfer.token_id)?;
+                    TransferIdReply::try_from((transfer_id, &transfer, &token)).ok()
+                })
+                .collect(),
+            ts,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod flash_swap;
+pub mod flash_swap_args;
+pub mod flash_swap_callback_args;
+pub mod flash_swap_reply;
@@ -1,3 +1,5 @@
 pub mod json_helpers;
 pub mod math_helpers;
 pub mod nat_helpers;
+#[cfg(test)]
+pub mod test_helpers;
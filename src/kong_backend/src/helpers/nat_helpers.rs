@@ -102,6 +102,19 @@ pub fn nat_divide(numerator: &Nat, denominator: &Nat) -> Option<Nat> {
     Some(numerator.clone() / denominator.clone())
 }
 
+// integer division rounded up
+pub fn nat_divide_ceil(numerator: &Nat, denominator: &Nat) -> Option<Nat> {
+    if nat_is_zero(denominator) {
+        None?
+    }
+    let quotient = nat_divide(numerator, denominator)?;
+    if nat_multiply(&quotient, denominator) == *numerator {
+        Some(quotient)
+    } else {
+        Some(quotient + 1_u32)
+    }
+}
+
 // division with decimal precision
 #[allow(dead_code)]
 pub fn nat_divide_as_f64(numerator: &Nat, denominator: &Nat) -> Option<f64> {
@@ -135,6 +148,17 @@ where
     serializer.serialize_str(&amount_str)
 }
 
+/// Serialize an optional Nat as a string, see serialize_amount_as_string()
+pub fn serialize_optional_amount_as_string<S>(amount: &Option<Nat>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match amount {
+        Some(amount) => serialize_amount_as_string(amount, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,6 +251,23 @@ mod tests {
         assert_eq!(x, Some(Nat::from(5_u128)));
     }
 
+    #[test]
+    fn test_nat_divide_ceil() {
+        let n1 = Nat::from(5_000_000_000_u128);
+        let n2 = Nat::from(0_u128);
+        let x = nat_divide_ceil(&n1, &n2);
+        assert_eq!(x, None);
+
+        let n1 = Nat::from(5_000_000_000_u128);
+        let n2 = Nat::from(1_000_000_000_u128);
+        let x = nat_divide_ceil(&n1, &n2);
+        assert_eq!(x, Some(Nat::from(5_u128)));
+
+        let n1 = Nat::from(5_000_000_001_u128);
+        let x = nat_divide_ceil(&n1, &n2);
+        assert_eq!(x, Some(Nat::from(6_u128)));
+    }
+
     #[test]
     fn test_nat_divide_f64() {
         let n1 = Nat::from(5_000_000_000_u128);
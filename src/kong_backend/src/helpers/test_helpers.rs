@@ -0,0 +1,182 @@
+//! Fixtures shared by the unit tests. Tokens and pools are inserted directly into the stable maps.
+use candid::{Nat, Principal};
+
+use crate::ripple::payment_job::{RipplePaymentJob, RipplePaymentJobId, RipplePaymentJobStatus};
+use crate::ripple::stable_memory::with_ripple_payment_job_queue_mut;
+use crate::stable_campaign::campaign_map;
+use crate::stable_campaign::stable_campaign::StableCampaign;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_kong_settings::stable_kong_settings::StableKongSettings;
+use crate::stable_lp_token::lp_position_map;
+use crate::stable_lp_token::stable_lp_position::StableLPPosition;
+use crate::stable_memory::{KONG_SETTINGS, POOL_MAP, TOKEN_MAP, USER_MAP};
+use crate::stable_pool::concentrated_pool::ConcentratedPool;
+use crate::stable_pool::pool_type::PoolType;
+use crate::stable_pool::stable_pool::{StablePool, StablePoolId};
+use crate::stable_pool::{concentrated_liquidity, concentrated_math, pool_map};
+use crate::stable_token::ic_token::ICToken;
+use crate::stable_token::solana_token::SolanaToken;
+use crate::stable_token::stable_token::{StableToken, StableTokenId};
+use crate::stable_token::token::Token;
+use crate::stable_user::principal_id_map;
+use crate::stable_user::stable_user::{StableUser, StableUserId};
+
+// the Kong settings are initialized from the stable maps, so they must be read once before a map is borrowed to
+// increment its index
+pub fn init_kong_settings() {
+    kong_settings_map::get();
+}
+
+pub fn update_kong_settings(f: impl FnOnce(&mut StableKongSettings)) {
+    let mut kong_settings = kong_settings_map::get();
+    f(&mut kong_settings);
+    KONG_SETTINGS.with(|s| _ = s.borrow_mut().set(kong_settings));
+}
+
+pub fn ic_token(token_id: u32, symbol: &str, decimals: u8) -> StableToken {
+    StableToken::IC(ICToken {
+        token_id,
+        name: symbol.to_string(),
+        symbol: symbol.to_string(),
+        canister_id: Principal::anonymous(),
+        decimals,
+        fee: Nat::from(10_000_u64),
+        icrc1: true,
+        icrc2: true,
+        icrc3: true,
+        is_removed: false,
+    })
+}
+
+// native SOL
+pub fn sol_token(token_id: u32) -> StableToken {
+    StableToken::Solana(SolanaToken {
+        token_id,
+        name: "Solana".to_string(),
+        symbol: "SOL".to_string(),
+        decimals: 9,
+        fee: Nat::from(5_000_u64),
+        mint_address: "So11111111111111111111111111111111111111112".to_string(),
+        program_id: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".to_string(),
+        is_spl_token: false,
+        has_transfer_fee: false,
+    })
+}
+
+pub fn insert_token(token: &StableToken) {
+    TOKEN_MAP.with(|m| m.borrow_mut().insert(StableTokenId(token.token_id()), token.clone()));
+}
+
+pub fn insert_ic_token(token_id: u32, symbol: &str, decimals: u8) -> StableToken {
+    let token = ic_token(token_id, symbol, decimals);
+    insert_token(&token);
+    token
+}
+
+// pool with a 0.3% LP fee
+pub fn insert_pool(pool_id: u32, token_id_0: u32, balance_0: u128, token_id_1: u32, balance_1: u128) -> StablePool {
+    let pool = StablePool {
+        pool_id,
+        balance_0: Nat::from(balance_0),
+        balance_1: Nat::from(balance_1),
+        ..StablePool::new(token_id_0, token_id_1, 30, 8, 0)
+    };
+    POOL_MAP.with(|m| m.borrow_mut().insert(StablePoolId(pool_id), pool.clone()));
+    pool
+}
+
+// concentrated liquidity pool at a price of 1 (tick 0) with a 0.3% LP fee and no positions
+pub fn insert_concentrated_pool(pool_id: u32, token_id_0: u32, token_id_1: u32, tick_spacing: u16) -> StablePool {
+    let sqrt_price = concentrated_math::sqrt_price_at_tick(0).unwrap();
+    let pool = StablePool {
+        pool_type: PoolType::Concentrated { tick_spacing },
+        concentrated: Some(ConcentratedPool::new(&sqrt_price).unwrap()),
+        ..insert_pool(pool_id, token_id_0, 0, token_id_1, 0)
+    };
+    POOL_MAP.with(|m| m.borrow_mut().insert(StablePoolId(pool_id), pool.clone()));
+    pool
+}
+
+// position of user_id with liquidity in [tick_lower, tick_upper), added to the pool's liquidity and ticks
+pub fn insert_position(user_id: u32, pool_id: u32, tick_lower: i32, tick_upper: i32, liquidity: u128) -> StableLPPosition {
+    let mut pool = pool_map::get_by_pool_id(pool_id).unwrap();
+    let mut position = StableLPPosition::new(user_id, pool_id, tick_lower, tick_upper, 0);
+    concentrated_liquidity::update_position(&mut pool, &mut position, &Nat::from(liquidity), true).unwrap();
+    pool_map::update(&pool);
+    position.position_id = lp_position_map::insert(&position);
+    position
+}
+
+// campaign funded by user_id distributing reward_amount of reward_token_id to the LPs of pool_id from start_ts to end_ts
+pub fn insert_campaign(
+    user_id: u32,
+    pool_id: u32,
+    reward_token_id: u32,
+    reward_amount: u128,
+    start_ts: u64,
+    end_ts: u64,
+) -> StableCampaign {
+    let pool = pool_map::get_by_pool_id(pool_id).unwrap();
+    let campaign = StableCampaign::new(
+        user_id,
+        1,
+        pool_id,
+        pool.lp_token_id,
+        reward_token_id,
+        &Nat::from(reward_amount),
+        start_ts,
+        end_ts,
+        0,
+    );
+    let campaign_id = campaign_map::insert(&campaign);
+    campaign_map::get_by_campaign_id(campaign_id).unwrap()
+}
+
+pub fn insert_user(user_id: u32, principal_id: Principal) -> StableUser {
+    let user = StableUser {
+        user_id,
+        principal_id: principal_id.to_text(),
+        my_referral_code: String::new(),
+        referred_by: None,
+        referred_by_expires_at: None,
+        fee_level: 0,
+        fee_level_expires_at: None,
+        earned_fee_level: 0,
+        volume_30d: 0.0,
+    };
+    USER_MAP.with(|m| m.borrow_mut().insert(StableUserId(user_id), user.clone()));
+    user
+}
+
+// user that can be looked up by principal_id, e.g. the ICRC-1 textual encoding of an account holding LP tokens
+pub fn insert_user_by_principal_id(user_id: u32, principal_id: &str) -> StableUser {
+    let user = StableUser {
+        principal_id: principal_id.to_string(),
+        ..insert_user(user_id, Principal::anonymous())
+    };
+    USER_MAP.with(|m| m.borrow_mut().insert(StableUserId(user_id), user.clone()));
+    principal_id_map::insert_principal_id(&user);
+    user
+}
+
+// payment of 1 XRP from Kong's XRPL account for request 1 of user 1, signed as transaction tx_hash
+pub fn insert_ripple_payment_job(job_id: u64, status: RipplePaymentJobStatus, tx_hash: &str) -> RipplePaymentJob {
+    let job = RipplePaymentJob {
+        id: job_id,
+        user_id: 1,
+        request_id: 1,
+        token_id: 1,
+        amount: Nat::from(1_000_000_u32),
+        to_address: "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".to_string(),
+        status,
+        created_at: 0,
+        updated_at: 0,
+        tx_blob: String::new(),
+        tx_hash: tx_hash.to_string(),
+        sequence: job_id as u32,
+        last_ledger_sequence: 100,
+        error_message: None,
+    };
+    with_ripple_payment_job_queue_mut(|queue| queue.insert(RipplePaymentJobId(job_id), job.clone()));
+    job
+}
@@ -4,12 +4,13 @@ use icrc_ledger_types::icrc1::account::Account;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
-/// Represents an address which can be either an Account ID, a Principal ID, or a Solana Address.
+/// Represents an address which can be either an Account ID, a Principal ID, a Solana Address or an XRPL Address.
 #[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
 pub enum Address {
     AccountId(AccountIdentifier),
     PrincipalId(Account),
     SolanaAddress(String),
+    RippleAddress(String),
 }
 
 impl Display for Address {
@@ -18,6 +19,7 @@ impl Display for Address {
             Address::AccountId(account_id) => write!(f, "{}", account_id),
             Address::PrincipalId(principal_id) => write!(f, "{}", principal_id),
             Address::SolanaAddress(address) => write!(f, "{}", address),
+            Address::RippleAddress(address) => write!(f, "{}", address),
         }
     }
 }
@@ -4,6 +4,7 @@ use icrc_ledger_types::icrc1::account::Account;
 use regex::Regex;
 use std::sync::OnceLock;
 
+use crate::ripple::utils::validation as ripple_validation;
 use crate::solana::utils::validation;
 use crate::stable_token::{stable_token::StableToken, token::Token};
 
@@ -23,6 +24,11 @@ pub fn get_address(token: &StableToken, address: &str) -> Result<Address, String
         return Ok(Address::SolanaAddress(address.to_string()));
     }
 
+    if let StableToken::Ripple(_) = token {
+        ripple_validation::validate_address(address).map_err(|e| format!("Invalid XRPL address: {}", e))?;
+        return Ok(Address::RippleAddress(address.to_string()));
+    }
+
     // Handle IC tokens (existing logic)
     let regrex_princiapl_id = PRINCIPAL_ID_LOCK.get_or_init(|| Regex::new(PRINCIPAL_ID_REGEX).unwrap());
     let regrex_account_id = ACCOUNT_ID_LOCK.get_or_init(|| Regex::new(ACCOUNT_ID_REGEX).unwrap());
@@ -1,14 +1,8 @@
-use candid::Principal;
-
 use crate::stable_memory::KONG_SETTINGS;
 use crate::stable_user::user_map;
 
 use super::network::ICNetwork;
 
-// Authorized proxy principals
-const PROXY_PRINCIPAL_ONE: &str = "6d7py-dit3v-5kk25-r7dci-gtr3f-rbxl7-m5oxw-7rhyu-4pmpp-j4lj2-lqe";
-const PROXY_PRINCIPAL_TWO: &str = "xlotz-rhc3l-wkpkp-yzvau-ww2qy-zftze-2wrpx-pzcd2-fqa4t-3n36y-rae";
-
 /// guard to make sure KongSwap is not in maintenance mode
 pub fn not_in_maintenance_mode() -> Result<(), String> {
     if KONG_SETTINGS.with(|s| s.borrow().get().maintenance_mode) {
@@ -30,10 +24,10 @@ pub fn caller_is_kingkong() -> Result<(), String> {
     Ok(())
 }
 
-/// Guard that checks if the caller is kong_rpc
+/// Guard that checks if the caller is one of the kong_rpc relayers of Kong settings
 pub fn caller_is_kong_rpc() -> Result<(), String> {
     let caller = ICNetwork::caller();
-    if caller == Principal::from_text(PROXY_PRINCIPAL_ONE).unwrap() || caller == Principal::from_text(PROXY_PRINCIPAL_TWO).unwrap() {
+    if KONG_SETTINGS.with(|s| s.borrow().get().kong_rpc_relayers.contains(&caller)) {
         Ok(())
     } else {
         Err("Caller is not kong_rpc".to_string())
@@ -1,5 +1,6 @@
 use candid::{CandidType, Nat, Principal};
 use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
 use serde::{Deserialize, Serialize};
 
 #[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +18,15 @@ pub async fn get_balance(principal_id: Account, ledger: &Principal) -> Result<Na
         .map_err(|e| format!("{:?}", e))
 }
 
+pub async fn get_allowance(account: Account, spender: Account, ledger: &Principal) -> Result<Allowance, String> {
+    ic_cdk::call::Call::unbounded_wait(*ledger, "icrc2_allowance")
+        .with_arg(AllowanceArgs { account, spender })
+        .await
+        .map_err(|e| format!("{:?}", e))?
+        .candid::<Allowance>()
+        .map_err(|e| format!("{:?}", e))
+}
+
 pub async fn get_name(ledger: &Principal) -> Result<String, String> {
     ic_cdk::call::Call::unbounded_wait(*ledger, "icrc1_name")
         .await
@@ -9,7 +9,13 @@ impl ICNetwork {
         ICNetwork::log("ERROR", msg);
     }
 
+    #[cfg(not(test))]
     fn log(level: &str, msg: &str) {
         ic_cdk::api::debug_print(format!("[{}] {}", level, msg));
     }
+
+    #[cfg(test)]
+    fn log(level: &str, msg: &str) {
+        println!("[{}] {}", level, msg);
+    }
 }
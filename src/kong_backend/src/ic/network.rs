@@ -6,15 +6,29 @@ pub struct ICNetwork;
 
 impl ICNetwork {
     /// return the current time in nanoseconds
+    #[cfg(not(test))]
     pub fn get_time() -> u64 {
         ic_cdk::api::time()
     }
 
+    /// Unit tests run outside of a canister, the time is fixed at 0.
+    #[cfg(test)]
+    pub fn get_time() -> u64 {
+        0
+    }
+
     /// Principal ID of the caller.
+    #[cfg(not(test))]
     pub fn caller() -> Principal {
         ic_cdk::api::msg_caller()
     }
 
+    /// Unit tests run outside of a canister, the caller is anonymous.
+    #[cfg(test)]
+    pub fn caller() -> Principal {
+        Principal::anonymous()
+    }
+
     /// Account of the caller.
     pub fn caller_id() -> Account {
         Account::from(ICNetwork::caller())
@@ -10,10 +10,17 @@ pub struct KongBackend {}
 
 impl KongBackend {
     /// Principal ID of the canister.
+    #[cfg(not(test))]
     pub fn canister() -> Principal {
         ic_cdk::api::canister_self()
     }
 
+    /// Unit tests run outside of a canister, use the anonymous principal.
+    #[cfg(test)]
+    pub fn canister() -> Principal {
+        Principal::anonymous()
+    }
+
     /// Account of the canister.
     pub fn canister_id() -> Account {
         Account::from(KongBackend::canister())
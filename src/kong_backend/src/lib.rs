@@ -2,25 +2,35 @@ pub mod add_liquidity;
 pub mod add_liquidity_amounts;
 pub mod add_pool;
 pub mod add_token;
+pub mod campaigns;
 pub mod canister;
 pub mod chains;
 pub mod claims;
 pub mod controllers;
+pub mod fee_tiers;
+pub mod flash_swap;
 pub mod helpers;
 pub mod ic;
 pub mod kong_backend;
 pub mod kong_data;
+pub mod lp_ledger;
+pub mod lp_locks;
+pub mod orders;
 pub mod pools;
+pub mod positions;
+pub mod protocol_fees;
 pub mod remove_liquidity;
 pub mod remove_liquidity_amounts;
 pub mod requests;
 pub mod ripple;
 pub mod send;
 pub mod solana;
+pub mod stable_campaign;
 pub mod stable_claim;
 pub mod stable_kong_settings;
 pub mod stable_lp_token;
 pub mod stable_memory;
+pub mod stable_order;
 pub mod stable_pool;
 pub mod stable_request;
 pub mod stable_token;
@@ -33,6 +43,7 @@ pub mod tokens;
 pub mod transfers;
 pub mod user;
 pub mod user_balances;
+pub mod zap_in;
 
 pub const APP_NAME: &str = "KongSwap";
 pub const APP_VERSION: &str = "v0.0.26";
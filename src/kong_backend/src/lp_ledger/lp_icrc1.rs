@@ -0,0 +1,48 @@
+use candid::{Nat, Principal};
+use ic_cdk::{query, update};
+use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue;
+use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc1::transfer::{TransferArg, TransferError};
+
+use crate::ic::{guards::not_in_maintenance_mode, network::ICNetwork};
+use crate::stable_lp_token::lp_token_map;
+
+use super::lp_ledger::{account_user_id, balance_of, get_caller_lp_token, transfer, LP_LEDGER_ERROR_CODE, LP_LEDGER_FEE};
+
+/// ICRC-1 metadata of the LP token of the calling kong_lp_ledger
+#[query(guard = "not_in_maintenance_mode")]
+fn lp_icrc1_metadata() -> Result<Vec<(String, MetadataValue)>, String> {
+    let lp_token = get_caller_lp_token()?;
+    Ok(vec![
+        MetadataValue::entry("icrc1:name", lp_token.name()),
+        MetadataValue::entry("icrc1:symbol", lp_token.symbol.as_str()),
+        MetadataValue::entry("icrc1:decimals", Nat::from(lp_token.decimals)),
+        MetadataValue::entry("icrc1:fee", Nat::from(LP_LEDGER_FEE)),
+    ])
+}
+
+#[query(guard = "not_in_maintenance_mode")]
+fn lp_icrc1_total_supply() -> Result<Nat, String> {
+    let lp_token = get_caller_lp_token()?;
+    Ok(lp_token_map::get_total_supply(lp_token.token_id))
+}
+
+#[query(guard = "not_in_maintenance_mode")]
+fn lp_icrc1_balance_of(account: Account) -> Result<Nat, String> {
+    let lp_token = get_caller_lp_token()?;
+    Ok(balance_of(lp_token.token_id, account_user_id(&account)?))
+}
+
+/// ICRC-1 transfer of the LP token of the calling kong_lp_ledger from its caller. returns the block index of the transfer
+#[update(guard = "not_in_maintenance_mode")]
+fn lp_icrc1_transfer(caller: Principal, args: TransferArg) -> Result<Nat, TransferError> {
+    let lp_token = get_caller_lp_token().map_err(|message| TransferError::GenericError {
+        error_code: Nat::from(LP_LEDGER_ERROR_CODE),
+        message,
+    })?;
+    let from = Account {
+        owner: caller,
+        subaccount: args.from_subaccount,
+    };
+    Ok(Nat::from(transfer(&lp_token, &from, &args, ICNetwork::get_time())?))
+}
@@ -0,0 +1,62 @@
+use candid::{Nat, Principal};
+use ic_cdk::{query, update};
+use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
+use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc2::transfer_from::{TransferFromArgs, TransferFromError};
+
+use crate::helpers::nat_helpers::nat_zero;
+use crate::ic::{guards::not_in_maintenance_mode, network::ICNetwork};
+use crate::stable_lp_token::lp_allowance_map;
+
+use super::lp_ledger::{account_user_id, approve, get_caller_lp_token, transfer_from, LP_LEDGER_ERROR_CODE};
+
+/// ICRC-2 allowance of spender over the LP token of account
+#[query(guard = "not_in_maintenance_mode")]
+fn lp_icrc2_allowance(args: AllowanceArgs) -> Result<Allowance, String> {
+    let lp_token = get_caller_lp_token()?;
+    let allowance = match (account_user_id(&args.account)?, account_user_id(&args.spender)?) {
+        (Some(owner_user_id), Some(spender_user_id)) => {
+            lp_allowance_map::get(lp_token.token_id, owner_user_id, spender_user_id, ICNetwork::get_time())
+        }
+        _ => None,
+    };
+    Ok(match allowance {
+        Some(allowance) => Allowance {
+            allowance: allowance.amount,
+            expires_at: allowance.expires_at,
+        },
+        None => Allowance {
+            allowance: nat_zero(),
+            expires_at: None,
+        },
+    })
+}
+
+/// ICRC-2 approve of spender to transfer the LP token of the caller. returns the block index of the approval
+#[update(guard = "not_in_maintenance_mode")]
+fn lp_icrc2_approve(caller: Principal, args: ApproveArgs) -> Result<Nat, ApproveError> {
+    let lp_token = get_caller_lp_token().map_err(|message| ApproveError::GenericError {
+        error_code: Nat::from(LP_LEDGER_ERROR_CODE),
+        message,
+    })?;
+    let from = Account {
+        owner: caller,
+        subaccount: args.from_subaccount,
+    };
+    Ok(Nat::from(approve(&lp_token, &from, &args, ICNetwork::get_time())?))
+}
+
+/// ICRC-2 transfer of an LP token by the caller as spender. returns the block index of the transfer
+#[update(guard = "not_in_maintenance_mode")]
+fn lp_icrc2_transfer_from(caller: Principal, args: TransferFromArgs) -> Result<Nat, TransferFromError> {
+    let lp_token = get_caller_lp_token().map_err(|message| TransferFromError::GenericError {
+        error_code: Nat::from(LP_LEDGER_ERROR_CODE),
+        message,
+    })?;
+    let spender = Account {
+        owner: caller,
+        subaccount: args.spender_subaccount,
+    };
+    Ok(Nat::from(transfer_from(&lp_token, &spender, &args, ICNetwork::get_time())?))
+}
@@ -0,0 +1,41 @@
+use candid::Nat;
+use ic_cdk::query;
+use icrc_ledger_types::icrc3::blocks::{BlockWithId, GetBlocksRequest, GetBlocksResult};
+
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::stable_lp_token::lp_block_map;
+
+use super::lp_ledger::get_caller_lp_token;
+
+/// max number of blocks returned by a single lp_icrc3_get_blocks call
+const MAX_BLOCKS_PER_REQUEST: u64 = 1_000;
+
+/// ICRC-3 blocks of the LP token of the calling kong_lp_ledger. all blocks are kept in kong_backend, so archived_blocks
+/// is always empty
+#[query(guard = "not_in_maintenance_mode")]
+fn lp_icrc3_get_blocks(args: Vec<GetBlocksRequest>) -> Result<GetBlocksResult, String> {
+    let lp_token = get_caller_lp_token()?;
+
+    let mut blocks = Vec::new();
+    for arg in args {
+        let remaining = MAX_BLOCKS_PER_REQUEST - blocks.len() as u64;
+        if remaining == 0 {
+            break;
+        }
+        let (start, length) = arg.as_start_and_length()?;
+        blocks.extend(
+            lp_block_map::get_by_range(lp_token.token_id, start, length.min(remaining))
+                .into_iter()
+                .map(|block| BlockWithId {
+                    id: Nat::from(block.block_id),
+                    block: block.to_icrc3_value(),
+                }),
+        );
+    }
+
+    Ok(GetBlocksResult {
+        log_length: Nat::from(lp_block_map::get_log_length(lp_token.token_id)),
+        blocks,
+        archived_blocks: Vec::new(),
+    })
+}
@@ -0,0 +1,612 @@
+use candid::{Nat, Principal};
+use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc1::transfer::{TransferArg, TransferError};
+use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc2::transfer_from::{TransferFromArgs, TransferFromError};
+use thiserror::Error;
+
+use crate::helpers::nat_helpers::{nat_subtract, nat_zero};
+use crate::ic::network::ICNetwork;
+use crate::kong_backend::KongBackend;
+use crate::stable_lp_token::lp_allowance_map;
+use crate::stable_lp_token::lp_block_map;
+use crate::stable_lp_token::lp_token_map;
+use crate::stable_lp_token::stable_lp_allowance::StableLPAllowance;
+use crate::stable_lp_token::stable_lp_block::{LPBlockOp, StableLPBlock};
+use crate::stable_lp_token::transfer::{burn_by_user_id, transfer_by_user_id};
+use crate::stable_memory::TOKEN_MAP;
+use crate::stable_token::lp_token::LPToken;
+use crate::stable_token::stable_token::StableToken;
+use crate::stable_token::token_map;
+use crate::stable_user::user_map;
+
+/// LP tokens do not charge a transfer or approve fee
+pub const LP_LEDGER_FEE: u32 = 0;
+
+/// error_code of GenericError replies
+pub const LP_LEDGER_ERROR_CODE: u32 = 0;
+
+/// transactions with a created_at_time are deduplicated for 24 hours
+pub const TX_WINDOW_NANOSECS: u64 = 86_400_000_000_000;
+
+/// created_at_time may be ahead of the ledger time by 2 minutes
+pub const PERMITTED_DRIFT_NANOSECS: u64 = 120_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum LPLedgerError {
+    #[error("Bad fee. Expected fee {expected_fee}")]
+    BadFee { expected_fee: Nat },
+    #[error("Insufficient funds. Balance {balance}")]
+    InsufficientFunds { balance: Nat },
+    #[error("Insufficient allowance. Allowance {allowance}")]
+    InsufficientAllowance { allowance: Nat },
+    #[error("Allowance changed. Current allowance {current_allowance}")]
+    AllowanceChanged { current_allowance: Nat },
+    #[error("Approval expired at ledger time {ledger_time}")]
+    Expired { ledger_time: u64 },
+    #[error("Transaction too old")]
+    TooOld,
+    #[error("Transaction created in the future of ledger time {ledger_time}")]
+    CreatedInFuture { ledger_time: u64 },
+    #[error("Duplicate of block {duplicate_of}")]
+    Duplicate { duplicate_of: Nat },
+    #[error("{0}")]
+    GenericError(String),
+}
+
+impl From<String> for LPLedgerError {
+    fn from(message: String) -> Self {
+        LPLedgerError::GenericError(message)
+    }
+}
+
+impl From<&str> for LPLedgerError {
+    fn from(message: &str) -> Self {
+        LPLedgerError::GenericError(message.to_string())
+    }
+}
+
+impl From<LPLedgerError> for TransferError {
+    fn from(e: LPLedgerError) -> Self {
+        match e {
+            LPLedgerError::BadFee { expected_fee } => TransferError::BadFee { expected_fee },
+            LPLedgerError::InsufficientFunds { balance } => TransferError::InsufficientFunds { balance },
+            LPLedgerError::TooOld => TransferError::TooOld,
+            LPLedgerError::CreatedInFuture { ledger_time } => TransferError::CreatedInFuture { ledger_time },
+            LPLedgerError::Duplicate { duplicate_of } => TransferError::Duplicate { duplicate_of },
+            e => TransferError::GenericError {
+                error_code: Nat::from(LP_LEDGER_ERROR_CODE),
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<LPLedgerError> for ApproveError {
+    fn from(e: LPLedgerError) -> Self {
+        match e {
+            LPLedgerError::BadFee { expected_fee } => ApproveError::BadFee { expected_fee },
+            LPLedgerError::InsufficientFunds { balance } => ApproveError::InsufficientFunds { balance },
+            LPLedgerError::AllowanceChanged { current_allowance } => ApproveError::AllowanceChanged { current_allowance },
+            LPLedgerError::Expired { ledger_time } => ApproveError::Expired { ledger_time },
+            LPLedgerError::TooOld => ApproveError::TooOld,
+            LPLedgerError::CreatedInFuture { ledger_time } => ApproveError::CreatedInFuture { ledger_time },
+            LPLedgerError::Duplicate { duplicate_of } => ApproveError::Duplicate { duplicate_of },
+            e => ApproveError::GenericError {
+                error_code: Nat::from(LP_LEDGER_ERROR_CODE),
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<LPLedgerError> for TransferFromError {
+    fn from(e: LPLedgerError) -> Self {
+        match e {
+            LPLedgerError::BadFee { expected_fee } => TransferFromError::BadFee { expected_fee },
+            LPLedgerError::InsufficientFunds { balance } => TransferFromError::InsufficientFunds { balance },
+            LPLedgerError::InsufficientAllowance { allowance } => TransferFromError::InsufficientAllowance { allowance },
+            LPLedgerError::TooOld => TransferFromError::TooOld,
+            LPLedgerError::CreatedInFuture { ledger_time } => TransferFromError::CreatedInFuture { ledger_time },
+            LPLedgerError::Duplicate { duplicate_of } => TransferFromError::Duplicate { duplicate_of },
+            e => TransferFromError::GenericError {
+                error_code: Nat::from(LP_LEDGER_ERROR_CODE),
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+/// LP token addressed by symbol or address, with or without the LP. prefix
+pub fn get_lp_token(token: &str) -> Result<LPToken, String> {
+    match token_map::get_by_token(token)? {
+        StableToken::LP(lp_token) if !lp_token.is_removed => Ok(lp_token),
+        StableToken::LP(_) => Err(format!("LP token {} is removed", token)),
+        _ => Err(format!("{} is not an LP token", token)),
+    }
+}
+
+/// LP token served by the kong_lp_ledger canister ledger_id
+pub fn get_lp_token_by_ledger(ledger_id: Principal) -> Result<LPToken, String> {
+    let lp_token = TOKEN_MAP
+        .with(|m| {
+            m.borrow().iter().find_map(|(_, v)| match v {
+                StableToken::LP(lp_token) if lp_token.ledger_id == Some(ledger_id) => Some(lp_token),
+                _ => None,
+            })
+        })
+        .ok_or(format!("{} is not an LP token ledger", ledger_id))?;
+    if lp_token.is_removed {
+        Err(format!("LP token {} is removed", lp_token.symbol))?
+    }
+    Ok(lp_token)
+}
+
+/// LP token of the kong_lp_ledger canister calling kong_backend
+pub fn get_caller_lp_token() -> Result<LPToken, String> {
+    get_lp_token_by_ledger(ICNetwork::caller())
+}
+
+/// minting account of all LP tokens. LP tokens are minted by add_pool and add_liquidity, transfers to it burn them
+pub fn minting_account() -> Account {
+    KongBackend::canister_id()
+}
+
+/// principal_id of the Kong user holding an account. the default subaccount is held by the owner's user,
+/// any other subaccount by a user of its own with the ICRC-1 textual encoding of the account as principal_id
+pub fn account_principal_id(account: &Account) -> Result<String, String> {
+    if account.owner == Principal::anonymous() {
+        Err("Anonymous account not allowed")?
+    }
+    Ok(account.to_string())
+}
+
+/// user_id of the holder of an account. None if the account has never held LP tokens
+pub fn account_user_id(account: &Account) -> Result<Option<u32>, String> {
+    let principal_id = account_principal_id(account)?;
+    Ok(user_map::get_by_principal_id(&principal_id)?.map(|user| user.user_id))
+}
+
+pub fn balance_of(token_id: u32, user_id: Option<u32>) -> Nat {
+    user_id
+        .and_then(|user_id| lp_token_map::get_by_token_id_by_user_id(token_id, user_id))
+        .map_or_else(nat_zero, |lp_token| lp_token.amount)
+}
+
+/// fee must be omitted or match LP_LEDGER_FEE
+pub fn check_fee(fee: &Option<Nat>) -> Result<(), LPLedgerError> {
+    match fee {
+        Some(fee) if *fee != LP_LEDGER_FEE => Err(LPLedgerError::BadFee {
+            expected_fee: Nat::from(LP_LEDGER_FEE),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// created_at_time must be within the deduplication window of the ledger time ts
+pub fn check_created_at_time(created_at_time: Option<u64>, ts: u64) -> Result<(), LPLedgerError> {
+    match created_at_time {
+        Some(created_at_time) if created_at_time.saturating_add(TX_WINDOW_NANOSECS + PERMITTED_DRIFT_NANOSECS) < ts => {
+            Err(LPLedgerError::TooOld)
+        }
+        Some(created_at_time) if created_at_time > ts.saturating_add(PERMITTED_DRIFT_NANOSECS) => {
+            Err(LPLedgerError::CreatedInFuture { ledger_time: ts })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// reject a block with a created_at_time that is already in the log
+fn check_duplicate(block: &StableLPBlock, ts: u64) -> Result<(), LPLedgerError> {
+    if block.created_at_time.is_none() {
+        return Ok(());
+    }
+    // a block can be created up to the permitted drift before its created_at_time
+    let since_ts = ts.saturating_sub(TX_WINDOW_NANOSECS + 2 * PERMITTED_DRIFT_NANOSECS);
+    match lp_block_map::find_duplicate(block, since_ts) {
+        Some(duplicate_of) => Err(LPLedgerError::Duplicate {
+            duplicate_of: Nat::from(duplicate_of),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// ICRC-1 transfer of an LP token from an account. a transfer to the minting account burns the LP tokens
+/// returns the block index of the transfer
+pub fn transfer(lp_token: &LPToken, from: &Account, args: &TransferArg, ts: u64) -> Result<u64, LPLedgerError> {
+    check_fee(&args.fee)?;
+    check_created_at_time(args.created_at_time, ts)?;
+    let minting_account = minting_account();
+    if *from == minting_account {
+        Err("Minting account cannot transfer LP tokens")?
+    }
+    let is_burn = args.to == minting_account;
+
+    let block = StableLPBlock {
+        from: Some(account_principal_id(from)?),
+        to: if is_burn { None } else { Some(account_principal_id(&args.to)?) },
+        memo: args.memo.clone().map(|memo| memo.0.into_vec()),
+        created_at_time: args.created_at_time,
+        ..StableLPBlock::new(
+            lp_token.token_id,
+            if is_burn { LPBlockOp::Burn } else { LPBlockOp::Transfer },
+            &args.amount,
+            ts,
+        )
+    };
+    check_duplicate(&block, ts)?;
+
+    let from_user_id = account_user_id(from)?;
+    let balance = balance_of(lp_token.token_id, from_user_id);
+    let Some(from_user_id) = from_user_id.filter(|_| balance >= args.amount) else {
+        Err(LPLedgerError::InsufficientFunds { balance })?
+    };
+    match &block.to {
+        Some(to_principal_id) => {
+            let to_user_id = user_map::insert_by_principal_id(to_principal_id)?;
+            transfer_by_user_id(lp_token.token_id, from_user_id, to_user_id, &args.amount, ts)?;
+        }
+        None => {
+            burn_by_user_id(lp_token.token_id, from_user_id, &args.amount, ts)?;
+        }
+    }
+
+    Ok(lp_block_map::insert(&block))
+}
+
+/// ICRC-2 approve of spender to transfer the LP token of an account. returns the block index of the approval
+pub fn approve(lp_token: &LPToken, from: &Account, args: &ApproveArgs, ts: u64) -> Result<u64, LPLedgerError> {
+    check_fee(&args.fee)?;
+    check_created_at_time(args.created_at_time, ts)?;
+    if args.spender == *from {
+        Err("Cannot approve own account as spender")?
+    }
+    if args.expires_at.is_some_and(|expires_at| expires_at <= ts) {
+        Err(LPLedgerError::Expired { ledger_time: ts })?
+    }
+
+    let block = StableLPBlock {
+        from: Some(account_principal_id(from)?),
+        spender: Some(account_principal_id(&args.spender)?),
+        expected_allowance: args.expected_allowance.clone(),
+        expires_at: args.expires_at,
+        memo: args.memo.clone().map(|memo| memo.0.into_vec()),
+        created_at_time: args.created_at_time,
+        ..StableLPBlock::new(lp_token.token_id, LPBlockOp::Approve, &args.amount, ts)
+    };
+    check_duplicate(&block, ts)?;
+
+    // make sure both accounts are registered, if not create new users
+    let owner_user_id = user_map::insert_by_principal_id(&account_principal_id(from)?)?;
+    let spender_user_id = user_map::insert_by_principal_id(&account_principal_id(&args.spender)?)?;
+
+    if let Some(expected_allowance) = &args.expected_allowance {
+        let current_allowance = lp_allowance_map::get(lp_token.token_id, owner_user_id, spender_user_id, ts)
+            .map_or_else(nat_zero, |allowance| allowance.amount);
+        if current_allowance != *expected_allowance {
+            Err(LPLedgerError::AllowanceChanged { current_allowance })?
+        }
+    }
+
+    lp_allowance_map::update(&StableLPAllowance {
+        token_id: lp_token.token_id,
+        owner_user_id,
+        spender_user_id,
+        amount: args.amount.clone(),
+        expires_at: args.expires_at,
+        ts,
+    });
+
+    Ok(lp_block_map::insert(&block))
+}
+
+/// ICRC-2 transfer of an LP token by a spender account. a transfer to the minting account burns the LP tokens
+/// returns the block index of the transfer
+pub fn transfer_from(lp_token: &LPToken, spender: &Account, args: &TransferFromArgs, ts: u64) -> Result<u64, LPLedgerError> {
+    check_fee(&args.fee)?;
+    check_created_at_time(args.created_at_time, ts)?;
+    let minting_account = minting_account();
+    if args.from == minting_account {
+        Err("Minting account cannot transfer LP tokens")?
+    }
+    let is_burn = args.to == minting_account;
+
+    let block = StableLPBlock {
+        from: Some(account_principal_id(&args.from)?),
+        to: if is_burn { None } else { Some(account_principal_id(&args.to)?) },
+        spender: Some(account_principal_id(spender)?),
+        memo: args.memo.clone().map(|memo| memo.0.into_vec()),
+        created_at_time: args.created_at_time,
+        ..StableLPBlock::new(
+            lp_token.token_id,
+            if is_burn { LPBlockOp::Burn } else { LPBlockOp::Transfer },
+            &args.amount,
+            ts,
+        )
+    };
+    check_duplicate(&block, ts)?;
+
+    let allowance = match (account_user_id(&args.from)?, account_user_id(spender)?) {
+        (Some(from_user_id), Some(spender_user_id)) => lp_allowance_map::get(lp_token.token_id, from_user_id, spender_user_id, ts),
+        _ => None,
+    };
+    let allowance = match allowance {
+        Some(allowance) if allowance.amount >= args.amount => allowance,
+        Some(allowance) => Err(LPLedgerError::InsufficientAllowance {
+            allowance: allowance.amount,
+        })?,
+        None => Err(LPLedgerError::InsufficientAllowance { allowance: nat_zero() })?,
+    };
+    let balance = balance_of(lp_token.token_id, Some(allowance.owner_user_id));
+    if balance < args.amount {
+        Err(LPLedgerError::InsufficientFunds { balance })?
+    }
+    match &block.to {
+        Some(to_principal_id) => {
+            let to_user_id = user_map::insert_by_principal_id(to_principal_id)?;
+            transfer_by_user_id(lp_token.token_id, allowance.owner_user_id, to_user_id, &args.amount, ts)?;
+        }
+        None => {
+            burn_by_user_id(lp_token.token_id, allowance.owner_user_id, &args.amount, ts)?;
+        }
+    }
+    lp_allowance_map::update(&StableLPAllowance {
+        amount: nat_subtract(&allowance.amount, &args.amount).unwrap_or(nat_zero()),
+        ts,
+        ..allowance
+    });
+
+    Ok(lp_block_map::insert(&block))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use icrc_ledger_types::icrc::generic_value::ICRC3Value;
+    use icrc_ledger_types::icrc1::transfer::Memo;
+    use serde_bytes::ByteBuf;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_pool, insert_user_by_principal_id};
+    use crate::stable_lp_token::stable_lp_token::StableLPToken;
+    use crate::stable_memory::POOL_MAP;
+    use crate::stable_pool::stable_pool::{StablePool, StablePoolId};
+
+    const LP_TOKEN_ID: u32 = 3;
+    const TS: u64 = 10 * TX_WINDOW_NANOSECS;
+
+    fn account(owner: u8, subaccount: Option<u8>) -> Account {
+        Account {
+            owner: Principal::from_slice(&[owner]),
+            subaccount: subaccount.map(|subaccount| [subaccount; 32]),
+        }
+    }
+
+    // ckBTC/ckUSDT pool with its LP token
+    fn setup() -> LPToken {
+        init_kong_settings();
+        let pool = insert_pool(1, 1, 100_000, 2, 6_000_000_000);
+        POOL_MAP.with(|m| {
+            m.borrow_mut().insert(
+                StablePoolId(1),
+                StablePool {
+                    lp_token_id: LP_TOKEN_ID,
+                    ..pool
+                },
+            )
+        });
+        LPToken {
+            token_id: LP_TOKEN_ID,
+            symbol: "ckBTC_ckUSDT".to_string(),
+            address: "1_2".to_string(),
+            decimals: 8,
+            is_removed: false,
+            ledger_id: None,
+        }
+    }
+
+    // registers the holder of an account, with an LP token balance if amount > 0
+    fn register(user_id: u32, account: &Account, amount: u64) {
+        insert_user_by_principal_id(user_id, &account.to_string());
+        if amount > 0 {
+            lp_token_map::insert(&StableLPToken::new(user_id, LP_TOKEN_ID, Nat::from(amount), TS)).unwrap();
+        }
+    }
+
+    fn balance(account: &Account) -> Nat {
+        balance_of(LP_TOKEN_ID, account_user_id(account).unwrap())
+    }
+
+    fn transfer_arg(to: &Account, amount: u64, created_at_time: Option<u64>) -> TransferArg {
+        TransferArg {
+            from_subaccount: None,
+            to: *to,
+            fee: None,
+            created_at_time,
+            memo: None,
+            amount: Nat::from(amount),
+        }
+    }
+
+    #[test]
+    fn test_transfer_from_subaccount() {
+        let lp_token = setup();
+        let (alice, alice_sub, bob) = (account(1, None), account(1, Some(7)), account(2, None));
+        register(100, &alice, 0);
+        register(101, &alice_sub, 1_000);
+        register(102, &bob, 0);
+
+        assert_eq!(transfer(&lp_token, &alice_sub, &transfer_arg(&bob, 400, None), TS), Ok(0));
+        assert_eq!(balance(&alice_sub), Nat::from(600_u64));
+        assert_eq!(balance(&alice), Nat::from(0_u64));
+        assert_eq!(balance(&bob), Nat::from(400_u64));
+        let block = &lp_block_map::get_by_range(LP_TOKEN_ID, 0, 1)[0];
+        assert_eq!(block.from, Some(alice_sub.to_string()));
+        assert_eq!(block.to, Some(bob.owner.to_text()));
+
+        // the default subaccount holds nothing
+        assert_eq!(
+            transfer(&lp_token, &alice, &transfer_arg(&bob, 1, None), TS),
+            Err(LPLedgerError::InsufficientFunds { balance: Nat::from(0_u64) })
+        );
+    }
+
+    #[test]
+    fn test_transfer_created_at_time_window() {
+        let lp_token = setup();
+        let (alice, bob) = (account(1, None), account(2, None));
+        register(100, &alice, 1_000);
+        register(101, &bob, 0);
+
+        let too_old = TS - TX_WINDOW_NANOSECS - PERMITTED_DRIFT_NANOSECS - 1;
+        assert_eq!(
+            transfer(&lp_token, &alice, &transfer_arg(&bob, 1, Some(too_old)), TS),
+            Err(LPLedgerError::TooOld)
+        );
+        let in_future = TS + PERMITTED_DRIFT_NANOSECS + 1;
+        assert_eq!(
+            transfer(&lp_token, &alice, &transfer_arg(&bob, 1, Some(in_future)), TS),
+            Err(LPLedgerError::CreatedInFuture { ledger_time: TS })
+        );
+        assert_eq!(balance(&alice), Nat::from(1_000_u64));
+        assert_eq!(lp_block_map::get_log_length(LP_TOKEN_ID), 0);
+
+        // oldest and latest created_at_time accepted
+        assert_eq!(transfer(&lp_token, &alice, &transfer_arg(&bob, 1, Some(too_old + 1)), TS), Ok(0));
+        assert_eq!(transfer(&lp_token, &alice, &transfer_arg(&bob, 1, Some(in_future - 1)), TS), Ok(1));
+    }
+
+    #[test]
+    fn test_transfer_duplicate() {
+        let lp_token = setup();
+        let (alice, bob) = (account(1, None), account(2, None));
+        register(100, &alice, 1_000);
+        register(101, &bob, 0);
+
+        let args = TransferArg {
+            memo: Some(Memo::from(42_u64)),
+            ..transfer_arg(&bob, 100, Some(TS))
+        };
+        assert_eq!(transfer(&lp_token, &alice, &args, TS), Ok(0));
+        assert_eq!(
+            transfer(&lp_token, &alice, &args, TS + 1),
+            Err(LPLedgerError::Duplicate {
+                duplicate_of: Nat::from(0_u64)
+            })
+        );
+        assert_eq!(balance(&bob), Nat::from(100_u64));
+
+        // a different memo or created_at_time is a new transaction
+        let other_memo = TransferArg {
+            memo: Some(Memo::from(43_u64)),
+            ..args.clone()
+        };
+        assert_eq!(transfer(&lp_token, &alice, &other_memo, TS + 1), Ok(1));
+        let other_time = TransferArg {
+            created_at_time: Some(TS + 1),
+            ..args.clone()
+        };
+        assert_eq!(transfer(&lp_token, &alice, &other_time, TS + 1), Ok(2));
+
+        // transactions without created_at_time are not deduplicated
+        let no_time = transfer_arg(&bob, 100, None);
+        assert_eq!(transfer(&lp_token, &alice, &no_time, TS + 1), Ok(3));
+        assert_eq!(transfer(&lp_token, &alice, &no_time, TS + 1), Ok(4));
+        assert_eq!(balance(&bob), Nat::from(500_u64));
+    }
+
+    #[test]
+    fn test_transfer_to_minting_account_burns() {
+        let lp_token = setup();
+        let alice = account(1, Some(1));
+        register(100, &alice, 1_000);
+        register(101, &account(2, None), 500);
+
+        assert_eq!(transfer(&lp_token, &alice, &transfer_arg(&minting_account(), 300, None), TS), Ok(0));
+        assert_eq!(balance(&alice), Nat::from(700_u64));
+        assert_eq!(lp_token_map::get_total_supply(LP_TOKEN_ID), Nat::from(1_200_u64));
+        let block = &lp_block_map::get_by_range(LP_TOKEN_ID, 0, 1)[0];
+        assert_eq!(block.btype(), "1burn");
+        assert_eq!(block.to, None);
+
+        // LP tokens are only minted by add_pool and add_liquidity
+        assert!(matches!(
+            transfer(&lp_token, &minting_account(), &transfer_arg(&alice, 1, None), TS),
+            Err(LPLedgerError::GenericError(_))
+        ));
+    }
+
+    #[test]
+    fn test_approve_and_transfer_from_subaccounts() {
+        let lp_token = setup();
+        let (alice_sub, bob, bob_sub, carol) = (account(1, Some(1)), account(2, None), account(2, Some(2)), account(3, None));
+        register(100, &alice_sub, 1_000);
+        register(101, &bob, 0);
+        register(102, &bob_sub, 0);
+        register(103, &carol, 0);
+
+        let approve_args = ApproveArgs {
+            from_subaccount: alice_sub.subaccount,
+            spender: bob_sub,
+            amount: Nat::from(500_u64),
+            expected_allowance: None,
+            expires_at: None,
+            fee: None,
+            memo: None,
+            created_at_time: Some(TS),
+        };
+        assert_eq!(approve(&lp_token, &alice_sub, &approve_args, TS), Ok(0));
+        assert_eq!(
+            approve(&lp_token, &alice_sub, &approve_args, TS),
+            Err(LPLedgerError::Duplicate {
+                duplicate_of: Nat::from(0_u64)
+            })
+        );
+
+        let transfer_from_args = TransferFromArgs {
+            spender_subaccount: bob_sub.subaccount,
+            from: alice_sub,
+            to: carol,
+            amount: Nat::from(300_u64),
+            fee: None,
+            memo: None,
+            created_at_time: None,
+        };
+        assert_eq!(transfer_from(&lp_token, &bob_sub, &transfer_from_args, TS), Ok(1));
+        assert_eq!(balance(&alice_sub), Nat::from(700_u64));
+        assert_eq!(balance(&carol), Nat::from(300_u64));
+        assert_eq!(
+            transfer_from(&lp_token, &bob_sub, &transfer_from_args, TS),
+            Err(LPLedgerError::InsufficientAllowance {
+                allowance: Nat::from(200_u64)
+            })
+        );
+        // the allowance is held by the spender's subaccount only
+        assert_eq!(
+            transfer_from(&lp_token, &bob, &transfer_from_args, TS),
+            Err(LPLedgerError::InsufficientAllowance { allowance: nat_zero() })
+        );
+        let block = &lp_block_map::get_by_range(LP_TOKEN_ID, 1, 1)[0];
+        assert_eq!(block.btype(), "2xfer");
+        assert_eq!(block.spender, Some(bob_sub.to_string()));
+    }
+
+    #[test]
+    fn test_icrc3_block_encodes_subaccounts() {
+        let lp_token = setup();
+        let (alice_sub, bob) = (account(1, Some(9)), account(2, None));
+        register(100, &alice_sub, 1_000);
+        register(101, &bob, 0);
+
+        transfer(&lp_token, &alice_sub, &transfer_arg(&bob, 100, Some(TS)), TS).unwrap();
+        let ICRC3Value::Map(block) = lp_block_map::get_by_range(LP_TOKEN_ID, 0, 1)[0].to_icrc3_value() else {
+            panic!("block is not a map");
+        };
+        let Some(ICRC3Value::Map(tx)) = block.get("tx") else {
+            panic!("tx is not a map");
+        };
+        let blob = |bytes: &[u8]| ICRC3Value::Blob(ByteBuf::from(bytes.to_vec()));
+        assert_eq!(tx.get("from"), Some(&ICRC3Value::Array(vec![blob(&[1]), blob(&[9; 32])])));
+        assert_eq!(tx.get("to"), Some(&ICRC3Value::Array(vec![blob(&[2])])));
+        assert_eq!(tx.get("ts"), Some(&ICRC3Value::Nat(Nat::from(TS))));
+    }
+}
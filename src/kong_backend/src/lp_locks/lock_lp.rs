@@ -0,0 +1,89 @@
+use candid::Nat;
+use ic_cdk::update;
+
+use crate::campaigns::campaign_rewards::update_lp_rewards;
+use crate::helpers::nat_helpers::{nat_is_zero, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_kong_settings::lp_lock_boost::lock_boost_bps;
+use crate::stable_lp_token::stable_lp_lock::StableLPLock;
+use crate::stable_lp_token::{lp_lock_map, lp_token_map};
+use crate::stable_pool::pool_map;
+use crate::stable_user::user_map;
+
+use super::lp_lock_reply::LPLockReply;
+
+/// Lock amount of the caller's LP tokens of a pool until unlock_ts
+/// locked LP tokens stay in the caller's LP token balance but can not be sent, transferred or removed from the pool
+/// until unlock_ts. locks earn a boost in LP incentive campaigns according to the lock boosts in Kong settings
+#[update(guard = "not_in_maintenance_mode")]
+fn lock_lp(pool: String, amount: Nat, unlock_ts: u64) -> Result<LPLockReply, String> {
+    let user_id = user_map::get_by_caller().ok().flatten().ok_or("Insufficient LP balance")?.user_id;
+    let lock = lock_user_lp(user_id, &pool, &amount, unlock_ts, ICNetwork::get_time())?;
+    Ok(LPLockReply::from(&lock))
+}
+
+fn lock_user_lp(user_id: u32, pool: &str, amount: &Nat, unlock_ts: u64, ts: u64) -> Result<StableLPLock, String> {
+    let pool = pool_map::get_by_token(pool)?;
+    if pool.is_removed {
+        Err("Pool is removed".to_string())?;
+    }
+    if pool.is_concentrated() {
+        Err("Concentrated liquidity pools do not have LP tokens".to_string())?;
+    }
+    if nat_is_zero(amount) {
+        Err("Lock amount is zero".to_string())?;
+    }
+    if unlock_ts <= ts {
+        Err("Unlock time must be in the future".to_string())?;
+    }
+
+    let lp_token_id = pool.lp_token_id;
+    let balance = lp_token_map::get_by_token_id_by_user_id(lp_token_id, user_id).map_or(nat_zero(), |lp_token| lp_token.amount);
+    if *amount > balance {
+        Err("Insufficient LP balance".to_string())?;
+    }
+    lp_lock_map::check_unlocked(user_id, lp_token_id, amount, ts)?;
+
+    let lock_secs = (unlock_ts - ts) / 1_000_000_000;
+    let boost_bps = lock_boost_bps(&kong_settings_map::get().lp_lock_boosts, lock_secs);
+    if boost_bps > 0 {
+        // campaign rewards are earned with the weights before the lock's boost is added
+        update_lp_rewards(user_id, lp_token_id);
+    }
+
+    let lock = StableLPLock::new(user_id, pool.pool_id, lp_token_id, amount, unlock_ts, boost_bps, ts);
+    let lock_id = lp_lock_map::insert(&lock);
+    lp_lock_map::get_by_lock_id(lock_id).ok_or("LP lock not found".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_ic_token, insert_pool};
+    use crate::stable_lp_token::stable_lp_token::StableLPToken;
+
+    #[test]
+    fn test_lock_lp_locks_unlocked_lp_tokens() {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckUSDT", 6);
+        init_kong_settings();
+        let pool = insert_pool(1, 1, 1_000, 2, 1_000);
+        lp_token_map::insert(&StableLPToken::new(1, pool.lp_token_id, Nat::from(100_u32), 0)).unwrap();
+        let lock = |amount: u32, unlock_ts: u64| lock_user_lp(1, "ICP_ckUSDT", &Nat::from(amount), unlock_ts, 50);
+
+        assert_eq!(lock(60, 100).unwrap().amount, Nat::from(60_u32));
+        // locked LP tokens can not be locked again or sent before unlock_ts
+        assert!(lock(41, 100).is_err());
+        assert!(lp_lock_map::check_unlocked(1, pool.lp_token_id, &Nat::from(41_u32), 50).is_err());
+        assert!(lock(40, 200).is_ok());
+        assert!(lp_lock_map::check_unlocked(1, pool.lp_token_id, &Nat::from(1_u32), 50).is_err());
+        assert!(lock(1, 50).is_err());
+
+        // the first lock is unlocked at its unlock_ts
+        assert!(lp_lock_map::check_unlocked(1, pool.lp_token_id, &Nat::from(60_u32), 100).is_ok());
+        assert!(lp_lock_map::check_unlocked(1, pool.lp_token_id, &Nat::from(61_u32), 100).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::ic::network::ICNetwork;
+use crate::stable_lp_token::stable_lp_lock::StableLPLock;
+use crate::stable_pool::pool_map;
+use crate::stable_token::token::Token;
+use crate::stable_user::user_map;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct LPLockReply {
+    pub lock_id: u64,
+    pub principal_id: String,
+    pub symbol: String,
+    pub lp_token_symbol: String,
+    pub amount: Nat,
+    pub unlock_ts: u64,
+    pub boost_bps: u16, // boost to the locked LP tokens in LP incentive campaigns until unlock_ts
+    pub is_locked: bool,
+    pub ts: u64,
+}
+
+impl From<&StableLPLock> for LPLockReply {
+    fn from(lock: &StableLPLock) -> Self {
+        let (symbol, lp_token_symbol) = match pool_map::get_by_pool_id(lock.pool_id) {
+            Some(pool) => (pool.symbol(), pool.lp_token().symbol()),
+            None => ("Pool not found".to_string(), "LP token not found".to_string()),
+        };
+        LPLockReply {
+            lock_id: lock.lock_id,
+            principal_id: user_map::get_by_user_id(lock.user_id).map_or_else(|| "User not found".to_string(), |user| user.principal_id),
+            symbol,
+            lp_token_symbol,
+            amount: lock.amount.clone(),
+            unlock_ts: lock.unlock_ts,
+            boost_bps: lock.boost_bps,
+            is_locked: lock.is_locked(ICNetwork::get_time()),
+            ts: lock.ts,
+        }
+    }
+}
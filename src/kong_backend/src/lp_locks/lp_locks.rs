@@ -0,0 +1,44 @@
+use ic_cdk::query;
+
+use crate::helpers::nat_helpers::{nat_add, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_lp_token::{lp_lock_map, lp_token_map};
+use crate::stable_pool::pool_map;
+use crate::stable_token::token::Token;
+use crate::stable_user::user_map;
+
+use super::lp_lock_reply::LPLockReply;
+use super::lp_locks_reply::LPLocksReply;
+
+/// Return the LP locks of a pool, latest first, with the LP tokens locked now
+#[query(guard = "not_in_maintenance_mode")]
+fn lp_locks(pool: String) -> Result<LPLocksReply, String> {
+    let pool = pool_map::get_by_token(&pool)?;
+    let ts = ICNetwork::get_time();
+    let locks = lp_lock_map::get_by_pool_id(pool.pool_id);
+    let locked = locks
+        .iter()
+        .filter(|lock| lock.is_locked(ts))
+        .fold(nat_zero(), |acc, lock| nat_add(&acc, &lock.amount));
+
+    Ok(LPLocksReply {
+        symbol: pool.symbol(),
+        lp_token_symbol: pool.lp_token().symbol(),
+        lp_token_supply: lp_token_map::get_total_supply(pool.lp_token_id),
+        locked,
+        locks: locks.iter().map(LPLockReply::from).collect(),
+    })
+}
+
+/// Return the LP locks of a user, latest first
+#[query(guard = "not_in_maintenance_mode")]
+fn user_lp_locks(principal_id: String) -> Result<Vec<LPLockReply>, String> {
+    let user_id = user_map::get_by_principal_id(&principal_id)
+        .ok()
+        .flatten()
+        .ok_or("User not found")?
+        .user_id;
+
+    Ok(lp_lock_map::get_by_user_id(user_id).iter().map(LPLockReply::from).collect())
+}
@@ -0,0 +1,13 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use super::lp_lock_reply::LPLockReply;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct LPLocksReply {
+    pub symbol: String,
+    pub lp_token_symbol: String,
+    pub lp_token_supply: Nat,
+    pub locked: Nat, // LP tokens locked now
+    pub locks: Vec<LPLockReply>,
+}
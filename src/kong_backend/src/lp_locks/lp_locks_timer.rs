@@ -0,0 +1,17 @@
+use crate::campaigns::campaign_rewards::update_lp_rewards;
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_lp_token::lp_lock_map;
+
+/// end the boosts of locks whose unlock_ts has passed for users whose LP token balances have not changed since
+pub fn expire_lp_locks() {
+    if not_in_maintenance_mode().is_err() {
+        return;
+    }
+
+    let ts = ICNetwork::get_time();
+    for lock in lp_lock_map::get_expired(ts) {
+        // settles the user's campaign rewards with the boosts and then ends them
+        update_lp_rewards(lock.user_id, lock.lp_token_id);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod lock_lp;
+pub mod lp_lock_reply;
+#[allow(clippy::module_inception)]
+pub mod lp_locks;
+pub mod lp_locks_reply;
+pub mod lp_locks_timer;
@@ -0,0 +1,33 @@
+use ic_cdk::update;
+
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_order::order_map;
+use crate::stable_order::stable_order::OrderStatus;
+use crate::stable_user::user_map;
+
+use super::order_reply::OrderReply;
+use super::return_order::return_order;
+
+/// Cancel an open order and return the pay token
+#[update(guard = "not_in_maintenance_mode")]
+async fn cancel_order(order_id: u64) -> Result<OrderReply, String> {
+    let order = order_map::get_by_order_id(order_id).ok_or("Order not found")?;
+    // make sure the caller is the owner of the order
+    let user_id = user_map::get_by_principal_id(&ICNetwork::caller().to_text())
+        .ok()
+        .flatten()
+        .ok_or("User not found")?
+        .user_id;
+    if order.user_id != user_id {
+        return Err("Order not found".to_string());
+    }
+
+    // set status to Cancelling to prevent the order from being filled or cancelled again
+    let order = order_map::update_open_status(order_id, OrderStatus::Cancelling).ok_or("Order is not open")?;
+
+    let ts = ICNetwork::get_time();
+    let (_, reply) = return_order(&order, OrderStatus::Cancelled, ts).await?;
+
+    Ok(reply)
+}
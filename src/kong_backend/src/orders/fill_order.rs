@@ -0,0 +1,466 @@
+use candid::Nat;
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply_f64, nat_subtract, nat_to_decimal_precision};
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_order::order_map;
+use crate::stable_order::stable_order::{OrderStatus, OrderType, StableOrder};
+use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
+use crate::stable_user::user_map;
+use crate::swap::archive_to_kong_data::archive_to_kong_data;
+use crate::swap::calculate_amounts::calculate_amounts;
+use crate::swap::send_receive_token::send_receive_token;
+use crate::swap::swap_amounts::swap_mid_price;
+use crate::swap::update_liquidity_pool::update_liquidity_pool;
+
+use super::order_reply::OrderReply;
+
+// slippage is not checked for limit orders as the limit price sets the minimum receive amount
+const LIMIT_ORDER_MAX_SLIPPAGE: f64 = 100.0;
+
+/// Minimum receive amount of a limit order
+/// limit orders must receive at least pay_amount * limit_price
+fn min_receive_amount(pay_amount: &Nat, limit_price: f64, pay_token: &StableToken, receive_token: &StableToken) -> Option<Nat> {
+    let receive_amount_pay_token_decimal = nat_multiply_f64(pay_amount, limit_price)?;
+    Some(nat_to_decimal_precision(
+        &receive_amount_pay_token_decimal,
+        pay_token.decimals(),
+        receive_token.decimals(),
+    ))
+}
+
+/// Pay amount of the next slice of a TWAP order. the unfilled pay amount divided by the slices left, so the last
+/// slice swaps whatever is left. None if the order is not a TWAP order or the slice rounds to 0
+fn twap_slice_pay_amount(order: &StableOrder) -> Option<Nat> {
+    let OrderType::Twap {
+        num_slices, slices_filled, ..
+    } = order.order_type
+    else {
+        return None;
+    };
+    let unfilled_pay_amount = nat_subtract(&order.pay_amount, &order.filled_pay_amount)?;
+    let slices_left = num_slices.saturating_sub(slices_filled).max(1);
+    nat_divide(&unfilled_pay_amount, &Nat::from(slices_left)).filter(|slice_pay_amount| !nat_is_zero(slice_pay_amount))
+}
+
+/// Pay amount, minimum receive amount and max slippage of the next fill of an order
+/// limit orders are filled in full, TWAP orders in equal slices of the unfilled pay amount
+fn fill_amounts(order: &StableOrder, pay_token: &StableToken, receive_token: &StableToken) -> Option<(Nat, Option<Nat>, f64)> {
+    match order.order_type {
+        OrderType::Limit { limit_price } => {
+            let unfilled_pay_amount = nat_subtract(&order.pay_amount, &order.filled_pay_amount)?;
+            let min_receive_amount = min_receive_amount(&unfilled_pay_amount, limit_price, pay_token, receive_token)?;
+            Some((unfilled_pay_amount, Some(min_receive_amount), LIMIT_ORDER_MAX_SLIPPAGE))
+        }
+        OrderType::Twap { max_slippage, .. } => Some((twap_slice_pay_amount(order)?, None, max_slippage)),
+    }
+}
+
+/// Check if a TWAP order can not be filled anymore and must be returned to the user
+/// either the next slice rounds to 0 or max_twap_failed_slices (kong settings) slices failed in a row
+pub fn is_exhausted(order: &StableOrder) -> bool {
+    match order.order_type {
+        OrderType::Limit { .. } => false,
+        OrderType::Twap { failed_slices, .. } => {
+            failed_slices >= kong_settings_map::get().max_twap_failed_slices || twap_slice_pay_amount(order).is_none()
+        }
+    }
+}
+
+/// Check if the next slice of a TWAP order is due
+pub fn is_slice_due(order: &StableOrder, ts: u64) -> bool {
+    matches!(order.order_type, OrderType::Twap { next_slice_at, .. } if ts >= next_slice_at)
+}
+
+/// Check if an open order can be filled at the current pool prices
+pub fn is_fillable(order: &StableOrder, ts: u64) -> bool {
+    let Some(pay_token) = token_map::get_by_token_id(order.pay_token_id) else {
+        return false;
+    };
+    let Some(receive_token) = token_map::get_by_token_id(order.receive_token_id) else {
+        return false;
+    };
+    if pay_token.is_removed() || receive_token.is_removed() {
+        return false;
+    }
+
+    match order.order_type {
+        // quick check with the mid price of the pools
+        OrderType::Limit { limit_price } => match swap_mid_price(&pay_token, &receive_token) {
+            Ok(mid_price) if mid_price >= limit_price => (),
+            _ => return false,
+        },
+        // slices are executed at fixed intervals
+        OrderType::Twap { next_slice_at, .. } => {
+            if ts < next_slice_at {
+                return false;
+            }
+        }
+    }
+
+    // check the swap fills at the minimum receive amount and max slippage including fees and gas
+    let Some((pay_amount, min_receive_amount, max_slippage)) = fill_amounts(order, &pay_token, &receive_token) else {
+        return false;
+    };
+    calculate_amounts(
+        &pay_token,
+        &pay_amount,
+        &receive_token,
+        min_receive_amount.as_ref(),
+        max_slippage,
+        None,
+        false,
+        user_map::get_fee_level(order.user_id),
+    )
+    .is_ok()
+}
+
+/// Re-open an order after a failed fill
+/// TWAP orders skip the slice. the next slice is scheduled and the failed slice is counted
+pub fn reopen_order(order_id: u64, ts: u64) {
+    if let Some(mut order) = order_map::get_by_order_id(order_id) {
+        order.status = OrderStatus::Open;
+        if let OrderType::Twap {
+            interval_secs,
+            ref mut next_slice_at,
+            ref mut failed_slices,
+            ..
+        } = order.order_type
+        {
+            *next_slice_at = ts + interval_secs * 1_000_000_000;
+            *failed_slices += 1;
+        }
+        order_map::update(&order);
+    }
+}
+
+/// Status of an order after a successful fill
+/// limit orders are filled, TWAP orders are re-opened for the next slice until the last slice is filled
+fn close_fill(order: &mut StableOrder, ts: u64) -> StatusCode {
+    match order.order_type {
+        OrderType::Limit { .. } => {
+            order.status = OrderStatus::Filled;
+            StatusCode::OrderFilled
+        }
+        OrderType::Twap {
+            num_slices,
+            interval_secs,
+            ref mut slices_filled,
+            ref mut next_slice_at,
+            ref mut failed_slices,
+            ..
+        } => {
+            *slices_filled += 1;
+            *failed_slices = 0;
+            if *slices_filled >= num_slices {
+                order.status = OrderStatus::Filled;
+                StatusCode::OrderFilled
+            } else {
+                order.status = OrderStatus::Open;
+                *next_slice_at = ts + interval_secs * 1_000_000_000;
+                StatusCode::OrderSliceFilled
+            }
+        }
+    }
+}
+
+/// Fill an open order by swapping the pay token held in custody through the pools
+/// a new request is created for each fill and the receive token is sent to the order's to_address
+/// if the swap fails, the order is re-opened
+/// the order leaves Filling before the receive token is sent, so a trap in the send can not leave it in Filling
+pub async fn fill_order(order_id: u64, ts: u64) -> Result<OrderReply, String> {
+    // set status to Filling to prevent the order from being filled or cancelled again
+    let mut order = order_map::update_open_status(order_id, OrderStatus::Filling).ok_or("Order is not open")?;
+    let (pay_token, receive_token) = match (
+        token_map::get_by_token_id(order.pay_token_id),
+        token_map::get_by_token_id(order.receive_token_id),
+    ) {
+        (Some(pay_token), Some(receive_token)) => (pay_token, receive_token),
+        _ => {
+            order_map::update_status(order_id, OrderStatus::Open);
+            Err("Order tokens not found")?
+        }
+    };
+
+    let request_id = request_map::insert(&StableRequest::new(order.user_id, &Request::FillOrder(order_id), ts));
+    request_map::update_status(request_id, StatusCode::FillOrder, Some(&format!("Order #{}", order_id)));
+
+    let Some((pay_amount, min_receive_amount, max_slippage)) = fill_amounts(&order, &pay_token, &receive_token) else {
+        reopen_order(order_id, ts);
+        request_map::update_status(request_id, StatusCode::Failed, None);
+        Err(format!("Req #{} failed. Invalid fill amounts", request_id))?
+    };
+    let (receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps) = match update_liquidity_pool(
+        request_id,
+        &pay_token,
+        &pay_amount,
+        &receive_token,
+        min_receive_amount.as_ref(),
+        max_slippage,
+        None,
+        false,
+        user_map::get_fee_level(order.user_id),
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            // price has moved, re-open the order
+            reopen_order(order_id, ts);
+            request_map::update_status(request_id, StatusCode::Failed, None);
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    };
+    request_map::update_status(request_id, StatusCode::SwapSuccess, None);
+
+    // close the fill before the receive token is sent
+    order.filled_pay_amount = nat_add(&order.filled_pay_amount, &pay_amount);
+    order.receive_amount = nat_add(&order.receive_amount, &receive_amount_with_fees_and_gas);
+    order.fill_request_ids.push(request_id);
+    let status_code = close_fill(&mut order, ts);
+    order_map::update(&order);
+
+    let mut transfer_ids = Vec::new();
+    let swap_reply = send_receive_token(
+        request_id,
+        order.user_id,
+        &pay_token,
+        &pay_amount,
+        &receive_token,
+        &receive_amount_with_fees_and_gas,
+        &order.to_address,
+        &mut transfer_ids,
+        &mut Vec::new(),
+        None,
+        mid_price,
+        price,
+        slippage,
+        &swaps,
+        ts,
+    )
+    .await;
+
+    // refresh order with the latest state
+    let mut order = order_map::get_by_order_id(order_id).ok_or("Order not found")?;
+    order.tx_ids.push(swap_reply.tx_id);
+    order_map::update(&order);
+
+    request_map::update_status(request_id, status_code, None);
+    request_map::update_status(request_id, StatusCode::Success, None);
+    let _ = archive_to_kong_data(request_id);
+
+    let reply = OrderReply::from(&order);
+    // update the order placement request if it has not been archived yet
+    request_map::update_reply(order.request_id, Reply::Order(reply.clone()));
+
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    use crate::helpers::test_helpers::{ic_token, insert_ic_token, insert_pool, insert_token};
+    use crate::ic::address::Address;
+    use crate::stable_token::ic_token::ICToken;
+
+    const CKUSDT: u32 = 1;
+    const ICP: u32 = 2;
+
+    // ICP/ckUSDT pool at 10 ckUSDT per ICP
+    fn insert_icp_ckusdt_pool() -> (StableToken, StableToken) {
+        let icp = insert_ic_token(ICP, "ICP", 8);
+        let ckusdt = insert_ic_token(CKUSDT, "ckUSDT", 6);
+        insert_pool(1, ICP, 1_000_000_000_000, CKUSDT, 100_000_000_000); // 10_000 ICP, 100_000 ckUSDT
+        (icp, ckusdt)
+    }
+
+    fn limit_order(pay_amount: u64, limit_price: f64) -> StableOrder {
+        StableOrder::new(
+            100,
+            1,
+            OrderType::Limit { limit_price },
+            ICP,
+            &Nat::from(pay_amount),
+            CKUSDT,
+            &Address::PrincipalId(Principal::anonymous().into()),
+            None,
+            0,
+        )
+    }
+
+    fn twap_order(pay_amount: u64, num_slices: u32, next_slice_at: u64) -> StableOrder {
+        StableOrder::new(
+            100,
+            1,
+            OrderType::Twap {
+                num_slices,
+                interval_secs: 60,
+                max_slippage: 2.0,
+                slices_filled: 0,
+                next_slice_at,
+                failed_slices: 0,
+            },
+            ICP,
+            &Nat::from(pay_amount),
+            CKUSDT,
+            &Address::PrincipalId(Principal::anonymous().into()),
+            None,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_fill_amounts_limit_order() {
+        let (icp, ckusdt) = (ic_token(ICP, "ICP", 8), ic_token(CKUSDT, "ckUSDT", 6));
+        let mut order = limit_order(300_000_000, 9.5);
+        order.filled_pay_amount = Nat::from(100_000_000_u64);
+        let (pay_amount, min_receive_amount, max_slippage) = fill_amounts(&order, &icp, &ckusdt).unwrap();
+        // the unfilled 2 ICP at 9.5 ckUSDT per ICP
+        assert_eq!(pay_amount, Nat::from(200_000_000_u64));
+        assert_eq!(min_receive_amount, Some(Nat::from(19_000_000_u64)));
+        assert_eq!(max_slippage, LIMIT_ORDER_MAX_SLIPPAGE);
+    }
+
+    #[test]
+    fn test_fill_amounts_twap_order() {
+        let (icp, ckusdt) = (ic_token(ICP, "ICP", 8), ic_token(CKUSDT, "ckUSDT", 6));
+        let mut order = twap_order(1_000, 3, 0);
+        let (pay_amount, min_receive_amount, max_slippage) = fill_amounts(&order, &icp, &ckusdt).unwrap();
+        assert_eq!(pay_amount, Nat::from(333_u64));
+        assert_eq!(min_receive_amount, None);
+        assert_eq!(max_slippage, 2.0);
+
+        // last slice swaps whatever is left
+        order.filled_pay_amount = Nat::from(666_u64);
+        if let OrderType::Twap { ref mut slices_filled, .. } = order.order_type {
+            *slices_filled = 2;
+        }
+        let (pay_amount, _, _) = fill_amounts(&order, &icp, &ckusdt).unwrap();
+        assert_eq!(pay_amount, Nat::from(334_u64));
+    }
+
+    #[test]
+    fn test_is_fillable_twap_order_waits_for_next_slice() {
+        insert_icp_ckusdt_pool();
+        let order = twap_order(1_000_000_000, 10, 1_000);
+        assert!(!is_fillable(&order, 999));
+    }
+
+    #[test]
+    fn test_is_fillable_token_not_found() {
+        assert!(!is_fillable(&limit_order(100_000_000, 9.0), 0));
+    }
+
+    #[test]
+    fn test_is_fillable_removed_token() {
+        insert_icp_ckusdt_pool();
+        let ckusdt = StableToken::IC(ICToken {
+            is_removed: true,
+            ..match ic_token(CKUSDT, "ckUSDT", 6) {
+                StableToken::IC(token) => token,
+                _ => unreachable!(),
+            }
+        });
+        insert_token(&ckusdt);
+        assert!(!is_fillable(&limit_order(100_000_000, 9.0), 0));
+    }
+
+    #[test]
+    fn test_twap_slice_rounding_to_zero_is_exhausted() {
+        let (icp, ckusdt) = (ic_token(ICP, "ICP", 8), ic_token(CKUSDT, "ckUSDT", 6));
+        // 10 units left for 20 slices
+        let mut order = twap_order(1_010, 100, 0);
+        order.filled_pay_amount = Nat::from(1_000_u64);
+        if let OrderType::Twap { ref mut slices_filled, .. } = order.order_type {
+            *slices_filled = 80;
+        }
+        assert!(fill_amounts(&order, &icp, &ckusdt).is_none());
+        assert!(is_exhausted(&order));
+
+        // fully filled
+        let mut order = twap_order(1_000, 2, 0);
+        order.filled_pay_amount = Nat::from(1_000_u64);
+        assert!(is_exhausted(&order));
+    }
+
+    #[test]
+    fn test_twap_failed_slices_are_capped() {
+        let mut order = twap_order(1_000_000, 10, 0);
+        assert!(!is_exhausted(&order));
+        if let OrderType::Twap { ref mut failed_slices, .. } = order.order_type {
+            *failed_slices = kong_settings_map::get().max_twap_failed_slices - 1;
+        }
+        assert!(!is_exhausted(&order));
+        if let OrderType::Twap { ref mut failed_slices, .. } = order.order_type {
+            *failed_slices = kong_settings_map::get().max_twap_failed_slices;
+        }
+        assert!(is_exhausted(&order));
+    }
+
+    #[test]
+    fn test_limit_order_is_never_exhausted() {
+        let mut order = limit_order(100_000_000, 9.0);
+        order.filled_pay_amount = Nat::from(100_000_000_u64);
+        assert!(!is_exhausted(&order));
+        assert!(!is_slice_due(&order, u64::MAX));
+    }
+
+    #[test]
+    fn test_reopen_order_counts_failed_slice() {
+        let order = StableOrder {
+            order_id: 1,
+            status: OrderStatus::Filling,
+            ..twap_order(1_000_000, 10, 0)
+        };
+        order_map::update(&order);
+        reopen_order(1, 5_000_000_000);
+        let order = order_map::get_by_order_id(1).unwrap();
+        assert_eq!(order.status, OrderStatus::Open);
+        match order.order_type {
+            OrderType::Twap {
+                next_slice_at,
+                failed_slices,
+                ..
+            } => {
+                assert_eq!(next_slice_at, 65_000_000_000);
+                assert_eq!(failed_slices, 1);
+            }
+            _ => panic!("not a TWAP order"),
+        }
+        assert!(!is_slice_due(&order, 64_999_999_999));
+        assert!(is_slice_due(&order, 65_000_000_000));
+    }
+
+    #[test]
+    fn test_close_fill_twap_order() {
+        let mut order = twap_order(1_000, 2, 0);
+        assert!(matches!(close_fill(&mut order, 5_000_000_000), StatusCode::OrderSliceFilled));
+        assert_eq!(order.status, OrderStatus::Open);
+        assert!(matches!(
+            order.order_type,
+            OrderType::Twap {
+                slices_filled: 1,
+                next_slice_at: 65_000_000_000,
+                ..
+            }
+        ));
+        assert!(matches!(close_fill(&mut order, 65_000_000_000), StatusCode::OrderFilled));
+        assert_eq!(order.status, OrderStatus::Filled);
+
+        let mut order = limit_order(100_000_000, 9.0);
+        assert!(matches!(close_fill(&mut order, 0), StatusCode::OrderFilled));
+        assert_eq!(order.status, OrderStatus::Filled);
+    }
+
+    #[test]
+    fn test_fill_order_token_not_found_reopens_order() {
+        insert_ic_token(ICP, "ICP", 8);
+        order_map::update(&StableOrder {
+            order_id: 1,
+            ..limit_order(100_000_000, 9.0)
+        });
+        assert!(futures::executor::block_on(fill_order(1, 0)).is_err());
+        // the order is not left in Filling
+        assert_eq!(order_map::get_by_order_id(1).unwrap().status, OrderStatus::Open);
+    }
+}
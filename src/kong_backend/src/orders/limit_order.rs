@@ -0,0 +1,88 @@
+use candid::Nat;
+use ic_cdk::update;
+
+use crate::helpers::nat_helpers::nat_is_zero;
+use crate::ic::address::Address;
+use crate::ic::address_helpers::get_address;
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_order::stable_order::{OrderType, StableOrder};
+use crate::stable_request::{request::Request, request_map, stable_request::StableRequest};
+use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
+use crate::stable_user::user_map;
+use crate::swap::swap_amounts::swap_mid_price;
+
+use super::limit_order_args::LimitOrderArgs;
+use super::order_reply::OrderReply;
+use super::place_order::place_order;
+
+/// Place a limit order
+/// pay token is taken into custody with icrc2_transfer_from and held until the order is filled, cancelled or expired
+/// open orders are filled by the orders timer when the price is at or above limit_price
+#[update(guard = "not_in_maintenance_mode")]
+pub async fn limit_order(args: LimitOrderArgs) -> Result<OrderReply, String> {
+    let (user_id, pay_token, pay_amount, receive_token, to_address) = check_arguments(&args)?;
+    let ts = ICNetwork::get_time();
+    let request_id = request_map::insert(&StableRequest::new(user_id, &Request::LimitOrder(args.clone()), ts));
+
+    let order = StableOrder::new(
+        user_id,
+        request_id,
+        OrderType::Limit {
+            limit_price: args.limit_price,
+        },
+        pay_token.token_id(),
+        &pay_amount,
+        receive_token.token_id(),
+        &to_address,
+        args.expires_at,
+        ts,
+    );
+
+    place_order(request_id, &order, &pay_token, &pay_amount, ts).await
+}
+
+fn check_arguments(args: &LimitOrderArgs) -> Result<(u32, StableToken, Nat, StableToken, Address), String> {
+    let pay_token = token_map::get_by_token(&args.pay_token)?;
+    if pay_token.is_removed() {
+        Err("Pay token is suspended or removed".to_string())?;
+    }
+    if !pay_token.is_icrc2() {
+        Err("Pay token must support ICRC2".to_string())?;
+    }
+    let pay_amount = args.pay_amount.clone();
+    if nat_is_zero(&pay_amount) {
+        Err("Pay amount is zero".to_string())?;
+    }
+
+    let receive_token = token_map::get_by_token(&args.receive_token)?;
+    if receive_token.is_removed() {
+        Err("Receive token is suspended or removed".to_string())?;
+    }
+    if pay_token.token_id() == receive_token.token_id() {
+        Err("Pay and receive tokens must be different".to_string())?;
+    }
+
+    if !args.limit_price.is_finite() || args.limit_price <= 0.0 {
+        Err("Invalid limit price".to_string())?;
+    }
+    if let Some(expires_at) = args.expires_at {
+        if expires_at <= ICNetwork::get_time() {
+            Err("Order expiry must be in the future".to_string())?;
+        }
+    }
+
+    // make sure there is a route between the tokens
+    swap_mid_price(&pay_token, &receive_token)?;
+
+    // use specified address or default to caller's principal id
+    let to_address = match args.receive_address {
+        Some(ref address) => get_address(&receive_token, address)?,
+        None => Address::PrincipalId(ICNetwork::caller_id()),
+    };
+
+    // make sure user is registered, if not create a new user
+    let user_id = user_map::insert(None)?;
+
+    Ok((user_id, pay_token, pay_amount, receive_token, to_address))
+}
@@ -0,0 +1,14 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+/// Data structure for the arguments of the `limit_order` function.
+/// Used in StableRequest
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct LimitOrderArgs {
+    pub pay_token: String,
+    pub pay_amount: Nat,
+    pub receive_token: String,
+    pub limit_price: f64,                // minimum price (receive token per pay token) to fill the order
+    pub receive_address: Option<String>, // Required for non-IC receive tokens
+    pub expires_at: Option<u64>,         // time in nanoseconds after which the order is expired and the pay token returned
+}
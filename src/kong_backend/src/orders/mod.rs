@@ -0,0 +1,12 @@
+pub mod cancel_order;
+pub mod fill_order;
+pub mod limit_order;
+pub mod limit_order_args;
+pub mod order_reply;
+#[allow(clippy::module_inception)]
+pub mod orders;
+pub mod orders_timer;
+pub mod place_order;
+pub mod return_order;
+pub mod twap_order;
+pub mod twap_order_args;
@@ -0,0 +1,108 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+use std::convert::From;
+
+use crate::stable_order::stable_order::{OrderType, StableOrder};
+use crate::stable_token::token::Token;
+use crate::stable_token::token_map;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct OrderReply {
+    pub order_id: u64,
+    pub request_id: u64,
+    pub status: String,
+    pub order_type: String,
+    pub pay_chain: String,
+    pub pay_address: String,
+    pub pay_symbol: String,
+    pub pay_amount: Nat,
+    pub receive_chain: String,
+    pub receive_address: String,
+    pub receive_symbol: String,
+    pub receive_amount: Nat,
+    pub filled_pay_amount: Nat,
+    pub limit_price: Option<f64>,
+    pub twap: Option<TwapReply>,
+    pub to_address: String,
+    pub fill_request_ids: Vec<u64>,
+    pub tx_ids: Vec<u64>,
+    pub expires_at: Option<u64>,
+    pub claim_ids: Vec<u64>,
+    pub ts: u64,
+}
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct TwapReply {
+    pub num_slices: u32,
+    pub interval_secs: u64,
+    pub max_slippage: f64,
+    pub slices_filled: u32,
+    pub next_slice_at: u64,
+    pub failed_slices: u32,
+}
+
+impl From<&StableOrder> for OrderReply {
+    fn from(order: &StableOrder) -> Self {
+        let (pay_chain, pay_address, pay_symbol) = match token_map::get_by_token_id(order.pay_token_id) {
+            Some(token) => (token.chain(), token.address(), token.symbol()),
+            None => (
+                "Pay chain not found".to_string(),
+                "Pay address not found".to_string(),
+                "Pay symbol not found".to_string(),
+            ),
+        };
+        let (receive_chain, receive_address, receive_symbol) = match token_map::get_by_token_id(order.receive_token_id) {
+            Some(token) => (token.chain(), token.address(), token.symbol()),
+            None => (
+                "Receive chain not found".to_string(),
+                "Receive address not found".to_string(),
+                "Receive symbol not found".to_string(),
+            ),
+        };
+        let (limit_price, twap) = match order.order_type {
+            OrderType::Limit { limit_price } => (Some(limit_price), None),
+            OrderType::Twap {
+                num_slices,
+                interval_secs,
+                max_slippage,
+                slices_filled,
+                next_slice_at,
+                failed_slices,
+            } => (
+                None,
+                Some(TwapReply {
+                    num_slices,
+                    interval_secs,
+                    max_slippage,
+                    slices_filled,
+                    next_slice_at,
+                    failed_slices,
+                }),
+            ),
+        };
+
+        OrderReply {
+            order_id: order.order_id,
+            request_id: order.request_id,
+            status: order.status.to_string(),
+            order_type: order.order_type.to_string(),
+            pay_chain,
+            pay_address,
+            pay_symbol,
+            pay_amount: order.pay_amount.clone(),
+            receive_chain,
+            receive_address,
+            receive_symbol,
+            receive_amount: order.receive_amount.clone(),
+            filled_pay_amount: order.filled_pay_amount.clone(),
+            limit_price,
+            twap,
+            to_address: order.to_address.to_string(),
+            fill_request_ids: order.fill_request_ids.clone(),
+            tx_ids: order.tx_ids.clone(),
+            expires_at: order.expires_at,
+            claim_ids: order.claim_ids.clone(),
+            ts: order.ts,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use ic_cdk::query;
+
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::stable_order::order_map;
+use crate::stable_user::user_map;
+
+use super::order_reply::OrderReply;
+
+/// Return all orders for a user, latest first
+#[query(guard = "not_in_maintenance_mode")]
+fn orders(principal_id: String) -> Result<Vec<OrderReply>, String> {
+    let user_id = user_map::get_by_principal_id(&principal_id)
+        .ok()
+        .flatten()
+        .ok_or("User not found")?
+        .user_id;
+
+    Ok(order_map::get_by_user_id(user_id).iter().map(OrderReply::from).collect())
+}
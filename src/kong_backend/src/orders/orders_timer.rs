@@ -0,0 +1,112 @@
+use std::cell::Cell;
+
+use crate::ic::{guards::not_in_maintenance_mode, network::ICNetwork};
+use crate::stable_order::order_map;
+use crate::stable_order::stable_order::OrderStatus;
+
+use super::fill_order::{fill_order, is_exhausted, is_fillable, is_slice_due, reopen_order};
+use super::return_order::return_order;
+
+// open orders quoted in one tick of the timer
+const MAX_ORDERS_PER_TICK: usize = 100;
+
+thread_local! {
+    // order_id of the last open order taken by the timer. the next tick continues after it
+    static LAST_ORDER_ID: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// take the next max_orders open orders after the last tick's, starting again from the oldest after the newest
+fn next_open_order_ids(max_orders: usize) -> Vec<u64> {
+    LAST_ORDER_ID.with(|last_order_id| {
+        let order_ids = order_map::get_open_order_ids_after(last_order_id.get(), max_orders);
+        if order_ids.len() == max_orders {
+            last_order_id.set(order_ids.last().copied());
+        } else {
+            last_order_id.set(None);
+        }
+        order_ids
+    })
+}
+
+/// Fill open orders where the price has crossed and return the pay token of expired orders
+/// TWAP orders are also returned when they can not be filled anymore, see is_exhausted()
+pub async fn process_orders_timer() {
+    if not_in_maintenance_mode().is_err() {
+        return;
+    }
+
+    // get snapshot of the next open order_ids. the cursor is moved before any inter-canister call so an overlapping tick takes the orders after them
+    for order_id in next_open_order_ids(MAX_ORDERS_PER_TICK) {
+        let order = match order_map::get_by_order_id(order_id) {
+            Some(order) => order,
+            None => continue,
+        };
+        if order.status != OrderStatus::Open {
+            // status may have changed from the time we got the order_ids above as fill_order() and return_order() make inter-canister calls
+            continue;
+        }
+
+        let ts = ICNetwork::get_time();
+        if order.expires_at.is_some_and(|expires_at| expires_at <= ts) || is_exhausted(&order) {
+            let Some(order) = order_map::update_open_status(order_id, OrderStatus::Cancelling) else {
+                continue;
+            };
+            if let Err(e) = return_order(&order, OrderStatus::Expired, ts).await {
+                ICNetwork::error_log(&format!("Failed to return expired order #{}. {}", order_id, e));
+            }
+            continue;
+        }
+
+        if is_fillable(&order, ts) {
+            if let Err(e) = fill_order(order_id, ts).await {
+                ICNetwork::info_log(&format!("Order #{} not filled. {}", order_id, e));
+            }
+        } else if is_slice_due(&order, ts) {
+            // the slice is not within max_slippage, skip it
+            reopen_order(order_id, ts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::{Nat, Principal};
+
+    use crate::ic::address::Address;
+    use crate::stable_order::stable_order::{OrderType, StableOrder};
+
+    fn place_order(order_id: u64) {
+        let order = StableOrder {
+            order_id,
+            ..StableOrder::new(
+                100,
+                1,
+                OrderType::Limit { limit_price: 10.0 },
+                1,
+                &Nat::from(1_000_u64),
+                2,
+                &Address::PrincipalId(Principal::anonymous().into()),
+                None,
+                0,
+            )
+        };
+        order_map::update(&order);
+    }
+
+    #[test]
+    fn test_next_open_order_ids_pages_and_starts_again() {
+        for order_id in 1..=5 {
+            place_order(order_id);
+        }
+        assert_eq!(next_open_order_ids(2), vec![1, 2]);
+        assert_eq!(next_open_order_ids(2), vec![3, 4]);
+        // order placed while paging is taken on this pass
+        place_order(6);
+        assert_eq!(next_open_order_ids(2), vec![5, 6]);
+        assert!(next_open_order_ids(2).is_empty());
+        // back to the oldest open order
+        order_map::update_status(1, OrderStatus::Filled);
+        assert_eq!(next_open_order_ids(2), vec![2, 3]);
+    }
+}
@@ -0,0 +1,41 @@
+use candid::Nat;
+
+use crate::ic::network::ICNetwork;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_order::order_map;
+use crate::stable_order::stable_order::StableOrder;
+use crate::stable_request::{reply::Reply, request_map, status::StatusCode};
+use crate::stable_token::stable_token::StableToken;
+use crate::swap::swap_transfer_from::transfer_from_token;
+
+use super::order_reply::OrderReply;
+
+/// Take the pay token of an order into custody with icrc2_transfer_from and insert the order
+/// request_id is the request of the order placement
+pub async fn place_order(
+    request_id: u64,
+    order: &StableOrder,
+    pay_token: &StableToken,
+    pay_amount: &Nat,
+    ts: u64,
+) -> Result<OrderReply, String> {
+    let mut transfer_ids = Vec::new();
+
+    request_map::update_status(request_id, StatusCode::Start, None);
+
+    let caller_id = ICNetwork::caller_id();
+    let kong_backend = kong_settings_map::get().kong_backend;
+    if let Err(e) = transfer_from_token(request_id, &caller_id, pay_token, pay_amount, &kong_backend, &mut transfer_ids, ts).await {
+        request_map::update_status(request_id, StatusCode::Failed, None);
+        Err(format!("Req #{} failed. Pay token transfer_from failed. {}", request_id, e))?
+    }
+
+    let order_id = order_map::insert(order);
+    request_map::update_status(request_id, StatusCode::OrderPlaced, Some(&format!("Order #{}", order_id)));
+
+    let reply = OrderReply::from(&order_map::get_by_order_id(order_id).ok_or("Order not found")?);
+    request_map::update_reply(request_id, Reply::Order(reply.clone()));
+    request_map::update_status(request_id, StatusCode::Success, None);
+
+    Ok(reply)
+}
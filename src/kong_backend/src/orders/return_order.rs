@@ -0,0 +1,197 @@
+use candid::{Nat, Principal};
+use icrc_ledger_types::icrc1::account::Account;
+
+use crate::helpers::nat_helpers::{nat_is_zero, nat_subtract};
+use crate::ic::address::Address;
+use crate::stable_claim::{claim_map, stable_claim::StableClaim};
+use crate::stable_order::order_map;
+use crate::stable_order::stable_order::{OrderStatus, OrderType, StableOrder};
+use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
+use crate::stable_user::user_map;
+use crate::swap::return_pay_token::{return_pay_token_with_claims, RETURN_PAY_TOKEN_STATUS};
+
+use super::order_reply::OrderReply;
+
+/// Pay token, principal and unfilled pay amount to return
+fn return_order_args(order: &StableOrder) -> Result<(StableToken, Account, Nat), String> {
+    let pay_token = token_map::get_by_token_id(order.pay_token_id).ok_or("Pay token not found")?;
+    let user = user_map::get_by_user_id(order.user_id).ok_or("User not found")?;
+    let to_principal_id = Account::from(Principal::from_text(&user.principal_id).map_err(|e| e.to_string())?);
+    let unfilled_pay_amount = nat_subtract(&order.pay_amount, &order.filled_pay_amount).ok_or("Invalid filled pay amount")?;
+    Ok((pay_token, to_principal_id, unfilled_pay_amount))
+}
+
+/// Return the unfilled pay token held in custody for an order to the user and close the order with status
+/// limit orders are returned directly, TWAP orders are saved as a claim
+/// order must already be in Cancelling status. it is closed with status before the pay token is returned, so a trap
+/// in the return can not leave it in Cancelling. if the order can not be returned, it is re-opened
+/// returns the request_id of the return
+pub async fn return_order(order: &StableOrder, status: OrderStatus, ts: u64) -> Result<(u64, OrderReply), String> {
+    let (pay_token, to_principal_id, unfilled_pay_amount) = match return_order_args(order) {
+        Ok(args) => args,
+        Err(e) => {
+            order_map::update_status(order.order_id, OrderStatus::Open);
+            Err(e)?
+        }
+    };
+
+    let request_id = request_map::insert(&StableRequest::new(order.user_id, &Request::CancelOrder(order.order_id), ts));
+    request_map::update_status(request_id, StatusCode::CancelOrder, Some(&format!("Order #{}", order.order_id)));
+    order_map::update_status(order.order_id, status.clone());
+
+    let mut claim_ids = Vec::new();
+    if !nat_is_zero(&unfilled_pay_amount) {
+        match order.order_type {
+            // pay token is returned directly or saved as a claim if the transfer fails
+            OrderType::Limit { .. } => {
+                let mut transfer_ids = Vec::new();
+                return_pay_token_with_claims(
+                    request_id,
+                    order.user_id,
+                    &to_principal_id,
+                    &pay_token,
+                    &unfilled_pay_amount,
+                    &mut transfer_ids,
+                    &mut claim_ids,
+                    ts,
+                    RETURN_PAY_TOKEN_STATUS,
+                )
+                .await;
+            }
+            // unfilled slices are saved as a claim
+            OrderType::Twap { .. } => {
+                let mut claim = StableClaim::new(
+                    order.user_id,
+                    pay_token.token_id(),
+                    &unfilled_pay_amount,
+                    Some(request_id),
+                    Some(Address::PrincipalId(to_principal_id)),
+                    ts,
+                );
+                claim.desc = Some(format!("Unfilled {} of order #{}", pay_token.symbol(), order.order_id));
+                let claim_id = claim_map::insert(&claim);
+                claim_ids.push(claim_id);
+                request_map::update_status(request_id, StatusCode::OrderClaimSaved, Some(&format!("Claim #{}", claim_id)));
+            }
+        }
+    }
+
+    let status_code = match status {
+        OrderStatus::Expired => StatusCode::OrderExpired,
+        _ => StatusCode::OrderCancelled,
+    };
+    // refresh order with the latest state
+    let mut order = order_map::get_by_order_id(order.order_id).ok_or("Order not found")?;
+    order.claim_ids.extend(claim_ids);
+    order_map::update(&order);
+    let reply = OrderReply::from(&order);
+    request_map::update_status(request_id, status_code, None);
+    request_map::update_reply(request_id, Reply::Order(reply.clone()));
+    request_map::update_status(request_id, StatusCode::Success, None);
+
+    Ok((request_id, reply))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_ic_token, insert_user};
+    use crate::orders::fill_order::is_exhausted;
+    use crate::stable_claim::stable_claim::ClaimStatus;
+
+    const ICP: u32 = 1;
+    const CKUSDT: u32 = 2;
+
+    fn cancelling_twap_order(pay_amount: u64, filled_pay_amount: u64) -> StableOrder {
+        let order = StableOrder {
+            order_id: 1,
+            status: OrderStatus::Cancelling,
+            filled_pay_amount: Nat::from(filled_pay_amount),
+            ..StableOrder::new(
+                100,
+                1,
+                OrderType::Twap {
+                    num_slices: 10,
+                    interval_secs: 60,
+                    max_slippage: 2.0,
+                    slices_filled: 5,
+                    next_slice_at: 0,
+                    failed_slices: 0,
+                },
+                ICP,
+                &Nat::from(pay_amount),
+                CKUSDT,
+                &Address::PrincipalId(Account::from(Principal::anonymous())),
+                None,
+                0,
+            )
+        };
+        order_map::update(&order);
+        order
+    }
+
+    #[test]
+    fn test_return_twap_order_saves_claim() {
+        init_kong_settings();
+        insert_ic_token(ICP, "ICP", 8);
+        insert_user(100, Principal::anonymous());
+        let order = cancelling_twap_order(1_000_000, 600_000);
+
+        let (request_id, reply) = block_on(return_order(&order, OrderStatus::Cancelled, 0)).unwrap();
+        let order = order_map::get_by_order_id(1).unwrap();
+        assert_eq!(order.status, OrderStatus::Cancelled);
+        assert_eq!(order.claim_ids.len(), 1);
+        assert_eq!(reply.status, "Cancelled");
+        let claim = claim_map::get_by_claim_id(order.claim_ids[0]).unwrap();
+        assert_eq!(claim.status, ClaimStatus::Unclaimed);
+        assert_eq!(claim.amount, Nat::from(400_000_u64));
+        assert_eq!(claim.request_id, Some(request_id));
+    }
+
+    #[test]
+    fn test_return_order_user_not_found_reopens_order() {
+        insert_ic_token(ICP, "ICP", 8);
+        let order = cancelling_twap_order(1_000_000, 0);
+        assert!(block_on(return_order(&order, OrderStatus::Cancelled, 0)).is_err());
+        // the order is not left in Cancelling
+        assert_eq!(order_map::get_by_order_id(1).unwrap().status, OrderStatus::Open);
+        assert_eq!(order_map::get_open_order_ids(), vec![1]);
+    }
+
+    #[test]
+    fn test_twap_order_rounding_to_zero_is_returned_as_claim() {
+        init_kong_settings();
+        insert_ic_token(ICP, "ICP", 8);
+        insert_user(100, Principal::anonymous());
+        // 10 units left for 20 slices
+        let mut order = cancelling_twap_order(1_010, 1_000);
+        order.status = OrderStatus::Open;
+        if let OrderType::Twap {
+            ref mut num_slices,
+            ref mut slices_filled,
+            ..
+        } = order.order_type
+        {
+            (*num_slices, *slices_filled) = (100, 80);
+        }
+        order_map::update(&order);
+        assert!(is_exhausted(&order));
+
+        // closed by the orders timer
+        let order = order_map::update_open_status(1, OrderStatus::Cancelling).unwrap();
+        let (request_id, _) = block_on(return_order(&order, OrderStatus::Expired, 0)).unwrap();
+        let order = order_map::get_by_order_id(1).unwrap();
+        assert_eq!(order.status, OrderStatus::Expired);
+        let claim = claim_map::get_by_claim_id(order.claim_ids[0]).unwrap();
+        assert_eq!(claim.amount, Nat::from(10_u64));
+        assert_eq!(claim.request_id, Some(request_id));
+        let statuses = request_map::get_by_request_id(request_id).unwrap().statuses;
+        assert!(statuses
+            .iter()
+            .any(|status| matches!(status.status_code, StatusCode::OrderClaimSaved)));
+        assert!(statuses.iter().any(|status| matches!(status.status_code, StatusCode::OrderExpired)));
+    }
+}
@@ -0,0 +1,105 @@
+use candid::Nat;
+use ic_cdk::update;
+
+use crate::helpers::nat_helpers::{nat_divide, nat_is_zero};
+use crate::ic::address::Address;
+use crate::ic::address_helpers::get_address;
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_order::stable_order::{OrderType, StableOrder};
+use crate::stable_request::{request::Request, request_map, stable_request::StableRequest};
+use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
+use crate::stable_user::user_map;
+use crate::swap::swap_amounts::swap_mid_price;
+
+use super::order_reply::OrderReply;
+use super::place_order::place_order;
+use super::twap_order_args::TwapOrderArgs;
+
+const MAX_TWAP_SLICES: u32 = 1_000;
+
+/// Place a time-weighted average price (TWAP) order
+/// pay token is taken into custody with icrc2_transfer_from and swapped in num_slices equal slices every interval_secs
+/// the first slice is swapped on the next run of the orders timer
+#[update(guard = "not_in_maintenance_mode")]
+pub async fn twap_order(args: TwapOrderArgs) -> Result<OrderReply, String> {
+    let (user_id, pay_token, pay_amount, receive_token, max_slippage, to_address) = check_arguments(&args)?;
+    let ts = ICNetwork::get_time();
+    let request_id = request_map::insert(&StableRequest::new(user_id, &Request::TwapOrder(args.clone()), ts));
+
+    let order = StableOrder::new(
+        user_id,
+        request_id,
+        OrderType::Twap {
+            num_slices: args.num_slices,
+            interval_secs: args.interval_secs,
+            max_slippage,
+            slices_filled: 0,
+            next_slice_at: ts,
+            failed_slices: 0,
+        },
+        pay_token.token_id(),
+        &pay_amount,
+        receive_token.token_id(),
+        &to_address,
+        None,
+        ts,
+    );
+
+    place_order(request_id, &order, &pay_token, &pay_amount, ts).await
+}
+
+fn check_arguments(args: &TwapOrderArgs) -> Result<(u32, StableToken, Nat, StableToken, f64, Address), String> {
+    let pay_token = token_map::get_by_token(&args.pay_token)?;
+    if pay_token.is_removed() {
+        Err("Pay token is suspended or removed".to_string())?;
+    }
+    if !pay_token.is_icrc2() {
+        Err("Pay token must support ICRC2".to_string())?;
+    }
+    let pay_amount = args.pay_amount.clone();
+    if nat_is_zero(&pay_amount) {
+        Err("Pay amount is zero".to_string())?;
+    }
+
+    let receive_token = token_map::get_by_token(&args.receive_token)?;
+    if receive_token.is_removed() {
+        Err("Receive token is suspended or removed".to_string())?;
+    }
+    if pay_token.token_id() == receive_token.token_id() {
+        Err("Pay and receive tokens must be different".to_string())?;
+    }
+
+    if args.num_slices < 2 || args.num_slices > MAX_TWAP_SLICES {
+        Err(format!("Number of slices must be between 2 and {}", MAX_TWAP_SLICES))?;
+    }
+    let slice_amount = nat_divide(&pay_amount, &Nat::from(args.num_slices)).ok_or("Invalid number of slices")?;
+    if nat_is_zero(&slice_amount) {
+        Err("Pay amount is too small for the number of slices".to_string())?;
+    }
+    // slices are executed by the orders timer so the interval can not be shorter than the timer interval
+    let orders_interval_secs = kong_settings_map::get().orders_interval_secs;
+    if args.interval_secs < orders_interval_secs {
+        Err(format!("Interval must be at least {} seconds", orders_interval_secs))?;
+    }
+
+    let max_slippage = args.max_slippage.unwrap_or(kong_settings_map::get().default_max_slippage);
+    if !max_slippage.is_finite() || max_slippage <= 0.0 {
+        Err("Invalid max slippage".to_string())?;
+    }
+
+    // make sure there is a route between the tokens
+    swap_mid_price(&pay_token, &receive_token)?;
+
+    // use specified address or default to caller's principal id
+    let to_address = match args.receive_address {
+        Some(ref address) => get_address(&receive_token, address)?,
+        None => Address::PrincipalId(ICNetwork::caller_id()),
+    };
+
+    // make sure user is registered, if not create a new user
+    let user_id = user_map::insert(None)?;
+
+    Ok((user_id, pay_token, pay_amount, receive_token, max_slippage, to_address))
+}
@@ -0,0 +1,15 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+/// Data structure for the arguments of the `twap_order` function.
+/// Used in StableRequest
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct TwapOrderArgs {
+    pub pay_token: String,
+    pub pay_amount: Nat,
+    pub receive_token: String,
+    pub num_slices: u32,                 // number of equal slices pay_amount is swapped in
+    pub interval_secs: u64,              // seconds between slices
+    pub max_slippage: Option<f64>,       // max slippage of each slice
+    pub receive_address: Option<String>, // Required for non-IC receive tokens
+}
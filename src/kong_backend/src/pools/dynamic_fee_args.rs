@@ -0,0 +1,10 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicFeeArgs {
+    pub min_fee_bps: u8,
+    pub max_fee_bps: u8,
+    pub max_volatility_bps: u32, // accumulated price moves in basis points at which max_fee_bps is charged
+    pub half_life_secs: u64,     // half-life of the accumulated price moves
+}
@@ -1,3 +1,6 @@
+pub mod dynamic_fee_args;
+pub mod pool_fee_change_reply;
+pub mod pool_fee_changes;
 #[allow(clippy::module_inception)]
 pub mod pools;
 pub mod pools_reply;
@@ -0,0 +1,45 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::stable_pool::pool_map;
+use crate::stable_pool::stable_pool_fee_change::StablePoolFeeChange;
+use crate::stable_token::token::Token;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct PoolFeeChangeReply {
+    pub fee_change_id: u64,
+    pub pool_id: u32,
+    pub symbol: String,
+    pub lp_token_symbol: String,
+    pub old_lp_fee_bps: u8,
+    pub old_kong_fee_bps: u8,
+    pub lp_fee_bps: u8,
+    pub kong_fee_bps: u8,
+    pub old_dynamic_fee_bps: Option<(u8, u8)>,
+    pub dynamic_fee_bps: Option<(u8, u8)>,
+    pub principal_id: String,
+    pub ts: u64,
+}
+
+impl From<&StablePoolFeeChange> for PoolFeeChangeReply {
+    fn from(fee_change: &StablePoolFeeChange) -> Self {
+        let (symbol, lp_token_symbol) = match pool_map::get_by_pool_id(fee_change.pool_id) {
+            Some(pool) => (pool.symbol(), pool.lp_token().symbol()),
+            None => ("Pool symbol not found".to_string(), "LP token symbol not found".to_string()),
+        };
+        PoolFeeChangeReply {
+            fee_change_id: fee_change.fee_change_id,
+            pool_id: fee_change.pool_id,
+            symbol,
+            lp_token_symbol,
+            old_lp_fee_bps: fee_change.old_lp_fee_bps,
+            old_kong_fee_bps: fee_change.old_kong_fee_bps,
+            lp_fee_bps: fee_change.lp_fee_bps,
+            kong_fee_bps: fee_change.kong_fee_bps,
+            old_dynamic_fee_bps: fee_change.old_dynamic_fee_bps,
+            dynamic_fee_bps: fee_change.dynamic_fee_bps,
+            principal_id: fee_change.principal_id.clone(),
+            ts: fee_change.ts,
+        }
+    }
+}
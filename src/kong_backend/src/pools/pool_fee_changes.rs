@@ -0,0 +1,18 @@
+use ic_cdk::query;
+
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::stable_pool::{pool_fee_change_map, pool_map};
+
+use super::pool_fee_change_reply::PoolFeeChangeReply;
+
+/// Return the history of fee changes of a pool, latest first
+/// pool can be the pool symbol, address or LP token symbol
+#[query(guard = "not_in_maintenance_mode")]
+fn pool_fee_changes(pool: String) -> Result<Vec<PoolFeeChangeReply>, String> {
+    let pool = pool_map::get_by_token(&pool)?;
+
+    Ok(pool_fee_change_map::get_by_pool_id(pool.pool_id)
+        .iter()
+        .map(PoolFeeChangeReply::from)
+        .collect())
+}
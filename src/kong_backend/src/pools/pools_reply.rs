@@ -1,6 +1,7 @@
 use candid::{CandidType, Nat};
 use serde::{Deserialize, Serialize};
 
+use crate::stable_pool::pool_type::PoolType;
 use crate::stable_pool::stable_pool::StablePool;
 use crate::stable_token::token::Token;
 use crate::stable_token::token_map;
@@ -22,6 +23,8 @@ pub struct PoolReply {
     pub lp_fee_1: Nat,
     pub price: f64,
     pub lp_fee_bps: u8,
+    pub live_lp_fee_bps: u8, // LP fee currently charged. differs from lp_fee_bps if dynamic fees are enabled
+    pub pool_type: PoolType,
     pub lp_token_symbol: String,
     pub is_removed: bool,
 }
@@ -67,6 +70,8 @@ impl From<&StablePool> for PoolReply {
             lp_fee_1: pool.lp_fee_1.clone(),
             price: pool.get_price_as_f64().unwrap_or(0_f64),
             lp_fee_bps: pool.lp_fee_bps,
+            live_lp_fee_bps: pool.live_lp_fee_bps(),
+            pool_type: pool.pool_type.clone(),
             lp_token_symbol,
             is_removed: pool.is_removed,
         }
@@ -0,0 +1,174 @@
+use candid::Nat;
+use ic_cdk::update;
+
+use crate::add_liquidity::add_liquidity::TokenIndex;
+use crate::helpers::nat_helpers::{nat_add, nat_subtract, nat_to_decimal_precision, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_lp_token::lp_position_map;
+use crate::stable_lp_token::stable_lp_position::StableLPPosition;
+use crate::stable_pool::{concentrated_liquidity, pool_map};
+use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+use crate::stable_token::token::Token;
+
+use super::mint_position::to_transfer_id_replies;
+use super::position_reply::PositionReply;
+use super::positions::get_caller_position;
+use super::transfer_position_token::send_position_token;
+
+/// Burn a concentrated liquidity position
+/// all the liquidity of the position is removed at the pool price and paid out together with the uncollected fees
+#[update(guard = "not_in_maintenance_mode")]
+pub async fn burn_position(position_id: u64) -> Result<PositionReply, String> {
+    let position = get_caller_position(position_id)?;
+    let ts = ICNetwork::get_time();
+    let request_id = request_map::insert(&StableRequest::new(position.user_id, &Request::BurnPosition(position_id), ts));
+
+    request_map::update_status(request_id, StatusCode::Start, None);
+
+    // remove the liquidity from the pool before the transfers
+    request_map::update_status(request_id, StatusCode::UpdatePoolAmounts, None);
+    let (position, amount_0, fees_0, amount_1, fees_1) = match remove_position(&position) {
+        Ok(burned) => {
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
+            burned
+        }
+        Err(e) => {
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsFailed, Some(&e));
+            request_map::update_status(request_id, StatusCode::Failed, None);
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    };
+    request_map::update_status(request_id, StatusCode::PositionBurned, Some(&format!("Position #{}", position_id)));
+
+    let pool = pool_map::get_by_pool_id(position.pool_id).ok_or("Pool not found")?;
+    let caller_id = ICNetwork::caller_id();
+    let mut transfer_ids = Vec::new();
+    let mut claim_ids = Vec::new();
+    let payout_amount_0 = nat_add(&amount_0, &fees_0);
+    let payout_amount_1 = nat_add(&amount_1, &fees_1);
+    send_position_token(
+        request_id,
+        position.user_id,
+        &caller_id,
+        &TokenIndex::Token0,
+        &pool.token_0(),
+        &payout_amount_0,
+        &mut transfer_ids,
+        &mut claim_ids,
+        ts,
+    )
+    .await;
+    send_position_token(
+        request_id,
+        position.user_id,
+        &caller_id,
+        &TokenIndex::Token1,
+        &pool.token_1(),
+        &payout_amount_1,
+        &mut transfer_ids,
+        &mut claim_ids,
+        ts,
+    )
+    .await;
+
+    let reply = PositionReply {
+        request_id,
+        amount_0,
+        fees_0,
+        amount_1,
+        fees_1,
+        transfer_ids: to_transfer_id_replies(&transfer_ids),
+        claim_ids,
+        ..PositionReply::from(&position)
+    };
+    request_map::update_reply(request_id, Reply::Position(reply.clone()));
+    request_map::update_status(request_id, StatusCode::Success, None);
+
+    Ok(reply)
+}
+
+/// remove all the liquidity of a position from the pool and delete the position
+/// returns the burned position and its amounts and fees of token_0 and token_1 in the tokens' decimals
+fn remove_position(position: &StableLPPosition) -> Result<(StableLPPosition, Nat, Nat, Nat, Nat), String> {
+    // refresh with the latest state
+    let mut position = lp_position_map::get_by_position_id(position.position_id).ok_or("Position not found")?;
+    let mut pool = pool_map::get_by_pool_id(position.pool_id).ok_or("Pool not found")?;
+    let token_0 = pool.token_0();
+    let token_1 = pool.token_1();
+    let max_decimals = std::cmp::max(token_0.decimals(), token_1.decimals());
+
+    let (amount_0, amount_1) = concentrated_liquidity::position_amounts(&pool, &position).ok_or("Invalid position amounts")?;
+    // the pool balances include rounding in favour of the pool so they should always cover the amounts
+    let amount_0 = std::cmp::min(
+        nat_to_decimal_precision(&amount_0, max_decimals, token_0.decimals()),
+        pool.balance_0.clone(),
+    );
+    let amount_1 = std::cmp::min(
+        nat_to_decimal_precision(&amount_1, max_decimals, token_1.decimals()),
+        pool.balance_1.clone(),
+    );
+    let liquidity = position.liquidity.clone();
+    concentrated_liquidity::update_position(&mut pool, &mut position, &liquidity, false)?;
+    let fees_0 = std::cmp::min(position.fees_owed_0.clone(), pool.lp_fee_0.clone());
+    let fees_1 = std::cmp::min(position.fees_owed_1.clone(), pool.lp_fee_1.clone());
+    position.fees_owed_0 = nat_zero();
+    position.fees_owed_1 = nat_zero();
+
+    pool.balance_0 = nat_subtract(&pool.balance_0, &amount_0).unwrap_or(nat_zero());
+    pool.balance_1 = nat_subtract(&pool.balance_1, &amount_1).unwrap_or(nat_zero());
+    pool.lp_fee_0 = nat_subtract(&pool.lp_fee_0, &fees_0).unwrap_or(nat_zero());
+    pool.lp_fee_1 = nat_subtract(&pool.lp_fee_1, &fees_1).unwrap_or(nat_zero());
+    pool_map::update(&pool);
+    lp_position_map::remove(position.position_id);
+
+    Ok((position, amount_0, fees_0, amount_1, fees_1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::nat_helpers::nat_is_zero;
+    use crate::helpers::test_helpers::{init_kong_settings, insert_concentrated_pool, insert_ic_token, insert_position};
+    use crate::stable_pool::tick_map;
+
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+
+    #[test]
+    fn test_remove_position_pays_amounts_and_fees() {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckBTC", 8);
+        init_kong_settings();
+        insert_concentrated_pool(1, 1, 2, 60);
+        let wide = insert_position(100, 1, -1_200, 1_200, LIQUIDITY);
+        let narrow = insert_position(101, 1, -60, 60, LIQUIDITY);
+        let mut pool = pool_map::get_by_pool_id(1).unwrap();
+        pool.balance_0 = Nat::from(1_000_000_000_000_u64);
+        pool.balance_1 = Nat::from(1_000_000_000_000_u64);
+        pool.lp_fee_1 = Nat::from(10_u64);
+        pool_map::update(&pool);
+        let mut owed = lp_position_map::get_by_position_id(narrow.position_id).unwrap();
+        owed.fees_owed_1 = Nat::from(10_u64);
+        lp_position_map::update(&owed);
+        let (amount_0, amount_1) = concentrated_liquidity::position_amounts(&pool, &narrow).unwrap();
+
+        let (position, removed_0, fees_0, removed_1, fees_1) = remove_position(&narrow).unwrap();
+
+        // at tick 0 the position holds both tokens
+        assert_eq!((removed_0.clone(), removed_1.clone()), (amount_0, amount_1));
+        assert!(!nat_is_zero(&removed_0) && !nat_is_zero(&removed_1));
+        assert!(nat_is_zero(&fees_0));
+        assert_eq!(fees_1, Nat::from(10_u64));
+        assert!(nat_is_zero(&position.liquidity));
+        assert!(lp_position_map::get_by_position_id(narrow.position_id).is_none());
+        // the pool keeps the wide position's liquidity
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.balance_0, nat_subtract(&Nat::from(1_000_000_000_000_u64), &removed_0).unwrap());
+        assert_eq!(pool.balance_1, nat_subtract(&Nat::from(1_000_000_000_000_u64), &removed_1).unwrap());
+        assert!(nat_is_zero(&pool.lp_fee_1));
+        assert_eq!(pool.concentrated.unwrap().liquidity, Nat::from(LIQUIDITY));
+        assert!(tick_map::get(1, -60).is_none());
+        assert!(tick_map::get(1, wide.tick_lower).is_some());
+    }
+}
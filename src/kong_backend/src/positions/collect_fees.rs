@@ -0,0 +1,191 @@
+use candid::Nat;
+use ic_cdk::update;
+
+use crate::add_liquidity::add_liquidity::TokenIndex;
+use crate::helpers::nat_helpers::{nat_subtract, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_lp_token::lp_position_map;
+use crate::stable_lp_token::stable_lp_position::StableLPPosition;
+use crate::stable_pool::{concentrated_liquidity, pool_map};
+use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+
+use super::mint_position::to_transfer_id_replies;
+use super::position_reply::PositionReply;
+use super::positions::get_caller_position;
+use super::transfer_position_token::send_position_token;
+
+/// Collect the LP fees earned by a concentrated liquidity position
+/// fees accrue to a position while the pool price is in its range. the liquidity of the position is unchanged
+/// the reply has the collected fees in fees_0 and fees_1
+#[update(guard = "not_in_maintenance_mode")]
+pub async fn collect_fees(position_id: u64) -> Result<PositionReply, String> {
+    let position = get_caller_position(position_id)?;
+    let ts = ICNetwork::get_time();
+    let request_id = request_map::insert(&StableRequest::new(position.user_id, &Request::CollectFees(position_id), ts));
+
+    request_map::update_status(request_id, StatusCode::Start, None);
+
+    // take the fees out of the pool before the transfers
+    request_map::update_status(request_id, StatusCode::UpdatePoolAmounts, None);
+    let (position, fees_0, fees_1) = match take_fees(&position, ts) {
+        Ok(collected) => {
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
+            collected
+        }
+        Err(e) => {
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsFailed, Some(&e));
+            request_map::update_status(request_id, StatusCode::Failed, None);
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    };
+    request_map::update_status(request_id, StatusCode::FeesCollected, Some(&format!("Position #{}", position_id)));
+
+    let pool = pool_map::get_by_pool_id(position.pool_id).ok_or("Pool not found")?;
+    let caller_id = ICNetwork::caller_id();
+    let mut transfer_ids = Vec::new();
+    let mut claim_ids = Vec::new();
+    send_position_token(
+        request_id,
+        position.user_id,
+        &caller_id,
+        &TokenIndex::Token0,
+        &pool.token_0(),
+        &fees_0,
+        &mut transfer_ids,
+        &mut claim_ids,
+        ts,
+    )
+    .await;
+    send_position_token(
+        request_id,
+        position.user_id,
+        &caller_id,
+        &TokenIndex::Token1,
+        &pool.token_1(),
+        &fees_1,
+        &mut transfer_ids,
+        &mut claim_ids,
+        ts,
+    )
+    .await;
+
+    let reply = PositionReply {
+        request_id,
+        fees_0,
+        fees_1,
+        transfer_ids: to_transfer_id_replies(&transfer_ids),
+        claim_ids,
+        ..PositionReply::from(&position)
+    };
+    request_map::update_reply(request_id, Reply::Position(reply.clone()));
+    request_map::update_status(request_id, StatusCode::Success, None);
+
+    Ok(reply)
+}
+
+/// accrue the fees earned by a position and take them out of the pool's LP fees
+/// returns the updated position and the fees of token_0 and token_1
+fn take_fees(position: &StableLPPosition, ts: u64) -> Result<(StableLPPosition, Nat, Nat), String> {
+    // refresh with the latest state
+    let mut position = lp_position_map::get_by_position_id(position.position_id).ok_or("Position not found")?;
+    let mut pool = pool_map::get_by_pool_id(position.pool_id).ok_or("Pool not found")?;
+
+    concentrated_liquidity::update_position(&mut pool, &mut position, &nat_zero(), true)?;
+    let fees_0 = std::cmp::min(position.fees_owed_0.clone(), pool.lp_fee_0.clone());
+    let fees_1 = std::cmp::min(position.fees_owed_1.clone(), pool.lp_fee_1.clone());
+    position.fees_owed_0 = nat_zero();
+    position.fees_owed_1 = nat_zero();
+    position.ts = ts;
+
+    pool.lp_fee_0 = nat_subtract(&pool.lp_fee_0, &fees_0).unwrap_or(nat_zero());
+    pool.lp_fee_1 = nat_subtract(&pool.lp_fee_1, &fees_1).unwrap_or(nat_zero());
+    pool_map::update(&pool);
+    lp_position_map::update(&position);
+
+    Ok((position, fees_0, fees_1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply};
+    use crate::helpers::test_helpers::{init_kong_settings, insert_concentrated_pool, insert_ic_token, insert_position};
+    use crate::stable_pool::concentrated_math;
+    use crate::swap::swap_calc::SwapCalc;
+    use crate::swap::update_liquidity_pool::update_pool_amounts;
+
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+
+    #[test]
+    fn test_take_fees_pays_fees_accrued_inside_range() {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckBTC", 8);
+        init_kong_settings();
+        insert_concentrated_pool(1, 1, 2, 60);
+        let wide = insert_position(100, 1, -1_200, 1_200, LIQUIDITY);
+        let narrow = insert_position(101, 1, -60, 60, LIQUIDITY);
+        let above = insert_position(102, 1, 600, 1_200, LIQUIDITY);
+
+        // swap receiving twice the token_1 down to tick -60, where the narrow position ends
+        let sqrt_price = concentrated_math::sqrt_price_at_tick(0).unwrap();
+        let sqrt_price_lower = concentrated_math::sqrt_price_at_tick(-60).unwrap();
+        let (_, step_amount_out) =
+            concentrated_math::amounts_for_liquidity(&sqrt_price, &sqrt_price_lower, &sqrt_price, &Nat::from(2 * LIQUIDITY), false)
+                .unwrap();
+        let receive_amount = nat_multiply(&step_amount_out, &Nat::from(2_u32));
+        let swap = SwapCalc {
+            pool_id: 1,
+            pay_token_id: 1,
+            pay_amount: nat_add(&receive_amount, &Nat::from(1_000_000_u64)),
+            receive_token_id: 2,
+            receive_amount: receive_amount.clone(),
+            lp_fee: Nat::from(3_000_000_u64),
+            gas_fee: nat_zero(),
+            lp_fee_bps: 30,
+        };
+        update_pool_amounts(0, &swap).unwrap();
+        // kong_fee = 3_000_000 * 8 / 30 = 800_000
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        let lp_fee = Nat::from(2_200_000_u64);
+        assert_eq!(pool.lp_fee_1, lp_fee);
+
+        // the narrow position earns half of the fee of the swap before it left its range
+        let (_, accrued_fees_1) =
+            concentrated_liquidity::position_fees(&pool, &lp_position_map::get_by_position_id(narrow.position_id).unwrap());
+        let (position, fees_0, fees_1) = take_fees(&narrow, 1).unwrap();
+        assert!(nat_is_zero(&fees_0));
+        assert_eq!(fees_1, accrued_fees_1);
+        let expected_fees_1 = nat_divide(
+            &nat_multiply(&lp_fee, &step_amount_out),
+            &nat_multiply(&receive_amount, &Nat::from(2_u32)),
+        )
+        .unwrap();
+        let diff = if fees_1 > expected_fees_1 {
+            nat_subtract(&fees_1, &expected_fees_1)
+        } else {
+            nat_subtract(&expected_fees_1, &fees_1)
+        };
+        assert!(diff.unwrap() <= 2_u32);
+        assert!(nat_is_zero(&position.fees_owed_1));
+        assert_eq!(position.liquidity, Nat::from(LIQUIDITY));
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.lp_fee_1, nat_subtract(&lp_fee, &fees_1).unwrap());
+
+        // nothing more to collect until the next swap
+        let (_, _, fees_1) = take_fees(&narrow, 2).unwrap();
+        assert!(nat_is_zero(&fees_1));
+        // a position the price never reached earns nothing
+        let (_, fees_0, fees_1) = take_fees(&above, 2).unwrap();
+        assert!(nat_is_zero(&fees_0) && nat_is_zero(&fees_1));
+        // the wide position gets the rest, never more than the pool holds
+        let (_, _, wide_fees_1) = take_fees(&wide, 2).unwrap();
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(
+            nat_add(&pool.lp_fee_1, &wide_fees_1),
+            nat_subtract(&lp_fee, &accrued_fees_1).unwrap()
+        );
+        assert!(pool.lp_fee_1 <= 2_u32);
+    }
+}
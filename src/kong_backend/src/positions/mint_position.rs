@@ -0,0 +1,331 @@
+use candid::Nat;
+use ic_cdk::update;
+
+use crate::add_liquidity::add_liquidity::TokenIndex;
+use crate::add_liquidity::add_liquidity_transfer_from::transfer_from_token;
+use crate::helpers::nat_helpers::{nat_10pow, nat_add, nat_divide_ceil, nat_is_zero, nat_subtract, nat_to_decimal_precision, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_lp_token::lp_position_map;
+use crate::stable_lp_token::stable_lp_position::StableLPPosition;
+use crate::stable_pool::pool_type::PoolType;
+use crate::stable_pool::stable_pool::StablePool;
+use crate::stable_pool::{concentrated_liquidity, concentrated_math, pool_map};
+use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+use crate::stable_token::{token::Token, token_map};
+use crate::stable_transfer::transfer_map;
+use crate::stable_user::user_map;
+use crate::transfers::transfer_reply::TransferIdReply;
+
+use super::mint_position_args::MintPositionArgs;
+use super::position_reply::PositionReply;
+use super::transfer_position_token::{return_token, return_unused_token};
+
+/// Mint a concentrated liquidity position in the price range [tick_lower, tick_upper)
+///
+/// amount_0 and amount_1 are the maximum amounts to deposit. The position gets the most liquidity both amounts allow
+/// at the pool price and any amount not needed is returned. Below the range only token_0 is used, above only token_1
+/// Tokens are taken with icrc2_transfer_from
+#[update(guard = "not_in_maintenance_mode")]
+pub async fn mint_position(args: MintPositionArgs) -> Result<PositionReply, String> {
+    let (user_id, pool, amount_0, amount_1) = check_arguments(&args)?;
+    let ts = ICNetwork::get_time();
+    let request_id = request_map::insert(&StableRequest::new(user_id, &Request::MintPosition(args.clone()), ts));
+
+    let result = process_mint_position(request_id, user_id, &pool, &amount_0, &amount_1, &args, ts).await;
+    match result {
+        Ok(_) => request_map::update_status(request_id, StatusCode::Success, None),
+        Err(_) => request_map::update_status(request_id, StatusCode::Failed, None),
+    };
+    result
+}
+
+/// check the arguments and calculate the amounts of token_0 and token_1 needed for the position
+fn check_arguments(args: &MintPositionArgs) -> Result<(u32, StablePool, Nat, Nat), String> {
+    let pool = pool_map::get_by_tokens(&args.token_0, &args.token_1, args.lp_fee_bps)?;
+    let PoolType::Concentrated { tick_spacing } = pool.pool_type else {
+        Err(format!("Pool {} is not a concentrated liquidity pool", pool.symbol()))?
+    };
+    if pool.is_removed {
+        Err(format!("Pool {} is removed", pool.symbol()))?
+    }
+    if !pool.token_0().is_icrc2() || !pool.token_1().is_icrc2() {
+        Err("Tokens must support ICRC2".to_string())?
+    }
+
+    let (min_tick, max_tick) = concentrated_math::full_range(tick_spacing);
+    if args.tick_lower >= args.tick_upper {
+        Err("tick_lower must be less than tick_upper".to_string())?
+    }
+    if args.tick_lower < min_tick || args.tick_upper > max_tick {
+        Err(format!("Ticks must be between {} and {}", min_tick, max_tick))?
+    }
+    if args.tick_lower % tick_spacing as i32 != 0 || args.tick_upper % tick_spacing as i32 != 0 {
+        Err(format!("Ticks must be multiples of the tick spacing {}", tick_spacing))?
+    }
+
+    let (_, amount_0, amount_1) = position_liquidity(&pool, args.tick_lower, args.tick_upper, &args.amount_0, &args.amount_1)?;
+
+    // make sure user is registered, if not create a new user
+    let user_id = user_map::insert(None)?;
+
+    Ok((user_id, pool, amount_0, amount_1))
+}
+
+/// liquidity for the range with at most max_amount_0 and max_amount_1 at the pool price
+/// returns the liquidity and the amounts of token_0 and token_1 it needs, rounded up
+fn position_liquidity(
+    pool: &StablePool,
+    tick_lower: i32,
+    tick_upper: i32,
+    max_amount_0: &Nat,
+    max_amount_1: &Nat,
+) -> Result<(Nat, Nat, Nat), String> {
+    let state = pool.concentrated.as_ref().ok_or("Pool is not a concentrated liquidity pool")?;
+    let token_0 = pool.token_0();
+    let token_1 = pool.token_1();
+    let max_decimals = std::cmp::max(token_0.decimals(), token_1.decimals());
+    let sqrt_price_lower = concentrated_math::sqrt_price_at_tick(tick_lower).ok_or("Invalid tick_lower")?;
+    let sqrt_price_upper = concentrated_math::sqrt_price_at_tick(tick_upper).ok_or("Invalid tick_upper")?;
+
+    let liquidity = concentrated_math::liquidity_for_amounts(
+        &state.sqrt_price,
+        &sqrt_price_lower,
+        &sqrt_price_upper,
+        &nat_to_decimal_precision(max_amount_0, token_0.decimals(), max_decimals),
+        &nat_to_decimal_precision(max_amount_1, token_1.decimals(), max_decimals),
+    )
+    .ok_or("Invalid liquidity")?;
+    if nat_is_zero(&liquidity) {
+        Err("Amounts are too small for the price range".to_string())?
+    }
+    let (amount_0, amount_1) =
+        concentrated_math::amounts_for_liquidity(&state.sqrt_price, &sqrt_price_lower, &sqrt_price_upper, &liquidity, true)
+            .ok_or("Invalid position amounts")?;
+
+    Ok((
+        liquidity,
+        to_token_decimals_ceil(&amount_0, max_decimals, token_0.decimals())?,
+        to_token_decimals_ceil(&amount_1, max_decimals, token_1.decimals())?,
+    ))
+}
+
+/// convert an amount in max_decimals to the decimals of a token, rounded up
+fn to_token_decimals_ceil(amount: &Nat, max_decimals: u8, decimals: u8) -> Result<Nat, String> {
+    nat_divide_ceil(amount, &nat_10pow(max_decimals - decimals)).ok_or("Invalid amount".to_string())
+}
+
+async fn process_mint_position(
+    request_id: u64,
+    user_id: u32,
+    pool: &StablePool,
+    amount_0: &Nat,
+    amount_1: &Nat,
+    args: &MintPositionArgs,
+    ts: u64,
+) -> Result<PositionReply, String> {
+    let caller_id = ICNetwork::caller_id();
+    let kong_backend = kong_settings_map::get().kong_backend;
+    let token_0 = pool.token_0();
+    let token_1 = pool.token_1();
+    let mut transfer_ids = Vec::new();
+    let mut claim_ids = Vec::new();
+
+    request_map::update_status(request_id, StatusCode::Start, None);
+
+    // a position out of range only needs one of the tokens
+    let transfer_0 = if nat_is_zero(amount_0) {
+        Ok(())
+    } else {
+        transfer_from_token(
+            request_id,
+            &caller_id,
+            &TokenIndex::Token0,
+            &token_0,
+            amount_0,
+            &kong_backend,
+            &mut transfer_ids,
+            ts,
+        )
+        .await
+    };
+    if let Err(e) = transfer_0 {
+        Err(format!("Req #{} failed. {}", request_id, e))?
+    }
+    let transfer_1 = if nat_is_zero(amount_1) {
+        Ok(())
+    } else {
+        transfer_from_token(
+            request_id,
+            &caller_id,
+            &TokenIndex::Token1,
+            &token_1,
+            amount_1,
+            &kong_backend,
+            &mut transfer_ids,
+            ts,
+        )
+        .await
+    };
+    if let Err(e) = transfer_1 {
+        // return token_0
+        return_token(
+            request_id,
+            user_id,
+            &caller_id,
+            &TokenIndex::Token0,
+            &token_0,
+            amount_0,
+            &mut transfer_ids,
+            &mut claim_ids,
+            ts,
+        )
+        .await;
+        failed_reply(request_id, user_id, pool, args, &transfer_ids, &claim_ids, ts);
+        Err(format!("Req #{} failed. {}", request_id, e))?
+    }
+
+    request_map::update_status(request_id, StatusCode::UpdatePoolAmounts, None);
+    let (position, return_amount_0, return_amount_1) = match add_position(pool.pool_id, user_id, amount_0, amount_1, args, ts) {
+        Ok(minted) => {
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
+            minted
+        }
+        Err(e) => {
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsFailed, Some(&e));
+            return_token(
+                request_id,
+                user_id,
+                &caller_id,
+                &TokenIndex::Token0,
+                &token_0,
+                amount_0,
+                &mut transfer_ids,
+                &mut claim_ids,
+                ts,
+            )
+            .await;
+            return_token(
+                request_id,
+                user_id,
+                &caller_id,
+                &TokenIndex::Token1,
+                &token_1,
+                amount_1,
+                &mut transfer_ids,
+                &mut claim_ids,
+                ts,
+            )
+            .await;
+            failed_reply(request_id, user_id, pool, args, &transfer_ids, &claim_ids, ts);
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    };
+    request_map::update_status(
+        request_id,
+        StatusCode::PositionMinted,
+        Some(&format!("Position #{}", position.position_id)),
+    );
+
+    // return the amounts not needed for the liquidity
+    return_unused_token(
+        request_id,
+        user_id,
+        &caller_id,
+        &TokenIndex::Token0,
+        &token_0,
+        &return_amount_0,
+        &mut transfer_ids,
+        &mut claim_ids,
+        ts,
+    )
+    .await;
+    return_unused_token(
+        request_id,
+        user_id,
+        &caller_id,
+        &TokenIndex::Token1,
+        &token_1,
+        &return_amount_1,
+        &mut transfer_ids,
+        &mut claim_ids,
+        ts,
+    )
+    .await;
+
+    let reply = PositionReply {
+        request_id,
+        transfer_ids: to_transfer_id_replies(&transfer_ids),
+        claim_ids,
+        ..PositionReply::from(&position)
+    };
+    request_map::update_reply(request_id, Reply::Position(reply.clone()));
+
+    Ok(reply)
+}
+
+/// add the position to the pool with its latest state, as the price could have moved during the transfers
+/// returns the position and the amounts of token_0 and token_1 to return
+fn add_position(
+    pool_id: u32,
+    user_id: u32,
+    amount_0: &Nat,
+    amount_1: &Nat,
+    args: &MintPositionArgs,
+    ts: u64,
+) -> Result<(StableLPPosition, Nat, Nat), String> {
+    let mut pool = pool_map::get_by_pool_id(pool_id).ok_or("Pool not found")?;
+    let (liquidity, used_amount_0, used_amount_1) = position_liquidity(&pool, args.tick_lower, args.tick_upper, amount_0, amount_1)?;
+    let mut position = StableLPPosition::new(user_id, pool_id, args.tick_lower, args.tick_upper, ts);
+    concentrated_liquidity::update_position(&mut pool, &mut position, &liquidity, true)?;
+    position.position_id = lp_position_map::insert(&position);
+
+    // amounts not needed for the liquidity are returned, unless too small to pay the gas fee
+    let return_amount = |amount: &Nat, used_amount: &Nat, fee: Nat| -> Nat {
+        let unused_amount = nat_subtract(amount, used_amount).unwrap_or(nat_zero());
+        if unused_amount > fee {
+            unused_amount
+        } else {
+            nat_zero()
+        }
+    };
+    let return_amount_0 = return_amount(amount_0, &used_amount_0, pool.token_0().fee());
+    let return_amount_1 = return_amount(amount_1, &used_amount_1, pool.token_1().fee());
+    pool.balance_0 = nat_add(&pool.balance_0, &nat_subtract(amount_0, &return_amount_0).unwrap_or(nat_zero()));
+    pool.balance_1 = nat_add(&pool.balance_1, &nat_subtract(amount_1, &return_amount_1).unwrap_or(nat_zero()));
+    pool_map::update(&pool);
+
+    Ok((position, return_amount_0, return_amount_1))
+}
+
+fn failed_reply(
+    request_id: u64,
+    user_id: u32,
+    pool: &StablePool,
+    args: &MintPositionArgs,
+    transfer_ids: &[u64],
+    claim_ids: &[u64],
+    ts: u64,
+) {
+    let reply = PositionReply {
+        status: StatusCode::Failed.to_string(),
+        request_id,
+        transfer_ids: to_transfer_id_replies(transfer_ids),
+        claim_ids: claim_ids.to_vec(),
+        ..PositionReply::from(&StableLPPosition::new(user_id, pool.pool_id, args.tick_lower, args.tick_upper, ts))
+    };
+    request_map::update_reply(request_id, Reply::Position(reply));
+}
+
+/// transfer ids of a position request for the reply
+pub fn to_transfer_id_replies(transfer_ids: &[u64]) -> Vec<TransferIdReply> {
+    transfer_ids
+        .iter()
+        .filter_map(|&transfer_id| {
+            let transfer = transfer_map::get_by_transfer_id(transfer_id)?;
+            let token = token_map::get_by_token_id(transfer.token_id)?;
+            TransferIdReply::try_from((transfer_id, &transfer, &token)).ok()
+        })
+        .collect()
+}
@@ -0,0 +1,15 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+/// Data structure for the arguments of the `mint_position` function.
+/// Used in StableRequest
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct MintPositionArgs {
+    pub token_0: String,
+    pub amount_0: Nat, // maximum amount of token_0 to deposit
+    pub token_1: String,
+    pub amount_1: Nat,          // maximum amount of token_1 to deposit
+    pub lp_fee_bps: Option<u8>, // fee tier of the pool, defaults to the first pool of the token pair
+    pub tick_lower: i32,        // lower tick of the price range. must be a multiple of the pool's tick spacing
+    pub tick_upper: i32,        // upper tick of the price range. must be a multiple of the pool's tick spacing
+}
@@ -0,0 +1,8 @@
+pub mod burn_position;
+pub mod collect_fees;
+pub mod mint_position;
+pub mod mint_position_args;
+pub mod position_reply;
+#[allow(clippy::module_inception)]
+pub mod positions;
+pub mod transfer_position_token;
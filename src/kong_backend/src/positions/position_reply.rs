@@ -0,0 +1,120 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+use std::convert::From;
+
+use crate::helpers::math_helpers::price_rounded;
+use crate::helpers::nat_helpers::{nat_to_decimal_precision, nat_zero};
+use crate::stable_lp_token::stable_lp_position::StableLPPosition;
+use crate::stable_pool::{concentrated_liquidity, concentrated_math, pool_map};
+use crate::stable_token::token::Token;
+use crate::transfers::transfer_reply::TransferIdReply;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct PositionReply {
+    pub position_id: u64,
+    pub request_id: u64, // request of the mint, burn or collect. 0 when queried with positions()
+    pub status: String,
+    pub symbol: String,
+    pub lp_fee_bps: u8,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub price_lower: f64, // price of token_0 in token_1 at tick_lower
+    pub price_upper: f64, // price of token_0 in token_1 at tick_upper
+    pub in_range: bool,   // true if the pool price is in the range and the position is earning fees
+    pub liquidity: Nat,
+    pub chain_0: String,
+    pub address_0: String,
+    pub symbol_0: String,
+    pub amount_0: Nat, // token_0 of the position at the pool price
+    pub fees_0: Nat,   // uncollected fees of token_0
+    pub chain_1: String,
+    pub address_1: String,
+    pub symbol_1: String,
+    pub amount_1: Nat, // token_1 of the position at the pool price
+    pub fees_1: Nat,   // uncollected fees of token_1
+    pub transfer_ids: Vec<TransferIdReply>,
+    pub claim_ids: Vec<u64>,
+    pub ts: u64,
+}
+
+impl From<&StableLPPosition> for PositionReply {
+    fn from(position: &StableLPPosition) -> Self {
+        let price_at_tick = |tick: i32| -> f64 {
+            concentrated_math::sqrt_price_at_tick(tick)
+                .and_then(|sqrt_price| concentrated_math::spot_price(&sqrt_price))
+                .and_then(|price| price_rounded(&price))
+                .unwrap_or(0_f64)
+        };
+        let pool = pool_map::get_by_pool_id(position.pool_id);
+        let (symbol, lp_fee_bps, in_range) = match pool {
+            Some(ref pool) => (
+                pool.symbol(),
+                pool.lp_fee_bps,
+                pool.concentrated
+                    .as_ref()
+                    .is_some_and(|state| position.tick_lower <= state.tick && state.tick < position.tick_upper),
+            ),
+            None => ("Pool not found".to_string(), 0, false),
+        };
+        let (chain_0, address_0, symbol_0, amount_0, fees_0, chain_1, address_1, symbol_1, amount_1, fees_1) = match pool {
+            Some(ref pool) => {
+                let token_0 = pool.token_0();
+                let token_1 = pool.token_1();
+                let max_decimals = std::cmp::max(token_0.decimals(), token_1.decimals());
+                let (amount_0, amount_1) = concentrated_liquidity::position_amounts(pool, position).unwrap_or((nat_zero(), nat_zero()));
+                let (fees_0, fees_1) = concentrated_liquidity::position_fees(pool, position);
+                (
+                    token_0.chain(),
+                    token_0.address(),
+                    token_0.symbol(),
+                    nat_to_decimal_precision(&amount_0, max_decimals, token_0.decimals()),
+                    fees_0,
+                    token_1.chain(),
+                    token_1.address(),
+                    token_1.symbol(),
+                    nat_to_decimal_precision(&amount_1, max_decimals, token_1.decimals()),
+                    fees_1,
+                )
+            }
+            None => (
+                "Chain_0 not found".to_string(),
+                "Address_0 not found".to_string(),
+                "Symbol_0 not found".to_string(),
+                nat_zero(),
+                position.fees_owed_0.clone(),
+                "Chain_1 not found".to_string(),
+                "Address_1 not found".to_string(),
+                "Symbol_1 not found".to_string(),
+                nat_zero(),
+                position.fees_owed_1.clone(),
+            ),
+        };
+
+        PositionReply {
+            position_id: position.position_id,
+            request_id: 0,
+            status: "Success".to_string(),
+            symbol,
+            lp_fee_bps,
+            tick_lower: position.tick_lower,
+            tick_upper: position.tick_upper,
+            price_lower: price_at_tick(position.tick_lower),
+            price_upper: price_at_tick(position.tick_upper),
+            in_range,
+            liquidity: position.liquidity.clone(),
+            chain_0,
+            address_0,
+            symbol_0,
+            amount_0,
+            fees_0,
+            chain_1,
+            address_1,
+            symbol_1,
+            amount_1,
+            fees_1,
+            transfer_ids: Vec::new(),
+            claim_ids: Vec::new(),
+            ts: position.ts,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use ic_cdk::query;
+
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_lp_token::lp_position_map;
+use crate::stable_lp_token::stable_lp_position::StableLPPosition;
+use crate::stable_user::user_map;
+
+use super::position_reply::PositionReply;
+
+/// Return all concentrated liquidity positions of a user, latest first
+#[query(guard = "not_in_maintenance_mode")]
+fn positions(principal_id: String) -> Result<Vec<PositionReply>, String> {
+    let user_id = user_map::get_by_principal_id(&principal_id)
+        .ok()
+        .flatten()
+        .ok_or("User not found")?
+        .user_id;
+
+    Ok(lp_position_map::get_by_user_id(user_id).iter().map(PositionReply::from).collect())
+}
+
+/// position of the caller
+pub fn get_caller_position(position_id: u64) -> Result<StableLPPosition, String> {
+    let position = lp_position_map::get_by_position_id(position_id).ok_or("Position not found")?;
+    // make sure the caller is the owner of the position
+    let user_id = user_map::get_by_principal_id(&ICNetwork::caller().to_text())
+        .ok()
+        .flatten()
+        .ok_or("User not found")?
+        .user_id;
+    if position.user_id != user_id {
+        return Err("Position not found".to_string());
+    }
+    Ok(position)
+}
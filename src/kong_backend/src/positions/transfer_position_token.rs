@@ -0,0 +1,154 @@
+use candid::Nat;
+use icrc_ledger_types::icrc1::account::Account;
+
+use crate::add_liquidity::add_liquidity::TokenIndex;
+use crate::helpers::nat_helpers::{nat_is_zero, nat_subtract, nat_zero};
+use crate::ic::{address::Address, transfer::icrc1_transfer};
+use crate::stable_claim::{claim_map, stable_claim::StableClaim};
+use crate::stable_request::{request_map, status::StatusCode};
+use crate::stable_token::{stable_token::StableToken, token::Token, token_management::handle_failed_transfer};
+use crate::stable_transfer::{stable_transfer::StableTransfer, transfer_map, tx_id::TxId};
+
+/// Send amount less the gas fee of token to the user. Saved as a claim if the transfer fails
+/// returns the error with the claim id if the transfer failed
+#[allow(clippy::too_many_arguments)]
+pub async fn transfer_position_token(
+    request_id: u64,
+    user_id: u32,
+    to_principal_id: &Account,
+    token: &StableToken,
+    amount: &Nat,
+    transfer_ids: &mut Vec<u64>,
+    claim_ids: &mut Vec<u64>,
+    ts: u64,
+) -> Result<(), String> {
+    let token_id = token.token_id();
+
+    let amount_with_gas = nat_subtract(amount, &token.fee()).unwrap_or(nat_zero());
+    match icrc1_transfer(&amount_with_gas, to_principal_id, token, None).await {
+        Ok(block_id) => {
+            let transfer_id = transfer_map::insert(&StableTransfer {
+                transfer_id: 0,
+                request_id,
+                is_send: false,
+                amount: amount_with_gas,
+                token_id,
+                tx_id: TxId::BlockIndex(block_id),
+                ts,
+            });
+            transfer_ids.push(transfer_id);
+            Ok(())
+        }
+        Err(e) => {
+            let claim = StableClaim::new(
+                user_id,
+                token_id,
+                amount,
+                Some(request_id),
+                Some(Address::PrincipalId(*to_principal_id)),
+                ts,
+            );
+            let claim_id = claim_map::insert(&claim);
+            claim_ids.push(claim_id);
+            let message = format!("Saved as claim #{}. {}", claim_id, e);
+            handle_failed_transfer(token, e);
+            Err(message)
+        }
+    }
+}
+
+/// Pay out token_0 or token_1 of a position to the user
+#[allow(clippy::too_many_arguments)]
+pub async fn send_position_token(
+    request_id: u64,
+    user_id: u32,
+    to_principal_id: &Account,
+    token_index: &TokenIndex,
+    token: &StableToken,
+    amount: &Nat,
+    transfer_ids: &mut Vec<u64>,
+    claim_ids: &mut Vec<u64>,
+    ts: u64,
+) {
+    if nat_is_zero(amount) {
+        return;
+    }
+    match token_index {
+        TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReceiveToken0, None),
+        TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReceiveToken1, None),
+    };
+    match transfer_position_token(request_id, user_id, to_principal_id, token, amount, transfer_ids, claim_ids, ts).await {
+        Ok(()) => match token_index {
+            TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReceiveToken0Success, None),
+            TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReceiveToken1Success, None),
+        },
+        Err(e) => match token_index {
+            TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReceiveToken0Failed, Some(&e)),
+            TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReceiveToken1Failed, Some(&e)),
+        },
+    };
+}
+
+#[allow(clippy::too_many_arguments)]
+/// Return a deposited token to the user, e.g. after a failed request
+pub async fn return_token(
+    request_id: u64,
+    user_id: u32,
+    to_principal_id: &Account,
+    token_index: &TokenIndex,
+    token: &StableToken,
+    amount: &Nat,
+    transfer_ids: &mut Vec<u64>,
+    claim_ids: &mut Vec<u64>,
+    ts: u64,
+) {
+    if nat_is_zero(amount) {
+        return;
+    }
+    match token_index {
+        TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnToken0, None),
+        TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnToken1, None),
+    };
+    match transfer_position_token(request_id, user_id, to_principal_id, token, amount, transfer_ids, claim_ids, ts).await {
+        Ok(()) => match token_index {
+            TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnToken0Success, None),
+            TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnToken1Success, None),
+        },
+        Err(e) => match token_index {
+            TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnToken0Failed, Some(&e)),
+            TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnToken1Failed, Some(&e)),
+        },
+    };
+}
+
+#[allow(clippy::too_many_arguments)]
+/// Return the part of a deposited token not needed for the liquidity
+pub async fn return_unused_token(
+    request_id: u64,
+    user_id: u32,
+    to_principal_id: &Account,
+    token_index: &TokenIndex,
+    token: &StableToken,
+    amount: &Nat,
+    transfer_ids: &mut Vec<u64>,
+    claim_ids: &mut Vec<u64>,
+    ts: u64,
+) {
+    if nat_is_zero(amount) {
+        return;
+    }
+    match token_index {
+        TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnUnusedToken0, None),
+        TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnUnusedToken1, None),
+    };
+    match transfer_position_token(request_id, user_id, to_principal_id, token, amount, transfer_ids, claim_ids, ts).await {
+        Ok(()) => match token_index {
+            TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnUnusedToken0Success, None),
+            TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnUnusedToken1Success, None),
+        },
+        Err(e) => match token_index {
+            TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReturnUnusedToken0Failed, Some(&e)),
+            TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReturnUnusedToken1Failed, Some(&e)),
+        },
+    };
+}
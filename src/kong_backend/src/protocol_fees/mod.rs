@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod protocol_fees;
+pub mod protocol_fees_reply;
+pub mod sweep_protocol_fees;
+pub mod sweep_protocol_fees_reply;
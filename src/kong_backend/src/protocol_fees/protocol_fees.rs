@@ -0,0 +1,60 @@
+use candid::Nat;
+use ic_cdk::query;
+use std::collections::BTreeMap;
+
+use crate::helpers::nat_helpers::{nat_add, nat_is_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_pool::pool_map;
+use crate::stable_token::token::Token;
+use crate::stable_token::token_map;
+
+use super::protocol_fees_reply::{PoolProtocolFeesReply, ProtocolFeesReply, TokenProtocolFeesReply};
+
+/// Return Kong's fees accrued and already swept to the treasury, per pool and per token
+#[query(guard = "not_in_maintenance_mode")]
+fn protocol_fees() -> Result<ProtocolFeesReply, String> {
+    let pools = pool_map::get_with_removed();
+
+    // (accrued, swept) by token_id
+    let mut token_fees: BTreeMap<u32, (Nat, Nat)> = BTreeMap::new();
+    for pool in pools.iter() {
+        for (token_id, accrued, swept) in [
+            (pool.token_id_0, &pool.kong_fee_0, &pool.swept_kong_fee_0),
+            (pool.token_id_1, &pool.kong_fee_1, &pool.swept_kong_fee_1),
+        ] {
+            let (token_accrued, token_swept) = token_fees.entry(token_id).or_default();
+            *token_accrued = nat_add(token_accrued, accrued);
+            *token_swept = nat_add(token_swept, swept);
+        }
+    }
+
+    let tokens = token_fees
+        .into_iter()
+        .filter_map(|(token_id, (accrued, swept))| {
+            let token = token_map::get_by_token_id(token_id)?;
+            Some(TokenProtocolFeesReply {
+                chain: token.chain(),
+                symbol: token.symbol(),
+                address: token.address(),
+                accrued,
+                swept,
+            })
+        })
+        .collect();
+
+    Ok(ProtocolFeesReply {
+        treasury: kong_settings_map::get().treasury.map(|treasury| treasury.to_string()),
+        tokens,
+        pools: pools
+            .iter()
+            .filter(|pool| {
+                !(nat_is_zero(&pool.kong_fee_0)
+                    && nat_is_zero(&pool.swept_kong_fee_0)
+                    && nat_is_zero(&pool.kong_fee_1)
+                    && nat_is_zero(&pool.swept_kong_fee_1))
+            })
+            .map(PoolProtocolFeesReply::from)
+            .collect(),
+    })
+}
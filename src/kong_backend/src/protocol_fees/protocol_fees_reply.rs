@@ -0,0 +1,49 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::stable_pool::stable_pool::StablePool;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct PoolProtocolFeesReply {
+    pub pool_id: u32,
+    pub symbol: String,
+    pub lp_fee_bps: u8,
+    pub symbol_0: String,
+    pub accrued_0: Nat, // Kong's fee not swept yet
+    pub swept_0: Nat,   // Kong's fee swept to the treasury
+    pub symbol_1: String,
+    pub accrued_1: Nat,
+    pub swept_1: Nat,
+}
+
+impl From<&StablePool> for PoolProtocolFeesReply {
+    fn from(pool: &StablePool) -> Self {
+        PoolProtocolFeesReply {
+            pool_id: pool.pool_id,
+            symbol: pool.symbol(),
+            lp_fee_bps: pool.lp_fee_bps,
+            symbol_0: pool.symbol_0(),
+            accrued_0: pool.kong_fee_0.clone(),
+            swept_0: pool.swept_kong_fee_0.clone(),
+            symbol_1: pool.symbol_1(),
+            accrued_1: pool.kong_fee_1.clone(),
+            swept_1: pool.swept_kong_fee_1.clone(),
+        }
+    }
+}
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct TokenProtocolFeesReply {
+    pub chain: String,
+    pub symbol: String,
+    pub address: String,
+    pub accrued: Nat,
+    pub swept: Nat,
+}
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolFeesReply {
+    pub treasury: Option<String>,
+    pub tokens: Vec<TokenProtocolFeesReply>,
+    pub pools: Vec<PoolProtocolFeesReply>,
+}
@@ -0,0 +1,250 @@
+use ic_cdk::update;
+use icrc_ledger_types::icrc1::account::Account;
+use std::collections::BTreeMap;
+
+use crate::helpers::nat_helpers::{nat_add, nat_is_zero, nat_subtract, nat_zero};
+use crate::ic::guards::caller_is_kingkong;
+use crate::ic::network::ICNetwork;
+use crate::ic::transfer::icrc1_transfer;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_pool::pool_map;
+use crate::stable_request::{request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+use crate::stable_token::stable_token::StableToken;
+use crate::stable_token::token::Token;
+use crate::stable_token::token_management::handle_failed_transfer;
+use crate::stable_token::token_map;
+use crate::stable_transfer::{stable_transfer::StableTransfer, transfer_map, tx_id::TxId};
+use crate::stable_tx::protocol_fee_sweep_tx::{ProtocolFeeSweepTx, SweptPoolFee};
+use crate::stable_tx::stable_tx::StableTx;
+use crate::stable_tx::status_tx::StatusTx;
+use crate::stable_tx::tx_map;
+use crate::stable_user::user_map;
+
+use super::sweep_protocol_fees_reply::SweepProtocolFeesReply;
+
+/// Transfer Kong's fees accrued in all pools to the treasury account in Kong settings
+/// one transfer is made per token. tokens where the fees do not cover the gas fee are left to accrue
+#[update(guard = "caller_is_kingkong")]
+async fn sweep_protocol_fees() -> Result<Vec<SweepProtocolFeesReply>, String> {
+    let treasury = kong_settings_map::get().treasury.ok_or("Treasury account not set")?;
+    let user_id = user_map::insert(None)?;
+    let ts = ICNetwork::get_time();
+    let request_id = request_map::insert(&StableRequest::new(user_id, &Request::SweepProtocolFees, ts));
+
+    // zero Kong's fee of the pools before any inter-canister call so swaps in between accrue to the next sweep
+    let sweeps = take_protocol_fees();
+
+    let mut replies = Vec::new();
+    let mut failed = false;
+    for (token, pools) in sweeps {
+        let protocol_fee_sweep_tx = sweep_token(request_id, user_id, &token, &pools, &treasury, ts).await;
+        failed |= protocol_fee_sweep_tx.status == StatusTx::Failed;
+        let tx_id = tx_map::insert(&StableTx::ProtocolFeeSweep(protocol_fee_sweep_tx.clone()));
+        _ = tx_map::archive_to_kong_data(tx_id);
+        replies.push(SweepProtocolFeesReply::from(&ProtocolFeeSweepTx {
+            tx_id,
+            ..protocol_fee_sweep_tx
+        }));
+    }
+
+    if failed {
+        request_map::update_status(request_id, StatusCode::Failed, None);
+    } else {
+        request_map::update_status(request_id, StatusCode::Success, None);
+    }
+
+    Ok(replies)
+}
+
+/// take Kong's fee out of all pools for each IC token where the total covers the gas fee
+fn take_protocol_fees() -> Vec<(StableToken, Vec<SweptPoolFee>)> {
+    let pools = pool_map::get_with_removed();
+
+    let mut sweeps: BTreeMap<u32, Vec<SweptPoolFee>> = BTreeMap::new();
+    for pool in pools.iter() {
+        for (token_id, kong_fee) in [(pool.token_id_0, &pool.kong_fee_0), (pool.token_id_1, &pool.kong_fee_1)] {
+            if !nat_is_zero(kong_fee) {
+                sweeps.entry(token_id).or_default().push(SweptPoolFee {
+                    pool_id: pool.pool_id,
+                    amount: kong_fee.clone(),
+                });
+            }
+        }
+    }
+    let sweeps: Vec<_> = sweeps
+        .into_iter()
+        .filter_map(|(token_id, pools)| {
+            // only IC tokens can be sent to the treasury account
+            let token = token_map::get_by_token_id(token_id).filter(|token| matches!(token, StableToken::IC(_)))?;
+            let amount = pools.iter().fold(nat_zero(), |acc, pool| nat_add(&acc, &pool.amount));
+            if amount <= token.fee() {
+                return None;
+            }
+            Some((token, pools))
+        })
+        .collect();
+
+    for (token, pools) in sweeps.iter() {
+        for swept_pool_fee in pools {
+            let Some(mut pool) = pool_map::get_by_pool_id(swept_pool_fee.pool_id) else {
+                continue;
+            };
+            // take out only the swept amount, never overwrite the pool's Kong fee
+            if pool.token_id_0 == token.token_id() {
+                pool.kong_fee_0 = nat_subtract(&pool.kong_fee_0, &swept_pool_fee.amount).unwrap_or(nat_zero());
+            } else {
+                pool.kong_fee_1 = nat_subtract(&pool.kong_fee_1, &swept_pool_fee.amount).unwrap_or(nat_zero());
+            }
+            pool_map::update(&pool);
+        }
+    }
+
+    sweeps
+}
+
+/// send the swept fees of a token to the treasury. if the transfer fails, the fees are returned to the pools
+async fn sweep_token(
+    request_id: u64,
+    user_id: u32,
+    token: &StableToken,
+    pools: &[SweptPoolFee],
+    treasury: &Account,
+    ts: u64,
+) -> ProtocolFeeSweepTx {
+    let token_id = token.token_id();
+    let amount = pools.iter().fold(nat_zero(), |acc, pool| nat_add(&acc, &pool.amount));
+    let mut transfer_ids = Vec::new();
+
+    request_map::update_status(request_id, StatusCode::SweepProtocolFees, Some(&token.symbol()));
+
+    let amount_less_gas = nat_subtract(&amount, &token.fee()).unwrap_or(nat_zero());
+    let status = match icrc1_transfer(&amount_less_gas, treasury, token, None).await {
+        Ok(block_id) => {
+            let transfer_id = transfer_map::insert(&StableTransfer {
+                transfer_id: 0,
+                request_id,
+                is_send: false,
+                amount: amount_less_gas,
+                token_id,
+                tx_id: TxId::BlockIndex(block_id),
+                ts,
+            });
+            transfer_ids.push(transfer_id);
+            update_pools(token_id, pools, true);
+            request_map::update_status(request_id, StatusCode::SweepProtocolFeesSuccess, Some(&token.symbol()));
+            StatusTx::Success
+        }
+        Err(e) => {
+            update_pools(token_id, pools, false);
+            let message = format!("{} {}", token.symbol(), e);
+            request_map::update_status(request_id, StatusCode::SweepProtocolFeesFailed, Some(&message));
+            handle_failed_transfer(token, e);
+            StatusTx::Failed
+        }
+    };
+
+    ProtocolFeeSweepTx::new(
+        user_id,
+        request_id,
+        status,
+        token_id,
+        &amount,
+        pools,
+        &treasury.to_string(),
+        &transfer_ids,
+        ts,
+    )
+}
+
+/// add the swept fees to the pools' swept totals, or back to Kong's fee if the sweep failed
+/// the pools are refreshed so fees accrued by swaps during the transfer are kept
+fn update_pools(token_id: u32, pools: &[SweptPoolFee], is_swept: bool) {
+    for swept_pool_fee in pools {
+        let Some(mut pool) = pool_map::get_by_pool_id(swept_pool_fee.pool_id) else {
+            continue;
+        };
+        match (pool.token_id_0 == token_id, is_swept) {
+            (true, true) => pool.swept_kong_fee_0 = nat_add(&pool.swept_kong_fee_0, &swept_pool_fee.amount),
+            (true, false) => pool.kong_fee_0 = nat_add(&pool.kong_fee_0, &swept_pool_fee.amount),
+            (false, true) => pool.swept_kong_fee_1 = nat_add(&pool.swept_kong_fee_1, &swept_pool_fee.amount),
+            (false, false) => pool.kong_fee_1 = nat_add(&pool.kong_fee_1, &swept_pool_fee.amount),
+        }
+        pool_map::update(&pool);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Nat;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_ic_token, insert_pool};
+    use crate::stable_memory::POOL_MAP;
+    use crate::stable_pool::stable_pool::StablePoolId;
+
+    const ICP: u32 = 1;
+    const CKUSDT: u32 = 2;
+
+    fn set_kong_fees(pool_id: u32, kong_fee_0: u64, kong_fee_1: u64) {
+        let mut pool = pool_map::get_by_pool_id(pool_id).unwrap();
+        pool.kong_fee_0 = Nat::from(kong_fee_0);
+        pool.kong_fee_1 = Nat::from(kong_fee_1);
+        POOL_MAP.with(|m| m.borrow_mut().insert(StablePoolId(pool_id), pool));
+    }
+
+    fn setup() {
+        insert_ic_token(ICP, "ICP", 8);
+        insert_ic_token(CKUSDT, "ckUSDT", 6);
+        init_kong_settings();
+        insert_pool(1, ICP, 1_000_000_000, CKUSDT, 1_000_000_000);
+        // ICP fees cover the 10_000 gas fee, ckUSDT fees do not
+        set_kong_fees(1, 50_000, 5_000);
+    }
+
+    #[test]
+    fn test_take_protocol_fees_leaves_fees_below_gas() {
+        setup();
+
+        let sweeps = take_protocol_fees();
+
+        assert_eq!(sweeps.len(), 1);
+        let (token, pools) = &sweeps[0];
+        assert_eq!(token.token_id(), ICP);
+        assert_eq!(pools[0].amount, Nat::from(50_000_u64));
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.kong_fee_0, nat_zero());
+        assert_eq!(pool.kong_fee_1, Nat::from(5_000_u64));
+    }
+
+    #[test]
+    fn test_update_pools_failed_sweep_adds_fees_back() {
+        setup();
+        let sweeps = take_protocol_fees();
+        let (_, pools) = &sweeps[0];
+
+        // a swap accrues more fees while the transfer is in flight
+        set_kong_fees(1, 7_000, 6_000);
+        update_pools(ICP, pools, false);
+
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.kong_fee_0, Nat::from(57_000_u64));
+        assert_eq!(pool.kong_fee_1, Nat::from(6_000_u64));
+        assert_eq!(pool.swept_kong_fee_0, nat_zero());
+    }
+
+    #[test]
+    fn test_update_pools_swept_adds_to_swept_total() {
+        setup();
+        let sweeps = take_protocol_fees();
+        let (_, pools) = &sweeps[0];
+
+        set_kong_fees(1, 7_000, 6_000);
+        update_pools(ICP, pools, true);
+        update_pools(ICP, pools, true);
+
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.kong_fee_0, Nat::from(7_000_u64));
+        assert_eq!(pool.swept_kong_fee_0, Nat::from(100_000_u64));
+        assert_eq!(pool.swept_kong_fee_1, nat_zero());
+    }
+}
@@ -0,0 +1,49 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::stable_token::token::Token;
+use crate::stable_token::token_map;
+use crate::stable_transfer::transfer_map;
+use crate::stable_tx::protocol_fee_sweep_tx::ProtocolFeeSweepTx;
+use crate::transfers::transfer_reply::TransferIdReply;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct SweepProtocolFeesReply {
+    pub tx_id: u64,
+    pub request_id: u64,
+    pub status: String,
+    pub chain: String,
+    pub symbol: String,
+    pub amount: Nat,
+    pub to_address: String,
+    pub transfer_ids: Vec<TransferIdReply>,
+    pub ts: u64,
+}
+
+impl From<&ProtocolFeeSweepTx> for SweepProtocolFeesReply {
+    fn from(protocol_fee_sweep_tx: &ProtocolFeeSweepTx) -> Self {
+        let (chain, symbol) = match token_map::get_by_token_id(protocol_fee_sweep_tx.token_id) {
+            Some(token) => (token.chain(), token.symbol()),
+            None => ("".to_string(), "".to_string()),
+        };
+        SweepProtocolFeesReply {
+            tx_id: protocol_fee_sweep_tx.tx_id,
+            request_id: protocol_fee_sweep_tx.request_id,
+            status: protocol_fee_sweep_tx.status.to_string(),
+            chain,
+            symbol,
+            amount: protocol_fee_sweep_tx.amount.clone(),
+            to_address: protocol_fee_sweep_tx.to_address.clone(),
+            transfer_ids: protocol_fee_sweep_tx
+                .transfer_ids
+                .iter()
+                .filter_map(|&transfer_id| {
+                    let transfer = transfer_map::get_by_transfer_id(transfer_id)?;
+                    let token = token_map::get_by_token_id(transfer.token_id)?;
+                    TransferIdReply::try_from((transfer_id, &transfer, &token)).ok()
+                })
+                .collect(),
+            ts: protocol_fee_sweep_tx.ts,
+        }
+    }
+}
@@ -22,6 +22,12 @@ pub fn validate_arguments() -> Result<(), String> {
         if remove_args.token_1.starts_with("SOL.") && remove_args.payout_address_1.is_none() {
             return Err("Solana token requires payout_address_1".to_string());
         }
+        if remove_args.token_0.starts_with("XRP.") && remove_args.payout_address_0.is_none() {
+            return Err("XRPL token requires payout_address_0".to_string());
+        }
+        if remove_args.token_1.starts_with("XRP.") && remove_args.payout_address_1.is_none() {
+            return Err("XRPL token requires payout_address_1".to_string());
+        }
     }
 
     Ok(())
@@ -2,27 +2,33 @@ use candid::Nat;
 use ic_cdk::update;
 use icrc_ledger_types::icrc1::account::Account;
 
-use crate::chains::chains::SOL_CHAIN;
+use crate::chains::chains::{SOL_CHAIN, XRP_CHAIN};
 use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply, nat_subtract, nat_zero};
 use crate::ic::network::ICNetwork;
 use crate::ic::{address::Address, guards::not_in_maintenance_mode, transfer::icrc1_transfer};
-use crate::solana::verify_transfer::verify_canonical_message;
+use crate::ripple::create_ripple_payment_job::create_ripple_payment_job;
+use crate::ripple::utils::validation as ripple_validation;
+use crate::solana::create_solana_swap_job::create_solana_swap_job;
 use crate::solana::utils::validation;
+use crate::solana::verify_transfer::verify_canonical_message;
 use crate::stable_claim::{claim_map, stable_claim::StableClaim};
 use crate::stable_kong_settings::kong_settings_map;
-use crate::stable_lp_token::{lp_token_map, stable_lp_token::StableLPToken};
-use crate::stable_pool::{pool_map, stable_pool::StablePool};
+use crate::stable_lp_token::lp_token_fees::LPTokenBasis;
+use crate::stable_lp_token::{lp_block_map, lp_lock_map, lp_token_map};
+use crate::stable_pool::{pool_lock, pool_map, stable_pool::StablePool};
 use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
 use crate::stable_token::token_management::handle_failed_transfer;
-use crate::stable_token::{stable_token::StableToken, token::Token};
+use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
 use crate::stable_transfer::{stable_transfer::StableTransfer, transfer_map, tx_id::TxId};
-use crate::stable_tx::{remove_liquidity_tx::RemoveLiquidityTx, stable_tx::StableTx, tx_map};
+use crate::stable_tx::{remove_liquidity_tx::RemoveLiquidityTx, stable_tx::StableTx, swap_tx::SwapTx, tx_map};
 use crate::stable_user::user_map;
-use crate::solana::create_solana_swap_job::create_solana_swap_job;
+use crate::swap::swap_amounts::swap_pool_amounts;
+use crate::swap::swap_calc::SwapCalc;
+use crate::swap::update_liquidity_pool::update_pool_amounts;
 
-use crate::solana::message_builders::remove_liquidity::CanonicalRemoveLiquidityMessage;
 use super::remove_liquidity_args::RemoveLiquidityArgs;
 use super::remove_liquidity_reply::RemoveLiquidityReply;
+use crate::solana::message_builders::remove_liquidity::CanonicalRemoveLiquidityMessage;
 
 enum TokenIndex {
     Token0,
@@ -71,6 +77,10 @@ fn calculate_spl_gas_fee_for_remove_liquidity(other_token: &StableToken) -> Resu
             // If other token is also Solana, use a fixed SOL amount
             Ok(Nat::from(5000_u64)) // 0.000005 SOL in lamports
         }
+        StableToken::Ripple(_) => {
+            // no rate between XRP Ledger tokens and SOL, the SPL gas is covered by Kong
+            Ok(nat_zero())
+        }
         StableToken::LP(_) => {
             // LP tokens shouldn't be used in liquidity removal
             Ok(nat_zero())
@@ -91,6 +101,12 @@ fn calculate_spl_gas_fee_for_remove_liquidity(other_token: &StableToken) -> Resu
 /// - If signature is None: IC-only remove liquidity (backward compatible)
 /// - If signature is Some: Cross-chain remove liquidity (requires timestamp and proper validation)
 ///
+/// Single-token remove liquidity (receive_token is set):
+/// - the payout of the other token is swapped for receive_token in the same pool after the liquidity is removed
+///   and only receive_token is paid out, in one transfer
+/// - min_receive_amount is the minimum amount of receive_token after gas. if not set, the swap must be within
+///   the default max slippage
+///
 /// Notes regarding gas:
 ///   - payout_amount_0, payout_lp_fee_0, payout_amount_1, payout_lp_fee_1 does not include gas fees
 #[update(guard = "not_in_maintenance_mode")]
@@ -244,7 +260,7 @@ async fn check_arguments(args: &RemoveLiquidityArgs) -> Result<(u32, StablePool,
 #[allow(clippy::type_complexity)]
 async fn check_arguments_with_user(args: &RemoveLiquidityArgs, user_id: u32) -> Result<(StablePool, Nat, Nat, Nat, Nat, Nat), String> {
     // Pool
-    let pool = pool_map::get_by_tokens(&args.token_0, &args.token_1)?;
+    let pool = pool_map::get_by_tokens(&args.token_0, &args.token_1, args.lp_fee_bps)?;
     // Token0
     let balance_0 = &pool.balance_0;
     // Token1
@@ -265,10 +281,25 @@ async fn check_arguments_with_user(args: &RemoveLiquidityArgs, user_id: u32) ->
     } else {
         args.remove_lp_token_amount.clone()
     };
+    lp_lock_map::check_unlocked(user_id, lp_token_id, &remove_lp_token_amount, ICNetwork::get_time())?;
 
     // calculate the payout amounts.
     let (payout_amount_0, payout_lp_fee_0, payout_amount_1, payout_lp_fee_1) = calculate_amounts(&pool, &args.remove_lp_token_amount)?;
 
+    // single-token remove liquidity. check the swap with the pool after the liquidity is removed
+    if let Some(receive_token_index) = receive_token_index(&pool, args)? {
+        let removed_pool = removed_pool(&pool, &payout_amount_0, &payout_lp_fee_0, &payout_amount_1, &payout_lp_fee_1);
+        zap_out_amounts(
+            &removed_pool,
+            &receive_token_index,
+            &payout_amount_0,
+            &payout_lp_fee_0,
+            &payout_amount_1,
+            &payout_lp_fee_1,
+            args.min_receive_amount.as_ref(),
+        )?;
+    }
+
     Ok((
         pool,
         remove_lp_token_amount,
@@ -321,7 +352,15 @@ async fn check_arguments_with_signature(args: &RemoveLiquidityArgs) -> Result<(u
     ))
 }
 
+/// amounts of token_0 and token_1 paid out for remove_lp_token_amount, in proportion to the pool balances
+/// the proportional payout keeps the price of both constant product and StableSwap pools unchanged
 pub fn calculate_amounts(pool: &StablePool, remove_lp_token_amount: &Nat) -> Result<(Nat, Nat, Nat, Nat), String> {
+    if pool.is_concentrated() {
+        Err(format!(
+            "Pool {} is a concentrated liquidity pool. Use burn_position()",
+            pool.symbol()
+        ))?
+    }
     // Token0
     let token_0 = pool.token_0();
     let balance_0 = &pool.balance_0;
@@ -419,6 +458,77 @@ pub fn calculate_amounts(pool: &StablePool, remove_lp_token_amount: &Nat) -> Res
     Ok((payout_amount_0, payout_lp_fee_0, payout_amount_1, payout_lp_fee_1))
 }
 
+/// index of receive_token in the pool for a single-token remove liquidity, None to pay out both tokens
+fn receive_token_index(pool: &StablePool, args: &RemoveLiquidityArgs) -> Result<Option<TokenIndex>, String> {
+    let Some(receive_token) = &args.receive_token else {
+        return Ok(None);
+    };
+    let token = token_map::get_by_token(receive_token)?;
+    if token.token_id() == pool.token_id_0 {
+        Ok(Some(TokenIndex::Token0))
+    } else if token.token_id() == pool.token_id_1 {
+        Ok(Some(TokenIndex::Token1))
+    } else {
+        Err(format!("Receive token {} is not in pool {}", token.symbol(), pool.symbol()))
+    }
+}
+
+/// swap of the other token of the payout for receive_token, within the pool after the liquidity is removed
+/// returns (receive_amount, mid_price, price, slippage, swap)
+#[allow(clippy::type_complexity)]
+fn zap_out_amounts(
+    pool: &StablePool,
+    receive_token_index: &TokenIndex,
+    payout_amount_0: &Nat,
+    payout_lp_fee_0: &Nat,
+    payout_amount_1: &Nat,
+    payout_lp_fee_1: &Nat,
+    min_receive_amount: Option<&Nat>,
+) -> Result<(Nat, f64, f64, f64, SwapCalc), String> {
+    let (pays_token_0, receive_token, swap_amount, payout_amount) = match receive_token_index {
+        TokenIndex::Token0 => (
+            false,
+            pool.token_0(),
+            nat_add(payout_amount_1, payout_lp_fee_1),
+            nat_add(payout_amount_0, payout_lp_fee_0),
+        ),
+        TokenIndex::Token1 => (
+            true,
+            pool.token_1(),
+            nat_add(payout_amount_0, payout_lp_fee_0),
+            nat_add(payout_amount_1, payout_lp_fee_1),
+        ),
+    };
+    let (receive_amount, price, mid_price, slippage, swap) = swap_pool_amounts(pool, pays_token_0, &swap_amount)?;
+
+    match min_receive_amount {
+        Some(min_receive_amount) => {
+            // the payout and the swap are sent in one transfer so gas is paid once
+            let total_receive_amount = nat_subtract(&nat_add(&payout_amount, &receive_amount), &receive_token.fee()).unwrap_or(nat_zero());
+            if total_receive_amount < *min_receive_amount {
+                Err(format!(
+                    "Receive amount {} {} is less than min_receive_amount {}",
+                    total_receive_amount,
+                    receive_token.symbol(),
+                    min_receive_amount
+                ))?
+            }
+        }
+        None => {
+            let max_slippage = kong_settings_map::get().default_max_slippage;
+            if slippage > max_slippage {
+                Err(format!(
+                    "Slippage exceeded. Swap of {} has {}% slippage",
+                    receive_token.symbol(),
+                    slippage
+                ))?
+            }
+        }
+    }
+
+    Ok((receive_amount, mid_price, price, slippage, swap))
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn process_remove_liquidity(
     request_id: u64,
@@ -439,15 +549,49 @@ async fn process_remove_liquidity(
     request_map::update_status(request_id, StatusCode::Start, None);
 
     // remove LP tokens from user's ledger
-    let transfer_lp_token = remove_lp_token(request_id, user_id, &lp_token, remove_lp_token_amount, ts);
-    if transfer_lp_token.is_err() {
-        return_tokens(request_id, user_id, pool, &transfer_lp_token, remove_lp_token_amount, ts);
-        Err(format!("Req #{} failed. {}", request_id, transfer_lp_token.unwrap_err()))?
-    }
+    let removed_basis = match remove_lp_token(request_id, user_id, &lp_token, remove_lp_token_amount, ts) {
+        Ok(removed_basis) => removed_basis,
+        Err(e) => {
+            return_tokens(request_id, user_id, pool, None, remove_lp_token_amount, ts);
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    };
 
     // update liquidity pool with new removed amounts
     update_liquidity_pool(request_id, pool, payout_amount_0, payout_lp_fee_0, payout_amount_1, payout_lp_fee_1);
 
+    // single-token remove liquidity. swap the other token for receive_token
+    let zap_out = match receive_token_index(pool, args).ok().flatten() {
+        Some(receive_token_index) => match zap_out(
+            request_id,
+            user_id,
+            pool,
+            receive_token_index,
+            payout_amount_0,
+            payout_lp_fee_0,
+            payout_amount_1,
+            payout_lp_fee_1,
+            args.min_receive_amount.as_ref(),
+            ts,
+        ) {
+            Ok(zap_out) => Some(zap_out),
+            Err(e) => {
+                // the user asked for receive_token only, so the liquidity is put back and the LP tokens are returned
+                restore_liquidity_pool(
+                    request_id,
+                    pool.pool_id,
+                    payout_amount_0,
+                    payout_lp_fee_0,
+                    payout_amount_1,
+                    payout_lp_fee_1,
+                );
+                return_tokens(request_id, user_id, pool, Some(&removed_basis), remove_lp_token_amount, ts);
+                Err(format!("Req #{} failed. {}", request_id, e))?
+            }
+        },
+        None => None,
+    };
+
     // successful, add tx and update request with reply
     send_payout_tokens(
         request_id,
@@ -459,13 +603,80 @@ async fn process_remove_liquidity(
         payout_amount_1,
         payout_lp_fee_1,
         remove_lp_token_amount,
+        zap_out,
         args,
         ts,
     )
     .await
 }
 
-fn remove_lp_token(request_id: u64, user_id: u32, lp_token: &StableToken, remove_lp_token_amount: &Nat, ts: u64) -> Result<(), String> {
+/// swap the other token of the payout for receive_token with the latest state of the pool
+/// returns (receive_token_index, receive_amount, swap_tx_id)
+#[allow(clippy::too_many_arguments)]
+fn zap_out(
+    request_id: u64,
+    user_id: u32,
+    pool: &StablePool,
+    receive_token_index: TokenIndex,
+    payout_amount_0: &Nat,
+    payout_lp_fee_0: &Nat,
+    payout_amount_1: &Nat,
+    payout_lp_fee_1: &Nat,
+    min_receive_amount: Option<&Nat>,
+    ts: u64,
+) -> Result<(TokenIndex, Nat, u64), String> {
+    request_map::update_status(request_id, StatusCode::ZapSwap, None);
+    let zap = pool_map::get_by_pool_id(pool.pool_id)
+        .ok_or_else(|| format!("Pool #{} not found", pool.pool_id))
+        .and_then(|pool| {
+            zap_out_amounts(
+                &pool,
+                &receive_token_index,
+                payout_amount_0,
+                payout_lp_fee_0,
+                payout_amount_1,
+                payout_lp_fee_1,
+                min_receive_amount,
+            )
+        })
+        .and_then(|zap| update_pool_amounts(request_id, &zap.4).map(|_| zap));
+    let (receive_amount, mid_price, price, slippage, swap) = match zap {
+        Ok(zap) => zap,
+        Err(e) => {
+            request_map::update_status(request_id, StatusCode::ZapSwapFailed, Some(&e));
+            return Err(e);
+        }
+    };
+
+    let swap_tx = SwapTx::new_success(
+        user_id,
+        request_id,
+        swap.pay_token_id,
+        &swap.pay_amount,
+        swap.receive_token_id,
+        &receive_amount,
+        mid_price,
+        price,
+        slippage,
+        std::slice::from_ref(&swap),
+        &[],
+        &[],
+        ts,
+    );
+    let swap_tx_id = tx_map::insert(&StableTx::Swap(swap_tx));
+    request_map::update_status(request_id, StatusCode::ZapSwapSuccess, Some(&format!("Swap tx #{}", swap_tx_id)));
+
+    Ok((receive_token_index, receive_amount, swap_tx_id))
+}
+
+/// burn the LP tokens removed from the user's balance. returns the cost basis and earned fees of the burned LP tokens
+fn remove_lp_token(
+    request_id: u64,
+    user_id: u32,
+    lp_token: &StableToken,
+    remove_lp_token_amount: &Nat,
+    ts: u64,
+) -> Result<LPTokenBasis, String> {
     // LP token
     let lp_token_id = lp_token.token_id();
 
@@ -474,25 +685,35 @@ fn remove_lp_token(request_id: u64, user_id: u32, lp_token: &StableToken, remove
     // make sure user has LP token in ledger and that has enough to remove
     match lp_token_map::get_by_token_id_by_user_id(lp_token_id, user_id) {
         Some(lp_token) => {
-            let amount = match nat_subtract(&lp_token.amount, remove_lp_token_amount) {
-                Some(amount) => amount,
+            // cost basis and earned fees are reduced in proportion to the LP tokens removed
+            let pool = match pool_map::get_by_lp_token_id(lp_token_id) {
+                Some(pool) => pool,
                 None => {
-                    let message = format!(
-                        "Insufficient LP tokens. {} available, {} required",
-                        lp_token.amount, remove_lp_token_amount
-                    );
+                    let message = "Unable to find LP token's pool".to_string();
                     request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountFailed, Some(&message));
                     Err(message)?
                 }
             };
-            let new_user_lp_token = StableLPToken {
-                amount,
-                ts,
-                ..lp_token.clone()
+            // the pool or the LP tokens may have been locked since the arguments were checked
+            if let Err(message) = pool_lock::check_unlocked(pool.pool_id)
+                .and_then(|_| lp_lock_map::check_unlocked(user_id, lp_token_id, remove_lp_token_amount, ts))
+            {
+                request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountFailed, Some(&message));
+                Err(message)?
+            }
+            let mut new_user_lp_token = lp_token.clone();
+            let Some(removed_basis) = new_user_lp_token.burn(&pool, remove_lp_token_amount, ts) else {
+                let message = format!(
+                    "Insufficient LP tokens. {} available, {} required",
+                    lp_token.amount, remove_lp_token_amount
+                );
+                request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountFailed, Some(&message));
+                Err(message)?
             };
             lp_token_map::update(&new_user_lp_token);
+            lp_block_map::insert_burn(lp_token_id, user_id, remove_lp_token_amount, ts);
             request_map::update_status(request_id, StatusCode::UpdateUserLPTokenAmountSuccess, None);
-            Ok(())
+            Ok(removed_basis)
         }
         None => {
             let message = format!("Insufficient LP tokens. 0 available, {} required", remove_lp_token_amount);
@@ -502,18 +723,23 @@ fn remove_lp_token(request_id: u64, user_id: u32, lp_token: &StableToken, remove
     }
 }
 
-fn return_lp_token(user_id: u32, lp_token: &StableToken, remove_lp_token_amount: &Nat, ts: u64) -> Result<(), String> {
+/// mint the burned LP tokens back to the user with the cost basis and earned fees they were burned with
+fn return_lp_token(
+    user_id: u32,
+    lp_token: &StableToken,
+    remove_lp_token_amount: &Nat,
+    removed_basis: &LPTokenBasis,
+    ts: u64,
+) -> Result<(), String> {
     // LP token
     let lp_token_id = lp_token.token_id();
 
     match lp_token_map::get_by_token_id_by_user_id(lp_token_id, user_id) {
-        Some(lp_token) => {
-            let new_user_lp_token = StableLPToken {
-                amount: nat_add(&lp_token.amount, remove_lp_token_amount),
-                ts,
-                ..lp_token.clone()
-            };
-            lp_token_map::update(&new_user_lp_token);
+        Some(mut lp_token) => {
+            let pool = pool_map::get_by_lp_token_id(lp_token_id).ok_or("Unable to find LP token's pool")?;
+            lp_token.mint(&pool, remove_lp_token_amount, removed_basis, ts);
+            lp_token_map::update(&lp_token);
+            lp_block_map::insert_mint(lp_token_id, user_id, remove_lp_token_amount, ts);
             Ok(())
         }
         None => Err("Unable to find LP tokens balance".to_string())?,
@@ -523,19 +749,45 @@ fn return_lp_token(user_id: u32, lp_token: &StableToken, remove_lp_token_amount:
 fn update_liquidity_pool(request_id: u64, pool: &StablePool, amount_0: &Nat, lp_fee_0: &Nat, amount_1: &Nat, lp_fee_1: &Nat) {
     request_map::update_status(request_id, StatusCode::UpdatePoolAmounts, None);
 
-    let update_pool = StablePool {
+    let update_pool = removed_pool(pool, amount_0, lp_fee_0, amount_1, lp_fee_1);
+    pool_map::update(&update_pool);
+    request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
+}
+
+/// add the removed amounts back to the latest state of the pool
+fn restore_liquidity_pool(request_id: u64, pool_id: u32, amount_0: &Nat, lp_fee_0: &Nat, amount_1: &Nat, lp_fee_1: &Nat) {
+    request_map::update_status(request_id, StatusCode::UpdatePoolAmounts, None);
+    match pool_map::get_by_pool_id(pool_id) {
+        Some(pool) => {
+            pool_map::update(&StablePool {
+                balance_0: nat_add(&pool.balance_0, amount_0),
+                lp_fee_0: nat_add(&pool.lp_fee_0, lp_fee_0),
+                balance_1: nat_add(&pool.balance_1, amount_1),
+                lp_fee_1: nat_add(&pool.lp_fee_1, lp_fee_1),
+                ..pool
+            });
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
+        }
+        None => {
+            let message = format!("Pool #{} not found", pool_id);
+            request_map::update_status(request_id, StatusCode::UpdatePoolAmountsFailed, Some(&message));
+        }
+    }
+}
+
+/// pool with the removed amounts taken out
+fn removed_pool(pool: &StablePool, amount_0: &Nat, lp_fee_0: &Nat, amount_1: &Nat, lp_fee_1: &Nat) -> StablePool {
+    StablePool {
         balance_0: nat_subtract(&pool.balance_0, amount_0).unwrap_or(nat_zero()),
         lp_fee_0: nat_subtract(&pool.lp_fee_0, lp_fee_0).unwrap_or(nat_zero()),
         balance_1: nat_subtract(&pool.balance_1, amount_1).unwrap_or(nat_zero()),
         lp_fee_1: nat_subtract(&pool.lp_fee_1, lp_fee_1).unwrap_or(nat_zero()),
         ..pool.clone()
-    };
-    pool_map::update(&update_pool);
-    request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
+    }
 }
 
 // send payout tokens to user and final balance integrity checks
-// - send payout token_0 and token_1 to user
+// - send payout token_0 and token_1 to user, or only receive_token with the swapped amount for a single-token remove liquidity
 // - any failures to send tokens will be saved as claims
 // - check the actual balances of the canister vs. expected balances in stable memory
 // - update successsful request reply
@@ -550,6 +802,7 @@ async fn send_payout_tokens(
     payout_amount_1: &Nat,
     payout_lp_fee_1: &Nat,
     remove_lp_token_amount: &Nat,
+    zap_out: Option<(TokenIndex, Nat, u64)>,
     args: &RemoveLiquidityArgs,
     ts: u64,
 ) -> Result<RemoveLiquidityReply, String> {
@@ -561,51 +814,65 @@ async fn send_payout_tokens(
     let mut transfer_ids = Vec::new();
     let mut claim_ids = Vec::new();
 
+    // for a single-token remove liquidity, the other token was swapped and the swapped amount is added to receive_token
+    let (send_0, send_1, swap_tx_id) = match zap_out {
+        Some((TokenIndex::Token0, receive_amount, swap_tx_id)) => (Some(nat_add(payout_amount_0, &receive_amount)), None, Some(swap_tx_id)),
+        Some((TokenIndex::Token1, receive_amount, swap_tx_id)) => (None, Some(nat_add(payout_amount_1, &receive_amount)), Some(swap_tx_id)),
+        None => (Some(payout_amount_0.clone()), Some(payout_amount_1.clone()), None),
+    };
+
     // send payout token_0 to the user
-    transfer_token(
-        request_id,
-        user_id,
-        to_principal_id,
-        TokenIndex::Token0,
-        &token_0,
-        payout_amount_0,
-        payout_lp_fee_0,
-        args.payout_address_0.as_ref(), // Pass Solana address if provided
-        &mut transfer_ids,
-        &mut claim_ids,
-        ts,
-    )
-    .await;
+    if let Some(send_amount_0) = send_0 {
+        transfer_token(
+            request_id,
+            user_id,
+            to_principal_id,
+            TokenIndex::Token0,
+            &token_0,
+            &send_amount_0,
+            payout_lp_fee_0,
+            args.payout_address_0.as_ref(), // Pass Solana or XRPL address if provided
+            &mut transfer_ids,
+            &mut claim_ids,
+            ts,
+        )
+        .await;
+    }
 
     // send payout token_1 to the user
-    transfer_token(
-        request_id,
-        user_id,
-        to_principal_id,
-        TokenIndex::Token1,
-        &token_1,
-        payout_amount_1,
-        payout_lp_fee_1,
-        args.payout_address_1.as_ref(), // Pass Solana address if provided
-        &mut transfer_ids,
-        &mut claim_ids,
-        ts,
-    )
-    .await;
+    if let Some(send_amount_1) = send_1 {
+        transfer_token(
+            request_id,
+            user_id,
+            to_principal_id,
+            TokenIndex::Token1,
+            &token_1,
+            &send_amount_1,
+            payout_lp_fee_1,
+            args.payout_address_1.as_ref(), // Pass Solana or XRPL address if provided
+            &mut transfer_ids,
+            &mut claim_ids,
+            ts,
+        )
+        .await;
+    }
 
-    let remove_liquidity_tx = RemoveLiquidityTx::new_success(
-        pool.pool_id,
-        user_id,
-        request_id,
-        payout_amount_0,
-        payout_lp_fee_0,
-        payout_amount_1,
-        payout_lp_fee_1,
-        remove_lp_token_amount,
-        &transfer_ids,
-        &claim_ids,
-        ts,
-    );
+    let remove_liquidity_tx = RemoveLiquidityTx {
+        swap_tx_id,
+        ..RemoveLiquidityTx::new_success(
+            pool.pool_id,
+            user_id,
+            request_id,
+            payout_amount_0,
+            payout_lp_fee_0,
+            payout_amount_1,
+            payout_lp_fee_1,
+            remove_lp_token_amount,
+            &transfer_ids,
+            &claim_ids,
+            ts,
+        )
+    };
     let tx_id = tx_map::insert(&StableTx::RemoveLiquidity(remove_liquidity_tx.clone()));
     let reply = match tx_map::get_by_user_and_token_id(Some(tx_id), None, None, None).first() {
         Some(StableTx::RemoveLiquidity(remove_liquidity_tx)) => RemoveLiquidityReply::try_from(remove_liquidity_tx)
@@ -626,7 +893,7 @@ async fn transfer_token(
     token: &StableToken,
     payout_amount: &Nat,
     payout_lp_fee: &Nat,
-    payout_address: Option<&String>, // NEW parameter for Solana addresses
+    payout_address: Option<&String>, // NEW parameter for Solana and XRPL addresses
     transfer_ids: &mut Vec<u64>,
     claim_ids: &mut Vec<u64>,
     ts: u64,
@@ -727,6 +994,53 @@ async fn transfer_token(
                 };
             }
         }
+    } else if token.chain() == XRP_CHAIN {
+        // XRPL tokens are paid out to payout_address by a payment job. the gas fee covers the network fee paid by Kong
+        let ripple_address = match payout_address {
+            Some(addr) => ripple_validation::validate_address(addr)
+                .map(|_| addr.to_string())
+                .map_err(|e| format!("Invalid XRPL address: {}", e)),
+            None => Err("XRPL token payouts require payout_address".to_string()),
+        };
+        let result = match ripple_address {
+            Ok(address) => {
+                let to_address = Address::RippleAddress(address);
+                create_ripple_payment_job(request_id, user_id, token, &amount_with_gas, &to_address, ts)
+                    .await
+                    .map_err(|e| (to_address, format!("Failed to create Ripple payment job: {}", e)))
+            }
+            // Fallback to principal
+            Err(e) => Err((Address::PrincipalId(*to_principal_id), e)),
+        };
+        match result {
+            Ok(job_id) => {
+                let transfer_id = transfer_map::insert(&StableTransfer {
+                    transfer_id: 0,
+                    request_id,
+                    is_send: false,
+                    amount: amount_with_gas,
+                    token_id,
+                    tx_id: TxId::TransactionId(format!("job_{}", job_id)),
+                    ts,
+                });
+                transfer_ids.push(transfer_id);
+                let message = format!("Ripple payment job #{} created", job_id);
+                match token_index {
+                    TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReceiveToken0Success, Some(&message)),
+                    TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReceiveToken1Success, Some(&message)),
+                };
+            }
+            Err((to_address, e)) => {
+                let claim = StableClaim::new(user_id, token_id, &amount, Some(request_id), Some(to_address), ts);
+                let claim_id = claim_map::insert(&claim);
+                claim_ids.push(claim_id);
+                let message = format!("Saved as claim #{}. {}", claim_id, e);
+                match token_index {
+                    TokenIndex::Token0 => request_map::update_status(request_id, StatusCode::ReceiveToken0Failed, Some(&message)),
+                    TokenIndex::Token1 => request_map::update_status(request_id, StatusCode::ReceiveToken1Failed, Some(&message)),
+                };
+            }
+        }
     } else {
         // Standard IC token transfer
         match icrc1_transfer(&amount_with_gas, to_principal_id, token, None).await {
@@ -772,17 +1086,17 @@ fn return_tokens(
     request_id: u64,
     user_id: u32,
     pool: &StablePool,
-    transfer_lp_token: &Result<(), String>,
+    removed_basis: Option<&LPTokenBasis>,
     remove_lp_token_amount: &Nat,
     ts: u64,
 ) {
     // LP token
     let lp_token = pool.lp_token();
 
-    // if transfer_lp_token was successful, then we need to return the LP token back to the user
-    if transfer_lp_token.is_ok() {
+    // if the LP tokens were burned, then we need to return them back to the user with their basis
+    if let Some(removed_basis) = removed_basis {
         request_map::update_status(request_id, StatusCode::ReturnUserLPTokenAmount, None);
-        match return_lp_token(user_id, &lp_token, remove_lp_token_amount, ts) {
+        match return_lp_token(user_id, &lp_token, remove_lp_token_amount, removed_basis, ts) {
             Ok(()) => {
                 request_map::update_status(request_id, StatusCode::ReturnUserLPTokenAmountSuccess, None);
             }
@@ -816,6 +1130,9 @@ fn archive_to_kong_data(request_id: u64) -> Result<(), String> {
             }
             // archive txs
             tx_map::archive_to_kong_data(reply.tx_id)?;
+            if let Some(swap_tx_id) = reply.swap_tx_id {
+                tx_map::archive_to_kong_data(swap_tx_id)?;
+            }
         }
         _ => return Err("Invalid reply type".to_string()),
     }
@@ -828,3 +1145,132 @@ fn archive_to_kong_data(request_id: u64) -> Result<(), String> {
 fn validate_remove_liquidity() -> Result<String, String> {
     Ok("remove_liquidity is valid".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use futures::executor::block_on;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_ic_token, insert_pool, insert_token};
+    use crate::stable_lp_token::stable_lp_token::StableLPToken;
+    use crate::stable_token::lp_token::LPToken;
+
+    const CKBTC: u32 = 1;
+    const CKUSDT: u32 = 2;
+    const LP_TOKEN_ID: u32 = 3;
+
+    // 1 ckBTC / 60,000 ckUSDT pool with 2,000,000 LP tokens, half of them held by user 100
+    fn setup() -> StablePool {
+        init_kong_settings();
+        insert_ic_token(CKBTC, "ckBTC", 8);
+        insert_ic_token(CKUSDT, "ckUSDT", 6);
+        insert_token(&StableToken::LP(LPToken {
+            token_id: LP_TOKEN_ID,
+            symbol: "ckBTC_ckUSDT".to_string(),
+            address: "1_2".to_string(),
+            decimals: 8,
+            is_removed: false,
+            ledger_id: None,
+        }));
+        let pool = StablePool {
+            lp_token_id: LP_TOKEN_ID,
+            ..insert_pool(1, CKBTC, 100_000_000, CKUSDT, 60_000_000_000)
+        };
+        pool_map::update(&pool);
+        for user_id in [100, 101] {
+            lp_token_map::insert(&StableLPToken::new(user_id, LP_TOKEN_ID, Nat::from(1_000_000_u64), 0)).unwrap();
+        }
+        pool
+    }
+
+    fn zap_out_args(min_receive_amount: Option<Nat>) -> RemoveLiquidityArgs {
+        RemoveLiquidityArgs {
+            token_0: "ckBTC".to_string(),
+            token_1: "ckUSDT".to_string(),
+            remove_lp_token_amount: Nat::from(100_000_u64),
+            lp_fee_bps: None,
+            payout_address_0: None,
+            payout_address_1: None,
+            signature_0: None,
+            signature_1: None,
+            receive_token: Some("ckUSDT".to_string()),
+            min_receive_amount,
+        }
+    }
+
+    #[test]
+    fn test_zap_out_amounts_min_receive_amount() {
+        let pool = setup();
+        let (amount_0, lp_fee_0, amount_1, lp_fee_1) = calculate_amounts(&pool, &Nat::from(100_000_u64)).unwrap();
+        let removed_pool = removed_pool(&pool, &amount_0, &lp_fee_0, &amount_1, &lp_fee_1);
+        let zap_out_with_min = |min_receive_amount: &Nat| {
+            zap_out_amounts(
+                &removed_pool,
+                &TokenIndex::Token1,
+                &amount_0,
+                &lp_fee_0,
+                &amount_1,
+                &lp_fee_1,
+                Some(min_receive_amount),
+            )
+            .map(|(receive_amount, ..)| receive_amount)
+        };
+
+        let receive_amount = zap_out_with_min(&nat_zero()).unwrap();
+        assert!(receive_amount > nat_zero());
+        // the payout and the swapped amount are sent in one transfer, less the 0.01 ckUSDT fee
+        let total_receive_amount = nat_subtract(&nat_add(&nat_add(&amount_1, &lp_fee_1), &receive_amount), &Nat::from(10_000_u64)).unwrap();
+        assert!(zap_out_with_min(&total_receive_amount).is_ok());
+        let e = zap_out_with_min(&nat_add(&total_receive_amount, &Nat::from(1_u64))).unwrap_err();
+        assert!(e.contains("less than min_receive_amount"), "{}", e);
+    }
+
+    #[test]
+    fn test_zap_out_swap_failure_returns_lp_tokens() {
+        let pool = setup();
+        // user 100 deposited 0.5 ckBTC and 30,000 ckUSDT for its LP tokens
+        let lp_token = StableLPToken {
+            deposit_0: Nat::from(50_000_000_u64),
+            deposit_1: Nat::from(30_000_000_000_u64),
+            ..lp_token_map::get_by_token_id_by_user_id(LP_TOKEN_ID, 100).unwrap()
+        };
+        lp_token_map::update(&lp_token);
+        // min_receive_amount passed the argument checks but the pool has since moved
+        let args = zap_out_args(Some(Nat::from(60_000_000_000_u64)));
+        let request_id = request_map::insert(&StableRequest::new(100, &Request::RemoveLiquidity(args.clone()), 0));
+        let (amount_0, lp_fee_0, amount_1, lp_fee_1) = calculate_amounts(&pool, &args.remove_lp_token_amount).unwrap();
+
+        let result = block_on(process_remove_liquidity(
+            request_id,
+            100,
+            &Account::from(Principal::from_slice(&[1])),
+            &pool,
+            &args.remove_lp_token_amount,
+            &amount_0,
+            &lp_fee_0,
+            &amount_1,
+            &lp_fee_1,
+            &args,
+            0,
+        ));
+        assert!(result.is_err());
+
+        // the LP tokens are returned with their cost basis and the pool balances are back to where they were
+        let returned_lp_token = lp_token_map::get_by_token_id_by_user_id(LP_TOKEN_ID, 100).unwrap();
+        assert_eq!(returned_lp_token.amount, Nat::from(1_000_000_u64));
+        assert_eq!(returned_lp_token.basis(), lp_token.basis());
+        assert_eq!(lp_token_map::get_total_supply(LP_TOKEN_ID), Nat::from(2_000_000_u64));
+        let restored_pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(restored_pool.balance_0, pool.balance_0);
+        assert_eq!(restored_pool.balance_1, pool.balance_1);
+        // no swap and no payout
+        assert!(tx_map::get_by_user_and_token_id(None, Some(100), None, None).is_empty());
+        let statuses = request_map::get_by_request_id(request_id).unwrap().statuses;
+        assert!(statuses.iter().any(|s| matches!(s.status_code, StatusCode::ZapSwapFailed)));
+        assert!(statuses
+            .iter()
+            .any(|s| matches!(s.status_code, StatusCode::ReturnUserLPTokenAmountSuccess)));
+        assert!(!statuses.iter().any(|s| matches!(s.status_code, StatusCode::ReceiveToken1)));
+    }
+}
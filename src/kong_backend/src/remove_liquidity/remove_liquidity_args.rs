@@ -8,10 +8,16 @@ pub struct RemoveLiquidityArgs {
     pub token_0: String,
     pub token_1: String,
     pub remove_lp_token_amount: Nat,
+    pub lp_fee_bps: Option<u8>, // fee tier of the pool, defaults to the first pool of the token pair
     // Optional Solana payout addresses (like swap's receive_address)
-    pub payout_address_0: Option<String>,  // For token_0 if Solana
-    pub payout_address_1: Option<String>,  // For token_1 if Solana
+    pub payout_address_0: Option<String>, // For token_0 if Solana
+    pub payout_address_1: Option<String>, // For token_1 if Solana
     // Cross-chain signature support (following issue #6 spec)
-    pub signature_0: Option<String>,       // Ed25519 signature for token_0 payout
-    pub signature_1: Option<String>,       // Ed25519 signature for token_1 payout
+    pub signature_0: Option<String>, // Ed25519 signature for token_0 payout
+    pub signature_1: Option<String>, // Ed25519 signature for token_1 payout
+    // single-token remove liquidity
+    #[serde(default)]
+    pub receive_token: Option<String>, // pay out only this token. the other token is swapped for it in the pool
+    #[serde(default)]
+    pub min_receive_amount: Option<Nat>, // minimum amount of receive_token to receive, after gas
 }
@@ -28,6 +28,8 @@ pub struct RemoveLiquidityReply {
     pub transfer_ids: Vec<TransferIdReply>,
     pub claim_ids: Vec<u64>,
     pub ts: u64,
+    #[serde(default)]
+    pub swap_tx_id: Option<u64>, // tx_id of the internal swap of a single-token remove_liquidity()
 }
 
 impl RemoveLiquidityReply {
@@ -51,6 +53,7 @@ impl RemoveLiquidityReply {
             transfer_ids: Vec::new(),
             claim_ids: claim_ids.to_vec(),
             ts,
+            swap_tx_id: None,
         }
     }
 }
@@ -85,6 +88,7 @@ impl TryFrom<&RemoveLiquidityTx> for RemoveLiquidityReply {
             }).collect(),
             claim_ids: remove_liquidity_tx.claim_ids.clone(),
             ts: remove_liquidity_tx.ts,
+            swap_tx_id: remove_liquidity_tx.swap_tx_id,
         })
     }
 }
@@ -8,10 +8,17 @@ use crate::stable_token::token::Token;
 
 use super::remove_liquidity_amounts_reply::RemoveLiquidityAmountsReply;
 
+/// amounts are paid out in proportion to the pool balances for all pool types
+/// lp_fee_bps selects the pool of the token pair by fee tier, defaults to the first pool of the token pair
 #[query(guard = "not_in_maintenance_mode")]
-fn remove_liquidity_amounts(token_0: String, token_1: String, remove_lp_token_amount: Nat) -> Result<RemoveLiquidityAmountsReply, String> {
+fn remove_liquidity_amounts(
+    token_0: String,
+    token_1: String,
+    remove_lp_token_amount: Nat,
+    lp_fee_bps: Option<u8>,
+) -> Result<RemoveLiquidityAmountsReply, String> {
     // Pool
-    let pool = pool_map::get_by_tokens(&token_0, &token_1)?;
+    let pool = pool_map::get_by_tokens(&token_0, &token_1, lp_fee_bps)?;
     let symbol = pool.symbol();
     // Token0
     let token_0 = pool.token_0();
@@ -1,6 +1,7 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 
+use crate::solana::swap_jobs::solana_swap_job_reply::{self, SolanaSwapJobReply};
 use crate::stable_request::reply::Reply;
 use crate::stable_request::request::Request;
 use crate::stable_request::stable_request::StableRequest;
@@ -11,6 +12,7 @@ pub struct RequestsReply {
     pub statuses: Vec<String>,
     pub request: Request,
     pub reply: Reply,
+    pub solana_swap_jobs: Vec<SolanaSwapJobReply>, // payouts of the request on Solana
     pub ts: u64,
 }
 
@@ -21,6 +23,7 @@ impl From<&StableRequest> for RequestsReply {
             statuses: request.statuses.iter().map(|status| status.to_string()).collect(),
             request: request.request.clone(),
             reply: request.reply.clone(),
+            solana_swap_jobs: solana_swap_job_reply::get_by_request_id(request.request_id),
             ts: request.ts,
         }
     }
@@ -0,0 +1,108 @@
+//! Module for creating Ripple payment jobs for outgoing transfers
+//!
+//! This handles the creation of payment jobs that will be submitted by kong_rpc
+//! to the XRP Ledger.
+
+use candid::Nat;
+
+use crate::ic::address::Address;
+use crate::kong_backend::KongBackend;
+use crate::stable_token::stable_token::StableToken;
+
+use super::network::RippleNetwork;
+use super::payment_job::{RipplePaymentJob, RipplePaymentJobId, RipplePaymentJobStatus};
+use super::stable_memory::{
+    get_cached_ripple_address, get_next_ripple_account_sequence, get_next_ripple_payment_job_id, get_ripple_ledger,
+    with_ripple_payment_job_queue_mut,
+};
+use super::transaction::amount::RippleAmount;
+use super::transaction::serialize::{Payment, TF_FULLY_CANONICAL_SIG};
+use super::transaction::sign::sign_payment;
+use super::utils::validation;
+
+/// ledgers after the latest validated ledger in which the payment must be validated (about 5 minutes)
+const LAST_LEDGER_SEQUENCE_OFFSET: u32 = 75;
+/// minimum transaction cost in drops
+const MIN_FEE_DROPS: u64 = 10;
+
+/// Creates a Ripple payment job for processing an outgoing transfer
+pub async fn create_ripple_payment_job(
+    request_id: u64,
+    user_id: u32,
+    receive_token: &StableToken,
+    receive_amount: &Nat,
+    to_address: &Address,
+    ts: u64,
+) -> Result<u64, String> {
+    let StableToken::Ripple(ripple_token) = receive_token else {
+        return Err("Not a Ripple token".to_string());
+    };
+
+    let destination_address = match to_address {
+        Address::RippleAddress(address) => address.clone(),
+        _ => return Err("Cannot send XRPL tokens to an IC or Solana address. Please provide an XRPL address.".to_string()),
+    };
+    let destination = validation::decode_address(&destination_address).map_err(|e| format!("Invalid XRPL address: {}", e))?;
+
+    // Get Kong's XRPL address
+    let kong_address = get_cached_ripple_address();
+    let account = validation::decode_address(&kong_address).map_err(|e| format!("Kong's XRPL address is not cached: {}", e))?;
+    if destination == account {
+        return Err("Cannot send XRPL tokens to Kong's own address".to_string());
+    }
+
+    let amount = RippleAmount::new(ripple_token, receive_amount).map_err(|e| e.to_string())?;
+
+    let ledger = get_ripple_ledger();
+    if ledger.ledger_index == 0 {
+        return Err("XRP Ledger state not reported by kong_rpc yet".to_string());
+    }
+
+    let signing_pub_key = RippleNetwork::get_signing_public_key(&KongBackend::canister())
+        .await
+        .map_err(|e| format!("Failed to get signing public key: {}", e))?;
+
+    // Get the job ID and the Sequence of Kong's account, right before signing so concurrent jobs get consecutive Sequences
+    let job_id = get_next_ripple_payment_job_id();
+    let sequence = get_next_ripple_account_sequence();
+    let last_ledger_sequence = ledger.ledger_index + LAST_LEDGER_SEQUENCE_OFFSET;
+
+    let payment = Payment {
+        account,
+        destination,
+        amount,
+        fee_drops: ledger.fee_drops.max(MIN_FEE_DROPS),
+        sequence,
+        last_ledger_sequence: Some(last_ledger_sequence),
+        signing_pub_key,
+        memo: Some(format!("Kong payment job #{}", job_id)),
+        flags: TF_FULLY_CANONICAL_SIG,
+    };
+
+    // Sign the payment
+    let signed_payment = sign_payment(&payment).await.map_err(|e| format!("Failed to sign payment: {}", e))?;
+
+    let payment_job = RipplePaymentJob {
+        id: job_id,
+        user_id,
+        request_id,
+        token_id: ripple_token.token_id,
+        amount: receive_amount.clone(),
+        to_address: destination_address,
+        status: RipplePaymentJobStatus::Pending,
+        created_at: ts,
+        updated_at: ts,
+        tx_blob: signed_payment.tx_blob,
+        tx_hash: signed_payment.tx_hash,
+        sequence,
+        last_ledger_sequence,
+        error_message: None,
+    };
+
+    // Store the job in the queue
+    with_ripple_payment_job_queue_mut(|queue| {
+        queue.insert(RipplePaymentJobId(job_id), payment_job);
+    });
+
+    Ok(job_id)
+}
@@ -18,4 +18,19 @@ pub enum RippleError {
 
     #[error("RPC error: {0}")]
     RpcError(String),
+
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
+    #[error("Signing error: {0}")]
+    SigningError(String),
+
+    #[error("Invalid message signing: {0}")]
+    InvalidMessageSigning(String),
 }
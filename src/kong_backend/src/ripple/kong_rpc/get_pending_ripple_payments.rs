@@ -0,0 +1,40 @@
+use ic_cdk::query;
+use std::ops::Bound::{Excluded, Unbounded};
+
+use crate::ic::guards::caller_is_kong_rpc;
+
+use super::super::payment_job::{RipplePaymentJob, RipplePaymentJobId, RipplePaymentJobStatus};
+use super::super::stable_memory::with_ripple_payment_job_queue;
+
+/// Get pending Ripple payment jobs for kong_rpc to submit, in Sequence order (called by kong_rpc)
+#[query(hidden = true, guard = "caller_is_kong_rpc")]
+pub fn get_pending_ripple_payments(from_job_id: Option<RipplePaymentJobId>) -> Result<Vec<RipplePaymentJob>, String> {
+    const MAX_BATCH_SIZE: usize = 100;
+
+    with_ripple_payment_job_queue(|queue| {
+        Ok(queue
+            .range((from_job_id.map_or(Unbounded, Excluded), Unbounded))
+            .filter_map(|(_, job)| (job.status == RipplePaymentJobStatus::Pending).then_some(job))
+            .take(MAX_BATCH_SIZE)
+            .collect())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::insert_ripple_payment_job;
+
+    #[test]
+    fn test_get_pending_ripple_payments() {
+        insert_ripple_payment_job(1, RipplePaymentJobStatus::Pending, "");
+        insert_ripple_payment_job(2, RipplePaymentJobStatus::Failed, "");
+        insert_ripple_payment_job(3, RipplePaymentJobStatus::Pending, "");
+
+        let job_ids = |jobs: Vec<RipplePaymentJob>| jobs.iter().map(|job| job.id).collect::<Vec<_>>();
+        assert_eq!(job_ids(get_pending_ripple_payments(None).unwrap()), vec![1, 3]);
+        assert_eq!(job_ids(get_pending_ripple_payments(Some(RipplePaymentJobId(1))).unwrap()), vec![3]);
+        assert!(get_pending_ripple_payments(Some(RipplePaymentJobId(3))).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+use ic_cdk::query;
+
+use super::super::stable_memory::get_cached_ripple_address;
+
+/// Get the cached XRPL address for this canister
+/// This is a fast query method that returns the cached address
+#[query]
+pub fn get_ripple_address() -> String {
+    get_cached_ripple_address()
+}
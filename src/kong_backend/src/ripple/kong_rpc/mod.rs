@@ -0,0 +1,6 @@
+pub mod get_pending_ripple_payments;
+pub mod get_ripple_address;
+pub mod notify_ripple_payment;
+pub mod transaction_notification;
+pub mod update_ripple_ledger;
+pub mod update_ripple_payment;
@@ -0,0 +1,27 @@
+use ic_cdk::update;
+
+use crate::ic::guards::caller_is_kong_rpc;
+use crate::ic::network::ICNetwork;
+
+use super::super::stable_memory::with_ripple_tx_notifications_mut;
+use super::transaction_notification::{RippleTxNotification, RippleTxNotificationId};
+
+/// Notify about a validated XRPL payment to Kong's account (called by kong_rpc)
+///
+/// metadata is JSON with the transaction fields as returned by rippled's tx method, e.g.
+/// {"TransactionType": "Payment", "Account": ..., "Destination": ..., "SigningPubKey": ..., "date": ...,
+///  "meta": {"TransactionResult": "tesSUCCESS", "delivered_amount": ...}}
+#[update(hidden = true, guard = "caller_is_kong_rpc")]
+pub fn notify_ripple_payment(tx_hash: String, metadata: String) -> Result<(), String> {
+    let tx_hash = tx_hash.to_uppercase();
+    let value = RippleTxNotification {
+        tx_hash: tx_hash.clone(),
+        metadata,
+        timestamp: ICNetwork::get_time(),
+    };
+    // insert the notification into the stable memory map for further processing when user calls swap(), add_liquidity() or add_pool()
+    with_ripple_tx_notifications_mut(|notifications| {
+        notifications.insert(RippleTxNotificationId(tx_hash), value);
+        Ok(())
+    })
+}
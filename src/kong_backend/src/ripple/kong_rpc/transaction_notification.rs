@@ -0,0 +1,39 @@
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::Serialize;
+use std::borrow::Cow;
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RippleTxNotificationId(pub String); // tx_hash
+
+impl Storable for RippleTxNotificationId {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode RippleTxNotificationId").into()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode RippleTxNotificationId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Validated XRPL payment to Kong's account, reported by kong_rpc
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct RippleTxNotification {
+    pub tx_hash: String,
+    pub metadata: String, // transaction fields and delivered_amount as JSON
+    pub timestamp: u64,
+}
+
+impl Storable for RippleTxNotification {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode RippleTxNotification").into()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode RippleTxNotification")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
@@ -0,0 +1,59 @@
+use ic_cdk::update;
+
+use crate::ic::guards::caller_is_kong_rpc;
+use crate::ic::network::ICNetwork;
+
+use super::super::ledger::RippleLedger;
+use super::super::payment_job::RipplePaymentJobStatus;
+use super::super::stable_memory::{get_ripple_ledger, set_ripple_ledger, with_ripple_payment_job_queue};
+
+/// Update the state of Kong's XRPL account and the latest validated ledger (called by kong_rpc)
+///
+/// Kong keeps its own account Sequence so payments signed before kong_rpc submits them get consecutive Sequences.
+/// The reported account_sequence only replaces it while no payment is pending, so a Sequence skipped by a payment
+/// that was never applied is reused once the payments after it have failed or expired.
+#[update(hidden = true, guard = "caller_is_kong_rpc")]
+pub fn update_ripple_ledger(account_sequence: u32, ledger_index: u32, fee_drops: u64) -> Result<(), String> {
+    let ledger = get_ripple_ledger();
+    let has_pending_jobs =
+        with_ripple_payment_job_queue(|queue| queue.iter().any(|(_, job)| job.status == RipplePaymentJobStatus::Pending));
+    let account_sequence = if has_pending_jobs {
+        ledger.account_sequence.max(account_sequence)
+    } else {
+        account_sequence
+    };
+    set_ripple_ledger(RippleLedger {
+        account_sequence,
+        ledger_index,
+        fee_drops,
+        updated_at: ICNetwork::get_time(),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::insert_ripple_payment_job;
+    use crate::ripple::stable_memory::get_next_ripple_account_sequence;
+
+    #[test]
+    fn test_account_sequence_is_kept_while_payments_are_pending() {
+        update_ripple_ledger(10, 1_000, 12).unwrap();
+        assert_eq!(get_next_ripple_account_sequence(), 10);
+        let job = insert_ripple_payment_job(1, RipplePaymentJobStatus::Pending, "");
+
+        // kong_rpc has not seen the pending payment yet
+        update_ripple_ledger(10, 1_001, 12).unwrap();
+        assert_eq!(get_ripple_ledger().account_sequence, 11);
+        assert_eq!(get_ripple_ledger().ledger_index, 1_001);
+        update_ripple_ledger(12, 1_002, 12).unwrap();
+        assert_eq!(get_ripple_ledger().account_sequence, 12);
+
+        // the skipped Sequence is reused once no payment is pending
+        insert_ripple_payment_job(1, RipplePaymentJobStatus::Failed, &job.tx_hash);
+        update_ripple_ledger(10, 1_003, 12).unwrap();
+        assert_eq!(get_ripple_ledger().account_sequence, 10);
+    }
+}
@@ -0,0 +1,137 @@
+use ic_cdk::update;
+
+use crate::ic::address::Address;
+use crate::ic::guards::caller_is_kong_rpc;
+use crate::ic::network::ICNetwork;
+use crate::stable_claim::{claim_map, stable_claim::StableClaim};
+
+use super::super::payment_job::{RipplePaymentJob, RipplePaymentJobId, RipplePaymentJobStatus};
+use super::super::stable_memory::with_ripple_payment_job_queue_mut;
+
+/// Update a Ripple payment job status (called by kong_rpc once the payment is validated or can no longer be)
+///
+/// A payment that failed creates a claim for the job's amount and destination so the user can recover the funds.
+#[update(hidden = true, guard = "caller_is_kong_rpc")]
+pub fn update_ripple_payment(job_id: u64, tx_hash: String, was_successful: bool, error_msg: Option<String>) -> Result<(), String> {
+    let ts = ICNetwork::get_time();
+    with_ripple_payment_job_queue_mut(|queue| {
+        let mut job = queue.get(&RipplePaymentJobId(job_id)).ok_or(format!("Job {} not found", job_id))?;
+        if !tx_hash.eq_ignore_ascii_case(&job.tx_hash) {
+            return Err(format!(
+                "Job {} was signed with transaction {}, not {}",
+                job_id, job.tx_hash, tx_hash
+            ));
+        }
+
+        match (job.status, was_successful) {
+            (RipplePaymentJobStatus::Pending, true) | (RipplePaymentJobStatus::Confirmed, true) => {
+                // Remove successfully completed jobs to prevent reprocessing
+                queue.remove(&RipplePaymentJobId(job_id));
+                Ok(())
+            }
+            (RipplePaymentJobStatus::Pending, false) => {
+                create_claim(&job, ts);
+                job.status = RipplePaymentJobStatus::Failed;
+                job.error_message = error_msg;
+                job.updated_at = ts;
+                queue.insert(RipplePaymentJobId(job_id), job);
+                Ok(())
+            }
+            (RipplePaymentJobStatus::Confirmed, false) => Err(format!("Job {} is already confirmed, cannot mark as failed", job_id)),
+            (RipplePaymentJobStatus::Failed, true) => {
+                // the claim of the job would pay the user twice
+                ICNetwork::error_log(&format!(
+                    "Ripple payment job #{} succeeded after it failed and a claim was created - manual investigation required. Request: {}",
+                    job_id, job.request_id
+                ));
+                queue.remove(&RipplePaymentJobId(job_id));
+                Ok(())
+            }
+            (RipplePaymentJobStatus::Failed, false) => {
+                // Already failed - update error message if different
+                if job.error_message != error_msg {
+                    job.error_message = error_msg;
+                    job.updated_at = ts;
+                    queue.insert(RipplePaymentJobId(job_id), job);
+                }
+                Ok(())
+            }
+            (RipplePaymentJobStatus::Expired, _) => Err(format!("Job {} is expired - requires manual investigation", job_id)),
+        }
+    })
+}
+
+/// create a claim for the amount and destination of a failed payment
+fn create_claim(job: &RipplePaymentJob, ts: u64) {
+    let claim = StableClaim::new(
+        job.user_id,
+        job.token_id,
+        &job.amount,
+        Some(job.request_id),
+        Some(Address::RippleAddress(job.to_address.clone())),
+        ts,
+    );
+    let claim_id = claim_map::insert(&claim);
+    ICNetwork::info_log(&format!(
+        "Created claim #{} for failed Ripple payment job #{} (user: {}, request: {}, dest: {})",
+        claim_id, job.id, job.user_id, job.request_id, job.to_address
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_ripple_payment_job};
+    use crate::ripple::stable_memory::with_ripple_payment_job_queue;
+
+    const TX_HASH: &str = "C53ECF838647FA5A4C780377025FEC7999AB4182590510CA461444B207AB74A9";
+
+    fn get_job(job_id: u64) -> Option<RipplePaymentJob> {
+        with_ripple_payment_job_queue(|queue| queue.get(&RipplePaymentJobId(job_id)))
+    }
+
+    #[test]
+    fn test_failed_payment_creates_claim() {
+        init_kong_settings();
+        let job = insert_ripple_payment_job(1, RipplePaymentJobStatus::Pending, TX_HASH);
+
+        // only the transaction the job was signed with
+        assert!(update_ripple_payment(1, "A".repeat(64), false, None).is_err());
+
+        update_ripple_payment(1, TX_HASH.to_lowercase(), false, Some("tecPATH_DRY".to_string())).unwrap();
+        let failed_job = get_job(1).unwrap();
+        assert_eq!(failed_job.status, RipplePaymentJobStatus::Failed);
+        assert_eq!(failed_job.error_message.as_deref(), Some("tecPATH_DRY"));
+        let claim = claim_map::get_by_claim_id(1).unwrap();
+        assert_eq!((claim.user_id, claim.token_id, claim.amount), (1, 1, job.amount));
+        assert_eq!(claim.request_id, Some(1));
+        assert!(matches!(claim.to_address, Some(Address::RippleAddress(ref address)) if *address == job.to_address));
+
+        // reported again, no second claim
+        update_ripple_payment(1, TX_HASH.to_string(), false, Some("tecPATH_DRY".to_string())).unwrap();
+        assert!(claim_map::get_by_claim_id(2).is_none());
+
+        // validated after all, the job is removed and the double payment logged
+        update_ripple_payment(1, TX_HASH.to_string(), true, None).unwrap();
+        assert!(get_job(1).is_none());
+    }
+
+    #[test]
+    fn test_successful_payment_removes_job() {
+        init_kong_settings();
+        insert_ripple_payment_job(1, RipplePaymentJobStatus::Pending, TX_HASH);
+        insert_ripple_payment_job(2, RipplePaymentJobStatus::Confirmed, TX_HASH);
+        insert_ripple_payment_job(3, RipplePaymentJobStatus::Expired, TX_HASH);
+
+        update_ripple_payment(1, TX_HASH.to_string(), true, None).unwrap();
+        assert!(get_job(1).is_none());
+        assert!(update_ripple_payment(1, TX_HASH.to_string(), true, None).is_err());
+
+        // a confirmed payment can not fail and an expired one needs a manual check
+        assert!(update_ripple_payment(2, TX_HASH.to_string(), false, None).is_err());
+        assert!(update_ripple_payment(3, TX_HASH.to_string(), true, None).is_err());
+        assert!(update_ripple_payment(3, TX_HASH.to_string(), false, None).is_err());
+        assert!(claim_map::get_by_claim_id(1).is_none());
+    }
+}
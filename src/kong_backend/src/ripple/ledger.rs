@@ -0,0 +1,25 @@
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// State of Kong's XRPL account and the XRP Ledger, reported by kong_rpc
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct RippleLedger {
+    pub account_sequence: u32, // Sequence of Kong's next payment
+    pub ledger_index: u32,     // latest validated ledger index
+    pub fee_drops: u64,        // transaction cost of a payment in drops
+    pub updated_at: u64,
+}
+
+impl Storable for RippleLedger {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode RippleLedger").into()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode RippleLedger")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
@@ -1,4 +1,11 @@
+pub mod create_ripple_payment_job;
 pub mod error;
+pub mod kong_rpc;
+pub mod ledger;
 pub mod network;
+pub mod payment_job;
+pub mod payment_job_cleanup;
 pub mod stable_memory;
+pub mod transaction;
 pub mod utils;
+pub mod verify_transfer;
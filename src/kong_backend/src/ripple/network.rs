@@ -1,17 +1,27 @@
 use anyhow::Result;
 use candid::Principal;
-use ripemd::Ripemd160;
-use sha2::{Digest, Sha256};
 
 use crate::ic::management_canister::ManagementCanister;
 
 use super::error::RippleError;
-use super::utils::base58;
+use super::utils::validation;
+
+/// prefix of Ed25519 public keys on the XRP Ledger
+pub const ED25519_PREFIX: u8 = 0xED;
 
 pub struct RippleNetwork;
 
 impl RippleNetwork {
+    /// Get the canister's XRPL address
     pub async fn get_public_key(canister: &Principal) -> Result<String> {
+        let signing_pub_key = Self::get_signing_public_key(canister).await?;
+
+        // account id = RIPEMD160(SHA256(0xED + pubkey)), address = base58 of version + account id + checksum
+        Ok(validation::encode_address(&validation::account_id(&signing_pub_key)))
+    }
+
+    /// Get the canister's Ed25519 public key as used in SigningPubKey of XRPL transactions, 0xED + 32-byte key
+    pub async fn get_signing_public_key(canister: &Principal) -> Result<Vec<u8>> {
         let derivation_path = ManagementCanister::get_canister_derivation_path(canister);
 
         // Get the Schnorr public key - fail properly if not available
@@ -19,28 +29,13 @@ impl RippleNetwork {
             .await
             .map_err(|e| RippleError::PublicKeyRetrievalError(e.to_string()))?;
 
-        // Step 1: SHA256(pubkey)
-        let sha = Sha256::digest(public_key_bytes);
-
-        // Step 2: RIPEMD160(SHA256(pubkey))
-        let mut ripemd = Ripemd160::new();
-        ripemd.update(sha);
-        let account_id = ripemd.finalize(); // 20 bytes
-
-        // Step 3: payload = version byte (0x00) + account_id
-        let mut payload = Vec::with_capacity(1 + account_id.len());
-        payload.push(0x00u8);
-        payload.extend_from_slice(&account_id);
-
-        // Step 4: checksum = first 4 bytes of SHA256(SHA256(payload))
-        let first = Sha256::digest(&payload);
-        let second = Sha256::digest(first);
-        let checksum = &second[..4];
-
-        // Step 5: final = payload + checksum
-        let mut final_bytes = payload;
-        final_bytes.extend_from_slice(checksum);
+        if public_key_bytes.len() != 32 {
+            return Err(RippleError::InvalidPublicKeyFormat(format!("Expected 32 bytes, got {}", public_key_bytes.len())).into());
+        }
 
-        Ok(base58::encode_wallet_address(&final_bytes))
+        let mut signing_pub_key = Vec::with_capacity(33);
+        signing_pub_key.push(ED25519_PREFIX);
+        signing_pub_key.extend_from_slice(&public_key_bytes);
+        Ok(signing_pub_key)
     }
 }
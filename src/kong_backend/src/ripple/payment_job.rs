@@ -0,0 +1,61 @@
+use candid::{CandidType, Deserialize, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::Serialize;
+use std::borrow::Cow;
+
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RipplePaymentJobId(pub u64);
+
+impl Storable for RipplePaymentJobId {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode RipplePaymentJobId").into()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode RipplePaymentJobId")
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: 16,
+        is_fixed_size: false,
+    };
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum RipplePaymentJobStatus {
+    Pending,   // Job created, awaiting submission by kong_rpc
+    Confirmed, // Confirmed by kong_rpc as validated with tesSUCCESS
+    Failed,    // Failed (either the XRPL payment failed, or an internal error)
+    Expired,   // Timed out after 300s without response from kong_rpc (status unknown)
+}
+
+/// Payment from Kong's XRPL account, signed by Kong and submitted by kong_rpc
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct RipplePaymentJob {
+    pub id: u64,
+    pub user_id: u32,
+    pub request_id: u64,
+    pub token_id: u32,
+    pub amount: Nat,
+    pub to_address: String,
+    pub status: RipplePaymentJobStatus,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub tx_blob: String,           // signed transaction as hex, submitted by kong_rpc
+    pub tx_hash: String,           // transaction id computed at signing time
+    pub sequence: u32,             // Sequence of the payment of Kong's account
+    pub last_ledger_sequence: u32, // the payment can not be validated after this ledger
+    pub error_message: Option<String>,
+}
+
+impl Storable for RipplePaymentJob {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode RipplePaymentJob").into()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode RipplePaymentJob")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
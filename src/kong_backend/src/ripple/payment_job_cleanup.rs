@@ -0,0 +1,50 @@
+//! Cleanup task for expired Ripple payment jobs
+//!
+//! This module handles the expiration of payment jobs that have been pending
+//! for too long without confirmation from kong_rpc.
+
+use crate::ic::network::ICNetwork;
+
+use super::payment_job::RipplePaymentJobStatus;
+use super::stable_memory::with_ripple_payment_job_queue_mut;
+
+/// Timeout for payment jobs in nanoseconds (300 seconds = 5 minutes)
+const PAYMENT_JOB_TIMEOUT_NS: u64 = 300_000_000_000;
+
+/// Mark payment jobs that have been pending for too long as Expired.
+///
+/// The payment may still have been validated, so expired jobs need a manual check and no claim is created.
+pub fn cleanup_expired_payment_jobs() {
+    let current_time = ICNetwork::get_time();
+    let cutoff_time = current_time.saturating_sub(PAYMENT_JOB_TIMEOUT_NS);
+
+    with_ripple_payment_job_queue_mut(|queue| {
+        let expired_jobs: Vec<_> = queue
+            .iter()
+            .filter(|(_, job)| job.status == RipplePaymentJobStatus::Pending && job.created_at < cutoff_time)
+            .collect();
+
+        for (job_id, mut job) in expired_jobs.iter().cloned() {
+            let age_secs = (current_time - job.created_at) / 1_000_000_000;
+            ICNetwork::error_log(&format!(
+                "[CLEANUP] Ripple payment job #{} expired after {}s - Status UNKNOWN, manual investigation required. User: {}, Request: {}, Tx: {}",
+                job.id, age_secs, job.user_id, job.request_id, job.tx_hash
+            ));
+
+            job.status = RipplePaymentJobStatus::Expired;
+            job.error_message = Some(format!(
+                "Payment expired after {} seconds without confirmation from kong_rpc - status unknown, requires manual check",
+                age_secs
+            ));
+            job.updated_at = current_time;
+            queue.insert(job_id, job);
+        }
+
+        if !expired_jobs.is_empty() {
+            ICNetwork::info_log(&format!(
+                "[CLEANUP] Marked {} Ripple payment job(s) as expired - manual investigation required",
+                expired_jobs.len()
+            ));
+        }
+    });
+}
@@ -1,6 +1,13 @@
-use ic_stable_structures::StableCell;
+use ic_stable_structures::{StableBTreeMap, StableCell};
 
-use crate::stable_memory::{Memory, CACHED_RIPPLE_ADDRESS};
+use crate::ic::network::ICNetwork;
+use crate::stable_memory::{
+    Memory, CACHED_RIPPLE_ADDRESS, NEXT_RIPPLE_PAYMENT_JOB_ID, RIPPLE_LEDGER, RIPPLE_PAYMENT_JOB_QUEUE, RIPPLE_TX_NOTIFICATIONS,
+};
+
+use super::kong_rpc::transaction_notification::{RippleTxNotification, RippleTxNotificationId};
+use super::ledger::RippleLedger;
+use super::payment_job::{RipplePaymentJob, RipplePaymentJobId};
 
 /// Helper function to access the cached Ripple address
 pub fn with_cached_ripple_address<R>(f: impl FnOnce(&StableCell<String, Memory>) -> R) -> R {
@@ -23,3 +30,84 @@ pub fn set_cached_ripple_address(address: String) {
         cell.set(address).expect("Failed to set cached Ripple address");
     });
 }
+
+/// Get the latest state of Kong's XRPL account and the ledger
+pub fn get_ripple_ledger() -> RippleLedger {
+    RIPPLE_LEDGER.with(|cell| cell.borrow().get().clone())
+}
+
+/// Set the state of Kong's XRPL account and the ledger
+pub fn set_ripple_ledger(ledger: RippleLedger) {
+    RIPPLE_LEDGER.with(|cell| {
+        cell.borrow_mut().set(ledger).expect("Failed to set Ripple ledger");
+    });
+}
+
+/// Get the Sequence for Kong's next payment and increment it
+pub fn get_next_ripple_account_sequence() -> u32 {
+    RIPPLE_LEDGER.with(|cell| {
+        let mut ledger = cell.borrow().get().clone();
+        let sequence = ledger.account_sequence;
+        ledger.account_sequence = sequence + 1;
+        cell.borrow_mut().set(ledger).expect("Failed to set Ripple account sequence");
+        sequence
+    })
+}
+
+/// Get the next unique ID for a Ripple payment job and increment the counter.
+pub fn get_next_ripple_payment_job_id() -> u64 {
+    NEXT_RIPPLE_PAYMENT_JOB_ID.with(|cell| {
+        let current_id = *cell.borrow().get();
+        cell.borrow_mut()
+            .set(current_id + 1)
+            .expect("Failed to set next_ripple_payment_job_id");
+        current_id
+    })
+}
+
+/// Helper function to access the payment job queue
+pub fn with_ripple_payment_job_queue<R>(f: impl FnOnce(&StableBTreeMap<RipplePaymentJobId, RipplePaymentJob, Memory>) -> R) -> R {
+    RIPPLE_PAYMENT_JOB_QUEUE.with(|cell| f(&cell.borrow()))
+}
+
+/// Helper function to mutate the payment job queue
+pub fn with_ripple_payment_job_queue_mut<R>(f: impl FnOnce(&mut StableBTreeMap<RipplePaymentJobId, RipplePaymentJob, Memory>) -> R) -> R {
+    RIPPLE_PAYMENT_JOB_QUEUE.with(|cell| f(&mut cell.borrow_mut()))
+}
+
+/// Helper function to access Ripple transaction notifications
+pub fn with_ripple_tx_notifications<R>(f: impl FnOnce(&StableBTreeMap<RippleTxNotificationId, RippleTxNotification, Memory>) -> R) -> R {
+    RIPPLE_TX_NOTIFICATIONS.with(|cell| f(&cell.borrow()))
+}
+
+/// Helper function to mutate Ripple transaction notifications
+pub fn with_ripple_tx_notifications_mut<R>(
+    f: impl FnOnce(&mut StableBTreeMap<RippleTxNotificationId, RippleTxNotification, Memory>) -> R,
+) -> R {
+    RIPPLE_TX_NOTIFICATIONS.with(|cell| f(&mut cell.borrow_mut()))
+}
+
+/// Get a transaction by tx_hash. hashes are stored in uppercase hex
+pub fn get_ripple_transaction(tx_hash: &str) -> Option<RippleTxNotification> {
+    with_ripple_tx_notifications(|notifications| notifications.get(&RippleTxNotificationId(tx_hash.to_uppercase())))
+}
+
+/// Clean up old notifications (older than 24 hours)
+///
+/// Used by the canister's background timer which runs every hour. Payments must be used within 5 minutes,
+/// so older notifications are no longer needed.
+pub fn cleanup_old_ripple_notifications() {
+    const TWENTY_FOUR_HOURS_NANOS: u64 = 24 * 60 * 60 * 1_000_000_000;
+    let cutoff_time = ICNetwork::get_time().saturating_sub(TWENTY_FOUR_HOURS_NANOS);
+
+    with_ripple_tx_notifications_mut(|notifications| {
+        let to_remove: Vec<_> = notifications
+            .iter()
+            .filter(|(_, notification)| notification.timestamp < cutoff_time)
+            .map(|(key, _)| key)
+            .collect();
+        for key in to_remove.iter() {
+            notifications.remove(key);
+        }
+    })
+}
@@ -0,0 +1,255 @@
+//! XRPL amounts
+//!
+//! Native XRP amounts are in drops. Issued currency amounts are a decimal value with 16 significant digits,
+//! a 160-bit currency code and the issuer's account.
+
+use candid::Nat;
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::ripple::error::RippleError;
+use crate::ripple::utils::validation;
+use crate::stable_token::ripple_token::{RippleToken, XRP_CURRENCY};
+
+/// maximum amount of XRP in drops (100 billion XRP)
+const MAX_DROPS: u64 = 100_000_000_000_000_000;
+/// normalized mantissa of issued currency values is 10^15 <= mantissa < 10^16
+const MIN_MANTISSA: u64 = 1_000_000_000_000_000;
+const MAX_MANTISSA: u64 = 9_999_999_999_999_999;
+const MIN_EXPONENT: i32 = -96;
+const MAX_EXPONENT: i32 = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RippleAmount {
+    Drops(u64),
+    Issued {
+        mantissa: u64,
+        exponent: i32,
+        currency: [u8; 20],
+        issuer: [u8; 20],
+    },
+}
+
+impl RippleAmount {
+    /// Amount of amount token units of token
+    /// issued currency values keep 16 significant digits, any further digits are truncated
+    pub fn new(token: &RippleToken, amount: &Nat) -> Result<Self, RippleError> {
+        match &token.issuer {
+            None => {
+                let drops = amount
+                    .0
+                    .to_u64()
+                    .filter(|drops| *drops <= MAX_DROPS)
+                    .ok_or(RippleError::InvalidAmount(format!("{} drops exceeds the XRP supply", amount)))?;
+                Ok(RippleAmount::Drops(drops))
+            }
+            Some(issuer) => {
+                let (mantissa, exponent) = normalize(&amount.0, -(token.decimals as i32))?;
+                Ok(RippleAmount::Issued {
+                    mantissa,
+                    exponent,
+                    currency: currency_code(&token.currency)?,
+                    issuer: validation::decode_address(issuer)?,
+                })
+            }
+        }
+    }
+
+    /// serialized amount. 8 bytes for XRP, 48 bytes for issued currencies
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            RippleAmount::Drops(drops) => encode_drops(*drops).to_vec(),
+            RippleAmount::Issued {
+                mantissa,
+                exponent,
+                currency,
+                issuer,
+            } => {
+                let mut bytes = Vec::with_capacity(48);
+                bytes.extend_from_slice(&encode_issued_value(*mantissa, *exponent));
+                bytes.extend_from_slice(currency);
+                bytes.extend_from_slice(issuer);
+                bytes
+            }
+        }
+    }
+}
+
+/// 8-byte native amount. bit 63 = 0 (XRP), bit 62 = 1 (positive), then the drops
+pub fn encode_drops(drops: u64) -> [u8; 8] {
+    (drops | 0x4000_0000_0000_0000).to_be_bytes()
+}
+
+/// 8-byte issued currency value. bit 63 = 1 (issued), bit 62 = 1 (positive),
+/// 8 bits of exponent + 97 and 54 bits of normalized mantissa. zero is only bit 63
+pub fn encode_issued_value(mantissa: u64, exponent: i32) -> [u8; 8] {
+    if mantissa == 0 {
+        return 0x8000_0000_0000_0000_u64.to_be_bytes();
+    }
+    (0xC000_0000_0000_0000 | (((exponent + 97) as u64) << 54) | mantissa).to_be_bytes()
+}
+
+/// normalize value * 10^exponent to a mantissa of 16 digits
+fn normalize(value: &BigUint, exponent: i32) -> Result<(u64, i32), RippleError> {
+    if value.is_zero() {
+        return Ok((0, 0));
+    }
+    let mut value = value.clone();
+    let mut exponent = exponent;
+    let max_mantissa = BigUint::from(MAX_MANTISSA);
+    while value > max_mantissa {
+        value /= 10_u32;
+        exponent += 1;
+    }
+    let mut mantissa = value.to_u64().unwrap_or(0);
+    while mantissa < MIN_MANTISSA {
+        mantissa *= 10;
+        exponent -= 1;
+    }
+    if !(MIN_EXPONENT..=MAX_EXPONENT).contains(&exponent) {
+        return Err(RippleError::InvalidAmount("Amount out of range for an issued currency".to_string()));
+    }
+    Ok((mantissa, exponent))
+}
+
+/// 160-bit currency code. standard 3-character codes are at bytes 12..15, other codes are given as 40 hex characters
+pub fn currency_code(currency: &str) -> Result<[u8; 20], RippleError> {
+    let mut code = [0u8; 20];
+    if currency.len() == 3 {
+        if currency == XRP_CURRENCY || !currency.bytes().all(|c| c.is_ascii_graphic()) {
+            return Err(RippleError::InvalidAmount(format!("Invalid currency code {}", currency)));
+        }
+        code[12..15].copy_from_slice(currency.as_bytes());
+        return Ok(code);
+    }
+    let bytes = hex::decode(currency).map_err(|_| RippleError::InvalidAmount(format!("Invalid currency code {}", currency)))?;
+    // non-standard codes must not start with 0x00 which is reserved for standard codes
+    if bytes.len() != 20 || bytes[0] == 0x00 {
+        return Err(RippleError::InvalidAmount(format!("Invalid currency code {}", currency)));
+    }
+    code.copy_from_slice(&bytes);
+    Ok(code)
+}
+
+/// Parse an issued currency value as given by rippled, e.g. "1.5" or "1e-5", into token units of decimals
+/// fails if the value is negative or has more precision than decimals
+pub fn parse_issued_value(value: &str, decimals: u8) -> Result<Nat, RippleError> {
+    let invalid = || RippleError::InvalidAmount(format!("Invalid issued currency value {}", value));
+    let (number, exponent) = match value.split_once(['e', 'E']) {
+        Some((number, exponent)) => (number, exponent.parse::<i32>().map_err(|_| invalid())?),
+        None => (value, 0),
+    };
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    let mut digits = format!("{}{}", integer, fraction);
+    if !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let mut exponent = exponent - fraction.len() as i32 + decimals as i32;
+    while exponent < 0 && digits.ends_with('0') {
+        digits.pop();
+        exponent += 1;
+    }
+    let digits = if digits.is_empty() { "0".to_string() } else { digits };
+    let mantissa = BigUint::parse_bytes(digits.as_bytes(), 10).ok_or_else(invalid)?;
+    if exponent < 0 {
+        if mantissa.is_zero() {
+            return Ok(Nat::from(0_u32));
+        }
+        return Err(RippleError::InvalidAmount(format!(
+            "Issued currency value {} has more than {} decimals",
+            value, decimals
+        )));
+    }
+    Ok(Nat(mantissa * BigUint::from(10_u32).pow(exponent as u32)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd_token(decimals: u8) -> RippleToken {
+        RippleToken {
+            token_id: 1,
+            name: "US Dollar".to_string(),
+            symbol: "USD".to_string(),
+            decimals,
+            fee: Nat::from(0_u32),
+            currency: "USD".to_string(),
+            issuer: Some("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_encode_drops() {
+        assert_eq!(hex::encode_upper(encode_drops(10)), "400000000000000A");
+        assert_eq!(hex::encode_upper(encode_drops(1_000_000)), "40000000000F4240");
+    }
+
+    #[test]
+    fn test_encode_issued_value() {
+        // 1 = 1000000000000000e-15
+        assert_eq!(
+            hex::encode_upper(encode_issued_value(1_000_000_000_000_000, -15)),
+            "D4838D7EA4C68000"
+        );
+        assert_eq!(hex::encode_upper(encode_issued_value(0, 0)), "8000000000000000");
+    }
+
+    #[test]
+    fn test_issued_amount() {
+        // 1.000000 USD with 6 decimals
+        let amount = RippleAmount::new(&usd_token(6), &Nat::from(1_000_000_u32)).unwrap();
+        let bytes = amount.to_bytes();
+        assert_eq!(bytes.len(), 48);
+        assert_eq!(hex::encode_upper(&bytes[..8]), "D4838D7EA4C68000");
+        assert_eq!(&bytes[20..23], b"USD");
+        assert_eq!(hex::encode_upper(&bytes[28..]), "B5F762798A53D543A014CAF8B297CFF8F2F937E8");
+    }
+
+    #[test]
+    fn test_issued_amount_truncated_to_16_digits() {
+        let amount = RippleAmount::new(&usd_token(0), &Nat::from(123_456_789_012_345_678_u64)).unwrap();
+        match amount {
+            RippleAmount::Issued { mantissa, exponent, .. } => {
+                assert_eq!(mantissa, 1_234_567_890_123_456);
+                assert_eq!(exponent, 2);
+            }
+            _ => panic!("expected issued amount"),
+        }
+    }
+
+    #[test]
+    fn test_drops_amount() {
+        let mut token = usd_token(6);
+        token.currency = XRP_CURRENCY.to_string();
+        token.issuer = None;
+        assert_eq!(RippleAmount::new(&token, &Nat::from(10_u32)).unwrap(), RippleAmount::Drops(10));
+        assert!(RippleAmount::new(&token, &Nat::from(MAX_DROPS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_currency_code() {
+        assert!(currency_code("XRP").is_err());
+        assert!(currency_code("0000000000000000000000005553440000000000").is_err());
+        let code = currency_code("524C555344000000000000000000000000000000").unwrap();
+        assert_eq!(&code[..5], b"RLUSD");
+    }
+
+    #[test]
+    fn test_parse_issued_value() {
+        assert_eq!(parse_issued_value("1", 6).unwrap(), Nat::from(1_000_000_u32));
+        assert_eq!(parse_issued_value("1.5", 6).unwrap(), Nat::from(1_500_000_u32));
+        assert_eq!(parse_issued_value("0.000001", 6).unwrap(), Nat::from(1_u32));
+        assert_eq!(parse_issued_value("1.500000000", 6).unwrap(), Nat::from(1_500_000_u32));
+        assert_eq!(parse_issued_value("1e-5", 6).unwrap(), Nat::from(10_u32));
+        assert_eq!(parse_issued_value("12E2", 0).unwrap(), Nat::from(1_200_u32));
+        assert_eq!(parse_issued_value("0", 6).unwrap(), Nat::from(0_u32));
+        assert!(parse_issued_value("0.0000001", 6).is_err());
+        assert!(parse_issued_value("-1", 6).is_err());
+        assert!(parse_issued_value("", 6).is_err());
+        assert!(parse_issued_value("1.2.3", 6).is_err());
+    }
+}
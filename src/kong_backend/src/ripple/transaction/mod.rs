@@ -0,0 +1,3 @@
+pub mod amount;
+pub mod serialize;
+pub mod sign;
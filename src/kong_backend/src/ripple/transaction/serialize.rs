@@ -0,0 +1,186 @@
+//! XRPL binary serialization of Payment transactions
+//!
+//! Fields are serialized in canonical order, sorted by type code and then field code.
+
+use sha2::{Digest, Sha512};
+
+use crate::ripple::error::RippleError;
+
+use super::amount::{encode_drops, RippleAmount};
+
+/// prefix of the data signed for a single-signed transaction
+const SIGNING_PREFIX: &[u8] = b"STX\0";
+/// prefix of the data hashed for the transaction id
+const TX_HASH_PREFIX: &[u8] = b"TXN\0";
+
+/// tfFullyCanonicalSig, required for secp256k1 signatures and harmless for Ed25519
+pub const TF_FULLY_CANONICAL_SIG: u32 = 0x8000_0000;
+
+const PAYMENT_TRANSACTION_TYPE: u16 = 0;
+
+// field headers, (type code << 4 | field code) for codes < 16
+const TRANSACTION_TYPE: &[u8] = &[0x12]; // UInt16, 2
+const FLAGS: &[u8] = &[0x22]; // UInt32, 2
+const SEQUENCE: &[u8] = &[0x24]; // UInt32, 4
+const LAST_LEDGER_SEQUENCE: &[u8] = &[0x20, 0x1B]; // UInt32, 27
+const AMOUNT: &[u8] = &[0x61]; // Amount, 1
+const FEE: &[u8] = &[0x68]; // Amount, 8
+const SIGNING_PUB_KEY: &[u8] = &[0x73]; // Blob, 3
+const TXN_SIGNATURE: &[u8] = &[0x74]; // Blob, 4
+const ACCOUNT: &[u8] = &[0x81]; // AccountID, 1
+const DESTINATION: &[u8] = &[0x83]; // AccountID, 3
+const MEMOS: &[u8] = &[0xF9]; // STArray, 9
+const MEMO: &[u8] = &[0xEA]; // STObject, 10
+const MEMO_DATA: &[u8] = &[0x7D]; // Blob, 13
+const OBJECT_END: u8 = 0xE1;
+const ARRAY_END: u8 = 0xF1;
+
+/// Payment from account to destination
+#[derive(Debug, Clone)]
+pub struct Payment {
+    pub account: [u8; 20],
+    pub destination: [u8; 20],
+    pub amount: RippleAmount,
+    pub fee_drops: u64,
+    pub sequence: u32,
+    pub last_ledger_sequence: Option<u32>, // ledger after which the payment can no longer be validated
+    pub signing_pub_key: Vec<u8>,          // 33 bytes, 0xED + Ed25519 public key
+    pub memo: Option<String>,
+    pub flags: u32,
+}
+
+impl Payment {
+    /// Serialize the payment, with the signature for submission or without for signing
+    pub fn serialize(&self, txn_signature: Option<&[u8]>) -> Result<Vec<u8>, RippleError> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(TRANSACTION_TYPE);
+        bytes.extend_from_slice(&PAYMENT_TRANSACTION_TYPE.to_be_bytes());
+        bytes.extend_from_slice(FLAGS);
+        bytes.extend_from_slice(&self.flags.to_be_bytes());
+        bytes.extend_from_slice(SEQUENCE);
+        bytes.extend_from_slice(&self.sequence.to_be_bytes());
+        if let Some(last_ledger_sequence) = self.last_ledger_sequence {
+            bytes.extend_from_slice(LAST_LEDGER_SEQUENCE);
+            bytes.extend_from_slice(&last_ledger_sequence.to_be_bytes());
+        }
+        bytes.extend_from_slice(AMOUNT);
+        bytes.extend_from_slice(&self.amount.to_bytes());
+        bytes.extend_from_slice(FEE);
+        bytes.extend_from_slice(&encode_drops(self.fee_drops));
+        bytes.extend_from_slice(SIGNING_PUB_KEY);
+        push_blob(&mut bytes, &self.signing_pub_key)?;
+        if let Some(txn_signature) = txn_signature {
+            bytes.extend_from_slice(TXN_SIGNATURE);
+            push_blob(&mut bytes, txn_signature)?;
+        }
+        bytes.extend_from_slice(ACCOUNT);
+        push_blob(&mut bytes, &self.account)?;
+        bytes.extend_from_slice(DESTINATION);
+        push_blob(&mut bytes, &self.destination)?;
+        if let Some(memo) = &self.memo {
+            bytes.extend_from_slice(MEMOS);
+            bytes.extend_from_slice(MEMO);
+            bytes.extend_from_slice(MEMO_DATA);
+            push_blob(&mut bytes, memo.as_bytes())?;
+            bytes.push(OBJECT_END);
+            bytes.push(ARRAY_END);
+        }
+        Ok(bytes)
+    }
+
+    /// data signed by the account's key
+    pub fn signing_data(&self) -> Result<Vec<u8>, RippleError> {
+        let mut data = SIGNING_PREFIX.to_vec();
+        data.extend_from_slice(&self.serialize(None)?);
+        Ok(data)
+    }
+}
+
+/// transaction id of a signed transaction blob. SHA-512Half of "TXN\0" + blob as uppercase hex
+pub fn tx_hash(tx_blob: &[u8]) -> String {
+    let mut hasher = Sha512::new();
+    hasher.update(TX_HASH_PREFIX);
+    hasher.update(tx_blob);
+    hex::encode_upper(&hasher.finalize()[..32])
+}
+
+/// variable length field. length prefix of 1 byte, blobs of Kong's payments are never longer than 192 bytes
+fn push_blob(bytes: &mut Vec<u8>, blob: &[u8]) -> Result<(), RippleError> {
+    if blob.len() > 192 {
+        return Err(RippleError::SerializationError(format!(
+            "Field of {} bytes is too long",
+            blob.len()
+        )));
+    }
+    bytes.push(blob.len() as u8);
+    bytes.extend_from_slice(blob);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // signed Payment of 1 USD from the XRPL binary format documentation
+    const TX_BLOB: &str = "1200002280000000240000000361D4838D7EA4C6800000000000000000000000000055534400000000004B4E9C06F24296074F7BC48F92A97916C6DC5EA968400000000000000A732103AB40A0490F9B7ED8DF29D246BF2D6269820A0EE7742ACDD457BEA7C7D0931EDB74473045022100D184EB4AE5956FF600E7536EE459345C7BBCF097A84CC61A93B9AF7197EDB98702201CEA8009B7BEEBAA2AACC0359B41C427C1C5B550A4CA4B80CF2174AF2D6D5DCE81144B4E9C06F24296074F7BC48F92A97916C6DC5EA983143E9D4A2B8AA0780F682D136F7A56D6724EF53754";
+    const TX_HASH: &str = "82230B9D489370504B39BC2CE46216176CAC9E752E5C1774A8CBEC9FBB819208";
+    const SIGNING_PUB_KEY: &str = "03AB40A0490F9B7ED8DF29D246BF2D6269820A0EE7742ACDD457BEA7C7D0931EDB";
+    const TXN_SIGNATURE: &str = "3045022100D184EB4AE5956FF600E7536EE459345C7BBCF097A84CC61A93B9AF7197EDB98702201CEA8009B7BEEBAA2AACC0359B41C427C1C5B550A4CA4B80CF2174AF2D6D5DCE";
+    const ACCOUNT: &str = "4B4E9C06F24296074F7BC48F92A97916C6DC5EA9";
+    const DESTINATION: &str = "3E9D4A2B8AA0780F682D136F7A56D6724EF53754";
+
+    fn account_id(hex_str: &str) -> [u8; 20] {
+        hex::decode(hex_str).unwrap().try_into().unwrap()
+    }
+
+    fn payment() -> Payment {
+        let mut currency = [0u8; 20];
+        currency[12..15].copy_from_slice(b"USD");
+        Payment {
+            account: account_id(ACCOUNT),
+            destination: account_id(DESTINATION),
+            amount: RippleAmount::Issued {
+                mantissa: 1_000_000_000_000_000,
+                exponent: -15,
+                currency,
+                issuer: account_id(ACCOUNT),
+            },
+            fee_drops: 10,
+            sequence: 3,
+            last_ledger_sequence: None,
+            signing_pub_key: hex::decode(SIGNING_PUB_KEY).unwrap(),
+            memo: None,
+            flags: TF_FULLY_CANONICAL_SIG,
+        }
+    }
+
+    #[test]
+    fn test_serialize_signed_payment() {
+        let signature = hex::decode(TXN_SIGNATURE).unwrap();
+        let tx_blob = payment().serialize(Some(&signature)).unwrap();
+        assert_eq!(hex::encode_upper(&tx_blob), TX_BLOB);
+        assert_eq!(tx_hash(&tx_blob), TX_HASH);
+    }
+
+    #[test]
+    fn test_signing_data() {
+        let signing_data = payment().signing_data().unwrap();
+        assert_eq!(&signing_data[..4], SIGNING_PREFIX);
+        // the signing data has no TxnSignature field
+        let unsigned = hex::encode_upper(&signing_data[4..]);
+        let signed_without_signature = TX_BLOB.replace(&format!("7447{}", TXN_SIGNATURE), "");
+        assert_eq!(unsigned, signed_without_signature);
+    }
+
+    #[test]
+    fn test_serialize_last_ledger_sequence_and_memo() {
+        let mut payment = payment();
+        payment.amount = RippleAmount::Drops(1_000_000);
+        payment.last_ledger_sequence = Some(0x0102_0304);
+        payment.memo = Some("Kong".to_string());
+        let hex = hex::encode_upper(payment.serialize(None).unwrap());
+        assert!(hex.contains("201B01020304"));
+        assert!(hex.contains("6140000000000F4240"));
+        assert!(hex.ends_with("F9EA7D044B6F6E67E1F1"));
+    }
+}
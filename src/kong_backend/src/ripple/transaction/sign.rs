@@ -0,0 +1,34 @@
+//! Transaction signing for the XRP Ledger
+//!
+//! This module handles signing of XRPL transactions using IC's Schnorr Ed25519 signatures.
+
+use anyhow::Result;
+
+use crate::kong_backend::KongBackend;
+use crate::ripple::error::RippleError;
+
+use super::serialize::{tx_hash, Payment};
+
+/// Signed transaction ready for submission
+#[derive(Debug, Clone)]
+pub struct SignedPayment {
+    pub tx_blob: String, // hex of the signed transaction, as submitted to rippled
+    pub tx_hash: String, // transaction id
+}
+
+/// Sign a payment with Kong's key
+pub async fn sign_payment(payment: &Payment) -> Result<SignedPayment> {
+    let signing_data = payment.signing_data()?;
+
+    // Ed25519 signs the signing data itself, not a hash of it
+    let signature = KongBackend::sign_with_schnorr(&signing_data)
+        .await
+        .map_err(|e| RippleError::SigningError(e.to_string()))?;
+
+    let tx_blob = payment.serialize(Some(&signature))?;
+
+    Ok(SignedPayment {
+        tx_hash: tx_hash(&tx_blob),
+        tx_blob: hex::encode_upper(&tx_blob),
+    })
+}
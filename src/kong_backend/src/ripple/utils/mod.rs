@@ -1 +1,2 @@
 pub mod base58;
+pub mod validation;
@@ -0,0 +1,88 @@
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use super::super::error::RippleError;
+use super::base58;
+
+/// version byte of classic XRPL account addresses
+const ACCOUNT_ID_VERSION: u8 = 0x00;
+
+/// Account id of a public key. RIPEMD160(SHA256(public_key))
+/// public_key is 33 bytes, 0xED + Ed25519 key or a compressed secp256k1 key
+pub fn account_id(public_key: &[u8]) -> [u8; 20] {
+    let sha = Sha256::digest(public_key);
+    let mut ripemd = Ripemd160::new();
+    ripemd.update(sha);
+    ripemd.finalize().into()
+}
+
+/// Encode an account id as a classic address. base58 of version + account_id + checksum
+pub fn encode_address(account_id: &[u8; 20]) -> String {
+    let mut payload = Vec::with_capacity(25);
+    payload.push(ACCOUNT_ID_VERSION);
+    payload.extend_from_slice(account_id);
+    payload.extend_from_slice(&checksum(&payload));
+    base58::encode_wallet_address(&payload)
+}
+
+/// Decode a classic address into its account id, checking the version and checksum
+pub fn decode_address(address: &str) -> Result<[u8; 20], RippleError> {
+    let bytes = base58::decode_wallet_address(address)?;
+    if bytes.len() != 25 {
+        return Err(RippleError::InvalidAddress(format!("Expected 25 bytes, got {}", bytes.len())));
+    }
+    if bytes[0] != ACCOUNT_ID_VERSION {
+        return Err(RippleError::InvalidAddress("Not a classic account address".to_string()));
+    }
+    if checksum(&bytes[..21]) != bytes[21..] {
+        return Err(RippleError::InvalidAddress("Invalid checksum".to_string()));
+    }
+    let mut account_id = [0u8; 20];
+    account_id.copy_from_slice(&bytes[1..21]);
+    Ok(account_id)
+}
+
+/// Validate a classic XRPL address is well-formed
+pub fn validate_address(address: &str) -> Result<(), RippleError> {
+    decode_address(address)?;
+    Ok(())
+}
+
+/// first 4 bytes of SHA256(SHA256(payload))
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // genesis account of the XRP Ledger
+    const GENESIS_PUBLIC_KEY: &str = "0330E7FC9D56BB25D6893BA3F317AE5BCF33B3291BD63DB32654A313222F7FD020";
+    const GENESIS_ACCOUNT_ID: &str = "B5F762798A53D543A014CAF8B297CFF8F2F937E8";
+    const GENESIS_ADDRESS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+
+    #[test]
+    fn test_account_id_and_address() {
+        let public_key = hex::decode(GENESIS_PUBLIC_KEY).unwrap();
+        let account_id = account_id(&public_key);
+        assert_eq!(hex::encode_upper(account_id), GENESIS_ACCOUNT_ID);
+        assert_eq!(encode_address(&account_id), GENESIS_ADDRESS);
+    }
+
+    #[test]
+    fn test_decode_address() {
+        let account_id = decode_address(GENESIS_ADDRESS).unwrap();
+        assert_eq!(hex::encode_upper(account_id), GENESIS_ACCOUNT_ID);
+    }
+
+    #[test]
+    fn test_decode_address_invalid() {
+        // last character changed, checksum no longer matches
+        assert!(decode_address("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTi").is_err());
+        // Solana address
+        assert!(decode_address("11111111111111111111111111111111").is_err());
+        assert!(decode_address("").is_err());
+    }
+}
@@ -0,0 +1,414 @@
+//! XRPL payment verification module
+//! Handles verification of payments to Kong's XRPL account by checking the sender's signature and the validated transaction
+
+use anyhow::Result;
+use candid::Nat;
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use sha2::{Digest, Sha512};
+
+use crate::ic::network::ICNetwork;
+use crate::stable_token::ripple_token::RippleToken;
+use crate::stable_token::stable_token::StableToken;
+use crate::stable_transfer::{transfer_map, tx_id::TxId};
+
+use super::error::RippleError;
+use super::network::ED25519_PREFIX;
+use super::stable_memory::{get_cached_ripple_address, get_ripple_transaction};
+use super::transaction::amount::parse_issued_value;
+use super::utils::validation;
+
+/// seconds between the Unix epoch and the Ripple epoch (2000-01-01)
+const RIPPLE_EPOCH_OFFSET_SECS: u64 = 946_684_800;
+/// payments must be used within 5 minutes of the ledger close time
+const MAX_TRANSACTION_AGE_SECS: u64 = 300;
+
+/// Result of XRPL payment verification
+#[derive(Debug, Clone)]
+pub struct RippleVerificationResult {
+    pub tx_hash: String,
+    pub from_address: String,
+    pub amount: Nat,
+}
+
+/// Verify an XRPL payment to Kong by checking the sender's signature and the validated transaction reported by kong_rpc
+///
+/// # Arguments
+/// * `tx_hash` - The transaction hash of the payment
+/// * `signature` - The hex signature of the canonical message by the sending account's master key
+/// * `amount` - The expected amount in token units
+/// * `canonical_message` - The message that was signed
+/// * `expected_token` - The expected XRPL token
+///
+/// # Returns
+/// * `Ok(RippleVerificationResult)` - If verification succeeds
+/// * `Err(String)` - If verification fails with reason
+pub fn verify_transfer(
+    tx_hash: &str,
+    signature: &str,
+    amount: &Nat,
+    canonical_message: &str,
+    expected_token: &StableToken,
+) -> Result<RippleVerificationResult, String> {
+    let StableToken::Ripple(token) = expected_token else {
+        return Err("Not an XRPL token".to_string());
+    };
+
+    let transaction = get_ripple_transaction(tx_hash).ok_or_else(|| {
+        format!(
+            "XRPL transaction {} not found. Make sure kong_rpc has processed this transaction.",
+            tx_hash
+        )
+    })?;
+
+    let metadata: serde_json::Value =
+        serde_json::from_str(&transaction.metadata).map_err(|e| format!("Failed to parse transaction metadata: {}", e))?;
+
+    validate_transaction(&metadata)?;
+    validate_freshness(&metadata, ICNetwork::get_time())?;
+
+    // Validate receiver address matches Kong's address
+    let kong_address = get_cached_ripple_address();
+    let destination = get_str(&metadata, "Destination")?;
+    if destination != kong_address {
+        return Err(format!(
+            "Invalid receiver address. Expected: {}, Got: {}",
+            kong_address, destination
+        ));
+    }
+
+    validate_delivered_amount(&metadata, token, amount)?;
+
+    // the payment must be signed by the sender's master key, which is also the key that signed the canonical message
+    let sender = get_str(&metadata, "Account")?;
+    let signing_pub_key = get_str(&metadata, "SigningPubKey")?;
+    let public_key = hex::decode(signing_pub_key).map_err(|_| "Invalid SigningPubKey in transaction metadata".to_string())?;
+    if validation::encode_address(&validation::account_id(&public_key)) != sender {
+        return Err(format!(
+            "Payment from {} must be signed with the account's master key. Regular keys and multi-signing are not supported",
+            sender
+        ));
+    }
+
+    verify_canonical_message(canonical_message, signing_pub_key, signature).map_err(|e| format!("Signature verification failed: {}", e))?;
+
+    Ok(RippleVerificationResult {
+        tx_hash: transaction.tx_hash,
+        from_address: sender.to_string(),
+        amount: amount.clone(),
+    })
+}
+
+/// Get the sender of a payment reported by kong_rpc
+pub fn get_sender(tx_hash: &str) -> Result<String, String> {
+    let transaction = get_ripple_transaction(tx_hash).ok_or(format!("XRPL transaction {} not found", tx_hash))?;
+    let metadata: serde_json::Value =
+        serde_json::from_str(&transaction.metadata).map_err(|e| format!("Failed to parse transaction metadata: {}", e))?;
+    Ok(get_str(&metadata, "Account")?.to_string())
+}
+
+/// Get the sender of the payment of token_id among the transfers of a request, to return the tokens to
+pub fn get_sender_from_transfers(token_id: u32, transfer_ids: &[u64]) -> Result<String, String> {
+    transfer_ids
+        .iter()
+        .filter_map(|&transfer_id| transfer_map::get_by_transfer_id(transfer_id))
+        .find_map(|transfer| match transfer.tx_id {
+            TxId::TransactionId(tx_hash) if transfer.is_send && transfer.token_id == token_id => Some(tx_hash),
+            _ => None,
+        })
+        .ok_or("No XRPL sender address found in transfers".to_string())
+        .and_then(|tx_hash| get_sender(&tx_hash))
+}
+
+fn get_str<'a>(metadata: &'a serde_json::Value, field: &str) -> Result<&'a str, String> {
+    metadata
+        .get(field)
+        .and_then(|v| v.as_str())
+        .ok_or(format!("Transaction metadata missing {}", field))
+}
+
+/// the transaction must be a validated Payment that succeeded
+fn validate_transaction(metadata: &serde_json::Value) -> Result<(), String> {
+    if metadata.get("validated").and_then(|v| v.as_bool()) != Some(true) {
+        return Err("XRPL transaction is not validated".to_string());
+    }
+    let transaction_type = get_str(metadata, "TransactionType")?;
+    if transaction_type != "Payment" {
+        return Err(format!("Expected a Payment transaction, got {}", transaction_type));
+    }
+    let result = metadata
+        .get("meta")
+        .and_then(|meta| meta.get("TransactionResult"))
+        .and_then(|v| v.as_str())
+        .ok_or("Transaction metadata missing TransactionResult")?;
+    if result != "tesSUCCESS" {
+        return Err(format!("XRPL payment failed with {}", result));
+    }
+    Ok(())
+}
+
+/// Verify ledger close time freshness (5 minute window) at ts to prevent replay attacks with old payments
+fn validate_freshness(metadata: &serde_json::Value, ts: u64) -> Result<(), String> {
+    let date = metadata
+        .get("date")
+        .and_then(|v| v.as_u64())
+        .ok_or("XRPL transaction missing ledger close time")?;
+    let tx_timestamp_secs = date + RIPPLE_EPOCH_OFFSET_SECS;
+    let current_time_secs = ts / 1_000_000_000;
+    let age_secs = current_time_secs.saturating_sub(tx_timestamp_secs);
+    if age_secs > MAX_TRANSACTION_AGE_SECS {
+        return Err(format!(
+            "XRPL transaction is too old: {} minutes ago. Transactions must be less than {} minutes old.",
+            age_secs / 60,
+            MAX_TRANSACTION_AGE_SECS / 60
+        ));
+    }
+    Ok(())
+}
+
+/// the amount delivered to Kong must be of the token and equal amount. partial payments are checked on what was delivered
+fn validate_delivered_amount(metadata: &serde_json::Value, token: &RippleToken, amount: &Nat) -> Result<(), String> {
+    let delivered_amount = metadata
+        .get("meta")
+        .and_then(|meta| meta.get("delivered_amount"))
+        .ok_or("Transaction metadata missing delivered_amount")?;
+
+    let actual_amount = match (&token.issuer, delivered_amount) {
+        // XRP amounts are a string of drops
+        (None, serde_json::Value::String(drops)) => drops
+            .parse::<u64>()
+            .map(Nat::from)
+            .map_err(|_| format!("Invalid delivered_amount {}", drops))?,
+        (Some(issuer), serde_json::Value::Object(issued)) => {
+            let currency = issued.get("currency").and_then(|v| v.as_str()).unwrap_or_default();
+            let actual_issuer = issued.get("issuer").and_then(|v| v.as_str()).unwrap_or_default();
+            if !currency.eq_ignore_ascii_case(&token.currency) || actual_issuer != issuer {
+                return Err(format!(
+                    "Token mismatch. Expected {} issued by {}, got {} issued by {}",
+                    token.currency, issuer, currency, actual_issuer
+                ));
+            }
+            let value = issued.get("value").and_then(|v| v.as_str()).unwrap_or_default();
+            parse_issued_value(value, token.decimals).map_err(|e| e.to_string())?
+        }
+        _ => return Err(format!("Token mismatch. Expected {} payment", token.symbol)),
+    };
+
+    if actual_amount != *amount {
+        return Err(format!("Transaction amount mismatch. Expected: {}, Got: {}", amount, actual_amount));
+    }
+    Ok(())
+}
+
+/// Verify a signature against a canonical message
+///
+/// Ed25519 keys (0xED prefix) sign the message itself. secp256k1 keys sign SHA-512Half of the message with ECDSA,
+/// as XRPL wallets sign messages with the account's key. signature is hex, DER or 64-byte for secp256k1
+pub fn verify_canonical_message(message: &str, public_key: &str, signature: &str) -> Result<()> {
+    let public_key = hex::decode(public_key).map_err(|_| RippleError::InvalidPublicKeyFormat("Invalid hex".to_string()))?;
+    let signature = hex::decode(signature).map_err(|_| RippleError::InvalidMessageSigning("Invalid hex signature".to_string()))?;
+
+    match public_key.first() {
+        Some(&ED25519_PREFIX) if public_key.len() == 33 => {
+            let verify_key = ed25519_dalek::VerifyingKey::from_bytes(public_key[1..].try_into()?)?;
+            let ed25519_signature = ed25519_dalek::Signature::from_slice(&signature)?;
+            verify_key.verify_strict(message.as_bytes(), &ed25519_signature)?;
+        }
+        Some(0x02) | Some(0x03) if public_key.len() == 33 => {
+            let verify_key = k256::ecdsa::VerifyingKey::from_sec1_bytes(&public_key)?;
+            let ecdsa_signature =
+                k256::ecdsa::Signature::from_der(&signature).or_else(|_| k256::ecdsa::Signature::from_slice(&signature))?;
+            // XRPL only accepts canonical low-S signatures on ledger, wallets may still produce high-S ones for messages
+            let ecdsa_signature = ecdsa_signature.normalize_s().unwrap_or(ecdsa_signature);
+            let hash = Sha512::digest(message.as_bytes());
+            verify_key.verify_prehash(&hash[..32], &ecdsa_signature)?;
+        }
+        _ => return Err(RippleError::InvalidPublicKeyFormat("Unsupported public key type".to_string()).into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Signer;
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use serde_json::json;
+
+    use crate::ripple::kong_rpc::notify_ripple_payment::notify_ripple_payment;
+    use crate::ripple::stable_memory::set_cached_ripple_address;
+
+    // genesis account of the XRP Ledger as Kong's account
+    const KONG_ADDRESS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+    const USD_ISSUER: &str = "rvYAfWj5gh67oV6fW32ZzP3Aw4Eubs59B";
+    const TX_HASH: &str = "C53ECF838647FA5A4C780377025FEC7999AB4182590510CA461444B207AB74A9";
+    const MESSAGE: &str = "kong swap 1000000 XRP to ICP";
+
+    fn ed25519_key() -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[1_u8; 32])
+    }
+
+    fn ed25519_public_key(key: &ed25519_dalek::SigningKey) -> String {
+        hex::encode_upper([&[ED25519_PREFIX][..], key.verifying_key().as_bytes()].concat())
+    }
+
+    fn secp256k1_key() -> k256::ecdsa::SigningKey {
+        k256::ecdsa::SigningKey::from_slice(&[2_u8; 32]).unwrap()
+    }
+
+    fn secp256k1_public_key(key: &k256::ecdsa::SigningKey) -> String {
+        hex::encode_upper(key.verifying_key().to_encoded_point(true).as_bytes())
+    }
+
+    // DER signature of SHA-512Half of the message, as XRPL wallets sign with secp256k1 keys
+    fn secp256k1_signature(key: &k256::ecdsa::SigningKey, message: &str) -> String {
+        let hash = Sha512::digest(message.as_bytes());
+        let signature: k256::ecdsa::Signature = key.sign_prehash(&hash[..32]).unwrap();
+        hex::encode(signature.to_der().as_bytes())
+    }
+
+    fn address(public_key: &str) -> String {
+        validation::encode_address(&validation::account_id(&hex::decode(public_key).unwrap()))
+    }
+
+    fn xrp_token() -> StableToken {
+        StableToken::Ripple(RippleToken {
+            token_id: 1,
+            name: "XRP".to_string(),
+            symbol: "XRP".to_string(),
+            decimals: 6,
+            fee: Nat::from(12_u32),
+            currency: "XRP".to_string(),
+            issuer: None,
+        })
+    }
+
+    fn usd_token() -> StableToken {
+        StableToken::Ripple(RippleToken {
+            token_id: 2,
+            name: "US Dollar".to_string(),
+            symbol: "USD".to_string(),
+            decimals: 6,
+            fee: Nat::from(0_u32),
+            currency: "USD".to_string(),
+            issuer: Some(USD_ISSUER.to_string()),
+        })
+    }
+
+    // validated payment of 1 XRP to Kong signed by the master key of the sending account
+    fn payment(public_key: &str) -> serde_json::Value {
+        json!({
+            "TransactionType": "Payment",
+            "Account": address(public_key),
+            "Destination": KONG_ADDRESS,
+            "Amount": "1000000",
+            "SigningPubKey": public_key,
+            "date": 800_000_000,
+            "validated": true,
+            "meta": {"TransactionResult": "tesSUCCESS", "delivered_amount": "1000000"}
+        })
+    }
+
+    // verify the payment reported by kong_rpc with metadata, signed with the ed25519 key
+    fn verify(metadata: &serde_json::Value, token: &StableToken, amount: u64) -> Result<RippleVerificationResult, String> {
+        set_cached_ripple_address(KONG_ADDRESS.to_string());
+        notify_ripple_payment(TX_HASH.to_lowercase(), metadata.to_string()).unwrap();
+        let signature = hex::encode(ed25519_key().sign(MESSAGE.as_bytes()).to_bytes());
+        verify_transfer(TX_HASH, &signature, &Nat::from(amount), MESSAGE, token)
+    }
+
+    #[test]
+    fn test_verify_transfer() {
+        let public_key = ed25519_public_key(&ed25519_key());
+        let result = verify(&payment(&public_key), &xrp_token(), 1_000_000).unwrap();
+        assert_eq!(result.from_address, address(&public_key));
+        assert_eq!(result.amount, Nat::from(1_000_000_u32));
+        assert_eq!(get_sender(&TX_HASH.to_lowercase()).unwrap(), address(&public_key));
+
+        // a different amount than paid
+        assert!(verify(&payment(&public_key), &xrp_token(), 1_000_001).is_err());
+        // a payment that failed on the ledger
+        let mut metadata = payment(&public_key);
+        metadata["meta"]["TransactionResult"] = json!("tecUNFUNDED_PAYMENT");
+        assert!(verify(&metadata, &xrp_token(), 1_000_000).is_err());
+    }
+
+    #[test]
+    fn test_partial_payment_is_checked_on_delivered_amount() {
+        let mut metadata = payment(&ed25519_public_key(&ed25519_key()));
+        // tfPartialPayment delivering less than Amount
+        metadata["Flags"] = json!(131_072);
+        metadata["meta"]["delivered_amount"] = json!("900000");
+
+        let e = verify(&metadata, &xrp_token(), 1_000_000).unwrap_err();
+        assert!(e.contains("amount mismatch"), "{}", e);
+        assert!(verify(&metadata, &xrp_token(), 900_000).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_issuer_or_currency_is_rejected() {
+        let mut metadata = payment(&ed25519_public_key(&ed25519_key()));
+        let issued = |currency: &str, issuer: &str| json!({"currency": currency, "issuer": issuer, "value": "1.5"});
+
+        metadata["meta"]["delivered_amount"] = issued("USD", USD_ISSUER);
+        assert!(verify(&metadata, &usd_token(), 1_500_000).is_ok());
+
+        metadata["meta"]["delivered_amount"] = issued("USD", KONG_ADDRESS);
+        let e = verify(&metadata, &usd_token(), 1_500_000).unwrap_err();
+        assert!(e.contains("Token mismatch"), "{}", e);
+
+        metadata["meta"]["delivered_amount"] = issued("EUR", USD_ISSUER);
+        assert!(verify(&metadata, &usd_token(), 1_500_000).is_err());
+
+        // XRP paid for an issued token and the other way round
+        metadata["meta"]["delivered_amount"] = json!("1500000");
+        assert!(verify(&metadata, &usd_token(), 1_500_000).is_err());
+        metadata["meta"]["delivered_amount"] = issued("XRP", USD_ISSUER);
+        assert!(verify(&metadata, &xrp_token(), 1_500_000).is_err());
+    }
+
+    #[test]
+    fn test_wrong_destination_is_rejected() {
+        let mut metadata = payment(&ed25519_public_key(&ed25519_key()));
+        metadata["Destination"] = json!(USD_ISSUER);
+
+        let e = verify(&metadata, &xrp_token(), 1_000_000).unwrap_err();
+        assert!(e.contains("Invalid receiver address"), "{}", e);
+    }
+
+    #[test]
+    fn test_regular_key_signed_payment_is_rejected() {
+        // payment of the secp256k1 account signed by its regular key, the ed25519 key that also signs the message
+        let mut metadata = payment(&ed25519_public_key(&ed25519_key()));
+        metadata["Account"] = json!(address(&secp256k1_public_key(&secp256k1_key())));
+
+        let e = verify(&metadata, &xrp_token(), 1_000_000).unwrap_err();
+        assert!(e.contains("master key"), "{}", e);
+    }
+
+    #[test]
+    fn test_verify_canonical_message() {
+        let ed25519_key = ed25519_key();
+        let ed25519_signature = hex::encode(ed25519_key.sign(MESSAGE.as_bytes()).to_bytes());
+        assert!(verify_canonical_message(MESSAGE, &ed25519_public_key(&ed25519_key), &ed25519_signature).is_ok());
+        assert!(verify_canonical_message("another message", &ed25519_public_key(&ed25519_key), &ed25519_signature).is_err());
+
+        let secp256k1_key = secp256k1_key();
+        let secp256k1_signature = secp256k1_signature(&secp256k1_key, MESSAGE);
+        assert!(verify_canonical_message(MESSAGE, &secp256k1_public_key(&secp256k1_key), &secp256k1_signature).is_ok());
+        assert!(verify_canonical_message("another message", &secp256k1_public_key(&secp256k1_key), &secp256k1_signature).is_err());
+        // a key of one type does not verify the other's signature
+        assert!(verify_canonical_message(MESSAGE, &ed25519_public_key(&ed25519_key), &secp256k1_signature).is_err());
+    }
+
+    #[test]
+    fn test_stale_ledger_time_is_rejected() {
+        let metadata = json!({"date": 800_000_000});
+        let close_ts = (800_000_000 + RIPPLE_EPOCH_OFFSET_SECS) * 1_000_000_000;
+
+        assert!(validate_freshness(&metadata, close_ts).is_ok());
+        assert!(validate_freshness(&metadata, close_ts + MAX_TRANSACTION_AGE_SECS * 1_000_000_000).is_ok());
+        let e = validate_freshness(&metadata, close_ts + (MAX_TRANSACTION_AGE_SECS + 1) * 1_000_000_000).unwrap_err();
+        assert!(e.contains("too old"), "{}", e);
+        assert!(validate_freshness(&json!({}), close_ts).is_err());
+    }
+}
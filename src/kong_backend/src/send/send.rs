@@ -7,6 +7,7 @@ use super::send_reply::SendReply;
 use crate::chains::chains::LP_CHAIN;
 use crate::ic::{guards::not_in_maintenance_mode, network::ICNetwork};
 use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_lp_token::lp_block_map;
 use crate::stable_lp_token::transfer::transfer;
 use crate::stable_request::request_map;
 use crate::stable_request::{reply::Reply, request::Request, stable_request::StableRequest, status::StatusCode};
@@ -85,8 +86,9 @@ fn process_send(
     request_map::update_status(request_id, StatusCode::Start, None);
 
     request_map::update_status(request_id, StatusCode::SendLPTokenToUser, None);
-    match transfer(lp_token_id, to_user_id, amount) {
+    match transfer(lp_token_id, to_user_id, amount, ts) {
         Ok(_) => {
+            lp_block_map::insert_transfer(lp_token_id, from_user_id, to_user_id, None, amount, None, ts);
             request_map::update_status(request_id, StatusCode::SendLPTokenToUserSuccess, None);
         }
         Err(e) => {
@@ -102,7 +104,8 @@ fn process_send(
     let send_tx = SendTx::new_success(from_user_id, request_id, to_user_id, lp_token_id, amount, ts);
     let tx_id = tx_map::insert(&StableTx::Send(send_tx.clone()));
     let reply = match tx_map::get_by_user_and_token_id(Some(tx_id), None, None, None).first() {
-        Some(StableTx::Send(send_tx)) => SendReply::try_from(send_tx).unwrap_or_else(|_| SendReply::failed(request_id, &lp_token_chain, &lp_token_symbol, &amount, &to_address, ts)),
+        Some(StableTx::Send(send_tx)) => SendReply::try_from(send_tx)
+            .unwrap_or_else(|_| SendReply::failed(request_id, &lp_token_chain, &lp_token_symbol, &amount, &to_address, ts)),
         _ => SendReply::failed(request_id, &lp_token_chain, &lp_token_symbol, &amount, &to_address, ts),
     };
     request_map::update_reply(request_id, Reply::Send(reply.clone()));
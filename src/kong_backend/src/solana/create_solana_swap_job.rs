@@ -7,11 +7,13 @@ use candid::Nat;
 use num_traits::ToPrimitive;
 
 use crate::ic::address::Address;
-use crate::solana::stable_memory::{get_cached_solana_address, get_next_solana_swap_job_id, with_swap_job_queue_mut};
+use crate::solana::stable_memory::{get_cached_solana_address, get_next_solana_swap_job_id, with_solana_blockhash, with_swap_job_queue_mut};
 use crate::solana::swap_job::{SwapJob, SwapJobId, SwapJobStatus};
 use crate::solana::transaction::builder::{SplTransferWithAtaParams, TransactionBuilder};
 use crate::solana::transaction::sign::sign_transaction;
+use crate::stable_token::solana_token::SolanaToken;
 use crate::stable_token::stable_token::StableToken;
+use crate::stable_token::token::Token;
 
 /// Creates a Solana swap job for processing an outgoing transfer
 pub async fn create_solana_swap_job(
@@ -38,11 +40,11 @@ pub async fn create_solana_swap_job(
                 // Direct Solana address - this is what we want
                 address.clone()
             }
+            Address::RippleAddress(_) => {
+                return Err("Cannot send Solana tokens to an XRPL address. Please provide a Solana address.".to_string());
+            }
         };
 
-        // Get Kong's Solana address
-        let kong_address = get_cached_solana_address();
-
         // Get the job ID
         let job_id = get_next_solana_swap_job_id();
 
@@ -53,69 +55,28 @@ pub async fn create_solana_swap_job(
             .to_u64()
             .ok_or("Amount too large for Solana transfer (max ~18.4e18)")?;
 
-        // Build transaction instructions based on token type
-        let instructions = if sol_token.mint_address == "11111111111111111111111111111111" {
-            // Native SOL transfer
-            TransactionBuilder::build_transfer_sol_transaction(
-                &kong_address,
-                &destination_address,
-                amount_u64,
-                Some(format!("Kong swap job #{}", job_id)),
-            )
-            .await
-            .map_err(|e| format!("Failed to build SOL transfer: {}", e))?
-        } else {
-            // SPL token transfer with ATA creation
-            let from_token_account = TransactionBuilder::derive_associated_token_account(&kong_address, &sol_token.mint_address)
-                .map_err(|e| format!("Failed to derive source ATA: {}", e))?;
-            let to_token_account = TransactionBuilder::derive_associated_token_account(&destination_address, &sol_token.mint_address)
-                .map_err(|e| format!("Failed to derive destination ATA: {}", e))?;
-
-            let params = SplTransferWithAtaParams {
-                from_address: &kong_address,
-                from_token_account: &from_token_account,
-                to_wallet_address: &destination_address,
-                to_token_account: &to_token_account,
-                mint_address: &sol_token.mint_address,
-                fee_payer: &kong_address,
-                amount: amount_u64,
-                memo: Some(format!("Kong swap job #{}", job_id)),
-            };
-
-            TransactionBuilder::build_transfer_spl_with_ata_transaction(params)
-                .await
-                .map_err(|e| format!("Failed to build SPL transfer with ATA: {}", e))?
-        };
-
-        // Sign the transaction
-        let signed_tx = sign_transaction(instructions, &kong_address)
-            .await
-            .map_err(|e| format!("Failed to sign transaction: {}", e))?;
-
-        // Extract signature for tracking first (before encoding)
-        let tx_sig = if !signed_tx.signatures.is_empty() {
-            bs58::encode(&signed_tx.signatures[0]).into_string()
-        } else {
-            return Err("No signature in signed transaction".to_string());
-        };
-
-        // Encode the signed transaction using proper Solana transaction format
-        let encoded_tx = signed_tx.encode().map_err(|e| format!("Failed to encode transaction: {}", e))?;
+        let signed_transfer = build_signed_transfer(sol_token, amount_u64, &destination_address, job_id, 1).await?;
 
         // Create the swap job using passed timestamp
 
-        let swap_job = SwapJob::new(
-            job_id,
-            user_id,
-            request_id,
-            SwapJobStatus::Pending,
-            ts,
-            ts,
-            encoded_tx,
-            None,
-            None,
-            tx_sig,
-        );
+        let swap_job = SwapJob {
+            token_id: receive_token.token_id(),
+            amount: amount_u64,
+            to_address: destination_address,
+            blockhash: signed_transfer.blockhash,
+            ..SwapJob::new(
+                job_id,
+                user_id,
+                request_id,
+                SwapJobStatus::Pending,
+                ts,
+                ts,
+                signed_transfer.encoded_tx,
+                None,
+                None,
+                signed_transfer.tx_sig,
+            )
+        };
 
         // Store the job in the queue
         with_swap_job_queue_mut(|queue| {
@@ -127,3 +88,90 @@ pub async fn create_solana_swap_job(
         Err("Not a Solana token".to_string())
     }
 }
+
+/// Signed transfer of a swap job
+pub struct SignedTransfer {
+    pub tx_sig: String,
+    pub encoded_tx: String,
+    pub blockhash: String,
+}
+
+/// Build and sign the transfer of amount of sol_token from Kong to destination_address
+/// with the latest blockhash. priority_fee_multiplier bumps the priority fee of re-signed transactions
+pub async fn build_signed_transfer(
+    sol_token: &SolanaToken,
+    amount: u64,
+    destination_address: &str,
+    job_id: u64,
+    priority_fee_multiplier: u64,
+) -> Result<SignedTransfer, String> {
+    // Get Kong's Solana address
+    let kong_address = get_cached_solana_address();
+
+    // Build transaction instructions based on token type
+    let mut instructions = if sol_token.mint_address == "11111111111111111111111111111111" {
+        // Native SOL transfer
+        TransactionBuilder::build_transfer_sol_transaction(
+            &kong_address,
+            destination_address,
+            amount,
+            Some(format!("Kong swap job #{}", job_id)),
+        )
+        .await
+        .map_err(|e| format!("Failed to build SOL transfer: {}", e))?
+    } else {
+        // SPL token transfer with ATA creation
+        // SPL Token or Token-2022 program of the mint
+        let token_program_id = sol_token.token_program_id();
+        let from_token_account =
+            TransactionBuilder::derive_associated_token_account(&kong_address, &sol_token.mint_address, token_program_id)
+                .map_err(|e| format!("Failed to derive source ATA: {}", e))?;
+        let to_token_account =
+            TransactionBuilder::derive_associated_token_account(destination_address, &sol_token.mint_address, token_program_id)
+                .map_err(|e| format!("Failed to derive destination ATA: {}", e))?;
+
+        let params = SplTransferWithAtaParams {
+            from_address: &kong_address,
+            from_token_account: &from_token_account,
+            to_wallet_address: destination_address,
+            to_token_account: &to_token_account,
+            mint_address: &sol_token.mint_address,
+            decimals: sol_token.decimals,
+            token_program_id,
+            fee_payer: &kong_address,
+            amount,
+            memo: Some(format!("Kong swap job #{}", job_id)),
+        };
+
+        TransactionBuilder::build_transfer_spl_with_ata_transaction(params)
+            .await
+            .map_err(|e| format!("Failed to build SPL transfer with ATA: {}", e))?
+    };
+    if priority_fee_multiplier > 1 {
+        TransactionBuilder::bump_priority_fee(&mut instructions, priority_fee_multiplier);
+    }
+
+    // blockhash used by sign_transaction
+    let blockhash = with_solana_blockhash(|cell| cell.get().clone());
+
+    // Sign the transaction
+    let signed_tx = sign_transaction(instructions, &kong_address)
+        .await
+        .map_err(|e| format!("Failed to sign transaction: {}", e))?;
+
+    // Extract signature for tracking first (before encoding)
+    let tx_sig = if !signed_tx.signatures.is_empty() {
+        bs58::encode(&signed_tx.signatures[0]).into_string()
+    } else {
+        return Err("No signature in signed transaction".to_string());
+    };
+
+    // Encode the signed transaction using proper Solana transaction format
+    let encoded_tx = signed_tx.encode().map_err(|e| format!("Failed to encode transaction: {}", e))?;
+
+    Ok(SignedTransfer {
+        tx_sig,
+        encoded_tx,
+        blockhash,
+    })
+}
@@ -0,0 +1,26 @@
+use ic_cdk::query;
+use std::ops::Bound::{Excluded, Unbounded};
+
+use crate::ic::guards::caller_is_kong_rpc;
+use crate::solana::swap_job::{SwapJob, SwapJobId};
+use crate::solana::swap_job_retry::is_retryable;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::with_swap_job_queue;
+
+/// Get expired Solana swap jobs waiting for a retry (called by kong_rpc)
+///
+/// kong_rpc checks the signatures of each job with getSignatureStatuses and reports those it did not find with
+/// notify_solana_swap_not_found(), or a landed one with update_solana_swap()
+#[query(hidden = true, guard = "caller_is_kong_rpc")]
+pub fn get_expired_solana_swaps(from_job_id: Option<SwapJobId>) -> Result<Vec<SwapJob>, String> {
+    const MAX_BATCH_SIZE: usize = 100;
+
+    let max_retries = kong_settings_map::get().solana_swap_job_max_retries;
+    with_swap_job_queue(|queue| {
+        Ok(queue
+            .range((from_job_id.map_or(Unbounded, Excluded), Unbounded))
+            .filter_map(|(_, job)| is_retryable(&job, max_retries).then_some(job))
+            .take(MAX_BATCH_SIZE)
+            .collect())
+    })
+}
@@ -1,6 +1,9 @@
+pub mod get_expired_solana_swaps;
 pub mod get_pending_solana_swaps;
 pub mod get_solana_address;
+pub mod notify_solana_swap_not_found;
 pub mod notify_solana_transfer;
 pub mod transaction_notification;
+pub mod transfer_attestation;
 pub mod update_solana_blockhash;
 pub mod update_solana_swap;
@@ -0,0 +1,23 @@
+use ic_cdk::update;
+
+use crate::ic::guards::caller_is_kong_rpc;
+use crate::ic::network::ICNetwork;
+use crate::solana::swap_job::SwapJobId;
+use crate::solana::swap_job_retry::add_not_found_report;
+use crate::stable_memory::with_swap_job_queue_mut;
+
+/// Report that none of the transactions of an expired Solana swap job is on Solana (called by each kong_rpc relayer)
+///
+/// tx_sigs are the signatures kong_rpc checked with getSignatureStatuses (searchTransactionHistory) and did not find.
+/// They must include all transactions of the job. The job is only re-signed once solana_attestation_threshold of the
+/// kong_rpc_relayers in Kong settings have reported it
+#[update(hidden = true, guard = "caller_is_kong_rpc")]
+pub fn notify_solana_swap_not_found(job_id: u64, tx_sigs: Vec<String>) -> Result<(), String> {
+    let relayer = ICNetwork::caller();
+    with_swap_job_queue_mut(|queue| {
+        let mut job = queue.get(&SwapJobId(job_id)).ok_or(format!("Swap job #{} not found", job_id))?;
+        add_not_found_report(&mut job, relayer, &tx_sigs)?;
+        queue.insert(SwapJobId(job_id), job);
+        Ok(())
+    })
+}
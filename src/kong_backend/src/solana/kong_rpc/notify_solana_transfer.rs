@@ -2,18 +2,51 @@ use ic_cdk::update;
 
 use crate::ic::guards::caller_is_kong_rpc;
 use crate::ic::network::ICNetwork;
-use crate::stable_memory::with_solana_tx_notifications_mut;
+use crate::solana::stable_memory::with_solana_tx_attestations_mut;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::{get_solana_transaction, with_solana_tx_notifications_mut};
 
 use super::transaction_notification::{TransactionNotification, TransactionNotificationId, TransactionNotificationStatus};
+use super::transfer_attestation::TransferAttestation;
 
-/// Notify about a Solana transfer (called by kong_rpc)
+/// Attest a Solana transfer to Kong (called by each kong_rpc relayer)
+///
+/// The transfer can only be used by swap(), add_liquidity() or add_pool() once solana_attestation_threshold of the
+/// kong_rpc_relayers in Kong settings have attested the same slot, amount and parsed transaction (metadata)
 #[update(hidden = true, guard = "caller_is_kong_rpc")]
-pub fn notify_solana_transfer(tx_signature: String, metadata: String) -> Result<(), String> {
+pub fn notify_solana_transfer(tx_signature: String, slot: u64, amount: u64, metadata: Option<String>) -> Result<(), String> {
     let key = TransactionNotificationId(tx_signature.clone());
+    let ts = ICNetwork::get_time();
+
+    // already confirmed by enough relayers
+    if get_solana_transaction(&tx_signature).is_some() {
+        return Ok(());
+    }
+
+    let metadata = metadata.ok_or("Transaction metadata is required")?;
+    let attestation = TransferAttestation::new(ICNetwork::caller(), &tx_signature, slot, amount, metadata, ts)?;
+
+    let kong_settings = kong_settings_map::get();
+    let threshold = std::cmp::max(kong_settings.solana_attestation_threshold, 1) as usize;
+    let confirmed = with_solana_tx_attestations_mut(|attestations| {
+        let mut tx_attestations = attestations.get(&key).unwrap_or_default();
+        tx_attestations.attest(attestation.clone());
+        if tx_attestations.count_agreeing(&attestation, &kong_settings.kong_rpc_relayers) >= threshold {
+            attestations.remove(&key);
+            true
+        } else {
+            attestations.insert(key.clone(), tx_attestations);
+            false
+        }
+    });
+    if !confirmed {
+        return Ok(());
+    }
+
     let value = TransactionNotification {
         status: TransactionNotificationStatus::Confirmed, // Incoming payments are always confirmed
-        metadata: Some(metadata),
-        timestamp: ICNetwork::get_time(),
+        metadata: Some(attestation.metadata),
+        timestamp: ts,
         tx_signature: tx_signature.clone(),
         job_id: 0,          // For incoming transfers, there's no associated job_id
         is_completed: true, // Incoming payments are always completed when notified
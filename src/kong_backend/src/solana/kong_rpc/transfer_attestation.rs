@@ -0,0 +1,160 @@
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// a relayer's report of an incoming Solana transfer
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TransferAttestation {
+    pub relayer: Principal, // kong_rpc relayer that reported the transfer
+    pub slot: u64,          // slot of the transaction
+    pub amount: u64,        // amount transferred to Kong
+    pub metadata: String,   // parsed transaction the relayer reported
+    pub timestamp: u64,
+}
+
+impl TransferAttestation {
+    /// make sure the attested signature, slot and amount are those of the parsed transaction
+    pub fn new(relayer: Principal, tx_signature: &str, slot: u64, amount: u64, metadata: String, timestamp: u64) -> Result<Self, String> {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&metadata).map_err(|e| format!("Failed to parse transaction metadata: {}", e))?;
+        if parsed.get("signature").and_then(|v| v.as_str()) != Some(tx_signature) {
+            Err(format!("Transaction metadata signature does not match {}", tx_signature))?
+        }
+        if parsed.get("slot").and_then(|v| v.as_u64()) != Some(slot) {
+            Err(format!("Transaction metadata slot does not match attested slot {}", slot))?
+        }
+        if parsed.get("amount").and_then(|v| v.as_u64()) != Some(amount) {
+            Err(format!("Transaction metadata amount does not match attested amount {}", amount))?
+        }
+        Ok(Self {
+            relayer,
+            slot,
+            amount,
+            metadata,
+            timestamp,
+        })
+    }
+
+    /// same slot, amount and parsed transaction. the order of the metadata fields does not matter
+    pub fn agrees_with(&self, other: &TransferAttestation) -> bool {
+        if self.slot != other.slot || self.amount != other.amount {
+            return false;
+        }
+        match (
+            serde_json::from_str::<serde_json::Value>(&self.metadata),
+            serde_json::from_str::<serde_json::Value>(&other.metadata),
+        ) {
+            (Ok(metadata), Ok(other_metadata)) => metadata == other_metadata,
+            _ => false,
+        }
+    }
+}
+
+/// attestations of a Solana transfer that has not reached the attestation threshold yet, one per relayer
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Default)]
+pub struct TransferAttestations(pub Vec<TransferAttestation>);
+
+impl TransferAttestations {
+    /// add or replace the relayer's attestation
+    pub fn attest(&mut self, attestation: TransferAttestation) {
+        self.0.retain(|a| a.relayer != attestation.relayer);
+        self.0.push(attestation);
+    }
+
+    /// number of current relayers whose attestation agrees with attestation
+    pub fn count_agreeing(&self, attestation: &TransferAttestation, relayers: &[Principal]) -> usize {
+        self.0
+            .iter()
+            .filter(|a| relayers.contains(&a.relayer) && a.agrees_with(attestation))
+            .count()
+    }
+
+    /// timestamp of the latest attestation
+    pub fn timestamp(&self) -> u64 {
+        self.0.iter().map(|a| a.timestamp).max().unwrap_or(0)
+    }
+}
+
+impl Storable for TransferAttestations {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode TransferAttestations").into()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode TransferAttestations")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TX_SIGNATURE: &str = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+
+    fn relayer(id: u8) -> Principal {
+        Principal::from_slice(&[id])
+    }
+
+    fn metadata(slot: u64, amount: u64, sender: &str) -> String {
+        format!(
+            r#"{{"signature":"{}","slot":{},"amount":{},"sender":"{}","blockTime":1700000000}}"#,
+            TX_SIGNATURE, slot, amount, sender
+        )
+    }
+
+    fn attestation(id: u8, slot: u64, amount: u64, sender: &str) -> TransferAttestation {
+        TransferAttestation::new(relayer(id), TX_SIGNATURE, slot, amount, metadata(slot, amount, sender), 0).unwrap()
+    }
+
+    #[test]
+    fn test_new_cross_checks_metadata() {
+        let metadata = metadata(100, 5_000, "sender");
+        assert!(TransferAttestation::new(relayer(1), TX_SIGNATURE, 100, 5_000, metadata.clone(), 0).is_ok());
+        // attested slot or amount differ from the parsed transaction
+        assert!(TransferAttestation::new(relayer(1), TX_SIGNATURE, 101, 5_000, metadata.clone(), 0).is_err());
+        assert!(TransferAttestation::new(relayer(1), TX_SIGNATURE, 100, 5_001, metadata.clone(), 0).is_err());
+        // metadata of another transaction
+        assert!(TransferAttestation::new(relayer(1), "other", 100, 5_000, metadata, 0).is_err());
+        // slot missing from metadata
+        let metadata = format!(r#"{{"signature":"{}","amount":5000}}"#, TX_SIGNATURE);
+        assert!(TransferAttestation::new(relayer(1), TX_SIGNATURE, 100, 5_000, metadata, 0).is_err());
+        // signature missing from metadata
+        let metadata = r#"{"slot":100,"amount":5000}"#.to_string();
+        assert!(TransferAttestation::new(relayer(1), TX_SIGNATURE, 100, 5_000, metadata, 0).is_err());
+    }
+
+    #[test]
+    fn test_agrees_with_ignores_field_order() {
+        let a = attestation(1, 100, 5_000, "sender");
+        let reordered = format!(
+            r#"{{"blockTime":1700000000,"sender":"sender","amount":5000,"slot":100,"signature":"{}"}}"#,
+            TX_SIGNATURE
+        );
+        let b = TransferAttestation::new(relayer(2), TX_SIGNATURE, 100, 5_000, reordered, 0).unwrap();
+        assert!(a.agrees_with(&b));
+        // a relayer reporting another sender does not agree
+        assert!(!a.agrees_with(&attestation(2, 100, 5_000, "attacker")));
+    }
+
+    #[test]
+    fn test_count_agreeing() {
+        let relayers = vec![relayer(1), relayer(2), relayer(3)];
+        let mut attestations = TransferAttestations::default();
+        attestations.attest(attestation(1, 100, 5_000, "sender"));
+        attestations.attest(attestation(2, 100, 5_000, "attacker"));
+        let honest = attestation(3, 100, 5_000, "sender");
+        attestations.attest(honest.clone());
+        assert_eq!(attestations.count_agreeing(&honest, &relayers), 2);
+
+        // attesting again replaces the relayer's attestation
+        attestations.attest(attestation(2, 100, 5_000, "sender"));
+        assert_eq!(attestations.0.len(), 3);
+        assert_eq!(attestations.count_agreeing(&honest, &relayers), 3);
+
+        // relayers removed from Kong settings no longer count
+        assert_eq!(attestations.count_agreeing(&honest, &relayers[..1]), 1);
+    }
+}
@@ -49,10 +49,8 @@ pub fn update_solana_swap(
                         job.solana_tx_signature_of_payout = Some(final_solana_tx_sig);
                         job.error_message = None;
                         job.updated_at = ICNetwork::get_time();
+                        // Confirmed jobs are kept for solana_swap_jobs() until swap_job_cleanup removes them
                         queue.insert(SwapJobId(job_id), job);
-                        
-                        // Remove successfully completed jobs to prevent reprocessing
-                        queue.remove(&SwapJobId(job_id));
                         Ok(())
                     } else {
                         // Transition: Pending -> Failed
@@ -169,11 +167,7 @@ pub fn update_solana_swap(
                     if was_successful {
                         // Already confirmed - idempotent if same signature
                         match &job.solana_tx_signature_of_payout {
-                            Some(existing_sig) if existing_sig == &final_solana_tx_sig => {
-                                // Remove completed jobs to prevent reprocessing
-                                queue.remove(&SwapJobId(job_id));
-                                Ok(())
-                            }
+                            Some(existing_sig) if existing_sig == &final_solana_tx_sig => Ok(()),
                             _ => Err(format!(
                                 "Job {} already confirmed with different signature",
                                 job_id
@@ -195,9 +189,6 @@ pub fn update_solana_swap(
                         job.error_message = None;
                         job.updated_at = ICNetwork::get_time();
                         queue.insert(SwapJobId(job_id), job);
-
-                        // Remove successfully completed jobs to prevent reprocessing
-                        queue.remove(&SwapJobId(job_id));
                         Ok(())
                     } else {
                         // Already failed - update error message if different
@@ -210,12 +201,21 @@ pub fn update_solana_swap(
                     }
                 }
                 SwapJobStatus::Expired => {
-                    // Job expired - requires manual intervention
-                    // kong_rpc should not be calling this for expired jobs
-                    Err(format!(
-                        "Job {} is expired - requires manual investigation",
-                        job_id
-                    ))
+                    if was_successful {
+                        // Late report: the transaction landed before it expired, so the job must not be retried
+                        job.status = SwapJobStatus::Confirmed;
+                        job.solana_tx_signature_of_payout = Some(final_solana_tx_sig);
+                        job.error_message = None;
+                        job.updated_at = ICNetwork::get_time();
+                        queue.insert(SwapJobId(job_id), job);
+                        Ok(())
+                    } else {
+                        // Left Expired for swap_job_retry, which re-signs it with a fresh blockhash
+                        job.error_message = error_msg;
+                        job.updated_at = ICNetwork::get_time();
+                        queue.insert(SwapJobId(job_id), job);
+                        Ok(())
+                    }
                 }
             }
         } else {
@@ -1,7 +1,7 @@
 use candid::Nat;
 use serde::{Deserialize, Serialize};
 
-use crate::helpers::nat_helpers::serialize_amount_as_string;
+use crate::helpers::nat_helpers::{serialize_amount_as_string, serialize_optional_amount_as_string};
 use crate::remove_liquidity::remove_liquidity_args::RemoveLiquidityArgs;
 
 /// A structure representing the canonical message format for signing liquidity removals
@@ -13,6 +13,11 @@ pub struct CanonicalRemoveLiquidityMessage {
     pub remove_lp_token_amount: Nat,
     pub payout_address_0: Option<String>,
     pub payout_address_1: Option<String>,
+    // only signed when set so messages of two-token remove liquidity are unchanged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receive_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_optional_amount_as_string")]
+    pub min_receive_amount: Option<Nat>,
 }
 
 impl CanonicalRemoveLiquidityMessage {
@@ -24,6 +29,8 @@ impl CanonicalRemoveLiquidityMessage {
             remove_lp_token_amount: args.remove_lp_token_amount.clone(),
             payout_address_0: args.payout_address_0.clone(),
             payout_address_1: args.payout_address_1.clone(),
+            receive_token: args.receive_token.clone(),
+            min_receive_amount: args.min_receive_amount.clone(),
         }
     }
 
@@ -6,6 +6,8 @@ pub mod sdk;
 pub mod stable_memory;
 pub mod swap_job;
 pub mod swap_job_cleanup;
+pub mod swap_job_retry;
+pub mod swap_jobs;
 pub mod create_solana_swap_job;
 pub mod transaction;
 pub mod utils;
@@ -3,10 +3,12 @@ use ic_stable_structures::{StableBTreeMap, StableCell};
 use crate::ic::network::ICNetwork;
 use crate::solana::swap_job::SwapJob;
 use crate::stable_memory::{
-    Memory, CACHED_SOLANA_ADDRESS, NEXT_SOLANA_SWAP_JOB_ID, SOLANA_BLOCKHASH, SOLANA_SWAP_JOB_QUEUE, SOLANA_TX_NOTIFICATIONS,
+    Memory, CACHED_SOLANA_ADDRESS, NEXT_SOLANA_SWAP_JOB_ID, SOLANA_BLOCKHASH, SOLANA_SWAP_JOB_QUEUE, SOLANA_TX_ATTESTATIONS,
+    SOLANA_TX_NOTIFICATIONS,
 };
 
 use super::kong_rpc::transaction_notification::{TransactionNotification, TransactionNotificationId};
+use super::kong_rpc::transfer_attestation::TransferAttestations;
 use super::swap_job::SwapJobId;
 
 /// Helper function to access the cached Solana address
@@ -77,6 +79,13 @@ pub fn with_solana_tx_notifications_mut<R>(
     SOLANA_TX_NOTIFICATIONS.with(|cell| f(&mut cell.borrow_mut()))
 }
 
+/// Helper function to mutate the attestations of incoming Solana transfers
+pub fn with_solana_tx_attestations_mut<R>(
+    f: impl FnOnce(&mut StableBTreeMap<TransactionNotificationId, TransferAttestations, Memory>) -> R,
+) -> R {
+    SOLANA_TX_ATTESTATIONS.with(|cell| f(&mut cell.borrow_mut()))
+}
+
 /// Get a transaction by tx_signature
 pub fn get_solana_transaction(tx_signature: String) -> Option<TransactionNotification> {
     with_solana_tx_notifications(|notifications| notifications.get(&TransactionNotificationId(tx_signature)))
@@ -105,5 +114,16 @@ pub fn cleanup_old_notifications() {
         for key in to_remove.iter() {
             notifications.remove(key);
         }
+    });
+
+    // attestations of transfers that never reached the attestation threshold
+    with_solana_tx_attestations_mut(|attestations| {
+        let to_remove: Vec<_> = attestations
+            .iter()
+            .filter_map(|(key, attestations)| if attestations.timestamp() < cutoff_time { Some(key) } else { None })
+            .collect();
+        for key in to_remove.iter() {
+            attestations.remove(key);
+        }
     })
 }
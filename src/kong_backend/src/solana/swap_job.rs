@@ -1,4 +1,4 @@
-use candid::{CandidType, Deserialize};
+use candid::{CandidType, Deserialize, Principal};
 use ic_stable_structures::{storable::Bound, Storable};
 use serde::Serialize;
 use std::borrow::Cow;
@@ -29,6 +29,17 @@ pub enum SwapJobStatus {
     Expired,   // Timed out after 300s without response from kong_rpc (status unknown)
 }
 
+impl std::fmt::Display for SwapJobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SwapJobStatus::Pending => write!(f, "Pending"),
+            SwapJobStatus::Confirmed => write!(f, "Confirmed"),
+            SwapJobStatus::Failed => write!(f, "Failed"),
+            SwapJobStatus::Expired => write!(f, "Expired"),
+        }
+    }
+}
+
 impl Storable for SwapJobStatus {
     fn to_bytes(&self) -> Cow<[u8]> {
         match self {
@@ -67,6 +78,21 @@ pub struct SwapJob {
     pub solana_tx_signature_of_payout: Option<String>, // Final tx signature confirmed by Solana network (after successful submission)
     pub error_message: Option<String>,
     pub tx_sig: String, // Initial tx signature computed locally at signing time (before network submission)
+    // transfer of the job, kept to rebuild the transaction if it expires
+    #[serde(default)]
+    pub token_id: u32,
+    #[serde(default)]
+    pub amount: u64,
+    #[serde(default)]
+    pub to_address: String,
+    #[serde(default)]
+    pub blockhash: String, // recent blockhash the transaction was signed with
+    #[serde(default)]
+    pub retry_count: u8, // number of times the job was re-signed after it expired
+    #[serde(default)]
+    pub previous_tx_sigs: Vec<String>, // tx signatures of the expired transactions
+    #[serde(default)]
+    pub not_found_relayers: Vec<Principal>, // kong_rpc relayers that found none of the job's transactions on Solana since it expired
 }
 
 impl Storable for SwapJob {
@@ -104,6 +130,18 @@ impl SwapJob {
             solana_tx_signature_of_payout,
             error_message,
             tx_sig,
+            token_id: 0,
+            amount: 0,
+            to_address: String::new(),
+            blockhash: String::new(),
+            retry_count: 0,
+            previous_tx_sigs: Vec::new(),
+            not_found_relayers: Vec::new(),
         }
     }
-}
\ No newline at end of file
+
+    /// signatures of all transactions of the job, the expired ones first
+    pub fn tx_sigs(&self) -> impl Iterator<Item = &String> {
+        self.previous_tx_sigs.iter().chain(std::iter::once(&self.tx_sig))
+    }
+}
@@ -5,6 +5,7 @@
 
 use crate::ic::network::ICNetwork;
 use crate::solana::swap_job::SwapJobStatus;
+use crate::stable_kong_settings::kong_settings_map;
 use crate::stable_memory::with_swap_job_queue_mut;
 
 /// Timeout for swap jobs in nanoseconds (300 seconds = 5 minutes)
@@ -14,14 +15,18 @@ const SWAP_JOB_TIMEOUT_NS: u64 = 300_000_000_000;
 /// 
 /// This function:
 /// 1. Finds all swap jobs in Pending status older than SWAP_JOB_TIMEOUT_NS
-/// 2. Marks them as Failed with an appropriate error message
-/// 3. Creates claims for fund recovery
+/// 2. Marks them as Expired with an appropriate error message
+/// 3. Leaves them to swap_job_retry, which re-signs them if their transaction did not land
 /// 
 /// This ensures that if kong_rpc fails to report back (network issues, crashes, etc.),
-/// users can still recover their funds through the claim system.
+/// the transfer is retried without paying twice.
 pub fn cleanup_expired_swap_jobs() {
-    let current_time = ICNetwork::get_time();
+    expire_swap_jobs(ICNetwork::get_time());
+}
+
+fn expire_swap_jobs(current_time: u64) {
     let cutoff_time = current_time.saturating_sub(SWAP_JOB_TIMEOUT_NS);
+    let max_retries = kong_settings_map::get().solana_swap_job_max_retries;
     
     with_swap_job_queue_mut(|queue| {
         let mut expired_count = 0;
@@ -30,18 +35,25 @@ pub fn cleanup_expired_swap_jobs() {
         let jobs_to_update: Vec<_> = queue
             .iter()
             .filter(|(_, job)| {
-                job.status == SwapJobStatus::Pending && job.created_at < cutoff_time
+                // updated_at is reset when an expired job is re-signed and queued again
+                job.status == SwapJobStatus::Pending && job.updated_at < cutoff_time
             })
             .map(|(id, job)| (id, job.clone()))
             .collect();
         
         for (job_id, mut job) in jobs_to_update {
-            // Expired = uncertain status, needs manual investigation
+            // Expired = uncertain status, retried by swap_job_retry until it runs out of retries
             // Only kong_rpc reported failures create claims
+            let next_step = if job.retry_count < max_retries {
+                format!("retry {} of {}", job.retry_count + 1, max_retries)
+            } else {
+                "manual investigation required".to_string()
+            };
             ICNetwork::error_log(&format!(
-                "[CLEANUP] Job #{} expired after {}s - Status UNKNOWN, manual investigation required. User: {}, Request: {}",
+                "[CLEANUP] Job #{} expired after {}s - Status UNKNOWN, {}. User: {}, Request: {}",
                 job.id,
-                (current_time - job.created_at) / 1_000_000_000,
+                (current_time - job.updated_at) / 1_000_000_000,
+                next_step,
                 job.user_id,
                 job.request_id
             ));
@@ -51,8 +63,8 @@ pub fn cleanup_expired_swap_jobs() {
             // Mark job as expired (NOT failed - status is unknown)
             job.status = SwapJobStatus::Expired;
             job.error_message = Some(format!(
-                "Transaction expired after {} seconds without confirmation from kong_rpc - status unknown, requires manual check",
-                (current_time - job.created_at) / 1_000_000_000
+                "Transaction expired after {} seconds without confirmation from kong_rpc - status unknown",
+                (current_time - job.updated_at) / 1_000_000_000
             ));
             job.updated_at = current_time;
             queue.insert(job_id, job);
@@ -60,9 +72,80 @@ pub fn cleanup_expired_swap_jobs() {
         
         if expired_count > 0 {
             ICNetwork::info_log(&format!(
-                "[CLEANUP] Marked {} swap job(s) as expired - manual investigation required",
+                "[CLEANUP] Marked {} swap job(s) as expired",
                 expired_count
             ));
         }
     });
-}
\ No newline at end of file
+}
+
+/// Time confirmed swap jobs are kept for solana_swap_jobs() in nanoseconds (7 days)
+const CONFIRMED_SWAP_JOB_RETENTION_NS: u64 = 7 * 24 * 3_600_000_000_000;
+
+/// Remove confirmed swap jobs older than CONFIRMED_SWAP_JOB_RETENTION_NS
+pub fn cleanup_confirmed_swap_jobs() {
+    let cutoff_time = ICNetwork::get_time().saturating_sub(CONFIRMED_SWAP_JOB_RETENTION_NS);
+
+    with_swap_job_queue_mut(|queue| {
+        let to_remove: Vec<_> = queue
+            .iter()
+            .filter_map(|(job_id, job)| (job.status == SwapJobStatus::Confirmed && job.updated_at < cutoff_time).then_some(job_id))
+            .collect();
+        for job_id in to_remove.iter() {
+            queue.remove(job_id);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{init_kong_settings, update_kong_settings};
+    use crate::solana::swap_job::{SwapJob, SwapJobId};
+    use crate::stable_memory::with_swap_job_queue;
+
+    const NOW: u64 = 1_000 * 1_000_000_000;
+
+    fn insert_job(id: u64, status: SwapJobStatus, updated_at: u64, retry_count: u8) {
+        let job = SwapJob {
+            retry_count,
+            ..SwapJob::new(id, 100, id, status, 0, updated_at, String::new(), None, None, format!("sig_{}", id))
+        };
+        with_swap_job_queue_mut(|queue| queue.insert(SwapJobId(id), job));
+    }
+
+    fn job(id: u64) -> SwapJob {
+        with_swap_job_queue(|queue| queue.get(&SwapJobId(id)).unwrap())
+    }
+
+    #[test]
+    fn test_expire_swap_jobs() {
+        init_kong_settings();
+        update_kong_settings(|s| s.solana_swap_job_max_retries = 3);
+        // pending for more than 300 seconds, one of them on its last retry
+        insert_job(1, SwapJobStatus::Pending, NOW - SWAP_JOB_TIMEOUT_NS - 1, 0);
+        insert_job(2, SwapJobStatus::Pending, NOW - SWAP_JOB_TIMEOUT_NS - 1, 3);
+        // pending for exactly 300 seconds
+        insert_job(3, SwapJobStatus::Pending, NOW - SWAP_JOB_TIMEOUT_NS, 0);
+        // reported by kong_rpc
+        insert_job(4, SwapJobStatus::Confirmed, 0, 0);
+        insert_job(5, SwapJobStatus::Failed, 0, 0);
+
+        expire_swap_jobs(NOW);
+
+        for id in [1, 2] {
+            let job = job(id);
+            assert_eq!(job.status, SwapJobStatus::Expired);
+            assert_eq!(job.updated_at, NOW);
+            assert!(job.error_message.unwrap().contains("status unknown"));
+        }
+        assert_eq!(job(3).status, SwapJobStatus::Pending);
+        assert_eq!(job(4).status, SwapJobStatus::Confirmed);
+        assert_eq!(job(5).status, SwapJobStatus::Failed);
+
+        // an expired job does not expire again
+        expire_swap_jobs(NOW + SWAP_JOB_TIMEOUT_NS + 1);
+        assert_eq!(job(1).updated_at, NOW);
+    }
+}
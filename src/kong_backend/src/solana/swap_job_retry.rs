@@ -0,0 +1,403 @@
+//! Recovery of expired Solana swap jobs
+//!
+//! A swap job expires when kong_rpc has not reported it for 300 seconds. By then the recent blockhash its
+//! transaction was signed with is no longer valid, so the transaction can not land anymore. Once the kong_rpc relayers
+//! confirmed none of its transactions is on Solana, the transfer is re-signed with a fresh blockhash and a higher
+//! priority fee and the job is queued again.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+use candid::Principal;
+
+use crate::ic::network::ICNetwork;
+use crate::solana::create_solana_swap_job::{build_signed_transfer, SignedTransfer};
+use crate::solana::kong_rpc::transaction_notification::TransactionNotificationId;
+use crate::solana::stable_memory::{with_solana_blockhash, with_solana_tx_notifications, with_swap_job_queue, with_swap_job_queue_mut};
+use crate::solana::swap_job::{SwapJob, SwapJobId, SwapJobStatus};
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_token::{stable_token::StableToken, token_map};
+
+/// Time to wait after a job expired before retrying it, for late reports of kong_rpc (120 seconds)
+const SWAP_JOB_RETRY_DELAY_NS: u64 = 120_000_000_000;
+
+thread_local! {
+    // swap jobs being re-signed. kept in heap memory so an upgrade releases them
+    static RETRYING_SWAP_JOBS: RefCell<BTreeSet<u64>> = RefCell::default();
+}
+
+/// lock of a swap job while it is re-signed, so the next timer tick does not retry it a second time
+struct RetryLock {
+    job_id: u64,
+}
+
+impl RetryLock {
+    fn new(job_id: u64) -> Option<Self> {
+        RETRYING_SWAP_JOBS.with(|m| m.borrow_mut().insert(job_id).then_some(RetryLock { job_id }))
+    }
+}
+
+impl Drop for RetryLock {
+    fn drop(&mut self) {
+        RETRYING_SWAP_JOBS.with(|m| m.borrow_mut().remove(&self.job_id));
+    }
+}
+
+/// Retry expired swap jobs, up to solana_swap_job_max_retries times each.
+///
+/// For each expired job:
+/// 1. if one of its transactions landed (notification store), the job is confirmed
+/// 2. otherwise, once solana_attestation_threshold relayers reported none of its transactions on Solana with
+///    notify_solana_swap_not_found() and kong_rpc reported a new blockhash, the transfer is re-signed with a priority
+///    fee doubled for each retry and the job is set back to Pending for kong_rpc
+///
+/// Jobs that used up their retries, or were created before the transfer was saved with the job, stay Expired
+/// and require manual investigation.
+pub async fn retry_expired_swap_jobs() {
+    let max_retries = kong_settings_map::get().solana_swap_job_max_retries;
+    if max_retries == 0 {
+        return;
+    }
+    let cutoff_time = ICNetwork::get_time().saturating_sub(SWAP_JOB_RETRY_DELAY_NS);
+
+    let jobs_to_retry: Vec<SwapJob> = with_swap_job_queue(|queue| {
+        queue
+            .iter()
+            .filter(|(_, job)| is_retryable(job, max_retries) && job.updated_at < cutoff_time)
+            .map(|(_, job)| job)
+            .collect()
+    });
+
+    for job in jobs_to_retry {
+        if let Err(e) = retry_expired_swap_job(job).await {
+            ICNetwork::error_log(&format!("[RETRY] {}", e));
+        }
+    }
+}
+
+async fn retry_expired_swap_job(job: SwapJob) -> Result<(), String> {
+    let Some(_retry_lock) = RetryLock::new(job.id) else {
+        return Ok(());
+    };
+
+    // never pay twice. if any transaction of the job landed, the job is done
+    if let Some(tx_sig) = landed_tx_sig(&job) {
+        with_swap_job_queue_mut(|queue| {
+            let mut confirmed_job = job.clone();
+            confirmed_job.status = SwapJobStatus::Confirmed;
+            confirmed_job.solana_tx_signature_of_payout = Some(tx_sig.clone());
+            confirmed_job.error_message = None;
+            confirmed_job.updated_at = ICNetwork::get_time();
+            queue.insert(SwapJobId(job.id), confirmed_job);
+        });
+        ICNetwork::info_log(&format!(
+            "[RETRY] Expired job #{} confirmed by transaction {}. User: {}, Request: {}",
+            job.id, tx_sig, job.user_id, job.request_id
+        ));
+        return Ok(());
+    }
+
+    // never re-sign before the relayers checked that none of the job's transactions is on Solana
+    let kong_settings = kong_settings_map::get();
+    if !is_not_found(&job, &kong_settings.kong_rpc_relayers, kong_settings.solana_attestation_threshold) {
+        return Ok(());
+    }
+
+    // wait for kong_rpc to report a new blockhash
+    let blockhash = with_solana_blockhash(|cell| cell.get().clone());
+    if blockhash.is_empty() || blockhash == job.blockhash {
+        return Ok(());
+    }
+
+    let sol_token = match token_map::get_by_token_id(job.token_id) {
+        Some(StableToken::Solana(sol_token)) => sol_token,
+        _ => Err(format!("Job #{} expired, Solana token #{} not found", job.id, job.token_id))?,
+    };
+    let signed_transfer = build_signed_transfer(
+        &sol_token,
+        job.amount,
+        &job.to_address,
+        job.id,
+        priority_fee_multiplier(job.retry_count),
+    )
+    .await
+    .map_err(|e| format!("Job #{} expired, failed to re-sign: {}", job.id, e))?;
+
+    // re-queue the job, unless kong_rpc reported it while it was being re-signed
+    let retry_count = requeue_swap_job(&job, signed_transfer, ICNetwork::get_time());
+
+    if let Some(retry_count) = retry_count {
+        ICNetwork::info_log(&format!(
+            "[RETRY] Expired job #{} re-signed and queued again (retry {}). User: {}, Request: {}",
+            job.id, retry_count, job.user_id, job.request_id
+        ));
+    }
+
+    Ok(())
+}
+
+/// expired job with its transfer saved that has retries left
+pub fn is_retryable(job: &SwapJob, max_retries: u8) -> bool {
+    job.status == SwapJobStatus::Expired && job.retry_count < max_retries && !job.to_address.is_empty()
+}
+
+/// add a relayer's report that none of the transactions of an expired job is on Solana
+/// the report must cover all of the job's transactions, checked with getSignatureStatuses after the job expired
+pub fn add_not_found_report(job: &mut SwapJob, relayer: Principal, tx_sigs: &[String]) -> Result<(), String> {
+    if job.status != SwapJobStatus::Expired {
+        Err(format!("Swap job #{} is not expired", job.id))?;
+    }
+    if let Some(tx_sig) = job.tx_sigs().find(|tx_sig| !tx_sigs.contains(tx_sig)) {
+        Err(format!("Transaction {} of swap job #{} was not checked", tx_sig, job.id))?;
+    }
+    if !job.not_found_relayers.contains(&relayer) {
+        job.not_found_relayers.push(relayer);
+    }
+    Ok(())
+}
+
+/// solana_attestation_threshold of the current relayers found none of the job's transactions on Solana
+fn is_not_found(job: &SwapJob, relayers: &[Principal], threshold: u8) -> bool {
+    let threshold = std::cmp::max(threshold, 1) as usize;
+    job.not_found_relayers.iter().filter(|relayer| relayers.contains(relayer)).count() >= threshold
+}
+
+/// priority fee multiplier of a re-signed transaction, doubled for each retry up to 1024
+fn priority_fee_multiplier(retry_count: u8) -> u64 {
+    1_u64 << (u32::from(retry_count) + 1).min(10)
+}
+
+/// queue the re-signed transfer of an expired job again, if it is still expired with the same transaction
+///
+/// # Returns
+///
+/// * the retry count of the job, or None if the job changed while it was being re-signed
+fn requeue_swap_job(job: &SwapJob, signed_transfer: SignedTransfer, ts: u64) -> Option<u8> {
+    with_swap_job_queue_mut(|queue| {
+        let mut current_job = match queue.get(&SwapJobId(job.id)) {
+            Some(current_job) if current_job.status == SwapJobStatus::Expired && current_job.tx_sig == job.tx_sig => current_job,
+            _ => return None,
+        };
+        current_job.previous_tx_sigs.push(current_job.tx_sig.clone());
+        current_job.tx_sig = signed_transfer.tx_sig;
+        current_job.encoded_signed_solana_tx = signed_transfer.encoded_tx;
+        current_job.blockhash = signed_transfer.blockhash;
+        current_job.retry_count += 1;
+        current_job.status = SwapJobStatus::Pending;
+        current_job.error_message = None;
+        // reports were for the previous transactions
+        current_job.not_found_relayers.clear();
+        current_job.updated_at = ts;
+        let retry_count = current_job.retry_count;
+        queue.insert(SwapJobId(job.id), current_job);
+        Some(retry_count)
+    })
+}
+
+/// tx signature of the job's transaction that landed, if any
+fn landed_tx_sig(job: &SwapJob) -> Option<String> {
+    with_solana_tx_notifications(|notifications| {
+        job.tx_sigs()
+            .find(|tx_sig| {
+                notifications
+                    .get(&TransactionNotificationId((*tx_sig).clone()))
+                    .is_some_and(|notification| notification.is_completed)
+            })
+            .cloned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    use crate::helpers::test_helpers::{init_kong_settings, update_kong_settings};
+    use crate::solana::kong_rpc::transaction_notification::{TransactionNotification, TransactionNotificationStatus};
+    use crate::solana::stable_memory::with_solana_tx_notifications_mut;
+
+    fn relayer(id: u8) -> Principal {
+        Principal::from_slice(&[id])
+    }
+
+    fn expired_job(id: u64, retry_count: u8) -> SwapJob {
+        SwapJob {
+            to_address: "destination".to_string(),
+            amount: 1_000_000,
+            blockhash: "blockhash".to_string(),
+            retry_count,
+            previous_tx_sigs: (0..retry_count).map(|retry| format!("sig_{}_{}", id, retry)).collect(),
+            ..SwapJob::new(
+                id,
+                100,
+                id,
+                SwapJobStatus::Expired,
+                0,
+                0,
+                String::new(),
+                None,
+                None,
+                format!("sig_{}", id),
+            )
+        }
+    }
+
+    fn insert_job(job: &SwapJob) {
+        with_swap_job_queue_mut(|queue| queue.insert(SwapJobId(job.id), job.clone()));
+    }
+
+    fn job(id: u64) -> SwapJob {
+        with_swap_job_queue(|queue| queue.get(&SwapJobId(id)).unwrap())
+    }
+
+    fn all_tx_sigs(job: &SwapJob) -> Vec<String> {
+        job.tx_sigs().cloned().collect()
+    }
+
+    fn signed_transfer(tx_sig: &str) -> SignedTransfer {
+        SignedTransfer {
+            tx_sig: tx_sig.to_string(),
+            encoded_tx: "encoded".to_string(),
+            blockhash: "new_blockhash".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_retryable_caps_retries() {
+        assert!(is_retryable(&expired_job(1, 0), 3));
+        assert!(is_retryable(&expired_job(1, 2), 3));
+        assert!(!is_retryable(&expired_job(1, 3), 3));
+        // retries disabled
+        assert!(!is_retryable(&expired_job(1, 0), 0));
+        // created before the transfer was saved with the job
+        let job = SwapJob {
+            to_address: String::new(),
+            ..expired_job(1, 0)
+        };
+        assert!(!is_retryable(&job, 3));
+        let job = SwapJob {
+            status: SwapJobStatus::Pending,
+            ..expired_job(1, 0)
+        };
+        assert!(!is_retryable(&job, 3));
+    }
+
+    #[test]
+    fn test_priority_fee_multiplier_escalates() {
+        assert_eq!(priority_fee_multiplier(0), 2);
+        assert_eq!(priority_fee_multiplier(1), 4);
+        assert_eq!(priority_fee_multiplier(2), 8);
+        assert_eq!(priority_fee_multiplier(9), 1_024);
+        assert_eq!(priority_fee_multiplier(u8::MAX), 1_024);
+    }
+
+    #[test]
+    fn test_add_not_found_report() {
+        let mut job = expired_job(1, 2);
+        let tx_sigs = all_tx_sigs(&job);
+
+        // the report must cover the expired transactions too
+        assert!(add_not_found_report(&mut job, relayer(1), &tx_sigs[1..]).is_err());
+        assert!(job.not_found_relayers.is_empty());
+
+        assert!(add_not_found_report(&mut job, relayer(1), &tx_sigs).is_ok());
+        assert!(add_not_found_report(&mut job, relayer(1), &tx_sigs).is_ok());
+        assert_eq!(job.not_found_relayers, vec![relayer(1)]);
+
+        // only expired jobs can be reported
+        let mut job = SwapJob {
+            status: SwapJobStatus::Pending,
+            ..expired_job(2, 0)
+        };
+        let tx_sigs = all_tx_sigs(&job);
+        assert!(add_not_found_report(&mut job, relayer(1), &tx_sigs).is_err());
+    }
+
+    #[test]
+    fn test_is_not_found_needs_threshold_of_current_relayers() {
+        let relayers = vec![relayer(1), relayer(2), relayer(3)];
+        let mut job = expired_job(1, 0);
+        let tx_sigs = all_tx_sigs(&job);
+        assert!(!is_not_found(&job, &relayers, 2));
+
+        add_not_found_report(&mut job, relayer(1), &tx_sigs).unwrap();
+        assert!(!is_not_found(&job, &relayers, 2));
+        // a relayer removed from Kong settings does not count
+        add_not_found_report(&mut job, relayer(4), &tx_sigs).unwrap();
+        assert!(!is_not_found(&job, &relayers, 2));
+        add_not_found_report(&mut job, relayer(2), &tx_sigs).unwrap();
+        assert!(is_not_found(&job, &relayers, 2));
+    }
+
+    #[test]
+    fn test_requeue_swap_job() {
+        let mut expired = expired_job(1, 1);
+        expired.not_found_relayers = vec![relayer(1), relayer(2)];
+        insert_job(&expired);
+
+        assert_eq!(requeue_swap_job(&expired, signed_transfer("sig_1_new"), 500), Some(2));
+
+        let job = job(1);
+        assert_eq!(job.status, SwapJobStatus::Pending);
+        assert_eq!(job.tx_sig, "sig_1_new");
+        assert_eq!(job.previous_tx_sigs, vec!["sig_1_0".to_string(), "sig_1".to_string()]);
+        assert_eq!(job.blockhash, "new_blockhash");
+        assert_eq!(job.retry_count, 2);
+        assert_eq!(job.updated_at, 500);
+        // the reports were for the previous transactions
+        assert!(job.not_found_relayers.is_empty());
+
+        // the job was re-signed or reported by kong_rpc in the meantime
+        assert_eq!(requeue_swap_job(&expired, signed_transfer("sig_1_other"), 600), None);
+        assert_eq!(self::job(1).tx_sig, "sig_1_new");
+    }
+
+    #[test]
+    fn test_retry_expired_swap_job_waits_for_not_found_reports() {
+        init_kong_settings();
+        update_kong_settings(|s| {
+            s.kong_rpc_relayers = vec![relayer(1), relayer(2)];
+            s.solana_attestation_threshold = 2;
+        });
+        let mut expired = expired_job(1, 0);
+        let tx_sigs = all_tx_sigs(&expired);
+        add_not_found_report(&mut expired, relayer(1), &tx_sigs).unwrap();
+        insert_job(&expired);
+
+        // only one of the two relayers checked the signatures, so the job is not re-signed
+        assert!(block_on(retry_expired_swap_job(expired)).is_ok());
+        let job = job(1);
+        assert_eq!(job.status, SwapJobStatus::Expired);
+        assert_eq!(job.retry_count, 0);
+        assert_eq!(job.tx_sig, "sig_1");
+    }
+
+    #[test]
+    fn test_retry_expired_swap_job_confirms_landed_transaction() {
+        init_kong_settings();
+        let mut expired = expired_job(1, 1);
+        let tx_sigs = all_tx_sigs(&expired);
+        add_not_found_report(&mut expired, relayer(1), &tx_sigs).unwrap();
+        insert_job(&expired);
+        // the first transaction landed after all
+        with_solana_tx_notifications_mut(|notifications| {
+            notifications.insert(
+                TransactionNotificationId("sig_1_0".to_string()),
+                TransactionNotification {
+                    status: TransactionNotificationStatus::Confirmed,
+                    metadata: None,
+                    timestamp: 0,
+                    tx_signature: "sig_1_0".to_string(),
+                    job_id: 1,
+                    is_completed: true,
+                },
+            )
+        });
+
+        assert!(block_on(retry_expired_swap_job(expired)).is_ok());
+        let job = job(1);
+        assert_eq!(job.status, SwapJobStatus::Confirmed);
+        assert_eq!(job.solana_tx_signature_of_payout, Some("sig_1_0".to_string()));
+        assert_eq!(job.retry_count, 1);
+    }
+}
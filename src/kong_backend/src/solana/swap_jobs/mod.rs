@@ -0,0 +1,2 @@
+pub mod solana_swap_job_reply;
+pub mod solana_swap_jobs;
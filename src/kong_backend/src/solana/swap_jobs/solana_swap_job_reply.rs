@@ -0,0 +1,102 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::solana::stable_memory::with_swap_job_queue;
+use crate::solana::swap_job::SwapJob;
+
+const MAX_SWAP_JOBS: usize = 100;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct SolanaSwapJobReply {
+    pub job_id: u64,
+    pub request_id: u64,
+    pub status: String,
+    pub tx_signature: String,                          // signature of the signed transaction, set before submission
+    pub solana_tx_signature_of_payout: Option<String>, // signature reported by kong_rpc once the transaction landed
+    pub error_message: Option<String>,
+    pub retry_count: u8,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl From<&SwapJob> for SolanaSwapJobReply {
+    fn from(job: &SwapJob) -> Self {
+        SolanaSwapJobReply {
+            job_id: job.id,
+            request_id: job.request_id,
+            status: job.status.to_string(),
+            tx_signature: job.tx_sig.clone(),
+            solana_tx_signature_of_payout: job.solana_tx_signature_of_payout.clone(),
+            error_message: job.error_message.clone(),
+            retry_count: job.retry_count,
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+        }
+    }
+}
+
+/// Solana swap jobs of a request, the payouts of the request on Solana
+pub fn get_by_request_id(request_id: u64) -> Vec<SolanaSwapJobReply> {
+    with_swap_job_queue(|queue| {
+        queue
+            .iter()
+            .filter(|(_, job)| job.request_id == request_id)
+            .map(|(_, job)| SolanaSwapJobReply::from(&job))
+            .collect()
+    })
+}
+
+/// Most recent Solana swap jobs of a user, newest first
+pub fn get_by_user_id(user_id: u32) -> Vec<SolanaSwapJobReply> {
+    with_swap_job_queue(|queue| {
+        queue
+            .iter()
+            .rev()
+            .filter(|(_, job)| job.user_id == user_id)
+            .take(MAX_SWAP_JOBS)
+            .map(|(_, job)| SolanaSwapJobReply::from(&job))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::solana::stable_memory::with_swap_job_queue_mut;
+    use crate::solana::swap_job::{SwapJobId, SwapJobStatus};
+
+    fn insert_job(id: u64, user_id: u32) {
+        let job = SwapJob::new(
+            id,
+            user_id,
+            id,
+            SwapJobStatus::Confirmed,
+            0,
+            0,
+            String::new(),
+            None,
+            None,
+            format!("sig_{}", id),
+        );
+        with_swap_job_queue_mut(|queue| queue.insert(SwapJobId(id), job));
+    }
+
+    #[test]
+    fn test_get_by_user_id_caps_page_size() {
+        for id in 1..=(MAX_SWAP_JOBS as u64 + 10) {
+            insert_job(id, 100);
+        }
+        insert_job(MAX_SWAP_JOBS as u64 + 11, 101);
+
+        let jobs = get_by_user_id(100);
+        assert_eq!(jobs.len(), MAX_SWAP_JOBS);
+        // newest first, the oldest jobs are left out
+        assert_eq!(jobs.first().unwrap().job_id, MAX_SWAP_JOBS as u64 + 10);
+        assert_eq!(jobs.last().unwrap().job_id, 11);
+
+        let jobs = get_by_user_id(101);
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].job_id, MAX_SWAP_JOBS as u64 + 11);
+    }
+}
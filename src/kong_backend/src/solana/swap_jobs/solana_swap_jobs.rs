@@ -0,0 +1,25 @@
+use ic_cdk::query;
+
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::stable_user::user_map;
+
+use super::solana_swap_job_reply::{get_by_request_id, get_by_user_id, SolanaSwapJobReply};
+
+/// Return the Solana swap jobs of a request or of a user
+///
+/// Confirmed jobs are kept for 7 days, a user gets the 100 most recent ones
+#[query(guard = "not_in_maintenance_mode")]
+fn solana_swap_jobs(request_id: Option<u64>, principal_id: Option<String>) -> Result<Vec<SolanaSwapJobReply>, String> {
+    match (request_id, principal_id) {
+        (Some(request_id), None) => Ok(get_by_request_id(request_id)),
+        (None, Some(principal_id)) => {
+            let user_id = user_map::get_by_principal_id(&principal_id)
+                .ok()
+                .flatten()
+                .ok_or("User not found")?
+                .user_id;
+            Ok(get_by_user_id(user_id))
+        }
+        _ => Err("Either request_id or principal_id is required".to_string()),
+    }
+}
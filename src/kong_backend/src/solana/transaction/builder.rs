@@ -8,7 +8,8 @@ use sha2::{Digest, Sha256};
 
 use crate::solana::error::SolanaError;
 use crate::solana::network::{
-    ASSOCIATED_TOKEN_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM_ID, MEMO_PROGRAM_ID, SYSTEM_PROGRAM_ID, SYSVAR_RENT_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    ASSOCIATED_TOKEN_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM_ID, MEMO_PROGRAM_ID, SYSTEM_PROGRAM_ID, SYSVAR_RENT_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
 };
 use crate::solana::sdk::account_meta::AccountMeta;
 use crate::solana::sdk::instruction::Instruction;
@@ -29,6 +30,27 @@ const PRIORITY_FEE_SOL: u64 = 1_600_000; // 1.6M micro/CU * 50k CU = 80,000 lamp
 const PRIORITY_FEE_SPL: u64 = 800_000;    // 800k micro/CU * 100k CU = 80,000 lamports
 const PRIORITY_FEE_SPL_WITH_ATA: u64 = 533_000; // 533k micro/CU * 150k CU = ~80,000 lamports
 
+/// Parameters for building a SPL token transfer transaction
+#[derive(Debug, Clone)]
+pub struct SplTransferParams<'a> {
+    /// The token owner's wallet address
+    pub owner_address: &'a str,
+    /// The sender's token account address
+    pub from_token_account: &'a str,
+    /// The recipient's token account address
+    pub to_token_account: &'a str,
+    /// The token mint address
+    pub mint_address: &'a str,
+    /// The decimals of the mint, checked by transfer_checked
+    pub decimals: u8,
+    /// The token program of the mint (SPL Token or Token-2022)
+    pub token_program_id: &'a str,
+    /// The amount of tokens to transfer
+    pub amount: u64,
+    /// Optional memo to include in the transaction
+    pub memo: Option<String>,
+}
+
 /// Parameters for building a SPL token transfer transaction with ATA creation
 #[derive(Debug, Clone)]
 pub struct SplTransferWithAtaParams<'a> {
@@ -42,6 +64,10 @@ pub struct SplTransferWithAtaParams<'a> {
     pub to_token_account: &'a str,
     /// The token mint address
     pub mint_address: &'a str,
+    /// The decimals of the mint, checked by transfer_checked
+    pub decimals: u8,
+    /// The token program of the mint (SPL Token or Token-2022)
+    pub token_program_id: &'a str,
     /// The fee payer's wallet address
     pub fee_payer: &'a str,
     /// The amount of tokens to transfer
@@ -121,49 +147,60 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    /// * `owner_address` - The token owner's wallet address
-    /// * `from_token_account` - The sender's token account address
-    /// * `to_token_account` - The recipient's token account address
-    /// * `amount` - The amount of tokens to transfer
-    /// * `memo` - Optional memo to include in the transaction
+    /// * `params` - The token accounts, mint, token program, amount and memo of the transfer
     ///
     /// # Returns
     ///
     /// Transaction instructions ready for signing
-    pub async fn build_transfer_spl_transaction(
-        owner_address: &str,
-        from_token_account: &str,
-        to_token_account: &str,
-        amount: u64,
-        memo: Option<String>,
-    ) -> Result<Vec<Instruction>> {
+    pub async fn build_transfer_spl_transaction(params: SplTransferParams<'_>) -> Result<Vec<Instruction>> {
         // Validate addresses
-        validation::validate_addresses(&[owner_address, from_token_account, to_token_account])?;
+        validation::validate_addresses(&[
+            params.owner_address,
+            params.from_token_account,
+            params.to_token_account,
+            params.mint_address,
+        ])?;
 
         // Start with compute budget instructions
         let mut instructions = Self::create_compute_budget_instructions(COMPUTE_UNITS_SPL_TRANSFER, PRIORITY_FEE_SPL)?;
 
         // Add the transfer instruction
-        let token_transfer_instruction =
-            Self::create_transfer_spl_instruction(owner_address, from_token_account, to_token_account, amount)?;
+        let token_transfer_instruction = Self::create_transfer_checked_instruction(
+            params.owner_address,
+            params.from_token_account,
+            params.mint_address,
+            params.to_token_account,
+            params.amount,
+            params.decimals,
+            params.token_program_id,
+        )?;
         instructions.push(token_transfer_instruction);
 
         // Add memo instruction if provided
-        if let Some(memo_text) = memo {
-            let memo_instrument = Self::create_memo_instruction(owner_address, &memo_text)?;
+        if let Some(memo_text) = params.memo {
+            let memo_instrument = Self::create_memo_instruction(params.owner_address, &memo_text)?;
             instructions.push(memo_instrument);
         }
 
         Ok(instructions)
     }
 
-    /// Create a token transfer instruction
-    fn create_transfer_spl_instruction(
+    /// Create a token transfer_checked instruction
+    ///
+    /// transfer_checked is required by Token-2022 mints (it checks the mint and decimals) and works for SPL Token
+    fn create_transfer_checked_instruction(
         owner_address: &str,
         from_token_account: &str,
+        mint_address: &str,
         to_token_account: &str,
         amount: u64,
+        decimals: u8,
+        token_program_id: &str,
     ) -> Result<Instruction> {
+        if token_program_id != TOKEN_PROGRAM_ID && token_program_id != TOKEN_2022_PROGRAM_ID {
+            return Err(SolanaError::TransactionBuildError(format!("Unsupported token program {}", token_program_id)).into());
+        }
+
         // Create account metadata
         let accounts = vec![
             // Source token account
@@ -172,6 +209,12 @@ impl TransactionBuilder {
                 is_signer: false,
                 is_writable: true,
             },
+            // Mint of the token
+            AccountMeta {
+                pubkey: mint_address.to_string(),
+                is_signer: false,
+                is_writable: false,
+            },
             // Destination token account
             AccountMeta {
                 pubkey: to_token_account.to_string(),
@@ -186,14 +229,15 @@ impl TransactionBuilder {
             },
         ];
 
-        // Create instruction data for token transfer
-        let mut data = Vec::with_capacity(9);
-        data.push(3); // Transfer instruction = 3
+        // Create instruction data for token transfer_checked
+        let mut data = Vec::with_capacity(10);
+        data.push(12); // TransferChecked instruction = 12
         data.extend_from_slice(&amount.to_le_bytes());
+        data.push(decimals);
 
-        // Return the instruction with the Token Program ID
+        // Return the instruction with the token program of the mint
         Ok(Instruction {
-            program_id: TOKEN_PROGRAM_ID.to_string(),
+            program_id: token_program_id.to_string(),
             accounts,
             data,
         })
@@ -252,8 +296,20 @@ impl TransactionBuilder {
         ])
     }
 
-    /// Derive the associated token account address for a wallet and mint
-    pub fn derive_associated_token_account(wallet_address: &str, mint_address: &str) -> Result<String> {
+    /// Multiply the priority fee of the SetComputeUnitPrice instruction, to land a re-signed transaction faster
+    pub fn bump_priority_fee(instructions: &mut [Instruction], multiplier: u64) {
+        for instruction in instructions.iter_mut() {
+            if instruction.program_id == COMPUTE_BUDGET_PROGRAM_ID && instruction.data.len() == 9 && instruction.data[0] == 0x03 {
+                let mut price_bytes = [0u8; 8];
+                price_bytes.copy_from_slice(&instruction.data[1..9]);
+                let price = u64::from_le_bytes(price_bytes).saturating_mul(multiplier);
+                instruction.data[1..9].copy_from_slice(&price.to_le_bytes());
+            }
+        }
+    }
+
+    /// Derive the associated token account address for a wallet and mint of the token program
+    pub fn derive_associated_token_account(wallet_address: &str, mint_address: &str, token_program_id: &str) -> Result<String> {
         if wallet_address.is_empty() || mint_address.is_empty() {
             return Err(SolanaError::InvalidPublicKeyFormat("Wallet address or mint address is empty".to_string()).into());
         }
@@ -261,7 +317,7 @@ impl TransactionBuilder {
         // Decode wallet and mint to 32-byte arrays
         let wallet_address_bytes = base58::decode_public_key(wallet_address)?;
         let mint_address_bytes = base58::decode_public_key(mint_address)?;
-        let token_program_bytes = base58::decode_public_key(TOKEN_PROGRAM_ID)?;
+        let token_program_bytes = base58::decode_public_key(token_program_id)?;
         let ata_program_bytes = base58::decode_public_key(ASSOCIATED_TOKEN_PROGRAM_ID)?;
 
         // Seeds: [wallet, token_program, mint]. Exact order specified in the Solana docs
@@ -328,9 +384,14 @@ impl TransactionBuilder {
     }
 
     /// Create instruction to create an associated token account (idempotent version)
-    pub fn create_associated_token_account_instruction(fee_payer: &str, wallet_address: &str, mint_address: &str) -> Result<Instruction> {
+    pub fn create_associated_token_account_instruction(
+        fee_payer: &str,
+        wallet_address: &str,
+        mint_address: &str,
+        token_program_id: &str,
+    ) -> Result<Instruction> {
         // Derive the associated token account address
-        let associated_token_account = Self::derive_associated_token_account(wallet_address, mint_address)?;
+        let associated_token_account = Self::derive_associated_token_account(wallet_address, mint_address, token_program_id)?;
 
         // Create the accounts for the instruction
         let accounts = vec![
@@ -364,9 +425,9 @@ impl TransactionBuilder {
                 is_signer: false,
                 is_writable: false,
             },
-            // Token program
+            // Token program of the mint
             AccountMeta {
-                pubkey: TOKEN_PROGRAM_ID.to_string(),
+                pubkey: token_program_id.to_string(),
                 is_signer: false,
                 is_writable: false,
             },
@@ -397,6 +458,7 @@ impl TransactionBuilder {
             || params.to_token_account.is_empty()
             || params.mint_address.is_empty()
             || params.fee_payer.is_empty()
+            || params.token_program_id.is_empty()
         {
             return Err(SolanaError::InvalidPublicKeyFormat("Invalid address for SPL transfer with ATA".to_string()).into());
         }
@@ -405,16 +467,23 @@ impl TransactionBuilder {
         let mut instructions = Self::create_compute_budget_instructions(COMPUTE_UNITS_SPL_WITH_ATA, PRIORITY_FEE_SPL_WITH_ATA)?;
 
         // 1. Create ATA instruction (idempotent - no error if ATA exists)
-        let create_ata_instruction =
-            Self::create_associated_token_account_instruction(params.fee_payer, params.to_wallet_address, params.mint_address)?;
+        let create_ata_instruction = Self::create_associated_token_account_instruction(
+            params.fee_payer,
+            params.to_wallet_address,
+            params.mint_address,
+            params.token_program_id,
+        )?;
         instructions.push(create_ata_instruction);
 
-        // 2. Create transfer instruction
-        let transfer_instruction = Self::create_transfer_spl_instruction(
+        // 2. Create transfer_checked instruction
+        let transfer_instruction = Self::create_transfer_checked_instruction(
             params.from_address,
             params.from_token_account,
+            params.mint_address,
             params.to_token_account,
             params.amount,
+            params.decimals,
+            params.token_program_id,
         )?;
         instructions.push(transfer_instruction);
 
@@ -427,3 +496,155 @@ impl TransactionBuilder {
         Ok(instructions)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    const WALLET: &str = "B8UwBUUnKwCyKuGMbFKWaG7exYdDk2ozZrPg72NyVbfj";
+    const MINT: &str = "7o36UsWR1JQLpZ9PE2gn9L4SQ69CNNiWAXd4Jt7rqz9Z";
+    // ATA of WALLET and MINT from the @solana/spl-token getAssociatedTokenAddressSync tests
+    const SPL_TOKEN_ATA: &str = "DShWnroshVbeUp28oopA3Pu7oFPDBtC1DBmPECXXAQ9n";
+    // ATA of WALLET and MINT under the Token-2022 program
+    const TOKEN_2022_ATA: &str = "6WD1d4QUPGyZ9pnwNqN1W6fsBd9zoJwZkDg9s7bYxVmq";
+    // PYUSD, a Token-2022 mint
+    const PYUSD_MINT: &str = "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo";
+    const PYUSD_ATA: &str = "C7qWCPDsN25sWj7sUc7Sb2omC32BpEU45ay3aGvnXr1q";
+
+    fn account(pubkey: &str, is_signer: bool, is_writable: bool) -> AccountMeta {
+        AccountMeta {
+            pubkey: pubkey.to_string(),
+            is_signer,
+            is_writable,
+        }
+    }
+
+    #[test]
+    fn test_program_ids_are_public_keys() {
+        for program_id in [
+            SYSTEM_PROGRAM_ID,
+            MEMO_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            TOKEN_2022_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            SYSVAR_RENT_PROGRAM_ID,
+            COMPUTE_BUDGET_PROGRAM_ID,
+        ] {
+            assert!(base58::decode_public_key(program_id).is_ok(), "{}", program_id);
+        }
+    }
+
+    #[test]
+    fn test_derive_associated_token_account() {
+        assert_eq!(
+            TransactionBuilder::derive_associated_token_account(WALLET, MINT, TOKEN_PROGRAM_ID).unwrap(),
+            SPL_TOKEN_ATA
+        );
+        // the token program is a seed, so the same wallet and mint have another ATA under Token-2022
+        assert_eq!(
+            TransactionBuilder::derive_associated_token_account(WALLET, MINT, TOKEN_2022_PROGRAM_ID).unwrap(),
+            TOKEN_2022_ATA
+        );
+        assert_eq!(
+            TransactionBuilder::derive_associated_token_account(WALLET, PYUSD_MINT, TOKEN_2022_PROGRAM_ID).unwrap(),
+            PYUSD_ATA
+        );
+        assert!(TransactionBuilder::derive_associated_token_account("", MINT, TOKEN_PROGRAM_ID).is_err());
+    }
+
+    #[test]
+    fn test_create_transfer_checked_instruction() {
+        for token_program_id in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+            let instruction = TransactionBuilder::create_transfer_checked_instruction(
+                WALLET,
+                SPL_TOKEN_ATA,
+                MINT,
+                TOKEN_2022_ATA,
+                1_234_567_890_123,
+                6,
+                token_program_id,
+            )
+            .unwrap();
+            assert_eq!(instruction.program_id, token_program_id);
+            assert_eq!(
+                instruction.accounts,
+                vec![
+                    account(SPL_TOKEN_ATA, false, true),
+                    account(MINT, false, false),
+                    account(TOKEN_2022_ATA, false, true),
+                    account(WALLET, true, false),
+                ]
+            );
+            // TransferChecked (12), amount as u64 little-endian, decimals
+            assert_eq!(instruction.data, vec![12, 0xcb, 0x04, 0xfb, 0x71, 0x1f, 0x01, 0x00, 0x00, 6]);
+        }
+        assert!(TransactionBuilder::create_transfer_checked_instruction(
+            WALLET,
+            SPL_TOKEN_ATA,
+            MINT,
+            TOKEN_2022_ATA,
+            1,
+            6,
+            SYSTEM_PROGRAM_ID
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_build_transfer_spl_with_ata_transaction_token_2022() {
+        let instructions = block_on(TransactionBuilder::build_transfer_spl_with_ata_transaction(
+            SplTransferWithAtaParams {
+                from_address: WALLET,
+                from_token_account: TOKEN_2022_ATA,
+                to_wallet_address: WALLET,
+                to_token_account: PYUSD_ATA,
+                mint_address: PYUSD_MINT,
+                decimals: 6,
+                token_program_id: TOKEN_2022_PROGRAM_ID,
+                fee_payer: WALLET,
+                amount: 1_000_000,
+                memo: Some("Kong swap job #1".to_string()),
+            },
+        ))
+        .unwrap();
+
+        assert_eq!(instructions.len(), 5);
+        // SetComputeUnitLimit 150_000 and SetComputeUnitPrice 533_000
+        assert_eq!(instructions[0].program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert_eq!(instructions[0].data, vec![2, 0xf0, 0x49, 0x02, 0x00]);
+        assert_eq!(instructions[1].data, vec![3, 0x08, 0x22, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        // CreateIdempotent of the recipient's ATA with the Token-2022 program
+        assert_eq!(instructions[2].program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+        assert_eq!(instructions[2].data, vec![1]);
+        assert_eq!(
+            instructions[2].accounts,
+            vec![
+                account(WALLET, true, true),
+                account(PYUSD_ATA, false, true),
+                account(WALLET, false, false),
+                account(PYUSD_MINT, false, false),
+                account(SYSTEM_PROGRAM_ID, false, false),
+                account(TOKEN_2022_PROGRAM_ID, false, false),
+                account(SYSVAR_RENT_PROGRAM_ID, false, false),
+            ]
+        );
+        // TransferChecked of 1_000_000 with 6 decimals
+        assert_eq!(instructions[3].program_id, TOKEN_2022_PROGRAM_ID);
+        assert_eq!(instructions[3].data, vec![12, 0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 6]);
+        assert_eq!(instructions[4].program_id, MEMO_PROGRAM_ID);
+        assert_eq!(instructions[4].data, b"Kong swap job #1".to_vec());
+    }
+
+    #[test]
+    fn test_bump_priority_fee() {
+        let mut instructions =
+            TransactionBuilder::create_compute_budget_instructions(COMPUTE_UNITS_SPL_TRANSFER, PRIORITY_FEE_SPL).unwrap();
+
+        TransactionBuilder::bump_priority_fee(&mut instructions, 4);
+
+        // the compute unit limit is unchanged, the price is 800_000 * 4 = 3_200_000
+        assert_eq!(instructions[0].data, vec![2, 0xa0, 0x86, 0x01, 0x00]);
+        assert_eq!(instructions[1].data, [vec![3], 3_200_000_u64.to_le_bytes().to_vec()].concat());
+    }
+}
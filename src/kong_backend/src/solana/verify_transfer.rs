@@ -12,7 +12,7 @@ use crate::solana::kong_rpc::transaction_notification::{TransactionNotification,
 use crate::solana::stable_memory::get_cached_solana_address;
 use crate::stable_token::stable_token::StableToken;
 use super::error::SolanaError;
-use super::network::{SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID as SPL_TOKEN_PROGRAM_ID};
+use super::network::{SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID as SPL_TOKEN_PROGRAM_ID};
 use super::sdk::offchain_message::OffchainMessage;
 use super::sdk::pubkey::Pubkey;
 use super::sdk::signature::Signature as SolanaSignature;
@@ -83,13 +83,13 @@ pub async fn verify_transfer(
     validate_receiver_address(&metadata, expected_token)?;
     
     // 2. Validate program ID matches expected token type
-    validate_program_id(&metadata, is_spl_token)?;
+    validate_program_id(&metadata, expected_token, is_spl_token)?;
     
     // 3. Validate mint address for SPL tokens
     validate_mint_address(&metadata, expected_token)?;
     
     // Verify the actual Solana transaction on-chain using the parsed metadata
-    verify_solana_transaction_with_metadata(&transaction, &metadata, &sender_pubkey, amount, is_spl_token)?;
+    verify_solana_transaction_with_metadata(&transaction, &metadata, &sender_pubkey, amount, expected_token, is_spl_token)?;
     
     Ok(SolanaVerificationResult {
         tx_signature: tx_id.to_string(),
@@ -125,6 +125,7 @@ fn verify_solana_transaction_with_metadata(
     metadata: &serde_json::Value,
     expected_sender: &str,
     expected_amount: &Nat,
+    expected_token: &StableToken,
     is_spl_token: bool,
 ) -> Result<(), String> {
     // Check transaction status
@@ -182,11 +183,8 @@ fn verify_solana_transaction_with_metadata(
         ));
     }
 
-    // Check amount matches
-    let actual_amount = metadata
-        .get("amount")
-        .and_then(|v| v.as_u64())
-        .ok_or("Transaction metadata missing amount")?;
+    // Check amount received by Kong matches
+    let actual_amount = received_amount(metadata, expected_token)?;
 
     // API boundary: Solana returns u64 amounts, so we must convert for comparison
     let expected_amount_u64 = expected_amount
@@ -203,6 +201,29 @@ fn verify_solana_transaction_with_metadata(
     Ok(())
 }
 
+/// Amount received by Kong
+///
+/// Token-2022 mints with the transfer fee extension withhold fee_amount from the amount sent, so only
+/// amount - fee_amount is credited
+fn received_amount(metadata: &serde_json::Value, expected_token: &StableToken) -> Result<u64, String> {
+    let amount = metadata
+        .get("amount")
+        .and_then(|v| v.as_u64())
+        .ok_or("Transaction metadata missing amount")?;
+
+    let fee_amount = match metadata.get("fee_amount").and_then(|v| v.as_u64()) {
+        Some(fee_amount) => fee_amount,
+        None if matches!(expected_token, StableToken::Solana(sol_token) if sol_token.has_transfer_fee) => {
+            Err("Transaction metadata missing fee_amount for token with transfer fee")?
+        }
+        None => 0,
+    };
+
+    amount
+        .checked_sub(fee_amount)
+        .ok_or_else(|| format!("Transaction fee_amount {} exceeds amount {}", fee_amount, amount))
+}
+
 fn verify_raw_message(message: &str, pubkey: &Pubkey, signature: &SolanaSignature) -> Result<()> {
     let verify_key = ed25519_dalek::VerifyingKey::from_bytes(&pubkey.to_bytes())?;
     let ed25519_signature = signature.as_ref().try_into()?;
@@ -269,13 +290,14 @@ fn validate_receiver_address(metadata: &serde_json::Value, expected_token: &Stab
     let program_id = metadata.get("program_id").and_then(|v| v.as_str());
     
     // For SPL tokens, receiver is an ATA. We need to validate it's the correct ATA for Kong
-    if program_id == Some(SPL_TOKEN_PROGRAM_ID) {
+    if program_id == Some(SPL_TOKEN_PROGRAM_ID) || program_id == Some(TOKEN_2022_PROGRAM_ID) {
         if let StableToken::Solana(sol_token) = expected_token {
             if sol_token.is_spl_token {
-                // Derive what the ATA should be for Kong's address and this mint
+                // Derive what the ATA should be for Kong's address, this mint and its token program
                 let expected_ata = crate::solana::transaction::builder::TransactionBuilder::derive_associated_token_account(
                     &kong_address,
-                    &sol_token.mint_address
+                    &sol_token.mint_address,
+                    sol_token.token_program_id(),
                 ).map_err(|e| format!("Failed to derive expected ATA: {}", e))?;
                 
                 if receiver != expected_ata {
@@ -299,15 +321,16 @@ fn validate_receiver_address(metadata: &serde_json::Value, expected_token: &Stab
 }
 
 /// Validate that the program ID matches the expected token type
-fn validate_program_id(metadata: &serde_json::Value, expected_is_spl: bool) -> Result<(), String> {
+/// SPL tokens must be transferred with the token program of their mint (SPL Token or Token-2022)
+fn validate_program_id(metadata: &serde_json::Value, expected_token: &StableToken, expected_is_spl: bool) -> Result<(), String> {
     let program_id = metadata.get("program_id")
         .and_then(|v| v.as_str())
         .ok_or("Missing program_id in transaction metadata")?;
     
-    let expected_program = if expected_is_spl {
-        SPL_TOKEN_PROGRAM_ID
-    } else {
-        SYSTEM_PROGRAM_ID
+    let expected_program = match expected_token {
+        StableToken::Solana(sol_token) if expected_is_spl => sol_token.token_program_id(),
+        _ if expected_is_spl => SPL_TOKEN_PROGRAM_ID,
+        _ => SYSTEM_PROGRAM_ID,
     };
     
     if program_id != expected_program {
@@ -342,3 +365,43 @@ fn validate_mint_address(metadata: &serde_json::Value, expected_token: &StableTo
     Ok(())
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::helpers::test_helpers::sol_token;
+    use crate::stable_token::solana_token::SolanaToken;
+
+    fn pyusd_token(has_transfer_fee: bool) -> StableToken {
+        StableToken::Solana(SolanaToken {
+            token_id: 2,
+            name: "PayPal USD".to_string(),
+            symbol: "PYUSD".to_string(),
+            decimals: 6,
+            fee: Nat::from(0_u64),
+            mint_address: "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo".to_string(),
+            program_id: TOKEN_2022_PROGRAM_ID.to_string(),
+            is_spl_token: true,
+            has_transfer_fee,
+        })
+    }
+
+    #[test]
+    fn test_received_amount_takes_out_transfer_fee() {
+        // 1_000_000 sent, 2_500 withheld by the mint
+        let metadata = json!({ "amount": 1_000_000_u64, "fee_amount": 2_500_u64 });
+        assert_eq!(received_amount(&metadata, &pyusd_token(true)), Ok(997_500));
+
+        // the fee must be reported for a mint with a transfer fee
+        let metadata = json!({ "amount": 1_000_000_u64 });
+        assert!(received_amount(&metadata, &pyusd_token(true)).is_err());
+        assert_eq!(received_amount(&metadata, &pyusd_token(false)), Ok(1_000_000));
+        assert_eq!(received_amount(&metadata, &sol_token(1)), Ok(1_000_000));
+
+        let metadata = json!({ "amount": 1_000_u64, "fee_amount": 1_001_u64 });
+        assert!(received_amount(&metadata, &pyusd_token(true)).is_err());
+        assert!(received_amount(&json!({}), &pyusd_token(true)).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::CAMPAIGN_MAP;
+
+use super::stable_campaign::{StableCampaign, StableCampaignId};
+
+pub fn get_by_campaign_id(campaign_id: u32) -> Option<StableCampaign> {
+    CAMPAIGN_MAP.with(|m| m.borrow().get(&StableCampaignId(campaign_id)))
+}
+
+/// get all campaigns, latest first
+pub fn get() -> Vec<StableCampaign> {
+    CAMPAIGN_MAP.with(|m| m.borrow().iter().rev().map(|(_, v)| v).collect())
+}
+
+/// get all campaigns of a pool
+pub fn get_by_pool_id(pool_id: u32) -> Vec<StableCampaign> {
+    CAMPAIGN_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .filter_map(|(_, v)| if v.pool_id == pool_id { Some(v) } else { None })
+            .collect()
+    })
+}
+
+pub fn insert(campaign: &StableCampaign) -> u32 {
+    CAMPAIGN_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        let campaign_id = kong_settings_map::inc_campaign_map_idx();
+        let insert_campaign = StableCampaign {
+            campaign_id,
+            ..campaign.clone()
+        };
+        map.insert(StableCampaignId(campaign_id), insert_campaign);
+        campaign_id
+    })
+}
+
+pub fn update(campaign: &StableCampaign) {
+    CAMPAIGN_MAP.with(|m| {
+        m.borrow_mut().insert(StableCampaignId(campaign.campaign_id), campaign.clone());
+    });
+}
@@ -0,0 +1,24 @@
+use crate::stable_memory::CAMPAIGN_REWARD_MAP;
+
+use super::stable_campaign_reward::{StableCampaignReward, StableCampaignRewardId};
+
+pub fn get(user_id: u32, campaign_id: u32) -> Option<StableCampaignReward> {
+    CAMPAIGN_REWARD_MAP.with(|m| m.borrow().get(&StableCampaignRewardId(user_id, campaign_id)))
+}
+
+/// return the rewards of a user in all campaigns
+pub fn get_by_user_id(user_id: u32) -> Vec<StableCampaignReward> {
+    CAMPAIGN_REWARD_MAP.with(|m| {
+        m.borrow()
+            .range(StableCampaignRewardId(user_id, 0)..=StableCampaignRewardId(user_id, u32::MAX))
+            .map(|(_, v)| v)
+            .collect()
+    })
+}
+
+pub fn update(reward: &StableCampaignReward) {
+    CAMPAIGN_REWARD_MAP.with(|m| {
+        m.borrow_mut()
+            .insert(StableCampaignRewardId(reward.user_id, reward.campaign_id), reward.clone())
+    });
+}
@@ -0,0 +1,5 @@
+pub mod campaign_map;
+pub mod campaign_reward_map;
+#[allow(clippy::module_inception)]
+pub mod stable_campaign;
+pub mod stable_campaign_reward;
@@ -0,0 +1,176 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply, nat_subtract, nat_zero};
+use crate::stable_lp_token::lp_token_fees::{earned_lp_fee, lp_fee_per_lp};
+
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableCampaignId(pub u32);
+
+impl Storable for StableCampaignId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableCampaignId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableCampaignId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// LP incentive campaign. reward_amount of the reward token is distributed to the LPs of a pool
+/// at a constant rate from start_ts to end_ts, in proportion to their LP tokens
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StableCampaign {
+    pub campaign_id: u32,
+    pub user_id: u32,    // user who funded the campaign
+    pub request_id: u64, // request of the campaign creation
+    pub pool_id: u32,
+    pub lp_token_id: u32, // LP token of the pool. rewards are earned by its holders
+    pub reward_token_id: u32,
+    pub reward_amount: Nat, // total rewards distributed from start_ts to end_ts
+    pub start_ts: u64,
+    pub end_ts: u64,
+    pub reward_per_lp: Nat,  // rewards per LP token since start_ts. scaled by LP_FEE_PER_LP_SCALE
+    pub distributed: Nat,    // rewards accrued to LPs. rewards for periods without LPs and rounding are not distributed
+    pub last_update_ts: u64, // time reward_per_lp was last updated
+    #[serde(default)]
+    pub refund_claim_id: Option<u64>, // claim of the undistributed rewards returned to the funder after end_ts
+    pub ts: u64,
+}
+
+impl StableCampaign {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_id: u32,
+        request_id: u64,
+        pool_id: u32,
+        lp_token_id: u32,
+        reward_token_id: u32,
+        reward_amount: &Nat,
+        start_ts: u64,
+        end_ts: u64,
+        ts: u64,
+    ) -> Self {
+        Self {
+            campaign_id: 0, // will be set with insert into CAMPAIGN_MAP
+            user_id,
+            request_id,
+            pool_id,
+            lp_token_id,
+            reward_token_id,
+            reward_amount: reward_amount.clone(),
+            start_ts,
+            end_ts,
+            reward_per_lp: nat_zero(),
+            distributed: nat_zero(),
+            last_update_ts: start_ts,
+            refund_claim_id: None,
+            ts,
+        }
+    }
+
+    pub fn is_active(&self, ts: u64) -> bool {
+        ts >= self.start_ts && ts < self.end_ts
+    }
+
+    /// rewards per second. reward_amount / duration
+    pub fn reward_per_sec(&self) -> Nat {
+        let duration = self.end_ts.saturating_sub(self.start_ts);
+        nat_divide(
+            &nat_multiply(&self.reward_amount, &Nat::from(1_000_000_000_u64)),
+            &Nat::from(duration),
+        )
+        .unwrap_or(nat_zero())
+    }
+
+    /// rewards released from start_ts to ts
+    pub fn emitted(&self, ts: u64) -> Nat {
+        let ts = ts.clamp(self.start_ts, self.end_ts);
+        let duration = self.end_ts.saturating_sub(self.start_ts);
+        let elapsed = ts - self.start_ts;
+        nat_divide(&nat_multiply(&self.reward_amount, &Nat::from(elapsed)), &Nat::from(duration)).unwrap_or(nat_zero())
+    }
+
+    /// accrue the rewards released since last_update_ts to reward_per_lp
+    /// total_lp is the LP token supply since last_update_ts
+    pub fn update_reward_per_lp(&mut self, total_lp: &Nat, ts: u64) {
+        let ts = std::cmp::min(ts, self.end_ts);
+        if ts <= self.last_update_ts {
+            return;
+        }
+        let reward = nat_subtract(&self.emitted(ts), &self.emitted(self.last_update_ts)).unwrap_or(nat_zero());
+        if !nat_is_zero(total_lp) {
+            let reward_per_lp = lp_fee_per_lp(&reward, total_lp);
+            // reward_per_lp is rounded down, only what it pays out to total_lp is distributed
+            self.distributed = nat_add(&self.distributed, &earned_lp_fee(total_lp, &reward_per_lp, &nat_zero()));
+            self.reward_per_lp = nat_add(&self.reward_per_lp, &reward_per_lp);
+        }
+        self.last_update_ts = ts;
+    }
+
+    /// rewards not distributed to LPs. the campaign must be updated to end_ts first
+    pub fn undistributed(&self) -> Nat {
+        nat_subtract(&self.reward_amount, &self.distributed).unwrap_or(nat_zero())
+    }
+}
+
+impl Storable for StableCampaign {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableCampaign").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableCampaign")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_reward_per_lp() {
+        // 1_000 rewards from 100 to 200
+        let mut campaign = StableCampaign::new(1, 1, 1, 1, 1, &Nat::from(1_000_u32), 100, 200, 0);
+        assert_eq!(campaign.emitted(50), nat_zero());
+        assert_eq!(campaign.emitted(150), Nat::from(500_u32));
+        assert_eq!(campaign.emitted(300), Nat::from(1_000_u32));
+
+        // no LPs for the first half, rewards are not distributed
+        campaign.update_reward_per_lp(&nat_zero(), 150);
+        assert_eq!(campaign.distributed, nat_zero());
+        assert_eq!(campaign.last_update_ts, 150);
+
+        // 50 LP tokens for the second half and after the end
+        campaign.update_reward_per_lp(&Nat::from(50_u32), 250);
+        assert_eq!(campaign.distributed, Nat::from(500_u32));
+        assert_eq!(campaign.last_update_ts, 200);
+        assert_eq!(
+            earned_lp_fee(&Nat::from(10_u32), &campaign.reward_per_lp, &nat_zero()),
+            Nat::from(100_u32)
+        );
+
+        // nothing more after the end
+        campaign.update_reward_per_lp(&Nat::from(50_u32), 300);
+        assert_eq!(campaign.distributed, Nat::from(500_u32));
+        assert_eq!(campaign.undistributed(), Nat::from(500_u32));
+    }
+
+    #[test]
+    fn test_rounding_is_not_distributed() {
+        let mut campaign = StableCampaign::new(1, 1, 1, 1, 1, &Nat::from(1_000_u32), 100, 200, 0);
+        campaign.update_reward_per_lp(&Nat::from(7_u32), 200);
+        // 1_000 / 7 per LP token is rounded down
+        assert_eq!(campaign.distributed, Nat::from(999_u32));
+        assert_eq!(campaign.undistributed(), Nat::from(1_u32));
+        assert_eq!(
+            earned_lp_fee(&Nat::from(7_u32), &campaign.reward_per_lp, &nat_zero()),
+            Nat::from(999_u32)
+        );
+    }
+}
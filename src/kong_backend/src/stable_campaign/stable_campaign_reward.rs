@@ -0,0 +1,57 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+/// (user_id, campaign_id)
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableCampaignRewardId(pub u32, pub u32);
+
+impl Storable for StableCampaignRewardId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableCampaignRewardId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableCampaignRewardId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// rewards of an LP in a campaign
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StableCampaignReward {
+    pub user_id: u32,
+    pub campaign_id: u32,
+    pub reward_per_lp: Nat, // campaign's reward_per_lp when pending was last updated
+    pub pending: Nat,       // rewards earned and not collected yet
+    pub collected: Nat,     // rewards collected as claims
+    pub claim_ids: Vec<u64>,
+    pub ts: u64, // timestamp of the last update
+}
+
+impl StableCampaignReward {
+    pub fn new(user_id: u32, campaign_id: u32, ts: u64) -> Self {
+        Self {
+            user_id,
+            campaign_id,
+            reward_per_lp: Nat::from(0_u32),
+            pending: Nat::from(0_u32),
+            collected: Nat::from(0_u32),
+            claim_ids: Vec::new(),
+            ts,
+        }
+    }
+}
+
+impl Storable for StableCampaignReward {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableCampaignReward").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableCampaignReward")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
@@ -1,3 +1,6 @@
+use candid::Nat;
+
+use crate::helpers::nat_helpers::nat_add;
 use crate::ic::network::ICNetwork;
 use crate::stable_kong_settings::kong_settings_map;
 use crate::stable_memory::CLAIM_MAP;
@@ -52,6 +55,21 @@ pub fn update_status(claim_id: u64, status: ClaimStatus) -> Option<StableClaim>
     })
 }
 
+// used to accrue to an open claim. only claims still claimable by the user can be added to
+pub fn add_claimable_amount(claim_id: u64, amount: &Nat) -> Option<StableClaim> {
+    CLAIM_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        match map.get(&StableClaimId(claim_id)) {
+            Some(mut v) if v.status == ClaimStatus::Claimable => {
+                v.amount = nat_add(&v.amount, amount);
+                map.insert(StableClaimId(claim_id), v.clone());
+                Some(v)
+            }
+            _ => None,
+        }
+    })
+}
+
 // used for setting the status of a claim to claiming to prevent reentrancy
 pub fn update_claiming_status(claim_id: u64) -> Option<StableClaim> {
     update_status(claim_id, ClaimStatus::Claiming)
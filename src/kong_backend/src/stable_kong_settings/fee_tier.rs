@@ -0,0 +1,70 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+/// fee level earned by users with a rolling 30-day swap volume of at least min_volume (in USD)
+#[derive(CandidType, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeTier {
+    pub fee_level: u8, // 0 = no discount, 100 = pays no lp_fee on swaps
+    pub min_volume: f64,
+}
+
+/// fee level of the highest tier reached with volume. 0 if no tier is reached
+pub fn earned_fee_level(fee_tiers: &[FeeTier], volume: f64) -> u8 {
+    fee_tiers
+        .iter()
+        .filter(|tier| volume >= tier.min_volume)
+        .map(|tier| std::cmp::min(tier.fee_level, 100))
+        .max()
+        .unwrap_or(0)
+}
+
+/// the next tier above fee_level and the volume needed to reach it
+pub fn next_fee_tier(fee_tiers: &[FeeTier], fee_level: u8) -> Option<&FeeTier> {
+    fee_tiers
+        .iter()
+        .filter(|tier| tier.fee_level > fee_level)
+        .min_by(|a, b| a.min_volume.total_cmp(&b.min_volume))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_tiers() -> Vec<FeeTier> {
+        vec![
+            FeeTier {
+                fee_level: 10,
+                min_volume: 10_000.0,
+            },
+            FeeTier {
+                fee_level: 25,
+                min_volume: 100_000.0,
+            },
+            FeeTier {
+                fee_level: 50,
+                min_volume: 1_000_000.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_earned_fee_level() {
+        let fee_tiers = fee_tiers();
+        assert_eq!(earned_fee_level(&fee_tiers, 0.0), 0);
+        assert_eq!(earned_fee_level(&fee_tiers, 9_999.99), 0);
+        assert_eq!(earned_fee_level(&fee_tiers, 10_000.0), 10);
+        assert_eq!(earned_fee_level(&fee_tiers, 500_000.0), 25);
+        assert_eq!(earned_fee_level(&fee_tiers, 5_000_000.0), 50);
+        assert_eq!(earned_fee_level(&[], 5_000_000.0), 0);
+    }
+
+    #[test]
+    fn test_next_fee_tier() {
+        let fee_tiers = fee_tiers();
+        assert_eq!(next_fee_tier(&fee_tiers, 0).map(|tier| tier.fee_level), Some(10));
+        assert_eq!(next_fee_tier(&fee_tiers, 10).map(|tier| tier.fee_level), Some(25));
+        // assigned fee level between tiers
+        assert_eq!(next_fee_tier(&fee_tiers, 30).map(|tier| tier.fee_level), Some(50));
+        assert_eq!(next_fee_tier(&fee_tiers, 50), None);
+    }
+}
@@ -1,3 +1,4 @@
+use candid::Principal;
 use std::cmp;
 
 use crate::stable_memory::KONG_SETTINGS;
@@ -121,3 +122,92 @@ pub fn inc_lp_token_map_idx() -> u64 {
         lp_token_map_idx
     })
 }
+
+pub fn inc_order_map_idx() -> u64 {
+    KONG_SETTINGS.with(|s| {
+        let mut map = s.borrow_mut();
+        let kong_settings = map.get();
+        let order_map_idx = kong_settings.order_map_idx + 1;
+        let new_kong_settings = StableKongSettings {
+            order_map_idx,
+            ..kong_settings.clone()
+        };
+        _ = map.set(new_kong_settings);
+        order_map_idx
+    })
+}
+
+pub fn inc_pool_fee_change_map_idx() -> u64 {
+    KONG_SETTINGS.with(|s| {
+        let mut map = s.borrow_mut();
+        let kong_settings = map.get();
+        let pool_fee_change_map_idx = kong_settings.pool_fee_change_map_idx + 1;
+        let new_kong_settings = StableKongSettings {
+            pool_fee_change_map_idx,
+            ..kong_settings.clone()
+        };
+        _ = map.set(new_kong_settings);
+        pool_fee_change_map_idx
+    })
+}
+
+pub fn inc_lp_position_map_idx() -> u64 {
+    KONG_SETTINGS.with(|s| {
+        let mut map = s.borrow_mut();
+        let kong_settings = map.get();
+        let lp_position_map_idx = kong_settings.lp_position_map_idx + 1;
+        let new_kong_settings = StableKongSettings {
+            lp_position_map_idx,
+            ..kong_settings.clone()
+        };
+        _ = map.set(new_kong_settings);
+        lp_position_map_idx
+    })
+}
+
+pub fn inc_campaign_map_idx() -> u32 {
+    KONG_SETTINGS.with(|s| {
+        let mut map = s.borrow_mut();
+        let kong_settings = map.get();
+        let campaign_map_idx = kong_settings.campaign_map_idx + 1;
+        let new_kong_settings = StableKongSettings {
+            campaign_map_idx,
+            ..kong_settings.clone()
+        };
+        _ = map.set(new_kong_settings);
+        campaign_map_idx
+    })
+}
+
+pub fn inc_lp_lock_map_idx() -> u64 {
+    KONG_SETTINGS.with(|s| {
+        let mut map = s.borrow_mut();
+        let kong_settings = map.get();
+        let lp_lock_map_idx = kong_settings.lp_lock_map_idx + 1;
+        let new_kong_settings = StableKongSettings {
+            lp_lock_map_idx,
+            ..kong_settings.clone()
+        };
+        _ = map.set(new_kong_settings);
+        lp_lock_map_idx
+    })
+}
+
+/// stop a canister from borrowing with flash_swap(), e.g. after it did not repay a loan
+pub fn remove_flash_swap_borrower(borrower: &Principal) {
+    KONG_SETTINGS.with(|s| {
+        let mut map = s.borrow_mut();
+        let kong_settings = map.get();
+        let flash_swap_borrowers = kong_settings
+            .flash_swap_borrowers
+            .iter()
+            .filter(|b| *b != borrower)
+            .cloned()
+            .collect();
+        let new_kong_settings = StableKongSettings {
+            flash_swap_borrowers,
+            ..kong_settings.clone()
+        };
+        _ = map.set(new_kong_settings);
+    })
+}
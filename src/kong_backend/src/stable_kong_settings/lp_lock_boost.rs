@@ -0,0 +1,43 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+/// boost to the weight of LP tokens locked for at least min_lock_secs in LP incentive campaigns
+#[derive(CandidType, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LPLockBoost {
+    pub min_lock_secs: u64,
+    pub boost_bps: u16, // 10_000 = locked LP tokens earn 2x
+}
+
+/// boost of the highest tier reached with a lock of lock_secs. 0 if no tier is reached
+pub fn lock_boost_bps(lp_lock_boosts: &[LPLockBoost], lock_secs: u64) -> u16 {
+    lp_lock_boosts
+        .iter()
+        .filter(|boost| lock_secs >= boost.min_lock_secs)
+        .map(|boost| boost.boost_bps)
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_boost_bps() {
+        let lp_lock_boosts = vec![
+            LPLockBoost {
+                min_lock_secs: 30 * 86_400,
+                boost_bps: 1_000,
+            },
+            LPLockBoost {
+                min_lock_secs: 365 * 86_400,
+                boost_bps: 5_000,
+            },
+        ];
+        assert_eq!(lock_boost_bps(&lp_lock_boosts, 0), 0);
+        assert_eq!(lock_boost_bps(&lp_lock_boosts, 30 * 86_400 - 1), 0);
+        assert_eq!(lock_boost_bps(&lp_lock_boosts, 30 * 86_400), 1_000);
+        assert_eq!(lock_boost_bps(&lp_lock_boosts, 400 * 86_400), 5_000);
+        assert_eq!(lock_boost_bps(&[], 400 * 86_400), 0);
+    }
+}
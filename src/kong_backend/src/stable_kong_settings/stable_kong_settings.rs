@@ -11,9 +11,13 @@ use crate::ic::{
 use crate::kong_backend::KongBackend;
 use crate::kong_data::KongData;
 use crate::stable_memory::{
-    CLAIM_MAP, LP_TOKEN_MAP, POOL_MAP, REQUEST_ARCHIVE_MAP, REQUEST_MAP, TOKEN_MAP, TRANSFER_ARCHIVE_MAP, TRANSFER_MAP, TX_ARCHIVE_MAP,
-    TX_MAP, USER_MAP,
+    CAMPAIGN_MAP, CLAIM_MAP, LP_LOCK_MAP, LP_POSITION_MAP, LP_TOKEN_MAP, ORDER_MAP, POOL_FEE_CHANGE_MAP, POOL_MAP, REQUEST_ARCHIVE_MAP,
+    REQUEST_MAP, TOKEN_MAP, TRANSFER_ARCHIVE_MAP, TRANSFER_MAP, TX_ARCHIVE_MAP, TX_MAP, USER_MAP,
 };
+use crate::stable_user::referral_code::REFERRAL_INTERVAL;
+
+use super::fee_tier::FeeTier;
+use super::lp_lock_boost::LPLockBoost;
 
 #[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
 pub struct StableKongSettings {
@@ -49,6 +53,86 @@ pub struct StableKongSettings {
     pub transfers_archive_interval_secs: u64,
     pub check_disabled_token_interval_secs: u64,
     pub archive_to_kong_data: bool,
+    #[serde(default = "default_max_swap_hops")]
+    pub max_swap_hops: u8, // maximum number of pools a swap can be routed through
+    #[serde(default)]
+    pub order_map_idx: u64, // counter for ORDER_MAP
+    #[serde(default = "default_orders_interval_secs")]
+    pub orders_interval_secs: u64,
+    #[serde(default = "default_max_twap_failed_slices")]
+    pub max_twap_failed_slices: u32, // TWAP orders are returned after this many slices failed in a row
+    #[serde(default)]
+    pub pool_fee_change_map_idx: u64, // counter for POOL_FEE_CHANGE_MAP
+    #[serde(default)]
+    pub lp_position_map_idx: u64, // counter for LP_POSITION_MAP
+    #[serde(default)]
+    pub referral_fee_share_bps: u16, // share of Kong's fee paid to the referrer of a swap. 10_000 = 100%
+    #[serde(default = "default_referral_interval_nanosecs")]
+    pub referral_interval_nanosecs: u64, // how long a referral stays active after the referred user signs up
+    #[serde(default)]
+    pub fee_tiers: Vec<FeeTier>, // fee levels earned with rolling 30-day swap volume
+    #[serde(default = "default_fee_tiers_interval_secs")]
+    pub fee_tiers_interval_secs: u64,
+    #[serde(default)]
+    pub treasury: Option<Account>, // account Kong's fees are swept to with sweep_protocol_fees()
+    #[serde(default)]
+    pub campaign_map_idx: u32, // counter for CAMPAIGN_MAP
+    #[serde(default)]
+    pub lp_lock_map_idx: u64, // counter for LP_LOCK_MAP
+    #[serde(default)]
+    pub lp_lock_boosts: Vec<LPLockBoost>, // campaign boosts earned by locking LP tokens
+    #[serde(default = "default_lp_locks_interval_secs")]
+    pub lp_locks_interval_secs: u64,
+    #[serde(default)]
+    pub flash_swap_borrowers: Vec<Principal>, // canisters allowed to borrow with flash_swap()
+    #[serde(default = "default_kong_rpc_relayers")]
+    pub kong_rpc_relayers: Vec<Principal>, // kong_rpc principals that relay Solana and XRPL transactions
+    #[serde(default = "default_solana_attestation_threshold")]
+    pub solana_attestation_threshold: u8, // number of kong_rpc_relayers that must attest an incoming Solana transfer
+    #[serde(default = "default_solana_swap_job_max_retries")]
+    pub solana_swap_job_max_retries: u8, // number of times an expired Solana swap job is re-signed and re-queued
+}
+
+fn default_max_swap_hops() -> u8 {
+    3
+}
+
+fn default_orders_interval_secs() -> u64 {
+    60
+}
+
+fn default_max_twap_failed_slices() -> u32 {
+    5
+}
+
+fn default_referral_interval_nanosecs() -> u64 {
+    REFERRAL_INTERVAL
+}
+
+fn default_fee_tiers_interval_secs() -> u64 {
+    3600
+}
+
+fn default_lp_locks_interval_secs() -> u64 {
+    600
+}
+
+fn default_kong_rpc_relayers() -> Vec<Principal> {
+    [
+        "6d7py-dit3v-5kk25-r7dci-gtr3f-rbxl7-m5oxw-7rhyu-4pmpp-j4lj2-lqe",
+        "xlotz-rhc3l-wkpkp-yzvau-ww2qy-zftze-2wrpx-pzcd2-fqa4t-3n36y-rae",
+    ]
+    .iter()
+    .filter_map(|principal| Principal::from_text(principal).ok())
+    .collect()
+}
+
+fn default_solana_attestation_threshold() -> u8 {
+    2
+}
+
+fn default_solana_swap_job_max_retries() -> u8 {
+    3
 }
 
 impl Default for StableKongSettings {
@@ -58,6 +142,11 @@ impl Default for StableKongSettings {
         let pool_map_idx = POOL_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0));
         let claim_map_idx = CLAIM_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0));
         let lp_token_map_idx = LP_TOKEN_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0));
+        let order_map_idx = ORDER_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0));
+        let pool_fee_change_map_idx = POOL_FEE_CHANGE_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0));
+        let lp_position_map_idx = LP_POSITION_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0));
+        let campaign_map_idx = CAMPAIGN_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0));
+        let lp_lock_map_idx = LP_LOCK_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0));
         let request_map_idx = cmp::max(
             REQUEST_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0)),
             REQUEST_ARCHIVE_MAP.with(|m| m.borrow().iter().map(|(k, _)| k.0).max().unwrap_or(0)),
@@ -96,6 +185,7 @@ impl Default for StableKongSettings {
             transfer_map_idx,
             claim_map_idx,
             lp_token_map_idx,
+            order_map_idx,
             claims_interval_secs: 300,                    // claims every 5 minutes
             transfer_expiry_nanosecs: 3_600_000_000_000,  // 1 hour (nano seconds)
             requests_archive_interval_secs: 3600,         // archive requests every hour
@@ -103,6 +193,24 @@ impl Default for StableKongSettings {
             transfers_archive_interval_secs: 3600,        // archive transfers every hour
             check_disabled_token_interval_secs: 3600 * 3, // check if disabled tokens became alive each 3 hours
             archive_to_kong_data: false,                  // replicate to kong_data
+            max_swap_hops: default_max_swap_hops(),
+            orders_interval_secs: default_orders_interval_secs(), // check open orders every minute
+            max_twap_failed_slices: default_max_twap_failed_slices(),
+            pool_fee_change_map_idx,
+            lp_position_map_idx,
+            referral_fee_share_bps: 0, // referral rewards disabled by default
+            referral_interval_nanosecs: default_referral_interval_nanosecs(),
+            fee_tiers: Vec::new(),                                      // no fee tiers by default
+            fee_tiers_interval_secs: default_fee_tiers_interval_secs(), // update earned fee levels every hour
+            treasury: None,
+            campaign_map_idx,
+            lp_lock_map_idx,
+            lp_lock_boosts: Vec::new(),                               // no lock boosts by default
+            lp_locks_interval_secs: default_lp_locks_interval_secs(), // end the boosts of expired locks every 10 minutes
+            flash_swap_borrowers: Vec::new(),                         // flash swaps disabled by default
+            kong_rpc_relayers: default_kong_rpc_relayers(),
+            solana_attestation_threshold: default_solana_attestation_threshold(), // both default relayers by default
+            solana_swap_job_max_retries: default_solana_swap_job_max_retries(),
         }
     }
 }
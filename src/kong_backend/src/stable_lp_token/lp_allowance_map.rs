@@ -0,0 +1,24 @@
+use crate::helpers::nat_helpers::nat_is_zero;
+use crate::stable_memory::LP_ALLOWANCE_MAP;
+
+use super::stable_lp_allowance::{StableLPAllowance, StableLPAllowanceId};
+
+/// get allowance of spender over owner's LP token. expired allowances are ignored
+pub fn get(token_id: u32, owner_user_id: u32, spender_user_id: u32, ts: u64) -> Option<StableLPAllowance> {
+    LP_ALLOWANCE_MAP
+        .with(|m| m.borrow().get(&StableLPAllowanceId(token_id, owner_user_id, spender_user_id)))
+        .filter(|allowance| !allowance.is_expired(ts))
+}
+
+/// insert or update an allowance. allowances of 0 are removed
+pub fn update(allowance: &StableLPAllowance) {
+    let key = StableLPAllowanceId(allowance.token_id, allowance.owner_user_id, allowance.spender_user_id);
+    LP_ALLOWANCE_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        if nat_is_zero(&allowance.amount) {
+            map.remove(&key);
+        } else {
+            map.insert(key, allowance.clone());
+        }
+    });
+}
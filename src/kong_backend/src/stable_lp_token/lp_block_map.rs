@@ -0,0 +1,99 @@
+use candid::Nat;
+
+use crate::stable_memory::LP_BLOCK_MAP;
+use crate::stable_user::user_map;
+
+use super::stable_lp_block::{LPBlockOp, StableLPBlock, StableLPBlockId};
+
+fn last_block(token_id: u32) -> Option<StableLPBlock> {
+    LP_BLOCK_MAP.with(|m| {
+        m.borrow()
+            .range(StableLPBlockId(token_id, 0)..=StableLPBlockId(token_id, u64::MAX))
+            .next_back()
+            .map(|(_, v)| v)
+    })
+}
+
+/// number of blocks in the log of an LP token
+pub fn get_log_length(token_id: u32) -> u64 {
+    last_block(token_id).map_or(0, |block| block.block_id + 1)
+}
+
+/// get up to length blocks of an LP token starting at block_id start
+pub fn get_by_range(token_id: u32, start: u64, length: u64) -> Vec<StableLPBlock> {
+    LP_BLOCK_MAP.with(|m| {
+        m.borrow()
+            .range(StableLPBlockId(token_id, start)..)
+            .take_while(|(k, _)| k.0 == token_id)
+            .take(length as usize)
+            .map(|(_, v)| v)
+            .collect()
+    })
+}
+
+/// block_id of an earlier block of the same transaction with ts at or after since_ts
+pub fn find_duplicate(block: &StableLPBlock, since_ts: u64) -> Option<u64> {
+    LP_BLOCK_MAP.with(|m| {
+        m.borrow()
+            .range(StableLPBlockId(block.token_id, 0)..=StableLPBlockId(block.token_id, u64::MAX))
+            .rev()
+            .map(|(_, v)| v)
+            .take_while(|v| v.ts >= since_ts)
+            .find(|v| block.is_duplicate_of(v))
+            .map(|v| v.block_id)
+    })
+}
+
+/// append a block to the log of its LP token, chaining it to the previous block's hash
+pub fn insert(block: &StableLPBlock) -> u64 {
+    let (block_id, phash) = match last_block(block.token_id) {
+        Some(last_block) => (last_block.block_id + 1, Some(last_block.hash())),
+        None => (0, None),
+    };
+    let insert_block = StableLPBlock {
+        block_id,
+        phash,
+        ..block.clone()
+    };
+    LP_BLOCK_MAP.with(|m| m.borrow_mut().insert(StableLPBlockId(block.token_id, block_id), insert_block));
+    block_id
+}
+
+fn principal_id(user_id: u32) -> Option<String> {
+    user_map::get_by_user_id(user_id).map(|user| user.principal_id)
+}
+
+/// log LP tokens minted to a user by add_pool and add_liquidity
+pub fn insert_mint(token_id: u32, to_user_id: u32, amount: &Nat, ts: u64) -> u64 {
+    insert(&StableLPBlock {
+        to: principal_id(to_user_id),
+        ..StableLPBlock::new(token_id, LPBlockOp::Mint, amount, ts)
+    })
+}
+
+/// log LP tokens burned from a user by remove_liquidity
+pub fn insert_burn(token_id: u32, from_user_id: u32, amount: &Nat, ts: u64) -> u64 {
+    insert(&StableLPBlock {
+        from: principal_id(from_user_id),
+        ..StableLPBlock::new(token_id, LPBlockOp::Burn, amount, ts)
+    })
+}
+
+/// log LP tokens transferred between users. spender is set for icrc2_transfer_from
+pub fn insert_transfer(
+    token_id: u32,
+    from_user_id: u32,
+    to_user_id: u32,
+    spender_user_id: Option<u32>,
+    amount: &Nat,
+    memo: Option<Vec<u8>>,
+    ts: u64,
+) -> u64 {
+    insert(&StableLPBlock {
+        from: principal_id(from_user_id),
+        to: principal_id(to_user_id),
+        spender: spender_user_id.and_then(principal_id),
+        memo,
+        ..StableLPBlock::new(token_id, LPBlockOp::Transfer, amount, ts)
+    })
+}
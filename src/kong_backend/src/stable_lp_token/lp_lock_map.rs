@@ -0,0 +1,224 @@
+use candid::Nat;
+
+use crate::helpers::nat_helpers::{nat_add, nat_is_zero, nat_subtract, nat_zero};
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::{LP_LOCK_IDS, LP_LOCK_MAP, LP_LOCK_TOTAL_BOOST};
+use crate::stable_pool::pool_map;
+
+use super::lp_token_map;
+use super::stable_lp_lock::{StableLPLock, StableLPLockId};
+
+/// index the locks of LP_LOCK_MAP by LP token and user and sum their boosts. called on init and upgrade
+pub fn create_lp_lock_index() {
+    LP_LOCK_IDS.with(|m| m.borrow_mut().clear());
+    LP_LOCK_TOTAL_BOOST.with(|m| m.borrow_mut().clear());
+    LP_LOCK_MAP.with(|m| {
+        m.borrow().iter().for_each(|(_, lock)| index_lock(None, &lock));
+    });
+}
+
+/// add a lock to the index and its boost to the total boost of its LP token in place of the boost of old_lock
+fn index_lock(old_lock: Option<&StableLPLock>, lock: &StableLPLock) {
+    if old_lock.is_none() {
+        LP_LOCK_IDS.with(|m| {
+            m.borrow_mut()
+                .entry((lock.lp_token_id, lock.user_id))
+                .or_default()
+                .push(lock.lock_id)
+        });
+    }
+    LP_LOCK_TOTAL_BOOST.with(|m| {
+        let mut map = m.borrow_mut();
+        let total_boost = map.entry(lock.lp_token_id).or_insert_with(nat_zero);
+        let old_boost = old_lock.map_or(nat_zero(), |old_lock| old_lock.boost());
+        *total_boost = nat_subtract(&nat_add(total_boost, &lock.boost()), &old_boost).unwrap_or(nat_zero());
+    });
+}
+
+/// get the locks of a user's LP tokens
+fn get_by_lp_token_id_by_user_id(lp_token_id: u32, user_id: u32) -> Vec<StableLPLock> {
+    let lock_ids = LP_LOCK_IDS.with(|m| m.borrow().get(&(lp_token_id, user_id)).cloned().unwrap_or_default());
+    LP_LOCK_MAP.with(|m| {
+        let map = m.borrow();
+        lock_ids.iter().filter_map(|lock_id| map.get(&StableLPLockId(*lock_id))).collect()
+    })
+}
+
+pub fn get_by_lock_id(lock_id: u64) -> Option<StableLPLock> {
+    LP_LOCK_MAP.with(|m| m.borrow().get(&StableLPLockId(lock_id)))
+}
+
+/// get all locks of a pool, latest first
+pub fn get_by_pool_id(pool_id: u32) -> Vec<StableLPLock> {
+    LP_LOCK_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .rev()
+            .filter_map(|(_, v)| if v.pool_id == pool_id { Some(v) } else { None })
+            .collect()
+    })
+}
+
+/// get all locks of a user, latest first
+pub fn get_by_user_id(user_id: u32) -> Vec<StableLPLock> {
+    LP_LOCK_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .rev()
+            .filter_map(|(_, v)| if v.user_id == user_id { Some(v) } else { None })
+            .collect()
+    })
+}
+
+/// get the locks whose unlock_ts has passed and whose boost still applies
+pub fn get_expired(ts: u64) -> Vec<StableLPLock> {
+    LP_LOCK_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .filter_map(|(_, v)| if !v.is_expired && !v.is_locked(ts) { Some(v) } else { None })
+            .collect()
+    })
+}
+
+/// LP tokens of a user that are locked at ts
+pub fn get_locked_amount(user_id: u32, lp_token_id: u32, ts: u64) -> Nat {
+    get_by_lp_token_id_by_user_id(lp_token_id, user_id)
+        .into_iter()
+        .filter(|lock| lock.is_locked(ts))
+        .fold(nat_zero(), |acc, lock| nat_add(&acc, &lock.amount))
+}
+
+/// weight added to a user's LP tokens in LP incentive campaigns by the user's locks
+pub fn get_boost(user_id: u32, lp_token_id: u32) -> Nat {
+    get_by_lp_token_id_by_user_id(lp_token_id, user_id)
+        .iter()
+        .fold(nat_zero(), |acc, lock| nat_add(&acc, &lock.boost()))
+}
+
+/// weight added to all LP tokens of lp_token_id in LP incentive campaigns by locks
+pub fn get_total_boost(lp_token_id: u32) -> Nat {
+    LP_LOCK_TOTAL_BOOST.with(|m| m.borrow().get(&lp_token_id).cloned().unwrap_or(nat_zero()))
+}
+
+/// make sure amount of a user's LP tokens can be sent, transferred or removed from the pool without touching locked LP tokens
+/// locks do not apply to removed pools so their liquidity can be returned to users
+pub fn check_unlocked(user_id: u32, lp_token_id: u32, amount: &Nat, ts: u64) -> Result<(), String> {
+    let locked_amount = get_locked_amount(user_id, lp_token_id, ts);
+    if nat_is_zero(&locked_amount) || pool_map::get_by_lp_token_id(lp_token_id).is_none_or(|pool| pool.is_removed) {
+        return Ok(());
+    }
+    let balance = lp_token_map::get_by_token_id_by_user_id(lp_token_id, user_id).map_or(nat_zero(), |lp_token| lp_token.amount);
+    let unlocked_amount = nat_subtract(&balance, &locked_amount).unwrap_or(nat_zero());
+    if *amount > unlocked_amount {
+        Err(format!(
+            "Insufficient unlocked LP tokens. {} available, {} locked, {} required",
+            unlocked_amount, locked_amount, amount
+        ))?
+    }
+    Ok(())
+}
+
+/// end the boosts of a user's locks whose unlock_ts has passed
+pub fn expire(user_id: u32, lp_token_id: u32, ts: u64) {
+    for lock in get_by_lp_token_id_by_user_id(lp_token_id, user_id) {
+        if !lock.is_expired && !lock.is_locked(ts) {
+            update(&StableLPLock { is_expired: true, ..lock });
+        }
+    }
+}
+
+pub fn insert(lock: &StableLPLock) -> u64 {
+    let insert_lock = LP_LOCK_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        let lock_id = kong_settings_map::inc_lp_lock_map_idx();
+        let insert_lock = StableLPLock { lock_id, ..lock.clone() };
+        map.insert(StableLPLockId(lock_id), insert_lock.clone());
+        insert_lock
+    });
+    index_lock(None, &insert_lock);
+    insert_lock.lock_id
+}
+
+pub fn update(lock: &StableLPLock) {
+    let old_lock = LP_LOCK_MAP.with(|m| m.borrow_mut().insert(StableLPLockId(lock.lock_id), lock.clone()));
+    index_lock(old_lock.as_ref(), lock);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_pool};
+    use crate::stable_lp_token::stable_lp_token::StableLPToken;
+    use crate::stable_pool::stable_pool::StablePool;
+
+    const LP_TOKEN_ID: u32 = 3;
+
+    // pool 1 with its LP token and 100 LP tokens of user 1 and 2
+    fn setup() -> StablePool {
+        init_kong_settings();
+        let pool = StablePool {
+            lp_token_id: LP_TOKEN_ID,
+            ..insert_pool(1, 1, 1_000, 2, 1_000)
+        };
+        pool_map::update(&pool);
+        for user_id in [1, 2] {
+            lp_token_map::insert(&StableLPToken::new(user_id, LP_TOKEN_ID, Nat::from(100_u32), 0)).unwrap();
+        }
+        pool
+    }
+
+    // lock of amount LP tokens until unlock_ts with a boost of 50%
+    fn lock(user_id: u32, amount: u32, unlock_ts: u64) -> u64 {
+        insert(&StableLPLock::new(user_id, 1, LP_TOKEN_ID, &Nat::from(amount), unlock_ts, 5_000, 0))
+    }
+
+    #[test]
+    fn test_locked_lp_tokens_can_not_be_sent_before_unlock_ts() {
+        setup();
+        lock(1, 60, 100);
+
+        assert!(check_unlocked(1, LP_TOKEN_ID, &Nat::from(40_u32), 50).is_ok());
+        assert!(check_unlocked(1, LP_TOKEN_ID, &Nat::from(41_u32), 50).is_err());
+        assert_eq!(get_locked_amount(1, LP_TOKEN_ID, 50), Nat::from(60_u32));
+        // other users' LP tokens are not locked
+        assert!(check_unlocked(2, LP_TOKEN_ID, &Nat::from(100_u32), 50).is_ok());
+
+        // all LP tokens can be sent or removed from unlock_ts
+        assert!(check_unlocked(1, LP_TOKEN_ID, &Nat::from(100_u32), 100).is_ok());
+        assert!(nat_is_zero(&get_locked_amount(1, LP_TOKEN_ID, 100)));
+    }
+
+    #[test]
+    fn test_removed_pool_bypasses_locks() {
+        let pool = setup();
+        lock(1, 100, 100);
+        assert!(check_unlocked(1, LP_TOKEN_ID, &Nat::from(1_u32), 50).is_err());
+
+        pool_map::update(&StablePool { is_removed: true, ..pool });
+        assert!(check_unlocked(1, LP_TOKEN_ID, &Nat::from(100_u32), 50).is_ok());
+    }
+
+    #[test]
+    fn test_boosts_are_indexed_and_expire() {
+        setup();
+        lock(1, 60, 100);
+        lock(1, 20, 200);
+        lock(2, 40, 100);
+        assert_eq!(get_boost(1, LP_TOKEN_ID), Nat::from(40_u32));
+        assert_eq!(get_boost(2, LP_TOKEN_ID), Nat::from(20_u32));
+        assert_eq!(get_total_boost(LP_TOKEN_ID), Nat::from(60_u32));
+
+        // only user 1's lock past unlock_ts expires
+        expire(1, LP_TOKEN_ID, 100);
+        assert_eq!(get_boost(1, LP_TOKEN_ID), Nat::from(10_u32));
+        assert_eq!(get_total_boost(LP_TOKEN_ID), Nat::from(30_u32));
+        assert_eq!(get_expired(100).len(), 1);
+
+        // the index is the same when created again from LP_LOCK_MAP, e.g. after an upgrade
+        create_lp_lock_index();
+        assert_eq!(get_boost(1, LP_TOKEN_ID), Nat::from(10_u32));
+        assert_eq!(get_total_boost(LP_TOKEN_ID), Nat::from(30_u32));
+        assert!(nat_is_zero(&get_total_boost(LP_TOKEN_ID + 1)));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::LP_POSITION_MAP;
+
+use super::stable_lp_position::{StableLPPosition, StableLPPositionId};
+
+pub fn get_by_position_id(position_id: u64) -> Option<StableLPPosition> {
+    LP_POSITION_MAP.with(|m| m.borrow().get(&StableLPPositionId(position_id)))
+}
+
+/// get all positions of a user, latest first
+pub fn get_by_user_id(user_id: u32) -> Vec<StableLPPosition> {
+    LP_POSITION_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .rev()
+            .filter_map(|(_, v)| if v.user_id == user_id { Some(v) } else { None })
+            .collect()
+    })
+}
+
+pub fn insert(position: &StableLPPosition) -> u64 {
+    LP_POSITION_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        let position_id = kong_settings_map::inc_lp_position_map_idx();
+        let insert_position = StableLPPosition {
+            position_id,
+            ..position.clone()
+        };
+        map.insert(StableLPPositionId(position_id), insert_position);
+        position_id
+    })
+}
+
+pub fn update(position: &StableLPPosition) {
+    LP_POSITION_MAP.with(|m| {
+        m.borrow_mut().insert(StableLPPositionId(position.position_id), position.clone());
+    });
+}
+
+pub fn remove(position_id: u64) -> Option<StableLPPosition> {
+    LP_POSITION_MAP.with(|m| m.borrow_mut().remove(&StableLPPositionId(position_id)))
+}
@@ -0,0 +1,98 @@
+use candid::Nat;
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_multiply, nat_subtract, nat_zero};
+
+// lp_fee_per_lp is scaled up so small fees on a large LP token supply are not rounded away
+pub const LP_FEE_PER_LP_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// cost basis and earned fees of an amount of LP tokens
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LPTokenBasis {
+    pub deposit_0: Nat,
+    pub deposit_1: Nat,
+    pub fees_0: Nat,
+    pub fees_1: Nat,
+}
+
+/// scaled LP fee per LP token. lp_fee * LP_FEE_PER_LP_SCALE / lp_total_supply
+/// zero if there are no LP tokens
+pub fn lp_fee_per_lp(lp_fee: &Nat, lp_total_supply: &Nat) -> Nat {
+    nat_divide(&nat_multiply(lp_fee, &Nat::from(LP_FEE_PER_LP_SCALE)), lp_total_supply).unwrap_or(nat_zero())
+}
+
+/// LP fee earned by amount of LP tokens since the pool's lp_fee_per_lp was at checkpoint
+pub fn earned_lp_fee(amount: &Nat, lp_fee_per_lp: &Nat, checkpoint: &Nat) -> Nat {
+    let lp_fee_per_lp_delta = nat_subtract(lp_fee_per_lp, checkpoint).unwrap_or(nat_zero());
+    nat_divide(&nat_multiply(amount, &lp_fee_per_lp_delta), &Nat::from(LP_FEE_PER_LP_SCALE)).unwrap_or(nat_zero())
+}
+
+/// split the basis of total_amount LP tokens into the basis of amount LP tokens and the basis of the rest
+/// the removed basis is rounded down so the remaining basis never goes to zero before the LP tokens do
+pub fn split_basis(basis: &LPTokenBasis, amount: &Nat, total_amount: &Nat) -> (LPTokenBasis, LPTokenBasis) {
+    let share = |value: &Nat| {
+        if amount >= total_amount {
+            return value.clone();
+        }
+        nat_divide(&nat_multiply(value, amount), total_amount).unwrap_or(nat_zero())
+    };
+    let removed = LPTokenBasis {
+        deposit_0: share(&basis.deposit_0),
+        deposit_1: share(&basis.deposit_1),
+        fees_0: share(&basis.fees_0),
+        fees_1: share(&basis.fees_1),
+    };
+    let remaining = LPTokenBasis {
+        deposit_0: nat_subtract(&basis.deposit_0, &removed.deposit_0).unwrap_or(nat_zero()),
+        deposit_1: nat_subtract(&basis.deposit_1, &removed.deposit_1).unwrap_or(nat_zero()),
+        fees_0: nat_subtract(&basis.fees_0, &removed.fees_0).unwrap_or(nat_zero()),
+        fees_1: nat_subtract(&basis.fees_1, &removed.fees_1).unwrap_or(nat_zero()),
+    };
+    (removed, remaining)
+}
+
+/// combine the basis of two amounts of LP tokens
+pub fn add_basis(basis: &LPTokenBasis, other: &LPTokenBasis) -> LPTokenBasis {
+    LPTokenBasis {
+        deposit_0: nat_add(&basis.deposit_0, &other.deposit_0),
+        deposit_1: nat_add(&basis.deposit_1, &other.deposit_1),
+        fees_0: nat_add(&basis.fees_0, &other.fees_0),
+        fees_1: nat_add(&basis.fees_1, &other.fees_1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_earned_lp_fee() {
+        // 1_000 LP tokens in the pool, 50 LP fee
+        let fee_per_lp = lp_fee_per_lp(&Nat::from(50_u32), &Nat::from(1_000_u32));
+        // a holder of 200 LP tokens earns 10
+        assert_eq!(earned_lp_fee(&Nat::from(200_u32), &fee_per_lp, &nat_zero()), Nat::from(10_u32));
+        // nothing earned since the last checkpoint
+        assert_eq!(earned_lp_fee(&Nat::from(200_u32), &fee_per_lp, &fee_per_lp), nat_zero());
+        // no LP tokens
+        assert_eq!(lp_fee_per_lp(&Nat::from(50_u32), &nat_zero()), nat_zero());
+    }
+
+    #[test]
+    fn test_split_basis() {
+        let basis = LPTokenBasis {
+            deposit_0: Nat::from(1_000_u32),
+            deposit_1: Nat::from(3_001_u32),
+            fees_0: Nat::from(10_u32),
+            fees_1: Nat::from(0_u32),
+        };
+        let (removed, remaining) = split_basis(&basis, &Nat::from(1_u32), &Nat::from(4_u32));
+        assert_eq!(removed.deposit_0, Nat::from(250_u32));
+        assert_eq!(removed.deposit_1, Nat::from(750_u32));
+        assert_eq!(remaining.deposit_1, Nat::from(2_251_u32));
+        assert_eq!(remaining.fees_0, Nat::from(8_u32));
+        assert_eq!(add_basis(&removed, &remaining), basis);
+        // removing all LP tokens removes all of the basis
+        let (removed, remaining) = split_basis(&basis, &Nat::from(4_u32), &Nat::from(4_u32));
+        assert_eq!(removed, basis);
+        assert_eq!(remaining, LPTokenBasis::default());
+    }
+}
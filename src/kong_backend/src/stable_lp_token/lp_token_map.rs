@@ -1,5 +1,6 @@
 use candid::Nat;
 
+use crate::campaigns::campaign_rewards;
 use crate::helpers::nat_helpers::{nat_add, nat_zero};
 use crate::ic::network::ICNetwork;
 use crate::stable_kong_settings::kong_settings_map;
@@ -46,6 +47,9 @@ pub fn get_total_supply(token_id: u32) -> Nat {
 }
 
 pub fn insert(lp_token: &StableLPToken) -> Result<u64, String> {
+    // campaign rewards are earned with the LP token balance before the change
+    campaign_rewards::update_lp_rewards(lp_token.user_id, lp_token.token_id);
+
     let insert_lp_token = LP_TOKEN_MAP.with(|m| {
         let mut map = m.borrow_mut();
         let lp_token_id = kong_settings_map::inc_lp_token_map_idx();
@@ -62,6 +66,8 @@ pub fn insert(lp_token: &StableLPToken) -> Result<u64, String> {
 }
 
 pub fn update(lp_token: &StableLPToken) {
+    // campaign rewards are earned with the LP token balance before the change
+    campaign_rewards::update_lp_rewards(lp_token.user_id, lp_token.token_id);
     LP_TOKEN_MAP.with(|m| m.borrow_mut().insert(StableLPTokenId(lp_token.lp_token_id), lp_token.clone()));
     _ = archive_to_kong_data(lp_token);
 }
@@ -1,4 +1,13 @@
+pub mod lp_allowance_map;
+pub mod lp_block_map;
+pub mod lp_lock_map;
+pub mod lp_position_map;
+pub mod lp_token_fees;
 pub mod lp_token_map;
+pub mod stable_lp_allowance;
+pub mod stable_lp_block;
+pub mod stable_lp_lock;
+pub mod stable_lp_position;
 #[allow(clippy::module_inception)]
 pub mod stable_lp_token;
 pub mod transfer;
@@ -0,0 +1,48 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+/// (token_id, owner user_id, spender user_id)
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableLPAllowanceId(pub u32, pub u32, pub u32);
+
+impl Storable for StableLPAllowanceId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableLPAllowanceId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableLPAllowanceId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// ICRC-2 allowance of a spender over the LP token of an owner
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StableLPAllowance {
+    pub token_id: u32,           // token id of the LP token
+    pub owner_user_id: u32,      // user id of the LP token holder
+    pub spender_user_id: u32,    // user id of the spender
+    pub amount: Nat,             // amount the spender can still transfer
+    pub expires_at: Option<u64>, // timestamp after which the allowance is void
+    pub ts: u64,                 // timestamp of the last approve or transfer_from
+}
+
+impl StableLPAllowance {
+    pub fn is_expired(&self, ts: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= ts)
+    }
+}
+
+impl Storable for StableLPAllowance {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableLPAllowance").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableLPAllowance")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
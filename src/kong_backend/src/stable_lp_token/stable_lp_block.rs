@@ -0,0 +1,153 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use icrc_ledger_types::icrc::generic_value::{ICRC3Map, ICRC3Value};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use std::str::FromStr;
+
+/// (token_id, block_id). blocks of an LP token are ordered oldest first
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableLPBlockId(pub u32, pub u64);
+
+impl Storable for StableLPBlockId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableLPBlockId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableLPBlockId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[derive(CandidType, Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LPBlockOp {
+    Mint, // add liquidity
+    Burn, // remove liquidity
+    #[default]
+    Transfer, // send, icrc1_transfer and icrc2_transfer_from
+    Approve, // icrc2_approve
+}
+
+/// ICRC-3 block of an LP token. block_ids are per LP token, starting at 0
+#[derive(CandidType, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StableLPBlock {
+    pub token_id: u32,                   // token id of the LP token
+    pub block_id: u64,                   // index of the block in the LP token's log
+    pub op: LPBlockOp,                   // operation of the block
+    pub from: Option<String>,            // account debited, or the approver. principal id for default subaccounts
+    pub to: Option<String>,              // account credited
+    pub spender: Option<String>,         // spender of icrc2_approve and icrc2_transfer_from
+    pub amount: Nat,                     // amount of LP token
+    pub expected_allowance: Option<Nat>, // icrc2_approve only
+    pub expires_at: Option<u64>,         // icrc2_approve only
+    pub memo: Option<Vec<u8>>,           // memo of the caller
+    pub ts: u64,                         // timestamp of the block
+    pub phash: Option<Vec<u8>>,          // hash of the previous block. None for the first block
+    #[serde(default)]
+    pub created_at_time: Option<u64>, // created_at_time of the caller, used to deduplicate transactions
+}
+
+impl StableLPBlock {
+    pub fn new(token_id: u32, op: LPBlockOp, amount: &Nat, ts: u64) -> Self {
+        Self {
+            token_id,
+            op,
+            amount: amount.clone(),
+            ts,
+            ..Default::default()
+        }
+    }
+
+    /// ICRC-3 block type
+    pub fn btype(&self) -> &str {
+        match self.op {
+            LPBlockOp::Mint => "1mint",
+            LPBlockOp::Burn => "1burn",
+            LPBlockOp::Transfer if self.spender.is_some() => "2xfer",
+            LPBlockOp::Transfer => "1xfer",
+            LPBlockOp::Approve => "2approve",
+        }
+    }
+
+    /// block in the ICRC-3 generic block format
+    pub fn to_icrc3_value(&self) -> ICRC3Value {
+        // accounts are kept in the ICRC-1 textual encoding. default subaccounts are encoded with the owner only
+        let account = |account: &str| {
+            let mut value = Vec::new();
+            if let Ok(account) = Account::from_str(account) {
+                value.push(ICRC3Value::Blob(ByteBuf::from(account.owner.as_slice().to_vec())));
+                if let Some(subaccount) = account.subaccount.filter(|subaccount| *subaccount != [0; 32]) {
+                    value.push(ICRC3Value::Blob(ByteBuf::from(subaccount.to_vec())));
+                }
+            }
+            ICRC3Value::Array(value)
+        };
+
+        let mut tx = ICRC3Map::new();
+        tx.insert("amt".to_string(), ICRC3Value::Nat(self.amount.clone()));
+        if let Some(from) = &self.from {
+            tx.insert("from".to_string(), account(from));
+        }
+        if let Some(to) = &self.to {
+            tx.insert("to".to_string(), account(to));
+        }
+        if let Some(spender) = &self.spender {
+            tx.insert("spender".to_string(), account(spender));
+        }
+        if let Some(expected_allowance) = &self.expected_allowance {
+            tx.insert("expected_allowance".to_string(), ICRC3Value::Nat(expected_allowance.clone()));
+        }
+        if let Some(expires_at) = self.expires_at {
+            tx.insert("expires_at".to_string(), ICRC3Value::Nat(Nat::from(expires_at)));
+        }
+        if let Some(memo) = &self.memo {
+            tx.insert("memo".to_string(), ICRC3Value::Blob(ByteBuf::from(memo.clone())));
+        }
+        if let Some(created_at_time) = self.created_at_time {
+            tx.insert("ts".to_string(), ICRC3Value::Nat(Nat::from(created_at_time)));
+        }
+
+        let mut block = ICRC3Map::new();
+        block.insert("btype".to_string(), ICRC3Value::Text(self.btype().to_string()));
+        block.insert("ts".to_string(), ICRC3Value::Nat(Nat::from(self.ts)));
+        block.insert("tx".to_string(), ICRC3Value::Map(tx));
+        if let Some(phash) = &self.phash {
+            block.insert("phash".to_string(), ICRC3Value::Blob(ByteBuf::from(phash.clone())));
+        }
+        ICRC3Value::Map(block)
+    }
+
+    /// same transaction submitted twice. only transactions with a created_at_time are deduplicated
+    pub fn is_duplicate_of(&self, block: &StableLPBlock) -> bool {
+        self.created_at_time.is_some()
+            && self.created_at_time == block.created_at_time
+            && self.token_id == block.token_id
+            && self.op == block.op
+            && self.from == block.from
+            && self.to == block.to
+            && self.spender == block.spender
+            && self.amount == block.amount
+            && self.expected_allowance == block.expected_allowance
+            && self.expires_at == block.expires_at
+            && self.memo == block.memo
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        self.to_icrc3_value().hash().to_vec()
+    }
+}
+
+impl Storable for StableLPBlock {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableLPBlock").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableLPBlock")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
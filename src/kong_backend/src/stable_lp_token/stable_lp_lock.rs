@@ -0,0 +1,75 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::nat_helpers::{nat_divide, nat_multiply, nat_zero};
+
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableLPLockId(pub u64);
+
+impl Storable for StableLPLockId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableLPLockId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableLPLockId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// LP tokens of a user that can not be sent, transferred or removed from the pool until unlock_ts
+/// the LP tokens stay in the user's LP token balance
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StableLPLock {
+    pub lock_id: u64,
+    pub user_id: u32,     // user id of the lock owner
+    pub pool_id: u32,     // pool id of the LP token
+    pub lp_token_id: u32, // token id of the LP token
+    pub amount: Nat,      // LP tokens locked
+    pub unlock_ts: u64,   // LP tokens are locked until unlock_ts
+    pub boost_bps: u16,   // boost to the weight of the locked LP tokens in LP incentive campaigns
+    pub is_expired: bool, // set by the LP locks timer after unlock_ts. the boost no longer applies
+    pub ts: u64,          // timestamp of the lock
+}
+
+impl StableLPLock {
+    pub fn new(user_id: u32, pool_id: u32, lp_token_id: u32, amount: &Nat, unlock_ts: u64, boost_bps: u16, ts: u64) -> Self {
+        Self {
+            lock_id: 0,
+            user_id,
+            pool_id,
+            lp_token_id,
+            amount: amount.clone(),
+            unlock_ts,
+            boost_bps,
+            is_expired: false,
+            ts,
+        }
+    }
+
+    pub fn is_locked(&self, ts: u64) -> bool {
+        ts < self.unlock_ts
+    }
+
+    /// weight added to the locked LP tokens in LP incentive campaigns until the lock has expired
+    pub fn boost(&self) -> Nat {
+        if self.is_expired {
+            return nat_zero();
+        }
+        nat_divide(&nat_multiply(&self.amount, &Nat::from(self.boost_bps)), &Nat::from(10_000_u32)).unwrap_or(nat_zero())
+    }
+}
+
+impl Storable for StableLPLock {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableLPLock").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableLPLock")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
@@ -0,0 +1,66 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::nat_helpers::nat_zero;
+
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableLPPositionId(pub u64);
+
+impl Storable for StableLPPositionId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableLPPositionId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableLPPositionId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Liquidity of a user in a price range of a concentrated liquidity pool
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StableLPPosition {
+    pub position_id: u64,
+    pub user_id: u32,                  // user id of the position owner
+    pub pool_id: u32,                  // pool id of the concentrated liquidity pool
+    pub tick_lower: i32,               // lower tick of the price range
+    pub tick_upper: i32,               // upper tick of the price range
+    pub liquidity: Nat,                // liquidity of the position. 0 once the position is burned
+    pub fee_growth_inside_0_last: Nat, // fee growth of token_0 inside the range when the fees were last updated
+    pub fee_growth_inside_1_last: Nat, // fee growth of token_1 inside the range when the fees were last updated
+    pub fees_owed_0: Nat,              // uncollected fees of token_0 as of the last update
+    pub fees_owed_1: Nat,              // uncollected fees of token_1 as of the last update
+    pub ts: u64,                       // timestamp of the last position update
+}
+
+impl StableLPPosition {
+    pub fn new(user_id: u32, pool_id: u32, tick_lower: i32, tick_upper: i32, ts: u64) -> Self {
+        Self {
+            position_id: 0,
+            user_id,
+            pool_id,
+            tick_lower,
+            tick_upper,
+            liquidity: nat_zero(),
+            fee_growth_inside_0_last: nat_zero(),
+            fee_growth_inside_1_last: nat_zero(),
+            fees_owed_0: nat_zero(),
+            fees_owed_1: nat_zero(),
+            ts,
+        }
+    }
+}
+
+impl Storable for StableLPPosition {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableLPPosition").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableLPPosition")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
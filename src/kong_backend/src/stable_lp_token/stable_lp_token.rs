@@ -2,6 +2,11 @@ use candid::{CandidType, Nat};
 use ic_stable_structures::{storable::Bound, Storable};
 use serde::{Deserialize, Serialize};
 
+use crate::helpers::nat_helpers::{nat_add, nat_subtract, nat_zero};
+use crate::stable_pool::stable_pool::StablePool;
+
+use super::lp_token_fees::{add_basis, earned_lp_fee, split_basis, LPTokenBasis};
+
 #[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct StableLPTokenId(pub u64);
 
@@ -24,6 +29,18 @@ pub struct StableLPToken {
     pub token_id: u32,    // token id of the token
     pub amount: Nat,      // amount the user holds of the token
     pub ts: u64,          // timestamp of the last token update
+    #[serde(default)]
+    pub deposit_0: Nat, // cost basis. amount of token_0 deposited for the LP tokens held
+    #[serde(default)]
+    pub deposit_1: Nat,
+    #[serde(default)]
+    pub fees_0: Nat, // LP fees in token_0 earned by the LP tokens held
+    #[serde(default)]
+    pub fees_1: Nat,
+    #[serde(default)]
+    pub lp_fee_per_lp_0: Nat, // pool's lp_fee_per_lp_0 when fees_0 was last updated
+    #[serde(default)]
+    pub lp_fee_per_lp_1: Nat,
 }
 
 impl StableLPToken {
@@ -34,8 +51,68 @@ impl StableLPToken {
             token_id,
             amount,
             ts,
+            deposit_0: nat_zero(),
+            deposit_1: nat_zero(),
+            fees_0: nat_zero(),
+            fees_1: nat_zero(),
+            lp_fee_per_lp_0: nat_zero(),
+            lp_fee_per_lp_1: nat_zero(),
+        }
+    }
+
+    pub fn basis(&self) -> LPTokenBasis {
+        LPTokenBasis {
+            deposit_0: self.deposit_0.clone(),
+            deposit_1: self.deposit_1.clone(),
+            fees_0: self.fees_0.clone(),
+            fees_1: self.fees_1.clone(),
         }
     }
+
+    fn set_basis(&mut self, basis: LPTokenBasis) {
+        self.deposit_0 = basis.deposit_0;
+        self.deposit_1 = basis.deposit_1;
+        self.fees_0 = basis.fees_0;
+        self.fees_1 = basis.fees_1;
+    }
+
+    /// LP fees earned since fees_0 and fees_1 were last updated
+    pub fn pending_fees(&self, pool: &StablePool) -> (Nat, Nat) {
+        (
+            earned_lp_fee(&self.amount, &pool.lp_fee_per_lp_0, &self.lp_fee_per_lp_0),
+            earned_lp_fee(&self.amount, &pool.lp_fee_per_lp_1, &self.lp_fee_per_lp_1),
+        )
+    }
+
+    /// add the LP fees earned since the last update to fees_0 and fees_1
+    /// must be called with the latest state of the pool before the amount changes
+    pub fn update_fees(&mut self, pool: &StablePool) {
+        let (pending_fee_0, pending_fee_1) = self.pending_fees(pool);
+        self.fees_0 = nat_add(&self.fees_0, &pending_fee_0);
+        self.fees_1 = nat_add(&self.fees_1, &pending_fee_1);
+        self.lp_fee_per_lp_0 = pool.lp_fee_per_lp_0.clone();
+        self.lp_fee_per_lp_1 = pool.lp_fee_per_lp_1.clone();
+    }
+
+    /// add LP tokens with their cost basis
+    pub fn mint(&mut self, pool: &StablePool, amount: &Nat, basis: &LPTokenBasis, ts: u64) {
+        self.update_fees(pool);
+        self.amount = nat_add(&self.amount, amount);
+        self.set_basis(add_basis(&self.basis(), basis));
+        self.ts = ts;
+    }
+
+    /// remove LP tokens. the cost basis and earned fees are reduced in proportion
+    /// returns the cost basis and earned fees of the removed LP tokens or None if there are not enough LP tokens
+    pub fn burn(&mut self, pool: &StablePool, amount: &Nat, ts: u64) -> Option<LPTokenBasis> {
+        let new_amount = nat_subtract(&self.amount, amount)?;
+        self.update_fees(pool);
+        let (removed, remaining) = split_basis(&self.basis(), amount, &self.amount);
+        self.amount = new_amount;
+        self.set_basis(remaining);
+        self.ts = ts;
+        Some(removed)
+    }
 }
 
 impl Storable for StableLPToken {
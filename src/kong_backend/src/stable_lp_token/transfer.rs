@@ -1,9 +1,10 @@
 use candid::Nat;
 
-use crate::helpers::nat_helpers::{nat_add, nat_subtract};
-use crate::ic::network::ICNetwork;
+use crate::helpers::nat_helpers::nat_zero;
+use crate::stable_pool::pool_map;
+use crate::stable_user::user_map;
 
-use super::lp_token_map;
+use super::lp_lock_map::check_unlocked;
 use super::lp_token_map::{get_by_token_id_by_user_id, insert, update};
 use super::stable_lp_token::StableLPToken;
 
@@ -13,39 +14,66 @@ use super::stable_lp_token::StableLPToken;
 /// token_id - token_id of the LP token
 /// to_user_id - user_id of the user to transfer LP token to
 /// amount - amount of LP token to transfer
+/// ts - timestamp of the transfer
 ///
 /// # Returns
 /// StableLPToken - updated LP token of the caller
 /// Err - if LP token not found or not enough LP token
-pub fn transfer(token_id: u32, to_user_id: u32, amount: &Nat) -> Result<StableLPToken, String> {
-    let ts = ICNetwork::get_time();
-
-    let from_user = match lp_token_map::get_by_token_id(token_id) {
-        Some(from_user_lp_token) => {
-            if from_user_lp_token.amount < *amount {
-                return Err("Not enough LP token".to_string());
-            }
-            let amount = nat_subtract(&from_user_lp_token.amount, amount).ok_or("Error calculating new user balance")?;
-            StableLPToken {
-                amount,
-                ts,
-                ..from_user_lp_token
-            }
+pub fn transfer(token_id: u32, to_user_id: u32, amount: &Nat, ts: u64) -> Result<StableLPToken, String> {
+    let from_user_id = user_map::get_by_caller().ok().flatten().ok_or("Not enough LP token")?.user_id;
+    transfer_by_user_id(token_id, from_user_id, to_user_id, amount, ts)
+}
+
+/// transfer LP token between 2 users
+///
+/// # Arguments
+/// token_id - token_id of the LP token
+/// from_user_id - user_id of the user to transfer LP token from
+/// to_user_id - user_id of the user to transfer LP token to
+/// amount - amount of LP token to transfer
+/// ts - timestamp of the transfer
+///
+/// # Returns
+/// StableLPToken - updated LP token of from_user_id
+/// Err - if LP token not found, not enough LP token or the LP tokens are locked
+pub fn transfer_by_user_id(token_id: u32, from_user_id: u32, to_user_id: u32, amount: &Nat, ts: u64) -> Result<StableLPToken, String> {
+    check_unlocked(from_user_id, token_id, amount, ts)?;
+    let pool = pool_map::get_by_lp_token_id(token_id).ok_or("LP token pool not found")?;
+
+    // cost basis and earned fees move with the LP tokens
+    let (from_user, basis) = match get_by_token_id_by_user_id(token_id, from_user_id) {
+        Some(mut from_user_lp_token) => {
+            let basis = from_user_lp_token.burn(&pool, amount, ts).ok_or("Not enough LP token")?;
+            (from_user_lp_token, basis)
         }
         None => return Err("Not enough LP token".to_string()),
     };
     update(&from_user);
 
     // get user's LP token balance if already exists or create new
-    if let Some(to_user_lp_token) = get_by_token_id_by_user_id(token_id, to_user_id) {
-        update(&StableLPToken {
-            amount: nat_add(&to_user_lp_token.amount, amount),
-            ts,
-            ..to_user_lp_token
-        });
+    if let Some(mut to_user_lp_token) = get_by_token_id_by_user_id(token_id, to_user_id) {
+        to_user_lp_token.mint(&pool, amount, &basis, ts);
+        update(&to_user_lp_token);
     } else {
-        insert(&StableLPToken::new(to_user_id, token_id, amount.clone(), ts))?;
+        let mut to_user_lp_token = StableLPToken::new(to_user_id, token_id, nat_zero(), ts);
+        to_user_lp_token.mint(&pool, amount, &basis, ts);
+        insert(&to_user_lp_token)?;
     }
 
     Ok(from_user)
 }
+
+/// burn LP tokens of a user without removing liquidity, i.e. a transfer to the minting account of the LP ledger
+/// the pool balances are unchanged, so the burned share goes to the other liquidity providers
+///
+/// # Returns
+/// StableLPToken - updated LP token of from_user_id
+/// Err - if LP token not found, not enough LP token or the LP tokens are locked
+pub fn burn_by_user_id(token_id: u32, from_user_id: u32, amount: &Nat, ts: u64) -> Result<StableLPToken, String> {
+    check_unlocked(from_user_id, token_id, amount, ts)?;
+    let pool = pool_map::get_by_lp_token_id(token_id).ok_or("LP token pool not found")?;
+    let mut from_user = get_by_token_id_by_user_id(token_id, from_user_id).ok_or("Not enough LP token")?;
+    from_user.burn(&pool, amount, ts).ok_or("Not enough LP token")?;
+    update(&from_user);
+    Ok(from_user)
+}
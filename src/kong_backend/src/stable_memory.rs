@@ -1,19 +1,34 @@
+use candid::Nat;
 use ic_stable_structures::memory_manager::{MemoryId, MemoryManager, VirtualMemory};
 use ic_stable_structures::{DefaultMemoryImpl, StableBTreeMap, StableCell};
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 
 use crate::ic::network::ICNetwork;
+use crate::ripple::kong_rpc::transaction_notification::{RippleTxNotification, RippleTxNotificationId};
+use crate::ripple::ledger::RippleLedger;
+use crate::ripple::payment_job::{RipplePaymentJob, RipplePaymentJobId};
 use crate::solana::kong_rpc::transaction_notification::{TransactionNotification, TransactionNotificationId};
+use crate::solana::kong_rpc::transfer_attestation::TransferAttestations;
 use crate::solana::swap_job::{SwapJob, SwapJobId};
+use crate::stable_campaign::stable_campaign::{StableCampaign, StableCampaignId};
+use crate::stable_campaign::stable_campaign_reward::{StableCampaignReward, StableCampaignRewardId};
 use crate::stable_claim::stable_claim::{StableClaim, StableClaimId};
 use crate::stable_kong_settings::stable_kong_settings::StableKongSettings;
+use crate::stable_lp_token::stable_lp_allowance::{StableLPAllowance, StableLPAllowanceId};
+use crate::stable_lp_token::stable_lp_block::{StableLPBlock, StableLPBlockId};
+use crate::stable_lp_token::stable_lp_lock::{StableLPLock, StableLPLockId};
+use crate::stable_lp_token::stable_lp_position::{StableLPPosition, StableLPPositionId};
 use crate::stable_lp_token::stable_lp_token::{StableLPToken, StableLPTokenId};
+use crate::stable_order::stable_order::{StableOrder, StableOrderId};
 use crate::stable_pool::stable_pool::{StablePool, StablePoolId};
+use crate::stable_pool::stable_pool_fee_change::{StablePoolFeeChange, StablePoolFeeChangeId};
+use crate::stable_pool::stable_tick::{StableTick, StableTickId};
 use crate::stable_request::stable_request::{StableRequest, StableRequestId};
 use crate::stable_token::stable_token::{StableToken, StableTokenId};
 use crate::stable_transfer::stable_transfer::{StableTransfer, StableTransferId};
 use crate::stable_tx::stable_tx::{StableTx, StableTxId};
+use crate::stable_user::stable_referral::{StableReferral, StableReferralId};
 use crate::stable_user::stable_user::{StableUser, StableUserId};
 use crate::stable_user::suspended_user_map::SuspendedUser;
 
@@ -29,14 +44,29 @@ pub const REQUEST_MEMORY_ID: MemoryId = MemoryId::new(26);
 pub const TRANSFER_MEMORY_ID: MemoryId = MemoryId::new(27);
 pub const CLAIM_MEMORY_ID: MemoryId = MemoryId::new(28);
 pub const LP_TOKEN_MEMORY_ID: MemoryId = MemoryId::new(29);
+pub const ORDER_MEMORY_ID: MemoryId = MemoryId::new(30);
+pub const POOL_FEE_CHANGE_MEMORY_ID: MemoryId = MemoryId::new(31);
+pub const TICK_MEMORY_ID: MemoryId = MemoryId::new(32);
+pub const LP_POSITION_MEMORY_ID: MemoryId = MemoryId::new(33);
+pub const LP_ALLOWANCE_MEMORY_ID: MemoryId = MemoryId::new(34);
+pub const LP_BLOCK_MEMORY_ID: MemoryId = MemoryId::new(35);
+pub const REFERRAL_MEMORY_ID: MemoryId = MemoryId::new(36);
+pub const CAMPAIGN_MEMORY_ID: MemoryId = MemoryId::new(37);
+pub const CAMPAIGN_REWARD_MEMORY_ID: MemoryId = MemoryId::new(38);
+pub const LP_LOCK_MEMORY_ID: MemoryId = MemoryId::new(39);
 // Stable memory for Solana
 pub const CACHED_SOLANA_ADDRESS_ID: MemoryId = MemoryId::new(60);
 pub const SOLANA_BLOCKHASH_ID: MemoryId = MemoryId::new(61);
 pub const NEXT_SOLANA_SWAP_JOB_ID_ID: MemoryId = MemoryId::new(62);
 pub const SOLANA_SWAP_JOB_QUEUE_ID: MemoryId = MemoryId::new(63);
 pub const SOLANA_TX_NOTIFICATIONS_ID: MemoryId = MemoryId::new(64);
+pub const SOLANA_TX_ATTESTATIONS_ID: MemoryId = MemoryId::new(65);
 // Stable memory for Ripple
 pub const CACHED_RIPPLE_ADDRESS_ID: MemoryId = MemoryId::new(70);
+pub const RIPPLE_LEDGER_ID: MemoryId = MemoryId::new(71);
+pub const NEXT_RIPPLE_PAYMENT_JOB_ID_ID: MemoryId = MemoryId::new(72);
+pub const RIPPLE_PAYMENT_JOB_QUEUE_ID: MemoryId = MemoryId::new(73);
+pub const RIPPLE_TX_NOTIFICATIONS_ID: MemoryId = MemoryId::new(74);
 // Archives
 pub const TX_ARCHIVE_MEMORY_ID: MemoryId = MemoryId::new(204);
 pub const REQUEST_ARCHIVE_MEMORY_ID: MemoryId = MemoryId::new(205);
@@ -46,6 +76,9 @@ thread_local! {
     // Static variables
     pub static PRINCIPAL_ID_MAP: RefCell<BTreeMap<String, u32>> = RefCell::default();
     pub static SUSPENDED_USERS: RefCell<BTreeMap<u32, SuspendedUser>> = RefCell::default();
+    // lock_ids of LP_LOCK_MAP by (lp_token_id, user_id) and the total boost of the locks of each LP token
+    pub static LP_LOCK_IDS: RefCell<BTreeMap<(u32, u32), Vec<u64>>> = RefCell::default();
+    pub static LP_LOCK_TOTAL_BOOST: RefCell<BTreeMap<u32, Nat>> = RefCell::default();
 
     // MEMORY_MANAGER is given management of the entire stable memory. Given a 'MemoryId', it can
     // return a memory that can be used by stable structures
@@ -97,6 +130,56 @@ thread_local! {
         RefCell::new(StableBTreeMap::init(memory_manager.get(LP_TOKEN_MEMORY_ID)))
     });
 
+    // stable memory for storing limit and TWAP orders
+    pub static ORDER_MAP: RefCell<StableBTreeMap<StableOrderId, StableOrder, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(ORDER_MEMORY_ID)))
+    });
+
+    // stable memory for storing the audit records of pool fee changes
+    pub static POOL_FEE_CHANGE_MAP: RefCell<StableBTreeMap<StablePoolFeeChangeId, StablePoolFeeChange, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(POOL_FEE_CHANGE_MEMORY_ID)))
+    });
+
+    // stable memory for storing initialized ticks of concentrated liquidity pools
+    pub static TICK_MAP: RefCell<StableBTreeMap<StableTickId, StableTick, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(TICK_MEMORY_ID)))
+    });
+
+    // stable memory for storing LP positions of concentrated liquidity pools
+    pub static LP_POSITION_MAP: RefCell<StableBTreeMap<StableLPPositionId, StableLPPosition, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(LP_POSITION_MEMORY_ID)))
+    });
+
+    // stable memory for storing ICRC-2 allowances of LP tokens
+    pub static LP_ALLOWANCE_MAP: RefCell<StableBTreeMap<StableLPAllowanceId, StableLPAllowance, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(LP_ALLOWANCE_MEMORY_ID)))
+    });
+
+    // stable memory for storing the ICRC-3 block logs of LP tokens
+    pub static LP_BLOCK_MAP: RefCell<StableBTreeMap<StableLPBlockId, StableLPBlock, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(LP_BLOCK_MEMORY_ID)))
+    });
+
+    // stable memory for storing referral volume and rewards of referrers
+    pub static REFERRAL_MAP: RefCell<StableBTreeMap<StableReferralId, StableReferral, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(REFERRAL_MEMORY_ID)))
+    });
+
+    // stable memory for storing LP incentive campaigns
+    pub static CAMPAIGN_MAP: RefCell<StableBTreeMap<StableCampaignId, StableCampaign, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(CAMPAIGN_MEMORY_ID)))
+    });
+
+    // stable memory for storing the rewards of LPs in incentive campaigns
+    pub static CAMPAIGN_REWARD_MAP: RefCell<StableBTreeMap<StableCampaignRewardId, StableCampaignReward, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(CAMPAIGN_REWARD_MEMORY_ID)))
+    });
+
+    // stable memory for storing locks of LP tokens
+    pub static LP_LOCK_MAP: RefCell<StableBTreeMap<StableLPLockId, StableLPLock, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(LP_LOCK_MEMORY_ID)))
+    });
+
     // Cached Solana address (persisted)
     pub static CACHED_SOLANA_ADDRESS: RefCell<StableCell<String, Memory>> = with_memory_manager(|memory_manager| {
         RefCell::new(StableCell::init(memory_manager.get(CACHED_SOLANA_ADDRESS_ID), String::new()).expect("Failed to initialize CACHED_SOLANA_ADDRESS cell"))
@@ -122,11 +205,36 @@ thread_local! {
         RefCell::new(StableBTreeMap::init(memory_manager.get(SOLANA_TX_NOTIFICATIONS_ID)))
     });
 
+    // Attestations of incoming Solana transfers waiting for the attestation threshold
+    pub static SOLANA_TX_ATTESTATIONS: RefCell<StableBTreeMap<TransactionNotificationId, TransferAttestations, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(SOLANA_TX_ATTESTATIONS_ID)))
+    });
+
     // Cached Ripple address (persisted)
     pub static CACHED_RIPPLE_ADDRESS: RefCell<StableCell<String, Memory>> = with_memory_manager(|memory_manager| {
         RefCell::new(StableCell::init(memory_manager.get(CACHED_RIPPLE_ADDRESS_ID), String::new()).expect("Failed to initialize CACHED_RIPPLE_ADDRESS cell"))
     });
 
+    // Kong's XRPL account sequence and the latest validated ledger (persisted)
+    pub static RIPPLE_LEDGER: RefCell<StableCell<RippleLedger, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableCell::init(memory_manager.get(RIPPLE_LEDGER_ID), RippleLedger::default()).expect("Failed to initialize RIPPLE_LEDGER cell"))
+    });
+
+    // Counter for Ripple payment job IDs (persisted)
+    pub static NEXT_RIPPLE_PAYMENT_JOB_ID: RefCell<StableCell<u64, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableCell::init(memory_manager.get(NEXT_RIPPLE_PAYMENT_JOB_ID_ID), 0u64).expect("Failed to initialize NEXT_RIPPLE_PAYMENT_JOB_ID cell"))
+    });
+
+    // Stable map for Ripple payment jobs
+    pub static RIPPLE_PAYMENT_JOB_QUEUE: RefCell<StableBTreeMap<RipplePaymentJobId, RipplePaymentJob, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(RIPPLE_PAYMENT_JOB_QUEUE_ID)))
+    });
+
+    // Stable map for Ripple transaction notifications
+    pub static RIPPLE_TX_NOTIFICATIONS: RefCell<StableBTreeMap<RippleTxNotificationId, RippleTxNotification, Memory>> = with_memory_manager(|memory_manager| {
+        RefCell::new(StableBTreeMap::init(memory_manager.get(RIPPLE_TX_NOTIFICATIONS_ID)))
+    });
+
     //
     // Archive Stable Memory
     //
@@ -0,0 +1,3 @@
+pub mod order_map;
+#[allow(clippy::module_inception)]
+pub mod stable_order;
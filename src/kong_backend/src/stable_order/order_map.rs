@@ -0,0 +1,166 @@
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::ORDER_MAP;
+
+use super::stable_order::{OrderStatus, StableOrder, StableOrderId};
+
+pub fn get_by_order_id(order_id: u64) -> Option<StableOrder> {
+    ORDER_MAP.with(|m| m.borrow().get(&StableOrderId(order_id)))
+}
+
+/// get all orders of a user, latest first
+pub fn get_by_user_id(user_id: u32) -> Vec<StableOrder> {
+    ORDER_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .rev()
+            .filter_map(|(_, v)| if v.user_id == user_id { Some(v) } else { None })
+            .collect()
+    })
+}
+
+/// get order_ids of all open orders, oldest first
+pub fn get_open_order_ids() -> Vec<u64> {
+    ORDER_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .filter_map(|(_, v)| if v.status == OrderStatus::Open { Some(v.order_id) } else { None })
+            .collect()
+    })
+}
+
+/// get order_ids of at most max_orders open orders after after_order_id, oldest first
+pub fn get_open_order_ids_after(after_order_id: Option<u64>, max_orders: usize) -> Vec<u64> {
+    ORDER_MAP.with(|m| {
+        let map = m.borrow();
+        let orders = match after_order_id {
+            Some(order_id) => map.range(StableOrderId(order_id.saturating_add(1))..),
+            None => map.range(..),
+        };
+        orders
+            .filter_map(|(_, v)| if v.status == OrderStatus::Open { Some(v.order_id) } else { None })
+            .take(max_orders)
+            .collect()
+    })
+}
+
+pub fn insert(order: &StableOrder) -> u64 {
+    ORDER_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        let order_id = kong_settings_map::inc_order_map_idx();
+        let insert_order = StableOrder { order_id, ..order.clone() };
+        map.insert(StableOrderId(order_id), insert_order);
+        order_id
+    })
+}
+
+pub fn update(order: &StableOrder) {
+    ORDER_MAP.with(|m| {
+        m.borrow_mut().insert(StableOrderId(order.order_id), order.clone());
+    });
+}
+
+pub fn update_status(order_id: u64, status: OrderStatus) -> Option<StableOrder> {
+    ORDER_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        match map.get(&StableOrderId(order_id)) {
+            Some(mut v) => {
+                v.status = status;
+                map.insert(StableOrderId(order_id), v.clone());
+                Some(v)
+            }
+            None => None,
+        }
+    })
+}
+
+/// set the status of an open order to guard against re-entrancy. returns None if the order is not open
+pub fn update_open_status(order_id: u64, status: OrderStatus) -> Option<StableOrder> {
+    ORDER_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        match map.get(&StableOrderId(order_id)) {
+            Some(mut v) if v.status == OrderStatus::Open => {
+                v.status = status;
+                map.insert(StableOrderId(order_id), v.clone());
+                Some(v)
+            }
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::{Nat, Principal};
+
+    use crate::ic::address::Address;
+    use crate::stable_order::stable_order::OrderType;
+
+    // placed orders are inserted directly as insert() needs the Kong settings of a canister
+    fn place_order(order_id: u64) {
+        let order = StableOrder {
+            order_id,
+            ..StableOrder::new(
+                100,
+                1,
+                OrderType::Limit { limit_price: 10.0 },
+                1,
+                &Nat::from(1_000_u64),
+                2,
+                &Address::PrincipalId(Principal::anonymous().into()),
+                None,
+                0,
+            )
+        };
+        update(&order);
+    }
+
+    #[test]
+    fn test_placed_order_is_open() {
+        place_order(1);
+        assert_eq!(get_by_order_id(1).unwrap().status, OrderStatus::Open);
+        assert_eq!(get_open_order_ids(), vec![1]);
+        assert_eq!(get_by_user_id(100).len(), 1);
+    }
+
+    #[test]
+    fn test_filling_order_can_not_be_cancelled() {
+        place_order(1);
+        assert!(update_open_status(1, OrderStatus::Filling).is_some());
+        assert!(get_open_order_ids().is_empty());
+        // cancel and a second fill are rejected while the order is filling
+        assert!(update_open_status(1, OrderStatus::Cancelling).is_none());
+        assert!(update_open_status(1, OrderStatus::Filling).is_none());
+        // re-opened after a failed fill or a TWAP slice
+        update_status(1, OrderStatus::Open);
+        assert_eq!(get_open_order_ids(), vec![1]);
+    }
+
+    #[test]
+    fn test_cancelling_order_can_not_be_filled() {
+        place_order(1);
+        place_order(2);
+        assert!(update_open_status(2, OrderStatus::Cancelling).is_some());
+        assert!(update_open_status(2, OrderStatus::Filling).is_none());
+        assert_eq!(get_open_order_ids(), vec![1]);
+        update_status(2, OrderStatus::Cancelled);
+        assert!(update_open_status(2, OrderStatus::Cancelling).is_none());
+    }
+
+    #[test]
+    fn test_open_order_ids_after() {
+        for order_id in 1..=4 {
+            place_order(order_id);
+        }
+        update_status(2, OrderStatus::Filled);
+        assert_eq!(get_open_order_ids_after(None, 2), vec![1, 3]);
+        assert_eq!(get_open_order_ids_after(Some(3), 2), vec![4]);
+        assert!(get_open_order_ids_after(Some(4), 2).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_order() {
+        assert!(update_open_status(1, OrderStatus::Cancelling).is_none());
+        assert!(update_status(1, OrderStatus::Cancelled).is_none());
+    }
+}
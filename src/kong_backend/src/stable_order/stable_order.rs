@@ -0,0 +1,136 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+use crate::ic::address::Address;
+
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableOrderId(pub u64);
+
+impl Storable for StableOrderId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableOrderId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableOrderId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderStatus {
+    Open,
+    Filling, // used as a guard to prevent re-entrancy
+    Filled,
+    Cancelling, // used as a guard to prevent re-entrancy
+    Cancelled,
+    Expired,
+}
+
+impl std::fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderStatus::Open => write!(f, "Open"),
+            OrderStatus::Filling => write!(f, "Filling"),
+            OrderStatus::Filled => write!(f, "Filled"),
+            OrderStatus::Cancelling => write!(f, "Cancelling"),
+            OrderStatus::Cancelled => write!(f, "Cancelled"),
+            OrderStatus::Expired => write!(f, "Expired"),
+        }
+    }
+}
+
+#[derive(CandidType, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OrderType {
+    Limit {
+        limit_price: f64,
+    }, // fill when the price (receive token per pay token) is at or above limit_price
+    Twap {
+        num_slices: u32,    // pay_amount is swapped in num_slices equal slices
+        interval_secs: u64, // time between slices
+        max_slippage: f64,  // max slippage of each slice
+        slices_filled: u32,
+        next_slice_at: u64, // time in nanoseconds of the next slice
+        #[serde(default)]
+        failed_slices: u32, // slices failed in a row, the order is returned after max_twap_failed_slices
+    },
+}
+
+impl std::fmt::Display for OrderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderType::Limit { .. } => write!(f, "Limit"),
+            OrderType::Twap { .. } => write!(f, "TWAP"),
+        }
+    }
+}
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StableOrder {
+    pub order_id: u64,
+    pub user_id: u32,
+    pub request_id: u64, // request_id of the order placement
+    pub status: OrderStatus,
+    pub order_type: OrderType,
+    pub pay_token_id: u32,
+    pub pay_amount: Nat, // pay amount held in custody by the canister
+    pub receive_token_id: u32,
+    pub receive_amount: Nat, // total amount received from fills
+    #[serde(default)]
+    pub filled_pay_amount: Nat, // total amount of pay_amount swapped by fills
+    pub to_address: Address,
+    pub fill_request_ids: Vec<u64>, // request_ids of the swaps that filled the order
+    pub tx_ids: Vec<u64>,           // SwapTx tx_ids of the fills
+    pub expires_at: Option<u64>,    // order is expired and pay token returned after this time
+    #[serde(default)]
+    pub claim_ids: Vec<u64>, // claims of the unfilled pay token on cancellation
+    pub ts: u64,
+}
+
+impl StableOrder {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_id: u32,
+        request_id: u64,
+        order_type: OrderType,
+        pay_token_id: u32,
+        pay_amount: &Nat,
+        receive_token_id: u32,
+        to_address: &Address,
+        expires_at: Option<u64>,
+        ts: u64,
+    ) -> Self {
+        Self {
+            order_id: 0, // will be set with insert into ORDER_MAP
+            user_id,
+            request_id,
+            status: OrderStatus::Open,
+            order_type,
+            pay_token_id,
+            pay_amount: pay_amount.clone(),
+            receive_token_id,
+            receive_amount: Nat::from(0_u32),
+            filled_pay_amount: Nat::from(0_u32),
+            to_address: to_address.clone(),
+            fill_request_ids: Vec::new(),
+            tx_ids: Vec::new(),
+            expires_at,
+            claim_ids: Vec::new(),
+            ts,
+        }
+    }
+}
+
+impl Storable for StableOrder {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableOrder").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableOrder")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
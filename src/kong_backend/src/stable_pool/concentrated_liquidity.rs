@@ -0,0 +1,352 @@
+//! Swaps and position updates of concentrated liquidity pools against the pool state and its initialized ticks
+use candid::Nat;
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply, nat_subtract, nat_zero};
+use crate::stable_lp_token::stable_lp_position::StableLPPosition;
+
+use super::concentrated_math::{self, SwapResult, TickLiquidity};
+use super::concentrated_pool::ConcentratedPool;
+use super::stable_pool::StablePool;
+use super::stable_tick::StableTick;
+use super::tick_map;
+
+/// initialized ticks of a pool, lowest first
+fn pool_ticks(pool_id: u32) -> Vec<TickLiquidity> {
+    tick_map::get_by_pool_id(pool_id).iter().map(TickLiquidity::from).collect()
+}
+
+/// amount_out received for amount_in, before fees. amounts are in max_decimals precision
+pub fn amount_out(pool: &StablePool, pays_token_0: bool, amount_in: &Nat) -> Option<Nat> {
+    let state = pool.concentrated.as_ref()?;
+    let ticks = pool_ticks(pool.pool_id);
+    concentrated_math::swap(
+        &state.sqrt_price,
+        state.tick,
+        &state.liquidity,
+        &ticks,
+        pays_token_0,
+        amount_in,
+        true,
+    )
+    .map(|result| result.amount_out)
+}
+
+/// amount_in required to receive amount_out, before fees. amounts are in max_decimals precision
+pub fn amount_in(pool: &StablePool, pays_token_0: bool, amount_out: &Nat) -> Option<Nat> {
+    let state = pool.concentrated.as_ref()?;
+    let ticks = pool_ticks(pool.pool_id);
+    concentrated_math::swap(
+        &state.sqrt_price,
+        state.tick,
+        &state.liquidity,
+        &ticks,
+        pays_token_0,
+        amount_out,
+        false,
+    )
+    .map(|result| result.amount_in)
+}
+
+/// swap receiving amount_out through the liquidity in range and the initialized ticks of the pool
+fn swap_exact_out(pool: &StablePool, pays_token_0: bool, amount_out: &Nat) -> Result<SwapResult, String> {
+    let state = pool.concentrated.as_ref().ok_or("Pool is not a concentrated liquidity pool")?;
+    let ticks = pool_ticks(pool.pool_id);
+    concentrated_math::swap(
+        &state.sqrt_price,
+        state.tick,
+        &state.liquidity,
+        &ticks,
+        pays_token_0,
+        amount_out,
+        false,
+    )
+    .ok_or_else(|| "Insufficient liquidity in pool".to_string())
+}
+
+/// move the pool price for a swap receiving amount_out without accruing fees or crossing ticks in tick_map
+/// used on a copy of the pool to check that a swap can be applied
+pub fn move_price(pool: &mut StablePool, pays_token_0: bool, amount_out: &Nat) -> Result<(), String> {
+    let result = swap_exact_out(pool, pays_token_0, amount_out)?;
+    let state = pool.concentrated.as_mut().ok_or("Pool is not a concentrated liquidity pool")?;
+    state.sqrt_price = result.sqrt_price;
+    state.tick = result.tick;
+    state.liquidity = result.liquidity;
+    Ok(())
+}
+
+/// move the pool price for a swap receiving amount_out and accrue lp_fee to the liquidity the swap went through
+/// amount_out is in max_decimals precision, lp_fee is the LP share of the fee in the receive token's precision
+/// nothing is updated if the swap fails
+pub fn apply_swap(pool: &mut StablePool, pays_token_0: bool, amount_out: &Nat, lp_fee: &Nat) -> Result<(), String> {
+    let result = swap_exact_out(pool, pays_token_0, amount_out)?;
+    let pool_id = pool.pool_id;
+    let state = pool.concentrated.as_mut().ok_or("Pool is not a concentrated liquidity pool")?;
+
+    for step in &result.steps {
+        // each step earns its share of the fee, split by the liquidity in range of the step
+        if !nat_is_zero(&step.liquidity) && !nat_is_zero(&result.amount_out) {
+            let step_fee = nat_divide(&nat_multiply(lp_fee, &step.amount_out), &result.amount_out).unwrap_or(nat_zero());
+            let fee_growth = concentrated_math::fee_growth(&step_fee, &step.liquidity);
+            // fees are in the receive token
+            if pays_token_0 {
+                state.fee_growth_1 = concentrated_math::fee_growth_add(&state.fee_growth_1, &fee_growth);
+            } else {
+                state.fee_growth_0 = concentrated_math::fee_growth_add(&state.fee_growth_0, &fee_growth);
+            }
+        }
+        // crossing a tick moves the fee growth outside of the tick to the other side
+        if let Some(mut tick) = step.crossed_tick.and_then(|tick| tick_map::get(pool_id, tick)) {
+            tick.fee_growth_outside_0 = concentrated_math::fee_growth_sub(&state.fee_growth_0, &tick.fee_growth_outside_0);
+            tick.fee_growth_outside_1 = concentrated_math::fee_growth_sub(&state.fee_growth_1, &tick.fee_growth_outside_1);
+            tick_map::update(&tick);
+        }
+    }
+
+    state.sqrt_price = result.sqrt_price;
+    state.tick = result.tick;
+    state.liquidity = result.liquidity;
+    Ok(())
+}
+
+/// fee growth of token_0 and token_1 inside the range of a position
+fn fee_growth_inside(state: &ConcentratedPool, position: &StableLPPosition, lower: &StableTick, upper: &StableTick) -> (Nat, Nat) {
+    let fee_growth_inside_0 = concentrated_math::fee_growth_inside(
+        state.tick,
+        position.tick_lower,
+        position.tick_upper,
+        &state.fee_growth_0,
+        &lower.fee_growth_outside_0,
+        &upper.fee_growth_outside_0,
+    );
+    let fee_growth_inside_1 = concentrated_math::fee_growth_inside(
+        state.tick,
+        position.tick_lower,
+        position.tick_upper,
+        &state.fee_growth_1,
+        &lower.fee_growth_outside_1,
+        &upper.fee_growth_outside_1,
+    );
+    (fee_growth_inside_0, fee_growth_inside_1)
+}
+
+/// tick of a pool, or a new tick if no position starts or ends at it
+fn get_or_new_tick(pool_id: u32, tick: i32, state: &ConcentratedPool) -> StableTick {
+    tick_map::get(pool_id, tick).unwrap_or_else(|| StableTick::new(pool_id, tick, state.tick, &state.fee_growth_0, &state.fee_growth_1))
+}
+
+/// add (or remove if add is false) liquidity_delta to a position
+/// fees earned by the position so far are added to its fees owed. the ticks of the range and
+/// the liquidity in range of the pool are updated. liquidity_delta of zero only accrues the fees
+pub fn update_position(pool: &mut StablePool, position: &mut StableLPPosition, liquidity_delta: &Nat, add: bool) -> Result<(), String> {
+    let pool_id = pool.pool_id;
+    let state = pool.concentrated.as_mut().ok_or("Pool is not a concentrated liquidity pool")?;
+    let mut lower = get_or_new_tick(pool_id, position.tick_lower, state);
+    let mut upper = get_or_new_tick(pool_id, position.tick_upper, state);
+
+    let (fee_growth_inside_0, fee_growth_inside_1) = fee_growth_inside(state, position, &lower, &upper);
+    position.fees_owed_0 = nat_add(
+        &position.fees_owed_0,
+        &concentrated_math::fees_earned(&position.liquidity, &fee_growth_inside_0, &position.fee_growth_inside_0_last),
+    );
+    position.fees_owed_1 = nat_add(
+        &position.fees_owed_1,
+        &concentrated_math::fees_earned(&position.liquidity, &fee_growth_inside_1, &position.fee_growth_inside_1_last),
+    );
+    position.fee_growth_inside_0_last = fee_growth_inside_0;
+    position.fee_growth_inside_1_last = fee_growth_inside_1;
+
+    let in_range = position.tick_lower <= state.tick && state.tick < position.tick_upper;
+    if add {
+        position.liquidity = nat_add(&position.liquidity, liquidity_delta);
+        lower.liquidity_lower = nat_add(&lower.liquidity_lower, liquidity_delta);
+        upper.liquidity_upper = nat_add(&upper.liquidity_upper, liquidity_delta);
+        if in_range {
+            state.liquidity = nat_add(&state.liquidity, liquidity_delta);
+        }
+    } else {
+        position.liquidity = nat_subtract(&position.liquidity, liquidity_delta).ok_or("Insufficient liquidity in position")?;
+        lower.liquidity_lower = nat_subtract(&lower.liquidity_lower, liquidity_delta).unwrap_or(nat_zero());
+        upper.liquidity_upper = nat_subtract(&upper.liquidity_upper, liquidity_delta).unwrap_or(nat_zero());
+        if in_range {
+            state.liquidity = nat_subtract(&state.liquidity, liquidity_delta).unwrap_or(nat_zero());
+        }
+    }
+
+    tick_map::update(&lower);
+    tick_map::update(&upper);
+    Ok(())
+}
+
+/// amounts of token_0 and token_1 of a position at the pool price in max_decimals precision, rounded down
+pub fn position_amounts(pool: &StablePool, position: &StableLPPosition) -> Option<(Nat, Nat)> {
+    let state = pool.concentrated.as_ref()?;
+    let sqrt_price_lower = concentrated_math::sqrt_price_at_tick(position.tick_lower)?;
+    let sqrt_price_upper = concentrated_math::sqrt_price_at_tick(position.tick_upper)?;
+    concentrated_math::amounts_for_liquidity(&state.sqrt_price, &sqrt_price_lower, &sqrt_price_upper, &position.liquidity, false)
+}
+
+/// uncollected fees of token_0 and token_1 of a position, including the fees earned since its last update
+pub fn position_fees(pool: &StablePool, position: &StableLPPosition) -> (Nat, Nat) {
+    let Some(state) = pool.concentrated.as_ref() else {
+        return (position.fees_owed_0.clone(), position.fees_owed_1.clone());
+    };
+    let lower = get_or_new_tick(pool.pool_id, position.tick_lower, state);
+    let upper = get_or_new_tick(pool.pool_id, position.tick_upper, state);
+    let (fee_growth_inside_0, fee_growth_inside_1) = fee_growth_inside(state, position, &lower, &upper);
+    (
+        nat_add(
+            &position.fees_owed_0,
+            &concentrated_math::fees_earned(&position.liquidity, &fee_growth_inside_0, &position.fee_growth_inside_0_last),
+        ),
+        nat_add(
+            &position.fees_owed_1,
+            &concentrated_math::fees_earned(&position.liquidity, &fee_growth_inside_1, &position.fee_growth_inside_1_last),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_concentrated_pool, insert_ic_token, insert_position};
+    use crate::stable_lp_token::lp_position_map;
+    use crate::stable_pool::pool_map;
+
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+    const LP_FEE: u128 = 1_000_000;
+
+    fn setup() -> (StableLPPosition, StableLPPosition, StableLPPosition) {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckBTC", 8);
+        init_kong_settings();
+        insert_concentrated_pool(1, 1, 2, 60);
+        let wide = insert_position(100, 1, -1_200, 1_200, LIQUIDITY);
+        let narrow = insert_position(101, 1, -60, 60, LIQUIDITY);
+        let above = insert_position(102, 1, 600, 1_200, LIQUIDITY);
+        (wide, narrow, above)
+    }
+
+    fn pool() -> StablePool {
+        pool_map::get_by_pool_id(1).unwrap()
+    }
+
+    fn position(position_id: u64) -> StableLPPosition {
+        lp_position_map::get_by_position_id(position_id).unwrap()
+    }
+
+    fn is_close(a: &Nat, b: &Nat, tolerance: u32) -> bool {
+        let diff = if a > b { nat_subtract(a, b) } else { nat_subtract(b, a) };
+        diff.is_some_and(|diff| diff <= tolerance)
+    }
+
+    // token_1 paid out from the price of 1 down to tick -60, where the narrow position ends
+    fn amount_out_to_tick_60() -> Nat {
+        let sqrt_price = concentrated_math::sqrt_price_at_tick(0).unwrap();
+        let sqrt_price_lower = concentrated_math::sqrt_price_at_tick(-60).unwrap();
+        concentrated_math::amounts_for_liquidity(&sqrt_price, &sqrt_price_lower, &sqrt_price, &Nat::from(2 * LIQUIDITY), false)
+            .unwrap()
+            .1
+    }
+
+    // swap paying token_0 for twice the token_1 down to tick -60, so half of it is received after crossing the tick
+    fn swap_across_tick_60() -> (Nat, Nat) {
+        let step_amount_out = amount_out_to_tick_60();
+        let amount_out = nat_multiply(&step_amount_out, &Nat::from(2_u32));
+        let mut pool = pool();
+        apply_swap(&mut pool, true, &amount_out, &Nat::from(LP_FEE)).unwrap();
+        pool_map::update(&pool);
+        (step_amount_out, amount_out)
+    }
+
+    #[test]
+    fn test_apply_swap_crossing_initialized_tick() {
+        setup();
+        assert_eq!(pool().concentrated.unwrap().liquidity, Nat::from(2 * LIQUIDITY));
+
+        let (step_amount_out, amount_out) = swap_across_tick_60();
+
+        // the narrow position is out of range after the crossing
+        let state = pool().concentrated.unwrap();
+        assert_eq!(state.liquidity, Nat::from(LIQUIDITY));
+        assert!((-1_200..-60).contains(&state.tick));
+        assert!(nat_is_zero(&state.fee_growth_0));
+        // fee growth outside of tick -60 is now the fee growth above it, from the step before the crossing
+        let tick = tick_map::get(1, -60).unwrap();
+        let step_fee = nat_divide(&nat_multiply(&Nat::from(LP_FEE), &step_amount_out), &amount_out).unwrap();
+        assert!(is_close(
+            &concentrated_math::fees_earned(&Nat::from(2 * LIQUIDITY), &tick.fee_growth_outside_1, &nat_zero()),
+            &step_fee,
+            2
+        ));
+        assert!(tick.fee_growth_outside_1 < state.fee_growth_1);
+        // ticks that were not crossed keep their fee growth outside
+        assert!(nat_is_zero(&tick_map::get(1, 60).unwrap().fee_growth_outside_1));
+        assert!(nat_is_zero(&tick_map::get(1, -1_200).unwrap().fee_growth_outside_1));
+
+        // crossing back up adds the liquidity back
+        let mut pool = pool();
+        apply_swap(&mut pool, false, &Nat::from(10_000_000_000_u64), &nat_zero()).unwrap();
+        let state = pool.concentrated.unwrap();
+        assert!(state.tick >= -60);
+        assert_eq!(state.liquidity, Nat::from(2 * LIQUIDITY));
+    }
+
+    #[test]
+    fn test_apply_swap_insufficient_liquidity_leaves_pool() {
+        setup();
+        let before = pool().concentrated.unwrap();
+
+        let mut pool = pool();
+        assert!(apply_swap(&mut pool, true, &Nat::from(1_000_000_000_000_000_u128), &Nat::from(LP_FEE)).is_err());
+
+        let state = pool.concentrated.unwrap();
+        assert_eq!(state.sqrt_price, before.sqrt_price);
+        assert_eq!(state.liquidity, before.liquidity);
+        assert!(nat_is_zero(&state.fee_growth_1));
+        assert!(nat_is_zero(&tick_map::get(1, -60).unwrap().fee_growth_outside_1));
+    }
+
+    #[test]
+    fn test_position_fees_accrue_inside_range() {
+        let (wide, narrow, above) = setup();
+
+        let (step_amount_out, amount_out) = swap_across_tick_60();
+
+        // both positions split the fee before the crossing, only the wide one earns after it
+        let pool = pool();
+        let step_fee = nat_divide(&nat_multiply(&Nat::from(LP_FEE), &step_amount_out), &amount_out).unwrap();
+        let (narrow_fees_0, narrow_fees_1) = position_fees(&pool, &position(narrow.position_id));
+        assert!(nat_is_zero(&narrow_fees_0));
+        assert!(is_close(&narrow_fees_1, &nat_divide(&step_fee, &Nat::from(2_u32)).unwrap(), 2));
+        let (_, wide_fees_1) = position_fees(&pool, &position(wide.position_id));
+        let wide_step_fee = nat_subtract(&Nat::from(LP_FEE), &nat_divide(&step_fee, &Nat::from(2_u32)).unwrap()).unwrap();
+        assert!(is_close(&wide_fees_1, &wide_step_fee, 2));
+        // never more than the LP fee
+        assert!(nat_add(&narrow_fees_1, &wide_fees_1) <= LP_FEE);
+        // the price never reached the range above
+        assert_eq!(position_fees(&pool, &position(above.position_id)), (nat_zero(), nat_zero()));
+    }
+
+    #[test]
+    fn test_update_position_remove_liquidity() {
+        let (_, narrow, _) = setup();
+        swap_across_tick_60();
+        let mut pool = pool();
+        let mut narrow = position(narrow.position_id);
+        let (_, fees_1) = position_fees(&pool, &narrow);
+
+        update_position(&mut pool, &mut narrow, &Nat::from(LIQUIDITY), false).unwrap();
+
+        // fees earned so far are owed to the position
+        assert!(nat_is_zero(&narrow.liquidity));
+        assert_eq!(narrow.fees_owed_1, fees_1);
+        assert_eq!(position_fees(&pool, &narrow), (nat_zero(), fees_1));
+        // the position was out of range, so the liquidity in range is unchanged. its ticks are no longer referenced
+        assert_eq!(pool.concentrated.as_ref().unwrap().liquidity, Nat::from(LIQUIDITY));
+        assert!(tick_map::get(1, -60).is_none());
+        assert!(tick_map::get(1, 60).is_none());
+        // no more liquidity than the position has can be removed
+        assert!(update_position(&mut pool, &mut narrow, &Nat::from(1_u32), false).is_err());
+    }
+}
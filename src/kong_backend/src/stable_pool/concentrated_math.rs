@@ -0,0 +1,620 @@
+//! Concentrated liquidity (Uniswap v3) for 2 token pools
+//!
+//! liquidity is provided in price ranges [tick_lower, tick_upper) where tick i is the price 1.0001^i
+//! within a range the pool is a constant product of the virtual reserves, L^2 = x * y
+//!
+//! prices are stored as sqrt(price) in Q64.96 fixed point, sqrt_price = sqrt(amount_1 / amount_0) * 2^96
+//! fee growth per unit of liquidity is stored in Q128.128 fixed point and wraps at 2^256
+//! all amounts must be in the same decimal precision (max_decimals of the 2 tokens)
+use candid::Nat;
+use num::{BigRational, BigUint, One};
+
+use crate::helpers::nat_helpers::{
+    nat_add, nat_divide, nat_divide_as_f64, nat_divide_ceil, nat_is_zero, nat_multiply, nat_sqrt, nat_subtract, nat_to_bigint,
+};
+
+pub const MIN_TICK: i32 = -887_272;
+pub const MAX_TICK: i32 = 887_272;
+
+// maximum number of steps (initialized ticks crossed + 1) of a swap
+const MAX_SWAP_STEPS: usize = 1_000;
+
+/// 2^96
+fn q96() -> Nat {
+    Nat::from(1_u128 << 96)
+}
+
+/// 2^128
+fn q128() -> Nat {
+    Nat(BigUint::one() << 128_u32)
+}
+
+/// 2^256
+fn q256() -> Nat {
+    Nat(BigUint::one() << 256_u32)
+}
+
+/// initialized tick of a pool with the liquidity of the positions starting and ending at the tick
+#[derive(Debug, Clone)]
+pub struct TickLiquidity {
+    pub tick: i32,
+    pub liquidity_lower: Nat, // liquidity of positions with tick_lower at the tick. added when the price crosses the tick upwards
+    pub liquidity_upper: Nat, // liquidity of positions with tick_upper at the tick. removed when the price crosses the tick upwards
+}
+
+/// part of a swap within a range of constant liquidity
+#[derive(Debug, Clone)]
+pub struct SwapStep {
+    pub liquidity: Nat,
+    pub amount_in: Nat,
+    pub amount_out: Nat,
+    pub crossed_tick: Option<i32>, // initialized tick crossed at the end of the step
+}
+
+#[derive(Debug, Clone)]
+pub struct SwapResult {
+    pub amount_in: Nat,
+    pub amount_out: Nat,
+    pub sqrt_price: Nat, // sqrt price after the swap
+    pub tick: i32,       // tick after the swap
+    pub liquidity: Nat,  // liquidity in range after the swap
+    pub steps: Vec<SwapStep>,
+}
+
+/// sqrt(1.0001^tick) in Q64.96
+pub fn sqrt_price_at_tick(tick: i32) -> Option<Nat> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    // 1.0001^(tick / 2) = e^(tick * ln(1.0001) / 2)
+    let sqrt_price = (tick as f64 * 0.0001_f64.ln_1p() / 2_f64).exp();
+    let sqrt_price_x96 = BigRational::from_float(sqrt_price)? * BigRational::from_integer(nat_to_bigint(&q96()));
+    Some(Nat(sqrt_price_x96.to_integer().to_biguint()?))
+}
+
+/// lowest and highest ticks that are multiples of tick_spacing
+pub fn full_range(tick_spacing: u16) -> (i32, i32) {
+    let tick_spacing = tick_spacing.max(1) as i32;
+    (MIN_TICK / tick_spacing * tick_spacing, MAX_TICK / tick_spacing * tick_spacing)
+}
+
+/// greatest tick with sqrt_price_at_tick(tick) <= sqrt_price
+pub fn tick_at_sqrt_price(sqrt_price: &Nat) -> Option<i32> {
+    let ratio = nat_divide_as_f64(sqrt_price, &q96())?;
+    if ratio <= 0_f64 {
+        return None;
+    }
+    let estimate = (2_f64 * ratio.ln() / 0.0001_f64.ln_1p()).floor();
+    let mut tick = estimate.clamp(MIN_TICK as f64, MAX_TICK as f64) as i32;
+    // correct the rounding of the estimate
+    while tick > MIN_TICK && sqrt_price_at_tick(tick)? > *sqrt_price {
+        tick -= 1;
+    }
+    while tick < MAX_TICK && sqrt_price_at_tick(tick + 1)? <= *sqrt_price {
+        tick += 1;
+    }
+    Some(tick)
+}
+
+/// sqrt price of a pool with amount_0 and amount_1 as reserves
+/// sqrt_price = sqrt(amount_1 * 2^192 / amount_0)
+pub fn sqrt_price_from_amounts(amount_0: &Nat, amount_1: &Nat) -> Option<Nat> {
+    if nat_is_zero(amount_0) || nat_is_zero(amount_1) {
+        return None;
+    }
+    let q192 = nat_multiply(&q96(), &q96());
+    Some(nat_sqrt(&nat_divide(&nat_multiply(amount_1, &q192), amount_0)?))
+}
+
+/// price of token_0 in token_1
+/// price = sqrt_price^2 / 2^192
+pub fn spot_price(sqrt_price: &Nat) -> Option<BigRational> {
+    if nat_is_zero(sqrt_price) {
+        return None;
+    }
+    let sqrt_price = nat_to_bigint(sqrt_price);
+    let q96 = nat_to_bigint(&q96());
+    Some(BigRational::new(&sqrt_price * &sqrt_price, &q96 * &q96))
+}
+
+/// amount of token_0 between 2 sqrt prices for liquidity
+/// amount_0 = L * 2^96 * (sqrt_price_upper - sqrt_price_lower) / (sqrt_price_upper * sqrt_price_lower)
+pub fn amount_0_delta(sqrt_price_a: &Nat, sqrt_price_b: &Nat, liquidity: &Nat, round_up: bool) -> Option<Nat> {
+    let (lower, upper) = if sqrt_price_a < sqrt_price_b {
+        (sqrt_price_a, sqrt_price_b)
+    } else {
+        (sqrt_price_b, sqrt_price_a)
+    };
+    let numerator = nat_multiply(&nat_multiply(liquidity, &q96()), &nat_subtract(upper, lower)?);
+    if round_up {
+        nat_divide_ceil(&nat_divide_ceil(&numerator, upper)?, lower)
+    } else {
+        nat_divide(&nat_divide(&numerator, upper)?, lower)
+    }
+}
+
+/// amount of token_1 between 2 sqrt prices for liquidity
+/// amount_1 = L * (sqrt_price_upper - sqrt_price_lower) / 2^96
+pub fn amount_1_delta(sqrt_price_a: &Nat, sqrt_price_b: &Nat, liquidity: &Nat, round_up: bool) -> Option<Nat> {
+    let (lower, upper) = if sqrt_price_a < sqrt_price_b {
+        (sqrt_price_a, sqrt_price_b)
+    } else {
+        (sqrt_price_b, sqrt_price_a)
+    };
+    let numerator = nat_multiply(liquidity, &nat_subtract(upper, lower)?);
+    if round_up {
+        nat_divide_ceil(&numerator, &q96())
+    } else {
+        nat_divide(&numerator, &q96())
+    }
+}
+
+/// sqrt price after adding amount_in to the pool. rounded so the price moves at least as far as amount_in
+fn next_sqrt_price_from_input(sqrt_price: &Nat, liquidity: &Nat, amount_in: &Nat, zero_for_one: bool) -> Option<Nat> {
+    if nat_is_zero(liquidity) {
+        return None;
+    }
+    if zero_for_one {
+        // sqrt_price' = L * 2^96 * sqrt_price / (L * 2^96 + amount_in * sqrt_price)
+        let numerator = nat_multiply(liquidity, &q96());
+        let denominator = nat_add(&numerator, &nat_multiply(amount_in, sqrt_price));
+        nat_divide_ceil(&nat_multiply(&numerator, sqrt_price), &denominator)
+    } else {
+        // sqrt_price' = sqrt_price + amount_in * 2^96 / L
+        Some(nat_add(sqrt_price, &nat_divide(&nat_multiply(amount_in, &q96()), liquidity)?))
+    }
+}
+
+/// sqrt price after taking amount_out from the pool. rounded so the price moves at least as far as amount_out
+fn next_sqrt_price_from_output(sqrt_price: &Nat, liquidity: &Nat, amount_out: &Nat, zero_for_one: bool) -> Option<Nat> {
+    if nat_is_zero(liquidity) {
+        return None;
+    }
+    if zero_for_one {
+        // sqrt_price' = sqrt_price - amount_out * 2^96 / L
+        let delta = nat_divide_ceil(&nat_multiply(amount_out, &q96()), liquidity)?;
+        if delta >= *sqrt_price {
+            return None;
+        }
+        nat_subtract(sqrt_price, &delta)
+    } else {
+        // sqrt_price' = L * 2^96 * sqrt_price / (L * 2^96 - amount_out * sqrt_price)
+        let numerator = nat_multiply(liquidity, &q96());
+        let denominator = nat_subtract(&numerator, &nat_multiply(amount_out, sqrt_price))?;
+        if nat_is_zero(&denominator) {
+            return None;
+        }
+        nat_divide_ceil(&nat_multiply(&numerator, sqrt_price), &denominator)
+    }
+}
+
+/// swap within a range of constant liquidity towards sqrt_price_target
+/// amount_remaining is the amount in for exact_in, otherwise the amount out
+/// returns (sqrt_price after the step, amount_in, amount_out). amount_in is rounded up, amount_out down
+fn swap_step(
+    sqrt_price: &Nat,
+    sqrt_price_target: &Nat,
+    liquidity: &Nat,
+    amount_remaining: &Nat,
+    zero_for_one: bool,
+    exact_in: bool,
+) -> Option<(Nat, Nat, Nat)> {
+    let sqrt_price_next = if exact_in {
+        let amount_in_max = if zero_for_one {
+            amount_0_delta(sqrt_price_target, sqrt_price, liquidity, true)?
+        } else {
+            amount_1_delta(sqrt_price, sqrt_price_target, liquidity, true)?
+        };
+        if *amount_remaining >= amount_in_max {
+            sqrt_price_target.clone()
+        } else {
+            next_sqrt_price_from_input(sqrt_price, liquidity, amount_remaining, zero_for_one)?
+        }
+    } else {
+        let amount_out_max = if zero_for_one {
+            amount_1_delta(sqrt_price_target, sqrt_price, liquidity, false)?
+        } else {
+            amount_0_delta(sqrt_price, sqrt_price_target, liquidity, false)?
+        };
+        if *amount_remaining >= amount_out_max {
+            sqrt_price_target.clone()
+        } else {
+            next_sqrt_price_from_output(sqrt_price, liquidity, amount_remaining, zero_for_one)?
+        }
+    };
+
+    let (amount_in, amount_out) = if zero_for_one {
+        (
+            amount_0_delta(&sqrt_price_next, sqrt_price, liquidity, true)?,
+            amount_1_delta(&sqrt_price_next, sqrt_price, liquidity, false)?,
+        )
+    } else {
+        (
+            amount_1_delta(sqrt_price, &sqrt_price_next, liquidity, true)?,
+            amount_0_delta(sqrt_price, &sqrt_price_next, liquidity, false)?,
+        )
+    };
+    // an exact input that ends within the range uses the remaining amount, the rounding stays in the pool
+    let amount_in = if exact_in && sqrt_price_next != *sqrt_price_target {
+        amount_remaining.clone()
+    } else {
+        amount_in
+    };
+    // never pay out more than asked for
+    let amount_out = if !exact_in && amount_out > *amount_remaining {
+        amount_remaining.clone()
+    } else {
+        amount_out
+    };
+
+    Some((sqrt_price_next, amount_in, amount_out))
+}
+
+/// swap through the initialized ticks of a pool
+///
+/// ticks - initialized ticks of the pool, lowest first
+/// zero_for_one - true if the swap pays token_0 and receives token_1
+/// amount - amount in for exact_in, otherwise the amount out
+///
+/// returns None if the pool does not have enough liquidity for the amount
+pub fn swap(
+    sqrt_price: &Nat,
+    tick: i32,
+    liquidity: &Nat,
+    ticks: &[TickLiquidity],
+    zero_for_one: bool,
+    amount: &Nat,
+    exact_in: bool,
+) -> Option<SwapResult> {
+    let mut sqrt_price = sqrt_price.clone();
+    let mut tick = tick;
+    let mut liquidity = liquidity.clone();
+    let mut amount_remaining = amount.clone();
+    let mut amount_in = Nat::from(0_u32);
+    let mut amount_out = Nat::from(0_u32);
+    let mut steps = Vec::new();
+
+    while !nat_is_zero(&amount_remaining) {
+        if steps.len() >= MAX_SWAP_STEPS {
+            return None;
+        }
+        // next initialized tick in the direction of the swap. moving down, the tick of the current price is crossed first
+        let next_tick = if zero_for_one {
+            ticks.iter().rev().find(|t| t.tick <= tick)
+        } else {
+            ticks.iter().find(|t| t.tick > tick)
+        };
+        let target_tick = next_tick.map_or(if zero_for_one { MIN_TICK } else { MAX_TICK }, |t| t.tick);
+        let sqrt_price_target = sqrt_price_at_tick(target_tick)?;
+
+        let (sqrt_price_next, step_amount_in, step_amount_out) = swap_step(
+            &sqrt_price,
+            &sqrt_price_target,
+            &liquidity,
+            &amount_remaining,
+            zero_for_one,
+            exact_in,
+        )?;
+        amount_remaining = nat_subtract(&amount_remaining, if exact_in { &step_amount_in } else { &step_amount_out })?;
+        amount_in = nat_add(&amount_in, &step_amount_in);
+        amount_out = nat_add(&amount_out, &step_amount_out);
+        sqrt_price = sqrt_price_next;
+
+        let reached_target = sqrt_price == sqrt_price_target;
+        steps.push(SwapStep {
+            liquidity: liquidity.clone(),
+            amount_in: step_amount_in,
+            amount_out: step_amount_out,
+            crossed_tick: next_tick.filter(|_| reached_target).map(|t| t.tick),
+        });
+
+        if !reached_target {
+            tick = tick_at_sqrt_price(&sqrt_price)?;
+            continue;
+        }
+        // no more liquidity in the direction of the swap
+        let next_tick = next_tick?;
+        // cross the tick and update the liquidity in range
+        if zero_for_one {
+            liquidity = nat_subtract(&nat_add(&liquidity, &next_tick.liquidity_upper), &next_tick.liquidity_lower)?;
+            tick = next_tick.tick - 1;
+        } else {
+            liquidity = nat_subtract(&nat_add(&liquidity, &next_tick.liquidity_lower), &next_tick.liquidity_upper)?;
+            tick = next_tick.tick;
+        }
+    }
+
+    Some(SwapResult {
+        amount_in,
+        amount_out,
+        sqrt_price,
+        tick,
+        liquidity,
+        steps,
+    })
+}
+
+/// liquidity of a position in a price range for amount_0 and amount_1, rounded down
+/// below the range only token_0 is used, above the range only token_1
+pub fn liquidity_for_amounts(
+    sqrt_price: &Nat,
+    sqrt_price_lower: &Nat,
+    sqrt_price_upper: &Nat,
+    amount_0: &Nat,
+    amount_1: &Nat,
+) -> Option<Nat> {
+    if sqrt_price_lower >= sqrt_price_upper {
+        return None;
+    }
+    // L = amount_0 * sqrt_price_a * sqrt_price_b / 2^96 / (sqrt_price_b - sqrt_price_a)
+    let liquidity_0 = |sqrt_price_a: &Nat| -> Option<Nat> {
+        let numerator = nat_divide(&nat_multiply(&nat_multiply(amount_0, sqrt_price_a), sqrt_price_upper), &q96())?;
+        nat_divide(&numerator, &nat_subtract(sqrt_price_upper, sqrt_price_a)?)
+    };
+    // L = amount_1 * 2^96 / (sqrt_price_b - sqrt_price_a)
+    let liquidity_1 =
+        |sqrt_price_b: &Nat| -> Option<Nat> { nat_divide(&nat_multiply(amount_1, &q96()), &nat_subtract(sqrt_price_b, sqrt_price_lower)?) };
+
+    if sqrt_price <= sqrt_price_lower {
+        liquidity_0(sqrt_price_lower)
+    } else if sqrt_price < sqrt_price_upper {
+        Some(std::cmp::min(liquidity_0(sqrt_price)?, liquidity_1(sqrt_price)?))
+    } else {
+        liquidity_1(sqrt_price_upper)
+    }
+}
+
+/// amounts of token_0 and token_1 of a position in a price range with liquidity
+/// round_up when the amounts are paid in, round down when paid out
+pub fn amounts_for_liquidity(
+    sqrt_price: &Nat,
+    sqrt_price_lower: &Nat,
+    sqrt_price_upper: &Nat,
+    liquidity: &Nat,
+    round_up: bool,
+) -> Option<(Nat, Nat)> {
+    if sqrt_price <= sqrt_price_lower {
+        Some((
+            amount_0_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?,
+            Nat::from(0_u32),
+        ))
+    } else if sqrt_price < sqrt_price_upper {
+        Some((
+            amount_0_delta(sqrt_price, sqrt_price_upper, liquidity, round_up)?,
+            amount_1_delta(sqrt_price_lower, sqrt_price, liquidity, round_up)?,
+        ))
+    } else {
+        Some((
+            Nat::from(0_u32),
+            amount_1_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?,
+        ))
+    }
+}
+
+/// fee growth per unit of liquidity for fee
+/// fee_growth = fee * 2^128 / L
+pub fn fee_growth(fee: &Nat, liquidity: &Nat) -> Nat {
+    nat_divide(&nat_multiply(fee, &q128()), liquidity).unwrap_or(Nat::from(0_u32))
+}
+
+/// a + b of fee growth, wrapping at 2^256
+pub fn fee_growth_add(a: &Nat, b: &Nat) -> Nat {
+    Nat(nat_add(a, b).0 % q256().0)
+}
+
+/// a - b of fee growth, wrapping at 2^256
+pub fn fee_growth_sub(a: &Nat, b: &Nat) -> Nat {
+    Nat((nat_add(a, &q256()).0 - &b.0) % q256().0)
+}
+
+/// fee growth inside a price range from the fee growth outside its ticks
+/// fee_growth_inside = fee_growth_global - fee_growth_below - fee_growth_above
+pub fn fee_growth_inside(
+    tick: i32,
+    tick_lower: i32,
+    tick_upper: i32,
+    fee_growth_global: &Nat,
+    fee_growth_outside_lower: &Nat,
+    fee_growth_outside_upper: &Nat,
+) -> Nat {
+    let fee_growth_below = if tick >= tick_lower {
+        fee_growth_outside_lower.clone()
+    } else {
+        fee_growth_sub(fee_growth_global, fee_growth_outside_lower)
+    };
+    let fee_growth_above = if tick < tick_upper {
+        fee_growth_outside_upper.clone()
+    } else {
+        fee_growth_sub(fee_growth_global, fee_growth_outside_upper)
+    };
+    fee_growth_sub(&fee_growth_sub(fee_growth_global, &fee_growth_below), &fee_growth_above)
+}
+
+/// fees earned by liquidity since fee_growth_inside_last
+/// fees = L * (fee_growth_inside - fee_growth_inside_last) / 2^128
+pub fn fees_earned(liquidity: &Nat, fee_growth_inside: &Nat, fee_growth_inside_last: &Nat) -> Nat {
+    let fee_growth = fee_growth_sub(fee_growth_inside, fee_growth_inside_last);
+    nat_divide(&nat_multiply(liquidity, &fee_growth), &q128()).unwrap_or(Nat::from(0_u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::nat_helpers::nat_zero;
+    use num_traits::ToPrimitive;
+
+    // 1,000,000 tokens with 8 decimals
+    const AMOUNT: u128 = 100_000_000_000_000;
+
+    fn is_close(a: &Nat, b: &Nat, tolerance: u32) -> bool {
+        let diff = if a > b { nat_subtract(a, b) } else { nat_subtract(b, a) };
+        diff.is_some_and(|diff| diff <= tolerance)
+    }
+
+    #[test]
+    fn test_sqrt_price_at_tick() {
+        assert_eq!(sqrt_price_at_tick(0).unwrap(), q96());
+        assert!(sqrt_price_at_tick(MAX_TICK + 1).is_none());
+        assert!(sqrt_price_at_tick(MIN_TICK).unwrap() > 0_u32);
+        // 1.0001^(10_000 / 2) ~ 1.6487
+        let sqrt_price = nat_divide_as_f64(&sqrt_price_at_tick(10_000).unwrap(), &q96()).unwrap();
+        assert!((sqrt_price - 1.0001_f64.powi(5_000)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_full_range() {
+        assert_eq!(full_range(1), (MIN_TICK, MAX_TICK));
+        assert_eq!(full_range(60), (-887_220, 887_220));
+        assert!(sqrt_price_at_tick(full_range(16_384).0).is_some());
+    }
+
+    #[test]
+    fn test_tick_at_sqrt_price() {
+        for tick in [MIN_TICK, -100_000, -1, 0, 1, 60, 123_456, MAX_TICK] {
+            let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+            assert_eq!(tick_at_sqrt_price(&sqrt_price), Some(tick));
+            if tick < MAX_TICK {
+                // just below the next tick
+                let next_sqrt_price = nat_subtract(&sqrt_price_at_tick(tick + 1).unwrap(), &Nat::from(1_u32)).unwrap();
+                assert_eq!(tick_at_sqrt_price(&next_sqrt_price), Some(tick));
+            }
+        }
+    }
+
+    #[test]
+    fn test_liquidity_and_amounts_round_trip() {
+        let sqrt_price = sqrt_price_from_amounts(&Nat::from(AMOUNT), &Nat::from(AMOUNT * 4)).unwrap();
+        assert_eq!(spot_price(&sqrt_price).unwrap().to_f64().unwrap().round(), 4.0);
+        let tick = tick_at_sqrt_price(&sqrt_price).unwrap();
+        let sqrt_price_lower = sqrt_price_at_tick(tick - 1_000).unwrap();
+        let sqrt_price_upper = sqrt_price_at_tick(tick + 1_000).unwrap();
+
+        let amount_0 = Nat::from(AMOUNT);
+        let amount_1 = Nat::from(AMOUNT * 4);
+        let liquidity = liquidity_for_amounts(&sqrt_price, &sqrt_price_lower, &sqrt_price_upper, &amount_0, &amount_1).unwrap();
+        let (pay_0, pay_1) = amounts_for_liquidity(&sqrt_price, &sqrt_price_lower, &sqrt_price_upper, &liquidity, true).unwrap();
+        // one of the amounts is fully used, the other is within the given amount
+        assert!(pay_0 <= nat_add(&amount_0, &Nat::from(1_u32)) && pay_1 <= nat_add(&amount_1, &Nat::from(1_u32)));
+        assert!(is_close(&pay_0, &amount_0, 1) || is_close(&pay_1, &amount_1, 1));
+        // paid out amounts are rounded down
+        let (receive_0, receive_1) = amounts_for_liquidity(&sqrt_price, &sqrt_price_lower, &sqrt_price_upper, &liquidity, false).unwrap();
+        assert!(receive_0 <= pay_0 && receive_1 <= pay_1);
+
+        // out of range positions only hold one token
+        let (amount_0, amount_1) = amounts_for_liquidity(
+            &sqrt_price_at_tick(tick - 2_000).unwrap(),
+            &sqrt_price_lower,
+            &sqrt_price_upper,
+            &liquidity,
+            false,
+        )
+        .unwrap();
+        assert!(amount_0 > 0_u32 && amount_1 == 0_u32);
+        let (amount_0, amount_1) = amounts_for_liquidity(
+            &sqrt_price_at_tick(tick + 2_000).unwrap(),
+            &sqrt_price_lower,
+            &sqrt_price_upper,
+            &liquidity,
+            false,
+        )
+        .unwrap();
+        assert!(amount_0 == 0_u32 && amount_1 > 0_u32);
+    }
+
+    #[test]
+    fn test_swap_within_range_is_constant_product() {
+        // liquidity over the full range behaves like a constant product pool
+        let reserve_0 = Nat::from(AMOUNT);
+        let reserve_1 = Nat::from(AMOUNT * 4);
+        let sqrt_price = sqrt_price_from_amounts(&reserve_0, &reserve_1).unwrap();
+        let tick = tick_at_sqrt_price(&sqrt_price).unwrap();
+        let liquidity = nat_sqrt(&nat_multiply(&reserve_0, &reserve_1));
+        let ticks = vec![
+            TickLiquidity {
+                tick: MIN_TICK,
+                liquidity_lower: liquidity.clone(),
+                liquidity_upper: nat_zero(),
+            },
+            TickLiquidity {
+                tick: MAX_TICK,
+                liquidity_lower: nat_zero(),
+                liquidity_upper: liquidity.clone(),
+            },
+        ];
+
+        let amount_in = Nat::from(AMOUNT / 10);
+        let result = swap(&sqrt_price, tick, &liquidity, &ticks, true, &amount_in, true).unwrap();
+        let constant_product = nat_divide(&nat_multiply(&amount_in, &reserve_1), &nat_add(&reserve_0, &amount_in)).unwrap();
+        assert!(result.amount_out <= constant_product);
+        assert!(is_close(&result.amount_out, &constant_product, 1_000));
+        assert_eq!(result.amount_in, amount_in);
+        assert_eq!(result.steps.len(), 1);
+        assert!(result.sqrt_price < sqrt_price);
+
+        // exact output is the inverse, rounded against the user
+        let exact_out = swap(&sqrt_price, tick, &liquidity, &ticks, true, &result.amount_out, false).unwrap();
+        assert_eq!(exact_out.amount_out, result.amount_out);
+        assert!(exact_out.amount_in <= amount_in);
+        assert!(is_close(&exact_out.amount_in, &amount_in, 1_000));
+    }
+
+    #[test]
+    fn test_swap_crosses_ticks() {
+        let sqrt_price = q96();
+        let liquidity = Nat::from(AMOUNT);
+        // position of AMOUNT liquidity from -100 to 100 and a wider one from -1_000 to 1_000
+        let ticks = vec![
+            TickLiquidity {
+                tick: -1_000,
+                liquidity_lower: liquidity.clone(),
+                liquidity_upper: nat_zero(),
+            },
+            TickLiquidity {
+                tick: -100,
+                liquidity_lower: liquidity.clone(),
+                liquidity_upper: nat_zero(),
+            },
+            TickLiquidity {
+                tick: 100,
+                liquidity_lower: nat_zero(),
+                liquidity_upper: liquidity.clone(),
+            },
+            TickLiquidity {
+                tick: 1_000,
+                liquidity_lower: nat_zero(),
+                liquidity_upper: liquidity.clone(),
+            },
+        ];
+        let total_liquidity = nat_add(&liquidity, &liquidity);
+
+        // token_1 of both positions between 0 and 100 plus some of the wider position
+        let amount_in = nat_add(
+            &amount_1_delta(&q96(), &sqrt_price_at_tick(100).unwrap(), &total_liquidity, true).unwrap(),
+            &Nat::from(AMOUNT / 1_000),
+        );
+        let result = swap(&sqrt_price, 0, &total_liquidity, &ticks, false, &amount_in, true).unwrap();
+        assert_eq!(result.steps.len(), 2);
+        assert_eq!(result.steps[0].crossed_tick, Some(100));
+        assert_eq!(result.steps[0].liquidity, total_liquidity);
+        assert_eq!(result.steps[1].liquidity, liquidity);
+        assert_eq!(result.liquidity, liquidity);
+        assert!(result.tick >= 100 && result.tick < 1_000);
+
+        // more than all the liquidity of the pool
+        assert!(swap(&sqrt_price, 0, &total_liquidity, &ticks, false, &Nat::from(AMOUNT * 1_000), true).is_none());
+    }
+
+    #[test]
+    fn test_fee_growth_inside_wraps() {
+        let fee_growth_global = fee_growth(&Nat::from(1_000_u32), &Nat::from(10_u32));
+        // all fees were earned while the price was in range
+        let inside = fee_growth_inside(0, -10, 10, &fee_growth_global, &nat_zero(), &nat_zero());
+        assert_eq!(fees_earned(&Nat::from(10_u32), &inside, &nat_zero()), 1_000_u32);
+        // upper tick initialized while the price was above it, then crossed down, so the inside starts "negative"
+        let inside_before = fee_growth_inside(0, -10, 10, &fee_growth_global, &fee_growth_global, &fee_growth_global);
+        assert!(inside_before > fee_growth_global);
+        let later = fee_growth_add(&fee_growth_global, &fee_growth(&Nat::from(500_u32), &Nat::from(10_u32)));
+        let inside_after = fee_growth_inside(0, -10, 10, &later, &fee_growth_global, &fee_growth_global);
+        assert_eq!(fees_earned(&Nat::from(10_u32), &inside_after, &inside_before), 500_u32);
+    }
+}
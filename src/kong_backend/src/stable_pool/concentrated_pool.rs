@@ -0,0 +1,30 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::nat_helpers::nat_zero;
+
+use super::concentrated_math;
+
+/// State of a concentrated liquidity pool. Liquidity is in the max_decimals precision of the 2 tokens,
+/// fee growth is in the precision of each token so fees can be paid out of lp_fee_0 and lp_fee_1
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct ConcentratedPool {
+    pub sqrt_price: Nat,   // sqrt(price) in Q64.96
+    pub tick: i32,         // greatest tick at or below the price
+    pub liquidity: Nat,    // liquidity of the positions in range of the price
+    pub fee_growth_0: Nat, // LP fees of token_0 per unit of liquidity earned by the pool, Q128.128
+    pub fee_growth_1: Nat, // LP fees of token_1 per unit of liquidity earned by the pool, Q128.128
+}
+
+impl ConcentratedPool {
+    pub fn new(sqrt_price: &Nat) -> Result<Self, String> {
+        let tick = concentrated_math::tick_at_sqrt_price(sqrt_price).ok_or("Invalid pool price")?;
+        Ok(Self {
+            sqrt_price: sqrt_price.clone(),
+            tick,
+            liquidity: nat_zero(),
+            fee_growth_0: nat_zero(),
+            fee_growth_1: nat_zero(),
+        })
+    }
+}
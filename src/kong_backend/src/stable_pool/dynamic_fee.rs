@@ -0,0 +1,108 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+/// Dynamic LP fee of a pool. The fee is scaled between min_fee_bps and max_fee_bps by a rolling
+/// accumulator of the pool's recent price moves. The accumulator decays by half every half_life_secs
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicFee {
+    pub min_fee_bps: u8,         // LP fee charged when the pool is calm
+    pub max_fee_bps: u8,         // LP fee charged when volatility_bps reaches max_volatility_bps
+    pub max_volatility_bps: u32, // accumulated price moves in basis points at which max_fee_bps is charged
+    pub half_life_secs: u64,     // half-life of the accumulator
+    pub volatility_bps: f64,     // accumulated price moves in basis points as of last_update_ts
+    pub last_update_ts: u64,     // timestamp of the last price move in nanoseconds
+}
+
+impl DynamicFee {
+    pub fn new(min_fee_bps: u8, max_fee_bps: u8, max_volatility_bps: u32, half_life_secs: u64) -> Self {
+        Self {
+            min_fee_bps,
+            max_fee_bps,
+            max_volatility_bps,
+            half_life_secs,
+            volatility_bps: 0_f64,
+            last_update_ts: 0,
+        }
+    }
+
+    /// accumulated price moves decayed to ts
+    pub fn volatility_at(&self, ts: u64) -> f64 {
+        if self.half_life_secs == 0 {
+            return 0_f64;
+        }
+        let elapsed_secs = ts.saturating_sub(self.last_update_ts) as f64 / 1_000_000_000_f64;
+        self.volatility_bps * 0.5_f64.powf(elapsed_secs / self.half_life_secs as f64)
+    }
+
+    /// LP fee in basis points at ts
+    /// fee_bps = min_fee_bps + (max_fee_bps - min_fee_bps) * min(volatility_bps / max_volatility_bps, 1)
+    pub fn fee_bps_at(&self, ts: u64) -> u8 {
+        if self.max_fee_bps <= self.min_fee_bps || self.max_volatility_bps == 0 {
+            return self.min_fee_bps;
+        }
+        let ratio = (self.volatility_at(ts) / self.max_volatility_bps as f64).min(1_f64);
+        let fee_range = (self.max_fee_bps - self.min_fee_bps) as f64;
+        self.min_fee_bps + (fee_range * ratio).round() as u8
+    }
+
+    /// add a price move to the accumulator
+    /// old_price and new_price are the pool prices before and after a swap
+    pub fn add_price_move(&mut self, old_price: f64, new_price: f64, ts: u64) {
+        let price_move_bps = if old_price > 0_f64 {
+            ((new_price - old_price) / old_price).abs() * 10_000_f64
+        } else {
+            0_f64
+        };
+        self.volatility_bps = self.volatility_at(ts) + price_move_bps;
+        self.last_update_ts = ts;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECS: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_fee_bps_at_calm_and_volatile() {
+        let mut fee = DynamicFee::new(10, 100, 200, 600);
+        assert_eq!(fee.fee_bps_at(0), 10);
+
+        fee.add_price_move(1.0, 1.01, 0); // 100 bps move
+        assert_eq!(fee.fee_bps_at(0), 55);
+
+        fee.add_price_move(1.01, 1.0201, 0); // another 100 bps move
+        assert_eq!(fee.fee_bps_at(0), 100);
+
+        // capped at max_fee_bps
+        fee.add_price_move(1.0, 2.0, 0);
+        assert_eq!(fee.fee_bps_at(0), 100);
+    }
+
+    #[test]
+    fn test_volatility_decays() {
+        let mut fee = DynamicFee::new(10, 100, 200, 600);
+        fee.add_price_move(1.0, 0.98, 100 * SECS); // 200 bps move down
+        assert!((fee.volatility_at(100 * SECS) - 200.0).abs() < 1e-6);
+        assert!((fee.volatility_at(700 * SECS) - 100.0).abs() < 1e-6);
+        assert!((fee.volatility_at(1_300 * SECS) - 50.0).abs() < 1e-6);
+        assert_eq!(fee.fee_bps_at(700 * SECS), 55);
+
+        // a new move adds to the decayed accumulator
+        fee.add_price_move(1.0, 1.005, 700 * SECS);
+        assert!((fee.volatility_at(700 * SECS) - 150.0).abs() < 1e-6);
+        assert_eq!(fee.last_update_ts, 700 * SECS);
+    }
+
+    #[test]
+    fn test_fee_bps_at_invalid_range() {
+        let mut fee = DynamicFee::new(30, 30, 200, 600);
+        fee.add_price_move(1.0, 2.0, 0);
+        assert_eq!(fee.fee_bps_at(0), 30);
+
+        let mut fee = DynamicFee::new(30, 100, 0, 600);
+        fee.add_price_move(1.0, 2.0, 0);
+        assert_eq!(fee.fee_bps_at(0), 30);
+    }
+}
@@ -1,4 +1,16 @@
 pub mod check_token_balance;
+pub mod concentrated_liquidity;
+pub mod concentrated_math;
+pub mod concentrated_pool;
+pub mod dynamic_fee;
+pub mod pool_fee_change_map;
+pub mod pool_lock;
 pub mod pool_map;
+pub mod pool_type;
 #[allow(clippy::module_inception)]
 pub mod stable_pool;
+pub mod stable_pool_fee_change;
+pub mod stable_swap_math;
+pub mod stable_tick;
+pub mod tick_map;
+pub mod weighted_math;
@@ -0,0 +1,28 @@
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::POOL_FEE_CHANGE_MAP;
+
+use super::stable_pool_fee_change::{StablePoolFeeChange, StablePoolFeeChangeId};
+
+/// get all fee changes of a pool, latest first
+pub fn get_by_pool_id(pool_id: u32) -> Vec<StablePoolFeeChange> {
+    POOL_FEE_CHANGE_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .rev()
+            .filter_map(|(_, v)| if v.pool_id == pool_id { Some(v) } else { None })
+            .collect()
+    })
+}
+
+pub fn insert(fee_change: &StablePoolFeeChange) -> u64 {
+    POOL_FEE_CHANGE_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        let fee_change_id = kong_settings_map::inc_pool_fee_change_map_idx();
+        let insert_fee_change = StablePoolFeeChange {
+            fee_change_id,
+            ..fee_change.clone()
+        };
+        map.insert(StablePoolFeeChangeId(fee_change_id), insert_fee_change);
+        fee_change_id
+    })
+}
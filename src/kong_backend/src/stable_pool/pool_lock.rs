@@ -0,0 +1,56 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+thread_local! {
+    // pools with a flash swap in progress. kept in heap memory so an upgrade releases them
+    static LOCKED_POOLS: RefCell<BTreeSet<u32>> = RefCell::default();
+}
+
+/// lock of a pool for the duration of a flash swap. swaps and liquidity changes of the pool fail until it is dropped
+/// it is dropped when the flash swap returns, or by ic-cdk's cleanup if the flash swap traps after an inter-canister call
+pub struct PoolLock {
+    pool_id: u32,
+}
+
+impl PoolLock {
+    pub fn new(pool_id: u32) -> Result<Self, String> {
+        LOCKED_POOLS.with(|m| {
+            if !m.borrow_mut().insert(pool_id) {
+                Err(format!("Pool #{} is locked by a flash swap", pool_id))?
+            }
+            Ok(PoolLock { pool_id })
+        })
+    }
+}
+
+impl Drop for PoolLock {
+    fn drop(&mut self) {
+        LOCKED_POOLS.with(|m| m.borrow_mut().remove(&self.pool_id));
+    }
+}
+
+/// make sure a pool is not locked by a flash swap before changing its balances
+pub fn check_unlocked(pool_id: u32) -> Result<(), String> {
+    if LOCKED_POOLS.with(|m| m.borrow().contains(&pool_id)) {
+        Err(format!("Pool #{} is locked by a flash swap", pool_id))?
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_lock() {
+        assert!(check_unlocked(1).is_ok());
+        {
+            let _lock = PoolLock::new(1).unwrap();
+            assert!(check_unlocked(1).is_err());
+            assert!(check_unlocked(2).is_ok());
+            assert!(PoolLock::new(1).is_err());
+        }
+        assert!(check_unlocked(1).is_ok());
+        assert!(PoolLock::new(1).is_ok());
+    }
+}
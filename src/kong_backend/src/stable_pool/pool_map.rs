@@ -8,6 +8,9 @@ use crate::stable_token::stable_token::StableToken;
 use crate::stable_token::token::Token;
 use crate::stable_token::token_map;
 
+// highest LP fee of a pool, 1%
+pub const MAX_LP_FEE_BPS: u8 = 100;
+
 fn symbol_with_chain(symbol: &str) -> Result<String, String> {
     let mut symbols = symbol.split('_');
     let symbol_0 = symbols.next().ok_or_else(|| format!("Invalid symbol {}", symbol))?;
@@ -66,6 +69,7 @@ pub fn get_by_token_wildcard(token: &str) -> Vec<StablePool> {
 }
 
 // token can be in the format of Symbol_Symbol, Chain.Symbol_Chain.Symbol, Address_Address, or Chain.Address_Chain.Address
+// or the symbol or address of the pool's LP token which is unique for pools at different fee tiers
 pub fn get_by_token(token: &str) -> Result<StablePool, String> {
     if let Ok(pool) = get_by_symbol(token) {
         return Ok(pool);
@@ -73,6 +77,11 @@ pub fn get_by_token(token: &str) -> Result<StablePool, String> {
     if let Ok(pool) = get_by_address(token) {
         return Ok(pool);
     }
+    if let Ok(StableToken::LP(lp_token)) = token_map::get_by_token(token) {
+        if let Some(pool) = get_by_lp_token_id(lp_token.token_id) {
+            return Ok(pool);
+        }
+    }
     Err(format!("Pool {} not found", token))
 }
 
@@ -108,10 +117,14 @@ fn get_by_address(address: &str) -> Result<StablePool, String> {
         .ok_or_else(|| format!("Pool {} not found", address_with_chain))
 }
 
-pub fn get_by_token_ids(token_id_0: u32, token_id_1: u32) -> Option<StablePool> {
+/// get the pool of a token pair
+/// a token pair can have several pools at different fee tiers. lp_fee_bps selects the live pool currently at the
+/// fee tier and if not specified, the default pool of the pair (the first pool created) is returned
+pub fn get_by_token_ids(token_id_0: u32, token_id_1: u32, lp_fee_bps: Option<u8>) -> Option<StablePool> {
     POOL_MAP.with(|m| {
         m.borrow().iter().find_map(|(_, v)| {
-            if v.token_id_0 == token_id_0 && v.token_id_1 == token_id_1 {
+            let is_fee_tier = lp_fee_bps.is_none_or(|lp_fee_bps| !v.is_removed && v.lp_fee_bps == lp_fee_bps);
+            if v.token_id_0 == token_id_0 && v.token_id_1 == token_id_1 && is_fee_tier {
                 return Some(v);
             }
             None
@@ -119,10 +132,28 @@ pub fn get_by_token_ids(token_id_0: u32, token_id_1: u32) -> Option<StablePool>
     })
 }
 
-pub fn get_by_tokens(token_0: &str, token_1: &str) -> Result<StablePool, String> {
+/// get all pools of a token pair, one for each fee tier
+pub fn get_all_by_token_ids(token_id_0: u32, token_id_1: u32) -> Vec<StablePool> {
+    POOL_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .filter_map(|(_, v)| {
+                if v.token_id_0 == token_id_0 && v.token_id_1 == token_id_1 {
+                    return Some(v);
+                }
+                None
+            })
+            .collect()
+    })
+}
+
+pub fn get_by_tokens(token_0: &str, token_1: &str, lp_fee_bps: Option<u8>) -> Result<StablePool, String> {
     let token_0: StableToken = token_map::get_by_token(token_0)?;
     let token_1 = token_map::get_by_token(token_1)?;
-    get_by_token_ids(token_0.token_id(), token_1.token_id()).ok_or_else(|| format!("Pool {} not found", symbol(&token_0, &token_1)))
+    get_by_token_ids(token_0.token_id(), token_1.token_id(), lp_fee_bps).ok_or_else(|| match lp_fee_bps {
+        Some(lp_fee_bps) => format!("Pool {} with LP fee {} bps not found", symbol(&token_0, &token_1), lp_fee_bps),
+        None => format!("Pool {} not found", symbol(&token_0, &token_1)),
+    })
 }
 
 /// Get pool by LP token's id.
@@ -144,6 +175,11 @@ pub fn get() -> Vec<StablePool> {
     })
 }
 
+/// get all pools including removed pools
+pub fn get_with_removed() -> Vec<StablePool> {
+    POOL_MAP.with(|m| m.borrow().iter().map(|(_, v)| v).collect())
+}
+
 /// check if pool exists
 pub fn exists(token_0: &StableToken, token_1: &StableToken) -> bool {
     POOL_MAP.with(|m| {
@@ -154,9 +190,40 @@ pub fn exists(token_0: &StableToken, token_1: &StableToken) -> bool {
     })
 }
 
+/// check if a live pool exists at the fee tier
+pub fn exists_fee_tier(token_0: &StableToken, token_1: &StableToken, lp_fee_bps: u8) -> bool {
+    exists_fee_tier_by_token_ids(token_0.token_id(), token_1.token_id(), lp_fee_bps)
+}
+
+fn exists_fee_tier_by_token_ids(token_id_0: u32, token_id_1: u32, lp_fee_bps: u8) -> bool {
+    POOL_MAP.with(|m| {
+        m.borrow().iter().any(|(_, v)| {
+            !v.is_removed
+                && v.lp_fee_bps == lp_fee_bps
+                && (v.token_id_0 == token_id_0 && v.token_id_1 == token_id_1 || v.token_id_0 == token_id_1 && v.token_id_1 == token_id_0)
+        })
+    })
+}
+
+/// check the LP and Kong fees of a pool
+pub fn check_fees(lp_fee_bps: u8, kong_fee_bps: u8) -> Result<(), String> {
+    if lp_fee_bps > MAX_LP_FEE_BPS {
+        Err(format!("LP fee cannot be more than {} bps", MAX_LP_FEE_BPS))?
+    }
+    if lp_fee_bps < kong_fee_bps {
+        Err(format!("LP fee cannot be less than Kong fee of {}", kong_fee_bps))?
+    }
+    Ok(())
+}
+
+/// pool_id the next inserted pool will get
+pub fn next_pool_id() -> u32 {
+    kong_settings_map::get().pool_map_idx + 1
+}
+
 pub fn insert(pool: &StablePool) -> Result<u32, String> {
-    if exists(&pool.token_0(), &pool.token_1()) {
-        Err(format!("Pool {} already exists", pool.symbol()))?
+    if exists_fee_tier(&pool.token_0(), &pool.token_1(), pool.lp_fee_bps) {
+        Err(format!("Pool {} with LP fee {} bps already exists", pool.symbol(), pool.lp_fee_bps))?
     }
 
     let insert_pool = POOL_MAP.with(|m| {
@@ -193,6 +260,10 @@ pub fn remove(pool_id: u32) -> Result<(), String> {
 
 pub fn unremove(pool_id: u32) -> Result<(), String> {
     let pool = get_by_pool_id(pool_id).ok_or_else(|| format!("Pool #{} not found", pool_id))?;
+    // the fee tier may have been taken by another pool of the token pair
+    if exists_fee_tier_by_token_ids(pool.token_id_0, pool.token_id_1, pool.lp_fee_bps) {
+        Err(format!("Pool {} with LP fee {} bps already exists", pool.symbol(), pool.lp_fee_bps))?
+    }
 
     // set is_removed to false to unremove pool
     update(&StablePool { is_removed: false, ..pool });
@@ -217,14 +288,13 @@ fn archive_to_kong_data(pool: &StablePool) -> Result<(), String> {
         Err(e) => return Err(format!("Failed to serialize pool_id #{}. {}", pool_id, e)),
     };
 
-            ic_cdk::futures::spawn(async move {
+    ic_cdk::futures::spawn(async move {
         let kong_data = kong_settings_map::get().kong_data;
         match ic_cdk::call::Call::unbounded_wait(kong_data, "update_pool")
             .with_arg(pool_json)
             .await
             .map_err(|e| format!("{:?}", e))
-            .and_then(|response| response.candid::<Result<String, String>>()
-            .map_err(|e| format!("{:?}", e)))
+            .and_then(|response| response.candid::<Result<String, String>>().map_err(|e| format!("{:?}", e)))
         {
             Ok(_) => (),
             Err(e) => ICNetwork::error_log(&format!("Failed to archive pool_id #{}. {}", pool_id, e)),
@@ -233,3 +303,70 @@ fn archive_to_kong_data(pool: &StablePool) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{ic_token, insert_pool};
+    use crate::stable_token::lp_token::LPToken;
+
+    const CKUSDT: u32 = 1;
+    const CKBTC: u32 = 2;
+
+    fn set_lp_fee_bps(pool_id: u32, lp_fee_bps: u8) {
+        let pool = get_by_pool_id(pool_id).unwrap();
+        POOL_MAP.with(|m| m.borrow_mut().insert(StablePoolId(pool_id), StablePool { lp_fee_bps, ..pool }));
+    }
+
+    #[test]
+    fn test_get_by_token_ids_fee_tier_after_fee_change() {
+        insert_pool(1, CKBTC, 1_000, CKUSDT, 1_000);
+        insert_pool(2, CKBTC, 1_000, CKUSDT, 1_000);
+        set_lp_fee_bps(2, 5);
+        assert_eq!(get_by_token_ids(CKBTC, CKUSDT, Some(5)).unwrap().pool_id, 2);
+        // the fee tiers of the pools are swapped
+        set_lp_fee_bps(1, 5);
+        set_lp_fee_bps(2, 30);
+        assert_eq!(get_by_token_ids(CKBTC, CKUSDT, Some(5)).unwrap().pool_id, 1);
+        assert_eq!(get_by_token_ids(CKBTC, CKUSDT, Some(30)).unwrap().pool_id, 2);
+        assert_eq!(get_by_token_ids(CKBTC, CKUSDT, None).unwrap().pool_id, 1);
+        assert!(get_by_token_ids(CKBTC, CKUSDT, Some(100)).is_none());
+    }
+
+    #[test]
+    fn test_get_by_token_ids_fee_tier_skips_removed_pool() {
+        let removed_pool = insert_pool(1, CKBTC, 1_000, CKUSDT, 1_000);
+        POOL_MAP.with(|m| {
+            m.borrow_mut().insert(
+                StablePoolId(1),
+                StablePool {
+                    is_removed: true,
+                    ..removed_pool
+                },
+            )
+        });
+        assert!(!exists_fee_tier_by_token_ids(CKBTC, CKUSDT, 30));
+        insert_pool(2, CKBTC, 1_000, CKUSDT, 1_000);
+        assert!(exists_fee_tier_by_token_ids(CKUSDT, CKBTC, 30));
+        assert_eq!(get_by_token_ids(CKBTC, CKUSDT, Some(30)).unwrap().pool_id, 2);
+    }
+
+    #[test]
+    fn test_additional_pool_lp_token_is_keyed_on_pool_id() {
+        let (ckbtc, ckusdt) = (ic_token(CKBTC, "ckBTC", 8), ic_token(CKUSDT, "ckUSDT", 6));
+        let lp_token = LPToken::new_additional_pool(&ckbtc, &ckusdt, 42);
+        assert_eq!(lp_token.symbol, "ckBTC_ckUSDT_42");
+        assert_eq!(lp_token.address, "2_1_42");
+        assert_eq!(LPToken::new(&ckbtc, &ckusdt).address, "2_1");
+    }
+
+    #[test]
+    fn test_check_fees() {
+        assert!(check_fees(30, 8).is_ok());
+        assert!(check_fees(MAX_LP_FEE_BPS, 8).is_ok());
+        assert!(check_fees(MAX_LP_FEE_BPS + 1, 8).is_err());
+        assert!(check_fees(u8::MAX, 8).is_err());
+        assert!(check_fees(5, 8).is_err());
+    }
+}
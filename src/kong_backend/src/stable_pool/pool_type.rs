@@ -0,0 +1,60 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_AMP: u64 = 10_000;
+pub const MIN_WEIGHT: u8 = 2; // minimum weight of a token in percent
+pub const MAX_TICK_SPACING: u16 = 16_384;
+
+/// Invariant used to price swaps of a pool
+#[derive(CandidType, Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolType {
+    #[default]
+    ConstantProduct, // x * y = k
+    StableSwap {
+        amp: u64, // amplification coefficient. higher values keep the price closer to 1:1
+    },
+    Weighted {
+        weight_0: u8, // weight of token_0 in percent. weight of token_1 is 100 - weight_0
+    },
+    Concentrated {
+        tick_spacing: u16, // positions can only start and end at multiples of tick_spacing
+    },
+}
+
+impl PoolType {
+    /// pool type of a new pool with weight_0 percent of the value in token_0
+    pub fn from_weight(weight_0: Option<u8>) -> Result<Self, String> {
+        match weight_0 {
+            None | Some(50) => Ok(PoolType::ConstantProduct),
+            Some(weight_0) if (MIN_WEIGHT..=100 - MIN_WEIGHT).contains(&weight_0) => Ok(PoolType::Weighted { weight_0 }),
+            Some(_) => Err(format!("Weight must be between {} and {}", MIN_WEIGHT, 100 - MIN_WEIGHT)),
+        }
+    }
+
+    /// pool type of a new concentrated liquidity pool
+    pub fn from_tick_spacing(tick_spacing: u16) -> Result<Self, String> {
+        if tick_spacing == 0 || tick_spacing > MAX_TICK_SPACING {
+            Err(format!("Tick spacing must be between 1 and {}", MAX_TICK_SPACING))?
+        }
+        Ok(PoolType::Concentrated { tick_spacing })
+    }
+
+    /// weights of token_0 and token_1 in percent. 50/50 for all but weighted pools
+    pub fn weights(&self) -> (u8, u8) {
+        match self {
+            PoolType::Weighted { weight_0 } => (*weight_0, 100 - weight_0),
+            PoolType::ConstantProduct | PoolType::StableSwap { .. } | PoolType::Concentrated { .. } => (50, 50),
+        }
+    }
+}
+
+impl std::fmt::Display for PoolType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PoolType::ConstantProduct => write!(f, "ConstantProduct"),
+            PoolType::StableSwap { amp } => write!(f, "StableSwap (A={})", amp),
+            PoolType::Weighted { weight_0 } => write!(f, "Weighted ({}/{})", weight_0, 100 - weight_0),
+            PoolType::Concentrated { tick_spacing } => write!(f, "Concentrated (tick spacing {})", tick_spacing),
+        }
+    }
+}
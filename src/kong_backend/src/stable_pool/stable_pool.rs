@@ -5,10 +5,16 @@ use serde::{Deserialize, Serialize};
 
 use crate::helpers::math_helpers::price_rounded;
 use crate::helpers::nat_helpers::{nat_add, nat_is_zero, nat_to_bigint, nat_to_decimal_precision, nat_zero};
+use crate::ic::network::ICNetwork;
 use crate::stable_token::stable_token::StableToken;
 use crate::stable_token::token::Token;
 use crate::stable_token::token_map;
 
+use super::concentrated_pool::ConcentratedPool;
+use super::dynamic_fee::DynamicFee;
+use super::pool_type::PoolType;
+use super::{concentrated_math, stable_swap_math, weighted_math};
+
 #[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct StablePoolId(pub u32);
 
@@ -40,6 +46,20 @@ pub struct StablePool {
     pub lp_token_id: u32, // token id of the LP token
     #[serde(default = "false_bool")]
     pub is_removed: bool,
+    #[serde(default)]
+    pub dynamic_fee: Option<DynamicFee>, // if set, LP fee is scaled by the pool's volatility
+    #[serde(default)]
+    pub pool_type: PoolType,
+    #[serde(default)]
+    pub concentrated: Option<ConcentratedPool>, // price and liquidity of a concentrated liquidity pool
+    #[serde(default)]
+    pub swept_kong_fee_0: Nat, // total of Kong's fee swept to the treasury
+    #[serde(default)]
+    pub swept_kong_fee_1: Nat,
+    #[serde(default)]
+    pub lp_fee_per_lp_0: Nat, // LP fee in token_0 earned per LP token since the pool was created. scaled by LP_FEE_PER_LP_SCALE
+    #[serde(default)]
+    pub lp_fee_per_lp_1: Nat,
 }
 
 fn false_bool() -> bool {
@@ -62,6 +82,13 @@ impl StablePool {
             kong_fee_bps,
             lp_token_id,
             is_removed: false,
+            dynamic_fee: None,
+            pool_type: PoolType::ConstantProduct,
+            concentrated: None,
+            swept_kong_fee_0: nat_zero(),
+            swept_kong_fee_1: nat_zero(),
+            lp_fee_per_lp_0: nat_zero(),
+            lp_fee_per_lp_1: nat_zero(),
         }
     }
 
@@ -131,15 +158,35 @@ impl StablePool {
         let token_0 = self.token_0();
         let token_1 = self.token_1();
         let max_decimals = std::cmp::max(token_0.decimals(), token_1.decimals());
-        let reserve_0 = nat_to_bigint(&nat_to_decimal_precision(&reserve_0, token_0.decimals(), max_decimals));
-        let reserve_1 = nat_to_bigint(&nat_to_decimal_precision(&reserve_1, token_1.decimals(), max_decimals));
-
-        Some(BigRational::new(reserve_1, reserve_0))
+        let reserve_0 = nat_to_decimal_precision(&reserve_0, token_0.decimals(), max_decimals);
+        let reserve_1 = nat_to_decimal_precision(&reserve_1, token_1.decimals(), max_decimals);
+
+        match self.pool_type {
+            PoolType::ConstantProduct => Some(BigRational::new(nat_to_bigint(&reserve_1), nat_to_bigint(&reserve_0))),
+            PoolType::StableSwap { amp } => stable_swap_math::spot_price(&reserve_0, &reserve_1, amp),
+            PoolType::Weighted { weight_0 } => weighted_math::spot_price(&reserve_0, &reserve_1, weight_0, 100 - weight_0),
+            // reserves of a concentrated liquidity pool are spread over price ranges, the price is kept in the pool state
+            PoolType::Concentrated { .. } => concentrated_math::spot_price(&self.concentrated.as_ref()?.sqrt_price),
+        }
     }
 
     pub fn get_price_as_f64(&self) -> Option<f64> {
         price_rounded(&self.get_price()?)
     }
+
+    /// LP fee in basis points currently charged by the pool
+    /// lp_fee_bps, or the fee scaled by the recent volatility if dynamic fees are enabled
+    pub fn live_lp_fee_bps(&self) -> u8 {
+        match &self.dynamic_fee {
+            Some(dynamic_fee) => dynamic_fee.fee_bps_at(ICNetwork::get_time()),
+            None => self.lp_fee_bps,
+        }
+    }
+
+    /// concentrated liquidity pools have positions instead of LP tokens
+    pub fn is_concentrated(&self) -> bool {
+        matches!(self.pool_type, PoolType::Concentrated { .. })
+    }
 }
 
 impl Storable for StablePool {
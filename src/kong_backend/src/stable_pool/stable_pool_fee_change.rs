@@ -0,0 +1,47 @@
+use candid::CandidType;
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StablePoolFeeChangeId(pub u64);
+
+impl Storable for StablePoolFeeChangeId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StablePoolFeeChangeId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StablePoolFeeChangeId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Audit record of a change of a pool's fees
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StablePoolFeeChange {
+    pub fee_change_id: u64,
+    pub pool_id: u32,
+    pub old_lp_fee_bps: u8,
+    pub old_kong_fee_bps: u8,
+    pub lp_fee_bps: u8,
+    pub kong_fee_bps: u8,
+    #[serde(default)]
+    pub old_dynamic_fee_bps: Option<(u8, u8)>, // (min_fee_bps, max_fee_bps) if dynamic fees were enabled
+    #[serde(default)]
+    pub dynamic_fee_bps: Option<(u8, u8)>, // (min_fee_bps, max_fee_bps) if dynamic fees are enabled
+    pub principal_id: String, // principal of the caller that changed the fees
+    pub ts: u64,
+}
+
+impl Storable for StablePoolFeeChange {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StablePoolFeeChange").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StablePoolFeeChange")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
@@ -0,0 +1,220 @@
+//! StableSwap (Curve) invariant for 2 token pools
+//!
+//! A * n^n * (x + y) + D = A * D * n^n + D^(n+1) / (n^n * x * y), with n = 2
+//!
+//! all amounts must be in the same decimal precision (max_decimals of the 2 tokens)
+use candid::Nat;
+use num::BigRational;
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply, nat_subtract, nat_to_bigint, nat_zero};
+
+const MAX_ITERATIONS: usize = 255;
+
+/// A * n^n
+fn ann(amp: u64) -> Nat {
+    Nat::from(amp) * 4_u32
+}
+
+/// difference of 2 iterations is within 1
+fn has_converged(a: &Nat, b: &Nat) -> bool {
+    let diff = if a > b { nat_subtract(a, b) } else { nat_subtract(b, a) };
+    diff.is_some_and(|diff| diff <= 1_u32)
+}
+
+/// invariant D of the pool from the reserves, solved with Newton's method
+/// D = (Ann * S + D_P * n) * D / ((Ann - 1) * D + (n + 1) * D_P), where D_P = D^(n+1) / (n^n * x * y)
+pub fn get_d(reserve_0: &Nat, reserve_1: &Nat, amp: u64) -> Option<Nat> {
+    if nat_is_zero(reserve_0) || nat_is_zero(reserve_1) || amp == 0 {
+        return None;
+    }
+    let ann = ann(amp);
+    let sum = nat_add(reserve_0, reserve_1);
+    let mut d = sum.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let d_p = nat_divide(&nat_multiply(&d, &d), &nat_multiply(reserve_0, &Nat::from(2_u32)))?;
+        let d_p = nat_divide(&nat_multiply(&d_p, &d), &nat_multiply(reserve_1, &Nat::from(2_u32)))?;
+        let numerator = nat_multiply(&nat_add(&nat_multiply(&ann, &sum), &nat_multiply(&d_p, &Nat::from(2_u32))), &d);
+        let denominator = nat_add(
+            &nat_multiply(&nat_subtract(&ann, &Nat::from(1_u32))?, &d),
+            &nat_multiply(&d_p, &Nat::from(3_u32)),
+        );
+        let d_prev = d;
+        d = nat_divide(&numerator, &denominator)?;
+        if has_converged(&d, &d_prev) {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// reserve of one token that keeps the invariant D given the reserve of the other token
+/// y = (y^2 + c) / (2 * y + b - D), where c = D^(n+1) / (n^n * x * Ann) and b = x + D / Ann
+pub fn get_y(reserve_x: &Nat, d: &Nat, amp: u64) -> Option<Nat> {
+    if nat_is_zero(reserve_x) || amp == 0 {
+        return None;
+    }
+    let ann = ann(amp);
+    let c = nat_divide(&nat_multiply(d, d), &nat_multiply(reserve_x, &Nat::from(2_u32)))?;
+    let c = nat_divide(&nat_multiply(&c, d), &nat_multiply(&ann, &Nat::from(2_u32)))?;
+    let b = nat_add(reserve_x, &nat_divide(d, &ann)?);
+    let mut y = d.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let numerator = nat_add(&nat_multiply(&y, &y), &c);
+        let denominator = nat_subtract(&nat_add(&nat_multiply(&y, &Nat::from(2_u32)), &b), d)?;
+        let y_prev = y;
+        y = nat_divide(&numerator, &denominator)?;
+        if has_converged(&y, &y_prev) {
+            return Some(y);
+        }
+    }
+    None
+}
+
+/// amount_out received for amount_in, before any fees. rounded down
+pub fn amount_out(reserve_in: &Nat, reserve_out: &Nat, amount_in: &Nat, amp: u64) -> Option<Nat> {
+    let d = get_d(reserve_in, reserve_out, amp)?;
+    let new_reserve_out = get_y(&nat_add(reserve_in, amount_in), &d, amp)?;
+    // - 1 to round against the user
+    Some(nat_subtract(&nat_subtract(reserve_out, &new_reserve_out)?, &Nat::from(1_u32)).unwrap_or(nat_zero()))
+}
+
+/// amount_in required to receive amount_out, before any fees. rounded up
+pub fn amount_in(reserve_in: &Nat, reserve_out: &Nat, amount_out: &Nat, amp: u64) -> Option<Nat> {
+    if amount_out >= reserve_out {
+        return None;
+    }
+    let d = get_d(reserve_in, reserve_out, amp)?;
+    let new_reserve_in = get_y(&nat_subtract(reserve_out, amount_out)?, &d, amp)?;
+    // + 1 to round against the user
+    Some(nat_add(&nat_subtract(&new_reserve_in, reserve_in)?, &Nat::from(1_u32)))
+}
+
+/// LP tokens minted for adding amount_0 and amount_1 in any ratio
+/// the part of the amounts that is not in the pool ratio pays fee_bps / 2, as with a swap of that part
+/// add_lp_token_amount = lp_total_supply * (D2 - D0) / D0, where D2 is the invariant after the deposit less the fees
+pub fn add_liquidity_lp_amount(
+    reserve_0: &Nat,
+    reserve_1: &Nat,
+    amount_0: &Nat,
+    amount_1: &Nat,
+    lp_total_supply: &Nat,
+    fee_bps: u8,
+    amp: u64,
+) -> Option<Nat> {
+    let d0 = get_d(reserve_0, reserve_1, amp)?;
+    let new_reserve_0 = nat_add(reserve_0, amount_0);
+    let new_reserve_1 = nat_add(reserve_1, amount_1);
+    let d1 = get_d(&new_reserve_0, &new_reserve_1, amp)?;
+
+    let imbalance_fee = |reserve: &Nat, new_reserve: &Nat| -> Option<Nat> {
+        // ideal_reserve = D1 * reserve / D0, the reserve if the deposit was in the pool ratio
+        let ideal_reserve = nat_divide(&nat_multiply(&d1, reserve), &d0)?;
+        let difference = if ideal_reserve > *new_reserve {
+            nat_subtract(&ideal_reserve, new_reserve)?
+        } else {
+            nat_subtract(new_reserve, &ideal_reserve)?
+        };
+        nat_divide(&nat_multiply(&difference, &Nat::from(fee_bps)), &Nat::from(20_000_u32))
+    };
+    let fee_0 = imbalance_fee(reserve_0, &new_reserve_0)?;
+    let fee_1 = imbalance_fee(reserve_1, &new_reserve_1)?;
+    let d2 = get_d(&nat_subtract(&new_reserve_0, &fee_0)?, &nat_subtract(&new_reserve_1, &fee_1)?, amp)?;
+
+    let d_increase = nat_subtract(&d2, &d0)?;
+    nat_divide(&nat_multiply(lp_total_supply, &d_increase), &d0)
+}
+
+/// marginal price of token_0 in token_1 (dy/dx)
+/// price = y * (4 * Ann * x^2 * y + D^3) / (x * (4 * Ann * x * y^2 + D^3))
+pub fn spot_price(reserve_0: &Nat, reserve_1: &Nat, amp: u64) -> Option<BigRational> {
+    let d = get_d(reserve_0, reserve_1, amp)?;
+    let x = nat_to_bigint(reserve_0);
+    let y = nat_to_bigint(reserve_1);
+    let d = nat_to_bigint(&d);
+    let ann_4 = nat_to_bigint(&ann(amp)) * 4;
+    let d_3 = &d * &d * &d;
+    let numerator = &y * (&ann_4 * &x * &x * &y + &d_3);
+    let denominator = &x * (&ann_4 * &x * &y * &y + &d_3);
+    Some(BigRational::new(numerator, denominator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::ToPrimitive;
+
+    // 1,000,000 tokens with 6 decimals
+    const RESERVE: u128 = 1_000_000_000_000;
+
+    #[test]
+    fn test_get_d_balanced() {
+        let d = get_d(&Nat::from(RESERVE), &Nat::from(RESERVE), 100).unwrap();
+        assert_eq!(d, Nat::from(2 * RESERVE));
+    }
+
+    #[test]
+    fn test_get_y_keeps_invariant() {
+        let reserve_0 = Nat::from(RESERVE);
+        let reserve_1 = Nat::from(RESERVE / 2);
+        let d = get_d(&reserve_0, &reserve_1, 100).unwrap();
+        let y = get_y(&reserve_0, &d, 100).unwrap();
+        assert!(has_converged(&y, &reserve_1));
+    }
+
+    #[test]
+    fn test_amount_out_less_slippage_than_constant_product() {
+        let reserve = Nat::from(RESERVE);
+        let amount = Nat::from(RESERVE / 10);
+        let stable_out = amount_out(&reserve, &reserve, &amount, 100).unwrap();
+        // constant product: amount * reserve / (reserve + amount)
+        let cp_out = nat_divide(&nat_multiply(&amount, &reserve), &nat_add(&reserve, &amount)).unwrap();
+        assert!(stable_out > cp_out);
+        assert!(stable_out < amount);
+        // 10% of the pool with A = 100 moves the price by less than 0.1%
+        assert!(stable_out > RESERVE / 10 * 999 / 1000);
+    }
+
+    #[test]
+    fn test_amount_in_inverse_of_amount_out() {
+        let reserve_in = Nat::from(RESERVE);
+        let reserve_out = Nat::from(RESERVE * 2);
+        let amount = Nat::from(RESERVE / 4);
+        let receive = amount_out(&reserve_in, &reserve_out, &amount, 200).unwrap();
+        let pay = amount_in(&reserve_in, &reserve_out, &receive, 200).unwrap();
+        // rounding is against the user both ways
+        assert!(pay >= amount);
+        assert!(pay <= nat_add(&amount, &Nat::from(3_u32)));
+        assert!(amount_in(&reserve_in, &reserve_out, &reserve_out, 200).is_none());
+    }
+
+    #[test]
+    fn test_add_liquidity_lp_amount() {
+        let reserve = Nat::from(RESERVE);
+        let supply = Nat::from(RESERVE);
+        let amount = Nat::from(RESERVE / 10);
+        // deposit in the pool ratio mints the same share as a constant product pool
+        let balanced = add_liquidity_lp_amount(&reserve, &reserve, &amount, &amount, &supply, 30, 100).unwrap();
+        assert!(has_converged(&balanced, &Nat::from(RESERVE / 10)));
+        // single sided deposit pays the fee on the imbalance
+        let single = add_liquidity_lp_amount(&reserve, &reserve, &nat_add(&amount, &amount), &nat_zero(), &supply, 30, 100).unwrap();
+        assert!(single < balanced);
+        assert!(single > RESERVE / 10 * 998 / 1000);
+        let single_no_fee = add_liquidity_lp_amount(&reserve, &reserve, &nat_add(&amount, &amount), &nat_zero(), &supply, 0, 100).unwrap();
+        assert!(single < single_no_fee);
+    }
+
+    #[test]
+    fn test_spot_price() {
+        let reserve = Nat::from(RESERVE);
+        assert_eq!(spot_price(&reserve, &reserve, 100).unwrap().to_f64().unwrap(), 1.0);
+        // more token_0 in the pool makes token_0 cheaper, but much less than with constant product
+        let price = spot_price(&Nat::from(RESERVE * 2), &reserve, 100).unwrap().to_f64().unwrap();
+        assert!(price < 1.0 && price > 0.99);
+    }
+
+    #[test]
+    fn test_zero_reserves() {
+        assert!(get_d(&nat_zero(), &Nat::from(RESERVE), 100).is_none());
+        assert!(amount_out(&nat_zero(), &Nat::from(RESERVE), &Nat::from(1_u32), 100).is_none());
+    }
+}
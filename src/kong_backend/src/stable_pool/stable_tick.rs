@@ -0,0 +1,80 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::nat_helpers::{nat_add, nat_zero};
+
+use super::concentrated_math::TickLiquidity;
+
+/// (pool_id, tick). ticks of a pool are ordered lowest first
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableTickId(pub u32, pub i32);
+
+impl Storable for StableTickId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableTickId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableTickId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Initialized tick of a concentrated liquidity pool. A tick is initialized while a position starts or ends at it
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StableTick {
+    pub pool_id: u32,
+    pub tick: i32,
+    pub liquidity_lower: Nat,      // liquidity of the positions with tick_lower at this tick
+    pub liquidity_upper: Nat,      // liquidity of the positions with tick_upper at this tick
+    pub fee_growth_outside_0: Nat, // fee growth of token_0 on the other side of the tick from the current price
+    pub fee_growth_outside_1: Nat, // fee growth of token_1 on the other side of the tick from the current price
+}
+
+impl StableTick {
+    /// new tick. fee growth before the tick is initialized is assumed to have happened below the tick
+    pub fn new(pool_id: u32, tick: i32, current_tick: i32, fee_growth_0: &Nat, fee_growth_1: &Nat) -> Self {
+        let (fee_growth_outside_0, fee_growth_outside_1) = if tick <= current_tick {
+            (fee_growth_0.clone(), fee_growth_1.clone())
+        } else {
+            (nat_zero(), nat_zero())
+        };
+        Self {
+            pool_id,
+            tick,
+            liquidity_lower: nat_zero(),
+            liquidity_upper: nat_zero(),
+            fee_growth_outside_0,
+            fee_growth_outside_1,
+        }
+    }
+
+    /// total liquidity of the positions referencing the tick. the tick can be removed when zero
+    pub fn liquidity_gross(&self) -> Nat {
+        nat_add(&self.liquidity_lower, &self.liquidity_upper)
+    }
+}
+
+impl From<&StableTick> for TickLiquidity {
+    fn from(tick: &StableTick) -> Self {
+        TickLiquidity {
+            tick: tick.tick,
+            liquidity_lower: tick.liquidity_lower.clone(),
+            liquidity_upper: tick.liquidity_upper.clone(),
+        }
+    }
+}
+
+impl Storable for StableTick {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableTick").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableTick")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
@@ -0,0 +1,30 @@
+use crate::helpers::nat_helpers::nat_is_zero;
+use crate::stable_memory::TICK_MAP;
+
+use super::stable_tick::{StableTick, StableTickId};
+
+pub fn get(pool_id: u32, tick: i32) -> Option<StableTick> {
+    TICK_MAP.with(|m| m.borrow().get(&StableTickId(pool_id, tick)))
+}
+
+/// get all initialized ticks of a pool, lowest first
+pub fn get_by_pool_id(pool_id: u32) -> Vec<StableTick> {
+    TICK_MAP.with(|m| {
+        m.borrow()
+            .range(StableTickId(pool_id, i32::MIN)..=StableTickId(pool_id, i32::MAX))
+            .map(|(_, v)| v)
+            .collect()
+    })
+}
+
+/// insert or update a tick. ticks no position references anymore are removed
+pub fn update(tick: &StableTick) {
+    TICK_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        if nat_is_zero(&tick.liquidity_gross()) {
+            map.remove(&StableTickId(tick.pool_id, tick.tick));
+        } else {
+            map.insert(StableTickId(tick.pool_id, tick.tick), tick.clone());
+        }
+    });
+}
@@ -0,0 +1,132 @@
+//! Weighted constant product (Balancer) for 2 token pools
+//!
+//! x^w_x * y^w_y = k, with weights in percent and w_x + w_y = 100
+//!
+//! powers with fractional exponents are calculated in f64 with ln_1p/exp_m1 to keep the precision of small swaps
+//! all amounts must be in the same decimal precision (max_decimals of the 2 tokens)
+use candid::Nat;
+use num::{BigInt, BigRational};
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide_as_f64, nat_is_zero, nat_multiply_f64, nat_to_bigint, nat_to_f64};
+
+/// amount_out received for amount_in, before any fees. rounded down
+/// amount_out = reserve_out * (1 - (reserve_in / (reserve_in + amount_in))^(weight_in / weight_out))
+pub fn amount_out(reserve_in: &Nat, reserve_out: &Nat, amount_in: &Nat, weight_in: u8, weight_out: u8) -> Option<Nat> {
+    if nat_is_zero(reserve_in) || weight_in == 0 || weight_out == 0 {
+        return None;
+    }
+    let ratio = nat_divide_as_f64(amount_in, &nat_add(reserve_in, amount_in))?;
+    let exponent = weight_in as f64 / weight_out as f64;
+    // 1 - (1 - ratio)^exponent
+    let fraction = -(exponent * (-ratio).ln_1p()).exp_m1();
+    nat_multiply_f64(reserve_out, fraction.clamp(0_f64, 1_f64))
+}
+
+/// amount_in required to receive amount_out, before any fees. rounded up
+/// amount_in = reserve_in * ((reserve_out / (reserve_out - amount_out))^(weight_out / weight_in) - 1)
+pub fn amount_in(reserve_in: &Nat, reserve_out: &Nat, amount_out: &Nat, weight_in: u8, weight_out: u8) -> Option<Nat> {
+    if amount_out >= reserve_out || weight_in == 0 || weight_out == 0 {
+        return None;
+    }
+    let ratio = nat_divide_as_f64(amount_out, reserve_out)?;
+    let exponent = weight_out as f64 / weight_in as f64;
+    // (1 - ratio)^-exponent - 1
+    let fraction = (-exponent * (-ratio).ln_1p()).exp_m1();
+    nat_multiply_f64(reserve_in, fraction).map(|amount_in| amount_in + 1_u32)
+}
+
+/// price of token_0 in token_1
+/// price = (reserve_1 / weight_1) / (reserve_0 / weight_0)
+pub fn spot_price(reserve_0: &Nat, reserve_1: &Nat, weight_0: u8, weight_1: u8) -> Option<BigRational> {
+    if nat_is_zero(reserve_0) || weight_1 == 0 {
+        return None;
+    }
+    Some(BigRational::new(
+        nat_to_bigint(reserve_1) * BigInt::from(weight_0),
+        nat_to_bigint(reserve_0) * BigInt::from(weight_1),
+    ))
+}
+
+/// LP tokens of a new pool as the weighted geometric mean of the amounts
+/// add_lp_token_amount = amount_0^(weight_0 / 100) * amount_1^(weight_1 / 100)
+pub fn initial_lp_amount(amount_0: &Nat, amount_1: &Nat, weight_0: u8, weight_1: u8) -> Option<Nat> {
+    if nat_is_zero(amount_0) || nat_is_zero(amount_1) {
+        return None;
+    }
+    let ln_amount = (weight_0 as f64 * nat_to_f64(amount_0)?.ln() + weight_1 as f64 * nat_to_f64(amount_1)?.ln()) / 100_f64;
+    let add_lp_token_amount = BigRational::from_float(ln_amount.exp())?.to_integer();
+    Some(Nat::from(add_lp_token_amount.to_biguint()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::nat_helpers::{nat_divide, nat_multiply, nat_sqrt, nat_subtract};
+    use num_traits::ToPrimitive;
+
+    // 1,000,000 tokens with 8 decimals
+    const RESERVE: u128 = 100_000_000_000_000;
+
+    fn is_close(a: &Nat, b: &Nat, tolerance: u32) -> bool {
+        let diff = if a > b { nat_subtract(a, b) } else { nat_subtract(b, a) };
+        diff.is_some_and(|diff| diff <= tolerance)
+    }
+
+    #[test]
+    fn test_amount_out_equal_weights_is_constant_product() {
+        let reserve_in = Nat::from(RESERVE);
+        let reserve_out = Nat::from(RESERVE * 3);
+        for amount in [1_000_u128, RESERVE / 1_000, RESERVE / 2] {
+            let amount = Nat::from(amount);
+            let weighted = amount_out(&reserve_in, &reserve_out, &amount, 50, 50).unwrap();
+            let constant_product = nat_divide(&nat_multiply(&amount, &reserve_out), &nat_add(&reserve_in, &amount)).unwrap();
+            assert!(
+                is_close(&weighted, &constant_product, 1_000),
+                "{} != {}",
+                weighted,
+                constant_product
+            );
+        }
+    }
+
+    #[test]
+    fn test_amount_out_80_20() {
+        // 80/20 pool holding 4x the value in token_0, at a price of 1
+        let reserve_0 = Nat::from(RESERVE * 4);
+        let reserve_1 = Nat::from(RESERVE);
+        let price = spot_price(&reserve_0, &reserve_1, 80, 20).unwrap();
+        assert_eq!(price.to_f64().unwrap(), 1.0);
+        // small swaps are close to the spot price both ways
+        let amount = Nat::from(RESERVE / 1_000_000);
+        let receive_1 = amount_out(&reserve_0, &reserve_1, &amount, 80, 20).unwrap();
+        let receive_0 = amount_out(&reserve_1, &reserve_0, &amount, 20, 80).unwrap();
+        assert!(receive_1 < amount && receive_1 > RESERVE / 1_000_000 * 9_999 / 10_000);
+        assert!(receive_0 < amount && receive_0 > RESERVE / 1_000_000 * 9_999 / 10_000);
+    }
+
+    #[test]
+    fn test_amount_in_inverse_of_amount_out() {
+        let reserve_in = Nat::from(RESERVE);
+        let reserve_out = Nat::from(RESERVE * 4);
+        let amount = Nat::from(RESERVE / 10);
+        let receive = amount_out(&reserve_in, &reserve_out, &amount, 20, 80).unwrap();
+        let pay = amount_in(&reserve_in, &reserve_out, &receive, 20, 80).unwrap();
+        assert!(pay >= amount);
+        assert!(is_close(&pay, &amount, 1_000));
+        assert!(amount_in(&reserve_in, &reserve_out, &reserve_out, 20, 80).is_none());
+    }
+
+    #[test]
+    fn test_initial_lp_amount() {
+        let amount_0 = Nat::from(RESERVE * 4);
+        let amount_1 = Nat::from(RESERVE);
+        // 50/50 is sqrt(amount_0 * amount_1)
+        let lp_amount = initial_lp_amount(&amount_0, &amount_1, 50, 50).unwrap();
+        assert!(is_close(&lp_amount, &nat_sqrt(&nat_multiply(&amount_0, &amount_1)), 1_000));
+        // 80/20 is amount_0^0.8 * amount_1^0.2
+        let lp_amount = initial_lp_amount(&amount_0, &amount_1, 80, 20).unwrap();
+        let expected = (4_f64.powf(0.8) * RESERVE as f64) as u128;
+        assert!(is_close(&lp_amount, &Nat::from(expected), 100_000));
+        assert!(initial_lp_amount(&Nat::from(0_u32), &amount_1, 80, 20).is_none());
+    }
+}
@@ -3,7 +3,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::add_liquidity::add_liquidity_reply::AddLiquidityReply;
 use crate::add_pool::add_pool_reply::AddPoolReply;
+use crate::campaigns::campaign_reply::CampaignReply;
 use crate::claims::claim_reply::ClaimReply;
+use crate::flash_swap::flash_swap_reply::FlashSwapReply;
+use crate::orders::order_reply::OrderReply;
+use crate::positions::position_reply::PositionReply;
 use crate::remove_liquidity::remove_liquidity_reply::RemoveLiquidityReply;
 use crate::send::send_reply::SendReply;
 use crate::swap::swap_reply::SwapReply;
@@ -17,4 +21,8 @@ pub enum Reply {
     Swap(SwapReply),
     Claim(ClaimReply),
     Send(SendReply),
+    Order(OrderReply),
+    Position(PositionReply),
+    Campaign(CampaignReply),
+    FlashSwap(FlashSwapReply),
 }
@@ -3,9 +3,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::add_liquidity::add_liquidity_args::AddLiquidityArgs;
 use crate::add_pool::add_pool_args::AddPoolArgs;
+use crate::campaigns::create_campaign_args::CreateCampaignArgs;
+use crate::flash_swap::flash_swap_args::FlashSwapArgs;
+use crate::orders::limit_order_args::LimitOrderArgs;
+use crate::orders::twap_order_args::TwapOrderArgs;
+use crate::positions::mint_position_args::MintPositionArgs;
 use crate::remove_liquidity::remove_liquidity_args::RemoveLiquidityArgs;
 use crate::send::send_args::SendArgs;
 use crate::swap::swap_args::SwapArgs;
+use crate::zap_in::zap_in_args::ZapInArgs;
 
 #[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
 pub enum Request {
@@ -16,4 +22,15 @@ pub enum Request {
     Claim(u64),
     Send(SendArgs),
     SolanaVerifyAsync(String), // For async Solana verification tracking
+    LimitOrder(LimitOrderArgs),
+    FillOrder(u64),
+    CancelOrder(u64),
+    TwapOrder(TwapOrderArgs),
+    MintPosition(MintPositionArgs),
+    BurnPosition(u64),
+    CollectFees(u64),
+    ZapIn(ZapInArgs),
+    SweepProtocolFees,
+    CreateCampaign(CreateCampaignArgs),
+    FlashSwap(FlashSwapArgs),
 }
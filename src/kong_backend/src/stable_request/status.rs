@@ -88,6 +88,9 @@ pub enum StatusCode {
     ReturnPayToken,
     ReturnPayTokenSuccess,
     ReturnPayTokenFailed,
+    ReturnUnusedPayToken,
+    ReturnUnusedPayTokenSuccess,
+    ReturnUnusedPayTokenFailed,
     // claim
     ClaimToken,
     ClaimTokenSuccess,
@@ -107,6 +110,43 @@ pub enum StatusCode {
     SendLPTokenToUser,
     SendLPTokenToUserSuccess,
     SendLPTokenToUserFailed,
+    // orders
+    OrderPlaced,
+    OrderNotFound,
+    FillOrder,
+    OrderFilled,
+    OrderSliceFilled,
+    CancelOrder,
+    OrderClaimSaved,
+    OrderCancelled,
+    OrderExpired,
+    // positions
+    PositionMinted,
+    PositionBurned,
+    FeesCollected,
+    // zap
+    ZapSwap,
+    ZapSwapSuccess,
+    ZapSwapFailed,
+    // sweep protocol fees
+    SweepProtocolFees,
+    SweepProtocolFeesSuccess,
+    SweepProtocolFeesFailed,
+    // campaigns
+    CampaignCreated,
+    // flash swaps
+    FlashSwapCheckFunds,
+    FlashSwapCheckFundsSuccess,
+    FlashSwapCheckFundsFailed,
+    FlashSwapSendLoan,
+    FlashSwapSendLoanSuccess,
+    FlashSwapSendLoanFailed,
+    FlashSwapCallback,
+    FlashSwapCallbackSuccess,
+    FlashSwapCallbackFailed,
+    FlashSwapRepay,
+    FlashSwapRepaySuccess,
+    FlashSwapRepayFailed,
     // general
     Success,
     Failed,
@@ -181,6 +221,9 @@ impl std::fmt::Display for StatusCode {
             StatusCode::ReturnPayToken => write!(f, "Returning pay token"),
             StatusCode::ReturnPayTokenSuccess => write!(f, "Pay token returned"),
             StatusCode::ReturnPayTokenFailed => write!(f, "Failing returning pay token"),
+            StatusCode::ReturnUnusedPayToken => write!(f, "Returning unused pay token"),
+            StatusCode::ReturnUnusedPayTokenSuccess => write!(f, "Unused pay token returned"),
+            StatusCode::ReturnUnusedPayTokenFailed => write!(f, "Failed returning unused pay token"),
             StatusCode::ClaimToken => write!(f, "Claiming token"),
             StatusCode::ClaimTokenSuccess => write!(f, "Token claimed"),
             StatusCode::ClaimTokenFailed => write!(f, "Failed claiming token"),
@@ -196,6 +239,37 @@ impl std::fmt::Display for StatusCode {
             StatusCode::SendLPTokenToUser => write!(f, "Sending LP token to user"),
             StatusCode::SendLPTokenToUserSuccess => write!(f, "LP token sent to user"),
             StatusCode::SendLPTokenToUserFailed => write!(f, "Failed sending LP token to user"),
+            StatusCode::OrderPlaced => write!(f, "Order placed"),
+            StatusCode::OrderNotFound => write!(f, "Order not found"),
+            StatusCode::FillOrder => write!(f, "Filling order"),
+            StatusCode::OrderFilled => write!(f, "Order filled"),
+            StatusCode::OrderSliceFilled => write!(f, "Order slice filled"),
+            StatusCode::CancelOrder => write!(f, "Cancelling order"),
+            StatusCode::OrderClaimSaved => write!(f, "Unfilled pay token saved as claim"),
+            StatusCode::OrderCancelled => write!(f, "Order cancelled"),
+            StatusCode::OrderExpired => write!(f, "Order expired"),
+            StatusCode::PositionMinted => write!(f, "Position minted"),
+            StatusCode::PositionBurned => write!(f, "Position burned"),
+            StatusCode::FeesCollected => write!(f, "Fees collected"),
+            StatusCode::ZapSwap => write!(f, "Swapping for the other token of the pool"),
+            StatusCode::ZapSwapSuccess => write!(f, "Swapped for the other token of the pool"),
+            StatusCode::ZapSwapFailed => write!(f, "Failed swapping for the other token of the pool"),
+            StatusCode::SweepProtocolFees => write!(f, "Sweeping protocol fees to treasury"),
+            StatusCode::SweepProtocolFeesSuccess => write!(f, "Protocol fees swept to treasury"),
+            StatusCode::SweepProtocolFeesFailed => write!(f, "Failed sweeping protocol fees to treasury"),
+            StatusCode::CampaignCreated => write!(f, "Campaign created"),
+            StatusCode::FlashSwapCheckFunds => write!(f, "Checking flash swap borrower funds"),
+            StatusCode::FlashSwapCheckFundsSuccess => write!(f, "Flash swap borrower funds checked"),
+            StatusCode::FlashSwapCheckFundsFailed => write!(f, "Flash swap borrower can not repay"),
+            StatusCode::FlashSwapSendLoan => write!(f, "Sending flash swap loan"),
+            StatusCode::FlashSwapSendLoanSuccess => write!(f, "Flash swap loan sent"),
+            StatusCode::FlashSwapSendLoanFailed => write!(f, "Failed sending flash swap loan"),
+            StatusCode::FlashSwapCallback => write!(f, "Calling flash swap borrower"),
+            StatusCode::FlashSwapCallbackSuccess => write!(f, "Flash swap borrower called"),
+            StatusCode::FlashSwapCallbackFailed => write!(f, "Failed calling flash swap borrower"),
+            StatusCode::FlashSwapRepay => write!(f, "Receiving flash swap repayment"),
+            StatusCode::FlashSwapRepaySuccess => write!(f, "Flash swap repaid"),
+            StatusCode::FlashSwapRepayFailed => write!(f, "Failed receiving flash swap repayment"),
             StatusCode::Success => write!(f, "Success"),
             StatusCode::Failed => write!(f, "Failed"),
         }
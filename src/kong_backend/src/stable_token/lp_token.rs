@@ -1,4 +1,4 @@
-use candid::CandidType;
+use candid::{CandidType, Principal};
 use serde::{Deserialize, Serialize};
 
 use crate::chains::chains::LP_CHAIN;
@@ -18,6 +18,8 @@ pub struct LPToken {
     pub decimals: u8,
     #[serde(default = "false_bool")]
     pub is_removed: bool,
+    #[serde(default)]
+    pub ledger_id: Option<Principal>, // kong_lp_ledger canister serving the ICRC-1/2/3 interface of the LP token
 }
 
 fn false_bool() -> bool {
@@ -36,6 +38,17 @@ impl LPToken {
             address,
             decimals: LP_DECIMALS,
             is_removed: false,
+            ledger_id: None,
+        }
+    }
+
+    /// LP token for an additional pool of the token pair
+    /// the pool_id is added to the symbol and address to keep them unique
+    pub fn new_additional_pool(token_0: &StableToken, token_1: &StableToken, pool_id: u32) -> Self {
+        Self {
+            symbol: token::pool_id_symbol(token_0, token_1, pool_id),
+            address: token::pool_id_address(token_0, token_1, pool_id),
+            ..Self::new(token_0, token_1)
         }
     }
 
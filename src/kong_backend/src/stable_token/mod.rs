@@ -1,5 +1,6 @@
 pub mod ic_token;
 pub mod lp_token;
+pub mod ripple_token;
 pub mod solana_token;
 #[allow(clippy::module_inception)]
 pub mod stable_token;
@@ -0,0 +1,36 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::chains::chains::XRP_CHAIN;
+
+/// currency code of native XRP
+pub const XRP_CURRENCY: &str = "XRP";
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct RippleToken {
+    pub token_id: u32,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8, // 6 for XRP (drops). for issued currencies, the decimals Kong keeps balances in
+    pub fee: Nat,
+    pub currency: String,       // "XRP" for native XRP, otherwise the currency code of the issued currency
+    pub issuer: Option<String>, // issuer account of the issued currency, None for native XRP
+}
+
+impl RippleToken {
+    pub fn chain(&self) -> String {
+        XRP_CHAIN.to_string()
+    }
+
+    /// unique address of the token. "XRP" for native XRP, otherwise currency.issuer
+    pub fn address(&self) -> String {
+        match &self.issuer {
+            Some(issuer) => format!("{}.{}", self.currency, issuer),
+            None => self.currency.to_string(),
+        }
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.issuer.is_none()
+    }
+}
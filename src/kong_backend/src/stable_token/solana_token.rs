@@ -2,6 +2,7 @@ use candid::{CandidType, Nat};
 use serde::{Deserialize, Serialize};
 
 use crate::chains::chains::SOL_CHAIN;
+use crate::solana::network::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
 
 #[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
 pub struct SolanaToken {
@@ -14,6 +15,8 @@ pub struct SolanaToken {
     pub program_id: String,        // SPL Token program ID
     #[serde(default = "default_is_spl_token")]
     pub is_spl_token: bool, // True for SPL tokens, false for native SOL
+    #[serde(default)]
+    pub has_transfer_fee: bool, // True for Token-2022 mints with the transfer fee extension
 }
 
 fn default_is_spl_token() -> bool {
@@ -24,4 +27,13 @@ impl SolanaToken {
     pub fn chain(&self) -> String {
         SOL_CHAIN.to_string()
     }
+
+    /// token program of the mint. the classic SPL Token program unless the mint is a Token-2022 mint
+    pub fn token_program_id(&self) -> &'static str {
+        if self.program_id == TOKEN_2022_PROGRAM_ID {
+            TOKEN_2022_PROGRAM_ID
+        } else {
+            TOKEN_PROGRAM_ID
+        }
+    }
 }
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use super::ic_token::ICToken;
 use super::lp_token::LPToken;
+use super::ripple_token::RippleToken;
 use super::solana_token::SolanaToken;
 
 #[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -26,6 +27,7 @@ pub enum StableToken {
     LP(LPToken),       // LP tokens
     IC(ICToken),       // IC tokens
     Solana(SolanaToken), // Solana tokens
+    Ripple(RippleToken), // XRP Ledger tokens
 }
 
 impl Storable for StableToken {
@@ -3,7 +3,7 @@ use candid::{Nat, Principal};
 use crate::helpers::nat_helpers::nat_zero;
 
 use super::stable_token::StableToken;
-use super::stable_token::StableToken::{Solana, IC, LP};
+use super::stable_token::StableToken::{Ripple, Solana, IC, LP};
 
 pub trait Token {
     fn token_id(&self) -> u32;
@@ -30,6 +30,7 @@ impl Token for StableToken {
             LP(token) => token.token_id,
             IC(token) => token.token_id,
             Solana(token) => token.token_id,
+            Ripple(token) => token.token_id,
         }
     }
 
@@ -38,6 +39,7 @@ impl Token for StableToken {
             LP(token) => token.name().to_string(),
             IC(token) => token.name.to_string(),
             Solana(token) => token.name.to_string(),
+            Ripple(token) => token.name.to_string(),
         }
     }
 
@@ -46,6 +48,7 @@ impl Token for StableToken {
             LP(token) => token.chain(),
             IC(token) => token.chain(),
             Solana(token) => token.chain(),
+            Ripple(token) => token.chain(),
         }
     }
 
@@ -55,6 +58,7 @@ impl Token for StableToken {
             LP(token) => token.address.to_string(),
             IC(token) => token.canister_id.to_string(),
             Solana(token) => token.mint_address.to_string(),
+            Ripple(token) => token.address(),
         }
     }
 
@@ -67,6 +71,7 @@ impl Token for StableToken {
             LP(_) => None,
             IC(token) => Some(&token.canister_id),
             Solana(_) => None,
+            Ripple(_) => None,
         }
     }
 
@@ -75,6 +80,7 @@ impl Token for StableToken {
             LP(token) => token.symbol.to_string(),
             IC(token) => token.symbol.to_string(),
             Solana(token) => token.symbol.to_string(),
+            Ripple(token) => token.symbol.to_string(),
         }
     }
 
@@ -87,6 +93,7 @@ impl Token for StableToken {
             LP(token) => token.decimals,
             IC(token) => token.decimals,
             Solana(token) => token.decimals,
+            Ripple(token) => token.decimals,
         }
     }
 
@@ -95,6 +102,7 @@ impl Token for StableToken {
             LP(_) => nat_zero(),
             IC(token) => token.fee.clone(),
             Solana(token) => token.fee.clone(),
+            Ripple(token) => token.fee.clone(),
         }
     }
 
@@ -103,6 +111,7 @@ impl Token for StableToken {
             LP(_) => false,
             IC(token) => token.icrc1,
             Solana(_) => false,
+            Ripple(_) => false,
         }
     }
 
@@ -111,6 +120,7 @@ impl Token for StableToken {
             LP(_) => false,
             IC(token) => token.icrc2,
             Solana(_) => false,
+            Ripple(_) => false,
         }
     }
 
@@ -119,6 +129,7 @@ impl Token for StableToken {
             LP(_) => false,
             IC(token) => token.icrc3,
             Solana(_) => false,
+            Ripple(_) => false,
         }
     }
 
@@ -127,6 +138,7 @@ impl Token for StableToken {
             LP(token) => token.is_removed,
             IC(token) => token.is_removed,
             Solana(_) => false, // Solana tokens don't have is_removed field yet
+            Ripple(_) => false,
         }
     }
 }
@@ -138,3 +150,13 @@ pub fn symbol(token_0: &StableToken, token_1: &StableToken) -> String {
 pub fn address(token_0: &StableToken, token_1: &StableToken) -> String {
     format!("{}_{}", token_0.token_id(), token_1.token_id())
 }
+
+/// symbol of the LP token for additional pools of a token pair
+/// keyed on the pool_id as the fee tier of a pool can be changed
+pub fn pool_id_symbol(token_0: &StableToken, token_1: &StableToken, pool_id: u32) -> String {
+    format!("{}_{}_{}", token_0.symbol(), token_1.symbol(), pool_id)
+}
+
+pub fn pool_id_address(token_0: &StableToken, token_1: &StableToken, pool_id: u32) -> String {
+    format!("{}_{}_{}", token_0.token_id(), token_1.token_id(), pool_id)
+}
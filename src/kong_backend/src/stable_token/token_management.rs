@@ -21,6 +21,7 @@ fn enable_token_impl(token: &mut StableToken, value: bool) {
         StableToken::LP(lptoken) => lptoken.is_removed = is_removed,
         StableToken::IC(ictoken) => ictoken.is_removed = is_removed,
         StableToken::Solana(_) => {}
+        StableToken::Ripple(_) => {}
     }
 
     update(&token);
@@ -83,6 +84,7 @@ pub async fn check_disabled_tokens() -> Vec<u32> {
             }
             StableToken::IC(ictoken) => check_alive(&ictoken.canister_id).await,
             StableToken::Solana(_) => false,
+            StableToken::Ripple(_) => false,
         };
 
         if is_alive {
@@ -1,6 +1,6 @@
 use wildmatch::WildMatch;
 
-use crate::chains::chains::{IC_CHAIN, SOL_CHAIN};
+use crate::chains::chains::{IC_CHAIN, SOL_CHAIN, XRP_CHAIN};
 use crate::ic::network::ICNetwork;
 use crate::stable_kong_settings::kong_settings_map;
 use crate::stable_memory::TOKEN_MAP;
@@ -8,6 +8,7 @@ use crate::stable_token::stable_token::{StableToken, StableTokenId};
 
 use super::ic_token::ICToken;
 use super::lp_token::LPToken;
+use super::ripple_token::RippleToken;
 use super::solana_token::SolanaToken;
 use super::token::Token;
 use super::token_map;
@@ -59,6 +60,7 @@ pub fn get_chain(token: &str) -> Option<String> {
     match token.split_once('.') {
         Some((prefix, _)) if prefix == IC_CHAIN => Some(IC_CHAIN.to_string()),
         Some((prefix, _)) if prefix == SOL_CHAIN => Some(SOL_CHAIN.to_string()),
+        Some((prefix, _)) if prefix == XRP_CHAIN => Some(XRP_CHAIN.to_string()),
         _ => None,
     }
 }
@@ -75,6 +77,7 @@ pub fn get_address(token: &str) -> Option<String> {
     match chain {
         Some(IC_CHAIN) => Some(address.to_string()),
         Some(SOL_CHAIN) => Some(address.to_string()), // Solana addresses are base58 strings
+        Some(XRP_CHAIN) => Some(address.to_string()), // XRP or currency.issuer
         None => Some(address.to_string()),
         _ => None,
     }
@@ -188,6 +191,7 @@ pub fn insert(token: &StableToken) -> Result<u32, String> {
             StableToken::LP(token) => StableToken::LP(LPToken { token_id, ..token.clone() }),
             StableToken::IC(token) => StableToken::IC(ICToken { token_id, ..token.clone() }),
             StableToken::Solana(token) => StableToken::Solana(SolanaToken { token_id, ..token.clone() }),
+            StableToken::Ripple(token) => StableToken::Ripple(RippleToken { token_id, ..token.clone() }),
         };
         map.insert(StableTokenId(token_id), insert_token.clone());
         insert_token
@@ -210,6 +214,7 @@ pub fn remove(token_id: u32) -> Result<(), String> {
         StableToken::IC(token) => &StableToken::IC(ICToken { is_removed: true, ..token }),
         StableToken::LP(token) => &StableToken::LP(LPToken { is_removed: true, ..token }),
         StableToken::Solana(_token) => return Err("Cannot remove Solana tokens".to_string()), // TODO: Add is_removed field to SolanaToken
+        StableToken::Ripple(_token) => return Err("Cannot remove Ripple tokens".to_string()),
     };
     update(remove_token);
 
@@ -230,6 +235,7 @@ pub fn unremove(token_id: u32) -> Result<(), String> {
             ..token
         }),
         StableToken::Solana(_token) => return Err("Cannot unremove Solana tokens".to_string()), // TODO: Add is_removed field to SolanaToken
+        StableToken::Ripple(_token) => return Err("Cannot unremove Ripple tokens".to_string()),
     };
     update(unremove_token);
 
@@ -16,6 +16,8 @@ pub struct AddLiquidityTx {
     pub transfer_ids: Vec<u64>,
     pub claim_ids: Vec<u64>,
     pub ts: u64,
+    #[serde(default)]
+    pub swap_tx_id: Option<u64>, // internal swap of zap_in()
 }
 
 impl AddLiquidityTx {
@@ -43,6 +45,7 @@ impl AddLiquidityTx {
             transfer_ids: transfer_ids.to_vec(),
             claim_ids: claim_ids.to_vec(),
             ts,
+            swap_tx_id: None,
         }
     }
 }
@@ -0,0 +1,54 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use super::status_tx::StatusTx;
+
+/// Kong's fee of a pool included in a sweep
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct SweptPoolFee {
+    pub pool_id: u32,
+    pub amount: Nat,
+}
+
+/// sweep of Kong's fees in a token from all pools to the treasury
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolFeeSweepTx {
+    pub tx_id: u64,
+    pub user_id: u32,
+    pub request_id: u64,
+    pub status: StatusTx,
+    pub token_id: u32,
+    pub amount: Nat, // Kong's fees swept. the treasury receives amount less the token's gas fee
+    pub pools: Vec<SweptPoolFee>,
+    pub to_address: String, // treasury account
+    pub transfer_ids: Vec<u64>,
+    pub ts: u64,
+}
+
+impl ProtocolFeeSweepTx {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_id: u32,
+        request_id: u64,
+        status: StatusTx,
+        token_id: u32,
+        amount: &Nat,
+        pools: &[SweptPoolFee],
+        to_address: &str,
+        transfer_ids: &[u64],
+        ts: u64,
+    ) -> Self {
+        Self {
+            tx_id: 0,
+            user_id,
+            request_id,
+            status,
+            token_id,
+            amount: amount.clone(),
+            pools: pools.to_vec(),
+            to_address: to_address.to_string(),
+            transfer_ids: transfer_ids.to_vec(),
+            ts,
+        }
+    }
+}
@@ -18,6 +18,8 @@ pub struct RemoveLiquidityTx {
     pub transfer_ids: Vec<u64>,
     pub claim_ids: Vec<u64>,
     pub ts: u64,
+    #[serde(default)]
+    pub swap_tx_id: Option<u64>, // internal swap of a single-token remove_liquidity()
 }
 
 impl RemoveLiquidityTx {
@@ -49,6 +51,7 @@ impl RemoveLiquidityTx {
             transfer_ids: transfer_ids.to_vec(),
             claim_ids: claim_ids.to_vec(),
             ts,
+            swap_tx_id: None,
         }
     }
 }
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use super::add_liquidity_tx::AddLiquidityTx;
 use super::add_pool_tx::AddPoolTx;
+use super::protocol_fee_sweep_tx::ProtocolFeeSweepTx;
 use super::remove_liquidity_tx::RemoveLiquidityTx;
 use super::send_tx::SendTx;
 use super::swap_tx::SwapTx;
@@ -30,6 +31,7 @@ pub enum StableTx {
     RemoveLiquidity(RemoveLiquidityTx),
     Swap(SwapTx),
     Send(SendTx),
+    ProtocolFeeSweep(ProtocolFeeSweepTx),
 }
 
 impl Storable for StableTx {
@@ -21,9 +21,19 @@ pub struct SwapTx {
     pub txs: Vec<SwapCalc>,
     pub transfer_ids: Vec<u64>,
     pub claim_ids: Vec<u64>,
+    #[serde(default)]
+    pub refund: Option<SwapRefund>, // unused pay amount of an exact output swap returned to the user
     pub ts: u64,
 }
 
+/// Return of the pay amount not used by an exact output swap
+#[derive(CandidType, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwapRefund {
+    pub amount: Nat,              // unused pay amount, before the transfer fee
+    pub transfer_id: Option<u64>, // transfer returning the amount
+    pub claim_id: Option<u64>,    // claim of the amount if the transfer failed
+}
+
 impl SwapTx {
     #[allow(clippy::too_many_arguments)]
     pub fn new_success(
@@ -56,6 +66,7 @@ impl SwapTx {
             txs: txs.to_vec(),
             transfer_ids: transfer_ids.to_vec(),
             claim_ids: claim_ids.to_vec(),
+            refund: None,
             ts,
         }
     }
@@ -8,9 +8,10 @@ use crate::stable_pool::pool_map;
 
 use super::add_liquidity_tx::AddLiquidityTx;
 use super::add_pool_tx::AddPoolTx;
+use super::protocol_fee_sweep_tx::ProtocolFeeSweepTx;
 use super::remove_liquidity_tx::RemoveLiquidityTx;
 use super::send_tx::SendTx;
-use super::stable_tx::StableTx::{AddLiquidity, AddPool, RemoveLiquidity, Send, Swap};
+use super::stable_tx::StableTx::{AddLiquidity, AddPool, ProtocolFeeSweep, RemoveLiquidity, Send, Swap};
 use super::stable_tx::{StableTx, StableTxId};
 use super::swap_tx::SwapTx;
 use super::tx::Tx;
@@ -78,6 +79,11 @@ pub fn get_by_user_and_token_id(
                                 return Some(v.clone());
                             }
                         }
+                        StableTx::ProtocolFeeSweep(ref protocol_fee_sweep_tx) => {
+                            if protocol_fee_sweep_tx.token_id == token_id {
+                                return Some(v.clone());
+                            }
+                        }
                     }
                     return None;
                 }
@@ -98,6 +104,7 @@ pub fn insert(tx: &StableTx) -> u64 {
             RemoveLiquidity(tx) => RemoveLiquidity(RemoveLiquidityTx { tx_id, ..tx.clone() }),
             Swap(tx) => Swap(SwapTx { tx_id, ..tx.clone() }),
             Send(tx) => Send(SendTx { tx_id, ..tx.clone() }),
+            ProtocolFeeSweep(tx) => ProtocolFeeSweep(ProtocolFeeSweepTx { tx_id, ..tx.clone() }),
         };
         map.insert(StableTxId(tx_id), insert_tx);
         tx_id
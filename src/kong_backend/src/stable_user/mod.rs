@@ -1,6 +1,8 @@
 pub mod suspended_user_map;
 pub mod principal_id_map;
-mod referral_code;
+pub mod referral_code;
+pub mod referral_map;
+pub mod stable_referral;
 #[allow(clippy::module_inception)]
 pub mod stable_user;
 pub mod user_map;
@@ -3,7 +3,7 @@ use rand::rngs::StdRng;
 
 use crate::stable_memory::USER_MAP;
 
-// default referral interval is 180 days
+// default referral interval is 180 days. can be changed with referral_interval_nanosecs in Kong settings
 // 180 days = 24 * 60 * 60 * 1_000_000_000
 pub const REFERRAL_INTERVAL: u64 = 15550000000000000;
 // referral code length
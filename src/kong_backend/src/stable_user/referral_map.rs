@@ -0,0 +1,55 @@
+use candid::Nat;
+
+use crate::helpers::nat_helpers::{nat_add, nat_zero};
+use crate::stable_memory::REFERRAL_MAP;
+
+use super::stable_referral::{StableReferral, StableReferralId};
+
+pub fn get(referrer_user_id: u32, token_id: u32) -> Option<StableReferral> {
+    REFERRAL_MAP.with(|m| m.borrow().get(&StableReferralId(referrer_user_id, token_id)))
+}
+
+/// return the referral volume and rewards of a referrer in all tokens
+pub fn get_by_referrer_user_id(referrer_user_id: u32) -> Vec<StableReferral> {
+    REFERRAL_MAP.with(|m| {
+        m.borrow()
+            .range(StableReferralId(referrer_user_id, 0)..=StableReferralId(referrer_user_id, u32::MAX))
+            .map(|(_, v)| v)
+            .collect()
+    })
+}
+
+fn get_or_default(referrer_user_id: u32, token_id: u32, ts: u64) -> StableReferral {
+    get(referrer_user_id, token_id).unwrap_or(StableReferral {
+        referrer_user_id,
+        token_id,
+        volume: nat_zero(),
+        earned: nat_zero(),
+        claim_id: None,
+        ts,
+    })
+}
+
+pub fn update(referral: &StableReferral) {
+    REFERRAL_MAP.with(|m| {
+        m.borrow_mut()
+            .insert(StableReferralId(referral.referrer_user_id, referral.token_id), referral.clone())
+    });
+}
+
+/// add swap volume of a referee to the referrer
+pub fn add_volume(referrer_user_id: u32, token_id: u32, amount: &Nat, ts: u64) {
+    let mut referral = get_or_default(referrer_user_id, token_id, ts);
+    referral.volume = nat_add(&referral.volume, amount);
+    referral.ts = ts;
+    update(&referral);
+}
+
+/// add a referral reward to the referrer and set the claim the reward was accrued to
+pub fn add_reward(referrer_user_id: u32, token_id: u32, amount: &Nat, claim_id: u64, ts: u64) {
+    let mut referral = get_or_default(referrer_user_id, token_id, ts);
+    referral.earned = nat_add(&referral.earned, amount);
+    referral.claim_id = Some(claim_id);
+    referral.ts = ts;
+    update(&referral);
+}
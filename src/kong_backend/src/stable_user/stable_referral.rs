@@ -0,0 +1,42 @@
+use candid::{CandidType, Nat};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+
+/// (referrer user_id, token_id)
+#[derive(CandidType, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableReferralId(pub u32, pub u32);
+
+impl Storable for StableReferralId {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableReferralId").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableReferralId")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// referral volume and rewards of a referrer in a token
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct StableReferral {
+    pub referrer_user_id: u32,
+    pub token_id: u32,
+    pub volume: Nat,           // amount of the token paid in swaps by the referrer's referees
+    pub earned: Nat,           // referral rewards earned in the token
+    pub claim_id: Option<u64>, // open claim the rewards accrue to until it is claimed
+    pub ts: u64,               // timestamp of the last update
+}
+
+impl Storable for StableReferral {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        serde_cbor::to_vec(self).expect("Failed to encode StableReferral").into()
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).expect("Failed to decode StableReferral")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
@@ -43,6 +43,23 @@ pub struct StableUser {
     // so 0 = no discount, 100 = pays no lp_fee on swaps
     pub fee_level: u8,
     pub fee_level_expires_at: Option<u64>,
+    // fee level earned from the rolling 30-day swap volume, updated by the fee tiers timer
+    #[serde(default)]
+    pub earned_fee_level: u8,
+    #[serde(default)]
+    pub volume_30d: f64, // rolling 30-day swap volume in USD
+}
+
+impl StableUser {
+    /// fee level used for swaps. the higher of the assigned fee level, if it has not expired, and the earned fee level
+    pub fn current_fee_level(&self, ts: u64) -> u8 {
+        let fee_level = if self.fee_level_expires_at.is_some_and(|expires_at| ts > expires_at) {
+            0
+        } else {
+            self.fee_level
+        };
+        std::cmp::min(std::cmp::max(fee_level, self.earned_fee_level), 100)
+    }
 }
 
 impl Default for StableUser {
@@ -55,6 +72,8 @@ impl Default for StableUser {
             referred_by_expires_at: None,
             fee_level: 0,
             fee_level_expires_at: None,
+            earned_fee_level: 0,
+            volume_30d: 0.0,
         }
     }
 }
@@ -4,7 +4,7 @@ use crate::stable_kong_settings::kong_settings_map;
 use crate::stable_memory::USER_MAP;
 
 use super::principal_id_map;
-use super::referral_code::{generate_referral_code, REFERRAL_INTERVAL};
+use super::referral_code::generate_referral_code;
 use super::stable_user::{StableUser, StableUserId};
 
 /// return StableUser by user_id
@@ -50,6 +50,19 @@ pub fn get_by_caller() -> Result<Option<StableUser>, String> {
     get_by_principal_id(&ICNetwork::caller().to_text())
 }
 
+/// return the fee level of the caller used for swaps. 0 if the caller is not a registered user
+pub fn get_caller_fee_level() -> u8 {
+    get_by_caller()
+        .ok()
+        .flatten()
+        .map_or(0, |user| user.current_fee_level(ICNetwork::get_time()))
+}
+
+/// return the fee level of user_id used for swaps. 0 if the user is not found
+/// used for swaps that are not made by the caller, like order fills
+pub fn get_fee_level(user_id: u32) -> u8 {
+    get_by_user_id(user_id).map_or(0, |user| user.current_fee_level(ICNetwork::get_time()))
+}
 
 /// return StableUser by referral code
 ///
@@ -99,9 +112,10 @@ pub fn insert(referred_by: Option<&str>) -> Result<u32, String> {
             // new user, create random user name and referral code
             let mut rng = ManagementCanister::get_pseudo_seed()?;
             // if referred_by is provided, check if it is a valid referral code
+            let referral_interval = kong_settings_map::get().referral_interval_nanosecs;
             let (referred_by, referred_by_expires_at) = match referred_by {
                 Some(referred) => match get_user_by_referral_code(referred) {
-                    Some(referred_user) => (Some(referred_user.user_id), Some(ICNetwork::get_time() + REFERRAL_INTERVAL)),
+                    Some(referred_user) => (Some(referred_user.user_id), Some(ICNetwork::get_time() + referral_interval)),
                     None => (None, None),
                 },
                 None => (None, None),
@@ -114,6 +128,8 @@ pub fn insert(referred_by: Option<&str>) -> Result<u32, String> {
                 referred_by_expires_at,
                 fee_level: 0,
                 fee_level_expires_at: None,
+                earned_fee_level: 0,
+                volume_30d: 0.0,
             };
             // insert to principal_id_map
             principal_id_map::insert_principal_id(&user);
@@ -133,6 +149,55 @@ pub fn insert(referred_by: Option<&str>) -> Result<u32, String> {
     Ok(user.user_id)
 }
 
+/// return user_id of principal_id, registering the principal as a new user if needed
+/// used when a principal other than the caller receives tokens, e.g. the recipient of an LP token transfer
+pub fn insert_by_principal_id(principal_id: &str) -> Result<u32, String> {
+    if let Some(user) = get_by_principal_id(principal_id)? {
+        return Ok(user.user_id);
+    }
+
+    let mut rng = ManagementCanister::get_pseudo_seed()?;
+    let user = StableUser {
+        user_id: kong_settings_map::inc_user_map_idx(),
+        principal_id: principal_id.to_string(),
+        my_referral_code: generate_referral_code(&mut rng),
+        referred_by: None,
+        referred_by_expires_at: None,
+        fee_level: 0,
+        fee_level_expires_at: None,
+        earned_fee_level: 0,
+        volume_30d: 0.0,
+    };
+    principal_id_map::insert_principal_id(&user);
+    USER_MAP.with(|m| {
+        m.borrow_mut().insert(StableUserId(user.user_id), user.clone());
+    });
+    _ = archive_to_kong_data(&user);
+
+    Ok(user.user_id)
+}
+
+pub fn update(user: &StableUser) {
+    USER_MAP.with(|m| {
+        m.borrow_mut().insert(StableUserId(user.user_id), user.clone());
+    });
+    _ = archive_to_kong_data(user);
+}
+
+/// update users and archive them to kong_data in one call
+pub fn update_users(users: &[StableUser]) {
+    if users.is_empty() {
+        return;
+    }
+    USER_MAP.with(|m| {
+        let mut map = m.borrow_mut();
+        for user in users {
+            map.insert(StableUserId(user.user_id), user.clone());
+        }
+    });
+    _ = archive_users_to_kong_data(users);
+}
+
 pub fn archive_to_kong_data(user: &StableUser) -> Result<(), String> {
     if !kong_settings_map::get().archive_to_kong_data {
         return Ok(());
@@ -159,3 +224,30 @@ pub fn archive_to_kong_data(user: &StableUser) -> Result<(), String> {
 
     Ok(())
 }
+
+pub fn archive_users_to_kong_data(users: &[StableUser]) -> Result<(), String> {
+    if !kong_settings_map::get().archive_to_kong_data {
+        return Ok(());
+    }
+
+    let num_users = users.len();
+    let users_json = match serde_json::to_string(users) {
+        Ok(users_json) => users_json,
+        Err(e) => Err(format!("Failed to serialize {} users. {}", num_users, e))?,
+    };
+
+    ic_cdk::futures::spawn(async move {
+        let kong_data = kong_settings_map::get().kong_data;
+        match ic_cdk::call::Call::unbounded_wait(kong_data, "update_user_batch")
+            .with_arg(users_json)
+            .await
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|response| response.candid::<Result<String, String>>().map_err(|e| format!("{:?}", e)))
+        {
+            Ok(_) => (),
+            Err(e) => ICNetwork::error_log(&format!("Failed to archive {} users. {}", num_users, e)),
+        };
+    });
+
+    Ok(())
+}
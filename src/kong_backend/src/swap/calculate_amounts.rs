@@ -3,17 +3,43 @@ use candid::Nat;
 use crate::helpers::nat_helpers::{nat_is_zero, nat_to_decimals_f64};
 use crate::stable_token::{stable_token::StableToken, token::Token};
 
-use super::swap_amounts::swap_amounts;
+use super::swap_amounts::{split_swap_amounts, swap_amounts, swap_amounts_exact_out};
 use super::swap_calc::SwapCalc;
 
+#[allow(clippy::too_many_arguments)]
 pub fn calculate_amounts(
     pay_token: &StableToken,
     pay_amount: &Nat,
     receive_token: &StableToken,
     user_receive_amount: Option<&Nat>,
     user_max_slippage: f64,
+    max_routes: Option<u8>,
+    exact_out: bool,
+    user_fee_level: u8,
 ) -> Result<(Nat, f64, f64, f64, Vec<SwapCalc>), String> {
-    let (receive_amount_with_fees_and_gas, price, mid_price, slippage, txs) = swap_amounts(pay_token, Some(pay_amount), receive_token)?;
+    let (receive_amount_with_fees_and_gas, price, mid_price, slippage, txs) = if exact_out {
+        // exact output swap, pay_amount is the maximum the user is willing to pay for user_receive_amount
+        let user_receive_amount = user_receive_amount.ok_or("Receive amount is required for exact output swaps")?;
+        let (required_pay_amount, receive_amount_with_fees_and_gas, price, mid_price, slippage, txs) =
+            swap_amounts_exact_out(pay_token, receive_token, user_receive_amount, user_fee_level)?;
+        if required_pay_amount > *pay_amount {
+            let decimals = pay_token.decimals();
+            let required_pay_amount_f64 = nat_to_decimals_f64(decimals, &required_pay_amount).unwrap_or(0_f64);
+            Err(format!(
+                "Insufficient pay amount. Requires {} {} with {}% slippage",
+                required_pay_amount_f64,
+                pay_token.symbol(),
+                slippage
+            ))?
+        }
+        (receive_amount_with_fees_and_gas, price, mid_price, slippage, txs)
+    } else {
+        // split the swap across multiple routes if requested
+        match max_routes {
+            Some(max_routes) if max_routes > 1 => split_swap_amounts(pay_token, pay_amount, receive_token, max_routes, user_fee_level)?,
+            _ => swap_amounts(pay_token, Some(pay_amount), receive_token, user_fee_level)?,
+        }
+    };
 
     // make sure receive_amount is not zero
     if nat_is_zero(&receive_amount_with_fees_and_gas) {
@@ -1,6 +1,7 @@
 use candid::decode_one;
 use ic_cdk;
 
+use crate::ripple::stable_memory::get_ripple_transaction;
 use crate::solana::stable_memory::get_solana_transaction;
 use crate::stable_transfer::tx_id::TxId;
 
@@ -16,6 +17,7 @@ pub mod swap_args;
 pub mod swap_calc;
 pub mod swap_calc_impl;
 pub mod swap_reply;
+pub mod swap_router;
 pub mod swap_transfer;
 pub mod swap_transfer_from;
 pub mod update_liquidity_pool;
@@ -24,7 +26,7 @@ use swap_args::SwapArgs;
 
 fn check_transaction_ready(signature: &Option<String>, tx_id: &Option<TxId>) -> Result<(), String> {
     if let (Some(_signature), Some(TxId::TransactionId(tx_sig))) = (signature, tx_id) {
-        if get_solana_transaction(tx_sig.to_string()).is_none() {
+        if get_solana_transaction(tx_sig.to_string()).is_none() && get_ripple_transaction(tx_sig).is_none() {
             return Err("TRANSACTION_NOT_READY".to_string());
         }
     }
@@ -35,6 +37,9 @@ fn check_solana_signature_provided(token: &str, signature: &Option<String>) -> R
     if token.starts_with("SOL.") && signature.is_none() {
         return Err("Solana token transfer requires signature".to_string());
     }
+    if token.starts_with("XRP.") && signature.is_none() {
+        return Err("XRPL token payment requires signature".to_string());
+    }
     Ok(())
 }
 
@@ -47,12 +52,15 @@ pub fn validate_arguments() -> Result<(), String> {
 
     if let Ok(swap_args) = decode_one::<SwapArgs>(&args_bytes) {
         check_transaction_ready(&swap_args.pay_signature, &swap_args.pay_tx_id)?;
-        
+
         check_solana_signature_provided(&swap_args.pay_token, &swap_args.pay_signature)?;
 
         if swap_args.receive_token.starts_with("SOL.") && swap_args.receive_address.is_none() {
             return Err("Solana token requires receive_address".to_string());
         }
+        if swap_args.receive_token.starts_with("XRP.") && swap_args.receive_address.is_none() {
+            return Err("XRPL token requires receive_address".to_string());
+        }
     }
 
     Ok(())
@@ -72,6 +80,9 @@ pub fn validate_arguments_async() -> Result<(), String> {
         if swap_args.receive_token.starts_with("SOL.") && swap_args.receive_address.is_none() {
             return Err("Solana token requires receive_address".to_string());
         }
+        if swap_args.receive_token.starts_with("XRP.") && swap_args.receive_address.is_none() {
+            return Err("XRPL token requires receive_address".to_string());
+        }
     }
 
     Ok(())
@@ -1,9 +1,12 @@
 use candid::Nat;
 use icrc_ledger_types::icrc1::account::Account;
 
-use crate::chains::chains::{IC_CHAIN, SOL_CHAIN};
-use crate::helpers::nat_helpers::{nat_subtract, nat_zero};
+use crate::chains::chains::{IC_CHAIN, SOL_CHAIN, XRP_CHAIN};
+use crate::helpers::nat_helpers::{nat_add, nat_subtract, nat_zero};
 use crate::ic::{address::Address, transfer::icrc1_transfer};
+use crate::ripple::create_ripple_payment_job::create_ripple_payment_job;
+use crate::ripple::verify_transfer::get_sender_from_transfers as get_ripple_sender_from_transfers;
+use crate::solana::create_solana_swap_job::create_solana_swap_job;
 use crate::solana::stable_memory::get_solana_transaction;
 use crate::stable_claim::{claim_map, stable_claim::StableClaim};
 use crate::stable_request::reply::Reply;
@@ -11,8 +14,9 @@ use crate::stable_request::request_map;
 use crate::stable_request::status::StatusCode;
 use crate::stable_token::{stable_token::StableToken, token::Token};
 use crate::stable_transfer::{stable_transfer::StableTransfer, transfer_map, tx_id::TxId};
-use crate::solana::create_solana_swap_job::create_solana_swap_job;
+use crate::stable_tx::swap_tx::SwapRefund;
 
+use super::swap_calc::SwapCalc;
 use super::swap_reply::SwapReply;
 
 #[allow(clippy::too_many_arguments)]
@@ -26,12 +30,150 @@ pub async fn return_pay_token(
     transfer_ids: &mut Vec<u64>,
     ts: u64,
 ) {
+    let mut claim_ids = Vec::new();
+
+    let _ = send_pay_token(
+        request_id,
+        user_id,
+        to_principal_id,
+        pay_token,
+        pay_amount,
+        transfer_ids,
+        &mut claim_ids,
+        ts,
+        RETURN_PAY_TOKEN_STATUS,
+    )
+    .await;
+
+    let reply = SwapReply::failed(request_id, pay_token, pay_amount, receive_token, transfer_ids, &claim_ids, ts);
+    request_map::update_reply(request_id, Reply::Swap(reply));
+}
+
+/// return the pay amount not used by an exact output swap
+/// the swap succeeded, so the reply is not touched. the transfer is added to transfer_ids and, if the return
+/// failed, the claim to claim_ids so both end up in the reply of the swap
+/// returns the pay amount used by the swaps and the refund of the unused amount, None if nothing was returned
+#[allow(clippy::too_many_arguments)]
+pub async fn return_unused_pay_token(
+    request_id: u64,
+    user_id: u32,
+    to_principal_id: &Account,
+    pay_token: &StableToken,
+    pay_amount: &Nat,
+    swaps: &[SwapCalc],
+    transfer_ids: &mut Vec<u64>,
+    claim_ids: &mut Vec<u64>,
+    ts: u64,
+) -> (Nat, Option<SwapRefund>) {
+    let used_pay_amount = used_pay_amount(pay_token.token_id(), swaps);
+    let unused_pay_amount = nat_subtract(pay_amount, &used_pay_amount).unwrap_or(nat_zero());
+
+    // only return if the unused amount is more than the transfer fee
+    if unused_pay_amount <= pay_token.fee() {
+        return (used_pay_amount, None);
+    }
+
+    let (num_transfer_ids, num_claim_ids) = (transfer_ids.len(), claim_ids.len());
+    return_pay_token_with_claims(
+        request_id,
+        user_id,
+        to_principal_id,
+        pay_token,
+        &unused_pay_amount,
+        transfer_ids,
+        claim_ids,
+        ts,
+        RETURN_UNUSED_PAY_TOKEN_STATUS,
+    )
+    .await;
+    let refund = SwapRefund {
+        amount: unused_pay_amount,
+        transfer_id: transfer_ids.get(num_transfer_ids).copied(),
+        claim_id: claim_ids.get(num_claim_ids).copied(),
+    };
+
+    (used_pay_amount, Some(refund))
+}
+
+/// return pay_amount of pay_token to the user without writing a reply, for requests that are not failed swaps
+/// the transfer is added to transfer_ids and, if the return failed, the claim to claim_ids so the caller can add
+/// them to its own reply
+#[allow(clippy::too_many_arguments)]
+pub async fn return_pay_token_with_claims(
+    request_id: u64,
+    user_id: u32,
+    to_principal_id: &Account,
+    pay_token: &StableToken,
+    pay_amount: &Nat,
+    transfer_ids: &mut Vec<u64>,
+    claim_ids: &mut Vec<u64>,
+    ts: u64,
+    status: ReturnStatus,
+) {
+    let status_failed = status.2.clone();
+    if let Err(e) = send_pay_token(
+        request_id,
+        user_id,
+        to_principal_id,
+        pay_token,
+        pay_amount,
+        transfer_ids,
+        claim_ids,
+        ts,
+        status,
+    )
+    .await
+    {
+        // the sender is unknown. save as a claim without address so the amount is not lost
+        let claim = StableClaim::new(user_id, pay_token.token_id(), pay_amount, Some(request_id), None, ts);
+        let claim_id = claim_map::insert(&claim);
+        claim_ids.push(claim_id);
+        request_map::update_status(request_id, status_failed, Some(&format!("Saved as claim #{}. {}", claim_id, e)));
+    }
+}
+
+/// pay amount used by the swaps. the first leg of each route pays with the pay token
+fn used_pay_amount(pay_token_id: u32, swaps: &[SwapCalc]) -> Nat {
+    swaps
+        .iter()
+        .filter(|swap| swap.pay_token_id == pay_token_id)
+        .fold(nat_zero(), |acc, swap| nat_add(&acc, &swap.pay_amount))
+}
+
+/// status codes of a pay token return: (start, success, failed)
+pub type ReturnStatus = (StatusCode, StatusCode, StatusCode);
+
+pub const RETURN_PAY_TOKEN_STATUS: ReturnStatus = (
+    StatusCode::ReturnPayToken,
+    StatusCode::ReturnPayTokenSuccess,
+    StatusCode::ReturnPayTokenFailed,
+);
+
+const RETURN_UNUSED_PAY_TOKEN_STATUS: ReturnStatus = (
+    StatusCode::ReturnUnusedPayToken,
+    StatusCode::ReturnUnusedPayTokenSuccess,
+    StatusCode::ReturnUnusedPayTokenFailed,
+);
+
+/// send pay_amount (less the transfer fee) of pay_token back to the user
+/// the transfer is added to transfer_ids. if the transfer fails, a claim is created and added to claim_ids
+/// returns an error, without creating a claim, if the sender of a Solana or XRPL payment is not found
+#[allow(clippy::too_many_arguments)]
+async fn send_pay_token(
+    request_id: u64,
+    user_id: u32,
+    to_principal_id: &Account,
+    pay_token: &StableToken,
+    pay_amount: &Nat,
+    transfer_ids: &mut Vec<u64>,
+    claim_ids: &mut Vec<u64>,
+    ts: u64,
+    (status, status_success, status_failed): ReturnStatus,
+) -> Result<(), String> {
     let token_id = pay_token.token_id();
     let fee = pay_token.fee();
 
-    let mut claim_ids = Vec::new();
-
-    request_map::update_status(request_id, StatusCode::ReturnPayToken, None);
+    request_map::update_status(request_id, status, None);
 
     let pay_amount_with_gas = nat_subtract(pay_amount, &fee).unwrap_or(nat_zero());
 
@@ -40,17 +182,12 @@ pub async fn return_pay_token(
             Ok(addr) => addr,
             Err(e) => {
                 // this should however not happen since the tx is already in the DB with metadata
-                request_map::update_status(
-                    request_id,
-                    StatusCode::ReturnPayTokenFailed,
-                    Some(&format!(
-                        "Cannot return Solana tokens: sender address not found in metadata. Need to implement metadata fetching: {}",
-                        e
-                    )),
+                let e = format!(
+                    "Cannot return Solana tokens: sender address not found in metadata. Need to implement metadata fetching: {}",
+                    e
                 );
-                let reply = SwapReply::failed(request_id, pay_token, pay_amount, receive_token, transfer_ids, &claim_ids, ts);
-                request_map::update_reply(request_id, Reply::Swap(reply));
-                return;
+                request_map::update_status(request_id, status_failed, Some(&e));
+                return Err(e);
             }
         };
 
@@ -67,11 +204,7 @@ pub async fn return_pay_token(
                     ts,
                 });
                 transfer_ids.push(transfer_id);
-                request_map::update_status(
-                    request_id,
-                    StatusCode::ReturnPayTokenSuccess,
-                    Some(&format!("Solana swap job #{} created", job_id)),
-                );
+                request_map::update_status(request_id, status_success, Some(&format!("Solana swap job #{} created", job_id)));
             }
             Err(e) => {
                 let claim = StableClaim::new(
@@ -86,11 +219,47 @@ pub async fn return_pay_token(
                 claim_ids.push(claim_id);
                 request_map::update_status(
                     request_id,
-                    StatusCode::ReturnPayTokenFailed,
+                    status_failed,
                     Some(&format!("Saved as claim #{}. Error creating swap job: {}", claim_id, e)),
                 );
             }
         }
+    } else if pay_token.chain() == XRP_CHAIN {
+        let sender_address = match get_ripple_sender_from_transfers(token_id, transfer_ids) {
+            Ok(addr) => addr,
+            Err(e) => {
+                let e = format!("Cannot return XRPL tokens: {}", e);
+                request_map::update_status(request_id, status_failed, Some(&e));
+                return Err(e);
+            }
+        };
+
+        let to_address = Address::RippleAddress(sender_address.clone());
+        match create_ripple_payment_job(request_id, user_id, pay_token, &pay_amount_with_gas, &to_address, ts).await {
+            Ok(job_id) => {
+                let transfer_id = transfer_map::insert(&StableTransfer {
+                    transfer_id: 0,
+                    request_id,
+                    is_send: false,
+                    amount: pay_amount_with_gas,
+                    token_id,
+                    tx_id: TxId::TransactionId(format!("job_{}", job_id)),
+                    ts,
+                });
+                transfer_ids.push(transfer_id);
+                request_map::update_status(request_id, status_success, Some(&format!("Ripple payment job #{} created", job_id)));
+            }
+            Err(e) => {
+                let claim = StableClaim::new(user_id, token_id, pay_amount, Some(request_id), Some(to_address), ts);
+                let claim_id = claim_map::insert(&claim);
+                claim_ids.push(claim_id);
+                request_map::update_status(
+                    request_id,
+                    status_failed,
+                    Some(&format!("Saved as claim #{}. Error creating payment job: {}", claim_id, e)),
+                );
+            }
+        }
     } else if pay_token.chain() == IC_CHAIN {
         match icrc1_transfer(&pay_amount_with_gas, to_principal_id, pay_token, None).await {
             Ok(tx_id) => {
@@ -104,7 +273,7 @@ pub async fn return_pay_token(
                     ts,
                 });
                 transfer_ids.push(transfer_id);
-                request_map::update_status(request_id, StatusCode::ReturnPayTokenSuccess, None);
+                request_map::update_status(request_id, status_success, None);
             }
             Err(e) => {
                 let claim = StableClaim::new(
@@ -117,11 +286,7 @@ pub async fn return_pay_token(
                 );
                 let claim_id = claim_map::insert(&claim);
                 claim_ids.push(claim_id);
-                request_map::update_status(
-                    request_id,
-                    StatusCode::ReturnPayTokenFailed,
-                    Some(&format!("Saved as claim #{}. {}", claim_id, e)),
-                );
+                request_map::update_status(request_id, status_failed, Some(&format!("Saved as claim #{}. {}", claim_id, e)));
             }
         }
     } else {
@@ -138,13 +303,12 @@ pub async fn return_pay_token(
         claim_ids.push(claim_id);
         request_map::update_status(
             request_id,
-            StatusCode::ReturnPayTokenFailed,
+            status_failed,
             Some(&format!("Saved as claim #{}. Unsupported chain: {}", claim_id, pay_token.chain())),
         );
     };
 
-    let reply = SwapReply::failed(request_id, pay_token, pay_amount, receive_token, transfer_ids, &claim_ids, ts);
-    request_map::update_reply(request_id, Reply::Swap(reply));
+    Ok(())
 }
 
 fn get_solana_sender_from_transfers(transfer_ids: &[u64]) -> Result<String, String> {
@@ -173,3 +337,114 @@ fn get_solana_sender_from_transfers(transfer_ids: &[u64]) -> Result<String, Stri
 
     Err("No Solana sender address found in transfers".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use futures::executor::block_on;
+
+    use crate::helpers::test_helpers::{init_kong_settings, insert_token, sol_token};
+    use crate::stable_request::{request::Request, stable_request::StableRequest};
+    use crate::stable_tx::swap_tx::SwapTx;
+
+    const SOL: u32 = 1;
+    const USDC: u32 = 2;
+
+    fn swap(pay_amount: u64) -> SwapCalc {
+        SwapCalc {
+            pool_id: 1,
+            pay_token_id: SOL,
+            pay_amount: Nat::from(pay_amount),
+            receive_token_id: USDC,
+            receive_amount: Nat::from(1_000_000_u64),
+            lp_fee: nat_zero(),
+            gas_fee: nat_zero(),
+            lp_fee_bps: 30,
+        }
+    }
+
+    #[test]
+    fn test_return_unused_pay_token_failed_return_is_claim() {
+        let sol = sol_token(SOL);
+        insert_token(&sol);
+        init_kong_settings();
+        let request_id = request_map::insert(&StableRequest::new(100, &Request::CancelOrder(1), 0));
+        let (mut transfer_ids, mut claim_ids) = (Vec::new(), Vec::new());
+
+        // the sender of the SOL payment is not known, so the unused 0.4 SOL can not be sent back
+        let (used_pay_amount, refund) = block_on(return_unused_pay_token(
+            request_id,
+            100,
+            &Account::from(Principal::anonymous()),
+            &sol,
+            &Nat::from(1_000_000_000_u64),
+            &[swap(600_000_000)],
+            &mut transfer_ids,
+            &mut claim_ids,
+            0,
+        ));
+        assert_eq!(used_pay_amount, Nat::from(600_000_000_u64));
+        let refund = refund.unwrap();
+        assert_eq!(refund.amount, Nat::from(400_000_000_u64));
+        assert_eq!(refund.transfer_id, None);
+        assert!(transfer_ids.is_empty());
+        let claim_id = refund.claim_id.unwrap();
+        assert_eq!(claim_ids, vec![claim_id]);
+        let claim = claim_map::get_by_claim_id(claim_id).unwrap();
+        assert_eq!(
+            (claim.user_id, claim.token_id, claim.amount),
+            (100, SOL, Nat::from(400_000_000_u64))
+        );
+        let statuses = request_map::get_by_request_id(request_id).unwrap().statuses;
+        assert!(matches!(
+            statuses.last().unwrap().status_code,
+            StatusCode::ReturnUnusedPayTokenFailed
+        ));
+
+        // the reply of the swap shows the unused amount is owed as a claim
+        let swap_tx = SwapTx {
+            refund: Some(refund),
+            ..SwapTx::new_success(
+                100,
+                request_id,
+                SOL,
+                &used_pay_amount,
+                USDC,
+                &Nat::from(1_000_000_u64),
+                1.0,
+                1.0,
+                0.0,
+                &[],
+                &[],
+                &claim_ids,
+                0,
+            )
+        };
+        let reply_refund = SwapReply::from(&swap_tx).refund.unwrap();
+        assert_eq!(reply_refund.symbol, "SOL");
+        assert_eq!(reply_refund.claim_id, Some(claim_id));
+    }
+
+    #[test]
+    fn test_return_unused_pay_token_below_fee() {
+        let sol = sol_token(SOL);
+        insert_token(&sol);
+        let (mut transfer_ids, mut claim_ids) = (Vec::new(), Vec::new());
+        // 5_000 unused is not more than the transfer fee and is kept
+        let (used_pay_amount, refund) = block_on(return_unused_pay_token(
+            1,
+            100,
+            &Account::from(Principal::anonymous()),
+            &sol,
+            &Nat::from(600_005_000_u64),
+            &[swap(600_000_000)],
+            &mut transfer_ids,
+            &mut claim_ids,
+            0,
+        ));
+        assert_eq!(used_pay_amount, Nat::from(600_000_000_u64));
+        assert_eq!(refund, None);
+        assert!(transfer_ids.is_empty() && claim_ids.is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use candid::Nat;
 
-use crate::chains::chains::SOL_CHAIN;
+use crate::chains::chains::{SOL_CHAIN, XRP_CHAIN};
 use crate::ic::{
     address::Address,
     transfer::{icp_transfer, icrc1_transfer},
@@ -9,11 +9,16 @@ use crate::stable_claim::{claim_map, stable_claim::StableClaim};
 use crate::stable_request::{reply::Reply, request_map, status::StatusCode};
 use crate::stable_token::{stable_token::StableToken, token::Token};
 use crate::stable_transfer::{stable_transfer::StableTransfer, transfer_map, tx_id::TxId};
-use crate::stable_tx::{stable_tx::StableTx, swap_tx::SwapTx, tx_map};
+use crate::stable_tx::{
+    stable_tx::StableTx,
+    swap_tx::{SwapRefund, SwapTx},
+    tx_map,
+};
 
-use crate::solana::create_solana_swap_job::create_solana_swap_job;
 use super::swap_calc::SwapCalc;
 use super::swap_reply::SwapReply;
+use crate::ripple::create_ripple_payment_job::create_ripple_payment_job;
+use crate::solana::create_solana_swap_job::create_solana_swap_job;
 
 #[allow(clippy::too_many_arguments)]
 pub async fn send_receive_token(
@@ -25,6 +30,8 @@ pub async fn send_receive_token(
     receive_amount: &Nat,
     to_address: &Address,
     transfer_ids: &mut Vec<u64>,
+    claim_ids: &mut Vec<u64>,
+    refund: Option<&SwapRefund>,
     mid_price: f64,
     price: f64,
     slippage: f64,
@@ -34,19 +41,28 @@ pub async fn send_receive_token(
     let pay_token_id = pay_token.token_id();
     let receive_token_id = receive_token.token_id();
 
-    let mut claim_ids = Vec::new();
-
     request_map::update_status(request_id, StatusCode::SendReceiveToken, None);
 
-    // Check if receive token is Solana - if so, create a swap job instead of direct transfer
-    if receive_token.chain() == SOL_CHAIN {
-        // For Solana tokens, we need to create a swap job that will be processed by kong_rpc
-        match create_solana_swap_job(request_id, user_id, receive_token, receive_amount, to_address, ts).await {
+    // Check if receive token is Solana or XRPL - if so, create a job instead of direct transfer
+    if receive_token.chain() == SOL_CHAIN || receive_token.chain() == XRP_CHAIN {
+        // For Solana and XRPL tokens, we need to create a job that will be processed by kong_rpc
+        let (job, job_name) = if receive_token.chain() == SOL_CHAIN {
+            (
+                create_solana_swap_job(request_id, user_id, receive_token, receive_amount, to_address, ts).await,
+                "Solana swap job",
+            )
+        } else {
+            (
+                create_ripple_payment_job(request_id, user_id, receive_token, receive_amount, to_address, ts).await,
+                "Ripple payment job",
+            )
+        };
+        match job {
             Ok(job_id) => {
                 request_map::update_status(
                     request_id,
                     StatusCode::SendReceiveTokenSuccess,
-                    Some(&format!("Solana swap job #{} created", job_id)),
+                    Some(&format!("{} #{} created", job_name, job_id)),
                 );
 
                 // Create a transfer record for the job
@@ -76,7 +92,7 @@ pub async fn send_receive_token(
                 request_map::update_status(
                     request_id,
                     StatusCode::SendReceiveTokenFailed,
-                    Some(&format!("Saved as claim #{}. Error creating swap job: {}", claim_id, e)),
+                    Some(&format!("Saved as claim #{}. Error creating {}: {}", claim_id, job_name, e)),
                 );
             }
         }
@@ -85,9 +101,11 @@ pub async fn send_receive_token(
         match match to_address {
             Address::AccountId(to_account_id) => icp_transfer(receive_amount, to_account_id, receive_token, None).await,
             Address::PrincipalId(to_principal_id) => icrc1_transfer(receive_amount, to_principal_id, receive_token, None).await,
-            Address::SolanaAddress(_) => {
-                // This should not happen as Solana tokens are handled above
-                Err(crate::ic::transfer::InternalTransferError::General("Solana addresses should be handled by Solana swap job creation".to_string()))
+            Address::SolanaAddress(_) | Address::RippleAddress(_) => {
+                // This should not happen as Solana and XRPL tokens are handled above
+                Err(crate::ic::transfer::InternalTransferError::General(
+                    "Solana and XRPL addresses should be handled by job creation".to_string(),
+                ))
             }
         } {
             Ok(tx_id) => {
@@ -124,25 +142,28 @@ pub async fn send_receive_token(
         }
     }
 
-    let swap_tx = SwapTx::new_success(
-        user_id,
-        request_id,
-        pay_token_id,
-        pay_amount,
-        receive_token_id,
-        receive_amount,
-        mid_price,
-        price,
-        slippage,
-        txs,
-        transfer_ids,
-        &claim_ids,
-        ts,
-    );
+    let swap_tx = SwapTx {
+        refund: refund.cloned(),
+        ..SwapTx::new_success(
+            user_id,
+            request_id,
+            pay_token_id,
+            pay_amount,
+            receive_token_id,
+            receive_amount,
+            mid_price,
+            price,
+            slippage,
+            txs,
+            transfer_ids,
+            claim_ids,
+            ts,
+        )
+    };
     let tx_id = tx_map::insert(&StableTx::Swap(swap_tx.clone()));
     let reply = match tx_map::get_by_user_and_token_id(Some(tx_id), None, None, None).first() {
         Some(StableTx::Swap(swap_tx)) => SwapReply::from(swap_tx),
-        _ => SwapReply::failed(request_id, pay_token, pay_amount, Some(receive_token), transfer_ids, &claim_ids, ts),
+        _ => SwapReply::failed(request_id, pay_token, pay_amount, Some(receive_token), transfer_ids, claim_ids, ts),
     };
     request_map::update_reply(request_id, Reply::Swap(reply.clone()));
 
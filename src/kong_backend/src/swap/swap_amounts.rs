@@ -2,21 +2,30 @@ use candid::Nat;
 use num::rational::BigRational;
 use num::{FromPrimitive, One, Zero};
 use num_traits::ToPrimitive;
+use std::collections::BTreeSet;
 
 use crate::helpers::math_helpers::price_rounded;
 use crate::helpers::math_helpers::round_f64;
 use crate::helpers::nat_helpers::nat_zero;
 use crate::helpers::nat_helpers::{
-    nat_add, nat_divide, nat_is_zero, nat_multiply, nat_multiply_f64, nat_subtract, nat_to_decimal_precision,
+    nat_add, nat_divide, nat_divide_ceil, nat_is_zero, nat_multiply, nat_multiply_f64, nat_subtract, nat_to_bigint,
+    nat_to_decimal_precision,
 };
-use crate::stable_pool::pool_map;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_pool::pool_type::PoolType;
 use crate::stable_pool::stable_pool::StablePool;
+use crate::stable_pool::{concentrated_liquidity, stable_swap_math, weighted_math};
 use crate::stable_token::stable_token::StableToken;
 use crate::stable_token::token::Token;
-use crate::stable_token::token_map;
 use crate::stable_user::user_map;
 
 use super::swap_calc::SwapCalc;
+use super::swap_router::{self, SwapLeg};
+
+// number of parts pay_amount is divided into when splitting a swap across routes
+const SPLIT_PARTS: u32 = 20;
+// maximum number of forward pricing corrections when solving an exact output swap
+const EXACT_OUT_MAX_ITERATIONS: usize = 10;
 
 /// calculate the receive_amount of a swap using mid price
 /// returns the receive_amount
@@ -28,20 +37,25 @@ pub fn swap_mid_amounts(pay_token: &StableToken, pay_amount: &Nat, receive_token
 }
 
 pub fn swap_mid_price(pay_token: &StableToken, receive_token: &StableToken) -> Result<f64, String> {
-    let (_, _, mid_price, _, _) = swap_amounts(pay_token, None, receive_token)?;
+    let (_, _, mid_price, _, _) = swap_amounts(pay_token, None, receive_token, 0)?;
     Ok(mid_price)
 }
 
 /// calculate the receive_amount of a swap using pool price (bid/offer, fee and gas included)
 /// returns the receive_amount, price, mid_price, slippage and the pools used
 ///
+/// all routes through the pools up to max_swap_hops are searched and the route with the
+/// best receive amount (or best mid price if pay_amount is None) is returned
+///
 /// pay_token - pay token
 /// pay_amount - amount of pay token. pay_amount is None if only mid price is requested
 /// receive_token - receive token
+/// user_fee_level - fee level of the user making the swap, see user_map::get_caller_fee_level()
 pub fn swap_amounts(
     pay_token: &StableToken,
     pay_amount: Option<&Nat>,
     receive_token: &StableToken,
+    user_fee_level: u8,
 ) -> Result<(Nat, f64, f64, f64, Vec<SwapCalc>), String> {
     let pay_token_id = pay_token.token_id();
     let receive_token_id = receive_token.token_id();
@@ -54,412 +68,441 @@ pub fn swap_amounts(
     }
 
     // if pay_amount is None, user_fee_level is None as only mid_price is needed
-    let user_fee_level = pay_amount.map(|_| user_map::get_by_caller().ok().flatten().unwrap_or_default().fee_level);
+    let user_fee_level = pay_amount.map(|_| user_fee_level);
 
-    // swaps stores all the swap permutations
-    let mut swaps: Vec<(Nat, f64, f64, f64, Vec<SwapCalc>)> = Vec::new();
+    let max_swap_hops = kong_settings_map::get().max_swap_hops;
+    let mut max_swap: Option<(Nat, f64, f64, f64, Vec<SwapCalc>)> = None;
+    let mut route_error = None;
+    for route in swap_router::get_routes(pay_token_id, receive_token_id, max_swap_hops) {
+        let swap = match route_swap_amounts(&route, pay_amount, user_fee_level) {
+            Ok(swap) => swap,
+            Err(e) => {
+                // keep the first error in case no route is valid
+                route_error.get_or_insert(e);
+                continue;
+            }
+        };
+        // routes are ordered by number of legs, only replace with a longer route if strictly better
+        let is_better = match &max_swap {
+            None => true,
+            // return the swap with the highest mid_price
+            Some(max_swap) if pay_amount.is_none() => swap.2 > max_swap.2,
+            // return the swap with the highest receive amount
+            Some(max_swap) => swap.0 > max_swap.0,
+        };
+        if is_better {
+            max_swap = Some(swap);
+        }
+    }
 
-    // 1-step swap
-    one_step_swaps(pay_token_id, pay_amount, receive_token_id, user_fee_level, &mut swaps)?;
+    max_swap.ok_or_else(|| route_error.unwrap_or_else(|| "Invalid swap".to_string()))
+}
 
-    // 2-step swap
-    two_step_swaps(pay_token_id, pay_amount, receive_token_id, user_fee_level, &mut swaps)?;
+/// calculate the receive_amount of a swap with pay_amount split across up to max_routes routes
+/// returns the receive_amount, price, mid_price, slippage and the pools used by all the routes
+///
+/// routes are picked best quote first and must not share any pool so the pool updates of one route
+/// do not change the amounts of another. pay_amount is allocated to the routes in SPLIT_PARTS parts,
+/// each part going to the route with the highest marginal receive amount before gas.
+/// the gas fee is only charged once, on the route with the largest receive amount.
+/// if splitting does not improve the receive amount, the best single route is returned
+///
+/// pay_token - pay token
+/// pay_amount - amount of pay token
+/// receive_token - receive token
+/// max_routes - maximum number of routes to split pay_amount into
+/// user_fee_level - fee level of the user making the swap
+pub fn split_swap_amounts(
+    pay_token: &StableToken,
+    pay_amount: &Nat,
+    receive_token: &StableToken,
+    max_routes: u8,
+    user_fee_level: u8,
+) -> Result<(Nat, f64, f64, f64, Vec<SwapCalc>), String> {
+    let single_swap = swap_amounts(pay_token, Some(pay_amount), receive_token, user_fee_level)?;
+    if max_routes < 2 || single_swap.4.is_empty() {
+        return Ok(single_swap);
+    }
 
-    // 3-step swap
-    three_step_swaps(pay_token_id, pay_amount, receive_token_id, user_fee_level, &mut swaps)?;
+    let user_fee_level = Some(user_fee_level);
+    let max_swap_hops = kong_settings_map::get().max_swap_hops;
 
-    let max_swap = if pay_amount.is_none() {
-        // return the swap with the highest mid_price
-        swaps
-            .into_iter()
-            .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
-            .ok_or("Invalid swap")?
-    } else {
-        // return the swap with the highest receive amount
-        swaps.into_iter().max_by(|a, b| a.0.cmp(&b.0)).ok_or("Invalid swap")?
-    };
+    // quote all routes with the full pay_amount and sort by best receive amount
+    let mut quotes: Vec<(Vec<SwapLeg>, Nat)> = swap_router::get_routes(pay_token.token_id(), receive_token.token_id(), max_swap_hops)
+        .into_iter()
+        .filter_map(|route| {
+            let swaps = route_swaps(&route, Some(pay_amount), user_fee_level, false).ok()?;
+            let receive_amount = swaps.last()?.receive_amount_with_fees_and_gas();
+            Some((route, receive_amount))
+        })
+        .collect();
+    quotes.sort_by(|a, b| b.1.cmp(&a.1));
+
+    // pick the best routes that do not share pools
+    let mut used_pool_ids = BTreeSet::new();
+    let mut routes: Vec<Vec<SwapLeg>> = Vec::new();
+    for (route, _) in quotes {
+        if routes.len() >= max_routes as usize {
+            break;
+        }
+        if route.iter().any(|leg| used_pool_ids.contains(&leg.pool.pool_id)) {
+            continue;
+        }
+        used_pool_ids.extend(route.iter().map(|leg| leg.pool.pool_id));
+        routes.push(route);
+    }
+    if routes.len() < 2 {
+        return Ok(single_swap);
+    }
 
-    Ok(max_swap)
+    // allocate pay_amount on the receive amounts before gas, gas is only paid once for the whole swap
+    let pay_amounts = split_pay_amount(pay_amount, routes.len(), |i, amount| {
+        route_swaps(&routes[i], Some(amount), user_fee_level, false)
+            .ok()
+            .and_then(|swaps| swaps.last().map(|swap| swap.receive_amount_with_fees_and_gas()))
+            .unwrap_or(nat_zero())
+    })?;
+
+    // calculate the final swaps of each route. the route with the largest receive amount is charged the gas fee
+    let mut route_swaps_before_gas = Vec::with_capacity(routes.len());
+    for (route, route_pay_amount) in routes.iter().zip(pay_amounts.iter()) {
+        if nat_is_zero(route_pay_amount) {
+            continue;
+        }
+        let swaps = route_swaps(route, Some(route_pay_amount), user_fee_level, false)?;
+        route_swaps_before_gas.push((route, route_pay_amount, swaps));
+    }
+    let gas_route_index = route_swaps_before_gas
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| {
+            let a = a.2.last().map(|swap| swap.receive_amount_with_fees_and_gas());
+            let b = b.2.last().map(|swap| swap.receive_amount_with_fees_and_gas());
+            a.cmp(&b)
+        })
+        .map(|(i, _)| i)
+        .ok_or("Invalid swap")?;
+
+    // price and mid_price are weighted by the pay amount of each route
+    let mut receive_amount = nat_zero();
+    let mut price = BigRational::zero();
+    let mut mid_price = BigRational::zero();
+    let mut swaps = Vec::new();
+    let total_pay_amount = BigRational::from_integer(nat_to_bigint(pay_amount));
+    for (i, (route, route_pay_amount, route_swaps_before_gas)) in route_swaps_before_gas.into_iter().enumerate() {
+        let route_swaps = if i == gas_route_index {
+            route_swaps(route, Some(route_pay_amount), user_fee_level, true)?
+        } else {
+            route_swaps_before_gas
+        };
+        let (route_price, route_mid_price) = route_prices(&route_swaps);
+        let weight = BigRational::from_integer(nat_to_bigint(route_pay_amount)) / &total_pay_amount;
+        receive_amount = nat_add(
+            &receive_amount,
+            &route_swaps.last().ok_or("Invalid swap")?.receive_amount_with_fees_and_gas(),
+        );
+        price += route_price * &weight;
+        mid_price += route_mid_price * &weight;
+        swaps.extend(route_swaps);
+    }
+
+    // only split if it gives a better receive amount than the best single route
+    if receive_amount <= single_swap.0 {
+        return Ok(single_swap);
+    }
+
+    let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
+    let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
+    let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
+
+    Ok((receive_amount, price_f64, mid_price_f64, slippage_f64, swaps))
 }
 
-/// returns (receive_amount_with_gas_and_fees, price, mid_price, slippage, swap)
+/// allocate pay_amount to num_routes routes in SPLIT_PARTS parts, each part going to the route with the highest
+/// marginal receive amount. route_receive_amount(i, amount) is the receive amount of route i for amount
+/// returns the pay amount of each route
+fn split_pay_amount(pay_amount: &Nat, num_routes: usize, route_receive_amount: impl Fn(usize, &Nat) -> Nat) -> Result<Vec<Nat>, String> {
+    let part_amount = nat_divide(pay_amount, &Nat::from(SPLIT_PARTS)).ok_or("Invalid pay amount")?;
+    let mut pay_amounts = vec![nat_zero(); num_routes];
+    let mut receive_amounts = vec![nat_zero(); num_routes];
+    let mut allocated_amount = nat_zero();
+    for part in 0..SPLIT_PARTS {
+        // last part takes any remainder from the integer division
+        let amount = if part == SPLIT_PARTS - 1 {
+            nat_subtract(pay_amount, &allocated_amount).unwrap_or(nat_zero())
+        } else {
+            part_amount.clone()
+        };
+        if nat_is_zero(&amount) {
+            continue;
+        }
+        let mut best: Option<(usize, Nat, Nat)> = None; // (route index, marginal receive amount, new receive amount)
+        for i in 0..num_routes {
+            let receive_amount = route_receive_amount(i, &nat_add(&pay_amounts[i], &amount));
+            let marginal_amount = nat_subtract(&receive_amount, &receive_amounts[i]).unwrap_or(nat_zero());
+            if best
+                .as_ref()
+                .is_none_or(|(_, best_marginal_amount, _)| marginal_amount > *best_marginal_amount)
+            {
+                best = Some((i, marginal_amount, receive_amount));
+            }
+        }
+        let (i, _, receive_amount) = best.ok_or("Invalid swap")?;
+        pay_amounts[i] = nat_add(&pay_amounts[i], &amount);
+        receive_amounts[i] = receive_amount;
+        allocated_amount = nat_add(&allocated_amount, &amount);
+    }
+    Ok(pay_amounts)
+}
+
+/// calculate the swaps along a route and the prices of the route
+/// returns (receive_amount_with_gas_and_fees, price, mid_price, slippage, swaps)
 #[allow(clippy::complexity)]
-fn one_step_swaps(
-    pay_token_id: u32,
+fn route_swap_amounts(
+    route: &[SwapLeg],
     pay_amount: Option<&Nat>,
-    receive_token_id: u32,
     user_fee_level: Option<u8>,
-    swaps: &mut Vec<(Nat, f64, f64, f64, Vec<SwapCalc>)>,
-) -> Result<(), String> {
-    let swap = if let Some(pool) = pool_map::get_by_token_ids(pay_token_id, receive_token_id) {
-        swap_amount_0(&pool, pay_amount, user_fee_level, None, None)?
-    } else if let Some(pool) = pool_map::get_by_token_ids(receive_token_id, pay_token_id) {
-        swap_amount_1(&pool, pay_amount, user_fee_level, None, None)?
-    } else {
-        return Ok(());
-    };
-
+) -> Result<(Nat, f64, f64, f64, Vec<SwapCalc>), String> {
+    let swaps = route_swaps(route, pay_amount, user_fee_level, true)?;
+    let (price, mid_price) = route_prices(&swaps);
+    let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
     if pay_amount.is_none() {
         // if pay_amount is None, return the mid price
-        let mid_price = swap.get_mid_price().unwrap_or(BigRational::zero());
-        let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-        swaps.push((nat_zero(), mid_price_f64, mid_price_f64, 0.0, vec![swap]));
-    } else {
-        let receive_amount = swap.receive_amount_with_fees_and_gas();
-        let price = swap.get_price().unwrap_or(BigRational::zero());
-        let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
-        let mid_price = swap.get_mid_price().unwrap_or(BigRational::zero());
-        let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-        let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
-        swaps.push((receive_amount, price_f64, mid_price_f64, slippage_f64, vec![swap]));
+        return Ok((nat_zero(), mid_price_f64, mid_price_f64, 0.0, swaps));
     }
 
-    Ok(())
+    let receive_amount = swaps.last().ok_or("Invalid swap")?.receive_amount_with_fees_and_gas();
+    let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
+    let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
+
+    Ok((receive_amount, price_f64, mid_price_f64, slippage_f64, swaps))
 }
 
-#[allow(clippy::complexity)]
-fn two_step_swaps(
-    pay_token_id: u32,
-    pay_amount: Option<&Nat>,
-    receive_token_id: u32,
-    user_fee_level: Option<u8>,
-    swaps: &mut Vec<(Nat, f64, f64, f64, Vec<SwapCalc>)>,
-) -> Result<(), String> {
-    let ckusdt_token_id = token_map::get_ckusdt()?.token_id();
-    let icp_token_id = token_map::get_icp()?.token_id();
-
-    // test for 2-step swap via ckUSDT
-    // token0/ckUSDT -> ckUSDT/token1. make sure:
-    // 1) token0 != ckUSDT
-    // 2) token1 != ckUSDT
-    if pay_token_id != ckusdt_token_id && receive_token_id != ckusdt_token_id {
-        let pool1_ckusdt = pool_map::get_by_token_ids(pay_token_id, ckusdt_token_id);
-        let pool2_ckusdt = pool_map::get_by_token_ids(receive_token_id, ckusdt_token_id);
-        if pool1_ckusdt.is_some() && pool2_ckusdt.is_some() {
-            // 2-step swap
-            // split the LP fee between the two swaps. the "+ 1) / 2" will round up the integer
-            // 1st swap no gas fees as this is intermediate swap
-            // 2nd swap use standard gas fees
-            let pool1 = pool1_ckusdt.as_ref().unwrap();
-            let pool2 = pool2_ckusdt.as_ref().unwrap();
-            let swap1_lp_fee = (pool1.lp_fee_bps + 1) / 2;
-            // swap token0 to ckUSDT
-            let swap1 = swap_amount_0(pool1, pay_amount, user_fee_level, Some(swap1_lp_fee), Some(&nat_zero()))?;
-            let swap2_lp_fee = (pool2.lp_fee_bps + 1) / 2;
-            // swap ckUSDT to token1 (reverse order of pool)
-            let swap2 = swap_amount_1(
-                pool2,
-                Some(&swap1.receive_amount_with_fees_and_gas()),
-                user_fee_level,
-                Some(swap2_lp_fee),
-                None,
-            )?;
-            if pay_amount.is_none() {
-                // if pay_amount is None, return the mid price
-                let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-                let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-                let mid_price = swap1_mid_price * swap2_mid_price;
-                let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-                swaps.push((nat_zero(), mid_price_f64, mid_price_f64, 0.0, vec![swap1, swap2]));
-            } else {
-                let receive_amount = swap2.receive_amount_with_fees_and_gas();
-                let swap1_price = swap1.get_price().unwrap_or(BigRational::zero());
-                let swap2_price = swap2.get_price().unwrap_or(BigRational::zero());
-                let price = swap1_price * swap2_price;
-                let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
-                let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-                let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-                let mid_price = swap1_mid_price * swap2_mid_price;
-                let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-                let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
-                swaps.push((receive_amount, price_f64, mid_price_f64, slippage_f64, vec![swap1, swap2]));
-            }
-        }
+/// calculate the swaps along a route. the output of each leg is the input of the next leg
+/// charge_gas is false for the routes of a split swap that do not pay the gas fee
+fn route_swaps(route: &[SwapLeg], pay_amount: Option<&Nat>, user_fee_level: Option<u8>, charge_gas: bool) -> Result<Vec<SwapCalc>, String> {
+    let num_legs = route.len();
+    let mut swaps: Vec<SwapCalc> = Vec::with_capacity(num_legs);
+    for (i, leg) in route.iter().enumerate() {
+        // pay amount of each leg is the receive amount of the previous leg
+        let leg_pay_amount = match swaps.last() {
+            Some(prev_swap) => pay_amount.map(|_| prev_swap.receive_amount_with_fees_and_gas()),
+            None => pay_amount.cloned(),
+        };
+        // split the LP fee between the legs of a multi-hop swap. the "+ 1) / num_legs" will round up the integer
+        let use_lp_fee = if num_legs > 1 {
+            Some(((leg.pool.live_lp_fee_bps() as usize + 1) / num_legs) as u8)
+        } else {
+            None
+        };
+        // intermediate legs do not take gas fees, last leg uses standard gas fees
+        let use_gas_fee = if i < num_legs - 1 || !charge_gas { Some(nat_zero()) } else { None };
+        let swap = if leg.pays_token_0 {
+            swap_amount_0(&leg.pool, leg_pay_amount.as_ref(), user_fee_level, use_lp_fee, use_gas_fee.as_ref())?
+        } else {
+            // reverse order of pool
+            swap_amount_1(&leg.pool, leg_pay_amount.as_ref(), user_fee_level, use_lp_fee, use_gas_fee.as_ref())?
+        };
+        swaps.push(swap);
     }
+    Ok(swaps)
+}
 
-    // test for 2-step swap via ICP
-    // token0/ICP -> ICP/token1. make sure:
-    // 1) token0 != ICP
-    // 2) token1 != ICP
-    if pay_token_id != icp_token_id && receive_token_id != icp_token_id {
-        let pool1_icp = pool_map::get_by_token_ids(pay_token_id, icp_token_id);
-        let pool2_icp = pool_map::get_by_token_ids(receive_token_id, icp_token_id);
-        if pool1_icp.is_some() && pool2_icp.is_some() {
-            // 2 pools: token0/ICP and token1/ICP routing token0 -> ICP -> token1
-            let pool1 = pool1_icp.as_ref().unwrap();
-            let pool2 = pool2_icp.as_ref().unwrap();
-            let swap1_lp_fee = (pool1.lp_fee_bps + 1) / 2;
-            // swap token0 to ICP
-            let swap1 = swap_amount_0(pool1, pay_amount, user_fee_level, Some(swap1_lp_fee), Some(&nat_zero()))?;
-            let swap2_lp_fee = (pool2.lp_fee_bps + 1) / 2;
-            // swap ICP to token1 (reverse order of pool)
-            let swap2 = swap_amount_1(
-                pool2,
-                Some(&swap1.receive_amount_with_fees_and_gas()),
-                user_fee_level,
-                Some(swap2_lp_fee),
-                None,
-            )?;
-            if pay_amount.is_none() {
-                // if pay_amount is None, return the mid price
-                let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-                let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-                let mid_price = swap1_mid_price * swap2_mid_price;
-                let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-                swaps.push((nat_zero(), mid_price_f64, mid_price_f64, 0.0, vec![swap1, swap2]));
-            } else {
-                let receive_amount = swap2.receive_amount_with_fees_and_gas();
-                let swap1_price = swap1.get_price().unwrap_or(BigRational::zero());
-                let swap2_price = swap2.get_price().unwrap_or(BigRational::zero());
-                let price = swap1_price * swap2_price;
-                let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
-                let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-                let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-                let mid_price = swap1_mid_price * swap2_mid_price;
-                let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-                let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
-                swaps.push((receive_amount, price_f64, mid_price_f64, slippage_f64, vec![swap1, swap2]));
-            };
-        }
+/// price and mid price of a route are the products of the prices of each leg
+/// returns (price, mid_price)
+fn route_prices(swaps: &[SwapCalc]) -> (BigRational, BigRational) {
+    let price = swaps.iter().fold(BigRational::one(), |acc, swap| {
+        acc * swap.get_price().unwrap_or(BigRational::zero())
+    });
+    let mid_price = swaps.iter().fold(BigRational::one(), |acc, swap| {
+        acc * swap.get_mid_price().unwrap_or(BigRational::zero())
+    });
+    (price, mid_price)
+}
+
+/// calculate the pay_amount required to receive receive_amount (fees and gas included)
+/// returns the pay_amount, receive_amount, price, mid_price, slippage and the pools used
+///
+/// each route is solved backwards from the last leg with the inverse of the constant product formula
+/// and then priced forwards to correct for rounding, so receive_amount may be slightly more than requested.
+/// the route with the lowest pay_amount is returned
+///
+/// pay_token - pay token
+/// receive_token - receive token
+/// receive_amount - amount of receive token to receive after fees and gas
+/// user_fee_level - fee level of the user making the swap
+#[allow(clippy::type_complexity)]
+pub fn swap_amounts_exact_out(
+    pay_token: &StableToken,
+    receive_token: &StableToken,
+    receive_amount: &Nat,
+    user_fee_level: u8,
+) -> Result<(Nat, Nat, f64, f64, f64, Vec<SwapCalc>), String> {
+    let pay_token_id = pay_token.token_id();
+    let receive_token_id = receive_token.token_id();
+
+    if nat_is_zero(receive_amount) {
+        Err("Receive amount is zero".to_string())?;
     }
 
-    // special case where pay token is ckUSDT so need to use
-    // ckUSDT/ICP -> token1/ICP
-    // because there's no ckUSDT/ICP pool so need to use ICP/ckUSDT pool
-    let pool1_icp_ckusdt = pool_map::get_by_token_ids(icp_token_id, ckusdt_token_id);
-    let pool2_icp = pool_map::get_by_token_ids(receive_token_id, icp_token_id);
-    if pay_token_id == ckusdt_token_id && pool1_icp_ckusdt.is_some() && pool2_icp.is_some() {
-        let pool1 = pool1_icp_ckusdt.as_ref().unwrap();
-        let pool2 = pool2_icp.as_ref().unwrap();
-        let swap1_lp_fee = (pool1.lp_fee_bps + 1) / 2;
-        // swap ckUSDT to ICP (reverse order of pool)
-        let swap1 = swap_amount_1(pool1, pay_amount, user_fee_level, Some(swap1_lp_fee), Some(&nat_zero()))?;
-        let swap2_lp_fee = (pool2.lp_fee_bps + 1) / 2;
-        // swap ICP to token1 (reverse order of pool)
-        let swap2 = swap_amount_1(
-            pool2,
-            Some(&swap1.receive_amount_with_fees_and_gas()),
-            user_fee_level,
-            Some(swap2_lp_fee),
-            None,
-        )?;
-        if pay_amount.is_none() {
-            // if pay_amount is None, return the mid price
-            let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-            let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-            let mid_price = swap1_mid_price * swap2_mid_price;
-            let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-            swaps.push((nat_zero(), mid_price_f64, mid_price_f64, 0.0, vec![swap1, swap2]));
-        } else {
-            let receive_amount = swap2.receive_amount_with_fees_and_gas();
-            let swap1_price = swap1.get_price().unwrap_or(BigRational::zero());
-            let swap2_price = swap2.get_price().unwrap_or(BigRational::zero());
-            let price = swap1_price * swap2_price;
-            let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
-            let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-            let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-            let mid_price = swap1_mid_price * swap2_mid_price;
-            let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-            let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
-            swaps.push((receive_amount, price_f64, mid_price_f64, slippage_f64, vec![swap1, swap2]));
-        }
-    };
+    // if tokens are the same return the same amount
+    if pay_token_id == receive_token_id {
+        return Ok((receive_amount.clone(), receive_amount.clone(), 1.0, 1.0, 0.0, Vec::new()));
+    }
 
-    // special case where receieve token is ckUSDT so need to use
-    // token0/ICP -> ICP/ckUSDT
-    let pool1_icp = pool_map::get_by_token_ids(pay_token_id, icp_token_id);
-    let pool2_icp_ckusdt = pool_map::get_by_token_ids(icp_token_id, ckusdt_token_id);
-    if receive_token_id == ckusdt_token_id && pool1_icp.is_some() && pool2_icp_ckusdt.is_some() {
-        let pool1 = pool1_icp.as_ref().unwrap();
-        let pool2 = pool2_icp_ckusdt.as_ref().unwrap();
-        let swap1_lp_fee = (pool1.lp_fee_bps + 1) / 2;
-        // swap token0 to ICP
-        let swap1 = swap_amount_0(pool1, pay_amount, user_fee_level, Some(swap1_lp_fee), Some(&nat_zero()))?;
-        let swap2_lp_fee = (pool2.lp_fee_bps + 1) / 2;
-        // swap ICP to ckUSDT
-        let swap2 = swap_amount_0(
-            pool2,
-            Some(&swap1.receive_amount_with_fees_and_gas()),
-            user_fee_level,
-            Some(swap2_lp_fee),
-            None,
-        )?;
-        if pay_amount.is_none() {
-            // if pay_amount is None, return the mid price
-            let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-            let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-            let mid_price = swap1_mid_price * swap2_mid_price;
-            let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-            swaps.push((nat_zero(), mid_price_f64, mid_price_f64, 0.0, vec![swap1, swap2]));
-        } else {
-            let receive_amount = swap2.receive_amount_with_fees_and_gas();
-            let swap1_price = swap1.get_price().unwrap_or(BigRational::zero());
-            let swap2_price = swap2.get_price().unwrap_or(BigRational::zero());
-            let price = swap1_price * swap2_price;
-            let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
-            let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-            let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-            let mid_price = swap1_mid_price * swap2_mid_price;
-            let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-            let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
-            swaps.push((receive_amount, price_f64, mid_price_f64, slippage_f64, vec![swap1, swap2]));
+    let user_fee_level = Some(user_fee_level);
+
+    let max_swap_hops = kong_settings_map::get().max_swap_hops;
+    let mut min_swap: Option<(Nat, Nat, f64, f64, f64, Vec<SwapCalc>)> = None;
+    let mut route_error = None;
+    for route in swap_router::get_routes(pay_token_id, receive_token_id, max_swap_hops) {
+        let swap = match route_exact_out_amounts(&route, receive_amount, user_fee_level) {
+            Ok(swap) => swap,
+            Err(e) => {
+                // keep the first error in case no route is valid
+                route_error.get_or_insert(e);
+                continue;
+            }
+        };
+        // routes are ordered by number of legs, only replace with a longer route if strictly cheaper
+        if min_swap.as_ref().is_none_or(|min_swap| swap.0 < min_swap.0) {
+            min_swap = Some(swap);
         }
-    };
+    }
 
-    Ok(())
+    min_swap.ok_or_else(|| route_error.unwrap_or_else(|| "Invalid swap".to_string()))
 }
 
-#[allow(clippy::complexity)]
-fn three_step_swaps(
-    pay_token_id: u32,
-    pay_amount: Option<&Nat>,
-    receive_token_id: u32,
+/// solve a route for the pay_amount to receive receive_amount
+/// returns (pay_amount, receive_amount_with_gas_and_fees, price, mid_price, slippage, swaps)
+#[allow(clippy::type_complexity)]
+fn route_exact_out_amounts(
+    route: &[SwapLeg],
+    receive_amount: &Nat,
     user_fee_level: Option<u8>,
-    swaps: &mut Vec<(Nat, f64, f64, f64, Vec<SwapCalc>)>,
-) -> Result<(), String> {
-    let ckusdt_token_id = token_map::get_ckusdt()?.token_id();
-    let icp_token_id = token_map::get_icp()?.token_id();
-    let pool2_icp_ckusdt = pool_map::get_by_token_ids(icp_token_id, ckusdt_token_id);
-
-    // token0/ckUSDT -> ckUSDT/ICP -> ICP/token1. make sure:
-    // 1) token0 != ckUSDT
-    // 2) token0 != ICP && token1 != ckUSDT
-    // 3) token1 != ICP
-    if pay_token_id != ckusdt_token_id
-        && (pay_token_id != icp_token_id && receive_token_id != ckusdt_token_id)
-        && receive_token_id != icp_token_id
-    {
-        let pool1_ckusdt = pool_map::get_by_token_ids(pay_token_id, ckusdt_token_id);
-        let pool3_icp = pool_map::get_by_token_ids(receive_token_id, icp_token_id);
-        if pool1_ckusdt.is_some() && pool2_icp_ckusdt.is_some() && pool3_icp.is_some() {
-            let pool1 = pool1_ckusdt.as_ref().unwrap();
-            let pool2 = pool2_icp_ckusdt.as_ref().unwrap();
-            let pool3 = pool3_icp.as_ref().unwrap();
-            let swap1_lp_fee = (pool1.lp_fee_bps + 1) / 3;
-            // swap token0 to ckUSDT
-            let swap1 = swap_amount_0(
-                pool1,
-                pay_amount,
-                user_fee_level,
-                Some(swap1_lp_fee),
-                Some(&nat_zero()), // swap1 do not take gas fees
-            )?;
-            let swap2_lp_fee = (pool2.lp_fee_bps + 1) / 3;
-            // swap ckUSDT to ICP (reverse order of pool)
-            let swap2 = swap_amount_1(
-                pool2,
-                Some(&swap1.receive_amount_with_fees_and_gas()),
-                user_fee_level,
-                Some(swap2_lp_fee),
-                Some(&nat_zero()), // swap2 do not take gas fees
-            )?;
-            let swap3_lp_fee = (pool3.lp_fee_bps + 1) / 3;
-            // swap ICP to token1 (reverse order of pool)
-            let swap3 = swap_amount_1(
-                pool3,
-                Some(&swap2.receive_amount_with_fees_and_gas()),
-                user_fee_level,
-                Some(swap3_lp_fee),
-                None,
-            )?;
-            if pay_amount.is_none() {
-                // if pay_amount is None, return the mid price
-                let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-                let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-                let swap3_mid_price = swap3.get_mid_price().unwrap_or(BigRational::zero());
-                let mid_price = swap1_mid_price * swap2_mid_price * swap3_mid_price;
-                let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-                swaps.push((nat_zero(), mid_price_f64, mid_price_f64, 0.0, vec![swap1, swap2, swap3]));
-            } else {
-                let receive_amount = swap3.receive_amount_with_fees_and_gas();
-                let swap1_price = swap1.get_price().unwrap_or(BigRational::zero());
-                let swap2_price = swap2.get_price().unwrap_or(BigRational::zero());
-                let swap3_price = swap3.get_price().unwrap_or(BigRational::zero());
-                let price = swap1_price * swap2_price * swap3_price;
-                let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
-                let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-                let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-                let swap3_mid_price = swap3.get_mid_price().unwrap_or(BigRational::zero());
-                let mid_price = swap1_mid_price * swap2_mid_price * swap3_mid_price;
-                let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-                let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
-                swaps.push((receive_amount, price_f64, mid_price_f64, slippage_f64, vec![swap1, swap2, swap3]));
-            }
+) -> Result<(Nat, Nat, f64, f64, f64, Vec<SwapCalc>), String> {
+    let mut pay_amount = route_pay_amount(route, receive_amount, user_fee_level)?;
+    // forward pricing can be a few units short because of integer rounding. increase pay_amount by the shortfall until covered
+    for _ in 0..EXACT_OUT_MAX_ITERATIONS {
+        let (route_receive_amount, price, mid_price, slippage, swaps) = route_swap_amounts(route, Some(&pay_amount), user_fee_level)?;
+        let Some(shortfall) = nat_subtract(receive_amount, &route_receive_amount).filter(|shortfall| !nat_is_zero(shortfall)) else {
+            return Ok((pay_amount, route_receive_amount, price, mid_price, slippage, swaps));
         };
+        // pay_amount += pay_amount * shortfall / receive_amount + 1
+        let increment = nat_divide(&nat_multiply(&pay_amount, &shortfall), receive_amount).ok_or("Invalid pay amount")?;
+        pay_amount = nat_add(&pay_amount, &(increment + 1_u32));
     }
 
-    // token0/ICP -> ICP/ckUSDT -> ckUSDT/token1. make sure:
-    // 1) token0 != ICP
-    // 2) token0 != ckUSDT && token1 != ICP
-    // 3) token1 != ckUSDT
-    if pay_token_id != icp_token_id
-        && (pay_token_id != ckusdt_token_id && receive_token_id != icp_token_id)
-        && receive_token_id != ckusdt_token_id
-    {
-        let pool1_icp = pool_map::get_by_token_ids(pay_token_id, icp_token_id);
-        let pool3_ckusdt = pool_map::get_by_token_ids(receive_token_id, ckusdt_token_id);
-        if pool1_icp.is_some() && pool2_icp_ckusdt.is_some() && pool3_ckusdt.is_some() {
-            let pool1 = pool1_icp.as_ref().unwrap();
-            let pool2 = pool2_icp_ckusdt.as_ref().unwrap();
-            let pool3 = pool3_ckusdt.as_ref().unwrap();
-            let swap1_lp_fee = (pool1.lp_fee_bps + 1) / 3;
-            // swap token0 to ICP
-            let swap1 = swap_amount_0(
-                pool1,
-                pay_amount,
-                user_fee_level,
-                Some(swap1_lp_fee),
-                Some(&nat_zero()), // swap1 do not take gas fees
-            )?;
-            let swap2_lp_fee = (pool2.lp_fee_bps + 1) / 3;
-            // swap ICP to ckUSDT
-            let swap2 = swap_amount_0(
-                pool2,
-                Some(&swap1.receive_amount_with_fees_and_gas()),
-                user_fee_level,
-                Some(swap2_lp_fee),
-                Some(&nat_zero()), // swap2 do not take gas fees
-            )?;
-            let swap3_lp_fee = (pool3.lp_fee_bps + 1) / 3;
-            // swap ckUSDT to token1 (reverse order of pool)
-            let swap3 = swap_amount_1(
-                pool3,
-                Some(&swap2.receive_amount_with_fees_and_gas()),
-                user_fee_level,
-                Some(swap3_lp_fee),
-                None,
-            )?;
-            if pay_amount.is_none() {
-                // if pay_amount is None, return the mid price
-                let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-                let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-                let swap3_mid_price = swap3.get_mid_price().unwrap_or(BigRational::zero());
-                let mid_price = swap1_mid_price * swap2_mid_price * swap3_mid_price;
-                let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-                swaps.push((nat_zero(), mid_price_f64, mid_price_f64, 0.0, vec![swap1, swap2, swap3]));
-            } else {
-                let receive_amount = swap3.receive_amount_with_fees_and_gas();
-                let swap1_price = swap1.get_price().unwrap_or(BigRational::zero());
-                let swap2_price = swap2.get_price().unwrap_or(BigRational::zero());
-                let swap3_price = swap3.get_price().unwrap_or(BigRational::zero());
-                let price = swap1_price * swap2_price * swap3_price;
-                let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
-                let swap1_mid_price = swap1.get_mid_price().unwrap_or(BigRational::zero());
-                let swap2_mid_price = swap2.get_mid_price().unwrap_or(BigRational::zero());
-                let swap3_mid_price = swap3.get_mid_price().unwrap_or(BigRational::zero());
-                let mid_price = swap1_mid_price * swap2_mid_price * swap3_mid_price;
-                let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
-                let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
-                swaps.push((receive_amount, price_f64, mid_price_f64, slippage_f64, vec![swap1, swap2, swap3]));
-            }
+    Err("Unable to calculate pay amount".to_string())
+}
+
+/// solve a route backwards, from the receive_amount of the last leg to the pay_amount of the first leg
+/// inverse of swap_amount_0/swap_amount_1 with the same LP fee split and gas fees as route_swaps
+fn route_pay_amount(route: &[SwapLeg], receive_amount: &Nat, user_fee_level: Option<u8>) -> Result<Nat, String> {
+    let num_legs = route.len();
+    // receive amount of the leg with fees and gas
+    let mut amount_out = receive_amount.clone();
+    for (i, leg) in route.iter().enumerate().rev() {
+        let pool = &leg.pool;
+        let (pay_token, receive_token, reserve_in, reserve_out) = if leg.pays_token_0 {
+            (
+                pool.token_0(),
+                pool.token_1(),
+                nat_add(&pool.balance_0, &pool.lp_fee_0),
+                nat_add(&pool.balance_1, &pool.lp_fee_1),
+            )
+        } else {
+            (
+                pool.token_1(),
+                pool.token_0(),
+                nat_add(&pool.balance_1, &pool.lp_fee_1),
+                nat_add(&pool.balance_0, &pool.lp_fee_0),
+            )
+        };
+
+        // same LP fee and gas fee as route_swaps
+        let live_lp_fee_bps = pool.live_lp_fee_bps();
+        let use_lp_fee = if num_legs > 1 {
+            ((live_lp_fee_bps as usize + 1) / num_legs) as u8
+        } else {
+            live_lp_fee_bps
+        };
+        let user_lp_fee_pct = 100_u8.saturating_sub(user_fee_level.unwrap_or(0_u8));
+        let user_lp_fee_bps = (user_lp_fee_pct as u32 * use_lp_fee as u32) / 100;
+        let gas_fee = if i < num_legs - 1 {
+            nat_zero()
+        } else {
+            receive_gas_fee(&pay_token, &receive_token)
         };
+
+        // add back the LP fee and gas fee
+        // amount_out_before_fees = (amount_out + gas_fee) * 10_000 / (10_000 - user_lp_fee_bps)
+        let amount_out_before_fees = nat_divide_ceil(
+            &nat_multiply(&nat_add(&amount_out, &gas_fee), &Nat::from(10_000_u32)),
+            &Nat::from(10_000_u32.saturating_sub(user_lp_fee_bps)),
+        )
+        .ok_or("Invalid LP fee")?;
+
+        // convert amount_out and pool reserves to the max_decimals precision
+        let max_decimals = std::cmp::max(pay_token.decimals(), receive_token.decimals());
+        let reserve_in_in_max_decimals = nat_to_decimal_precision(&reserve_in, pay_token.decimals(), max_decimals);
+        let reserve_out_in_max_decimals = nat_to_decimal_precision(&reserve_out, receive_token.decimals(), max_decimals);
+        let amount_out_in_max_decimals = nat_to_decimal_precision(&amount_out_before_fees, receive_token.decimals(), max_decimals);
+        if amount_out_in_max_decimals >= reserve_out_in_max_decimals {
+            Err(format!("Insufficient {} in pool", receive_token.symbol()))?
+        }
+
+        let amount_in_in_max_decimals = pool_amount_in(
+            pool,
+            leg.pays_token_0,
+            &reserve_in_in_max_decimals,
+            &reserve_out_in_max_decimals,
+            &amount_out_in_max_decimals,
+        )
+        .ok_or("Invalid amount_in")?;
+        let mut amount_in = nat_to_decimal_precision(&amount_in_in_max_decimals, max_decimals, pay_token.decimals());
+
+        // add back the SPL gas fee deducted from the pay amount. like the gas fee, only the last leg deducts it
+        if i == num_legs - 1 && is_spl_requiring_gas_deduction(&receive_token) {
+            if let Ok(spl_fee) = calculate_spl_gas_fee(&pay_token, &receive_token) {
+                amount_in = nat_add(&amount_in, &spl_fee);
+            }
+        }
+
+        amount_out = amount_in;
     }
 
-    Ok(())
+    Ok(amount_out)
+}
+
+/// calculate the receive_amount of a swap within a single pool. the receive token stays in Kong so there is no gas fee
+/// used by zap_in() to swap part of a deposit into the other token of the pool
+/// returns (receive_amount_with_gas_and_fees, price, mid_price, slippage, swap)
+#[allow(clippy::type_complexity)]
+pub fn swap_pool_amounts(pool: &StablePool, pays_token_0: bool, pay_amount: &Nat) -> Result<(Nat, f64, f64, f64, SwapCalc), String> {
+    let user_fee_level = Some(user_map::get_caller_fee_level());
+    let swap = if pays_token_0 {
+        swap_amount_0(pool, Some(pay_amount), user_fee_level, None, Some(&nat_zero()))?
+    } else {
+        swap_amount_1(pool, Some(pay_amount), user_fee_level, None, Some(&nat_zero()))?
+    };
+
+    let (price, mid_price) = route_prices(std::slice::from_ref(&swap));
+    let price_f64 = price_rounded(&price).ok_or("Invalid price")?;
+    let mid_price_f64 = price_rounded(&mid_price).ok_or("Invalid mid price")?;
+    let slippage_f64 = get_slippage(&price, &mid_price).unwrap_or(0_f64);
+
+    Ok((
+        swap.receive_amount_with_fees_and_gas(),
+        price_f64,
+        mid_price_f64,
+        slippage_f64,
+        swap,
+    ))
 }
 
 /// Swap amount 0 of a given pool
-/// use_lp_fee and use_gas_fee are used to overwrite the default LP and gas fees, if None, then use the pool's default
+/// use_lp_fee and use_gas_fee are used to overwrite the default LP and gas fees, if None, then use the pool's live LP fee and default gas fee
 fn swap_amount_0(
     pool: &StablePool,
     amount_0: Option<&Nat>,
@@ -486,6 +529,7 @@ fn swap_amount_0(
             receive_amount: nat_zero(),
             lp_fee: nat_zero(),
             gas_fee: nat_zero(),
+            lp_fee_bps: 0,
         });
     }
 
@@ -500,13 +544,14 @@ fn swap_amount_0(
                 receive_amount: nat_zero(),
                 lp_fee: nat_zero(),
                 gas_fee: nat_zero(),
+                lp_fee_bps: 0,
             });
         }
         Some(amount) => amount,
     };
 
-    // Reduce amount_0 by SPL gas fee if receive token is SPL
-    let effective_amount_0 = if is_spl_requiring_gas_deduction(&token_1) {
+    // Reduce amount_0 by SPL gas fee if receive token is SPL, unless the gas fee is overwritten (intermediate legs and split routes)
+    let effective_amount_0 = if use_gas_fee.is_none() && is_spl_requiring_gas_deduction(&token_1) {
         match calculate_spl_gas_fee(&token_0, &token_1) {
             Ok(spl_fee) => {
                 if spl_fee <= *amount_0 {
@@ -527,10 +572,14 @@ fn swap_amount_0(
     let reserve_1_in_max_decimals = nat_to_decimal_precision(&reserve_1, token_1.decimals(), max_decimals);
     let amount_0_in_max_decimals = nat_to_decimal_precision(&effective_amount_0, token_0.decimals(), max_decimals);
 
-    // amount_1 = (amount_0 * reserve_1) / (reserve_0 + amount_0)
-    let numerator_in_max_decimals = nat_multiply(&amount_0_in_max_decimals, &reserve_1_in_max_decimals);
-    let denominator_in_max_decimals = nat_add(&reserve_0_in_max_decimals, &amount_0_in_max_decimals);
-    let amount_1_in_max_decimals = nat_divide(&numerator_in_max_decimals, &denominator_in_max_decimals).ok_or("Invalid amount_1")?;
+    let amount_1_in_max_decimals = pool_amount_out(
+        pool,
+        true,
+        &reserve_0_in_max_decimals,
+        &reserve_1_in_max_decimals,
+        &amount_0_in_max_decimals,
+    )
+    .ok_or("Invalid amount_1")?;
 
     // calculate the LP fees
     // any user fee discount. user.fee_level is 0 = 100% fee (no discount), 100 = 0% fee (max discount)
@@ -538,7 +587,7 @@ fn swap_amount_0(
     let user_lp_fee_pct = nat_subtract(&Nat::from(100_u8), &Nat::from(user_fee_level.unwrap_or(0_u8))).unwrap_or(Nat::from(100_u8));
     // user_lp_fee_bps = (user_lp_fee * user_lp_fee_pct) / 100 - user's fee level in bps with discount
     let user_lp_fee_bps = nat_divide(
-        &nat_multiply(&user_lp_fee_pct, &Nat::from(use_lp_fee.unwrap_or(pool.lp_fee_bps))),
+        &nat_multiply(&user_lp_fee_pct, &Nat::from(use_lp_fee.unwrap_or_else(|| pool.live_lp_fee_bps()))),
         &Nat::from(100_u8),
     )
     .ok_or("Invalid LP fee")?;
@@ -549,22 +598,9 @@ fn swap_amount_0(
     // convert amount_1 and lp_fee_1 from max_decimals to token_1 precision
     let amount_1 = nat_to_decimal_precision(&amount_1_in_max_decimals, max_decimals, token_1.decimals());
     let lp_fee = nat_to_decimal_precision(&lp_fee_1_in_max_decimals, max_decimals, token_1.decimals());
-    
+
     // Calculate gas fee - show SPL gas fee equivalent in receive token denomination
-    let gas_fee = use_gas_fee.map_or_else(|| {
-        if is_spl_requiring_gas_deduction(&token_1) {
-            // For SPL tokens, show the gas fee in receive token denomination
-            match calculate_spl_gas_fee(&token_0, &token_1) {
-                Ok(spl_fee) => {
-                    // Convert SPL fee from pay token to receive token denomination
-                    nat_to_decimal_precision(&spl_fee, token_0.decimals(), token_1.decimals())
-                }
-                Err(_) => token_1.fee(),
-            }
-        } else {
-            token_1.fee()
-        }
-    }, |fee| fee.clone());
+    let gas_fee = use_gas_fee.map_or_else(|| receive_gas_fee(&token_0, &token_1), |fee| fee.clone());
 
     if amount_1 > reserve_1 {
         Err(format!("Insufficient {} in pool", token_1.symbol()))?
@@ -578,11 +614,12 @@ fn swap_amount_0(
         receive_amount: amount_1,
         lp_fee,
         gas_fee,
+        lp_fee_bps: user_lp_fee_bps.0.to_u8().unwrap_or(u8::MAX),
     })
 }
 
 /// Swap amount 1 of a given pool
-/// use_lp_fee and use_gas_fee are used to overwrite the default LP and gas fees, if None, then use the pool's default
+/// use_lp_fee and use_gas_fee are used to overwrite the default LP and gas fees, if None, then use the pool's live LP fee and default gas fee
 fn swap_amount_1(
     pool: &StablePool,
     amount_1: Option<&Nat>,
@@ -609,6 +646,7 @@ fn swap_amount_1(
             receive_amount: nat_zero(),
             lp_fee: nat_zero(),
             gas_fee: nat_zero(),
+            lp_fee_bps: 0,
         });
     }
 
@@ -623,13 +661,14 @@ fn swap_amount_1(
                 receive_amount: nat_zero(),
                 lp_fee: nat_zero(),
                 gas_fee: nat_zero(),
+                lp_fee_bps: 0,
             });
         }
         Some(amount) => amount,
     };
 
-    // Reduce amount_1 by SPL gas fee if receive token is SPL
-    let effective_amount_1 = if is_spl_requiring_gas_deduction(&token_0) {
+    // Reduce amount_1 by SPL gas fee if receive token is SPL, unless the gas fee is overwritten (intermediate legs and split routes)
+    let effective_amount_1 = if use_gas_fee.is_none() && is_spl_requiring_gas_deduction(&token_0) {
         match calculate_spl_gas_fee(&token_1, &token_0) {
             Ok(spl_fee) => {
                 if spl_fee <= *amount_1 {
@@ -650,16 +689,20 @@ fn swap_amount_1(
     let reserve_1_in_max_decimals = nat_to_decimal_precision(&reserve_1, token_1.decimals(), max_decimals);
     let amount_1_in_max_decimals = nat_to_decimal_precision(&effective_amount_1, token_1.decimals(), max_decimals);
 
-    // amount_0 = (amount_1 * reserve_0) / (reserve_1 + amount_1)
-    let numerator_in_max_decimals = nat_multiply(&amount_1_in_max_decimals, &reserve_0_in_max_decimals);
-    let denominator_in_max_decimals = nat_add(&reserve_1_in_max_decimals, &amount_1_in_max_decimals);
-    let amount_0_in_max_decimals = nat_divide(&numerator_in_max_decimals, &denominator_in_max_decimals).ok_or("Invalid amount_0")?;
+    let amount_0_in_max_decimals = pool_amount_out(
+        pool,
+        false,
+        &reserve_1_in_max_decimals,
+        &reserve_0_in_max_decimals,
+        &amount_1_in_max_decimals,
+    )
+    .ok_or("Invalid amount_0")?;
 
     // calculate the LP fees
     // user_lp_fee_pct = 100 - user.fee_level
     let user_lp_fee_pct = nat_subtract(&Nat::from(100_u8), &Nat::from(user_fee_level.unwrap_or(0_u8))).unwrap_or(Nat::from(100_u8));
     let user_lp_fee_bps = nat_divide(
-        &nat_multiply(&user_lp_fee_pct, &Nat::from(use_lp_fee.unwrap_or(pool.lp_fee_bps))),
+        &nat_multiply(&user_lp_fee_pct, &Nat::from(use_lp_fee.unwrap_or_else(|| pool.live_lp_fee_bps()))),
         &Nat::from(100_u8),
     )
     .ok_or("Invalid LP fee")?;
@@ -669,22 +712,9 @@ fn swap_amount_1(
     // convert amount_0 and lp_fee_0 to token_0 precision
     let amount_0 = nat_to_decimal_precision(&amount_0_in_max_decimals, max_decimals, token_0.decimals());
     let lp_fee = nat_to_decimal_precision(&lp_fee_0_in_max_decimals, max_decimals, token_0.decimals());
-    
+
     // Calculate gas fee - show SPL gas fee equivalent in receive token denomination
-    let gas_fee = use_gas_fee.map_or_else(|| {
-        if is_spl_requiring_gas_deduction(&token_0) {
-            // For SPL tokens, show the gas fee in receive token denomination
-            match calculate_spl_gas_fee(&token_1, &token_0) {
-                Ok(spl_fee) => {
-                    // Convert SPL fee from pay token to receive token denomination
-                    nat_to_decimal_precision(&spl_fee, token_1.decimals(), token_0.decimals())
-                }
-                Err(_) => token_0.fee(),
-            }
-        } else {
-            token_0.fee()
-        }
-    }, |fee| fee.clone());
+    let gas_fee = use_gas_fee.map_or_else(|| receive_gas_fee(&token_1, &token_0), |fee| fee.clone());
 
     if amount_0 > reserve_0 {
         Err(format!("Insufficient {} in pool", token_0.symbol()))?
@@ -698,9 +728,66 @@ fn swap_amount_1(
         receive_amount: amount_0,
         lp_fee,
         gas_fee,
+        lp_fee_bps: user_lp_fee_bps.0.to_u8().unwrap_or(u8::MAX),
     })
 }
 
+/// amount_out of a swap through the pool's invariant, before fees. amounts and reserves are in max_decimals precision
+/// pays_token_0 is true if amount_in is in token_0 of the pool
+fn pool_amount_out(pool: &StablePool, pays_token_0: bool, reserve_in: &Nat, reserve_out: &Nat, amount_in: &Nat) -> Option<Nat> {
+    match pool.pool_type {
+        // amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
+        PoolType::ConstantProduct => nat_divide(&nat_multiply(amount_in, reserve_out), &nat_add(reserve_in, amount_in)),
+        PoolType::StableSwap { amp } => stable_swap_math::amount_out(reserve_in, reserve_out, amount_in, amp),
+        PoolType::Weighted { .. } => {
+            let (weight_in, weight_out) = swap_weights(pool, pays_token_0);
+            weighted_math::amount_out(reserve_in, reserve_out, amount_in, weight_in, weight_out)
+        }
+        // priced from the liquidity of the positions, not the reserves
+        PoolType::Concentrated { .. } => concentrated_liquidity::amount_out(pool, pays_token_0, amount_in),
+    }
+}
+
+/// amount_in required for amount_out through the pool's invariant, before fees. inverse of pool_amount_out, rounded up
+fn pool_amount_in(pool: &StablePool, pays_token_0: bool, reserve_in: &Nat, reserve_out: &Nat, amount_out: &Nat) -> Option<Nat> {
+    match pool.pool_type {
+        // amount_in = (amount_out * reserve_in) / (reserve_out - amount_out)
+        PoolType::ConstantProduct => nat_divide_ceil(&nat_multiply(amount_out, reserve_in), &nat_subtract(reserve_out, amount_out)?),
+        PoolType::StableSwap { amp } => stable_swap_math::amount_in(reserve_in, reserve_out, amount_out, amp),
+        PoolType::Weighted { .. } => {
+            let (weight_in, weight_out) = swap_weights(pool, pays_token_0);
+            weighted_math::amount_in(reserve_in, reserve_out, amount_out, weight_in, weight_out)
+        }
+        PoolType::Concentrated { .. } => concentrated_liquidity::amount_in(pool, pays_token_0, amount_out),
+    }
+}
+
+/// weights of the pay and receive tokens of a swap
+fn swap_weights(pool: &StablePool, pays_token_0: bool) -> (u8, u8) {
+    let (weight_0, weight_1) = pool.pool_type.weights();
+    if pays_token_0 {
+        (weight_0, weight_1)
+    } else {
+        (weight_1, weight_0)
+    }
+}
+
+/// Default gas fee of a swap in receive token denomination
+fn receive_gas_fee(pay_token: &StableToken, receive_token: &StableToken) -> Nat {
+    if is_spl_requiring_gas_deduction(receive_token) {
+        // For SPL tokens, show the gas fee in receive token denomination
+        match calculate_spl_gas_fee(pay_token, receive_token) {
+            Ok(spl_fee) => {
+                // Convert SPL fee from pay token to receive token denomination
+                nat_to_decimal_precision(&spl_fee, pay_token.decimals(), receive_token.decimals())
+            }
+            Err(_) => receive_token.fee(),
+        }
+    } else {
+        receive_token.fee()
+    }
+}
+
 fn get_slippage(price_achieved: &BigRational, price_expected: &BigRational) -> Option<f64> {
     if price_achieved > price_expected {
         return Some(0.0); // if price is greater than expected, slippage is 0
@@ -740,10 +827,10 @@ fn calculate_spl_gas_fee(pay_token: &StableToken, receive_token: &StableToken) -
     // 1. Integrating with a price oracle (e.g., Chainlink, Exchange Rate Canister)
     // 2. Making gas fees configurable through kong_settings
     // 3. Implementing a fallback mechanism when price data is unavailable
-    
+
     // Fixed SPL gas fee: approximately $0.50 worth
     // WARNING: These exchange rates are hardcoded and need to be updated regularly
-    
+
     match pay_token {
         StableToken::IC(ic_token) => {
             match ic_token.symbol.as_str() {
@@ -772,9 +859,200 @@ fn calculate_spl_gas_fee(pay_token: &StableToken, receive_token: &StableToken) -
             // TODO: Calculate based on current SOL price
             Ok(Nat::from(5000_u64)) // 0.000005 SOL in lamports
         }
+        StableToken::Ripple(_) => {
+            // no rate between XRP Ledger tokens and SOL, the SPL gas is covered by Kong
+            Ok(nat_zero())
+        }
         StableToken::LP(_) => {
             // LP tokens shouldn't be used as pay tokens for SPL swaps
             Ok(nat_zero())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::test_helpers::{insert_ic_token, insert_pool, insert_token};
+    use crate::stable_token::solana_token::SolanaToken;
+
+    // constant product swap without fees
+    fn constant_product(reserve_in: u128, reserve_out: u128) -> impl Fn(&Nat) -> Nat {
+        move |pay_amount: &Nat| {
+            nat_divide(
+                &nat_multiply(pay_amount, &Nat::from(reserve_out)),
+                &nat_add(&Nat::from(reserve_in), pay_amount),
+            )
+            .unwrap()
+        }
+    }
+
+    #[test]
+    fn test_split_pay_amount_equal_routes() {
+        let route = constant_product(1_000_000, 1_000_000);
+        let pay_amounts = split_pay_amount(&Nat::from(100_000_u128), 2, |_, amount| route(amount)).unwrap();
+        assert_eq!(pay_amounts, vec![Nat::from(50_000_u128), Nat::from(50_000_u128)]);
+    }
+
+    #[test]
+    fn test_split_pay_amount_deeper_route_gets_more() {
+        let routes = [constant_product(1_000_000, 1_000_000), constant_product(3_000_000, 3_000_000)];
+        let pay_amounts = split_pay_amount(&Nat::from(400_000_u128), 2, |i, amount| routes[i](amount)).unwrap();
+        assert!(pay_amounts[1] > pay_amounts[0]);
+        assert_eq!(nat_add(&pay_amounts[0], &pay_amounts[1]), Nat::from(400_000_u128));
+    }
+
+    #[test]
+    fn test_split_pay_amount_allocates_remainder() {
+        let route = constant_product(1_000_000, 1_000_000);
+        let pay_amounts = split_pay_amount(&Nat::from(1_013_u128), 3, |_, amount| route(amount)).unwrap();
+        let total = pay_amounts.iter().fold(nat_zero(), |acc, amount| nat_add(&acc, amount));
+        assert_eq!(total, Nat::from(1_013_u128));
+    }
+
+    #[test]
+    fn test_split_pay_amount_ignores_gas() {
+        // a fixed gas fee per route would make the first part look worse on an empty route and keep everything
+        // on one route. allocating before gas splits evenly and gas is then charged once on the combined amount
+        let gas_fee = 10_000_u128;
+        let route = constant_product(1_000_000, 1_000_000);
+        let with_gas = |amount: &Nat| nat_subtract(&route(amount), &Nat::from(gas_fee)).unwrap_or(nat_zero());
+        let pay_amount = Nat::from(100_000_u128);
+
+        let per_route_gas = split_pay_amount(&pay_amount, 2, |_, amount| with_gas(amount)).unwrap();
+        assert_eq!(per_route_gas, vec![pay_amount.clone(), nat_zero()]);
+
+        let before_gas = split_pay_amount(&pay_amount, 2, |_, amount| route(amount)).unwrap();
+        assert_eq!(before_gas, vec![Nat::from(50_000_u128), Nat::from(50_000_u128)]);
+        let split_receive_amount = nat_subtract(&nat_add(&route(&before_gas[0]), &route(&before_gas[1])), &Nat::from(gas_fee)).unwrap();
+        assert!(split_receive_amount > with_gas(&pay_amount));
+    }
+
+    // ckBTC (8 decimals) / ckUSDT (6 decimals) pool at 100_000 ckUSDT per ckBTC
+    fn single_leg_route() -> Vec<SwapLeg> {
+        insert_ic_token(1, "ckUSDT", 6);
+        insert_ic_token(2, "ckBTC", 8);
+        let pool = insert_pool(1, 2, 10_000_000_000, 1, 1_000_000_000_000);
+        vec![SwapLeg { pool, pays_token_0: true }]
+    }
+
+    #[test]
+    fn test_exact_out_receives_requested_amount() {
+        let route = single_leg_route();
+        let receive_amount = Nat::from(1_000_000_000_u128); // 1_000 ckUSDT
+        let (pay_amount, route_receive_amount, _, _, _, swaps) = route_exact_out_amounts(&route, &receive_amount, Some(0)).unwrap();
+        assert!(route_receive_amount >= receive_amount);
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(swaps[0].pay_amount, pay_amount);
+        assert_eq!(swaps[0].receive_amount_with_fees_and_gas(), route_receive_amount);
+    }
+
+    #[test]
+    fn test_exact_out_pay_amount_is_minimal() {
+        let route = single_leg_route();
+        let receive_amount = Nat::from(1_000_000_000_u128);
+        let (pay_amount, _, _, _, _, _) = route_exact_out_amounts(&route, &receive_amount, Some(0)).unwrap();
+        // paying 1 less must not cover the requested amount
+        let less_pay_amount = nat_subtract(&pay_amount, &Nat::from(1_u8)).unwrap();
+        let (less_receive_amount, _, _, _, _) = route_swap_amounts(&route, Some(&less_pay_amount), Some(0)).unwrap();
+        assert!(less_receive_amount < receive_amount);
+    }
+
+    #[test]
+    fn test_exact_out_inverse_of_exact_in() {
+        let route = single_leg_route();
+        let pay_amount = Nat::from(123_456_789_u128);
+        let (receive_amount, _, _, _, _) = route_swap_amounts(&route, Some(&pay_amount), Some(0)).unwrap();
+        let (exact_out_pay_amount, exact_out_receive_amount, _, _, _, _) =
+            route_exact_out_amounts(&route, &receive_amount, Some(0)).unwrap();
+        // the inverse rounds up, so it can cost at most 1 more unit of the pay token
+        assert!(exact_out_pay_amount <= nat_add(&pay_amount, &Nat::from(1_u8)));
+        assert!(exact_out_receive_amount >= receive_amount);
+    }
+
+    #[test]
+    fn test_exact_out_user_fee_discount_pays_less() {
+        let route = single_leg_route();
+        let receive_amount = Nat::from(1_000_000_000_u128);
+        let (pay_amount, _, _, _, _, _) = route_exact_out_amounts(&route, &receive_amount, Some(0)).unwrap();
+        let (discount_pay_amount, _, _, _, _, _) = route_exact_out_amounts(&route, &receive_amount, Some(50)).unwrap();
+        assert!(discount_pay_amount < pay_amount);
+    }
+
+    #[test]
+    fn test_exact_out_multi_hop() {
+        // ckBTC -> ckUSDT -> ICP
+        insert_ic_token(1, "ckUSDT", 6);
+        insert_ic_token(2, "ckBTC", 8);
+        insert_ic_token(3, "ICP", 8);
+        let pool_0 = insert_pool(1, 2, 10_000_000_000, 1, 1_000_000_000_000);
+        let pool_1 = insert_pool(2, 3, 100_000_000_000_000, 1, 1_000_000_000_000);
+        let route = vec![
+            SwapLeg {
+                pool: pool_0,
+                pays_token_0: true,
+            },
+            SwapLeg {
+                pool: pool_1,
+                pays_token_0: false,
+            },
+        ];
+        let receive_amount = Nat::from(10_000_000_000_u128); // 100 ICP
+        let (pay_amount, route_receive_amount, _, _, _, swaps) = route_exact_out_amounts(&route, &receive_amount, Some(0)).unwrap();
+        assert!(route_receive_amount >= receive_amount);
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(swaps[0].pay_amount, pay_amount);
+        // the intermediate leg does not take gas
+        assert_eq!(swaps[0].gas_fee, nat_zero());
+        assert_eq!(swaps[1].pay_amount, swaps[0].receive_amount_with_fees_and_gas());
+    }
+
+    #[test]
+    fn test_exact_out_multi_hop_through_spl_token() {
+        // ICP -> USDC (SPL) -> ckUSDT. the SPL gas fee is only deducted when USDC is sent out on the last leg
+        insert_ic_token(1, "ckUSDT", 6);
+        insert_ic_token(3, "ICP", 8);
+        let usdc = StableToken::Solana(SolanaToken {
+            token_id: 4,
+            name: "USD Coin".to_string(),
+            symbol: "USDC".to_string(),
+            decimals: 6,
+            fee: nat_zero(),
+            mint_address: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            program_id: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".to_string(),
+            is_spl_token: true,
+            has_transfer_fee: false,
+        });
+        insert_token(&usdc);
+        let pool_0 = insert_pool(1, 3, 100_000_000_000_000, 4, 1_000_000_000_000);
+        let pool_1 = insert_pool(2, 4, 1_000_000_000_000, 1, 1_000_000_000_000);
+        let route = vec![
+            SwapLeg {
+                pool: pool_0,
+                pays_token_0: true,
+            },
+            SwapLeg {
+                pool: pool_1,
+                pays_token_0: true,
+            },
+        ];
+        let receive_amount = Nat::from(1_000_000_000_u128); // 1_000 ckUSDT
+        let (pay_amount, route_receive_amount, _, _, _, _) = route_exact_out_amounts(&route, &receive_amount, Some(0)).unwrap();
+        assert!(route_receive_amount >= receive_amount);
+        // no SPL gas fee (5_000_000 ICP) is added for the intermediate leg, so paying 1_000 ICP less (about 10 USDC)
+        // must not cover the requested amount
+        let less_pay_amount = nat_subtract(&pay_amount, &Nat::from(1_000_u32)).unwrap();
+        let (less_receive_amount, _, _, _, _) = route_swap_amounts(&route, Some(&less_pay_amount), Some(0)).unwrap();
+        assert!(less_receive_amount < receive_amount);
+    }
+
+    #[test]
+    fn test_exact_out_insufficient_liquidity() {
+        let route = single_leg_route();
+        // more than the ckUSDT reserve
+        let receive_amount = Nat::from(2_000_000_000_000_u128);
+        let e = route_exact_out_amounts(&route, &receive_amount, Some(0)).unwrap_err();
+        assert!(e.contains("Insufficient ckUSDT in pool"));
+    }
+}
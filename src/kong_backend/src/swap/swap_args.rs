@@ -19,6 +19,8 @@ pub struct SwapArgs {
     pub receive_address: Option<String>, // Required for non-IC receive tokens
     pub max_slippage: Option<f64>,
     pub referred_by: Option<String>,
+    pub max_routes: Option<u8>,          // split pay_amount across up to max_routes routes. None or 1 for a single route
+    pub exact_out: Option<bool>,         // receive exactly receive_amount, pay_amount is the maximum to pay and any unused amount is returned
     // Cross-chain fields
     pub pay_signature: Option<String>,   // Ed25519 signature of canonical message for payment verification
 }
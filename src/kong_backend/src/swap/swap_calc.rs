@@ -11,4 +11,6 @@ pub struct SwapCalc {
     pub receive_amount: Nat, // does not include any fees. used to keep a constant K with pay amount
     pub lp_fee: Nat,         // will be in receive_token
     pub gas_fee: Nat,        // will be in receive_token
+    #[serde(default)]
+    pub lp_fee_bps: u8, // LP fee in basis points charged, after multi-hop split and user discount
 }
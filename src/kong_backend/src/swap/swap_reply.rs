@@ -2,15 +2,16 @@ use candid::{CandidType, Nat};
 use num::{BigRational, Zero};
 use serde::{Deserialize, Serialize};
 
+use crate::chains::chains::SOL_CHAIN;
 use crate::helpers::math_helpers::price_rounded;
 use crate::helpers::nat_helpers::nat_zero;
 use crate::stable_pool::pool_map;
 use crate::stable_token::stable_token::StableToken;
 use crate::stable_token::token::Token;
 use crate::stable_token::token_map;
-use crate::stable_transfer::transfer_map;
+use crate::stable_transfer::{transfer_map, tx_id::TxId};
 use crate::stable_tx::status_tx::StatusTx;
-use crate::stable_tx::swap_tx::SwapTx;
+use crate::stable_tx::swap_tx::{SwapRefund, SwapTx};
 use crate::transfers::transfer_reply::TransferIdReply;
 
 use super::swap_calc::SwapCalc;
@@ -29,6 +30,8 @@ pub struct SwapTxReply {
     pub price: f64,
     pub lp_fee: Nat,  // will be in receive_symbol
     pub gas_fee: Nat, // will be in receive_symbol
+    #[serde(default)]
+    pub lp_fee_bps: u8, // LP fee in basis points charged
     pub ts: u64,
 }
 
@@ -51,9 +54,34 @@ pub struct SwapReply {
     pub txs: Vec<SwapTxReply>,
     pub transfer_ids: Vec<TransferIdReply>,
     pub claim_ids: Vec<u64>,
+    #[serde(default)]
+    pub solana_job_id: Option<u64>, // Solana swap job of the payout, status and signature from solana_swap_jobs()
+    #[serde(default)]
+    pub refund: Option<SwapRefundReply>, // unused pay amount of an exact output swap returned to the user
     pub ts: u64,
 }
 
+/// Return of the unused pay amount of an exact output swap. pay_amount of the reply is the amount used by the swap,
+/// amount was returned by transfer_id or, if the transfer failed, is owed to the user as claim_id
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct SwapRefundReply {
+    pub symbol: String,
+    pub amount: Nat,
+    pub transfer_id: Option<u64>,
+    pub claim_id: Option<u64>,
+}
+
+impl SwapRefundReply {
+    fn from(refund: &SwapRefund, symbol: &str) -> Self {
+        SwapRefundReply {
+            symbol: symbol.to_string(),
+            amount: refund.amount.clone(),
+            transfer_id: refund.transfer_id,
+            claim_id: refund.claim_id,
+        }
+    }
+}
+
 impl From<(&SwapCalc, u64)> for SwapTxReply {
     fn from((swap, ts): (&SwapCalc, u64)) -> Self {
         let pool = pool_map::get_by_pool_id(swap.pool_id).expect("Pool not found");
@@ -81,6 +109,7 @@ impl From<(&SwapCalc, u64)> for SwapTxReply {
             price: price_f64,
             lp_fee: swap.lp_fee.clone(),
             gas_fee: swap.gas_fee.clone(),
+            lp_fee_bps: swap.lp_fee_bps,
             ts,
         }
     }
@@ -112,6 +141,20 @@ fn get_tokens_info(pay_token_id: u32, receive_token_id: u32) -> (String, String,
     (pay_chain, pay_address, pay_symbol, receive_chain, receive_address, receive_symbol)
 }
 
+/// id of the Solana swap job sending the receive token, or returning the pay token, from its "job_<id>" transfer
+fn get_solana_job_id(transfer_ids: &[u64]) -> Option<u64> {
+    transfer_ids.iter().find_map(|&transfer_id| {
+        let transfer = transfer_map::get_by_transfer_id(transfer_id)?;
+        if transfer.is_send || token_map::get_by_token_id(transfer.token_id)?.chain() != SOL_CHAIN {
+            return None;
+        }
+        match transfer.tx_id {
+            TxId::TransactionId(tx_id) => tx_id.strip_prefix("job_")?.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
 fn to_txs(swap_tx: &SwapTx) -> Vec<SwapTxReply> {
     swap_tx
         .txs
@@ -124,6 +167,7 @@ impl From<&SwapTx> for SwapReply {
     fn from(swap_tx: &SwapTx) -> Self {
         let (pay_chain, pay_address, pay_symbol, receive_chain, receive_address, receive_symbol) =
             get_tokens_info(swap_tx.pay_token_id, swap_tx.receive_token_id);
+        let refund = swap_tx.refund.as_ref().map(|refund| SwapRefundReply::from(refund, &pay_symbol));
 
         SwapReply {
             tx_id: swap_tx.tx_id,
@@ -151,6 +195,8 @@ impl From<&SwapTx> for SwapReply {
                 })
                 .collect(),
             claim_ids: swap_tx.claim_ids.clone(),
+            solana_job_id: get_solana_job_id(&swap_tx.transfer_ids),
+            refund,
             ts: swap_tx.ts,
         }
     }
@@ -199,6 +245,8 @@ impl SwapReply {
                 })
                 .collect(),
             claim_ids: claim_ids.to_vec(),
+            solana_job_id: get_solana_job_id(transfer_ids),
+            refund: None,
             ts,
         }
     }
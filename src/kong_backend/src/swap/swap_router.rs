@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use crate::helpers::nat_helpers::nat_is_zero;
+use crate::stable_pool::pool_map;
+use crate::stable_pool::stable_pool::StablePool;
+
+// maximum number of routes returned by get_routes(). each route is quoted by swap_amounts() so this bounds the
+// instructions used by a swap or quote on a graph with many pools
+const MAX_ROUTES: usize = 16;
+
+/// One leg of a swap route through a pool
+#[derive(Debug, Clone)]
+pub struct SwapLeg {
+    pub pool: StablePool,
+    pub pays_token_0: bool, // true if the leg pays token_0 of the pool and receives token_1
+}
+
+/// find the routes from pay_token_id to receive_token_id through the active pools
+/// removed pools and pools with an empty balance are skipped. routes are returned with the least number of
+/// legs first and at most MAX_ROUTES routes are returned
+///
+/// pay_token_id - token id of the pay token
+/// receive_token_id - token id of the receive token
+/// max_hops - maximum number of pools a route can go through
+pub fn get_routes(pay_token_id: u32, receive_token_id: u32, max_hops: u8) -> Vec<Vec<SwapLeg>> {
+    let pools: Vec<StablePool> = pool_map::get()
+        .into_iter()
+        .filter(|pool| !pool.is_removed && !nat_is_zero(&pool.balance_0) && !nat_is_zero(&pool.balance_1))
+        .collect();
+    let edges: Vec<(u32, u32)> = pools.iter().map(|pool| (pool.token_id_0, pool.token_id_1)).collect();
+
+    find_paths(&edges, pay_token_id, receive_token_id, max_hops as usize, MAX_ROUTES)
+        .into_iter()
+        .map(|path| {
+            path.into_iter()
+                .map(|(pool_idx, pays_token_0)| SwapLeg {
+                    pool: pools[pool_idx].clone(),
+                    pays_token_0,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// iterative deepening depth-first search of the simple paths between two tokens
+/// a token is never visited twice in a path so a pool can only be used once per route
+/// paths are searched one number of hops at a time so the shortest paths are found first, and the search stops
+/// once max_routes paths are found
+///
+/// edges - list of (token_id_0, token_id_1) for each pool
+/// returns a list of paths, each path is a list of (index into edges, pays_token_0)
+fn find_paths(
+    edges: &[(u32, u32)],
+    pay_token_id: u32,
+    receive_token_id: u32,
+    max_hops: usize,
+    max_routes: usize,
+) -> Vec<Vec<(usize, bool)>> {
+    if pay_token_id == receive_token_id || max_hops == 0 || max_routes == 0 {
+        return Vec::new();
+    }
+
+    // adjacency list of token_id -> (edge index, pays_token_0, next token_id)
+    let mut graph: BTreeMap<u32, Vec<(usize, bool, u32)>> = BTreeMap::new();
+    for (idx, (token_id_0, token_id_1)) in edges.iter().enumerate() {
+        graph.entry(*token_id_0).or_default().push((idx, true, *token_id_1));
+        graph.entry(*token_id_1).or_default().push((idx, false, *token_id_0));
+    }
+
+    // shortest routes first so they are preferred when quotes are equal
+    let mut paths = Vec::new();
+    for hops in 1..=max_hops {
+        let mut path = Vec::new();
+        let mut visited = vec![pay_token_id];
+        search_paths(
+            &graph,
+            pay_token_id,
+            receive_token_id,
+            hops,
+            max_routes,
+            &mut visited,
+            &mut path,
+            &mut paths,
+        );
+        if paths.len() >= max_routes {
+            break;
+        }
+    }
+    paths
+}
+
+/// depth-first search of the paths with exactly hops legs. stops once paths has max_routes paths
+#[allow(clippy::too_many_arguments)]
+fn search_paths(
+    graph: &BTreeMap<u32, Vec<(usize, bool, u32)>>,
+    token_id: u32,
+    receive_token_id: u32,
+    hops: usize,
+    max_routes: usize,
+    visited: &mut Vec<u32>,
+    path: &mut Vec<(usize, bool)>,
+    paths: &mut Vec<Vec<(usize, bool)>>,
+) {
+    let Some(neighbours) = graph.get(&token_id) else {
+        return;
+    };
+
+    for (edge_idx, pays_token_0, next_token_id) in neighbours {
+        if paths.len() >= max_routes {
+            return;
+        }
+        if visited.contains(next_token_id) {
+            continue;
+        }
+        path.push((*edge_idx, *pays_token_0));
+        if path.len() == hops {
+            if *next_token_id == receive_token_id {
+                paths.push(path.clone());
+            }
+        } else if *next_token_id != receive_token_id {
+            visited.push(*next_token_id);
+            search_paths(graph, *next_token_id, receive_token_id, hops, max_routes, visited, path, paths);
+            visited.pop();
+        }
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // token ids used in tests
+    const CKUSDT: u32 = 1;
+    const ICP: u32 = 2;
+    const CKBTC: u32 = 3;
+    const CKETH: u32 = 4;
+    const KONG: u32 = 5;
+
+    #[test]
+    fn test_find_paths_direct() {
+        let edges = vec![(CKBTC, CKUSDT)];
+        assert_eq!(find_paths(&edges, CKBTC, CKUSDT, 3, MAX_ROUTES), vec![vec![(0, true)]]);
+        assert_eq!(find_paths(&edges, CKUSDT, CKBTC, 3, MAX_ROUTES), vec![vec![(0, false)]]);
+    }
+
+    #[test]
+    fn test_find_paths_same_token() {
+        let edges = vec![(CKBTC, CKUSDT)];
+        assert!(find_paths(&edges, CKBTC, CKBTC, 3, MAX_ROUTES).is_empty());
+    }
+
+    #[test]
+    fn test_find_paths_through_third_hub() {
+        // ckETH -> ckBTC -> KONG, no ckUSDT or ICP involved
+        let edges = vec![(CKETH, CKBTC), (KONG, CKBTC)];
+        assert_eq!(find_paths(&edges, CKETH, KONG, 3, MAX_ROUTES), vec![vec![(0, true), (1, false)]]);
+    }
+
+    #[test]
+    fn test_find_paths_parallel_pools() {
+        // two ckBTC/ckUSDT pools at different fee tiers
+        let edges = vec![(CKBTC, CKUSDT), (CKBTC, CKUSDT), (KONG, CKUSDT)];
+        assert_eq!(find_paths(&edges, CKBTC, CKUSDT, 3, MAX_ROUTES), vec![vec![(0, true)], vec![(1, true)]]);
+        assert_eq!(
+            find_paths(&edges, CKBTC, KONG, 3, MAX_ROUTES),
+            vec![vec![(0, true), (2, false)], vec![(1, true), (2, false)]]
+        );
+    }
+
+    #[test]
+    fn test_find_paths_respects_max_hops() {
+        // ckBTC -> ckUSDT -> ICP -> KONG
+        let edges = vec![(CKBTC, CKUSDT), (ICP, CKUSDT), (KONG, ICP)];
+        assert_eq!(find_paths(&edges, CKBTC, KONG, 3, MAX_ROUTES).len(), 1);
+        assert!(find_paths(&edges, CKBTC, KONG, 2, MAX_ROUTES).is_empty());
+    }
+
+    #[test]
+    fn test_find_paths_shortest_first() {
+        let edges = vec![(CKBTC, CKUSDT), (ICP, CKUSDT), (CKBTC, ICP), (KONG, ICP), (KONG, CKUSDT)];
+        let paths = find_paths(&edges, CKBTC, KONG, 3, MAX_ROUTES);
+        // ckBTC->ckUSDT->KONG, ckBTC->ICP->KONG, ckBTC->ckUSDT->ICP->KONG, ckBTC->ICP->ckUSDT->KONG
+        assert_eq!(paths.len(), 4);
+        assert!(paths.windows(2).all(|w| w[0].len() <= w[1].len()));
+        assert_eq!(paths[0].len(), 2);
+        // no path visits a token twice
+        for path in paths {
+            let mut pool_ids: Vec<usize> = path.iter().map(|(idx, _)| *idx).collect();
+            pool_ids.sort();
+            pool_ids.dedup();
+            assert_eq!(pool_ids.len(), path.len());
+        }
+    }
+
+    #[test]
+    fn test_find_paths_dense_hub_graph() {
+        // 40 tokens all paired with each other: 780 pools and over 1_400 paths of up to 3 hops between two tokens
+        let num_tokens = 40;
+        let edges: Vec<(u32, u32)> = (1..=num_tokens)
+            .flat_map(|token_id_0| (token_id_0 + 1..=num_tokens).map(move |token_id_1| (token_id_0, token_id_1)))
+            .collect();
+        let paths = find_paths(&edges, 1, 2, 3, MAX_ROUTES);
+        assert_eq!(paths.len(), MAX_ROUTES);
+        // direct pool first, then the 2 hop routes through every other token
+        assert_eq!(paths[0].len(), 1);
+        assert!(paths[1..].iter().all(|path| path.len() == 2));
+        assert!(paths.windows(2).all(|w| w[0].len() <= w[1].len()));
+    }
+
+    #[test]
+    fn test_find_paths_max_routes() {
+        let edges = vec![(CKBTC, CKUSDT), (ICP, CKUSDT), (CKBTC, ICP), (KONG, ICP), (KONG, CKUSDT)];
+        let paths = find_paths(&edges, CKBTC, KONG, 3, 3);
+        assert_eq!(paths.len(), 3);
+        // both 2 hop routes are kept before any 3 hop route
+        assert_eq!(paths.iter().filter(|path| path.len() == 2).count(), 2);
+        assert!(find_paths(&edges, CKBTC, KONG, 3, 0).is_empty());
+    }
+}
@@ -6,8 +6,9 @@ use crate::ic::address::Address;
 use crate::ic::address_helpers::get_address;
 use crate::ic::network::ICNetwork;
 use crate::ic::verify_transfer::verify_transfer;
+use crate::ripple::verify_transfer::{get_sender as get_ripple_sender, verify_transfer as verify_transfer_ripple};
 use crate::solana::message_builders::swap::CanonicalSwapMessage;
-use crate::solana::verify_transfer::{verify_transfer as verify_transfer_solana, extract_solana_sender_from_transaction};
+use crate::solana::verify_transfer::{extract_solana_sender_from_transaction, verify_transfer as verify_transfer_solana};
 use crate::stable_kong_settings::kong_settings_map;
 use crate::stable_request::{request::Request, request_map, stable_request::StableRequest, status::StatusCode};
 use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
@@ -15,7 +16,7 @@ use crate::stable_transfer::{stable_transfer::StableTransfer, transfer_map, tx_i
 use crate::stable_user::user_map;
 
 use super::archive_to_kong_data::archive_to_kong_data;
-use super::return_pay_token::return_pay_token;
+use super::return_pay_token::{return_pay_token, return_unused_pay_token};
 use super::send_receive_token::send_receive_token;
 use super::swap_args::SwapArgs;
 use super::swap_calc::SwapCalc;
@@ -29,6 +30,7 @@ pub async fn swap_transfer(args: SwapArgs) -> Result<SwapReply, String> {
     let ts = ICNetwork::get_time();
     let request_id = request_map::insert(&StableRequest::new(user_id, &Request::Swap(args.clone()), ts));
     let mut transfer_ids = Vec::new();
+    let mut claim_ids = Vec::new();
 
     let (pay_token, pay_amount, pay_transfer_id) = check_arguments(&args, request_id, ts).await.inspect_err(|_| {
         request_map::update_status(request_id, StatusCode::Failed, None);
@@ -51,6 +53,24 @@ pub async fn swap_transfer(args: SwapArgs) -> Result<SwapReply, String> {
         let _ = archive_to_kong_data(request_id);
     })?;
 
+    // exact output swaps return the unused pay amount
+    let (pay_amount, refund) = if args.exact_out.unwrap_or(false) {
+        return_unused_pay_token(
+            request_id,
+            user_id,
+            &ICNetwork::caller_id(),
+            &pay_token,
+            &pay_amount,
+            &swaps,
+            &mut transfer_ids,
+            &mut claim_ids,
+            ts,
+        )
+        .await
+    } else {
+        (pay_amount, None)
+    };
+
     let result = send_receive_token(
         request_id,
         user_id,
@@ -60,6 +80,8 @@ pub async fn swap_transfer(args: SwapArgs) -> Result<SwapReply, String> {
         &receive_amount_with_fees_and_gas,
         &to_address,
         &mut transfer_ids,
+        &mut claim_ids,
+        refund.as_ref(),
         mid_price,
         price,
         slippage,
@@ -207,6 +229,7 @@ pub async fn swap_transfer_async(args: SwapArgs) -> Result<u64, String> {
         }
 
         let mut transfer_ids = Vec::new();
+        let mut claim_ids = Vec::new();
 
         let Ok((receive_token, receive_amount_with_fees_and_gas, to_address, mid_price, price, slippage, swaps)) = process_swap(
             request_id,
@@ -225,6 +248,24 @@ pub async fn swap_transfer_async(args: SwapArgs) -> Result<u64, String> {
             return;
         };
 
+        // exact output swaps return the unused pay amount
+        let (pay_amount, refund) = if args.exact_out.unwrap_or(false) {
+            return_unused_pay_token(
+                request_id,
+                user_id,
+                &ICNetwork::caller_id(),
+                &pay_token,
+                &pay_amount,
+                &swaps,
+                &mut transfer_ids,
+                &mut claim_ids,
+                ts,
+            )
+            .await
+        } else {
+            (pay_amount, None)
+        };
+
         spawn(async move {
             send_receive_token(
                 request_id,
@@ -235,6 +276,8 @@ pub async fn swap_transfer_async(args: SwapArgs) -> Result<u64, String> {
                 &receive_amount_with_fees_and_gas,
                 &to_address,
                 &mut transfer_ids,
+                &mut claim_ids,
+                refund.as_ref(),
                 mid_price,
                 price,
                 slippage,
@@ -324,6 +367,14 @@ async fn check_arguments(args: &SwapArgs, request_id: u64, ts: u64) -> Result<(S
                         Err("Solana tokens require TransactionId".to_string())?
                     }
                 }
+            } else if pay_token.chain() == "XRP" {
+                match pay_tx_id {
+                    TxId::TransactionId(tx_hash) => verify_ripple_payment(request_id, args, &pay_token, tx_hash, &pay_amount, ts)?,
+                    _ => {
+                        request_map::update_status(request_id, StatusCode::PayTxIdNotSupported, None);
+                        Err("XRPL tokens require TransactionId".to_string())?
+                    }
+                }
             } else {
                 request_map::update_status(request_id, StatusCode::PayTxIdNotSupported, None);
                 Err("Unsupported chain".to_string())?
@@ -338,6 +389,57 @@ async fn check_arguments(args: &SwapArgs, request_id: u64, ts: u64) -> Result<(S
     Ok((pay_token, pay_amount, transfer_id))
 }
 
+/// verify the XRPL payment of the pay token. kong_rpc has already reported validated payments, so unlike Solana the
+/// payment is verified before the swap starts
+fn verify_ripple_payment(
+    request_id: u64,
+    args: &SwapArgs,
+    pay_token: &StableToken,
+    tx_hash: &str,
+    pay_amount: &Nat,
+    ts: u64,
+) -> Result<u64, String> {
+    request_map::update_status(request_id, StatusCode::VerifyPayToken, None);
+
+    let signature = args.pay_signature.as_ref().ok_or_else(|| {
+        request_map::update_status(
+            request_id,
+            StatusCode::VerifyPayTokenFailed,
+            Some("Payment signature is required for XRPL tokens"),
+        );
+        "Payment signature is required for XRPL tokens".to_string()
+    })?;
+
+    // hashes are case-insensitive hex, so compare in uppercase as stored
+    let tx_hash = tx_hash.to_uppercase();
+    if transfer_map::contains_tx_signature(pay_token.token_id(), &tx_hash) {
+        request_map::update_status(request_id, StatusCode::VerifyPayTokenFailed, Some("XRPL transaction already used"));
+        return Err("XRPL transaction already used".to_string());
+    }
+
+    // the canonical message includes the sender, taken from the payment
+    let verification = get_ripple_sender(&tx_hash)
+        .and_then(|sender| {
+            let canonical_message = CanonicalSwapMessage::from_swap_args(args).with_sender(sender).to_signing_message();
+            verify_transfer_ripple(&tx_hash, signature, pay_amount, &canonical_message, pay_token)
+        })
+        .inspect_err(|e| {
+            request_map::update_status(request_id, StatusCode::VerifyPayTokenFailed, Some(e));
+        })?;
+
+    let transfer_id = transfer_map::insert(&StableTransfer {
+        transfer_id: 0,
+        request_id,
+        is_send: true,
+        amount: verification.amount,
+        token_id: pay_token.token_id(),
+        tx_id: TxId::TransactionId(verification.tx_hash),
+        ts,
+    });
+    request_map::update_status(request_id, StatusCode::VerifyPayTokenSuccess, None);
+    Ok(transfer_id)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn process_swap(
     request_id: u64,
@@ -429,24 +531,33 @@ async fn process_swap(
         None => Address::PrincipalId(caller_id),
     };
 
-    let (receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps) =
-        match update_liquidity_pool(request_id, pay_token, pay_amount, &receive_token, receive_amount, max_slippage) {
-            Ok((receive_amount, mid_price, price, slippage, swaps)) => (receive_amount, mid_price, price, slippage, swaps),
-            Err(e) => {
-                return_pay_token(
-                    request_id,
-                    user_id,
-                    &caller_id,
-                    pay_token,
-                    pay_amount,
-                    Some(&receive_token),
-                    transfer_ids,
-                    ts,
-                )
-                .await;
-                Err(format!("Req #{} failed. {}", request_id, e))?
-            }
-        };
+    let (receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps) = match update_liquidity_pool(
+        request_id,
+        pay_token,
+        pay_amount,
+        &receive_token,
+        receive_amount,
+        max_slippage,
+        args.max_routes,
+        args.exact_out.unwrap_or(false),
+        user_map::get_fee_level(user_id),
+    ) {
+        Ok((receive_amount, mid_price, price, slippage, swaps)) => (receive_amount, mid_price, price, slippage, swaps),
+        Err(e) => {
+            return_pay_token(
+                request_id,
+                user_id,
+                &caller_id,
+                pay_token,
+                pay_amount,
+                Some(&receive_token),
+                transfer_ids,
+                ts,
+            )
+            .await;
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    };
 
     request_map::update_status(request_id, StatusCode::SwapSuccess, None);
 
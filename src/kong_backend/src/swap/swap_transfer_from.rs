@@ -17,7 +17,7 @@ use crate::stable_user::user_map;
 
 use super::archive_to_kong_data::archive_to_kong_data;
 use super::calculate_amounts::calculate_amounts;
-use super::return_pay_token::return_pay_token;
+use super::return_pay_token::{return_pay_token, return_unused_pay_token};
 use super::send_receive_token::send_receive_token;
 use super::swap_args::SwapArgs;
 use super::swap_calc::SwapCalc;
@@ -28,8 +28,11 @@ pub async fn swap_transfer_from(args: SwapArgs) -> Result<SwapReply, String> {
     let (user_id, pay_token, pay_amount, receive_token, max_slippage, to_address) = check_arguments(&args).await?;
     let ts = ICNetwork::get_time();
     let receive_amount = args.receive_amount.clone();
+    let max_routes = args.max_routes;
+    let exact_out = args.exact_out.unwrap_or(false);
     let request_id = request_map::insert(&StableRequest::new(user_id, &Request::Swap(args), ts));
     let mut transfer_ids = Vec::new();
+    let mut claim_ids = Vec::new();
 
     let (receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps) = process_swap(
         request_id,
@@ -39,6 +42,8 @@ pub async fn swap_transfer_from(args: SwapArgs) -> Result<SwapReply, String> {
         &receive_token,
         receive_amount.as_ref(),
         max_slippage,
+        max_routes,
+        exact_out,
         &mut transfer_ids,
         ts,
     )
@@ -49,6 +54,24 @@ pub async fn swap_transfer_from(args: SwapArgs) -> Result<SwapReply, String> {
         let _ = archive_to_kong_data(request_id);
     })?;
 
+    // exact output swaps return the unused pay amount
+    let (pay_amount, refund) = if exact_out {
+        return_unused_pay_token(
+            request_id,
+            user_id,
+            &ICNetwork::caller_id(),
+            &pay_token,
+            &pay_amount,
+            &swaps,
+            &mut transfer_ids,
+            &mut claim_ids,
+            ts,
+        )
+        .await
+    } else {
+        (pay_amount, None)
+    };
+
     let result = send_receive_token(
         request_id,
         user_id,
@@ -58,6 +81,8 @@ pub async fn swap_transfer_from(args: SwapArgs) -> Result<SwapReply, String> {
         &receive_amount_with_fees_and_gas,
         &to_address,
         &mut transfer_ids,
+        &mut claim_ids,
+        refund.as_ref(),
         mid_price,
         price,
         slippage,
@@ -77,10 +102,13 @@ pub async fn swap_transfer_from_async(args: SwapArgs) -> Result<u64, String> {
     let (user_id, pay_token, pay_amount, receive_token, max_slippage, to_address) = check_arguments(&args).await?;
     let ts = ICNetwork::get_time();
     let receive_amount = args.receive_amount.clone();
+    let max_routes = args.max_routes;
+    let exact_out = args.exact_out.unwrap_or(false);
     let request_id = request_map::insert(&StableRequest::new(user_id, &Request::Swap(args), ts));
 
     ic_cdk::futures::spawn(async move {
         let mut transfer_ids = Vec::new();
+        let mut claim_ids = Vec::new();
 
         let Ok((receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps)) = process_swap(
             request_id,
@@ -90,6 +118,8 @@ pub async fn swap_transfer_from_async(args: SwapArgs) -> Result<u64, String> {
             &receive_token,
             receive_amount.as_ref(),
             max_slippage,
+            max_routes,
+            exact_out,
             &mut transfer_ids,
             ts,
         )
@@ -101,6 +131,24 @@ pub async fn swap_transfer_from_async(args: SwapArgs) -> Result<u64, String> {
             return;
         };
 
+        // exact output swaps return the unused pay amount
+        let (pay_amount, refund) = if exact_out {
+            return_unused_pay_token(
+                request_id,
+                user_id,
+                &ICNetwork::caller_id(),
+                &pay_token,
+                &pay_amount,
+                &swaps,
+                &mut transfer_ids,
+                &mut claim_ids,
+                ts,
+            )
+            .await
+        } else {
+            (pay_amount, None)
+        };
+
         ic_cdk::futures::spawn(async move {
             send_receive_token(
                 request_id,
@@ -111,6 +159,8 @@ pub async fn swap_transfer_from_async(args: SwapArgs) -> Result<u64, String> {
                 &receive_amount_with_fees_and_gas,
                 &to_address,
                 &mut transfer_ids,
+                &mut claim_ids,
+                refund.as_ref(),
                 mid_price,
                 price,
                 slippage,
@@ -177,7 +227,16 @@ async fn check_arguments(args: &SwapArgs) -> Result<(u32, StableToken, Nat, Stab
 
     // calculate receive_amount and swaps. do after user_id is created as it will be needed to calculate the receive_amount (user fee level)
     // no needs to store the return values as it'll be called again in process_swap
-    calculate_amounts(&pay_token, &pay_amount, &receive_token, args.receive_amount.as_ref(), max_slippage)?;
+    calculate_amounts(
+        &pay_token,
+        &pay_amount,
+        &receive_token,
+        args.receive_amount.as_ref(),
+        max_slippage,
+        args.max_routes,
+        args.exact_out.unwrap_or(false),
+        user_map::get_fee_level(user_id),
+    )?;
 
     Ok((user_id, pay_token, pay_amount, receive_token, max_slippage, to_address))
 }
@@ -193,6 +252,8 @@ async fn process_swap(
     receive_token: &StableToken,
     receive_amount: Option<&Nat>,
     max_slippage: f64,
+    max_routes: Option<u8>,
+    exact_out: bool,
     transfer_ids: &mut Vec<u64>,
     ts: u64,
 ) -> Result<(Nat, f64, f64, f64, Vec<SwapCalc>), String> {
@@ -207,32 +268,41 @@ async fn process_swap(
         .map_err(|e| format!("Pay token transfer_from failed. {}", e))?;
 
     // re-calculate receive_amount and swaps with the latest pool state
-    let (receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps) =
-        match update_liquidity_pool(request_id, pay_token, pay_amount, receive_token, receive_amount, max_slippage) {
-            Ok((receive_amount, mid_price, price, slippage, swaps)) => (receive_amount, mid_price, price, slippage, swaps),
-            Err(e) => {
-                // return pay token back to user
-                return_pay_token(
-                    request_id,
-                    user_id,
-                    &caller_id,
-                    pay_token,
-                    pay_amount,
-                    Some(receive_token),
-                    transfer_ids,
-                    ts,
-                )
-                .await;
-                Err(format!("Req #{} failed. {}", request_id, e))?
-            }
-        };
+    let (receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps) = match update_liquidity_pool(
+        request_id,
+        pay_token,
+        pay_amount,
+        receive_token,
+        receive_amount,
+        max_slippage,
+        max_routes,
+        exact_out,
+        user_map::get_fee_level(user_id),
+    ) {
+        Ok((receive_amount, mid_price, price, slippage, swaps)) => (receive_amount, mid_price, price, slippage, swaps),
+        Err(e) => {
+            // return pay token back to user
+            return_pay_token(
+                request_id,
+                user_id,
+                &caller_id,
+                pay_token,
+                pay_amount,
+                Some(receive_token),
+                transfer_ids,
+                ts,
+            )
+            .await;
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    };
 
     request_map::update_status(request_id, StatusCode::SwapSuccess, None);
 
     Ok((receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps))
 }
 
-async fn transfer_from_token(
+pub async fn transfer_from_token(
     request_id: u64,
     from_principal_id: &Account,
     token: &StableToken,
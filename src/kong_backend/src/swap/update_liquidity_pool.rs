@@ -1,14 +1,24 @@
 use candid::Nat;
+use num_traits::ToPrimitive;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
 
-use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_multiply, nat_subtract, nat_zero};
-use crate::stable_pool::pool_map;
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_multiply, nat_subtract, nat_to_decimal_precision, nat_zero};
+use crate::ic::network::ICNetwork;
+use crate::stable_lp_token::lp_token_fees::lp_fee_per_lp;
+use crate::stable_lp_token::lp_token_map;
+use crate::stable_pool::stable_pool::StablePool;
+use crate::stable_pool::{concentrated_liquidity, pool_lock, pool_map};
 use crate::stable_request::request_map;
 use crate::stable_request::status::StatusCode;
 use crate::stable_token::stable_token::StableToken;
+use crate::stable_token::token::Token;
+use crate::user::referral_reward::{accrue_referral_reward, add_referral_volume};
 
 use super::calculate_amounts::calculate_amounts;
 use super::swap_calc::SwapCalc;
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_liquidity_pool(
     request_id: u64,
     pay_token: &StableToken,
@@ -16,51 +26,40 @@ pub fn update_liquidity_pool(
     receive_token: &StableToken,
     receive_amount: Option<&Nat>,
     max_slippage: f64,
+    max_routes: Option<u8>,
+    exact_out: bool,
+    user_fee_level: u8,
 ) -> Result<(Nat, f64, f64, f64, Vec<SwapCalc>), String> {
     request_map::update_status(request_id, StatusCode::CalculatePoolAmounts, None);
 
-    match calculate_amounts(pay_token, pay_amount, receive_token, receive_amount, max_slippage) {
+    match calculate_amounts(
+        pay_token,
+        pay_amount,
+        receive_token,
+        receive_amount,
+        max_slippage,
+        max_routes,
+        exact_out,
+        user_fee_level,
+    ) {
         Ok((receive_amount_with_fees_and_gas, price, mid_price, slippage, swaps)) => {
             request_map::update_status(request_id, StatusCode::CalculatePoolAmountsSuccess, None);
 
-            // update the pool, in some cases there could be multiple pools
+            // update the pool, in some cases there could be multiple pools (multi-hop or split routes)
             request_map::update_status(request_id, StatusCode::UpdatePoolAmounts, None);
-            for swap in &swaps {
-                // refresh pool with the latest state
-                let mut pool = match pool_map::get_by_pool_id(swap.pool_id) {
-                    Some(pool) => pool,
-                    None => continue, // should not get here
-                };
-
-                if swap.receive_token_id == pool.token_id_1 {
-                    // user pays token_0 and receives token_1
-                    pool.balance_0 = nat_add(&pool.balance_0, &swap.pay_amount); // pay_amount is in token_0
-                    pool.balance_1 = nat_subtract(&pool.balance_1, &swap.receive_amount).unwrap_or(nat_zero()); // receive_amount is in token_1
-                                                                                                                // fees are in token_1. take out Kong's fee
-                                                                                                                // kong_fee_1 = lp_fee * kong_fee_bps / lp_fee_bps
-                                                                                                                // lp_fee_1 = lp_fee - kong_fee_1
-                    let numerator = nat_multiply(&swap.lp_fee, &Nat::from(pool.kong_fee_bps)); //swap.lp_fee is in token_1
-                    let kong_fee_1 = nat_divide(&numerator, &Nat::from(pool.lp_fee_bps)).unwrap_or(nat_zero());
-                    let lp_fee_1 = nat_subtract(&swap.lp_fee, &kong_fee_1).unwrap_or(nat_zero());
-                    pool.lp_fee_1 = nat_add(&pool.lp_fee_1, &lp_fee_1);
-                    pool.kong_fee_1 = nat_add(&pool.kong_fee_1, &kong_fee_1);
-                } else {
-                    // user pays token_1 and receives token_0
-                    pool.balance_1 = nat_add(&pool.balance_1, &swap.pay_amount); // pay_amount is in token_1
-                    pool.balance_0 = nat_subtract(&pool.balance_0, &swap.receive_amount).unwrap_or(nat_zero()); // receive_amount is in token_0
-                                                                                                                // fees are in token_0. take out Kong's fee
-                                                                                                                // kong_fee_0 = lp_fee * kong_fee_bps / lp_fee_bps
-                                                                                                                // lp_fee_0 = lp_fee - kong_fee_0
-                    let numerator = nat_multiply(&swap.lp_fee, &Nat::from(pool.kong_fee_bps)); //swap.lp_fee is in token_0
-                    let kong_fee_0 = nat_divide(&numerator, &Nat::from(pool.lp_fee_bps)).unwrap_or(nat_zero());
-                    let lp_fee_0 = nat_subtract(&swap.lp_fee, &kong_fee_0).unwrap_or(nat_zero());
-                    pool.lp_fee_0 = nat_add(&pool.lp_fee_0, &lp_fee_0);
-                    pool.kong_fee_0 = nat_add(&pool.kong_fee_0, &kong_fee_0);
-                }
-                pool_map::update(&pool);
+            if let Err(e) = swaps
+                .iter()
+                .try_for_each(|swap| pool_lock::check_unlocked(swap.pool_id))
+                .and_then(|_| check_pool_amounts(&swaps))
+                .and_then(|_| swaps.iter().try_for_each(|swap| update_pool_amounts(request_id, swap)))
+            {
+                request_map::update_status(request_id, StatusCode::UpdatePoolAmountsFailed, Some(&e));
+                return Err(e);
             }
 
             request_map::update_status(request_id, StatusCode::UpdatePoolAmountsSuccess, None);
+            // referee volume is recorded once per request, not per leg
+            add_referral_volume(request_id, &swaps);
 
             Ok((receive_amount_with_fees_and_gas, mid_price, price, slippage, swaps))
         }
@@ -70,3 +69,293 @@ pub fn update_liquidity_pool(
         }
     }
 }
+
+/// update the pool with the amounts and fees of a swap
+/// refreshes the pool first to make sure we have the latest state
+/// a concentrated liquidity pool is moved through its positions first, if that fails the pool is left untouched
+pub fn update_pool_amounts(request_id: u64, swap: &SwapCalc) -> Result<(), String> {
+    // refresh pool with the latest state
+    let mut pool = pool_map::get_by_pool_id(swap.pool_id).ok_or_else(|| format!("Pool #{} not found", swap.pool_id))?;
+    let old_price = pool.get_price().and_then(|price| price.to_f64());
+    // fees are in the receive token. take out Kong's fee
+    // kong_fee = lp_fee * kong_fee_bps / lp_fee_bps, lp_fee_bps is the fee charged by the swap
+    // lp_fee = lp_fee - kong_fee
+    let kong_fee = kong_fee(swap, pool.kong_fee_bps);
+    let lp_fee = nat_subtract(&swap.lp_fee, &kong_fee).unwrap_or(nat_zero());
+    // concentrated liquidity pools also move the price through the positions in range
+    if pool.concentrated.is_some() {
+        update_concentrated_pool(&mut pool, swap, &lp_fee)?;
+    }
+    // LP token holders earn the LP fee in proportion to their LP tokens. concentrated liquidity pools accrue it to the positions instead
+    let lp_total_supply = if pool.concentrated.is_none() {
+        lp_token_map::get_total_supply(pool.lp_token_id)
+    } else {
+        nat_zero()
+    };
+    // referrer's share of Kong's fee is paid out as a claim
+    let referral_reward = accrue_referral_reward(request_id, swap, &kong_fee);
+    let kong_fee = nat_subtract(&kong_fee, &referral_reward).unwrap_or(nat_zero());
+
+    if swap.receive_token_id == pool.token_id_1 {
+        // user pays token_0 and receives token_1
+        pool.balance_0 = nat_add(&pool.balance_0, &swap.pay_amount); // pay_amount is in token_0
+        pool.balance_1 = nat_subtract(&pool.balance_1, &swap.receive_amount).unwrap_or(nat_zero()); // receive_amount is in token_1
+        pool.lp_fee_1 = nat_add(&pool.lp_fee_1, &lp_fee); // fees are in token_1
+        pool.lp_fee_per_lp_1 = nat_add(&pool.lp_fee_per_lp_1, &lp_fee_per_lp(&lp_fee, &lp_total_supply));
+        pool.kong_fee_1 = nat_add(&pool.kong_fee_1, &kong_fee);
+    } else {
+        // user pays token_1 and receives token_0
+        pool.balance_1 = nat_add(&pool.balance_1, &swap.pay_amount); // pay_amount is in token_1
+        pool.balance_0 = nat_subtract(&pool.balance_0, &swap.receive_amount).unwrap_or(nat_zero()); // receive_amount is in token_0
+        pool.lp_fee_0 = nat_add(&pool.lp_fee_0, &lp_fee); // fees are in token_0
+        pool.lp_fee_per_lp_0 = nat_add(&pool.lp_fee_per_lp_0, &lp_fee_per_lp(&lp_fee, &lp_total_supply));
+        pool.kong_fee_0 = nat_add(&pool.kong_fee_0, &kong_fee);
+    }
+    // accumulate the price move of the swap for the pool's dynamic fee
+    let new_price = pool.get_price().and_then(|price| price.to_f64());
+    if let (Some(dynamic_fee), Some(old_price), Some(new_price)) = (pool.dynamic_fee.as_mut(), old_price, new_price) {
+        dynamic_fee.add_price_move(old_price, new_price, ICNetwork::get_time());
+    }
+    pool_map::update(&pool);
+    Ok(())
+}
+
+/// check that every swap can be applied to its pool before any pool is updated, so a multi-hop or split swap is never half done
+/// only concentrated liquidity pools can fail. legs through the same pool are checked one after the other
+fn check_pool_amounts(swaps: &[SwapCalc]) -> Result<(), String> {
+    let mut pools: BTreeMap<u32, StablePool> = BTreeMap::new();
+    for swap in swaps {
+        let pool = match pools.entry(swap.pool_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(pool_map::get_by_pool_id(swap.pool_id).ok_or_else(|| format!("Pool #{} not found", swap.pool_id))?)
+            }
+        };
+        if pool.concentrated.is_some() {
+            let (pays_token_0, receive_amount_in_max_decimals) = concentrated_receive_amount(pool, swap);
+            concentrated_liquidity::move_price(pool, pays_token_0, &receive_amount_in_max_decimals)?;
+        }
+    }
+    Ok(())
+}
+
+/// Kong's share of the LP fee of a swap
+/// the LP fee was charged at swap.lp_fee_bps, which is the pool's live fee with dynamic fees, so split with it
+/// and never take more than the LP fee
+fn kong_fee(swap: &SwapCalc, kong_fee_bps: u8) -> Nat {
+    let numerator = nat_multiply(&swap.lp_fee, &Nat::from(kong_fee_bps));
+    let kong_fee = nat_divide(&numerator, &Nat::from(swap.lp_fee_bps)).unwrap_or(nat_zero());
+    std::cmp::min(kong_fee, swap.lp_fee.clone())
+}
+
+/// move the price of a concentrated liquidity pool for the swap and accrue the LP fee to the positions the swap went through
+fn update_concentrated_pool(pool: &mut StablePool, swap: &SwapCalc, lp_fee: &Nat) -> Result<(), String> {
+    let (pays_token_0, receive_amount_in_max_decimals) = concentrated_receive_amount(pool, swap);
+    concentrated_liquidity::apply_swap(pool, pays_token_0, &receive_amount_in_max_decimals, lp_fee)
+}
+
+/// whether the swap pays token_0 and its receive amount in the max_decimals precision of the pool
+fn concentrated_receive_amount(pool: &StablePool, swap: &SwapCalc) -> (bool, Nat) {
+    let pays_token_0 = swap.receive_token_id == pool.token_id_1;
+    let token_0 = pool.token_0();
+    let token_1 = pool.token_1();
+    let max_decimals = std::cmp::max(token_0.decimals(), token_1.decimals());
+    let receive_decimals = if pays_token_0 { token_1.decimals() } else { token_0.decimals() };
+    (
+        pays_token_0,
+        nat_to_decimal_precision(&swap.receive_amount, receive_decimals, max_decimals),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::nat_helpers::nat_is_zero;
+    use crate::helpers::test_helpers::{
+        init_kong_settings, insert_concentrated_pool, insert_ic_token, insert_pool, insert_position, insert_user, update_kong_settings,
+    };
+    use crate::stable_claim::claim_map;
+    use crate::stable_memory::USER_MAP;
+    use crate::stable_pool::dynamic_fee::DynamicFee;
+    use crate::stable_request::{request::Request, stable_request::StableRequest};
+    use crate::stable_user::referral_map;
+    use crate::stable_user::stable_user::{StableUser, StableUserId};
+    use candid::Principal;
+
+    const USER: u32 = 100;
+    const REFERRER: u32 = 101;
+
+    // user pays 1 token_0 and receives token_1 with a 0.3% LP fee of 30_000 token_1
+    fn swap() -> SwapCalc {
+        SwapCalc {
+            pool_id: 1,
+            pay_token_id: 1,
+            pay_amount: Nat::from(10_000_000_u64),
+            receive_token_id: 2,
+            receive_amount: Nat::from(10_000_000_u64),
+            lp_fee: Nat::from(30_000_u64),
+            gas_fee: nat_zero(),
+            lp_fee_bps: 30,
+        }
+    }
+
+    fn setup(referred_by: Option<u32>) -> u64 {
+        insert_ic_token(1, "ICP", 8);
+        insert_ic_token(2, "ckUSDT", 6);
+        init_kong_settings();
+        insert_pool(1, 1, 1_000_000_000, 2, 1_000_000_000);
+        let user = StableUser {
+            referred_by,
+            ..insert_user(USER, Principal::anonymous())
+        };
+        USER_MAP.with(|m| m.borrow_mut().insert(StableUserId(USER), user));
+        insert_user(REFERRER, Principal::anonymous());
+        // referrer earns half of Kong's fee
+        update_kong_settings(|s| s.referral_fee_share_bps = 5_000);
+        request_map::insert(&StableRequest::new(USER, &Request::CancelOrder(1), 0))
+    }
+
+    #[test]
+    fn test_update_pool_amounts_referral_reward_taken_out_of_kong_fee() {
+        let request_id = setup(Some(REFERRER));
+
+        update_pool_amounts(request_id, &swap()).unwrap();
+
+        // kong_fee = 30_000 * 8 / 30 = 8_000, of which the referrer gets 4_000. the LP fee is not touched
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.lp_fee_1, Nat::from(22_000_u64));
+        assert_eq!(pool.kong_fee_1, Nat::from(4_000_u64));
+        assert_eq!(pool.kong_fee_0, nat_zero());
+        let referral = referral_map::get(REFERRER, 2).unwrap();
+        let claim = claim_map::get_by_claim_id(referral.claim_id.unwrap()).unwrap();
+        assert_eq!((claim.user_id, claim.token_id, claim.amount), (REFERRER, 2, Nat::from(4_000_u64)));
+
+        // the next swap adds to the open claim
+        update_pool_amounts(request_id, &swap()).unwrap();
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.lp_fee_1, Nat::from(44_000_u64));
+        assert_eq!(pool.kong_fee_1, Nat::from(8_000_u64));
+        let claim = claim_map::get_by_claim_id(referral.claim_id.unwrap()).unwrap();
+        assert_eq!(claim.amount, Nat::from(8_000_u64));
+    }
+
+    #[test]
+    fn test_referral_volume_is_the_pay_amount_of_the_request() {
+        let request_id = setup(Some(REFERRER));
+        // ICP -> ckUSDT -> ckBTC. the second leg pays the ckUSDT received from the first
+        let swaps = [
+            swap(),
+            SwapCalc {
+                pay_token_id: 2,
+                receive_token_id: 3,
+                ..swap()
+            },
+        ];
+
+        add_referral_volume(request_id, &swaps);
+
+        assert_eq!(referral_map::get(REFERRER, 1).unwrap().volume, Nat::from(10_000_000_u64));
+        assert!(referral_map::get(REFERRER, 2).is_none());
+        // fees accrued per leg do not add volume
+        update_pool_amounts(request_id, &swaps[0]).unwrap();
+        assert_eq!(referral_map::get(REFERRER, 1).unwrap().volume, Nat::from(10_000_000_u64));
+        assert_eq!(referral_map::get(REFERRER, 2).unwrap().volume, nat_zero());
+    }
+
+    #[test]
+    fn test_update_pool_amounts_without_referrer_keeps_kong_fee() {
+        let request_id = setup(None);
+
+        update_pool_amounts(request_id, &swap()).unwrap();
+
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.lp_fee_1, Nat::from(22_000_u64));
+        assert_eq!(pool.kong_fee_1, Nat::from(8_000_u64));
+        assert!(referral_map::get(REFERRER, 2).is_none());
+    }
+
+    #[test]
+    fn test_update_pool_amounts_splits_kong_fee_at_the_dynamic_fee_charged() {
+        let request_id = setup(None);
+        // calm pool with min 10 / static 30 / kong 8 charges 10 bps
+        let mut pool = pool_map::get_by_pool_id(1).unwrap();
+        pool.dynamic_fee = Some(DynamicFee::new(10, 50, 200, 600));
+        pool_map::update(&pool);
+        let lp_fee_bps = pool.live_lp_fee_bps();
+        assert_eq!(lp_fee_bps, 10);
+        let swap = SwapCalc {
+            lp_fee: Nat::from(10_000_u64),
+            lp_fee_bps,
+            ..swap()
+        };
+
+        update_pool_amounts(request_id, &swap).unwrap();
+
+        // kong_fee = 10_000 * 8 / 10 = 8_000, not 10_000 * 8 / 30
+        let pool = pool_map::get_by_pool_id(1).unwrap();
+        assert_eq!(pool.kong_fee_1, Nat::from(8_000_u64));
+        assert_eq!(pool.lp_fee_1, Nat::from(2_000_u64));
+    }
+
+    #[test]
+    fn test_kong_fee_never_more_than_lp_fee() {
+        // user fee discount charged 4 bps, below Kong's 8 bps
+        let swap = SwapCalc {
+            lp_fee: Nat::from(4_000_u64),
+            lp_fee_bps: 4,
+            ..swap()
+        };
+        assert_eq!(kong_fee(&swap, 8), Nat::from(4_000_u64));
+        // no LP fee charged
+        let swap = SwapCalc {
+            lp_fee: nat_zero(),
+            lp_fee_bps: 0,
+            ..swap
+        };
+        assert_eq!(kong_fee(&swap, 8), nat_zero());
+    }
+
+    #[test]
+    fn test_update_pool_amounts_failed_concentrated_swap_leaves_pool() {
+        let request_id = setup(None);
+        insert_concentrated_pool(2, 1, 2, 60);
+        insert_position(USER, 2, -60, 60, 1_000_000_000);
+        let mut pool = pool_map::get_by_pool_id(2).unwrap();
+        pool.balance_0 = Nat::from(1_000_000_000_u64);
+        pool.balance_1 = Nat::from(1_000_000_000_u64);
+        pool_map::update(&pool);
+        // more than the liquidity in range can pay out
+        let swap = SwapCalc {
+            pool_id: 2,
+            receive_amount: Nat::from(100_000_000_u64),
+            ..swap()
+        };
+
+        assert!(check_pool_amounts(std::slice::from_ref(&swap)).is_err());
+        assert!(update_pool_amounts(request_id, &swap).is_err());
+
+        let pool = pool_map::get_by_pool_id(2).unwrap();
+        assert_eq!(pool.balance_0, Nat::from(1_000_000_000_u64));
+        assert_eq!(pool.balance_1, Nat::from(1_000_000_000_u64));
+        assert!(nat_is_zero(&pool.lp_fee_1) && nat_is_zero(&pool.kong_fee_1));
+        assert_eq!(pool.concentrated.unwrap().tick, 0);
+    }
+
+    #[test]
+    fn test_check_pool_amounts_legs_through_the_same_pool() {
+        setup(None);
+        insert_concentrated_pool(2, 1, 2, 60);
+        insert_position(USER, 2, -60, 60, 1_000_000_000);
+        // each leg fits in the liquidity in range, both together do not
+        let concentrated_swap = SwapCalc {
+            pool_id: 2,
+            receive_amount: Nat::from(20_000_u64), // 2_000_000 in the 8 decimals of the pool
+            ..swap()
+        };
+        assert!(check_pool_amounts(std::slice::from_ref(&concentrated_swap)).is_ok());
+        assert!(check_pool_amounts(&[swap(), concentrated_swap.clone(), concentrated_swap]).is_err());
+        // nothing is written by the check
+        assert_eq!(pool_map::get_by_pool_id(2).unwrap().concentrated.unwrap().tick, 0);
+    }
+}
@@ -1,4 +1,5 @@
 #[allow(clippy::module_inception)]
 pub mod swap_amounts;
+pub mod swap_amounts_exact_out;
 pub mod swap_amounts_reply;
 pub mod swap_amounts_reply_impl;
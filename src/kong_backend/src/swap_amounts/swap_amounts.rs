@@ -4,13 +4,14 @@ use ic_cdk::query;
 use crate::ic::guards::not_in_maintenance_mode;
 use crate::stable_token::token::Token;
 use crate::stable_token::token_map;
+use crate::stable_user::user_map;
 use crate::swap;
 
 use super::swap_amounts_reply::SwapAmountsReply;
 use super::swap_amounts_reply_impl::to_swap_amounts_tx_reply;
 
 #[query(guard = "not_in_maintenance_mode")]
-pub fn swap_amounts(pay_token: String, pay_amount: Nat, receive_token: String) -> Result<SwapAmountsReply, String> {
+pub fn swap_amounts(pay_token: String, pay_amount: Nat, receive_token: String, max_routes: Option<u8>) -> Result<SwapAmountsReply, String> {
     // Pay token
     let pay_token = token_map::get_by_token(&pay_token)?;
     let pay_chain = pay_token.chain();
@@ -22,8 +23,14 @@ pub fn swap_amounts(pay_token: String, pay_amount: Nat, receive_token: String) -
     let receive_symbol = receive_token.symbol();
     let receive_address = receive_token.address();
 
-    let (receive_amount, price, mid_price, slippage, txs) =
-        swap::swap_amounts::swap_amounts(&pay_token, Some(&pay_amount), &receive_token)?;
+    let user_fee_level = user_map::get_caller_fee_level();
+    // split the swap across multiple routes if requested
+    let (receive_amount, price, mid_price, slippage, txs) = match max_routes {
+        Some(max_routes) if max_routes > 1 => {
+            swap::swap_amounts::split_swap_amounts(&pay_token, &pay_amount, &receive_token, max_routes, user_fee_level)?
+        }
+        _ => swap::swap_amounts::swap_amounts(&pay_token, Some(&pay_amount), &receive_token, user_fee_level)?,
+    };
     let swap_amounts_tx_reply: Vec<_> = txs.iter().filter_map(to_swap_amounts_tx_reply).collect();
 
     Ok(SwapAmountsReply {
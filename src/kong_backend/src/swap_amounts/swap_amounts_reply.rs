@@ -15,6 +15,7 @@ pub struct SwapAmountsTxReply {
     pub price: f64,
     pub lp_fee: Nat,
     pub gas_fee: Nat,
+    pub lp_fee_bps: u8,
 }
 
 #[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
@@ -1,4 +1,4 @@
-use candid::{CandidType, Nat};
+use candid::{CandidType, Nat, Principal};
 use serde::{Deserialize, Serialize};
 
 #[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
@@ -13,4 +13,5 @@ pub struct LPReply {
     pub fee: Nat,
     pub total_supply: Nat,
     pub is_removed: bool,
+    pub ledger_id: Option<Principal>,
 }
@@ -1,5 +1,6 @@
 pub mod ic_reply;
 pub mod lp_reply;
+pub mod ripple_reply;
 pub mod solana_reply;
 #[allow(clippy::module_inception)]
 pub mod tokens;
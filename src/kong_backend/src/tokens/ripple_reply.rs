@@ -0,0 +1,14 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct RippleReply {
+    pub token_id: u32,
+    pub chain: String,
+    pub name: String,
+    pub symbol: String,
+    pub currency: String,
+    pub issuer: Option<String>,
+    pub decimals: u8,
+    pub fee: Nat,
+}
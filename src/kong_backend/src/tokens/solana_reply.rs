@@ -12,4 +12,5 @@ pub struct SolanaReply {
     pub decimals: u8,
     pub fee: Nat,
     pub is_spl_token: bool,
+    pub has_transfer_fee: bool,
 }
\ No newline at end of file
@@ -3,11 +3,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::stable_lp_token::lp_token_map;
 use crate::stable_token::stable_token::StableToken;
-use crate::stable_token::stable_token::StableToken::{Solana, IC, LP};
+use crate::stable_token::stable_token::StableToken::{Ripple, Solana, IC, LP};
 use crate::stable_token::token::Token;
 
 use super::ic_reply::ICReply;
 use super::lp_reply::LPReply;
+use super::ripple_reply::RippleReply;
 use super::solana_reply::SolanaReply;
 
 #[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
@@ -15,6 +16,7 @@ pub enum TokensReply {
     LP(LPReply),
     IC(ICReply),
     Solana(SolanaReply),
+    Ripple(RippleReply),
 }
 
 impl From<&StableToken> for TokensReply {
@@ -35,6 +37,7 @@ impl From<&StableToken> for TokensReply {
                 fee: token.fee(),
                 total_supply: lp_token_map::get_total_supply(token_id),
                 is_removed: token.is_removed(),
+                ledger_id: lp_token.ledger_id,
             }),
             IC(ic_token) => TokensReply::IC(ICReply {
                 token_id,
@@ -59,6 +62,17 @@ impl From<&StableToken> for TokensReply {
                 decimals: token.decimals(),
                 fee: token.fee(),
                 is_spl_token: solana_token.is_spl_token,
+                has_transfer_fee: solana_token.has_transfer_fee,
+            }),
+            Ripple(ripple_token) => TokensReply::Ripple(RippleReply {
+                token_id,
+                chain: token.chain(),
+                name: token.name(),
+                symbol: token.symbol(),
+                currency: ripple_token.currency.clone(),
+                issuer: ripple_token.issuer.clone(),
+                decimals: token.decimals(),
+                fee: token.fee(),
             }),
         }
     }
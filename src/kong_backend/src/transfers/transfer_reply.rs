@@ -1,7 +1,7 @@
 use candid::{CandidType, Deserialize, Nat};
 use serde::Serialize;
 
-use crate::chains::chains::{IC_CHAIN, SOL_CHAIN};
+use crate::chains::chains::{IC_CHAIN, SOL_CHAIN, XRP_CHAIN};
 use crate::stable_token::stable_token::StableToken;
 use crate::stable_transfer::stable_transfer::StableTransfer;
 use crate::stable_transfer::tx_id::TxId;
@@ -16,6 +16,7 @@ pub struct TransferIdReply {
 pub enum TransferReply {
     IC(ICTransferReply),
     Solana(SolanaTransferReply),
+    Ripple(RippleTransferReply),
 }
 
 #[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +39,16 @@ pub struct SolanaTransferReply {
     pub signature: String,
 }
 
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct RippleTransferReply {
+    pub chain: String,
+    pub symbol: String,
+    pub is_send: bool, // from user's perspective. so if is_send is true, it means the user is sending the token
+    pub amount: Nat,
+    pub address: String, // "XRP" or "CUR.rIssuer"
+    pub tx_hash: String,
+}
+
 impl From<ICTransferReply> for TransferReply {
     fn from(reply: ICTransferReply) -> Self {
         TransferReply::IC(reply)
@@ -50,6 +61,12 @@ impl From<SolanaTransferReply> for TransferReply {
     }
 }
 
+impl From<RippleTransferReply> for TransferReply {
+    fn from(reply: RippleTransferReply) -> Self {
+        TransferReply::Ripple(reply)
+    }
+}
+
 impl TryFrom<(u64, &StableTransfer, &StableToken)> for TransferIdReply {
     type Error = String;
     
@@ -85,6 +102,22 @@ impl TryFrom<(u64, &StableTransfer, &StableToken)> for TransferIdReply {
                 }),
                 _ => Err("A TransactionId is expected for Solana tokens".to_string()),
             },
+            // Case 3: The token is an XRPL token
+            StableToken::Ripple(token) => match &transfer.tx_id {
+                TxId::TransactionId(tx_hash) => Ok(TransferIdReply {
+                    transfer_id,
+                    transfer: RippleTransferReply {
+                        chain: XRP_CHAIN.to_string(),
+                        symbol: token.symbol.clone(),
+                        is_send: transfer.is_send,
+                        amount: transfer.amount.clone(),
+                        address: token.address(),
+                        tx_hash: tx_hash.clone(),
+                    }
+                    .into(),
+                }),
+                _ => Err("A TransactionId is expected for XRPL tokens".to_string()),
+            },
             _ => Err("Unsupported token type".to_string()),
         }
     }
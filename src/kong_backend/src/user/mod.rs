@@ -1,2 +1,5 @@
 pub mod get_user;
+pub mod referral_reward;
+pub mod referral_stats;
+pub mod referral_stats_reply;
 pub mod user_reply;
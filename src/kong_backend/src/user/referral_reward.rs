@@ -0,0 +1,79 @@
+use candid::Nat;
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply, nat_zero};
+use crate::ic::network::ICNetwork;
+use crate::stable_claim::claim_map;
+use crate::stable_claim::stable_claim::{ClaimStatus, StableClaim};
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_request::request_map;
+use crate::stable_user::referral_map;
+use crate::stable_user::user_map;
+use crate::swap::swap_calc::SwapCalc;
+
+/// add the volume of a swap request to the referrer of the user who made the request
+/// the volume is the user's pay amount, the pay amount of the first leg of each route. intermediate legs of a
+/// multi-hop swap are not counted
+pub fn add_referral_volume(request_id: u64, swaps: &[SwapCalc]) {
+    let Some(pay_token_id) = swaps.first().map(|swap| swap.pay_token_id) else {
+        return;
+    };
+    let ts = ICNetwork::get_time();
+    let Some(referrer_user_id) = active_referrer(request_id, ts) else {
+        return;
+    };
+    let pay_amount = swaps
+        .iter()
+        .filter(|swap| swap.pay_token_id == pay_token_id)
+        .fold(nat_zero(), |acc, swap| nat_add(&acc, &swap.pay_amount));
+    referral_map::add_volume(referrer_user_id, pay_token_id, &pay_amount, ts);
+}
+
+/// accrue the referral reward of a swap to the referrer of the user who made the request
+/// the reward is referral_fee_share_bps of Kong's fee and is paid in the receive token of the swap
+/// rewards accrue to an open claim of the referrer which they collect with claim()
+///
+/// # Returns
+///
+/// * the referral reward taken out of Kong's fee. 0 if the user has no active referrer
+pub fn accrue_referral_reward(request_id: u64, swap: &SwapCalc, kong_fee: &Nat) -> Nat {
+    let referral_fee_share_bps = std::cmp::min(kong_settings_map::get().referral_fee_share_bps, 10_000);
+    if referral_fee_share_bps == 0 {
+        return nat_zero();
+    }
+    let ts = ICNetwork::get_time();
+    let Some(referrer_user_id) = active_referrer(request_id, ts) else {
+        return nat_zero();
+    };
+
+    let reward = nat_divide(&nat_multiply(kong_fee, &Nat::from(referral_fee_share_bps)), &Nat::from(10_000_u16)).unwrap_or(nat_zero());
+    if nat_is_zero(&reward) {
+        return nat_zero();
+    }
+
+    let token_id = swap.receive_token_id;
+    // add to the referrer's open claim, or open a new one if it has been claimed
+    let claim_id = match referral_map::get(referrer_user_id, token_id)
+        .and_then(|referral| referral.claim_id)
+        .and_then(|claim_id| claim_map::add_claimable_amount(claim_id, &reward))
+    {
+        Some(claim) => claim.claim_id,
+        None => {
+            let mut claim = StableClaim::new(referrer_user_id, token_id, &reward, Some(request_id), None, ts);
+            claim.status = ClaimStatus::Claimable;
+            claim.desc = Some("Referral reward".to_string());
+            claim_map::insert(&claim)
+        }
+    };
+    referral_map::add_reward(referrer_user_id, token_id, &reward, claim_id, ts);
+
+    reward
+}
+
+/// referrer of the user who made the request, None if the user has no referrer or the referral has expired
+fn active_referrer(request_id: u64, ts: u64) -> Option<u32> {
+    let user = request_map::get_by_request_id(request_id).and_then(|request| user_map::get_by_user_id(request.user_id))?;
+    if user.referred_by_expires_at.is_some_and(|expires_at| ts > expires_at) {
+        return None;
+    }
+    user.referred_by
+}
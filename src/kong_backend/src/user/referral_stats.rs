@@ -0,0 +1,41 @@
+use ic_cdk::query;
+
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_memory::USER_MAP;
+use crate::stable_user::referral_map;
+use crate::stable_user::user_map;
+
+use super::referral_stats_reply::{ReferralStatsReply, ReferralTokenReply};
+
+/// Return the referees, referred swap volume and referral rewards earned per token of a user
+#[query(guard = "not_in_maintenance_mode")]
+fn referral_stats(principal_id: String) -> Result<ReferralStatsReply, String> {
+    let user = user_map::get_by_principal_id(&principal_id)
+        .ok()
+        .flatten()
+        .ok_or("User not found")?;
+
+    let ts = ICNetwork::get_time();
+    let (referees, active_referees) = USER_MAP.with(|m| {
+        m.borrow()
+            .iter()
+            .filter(|(_, v)| v.referred_by == Some(user.user_id))
+            .fold((0, 0), |(referees, active_referees), (_, v)| {
+                let active = v.referred_by_expires_at.is_none_or(|expires_at| ts <= expires_at);
+                (referees + 1, if active { active_referees + 1 } else { active_referees })
+            })
+    });
+
+    Ok(ReferralStatsReply {
+        my_referral_code: user.my_referral_code,
+        referral_fee_share_bps: std::cmp::min(kong_settings_map::get().referral_fee_share_bps, 10_000),
+        referees,
+        active_referees,
+        tokens: referral_map::get_by_referrer_user_id(user.user_id)
+            .iter()
+            .map(ReferralTokenReply::from)
+            .collect(),
+    })
+}
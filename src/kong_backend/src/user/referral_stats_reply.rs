@@ -0,0 +1,42 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::stable_token::token::Token;
+use crate::stable_token::token_map;
+use crate::stable_user::stable_referral::StableReferral;
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct ReferralTokenReply {
+    pub chain: String,
+    pub symbol: String,
+    pub address: String,
+    pub volume: Nat,
+    pub earned: Nat,
+    pub claim_id: Option<u64>,
+}
+
+impl From<&StableReferral> for ReferralTokenReply {
+    fn from(referral: &StableReferral) -> Self {
+        let (chain, symbol, address) = match token_map::get_by_token_id(referral.token_id) {
+            Some(token) => (token.chain(), token.symbol(), token.address()),
+            None => ("".to_string(), "".to_string(), "".to_string()),
+        };
+        ReferralTokenReply {
+            chain,
+            symbol,
+            address,
+            volume: referral.volume.clone(),
+            earned: referral.earned.clone(),
+            claim_id: referral.claim_id,
+        }
+    }
+}
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct ReferralStatsReply {
+    pub my_referral_code: String,
+    pub referral_fee_share_bps: u16,
+    pub referees: u32,        // users who signed up with the referral code
+    pub active_referees: u32, // referees whose referral has not expired
+    pub tokens: Vec<ReferralTokenReply>,
+}
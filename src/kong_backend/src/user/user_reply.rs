@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::convert::From;
 
 use crate::ic::network::ICNetwork;
+use crate::stable_kong_settings::fee_tier::next_fee_tier;
+use crate::stable_kong_settings::kong_settings_map;
 use crate::stable_user::stable_user::StableUser;
 use crate::stable_user::user_map;
 
@@ -16,6 +18,11 @@ pub struct UserReply {
     pub referred_by_expires_at: Option<u64>,
     pub fee_level: u8,
     pub fee_level_expires_at: Option<u64>,
+    pub earned_fee_level: u8,
+    pub current_fee_level: u8,              // fee level used for swaps
+    pub volume_30d: f64,                    // rolling 30-day swap volume in USD
+    pub next_fee_level: Option<u8>,         // fee level of the next fee tier
+    pub next_fee_level_volume: Option<f64>, // 30-day swap volume needed for the next fee tier
 }
 
 impl From<&StableUser> for UserReply {
@@ -27,7 +34,17 @@ impl From<&StableUser> for UserReply {
             .referred_by
             .and_then(|referred_user| user_map::get_by_user_id(referred_user)
                 .map(|referred_user| referred_user.my_referral_code));
-        
+        let ts = ICNetwork::get_time();
+        // hide an expired fee level the fee tiers timer has not cleared yet
+        let (fee_level, fee_level_expires_at) = if user.fee_level_expires_at.is_some_and(|expires_at| ts > expires_at) {
+            (0, None)
+        } else {
+            (user.fee_level, user.fee_level_expires_at)
+        };
+        let current_fee_level = user.current_fee_level(ts);
+        let fee_tiers = kong_settings_map::get().fee_tiers;
+        let next_fee_tier = next_fee_tier(&fee_tiers, current_fee_level);
+
         UserReply {
             user_id: user.user_id,
             principal_id: user.principal_id.clone(),
@@ -35,8 +52,13 @@ impl From<&StableUser> for UserReply {
             my_referral_code: user.my_referral_code.clone(),
             referred_by,
             referred_by_expires_at: user.referred_by_expires_at,
-            fee_level: user.fee_level,
-            fee_level_expires_at: user.fee_level_expires_at,
+            fee_level,
+            fee_level_expires_at,
+            earned_fee_level: user.earned_fee_level,
+            current_fee_level,
+            volume_30d: user.volume_30d,
+            next_fee_level: next_fee_tier.map(|tier| tier.fee_level),
+            next_fee_level_volume: next_fee_tier.map(|tier| tier.min_volume),
         }
     }
 }
@@ -0,0 +1,81 @@
+use candid::Nat;
+use ic_cdk::query;
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_is_zero, nat_multiply, nat_zero};
+use crate::ic::ckusdt::{ckusdt_amount, to_ckusdt_decimals_f64};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::stable_lp_token::lp_token_map;
+use crate::stable_pool::pool_map;
+use crate::stable_token::stable_token::StableToken;
+use crate::stable_token::token::Token;
+use crate::stable_user::user_map;
+
+use super::position_pnl_reply::PositionPnlReply;
+
+/// Return the deposited amounts, current value, fees earned and impermanent loss of a user's LP tokens in a pool
+#[query(guard = "not_in_maintenance_mode")]
+pub fn position_pnl(principal_id: String, pool: String) -> Result<PositionPnlReply, String> {
+    let user_id = user_map::get_by_principal_id(&principal_id)
+        .ok()
+        .flatten()
+        .ok_or("User not found")?
+        .user_id;
+    let pool = pool_map::get_by_token(&pool)?;
+    if pool.is_concentrated() {
+        Err(format!("Pool {} is a concentrated liquidity pool. Use positions", pool.symbol()))?
+    }
+    let lp_token = lp_token_map::get_by_token_id_by_user_id(pool.lp_token_id, user_id)
+        .filter(|lp_token| !nat_is_zero(&lp_token.amount))
+        .ok_or(format!("No LP tokens in pool {}", pool.symbol()))?;
+    let lp_total_supply = lp_token_map::get_total_supply(pool.lp_token_id);
+
+    // user's share of the pool. amount_0 = (balance_0 + lp_fee_0) * user_lp_token_balance / lp_token_total_supply
+    let share_of = |reserve: &Nat| nat_divide(&nat_multiply(reserve, &lp_token.amount), &lp_total_supply).unwrap_or(nat_zero());
+    let amount_0 = share_of(&nat_add(&pool.balance_0, &pool.lp_fee_0));
+    let amount_1 = share_of(&nat_add(&pool.balance_1, &pool.lp_fee_1));
+
+    // fees earned including the fees since the LP token was last updated
+    let (pending_fee_0, pending_fee_1) = lp_token.pending_fees(&pool);
+    let fees_0 = nat_add(&lp_token.fees_0, &pending_fee_0);
+    let fees_1 = nat_add(&lp_token.fees_1, &pending_fee_1);
+
+    let token_0 = pool.token_0();
+    let token_1 = pool.token_1();
+    let usd_value = usd_amount(&token_0, &amount_0) + usd_amount(&token_1, &amount_1);
+    let usd_fees = usd_amount(&token_0, &fees_0) + usd_amount(&token_1, &fees_1);
+    // LP tokens from before cost basis was tracked have no deposits
+    let usd_deposit = if nat_is_zero(&lp_token.deposit_0) && nat_is_zero(&lp_token.deposit_1) {
+        None
+    } else {
+        Some(usd_amount(&token_0, &lp_token.deposit_0) + usd_amount(&token_1, &lp_token.deposit_1))
+    };
+
+    Ok(PositionPnlReply {
+        pool_id: pool.pool_id,
+        symbol: pool.symbol(),
+        lp_token_symbol: pool.lp_token().symbol(),
+        balance: lp_token.amount.clone(),
+        symbol_0: token_0.symbol(),
+        deposit_0: lp_token.deposit_0.clone(),
+        amount_0,
+        fees_0,
+        symbol_1: token_1.symbol(),
+        deposit_1: lp_token.deposit_1.clone(),
+        amount_1,
+        fees_1,
+        usd_deposit,
+        usd_value,
+        usd_fees,
+        usd_impermanent_loss: usd_deposit.map(|usd_deposit| usd_deposit - (usd_value - usd_fees)),
+        usd_pnl: usd_deposit.map(|usd_deposit| usd_value - usd_deposit),
+        ts: ICNetwork::get_time(),
+    })
+}
+
+fn usd_amount(token: &StableToken, amount: &Nat) -> f64 {
+    ckusdt_amount(token, amount)
+        .ok()
+        .and_then(|usd_amount| to_ckusdt_decimals_f64(&usd_amount))
+        .unwrap_or(0_f64)
+}
@@ -0,0 +1,24 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct PositionPnlReply {
+    pub pool_id: u32,
+    pub symbol: String,
+    pub lp_token_symbol: String,
+    pub balance: Nat, // LP tokens held
+    pub symbol_0: String,
+    pub deposit_0: Nat, // cost basis. amount of token_0 deposited for the LP tokens held
+    pub amount_0: Nat,  // current value. share of the pool's token_0 including LP fees
+    pub fees_0: Nat,    // LP fees in token_0 earned by the LP tokens held
+    pub symbol_1: String,
+    pub deposit_1: Nat,
+    pub amount_1: Nat,
+    pub fees_1: Nat,
+    pub usd_deposit: Option<f64>, // value of the deposited amounts at current prices. None if the cost basis is not known
+    pub usd_value: f64,           // value of amount_0 and amount_1
+    pub usd_fees: f64,            // value of fees_0 and fees_1
+    pub usd_impermanent_loss: Option<f64>, // usd_deposit - (usd_value - usd_fees). loss against holding the deposited amounts
+    pub usd_pnl: Option<f64>,     // usd_value - usd_deposit. usd_fees - usd_impermanent_loss
+    pub ts: u64,
+}
@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+pub mod zap_in;
+pub mod zap_in_amounts;
+pub mod zap_in_args;
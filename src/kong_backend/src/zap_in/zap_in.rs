@@ -0,0 +1,321 @@
+use candid::Nat;
+use ic_cdk::update;
+
+use crate::add_liquidity::add_liquidity::TokenIndex;
+use crate::add_liquidity::add_liquidity_reply::AddLiquidityReply;
+use crate::add_liquidity::add_liquidity_transfer_from::{self, transfer_from_token, update_liquidity_pool};
+use crate::helpers::nat_helpers::{nat_add, nat_is_zero, nat_subtract, nat_zero};
+use crate::ic::guards::not_in_maintenance_mode;
+use crate::ic::network::ICNetwork;
+use crate::positions::transfer_position_token::{return_token, return_unused_token};
+use crate::stable_kong_settings::kong_settings_map;
+use crate::stable_pool::{pool_lock, pool_map, stable_pool::StablePool};
+use crate::stable_request::{reply::Reply, request::Request, request_map, stable_request::StableRequest, status::StatusCode};
+use crate::stable_token::{stable_token::StableToken, token::Token, token_map};
+use crate::stable_tx::{add_liquidity_tx::AddLiquidityTx, stable_tx::StableTx, swap_tx::SwapTx, tx_map};
+use crate::stable_user::user_map;
+use crate::swap::swap_amounts::swap_pool_amounts;
+use crate::swap::swap_calc::SwapCalc;
+use crate::swap::update_liquidity_pool::update_pool_amounts;
+
+use super::zap_in_amounts::zap_swap_amount;
+use super::zap_in_args::ZapInArgs;
+
+/// Add liquidity with a single token
+///
+/// Part of amount is swapped in the pool for the other token so the rest of the deposit and the received amount are
+/// in the pool ratio. Both are then added to the pool for LP tokens and any amount not needed is returned
+/// Token is taken with icrc2_transfer_from
+#[update(guard = "not_in_maintenance_mode")]
+pub async fn zap_in(args: ZapInArgs) -> Result<AddLiquidityReply, String> {
+    let (user_id, pool, token, pays_token_0, max_slippage) = check_arguments(&args)?;
+    let ts = ICNetwork::get_time();
+    let request_id = request_map::insert(&StableRequest::new(user_id, &Request::ZapIn(args.clone()), ts));
+
+    let result = process_zap_in(request_id, user_id, &pool, &token, pays_token_0, &args.amount, max_slippage, ts).await;
+    match result {
+        Ok(_) => request_map::update_status(request_id, StatusCode::Success, None),
+        Err(_) => request_map::update_status(request_id, StatusCode::Failed, None),
+    };
+    _ = archive_to_kong_data(request_id);
+
+    result
+}
+
+/// check the arguments and that the swap is within max_slippage with the current state of the pool
+fn check_arguments(args: &ZapInArgs) -> Result<(u32, StablePool, StableToken, bool, f64), String> {
+    if nat_is_zero(&args.amount) {
+        Err("Invalid zero amount".to_string())?
+    }
+
+    let pool = pool_map::get_by_token(&args.pool)?;
+    if pool.is_removed {
+        Err(format!("Pool {} is removed", pool.symbol()))?
+    }
+    if pool.is_concentrated() {
+        Err(format!(
+            "Pool {} is a concentrated liquidity pool. Use mint_position()",
+            pool.symbol()
+        ))?
+    }
+    let token = token_map::get_by_token(&args.token)?;
+    let pays_token_0 = if token.token_id() == pool.token_id_0 {
+        true
+    } else if token.token_id() == pool.token_id_1 {
+        false
+    } else {
+        Err(format!("Token {} is not in pool {}", token.symbol(), pool.symbol()))?
+    };
+    let (token_0, token_1) = (pool.token_0(), pool.token_1());
+    if token_0.is_removed() || token_1.is_removed() {
+        Err("Token is suspended or removed".to_string())?
+    }
+    if !token_0.is_icrc2() || !token_1.is_icrc2() {
+        Err("Tokens must support ICRC2".to_string())?
+    }
+
+    let max_slippage = args.max_slippage.unwrap_or(kong_settings_map::get().default_max_slippage);
+    zap_amounts(&pool, pays_token_0, &args.amount, max_slippage)?;
+
+    // make sure user is registered, if not create a new user
+    let user_id = user_map::insert(None)?;
+
+    Ok((user_id, pool, token, pays_token_0, max_slippage))
+}
+
+/// calculate the swap of the zap with the state of the pool
+/// returns (swap_amount, receive_amount, mid_price, price, slippage, swap)
+#[allow(clippy::type_complexity)]
+fn zap_amounts(
+    pool: &StablePool,
+    pays_token_0: bool,
+    amount: &Nat,
+    max_slippage: f64,
+) -> Result<(Nat, Nat, f64, f64, f64, SwapCalc), String> {
+    // reserves are the total balances of the pool, as in add_liquidity()
+    let reserve_0 = nat_add(&pool.balance_0, &pool.lp_fee_0);
+    let reserve_1 = nat_add(&pool.balance_1, &pool.lp_fee_1);
+    if nat_is_zero(&reserve_0) || nat_is_zero(&reserve_1) {
+        Err(format!("Pool {} has no liquidity", pool.symbol()))?
+    }
+    let (reserve_in, reserve_out) = if pays_token_0 {
+        (reserve_0, reserve_1)
+    } else {
+        (reserve_1, reserve_0)
+    };
+
+    let swap_amount = zap_swap_amount(amount, &reserve_in, &reserve_out, |pay_amount| {
+        swap_pool_amounts(pool, pays_token_0, pay_amount)
+            .map(|(receive_amount, ..)| receive_amount)
+            .unwrap_or(nat_zero())
+    });
+    let (receive_amount, price, mid_price, slippage, swap) = swap_pool_amounts(pool, pays_token_0, &swap_amount)?;
+    if nat_is_zero(&receive_amount) {
+        Err("Amount is too small to swap".to_string())?
+    }
+    if slippage > max_slippage {
+        Err(format!("Slippage exceeded. Swap of the zap has {}% slippage", slippage))?
+    }
+
+    Ok((swap_amount, receive_amount, mid_price, price, slippage, swap))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_zap_in(
+    request_id: u64,
+    user_id: u32,
+    pool: &StablePool,
+    token: &StableToken,
+    pays_token_0: bool,
+    amount: &Nat,
+    max_slippage: f64,
+    ts: u64,
+) -> Result<AddLiquidityReply, String> {
+    let caller_id = ICNetwork::caller_id();
+    let kong_backend = kong_settings_map::get().kong_backend;
+    let token_index = if pays_token_0 { TokenIndex::Token0 } else { TokenIndex::Token1 };
+    let receive_token = if pays_token_0 { pool.token_1() } else { pool.token_0() };
+    let mut transfer_ids = Vec::new();
+    let mut claim_ids = Vec::new();
+
+    request_map::update_status(request_id, StatusCode::Start, None);
+
+    if let Err(e) = transfer_from_token(
+        request_id,
+        &caller_id,
+        &token_index,
+        token,
+        amount,
+        &kong_backend,
+        &mut transfer_ids,
+        ts,
+    )
+    .await
+    {
+        failed_reply(request_id, pool, &transfer_ids, &claim_ids, ts);
+        Err(format!("Req #{} failed. {}", request_id, e))?
+    }
+
+    // swap with the latest state of the pool, as it could have changed during the transfer
+    request_map::update_status(request_id, StatusCode::ZapSwap, None);
+    let zap = pool_map::get_by_pool_id(pool.pool_id)
+        .ok_or_else(|| format!("Pool #{} not found", pool.pool_id))
+        .and_then(|pool| pool_lock::check_unlocked(pool.pool_id).and_then(|_| zap_amounts(&pool, pays_token_0, amount, max_slippage)))
+        .and_then(|zap| update_pool_amounts(request_id, &zap.5).map(|_| zap));
+    let (swap_amount, receive_amount, mid_price, price, slippage, swap) = match zap {
+        Ok(zap) => zap,
+        Err(e) => {
+            request_map::update_status(request_id, StatusCode::ZapSwapFailed, Some(&e));
+            return_token(
+                request_id,
+                user_id,
+                &caller_id,
+                &token_index,
+                token,
+                amount,
+                &mut transfer_ids,
+                &mut claim_ids,
+                ts,
+            )
+            .await;
+            failed_reply(request_id, pool, &transfer_ids, &claim_ids, ts);
+            Err(format!("Req #{} failed. {}", request_id, e))?
+        }
+    };
+    let swap_tx = SwapTx::new_success(
+        user_id,
+        request_id,
+        token.token_id(),
+        &swap_amount,
+        receive_token.token_id(),
+        &receive_amount,
+        mid_price,
+        price,
+        slippage,
+        &[swap],
+        &[],
+        &[],
+        ts,
+    );
+    let swap_tx_id = tx_map::insert(&StableTx::Swap(swap_tx));
+    request_map::update_status(request_id, StatusCode::ZapSwapSuccess, Some(&format!("Swap tx #{}", swap_tx_id)));
+
+    // the rest of the deposit and the received amount are added to the pool
+    let deposit_amount = nat_subtract(amount, &swap_amount).unwrap_or(nat_zero());
+    let (add_amount_0, add_amount_1) = if pays_token_0 {
+        (deposit_amount, receive_amount)
+    } else {
+        (receive_amount, deposit_amount)
+    };
+    let (pool, amount_0, amount_1, add_lp_token_amount) =
+        match update_liquidity_pool(request_id, user_id, pool, &add_amount_0, &add_amount_1, ts) {
+            Ok(added) => added,
+            Err(e) => {
+                // return both tokens as the swap has been done
+                return_token(
+                    request_id,
+                    user_id,
+                    &caller_id,
+                    &TokenIndex::Token0,
+                    &pool.token_0(),
+                    &add_amount_0,
+                    &mut transfer_ids,
+                    &mut claim_ids,
+                    ts,
+                )
+                .await;
+                return_token(
+                    request_id,
+                    user_id,
+                    &caller_id,
+                    &TokenIndex::Token1,
+                    &pool.token_1(),
+                    &add_amount_1,
+                    &mut transfer_ids,
+                    &mut claim_ids,
+                    ts,
+                )
+                .await;
+                failed_reply(request_id, pool, &transfer_ids, &claim_ids, ts);
+                Err(format!("Req #{} failed. {}", request_id, e))?
+            }
+        };
+
+    // return the amounts not needed for the liquidity, unless too small to pay the gas fee
+    let (token_0, token_1) = (pool.token_0(), pool.token_1());
+    let return_amount = |add_amount: &Nat, amount: &Nat, fee: Nat| -> Nat {
+        let unused_amount = nat_subtract(add_amount, amount).unwrap_or(nat_zero());
+        if unused_amount > fee {
+            unused_amount
+        } else {
+            nat_zero()
+        }
+    };
+    return_unused_token(
+        request_id,
+        user_id,
+        &caller_id,
+        &TokenIndex::Token0,
+        &token_0,
+        &return_amount(&add_amount_0, &amount_0, token_0.fee()),
+        &mut transfer_ids,
+        &mut claim_ids,
+        ts,
+    )
+    .await;
+    return_unused_token(
+        request_id,
+        user_id,
+        &caller_id,
+        &TokenIndex::Token1,
+        &token_1,
+        &return_amount(&add_amount_1, &amount_1, token_1.fee()),
+        &mut transfer_ids,
+        &mut claim_ids,
+        ts,
+    )
+    .await;
+
+    // successful, add tx linked to the swap and update request with reply
+    let add_liquidity_tx = AddLiquidityTx {
+        swap_tx_id: Some(swap_tx_id),
+        ..AddLiquidityTx::new_success(
+            pool.pool_id,
+            user_id,
+            request_id,
+            &amount_0,
+            &amount_1,
+            &add_lp_token_amount,
+            &transfer_ids,
+            &claim_ids,
+            ts,
+        )
+    };
+    let tx_id = tx_map::insert(&StableTx::AddLiquidity(add_liquidity_tx));
+    let reply = match tx_map::get_by_user_and_token_id(Some(tx_id), None, None, None).first() {
+        Some(StableTx::AddLiquidity(add_liquidity_tx)) => AddLiquidityReply::try_from(add_liquidity_tx)
+            .unwrap_or_else(|_| AddLiquidityReply::failed(pool.pool_id, request_id, &transfer_ids, &claim_ids, ts)),
+        _ => AddLiquidityReply::failed(pool.pool_id, request_id, &transfer_ids, &claim_ids, ts),
+    };
+    request_map::update_reply(request_id, Reply::AddLiquidity(reply.clone()));
+
+    Ok(reply)
+}
+
+fn failed_reply(request_id: u64, pool: &StablePool, transfer_ids: &[u64], claim_ids: &[u64], ts: u64) {
+    let reply = AddLiquidityReply::failed(pool.pool_id, request_id, transfer_ids, claim_ids, ts);
+    request_map::update_reply(request_id, Reply::AddLiquidity(reply));
+}
+
+fn archive_to_kong_data(request_id: u64) -> Result<(), String> {
+    add_liquidity_transfer_from::archive_to_kong_data(request_id)?;
+
+    // archive the internal swap
+    if let Some(Reply::AddLiquidity(reply)) = request_map::get_by_request_id(request_id).map(|request| request.reply) {
+        if let Some(swap_tx_id) = reply.swap_tx_id {
+            tx_map::archive_to_kong_data(swap_tx_id)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use candid::Nat;
+
+use crate::helpers::nat_helpers::{nat_add, nat_divide, nat_multiply, nat_subtract, nat_zero};
+
+// stop the search when the swap amount is within amount / ZAP_TOLERANCE
+const ZAP_TOLERANCE: u64 = 1_000_000_000;
+const MAX_ITERATIONS: u32 = 128;
+
+/// amount of the deposit to swap so the rest of the deposit and the received amount are in the pool ratio after the swap
+///
+/// swapping s of amount in the pool gives receive_amount(s) of the other token and the deposit is in ratio when
+/// (amount - s) / receive_amount(s) = (reserve_in + s) / (reserve_out - receive_amount(s))
+/// the left side falls and the right side rises as s grows, so s is found by bisection
+pub fn zap_swap_amount<F>(amount: &Nat, reserve_in: &Nat, reserve_out: &Nat, receive_amount: F) -> Nat
+where
+    F: Fn(&Nat) -> Nat,
+{
+    let tolerance = nat_divide(amount, &Nat::from(ZAP_TOLERANCE)).unwrap_or(nat_zero());
+    let mut low = nat_zero();
+    let mut high = amount.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let range = nat_subtract(&high, &low).unwrap_or(nat_zero());
+        if range <= tolerance || range <= 1_u8 {
+            break;
+        }
+        let mid = nat_divide(&nat_add(&low, &high), &Nat::from(2_u8)).unwrap_or(nat_zero());
+        let out = receive_amount(&mid);
+        // (amount - mid) * (reserve_out - out) > out * (reserve_in + mid) means too little was swapped
+        let deposit_side = nat_multiply(
+            &nat_subtract(amount, &mid).unwrap_or(nat_zero()),
+            &nat_subtract(reserve_out, &out).unwrap_or(nat_zero()),
+        );
+        let receive_side = nat_multiply(&out, &nat_add(reserve_in, &mid));
+        if deposit_side > receive_side {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    high
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::nat_helpers::nat_to_f64;
+
+    const RESERVE: u128 = 1_000_000_000_000;
+
+    // constant product swap with lp_fee_bps
+    fn constant_product(reserve_in: u128, reserve_out: u128, lp_fee_bps: u128) -> impl Fn(&Nat) -> Nat {
+        move |pay_amount: &Nat| {
+            let pay_amount = nat_divide(&nat_multiply(pay_amount, &Nat::from(10_000 - lp_fee_bps)), &Nat::from(10_000_u128)).unwrap();
+            let numerator = nat_multiply(&pay_amount, &Nat::from(reserve_out));
+            nat_divide(&numerator, &nat_add(&Nat::from(reserve_in), &pay_amount)).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_zap_swap_amount_without_fee() {
+        // without fees s = sqrt(reserve_in^2 + amount * reserve_in) - reserve_in = RESERVE for amount = 3 * RESERVE
+        let amount = Nat::from(3 * RESERVE);
+        let s = zap_swap_amount(
+            &amount,
+            &Nat::from(RESERVE),
+            &Nat::from(RESERVE),
+            constant_product(RESERVE, RESERVE, 0),
+        );
+        let diff = if s > RESERVE {
+            nat_subtract(&s, &Nat::from(RESERVE)).unwrap()
+        } else {
+            nat_subtract(&Nat::from(RESERVE), &s).unwrap()
+        };
+        assert!(diff <= 3 * RESERVE / ZAP_TOLERANCE as u128 + 1);
+    }
+
+    #[test]
+    fn test_zap_swap_amount_with_fee_swaps_more() {
+        let amount = Nat::from(RESERVE / 10);
+        let reserve_out = 2 * RESERVE;
+        let without_fee = zap_swap_amount(
+            &amount,
+            &Nat::from(RESERVE),
+            &Nat::from(reserve_out),
+            constant_product(RESERVE, reserve_out, 0),
+        );
+        let with_fee = zap_swap_amount(
+            &amount,
+            &Nat::from(RESERVE),
+            &Nat::from(reserve_out),
+            constant_product(RESERVE, reserve_out, 30),
+        );
+        assert!(with_fee > without_fee);
+        assert!(with_fee < amount);
+    }
+
+    #[test]
+    fn test_zap_swap_amount_deposit_in_pool_ratio() {
+        let amount = Nat::from(RESERVE / 4);
+        let reserve_out = RESERVE / 2;
+        let receive_amount = constant_product(RESERVE, reserve_out, 30);
+        let s = zap_swap_amount(&amount, &Nat::from(RESERVE), &Nat::from(reserve_out), &receive_amount);
+        let out = receive_amount(&s);
+        // ratio of the deposit and of the pool after the swap agree to 1e-6
+        let deposit = nat_subtract(&amount, &s).unwrap();
+        let lhs = nat_multiply(&deposit, &nat_subtract(&Nat::from(reserve_out), &out).unwrap());
+        let rhs = nat_multiply(&out, &nat_add(&Nat::from(RESERVE), &s));
+        let lhs = nat_to_f64(&lhs).unwrap();
+        let rhs = nat_to_f64(&rhs).unwrap();
+        assert!((lhs - rhs).abs() / rhs < 1e-6);
+    }
+
+    #[test]
+    fn test_zap_swap_amount_zero() {
+        let s = zap_swap_amount(
+            &nat_zero(),
+            &Nat::from(RESERVE),
+            &Nat::from(RESERVE),
+            constant_product(RESERVE, RESERVE, 30),
+        );
+        assert_eq!(s, nat_zero());
+    }
+}
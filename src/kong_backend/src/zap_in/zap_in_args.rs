@@ -0,0 +1,12 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+/// Data structure for the arguments of the `zap_in` function.
+/// Used in StableRequest
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct ZapInArgs {
+    pub token: String,             // token to deposit. must be token_0 or token_1 of the pool
+    pub amount: Nat,               // amount of token to deposit
+    pub pool: String,              // pool symbol or its LP token, ie. ckBTC_ckUSDT or LP.ckBTC_ckUSDT
+    pub max_slippage: Option<f64>, // maximum slippage of the internal swap
+}
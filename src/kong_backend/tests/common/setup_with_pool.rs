@@ -11,6 +11,7 @@ use super::setup::{setup_ic_environment, CONTROLLER_PEM_FILE};
 use kong_backend::add_pool::add_pool_args::AddPoolArgs;
 use kong_backend::add_pool::add_pool_reply::AddPoolReply;
 use kong_backend::pools::pools_reply::PoolReply;
+use kong_backend::stable_pool::pool_type::PoolType;
 use kong_backend::stable_transfer::tx_id::TxId;
 
 // Use the token constants from the default setup
@@ -188,6 +189,8 @@ pub fn setup_swap_test_environment() -> Result<SwapTestSetup> {
         amount_1: Nat::from(base_liquidity_b),
         tx_id_1: Some(TxId::BlockIndex(token_b_tx_id)),
         lp_fee_bps: Some(30),
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None
     };
@@ -221,6 +224,8 @@ pub fn setup_swap_test_environment() -> Result<SwapTestSetup> {
         lp_fee_1: Nat::from(0u64), // not provided in AddPoolReply
         price: 0.0, // not provided in AddPoolReply
         lp_fee_bps: add_pool_reply.lp_fee_bps,
+        live_lp_fee_bps: add_pool_reply.lp_fee_bps, // new pools do not have dynamic fees
+        pool_type: PoolType::ConstantProduct,
         lp_token_symbol: add_pool_reply.lp_token_symbol.clone(),
         is_removed: add_pool_reply.is_removed,
     };
@@ -194,6 +194,8 @@ fn test_add_pool_icrc2_transfer_from() {
         amount_1: token_b_liquidity_amount.clone(),
         tx_id_1: None,
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
@@ -419,6 +421,8 @@ fn test_add_pool_with_icrc1_icrc2_mix() {
         amount_1: token_b_liquidity_amount.clone(),
         tx_id_1: None, // No tx_id for Token B, will use approve
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
@@ -654,6 +658,8 @@ fn test_add_pool_with_icrc1_transfer() {
         amount_1: token_b_liquidity_amount.clone(),
         tx_id_1: Some(TxId::BlockIndex(transfer_result_b_to_kong.unwrap())),
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
@@ -868,6 +874,8 @@ fn test_add_pool_insufficient_token0_balance() {
         amount_1: token_b_liquidity_amount.clone(),
         tx_id_1: None, // Use approve for Token B
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
@@ -1097,6 +1105,8 @@ fn test_add_pool_insufficient_token1_balance() {
         amount_1: token_b_liquidity_amount.clone(), // This exceeds user's available balance
         tx_id_1: None,                              // Use approve for Token B
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
@@ -1344,6 +1354,8 @@ fn test_add_pool_insufficient_allowance() {
         amount_1: token_b_liquidity_amount.clone(),
         tx_id_1: None, // Use approve for Token B
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
@@ -1562,6 +1574,8 @@ fn test_add_pool_setup() {
         amount_1: token_b_liquidity_amount.clone(),
         tx_id_1: Some(TxId::BlockIndex(tx_id_b)), // Use the transaction ID from the transfer
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
@@ -1636,6 +1650,8 @@ fn test_add_pool_identical_tokens() {
         amount_1: token_liquidity_amount.clone(),
         tx_id_1: None,
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
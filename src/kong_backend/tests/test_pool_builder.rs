@@ -467,6 +467,8 @@ impl TestSetup {
             amount_1: token_b_amount,
             tx_id_1: tx_id_b.map(TxId::BlockIndex),
             lp_fee_bps: config.lp_fee_bps,
+            weight_0: None,
+            tick_spacing: None,
             signature_0: None,
             signature_1: None,
         };
@@ -677,6 +679,8 @@ pub fn run_pool_test(config: PoolTestConfig) -> Result<()> {
             amount_1: setup.token_b_liquidity_amount.clone(),
             tx_id_1: other_user_tx_id_b.map(TxId::BlockIndex), // Use other user's tx ID
             lp_fee_bps: None,
+            weight_0: None,
+            tick_spacing: None,
             signature_0: None,
             signature_1: None,
         };
@@ -914,6 +918,8 @@ fn test_add_pool_with_other_user_tx_id() {
         amount_1: token_b_amount,
         tx_id_1: Some(TxId::BlockIndex(tx_id_b)),  // Using OTHER user's tx ID
         lp_fee_bps: None,
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None,
     };
@@ -102,7 +102,9 @@ fn test_swap_approve_transfer_from_a_to_b() {
         receive_address: Some(user_principal.to_text()),             // Explicitly set receive address
         max_slippage: Some(50.0),                                    // Explicitly allow up to 50% slippage for this test
         referred_by: None,
-        pay_signature: None
+        pay_signature: None,
+        max_routes: None,
+        exact_out: None,
     };
     let swap_payload_approve = encode_one(&swap_args_approve).expect("Failed to encode swap_args_approve ");
 
@@ -247,7 +249,9 @@ fn test_swap_direct_transfer_a_to_b() {
         receive_address: Some(user_principal.to_text()),           // Explicitly set receive address
         max_slippage: Some(50.0),                                  // Explicitly allow up to 50% slippage
         referred_by: None,
-        pay_signature: None
+        pay_signature: None,
+        max_routes: None,
+        exact_out: None,
     };
     let swap_payload_direct_a = encode_one(&swap_args_direct_a).expect("Failed to encode swap_args_direct_a ");
 
@@ -426,6 +430,8 @@ fn test_swap_with_transfer_fee_token() {
         amount_1: liquidity_b_amount.clone(),
         tx_id_1: Some(TxId::BlockIndex(b_tx_id)),
         lp_fee_bps: Some(30),
+        weight_0: None,
+        tick_spacing: None,
         signature_0: None,
         signature_1: None
     };
@@ -485,7 +491,9 @@ fn test_swap_with_transfer_fee_token() {
         receive_address: Some(user_principal.to_text()),
         max_slippage: Some(50.0),
         referred_by: None,
-        pay_signature: None
+        pay_signature: None,
+        max_routes: None,
+        exact_out: None,
     };
     
     let user_b_balance_before_swap = get_icrc1_balance(&ic, token_b_ledger_id, user_account);
@@ -583,7 +591,9 @@ fn test_swap_direct_transfer_b_to_a() {
         receive_address: Some(user_principal.to_text()),        // Explicitly set receive address
         max_slippage: Some(50.0),                               // Explicitly allow up to 50% slippage
         referred_by: None,
-        pay_signature: None
+        pay_signature: None,
+        max_routes: None,
+        exact_out: None,
     };
     let swap_payload_direct_b = encode_one(&swap_args_direct_b).expect("Failed to encode swap_args_direct_b ");
 
@@ -729,6 +739,8 @@ fn test_swap_duplicate_block_id_protection() {
         max_slippage: Some(100.0), // 100% slippage tolerance for test
         referred_by: None,
         pay_signature: None,
+        max_routes: None,
+        exact_out: None,
     };
     
     let payload_1 = encode_one(swap_args_1).expect("Failed to encode swap args 1");
@@ -755,6 +767,8 @@ fn test_swap_duplicate_block_id_protection() {
         max_slippage: Some(100.0),
         referred_by: None,
         pay_signature: None,
+        max_routes: None,
+        exact_out: None,
     };
     
     let payload_2 = encode_one(swap_args_2).expect("Failed to encode swap args 2");
@@ -93,6 +93,33 @@ fn update_user(stable_user_json: String) -> Result<String, String> {
     Ok("User updated".to_string())
 }
 
+/// users updated by kong_backend in a batch, e.g. the fee tiers update
+#[update(hidden = true, guard = "caller_is_kong_backend")]
+fn update_user_batch(stable_users_json: String) -> Result<String, String> {
+    let users: Vec<StableUser> = match serde_json::from_str(&stable_users_json) {
+        Ok(users) => users,
+        Err(e) => return Err(format!("Invalid users: {}", e)),
+    };
+
+    let ts = get_time();
+    for user in users {
+        USER_MAP.with(|m| {
+            m.borrow_mut().insert(StableUserId(user.user_id), user.clone());
+        });
+        principal_id_map::insert_principal_id(&user);
+
+        // add to UpdateMap for archiving to database
+        let update = StableDBUpdate {
+            db_update_id: 0,
+            stable_memory: StableMemory::UserMap(user),
+            ts,
+        };
+        db_update_map::insert(&update);
+    }
+
+    Ok("Users updated".to_string())
+}
+
 #[update(hidden = true, guard = "caller_is_kingkong")]
 fn clear_users() -> Result<String, String> {
     USER_MAP.with(|m| {
@@ -24,6 +24,18 @@ pub struct StableLPToken {
     pub token_id: u32,    // token id of the token
     pub amount: Nat,      // amount the user holds of the token
     pub ts: u64,          // timestamp of the last token update
+    #[serde(default)]
+    pub deposit_0: Nat, // cost basis. amount of token_0 deposited for the LP tokens held
+    #[serde(default)]
+    pub deposit_1: Nat,
+    #[serde(default)]
+    pub fees_0: Nat, // LP fees in token_0 earned by the LP tokens held
+    #[serde(default)]
+    pub fees_1: Nat,
+    #[serde(default)]
+    pub lp_fee_per_lp_0: Nat, // pool's lp_fee_per_lp_0 when fees_0 was last updated
+    #[serde(default)]
+    pub lp_fee_per_lp_1: Nat,
 }
 
 impl Storable for StableLPToken {
@@ -0,0 +1,24 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use super::status_tx::StatusTx;
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct SweptPoolFee {
+    pub pool_id: u32,
+    pub amount: Nat,
+}
+
+#[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolFeeSweepTx {
+    pub tx_id: u64,
+    pub user_id: u32,
+    pub request_id: u64,
+    pub status: StatusTx,
+    pub token_id: u32,
+    pub amount: Nat,
+    pub pools: Vec<SweptPoolFee>,
+    pub to_address: String,
+    pub transfer_ids: Vec<u64>,
+    pub ts: u64,
+}
@@ -13,6 +13,7 @@ impl Tx for StableTx {
             StableTx::RemoveLiquidity(tx) => tx.tx_id,
             StableTx::Swap(tx) => tx.tx_id,
             StableTx::Send(tx) => tx.tx_id,
+            StableTx::ProtocolFeeSweep(tx) => tx.tx_id,
         }
     }
 
@@ -23,6 +24,7 @@ impl Tx for StableTx {
             StableTx::RemoveLiquidity(tx) => tx.user_id,
             StableTx::Swap(tx) => tx.user_id,
             StableTx::Send(tx) => tx.user_id,
+            StableTx::ProtocolFeeSweep(tx) => tx.user_id,
         }
     }
 }
@@ -69,6 +69,11 @@ pub fn get_by_user_and_token_id(
                                 return Some(v.clone());
                             }
                         }
+                        StableTx::ProtocolFeeSweep(ref protocol_fee_sweep_tx) => {
+                            if protocol_fee_sweep_tx.token_id == token_id {
+                                return Some(v.clone());
+                            }
+                        }
                     }
                     return None;
                 }
@@ -5,6 +5,7 @@ use crate::add_liquidity::add_liquidity_reply::AddLiquidityReply;
 use crate::add_pool::add_pool_reply::AddPoolReply;
 use crate::remove_liquidity::remove_liquidity_reply::RemoveLiquidityReply;
 use crate::send::send_reply::SendReply;
+use crate::stable_tx::protocol_fee_sweep_tx::ProtocolFeeSweepTx;
 use crate::swap::swap_reply::SwapReply;
 
 #[derive(CandidType, Debug, Clone, Serialize, Deserialize)]
@@ -14,4 +15,5 @@ pub enum TxsReply {
     RemoveLiquidity(RemoveLiquidityReply),
     Swap(SwapReply),
     Send(SendReply),
+    ProtocolFeeSweep(ProtocolFeeSweepTx),
 }
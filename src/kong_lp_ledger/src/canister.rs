@@ -0,0 +1,22 @@
+use candid::{CandidType, Principal};
+use ic_cdk::{init, post_upgrade};
+use serde::Deserialize;
+
+use crate::kong_backend::set_kong_backend;
+
+#[derive(CandidType, Debug, Clone, Deserialize)]
+pub struct LPLedgerArgs {
+    pub kong_backend: Principal,
+}
+
+#[init]
+fn init(args: LPLedgerArgs) {
+    set_kong_backend(args.kong_backend);
+}
+
+#[post_upgrade]
+fn post_upgrade(args: LPLedgerArgs) {
+    set_kong_backend(args.kong_backend);
+}
+
+ic_cdk::export_candid!();
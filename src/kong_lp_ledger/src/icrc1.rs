@@ -0,0 +1,115 @@
+use candid::{CandidType, Nat};
+use ic_cdk::{query, update};
+use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue;
+use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc1::transfer::{TransferArg, TransferError};
+use serde::Deserialize;
+
+use crate::kong_backend::{call, kong_backend, query};
+
+/// error_code of GenericError replies when kong_backend cannot be called
+pub const CALL_ERROR_CODE: u32 = 1;
+
+#[derive(CandidType, Debug, Clone, Deserialize)]
+pub struct StandardRecord {
+    pub name: String,
+    pub url: String,
+}
+
+async fn metadata_entry(key: &str) -> MetadataValue {
+    query::<_, Vec<(String, MetadataValue)>>("lp_icrc1_metadata", &())
+        .await
+        .into_iter()
+        .find_map(|(k, v)| if k == key { Some(v) } else { None })
+        .unwrap_or_else(|| ic_cdk::trap(format!("{} not found", key)))
+}
+
+#[query(composite = true)]
+async fn icrc1_name() -> String {
+    match metadata_entry("icrc1:name").await {
+        MetadataValue::Text(name) => name,
+        _ => ic_cdk::trap("Invalid icrc1:name"),
+    }
+}
+
+#[query(composite = true)]
+async fn icrc1_symbol() -> String {
+    match metadata_entry("icrc1:symbol").await {
+        MetadataValue::Text(symbol) => symbol,
+        _ => ic_cdk::trap("Invalid icrc1:symbol"),
+    }
+}
+
+#[query(composite = true)]
+async fn icrc1_decimals() -> u8 {
+    match metadata_entry("icrc1:decimals").await {
+        MetadataValue::Nat(decimals) => u8::try_from(decimals.0).unwrap_or_else(|_| ic_cdk::trap("Invalid icrc1:decimals")),
+        _ => ic_cdk::trap("Invalid icrc1:decimals"),
+    }
+}
+
+#[query(composite = true)]
+async fn icrc1_fee() -> Nat {
+    match metadata_entry("icrc1:fee").await {
+        MetadataValue::Nat(fee) => fee,
+        _ => ic_cdk::trap("Invalid icrc1:fee"),
+    }
+}
+
+#[query(composite = true)]
+async fn icrc1_metadata() -> Vec<(String, MetadataValue)> {
+    query("lp_icrc1_metadata", &()).await
+}
+
+#[query(composite = true)]
+async fn icrc1_total_supply() -> Nat {
+    query("lp_icrc1_total_supply", &()).await
+}
+
+/// LP tokens are minted to liquidity providers by kong_backend. transfers to kong_backend burn them
+#[query]
+fn icrc1_minting_account() -> Option<Account> {
+    Some(Account::from(kong_backend()))
+}
+
+#[query(composite = true)]
+async fn icrc1_balance_of(account: Account) -> Nat {
+    query("lp_icrc1_balance_of", &(account,)).await
+}
+
+#[update]
+async fn icrc1_transfer(args: TransferArg) -> Result<Nat, TransferError> {
+    call("lp_icrc1_transfer", &(ic_cdk::api::msg_caller(), args))
+        .await
+        .unwrap_or_else(|message| {
+            Err(TransferError::GenericError {
+                error_code: Nat::from(CALL_ERROR_CODE),
+                message,
+            })
+        })
+}
+
+fn supported_standards() -> Vec<StandardRecord> {
+    [
+        ("ICRC-1", "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-1"),
+        ("ICRC-2", "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2"),
+        ("ICRC-3", "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-3"),
+        ("ICRC-10", "https://github.com/dfinity/ICRC/tree/main/ICRCs/ICRC-10"),
+    ]
+    .into_iter()
+    .map(|(name, url)| StandardRecord {
+        name: name.to_string(),
+        url: url.to_string(),
+    })
+    .collect()
+}
+
+#[query]
+fn icrc1_supported_standards() -> Vec<StandardRecord> {
+    supported_standards()
+}
+
+#[query]
+fn icrc10_supported_standards() -> Vec<StandardRecord> {
+    supported_standards()
+}
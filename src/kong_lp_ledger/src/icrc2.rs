@@ -0,0 +1,37 @@
+use candid::Nat;
+use ic_cdk::{query, update};
+use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
+use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc2::transfer_from::{TransferFromArgs, TransferFromError};
+
+use crate::icrc1::CALL_ERROR_CODE;
+use crate::kong_backend::{call, query};
+
+#[update]
+async fn icrc2_approve(args: ApproveArgs) -> Result<Nat, ApproveError> {
+    call("lp_icrc2_approve", &(ic_cdk::api::msg_caller(), args))
+        .await
+        .unwrap_or_else(|message| {
+            Err(ApproveError::GenericError {
+                error_code: Nat::from(CALL_ERROR_CODE),
+                message,
+            })
+        })
+}
+
+#[query(composite = true)]
+async fn icrc2_allowance(args: AllowanceArgs) -> Allowance {
+    query("lp_icrc2_allowance", &(args,)).await
+}
+
+#[update]
+async fn icrc2_transfer_from(args: TransferFromArgs) -> Result<Nat, TransferFromError> {
+    call("lp_icrc2_transfer_from", &(ic_cdk::api::msg_caller(), args))
+        .await
+        .unwrap_or_else(|message| {
+            Err(TransferFromError::GenericError {
+                error_code: Nat::from(CALL_ERROR_CODE),
+                message,
+            })
+        })
+}
@@ -0,0 +1,27 @@
+use ic_cdk::query;
+use icrc_ledger_types::icrc3::archive::{GetArchivesArgs, GetArchivesResult};
+use icrc_ledger_types::icrc3::blocks::{GetBlocksRequest, GetBlocksResult, SupportedBlockType};
+
+use crate::kong_backend::query;
+
+#[query(composite = true)]
+async fn icrc3_get_blocks(args: Vec<GetBlocksRequest>) -> GetBlocksResult {
+    query("lp_icrc3_get_blocks", &(args,)).await
+}
+
+/// blocks are never archived
+#[query]
+fn icrc3_get_archives(_args: GetArchivesArgs) -> GetArchivesResult {
+    Vec::new()
+}
+
+#[query]
+fn icrc3_supported_block_types() -> Vec<SupportedBlockType> {
+    ["1mint", "1burn", "1xfer", "2xfer", "2approve"]
+        .into_iter()
+        .map(|block_type| SupportedBlockType {
+            block_type: block_type.to_string(),
+            url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-3".to_string(),
+        })
+        .collect()
+}
@@ -0,0 +1,35 @@
+use candid::utils::ArgumentEncoder;
+use candid::{CandidType, Principal};
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+
+thread_local! {
+    // kong_backend holding the LP token. set by init and post_upgrade
+    static KONG_BACKEND: RefCell<Principal> = const { RefCell::new(Principal::anonymous()) };
+}
+
+pub fn set_kong_backend(kong_backend: Principal) {
+    KONG_BACKEND.with(|k| *k.borrow_mut() = kong_backend);
+}
+
+pub fn kong_backend() -> Principal {
+    KONG_BACKEND.with(|k| *k.borrow())
+}
+
+/// call a method of kong_backend
+pub async fn call<A: ArgumentEncoder, R: CandidType + DeserializeOwned>(method: &str, args: &A) -> Result<R, String> {
+    ic_cdk::call::Call::unbounded_wait(kong_backend(), method)
+        .with_args(args)
+        .await
+        .map_err(|e| format!("{:?}", e))?
+        .candid::<R>()
+        .map_err(|e| format!("{:?}", e))
+}
+
+/// call a query of kong_backend that returns Result<R, String>. traps on errors as the ICRC queries have no error type
+pub async fn query<A: ArgumentEncoder, R: CandidType + DeserializeOwned>(method: &str, args: &A) -> R {
+    match call::<A, Result<R, String>>(method, args).await {
+        Ok(Ok(reply)) => reply,
+        Ok(Err(e)) | Err(e) => ic_cdk::trap(format!("{} failed. {}", method, e)),
+    }
+}
@@ -0,0 +1,12 @@
+//! ICRC-1, ICRC-2 and ICRC-3 ledger of a Kong LP token. one canister is deployed per LP token and registered with
+//! set_lp_token_ledger in kong_backend, which holds the balances, allowances and blocks. every endpoint forwards to
+//! the lp_ endpoint of kong_backend. queries are composite queries, so kong_lp_ledger must be on the subnet of
+//! kong_backend and its queries cannot be called from the update of another canister.
+mod canister;
+mod icrc1;
+mod icrc2;
+mod icrc3;
+mod kong_backend;
+
+pub const APP_NAME: &str = "Kong Swap LP Ledger";
+pub const APP_VERSION: &str = "v0.0.1";